@@ -1,6 +1,7 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use gibberlink_core::visual::{VisualEngine, VisualPayload};
 use gibberlink_core::crypto::CryptoEngine;
+use gibberlink_core::SessionId;
 use std::sync::Arc;
 
 fn visual_benchmarks(c: &mut Criterion) {
@@ -13,7 +14,7 @@ fn visual_benchmarks(c: &mut Criterion) {
             let crypto = CryptoEngine::new();
 
             // Create test payload
-            let session_id = CryptoEngine::generate_nonce();
+            let session_id = SessionId::random();
             let public_key = crypto.ed25519_public_key().to_vec();
             let nonce = CryptoEngine::generate_nonce();
             let signature = CryptoEngine::generate_nonce().to_vec(); // Mock signature
@@ -23,6 +24,9 @@ fn visual_benchmarks(c: &mut Criterion) {
                 public_key,
                 nonce,
                 signature,
+                version: None,
+                version_offer: None,
+                psk: false,
             };
 
             let _qr_svg = black_box(visual.encode_payload(&payload));
@@ -36,7 +40,7 @@ fn visual_benchmarks(c: &mut Criterion) {
             let crypto = CryptoEngine::new();
 
             // Create and encode payload first
-            let session_id = CryptoEngine::generate_nonce();
+            let session_id = SessionId::random();
             let public_key = crypto.ed25519_public_key().to_vec();
             let nonce = CryptoEngine::generate_nonce();
             let signature = CryptoEngine::generate_nonce().to_vec();
@@ -46,6 +50,9 @@ fn visual_benchmarks(c: &mut Criterion) {
                 public_key: public_key.clone(),
                 nonce,
                 signature: signature.clone(),
+                version: None,
+                version_offer: None,
+                psk: false,
             };
 
             let qr_svg = visual.encode_payload(&payload).unwrap();
@@ -63,7 +70,7 @@ fn visual_benchmarks(c: &mut Criterion) {
         b.iter(|| {
             let crypto = CryptoEngine::new();
 
-            let session_id = CryptoEngine::generate_nonce();
+            let session_id = SessionId::random();
             let public_key = crypto.ed25519_public_key().to_vec();
             let nonce = CryptoEngine::generate_nonce();
             let signature = CryptoEngine::generate_nonce().to_vec();
@@ -73,6 +80,9 @@ fn visual_benchmarks(c: &mut Criterion) {
                 public_key,
                 nonce,
                 signature,
+                version: None,
+                version_offer: None,
+                psk: false,
             });
         });
     });
@@ -89,7 +99,7 @@ fn latency_benchmarks(c: &mut Criterion) {
             let visual = VisualEngine::new();
             let crypto = CryptoEngine::new();
 
-            let session_id = CryptoEngine::generate_nonce();
+            let session_id = SessionId::random();
             let public_key = crypto.ed25519_public_key().to_vec();
             let nonce = CryptoEngine::generate_nonce();
             let signature = CryptoEngine::generate_nonce().to_vec();
@@ -99,6 +109,9 @@ fn latency_benchmarks(c: &mut Criterion) {
                 public_key,
                 nonce,
                 signature,
+                version: None,
+                version_offer: None,
+                psk: false,
             };
 
             let start = std::time::Instant::now();
@@ -116,7 +129,7 @@ fn latency_benchmarks(c: &mut Criterion) {
             let crypto = CryptoEngine::new();
 
             // Pre-generate QR data
-            let session_id = CryptoEngine::generate_nonce();
+            let session_id = SessionId::random();
             let public_key = crypto.ed25519_public_key().to_vec();
             let nonce = CryptoEngine::generate_nonce();
             let signature = CryptoEngine::generate_nonce().to_vec();
@@ -126,6 +139,9 @@ fn latency_benchmarks(c: &mut Criterion) {
                 public_key: public_key.clone(),
                 nonce,
                 signature: signature.clone(),
+                version: None,
+                version_offer: None,
+                psk: false,
             };
 
             let qr_svg = visual.encode_payload(&payload).unwrap();
@@ -153,7 +169,7 @@ fn throughput_benchmarks(c: &mut Criterion) {
             let visual = VisualEngine::new();
             let crypto = CryptoEngine::new();
 
-            let session_id = CryptoEngine::generate_nonce();
+            let session_id = SessionId::random();
             let public_key = crypto.ed25519_public_key().to_vec();
             let nonce = CryptoEngine::generate_nonce();
             let signature = CryptoEngine::generate_nonce().to_vec();
@@ -163,6 +179,9 @@ fn throughput_benchmarks(c: &mut Criterion) {
                 public_key,
                 nonce,
                 signature,
+                version: None,
+                version_offer: None,
+                psk: false,
             };
 
             let _qr = black_box(visual.encode_payload(&payload).unwrap());
@@ -179,7 +198,7 @@ fn throughput_benchmarks(c: &mut Criterion) {
                 std::thread::spawn(move || {
                     let crypto = CryptoEngine::new();
 
-                    let session_id = CryptoEngine::generate_nonce();
+                    let session_id = SessionId::random();
                     let public_key = crypto.ed25519_public_key().to_vec();
                     let nonce = CryptoEngine::generate_nonce();
                     let signature = CryptoEngine::generate_nonce().to_vec();
@@ -189,6 +208,9 @@ fn throughput_benchmarks(c: &mut Criterion) {
                         public_key,
                         nonce,
                         signature,
+                        version: None,
+                        version_offer: None,
+                        psk: false,
                     };
 
                     let _qr = visual.encode_payload(&payload).unwrap();
@@ -214,7 +236,7 @@ fn payload_size_benchmarks(c: &mut Criterion) {
                 let visual = VisualEngine::new();
 
                 // Create payload with specified key size
-                let session_id = [0u8; 16];
+                let session_id = SessionId::from([0u8; 16]);
                 let public_key = vec![0u8; *size];
                 let nonce = [0u8; 16];
                 let signature = vec![0u8; 64];
@@ -224,6 +246,9 @@ fn payload_size_benchmarks(c: &mut Criterion) {
                     public_key,
                     nonce,
                     signature,
+                    version: None,
+                    version_offer: None,
+                    psk: false,
                 };
 
                 let qr_svg = visual.encode_payload(&payload).unwrap();
@@ -255,7 +280,7 @@ fn error_handling_benchmarks(c: &mut Criterion) {
 
             // Create valid QR first, then corrupt it
             let crypto = CryptoEngine::new();
-            let session_id = CryptoEngine::generate_nonce();
+            let session_id = SessionId::random();
             let public_key = crypto.ed25519_public_key().to_vec();
             let nonce = CryptoEngine::generate_nonce();
             let signature = CryptoEngine::generate_nonce().to_vec();
@@ -265,6 +290,9 @@ fn error_handling_benchmarks(c: &mut Criterion) {
                 public_key,
                 nonce,
                 signature,
+                version: None,
+                version_offer: None,
+                psk: false,
             };
 
             let qr_svg = visual.encode_payload(&payload).unwrap();
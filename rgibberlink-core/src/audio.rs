@@ -3,6 +3,10 @@ use std::sync::Arc;
 use tokio::sync::Mutex;
 use std::collections::VecDeque;
 use std::time::{Duration, Instant};
+use crate::clock::{Clock, SystemClock, minutes_since_midnight};
+use crate::demod::{Demodulator, detect_demodulator};
+use crate::tone_modem::{self, ToneProtocol};
+use rand::Rng;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum AudioMode {
@@ -26,6 +30,160 @@ pub enum AudioError {
     BufferOverflow,
     #[error("Timeout")]
     Timeout,
+    #[error("Audio decode failed: {0}")]
+    DecodeFailed(DecodeDiagnostics),
+    #[error("Audible-band transmission blocked during quiet hours")]
+    QuietHoursActive,
+    #[error("Channel still busy after {0} carrier-sense attempts")]
+    ChannelBusy(u32),
+    #[error("Transmission collided with another station {0} times")]
+    Collision(u32),
+}
+
+/// Nominal FSK tone for a `0` bit in ultrasonic mode
+pub const ULTRASONIC_TONE_ZERO_HZ: f32 = 18000.0;
+/// Nominal FSK tone for a `1` bit in ultrasonic mode
+pub const ULTRASONIC_TONE_ONE_HZ: f32 = 20000.0;
+/// FSK alphabet sizes the ultrasonic modem supports; M-ary tones are spread
+/// evenly between the binary `0` and `1` tones
+pub const SUPPORTED_TONES_PER_SYMBOL: [usize; 3] = [2, 4, 8];
+/// Extra SNR required per additional bit per symbol on top of the binary threshold
+pub const MARY_SNR_STEP_DB: f32 = 3.0;
+
+/// Peak level FSK output is normalized to at unity gain
+pub const NORMALIZED_PEAK: f32 = 0.9;
+/// Level above which the peak limiter starts compressing
+const LIMITER_THRESHOLD: f32 = 0.9;
+/// Largest accepted output gain
+const MAX_OUTPUT_GAIN: f32 = 16.0;
+
+/// Half-width of the frequency search around each nominal tone
+const FREQ_SEARCH_SPAN_HZ: f32 = 600.0;
+/// Step of the frequency search
+const FREQ_SEARCH_STEP_HZ: f32 = 10.0;
+/// Number of leading symbols used to acquire the carrier
+const ACQUISITION_SYMBOLS: usize = 16;
+
+/// Channels a hopping transmission moves between
+pub const HOPPING_CHANNELS: usize = 16;
+/// Width of one hopping channel; the 16 channels tile 18-22 kHz
+pub const HOPPING_CHANNEL_SPACING_HZ: f32 = 250.0;
+/// Separation of the binary tones inside a hopping channel, orthogonal over a 10ms symbol
+const HOPPING_TONE_SPACING_HZ: f32 = 100.0;
+/// Upcoming channels the receiver tries, so a lost packet does not desynchronize it
+const HOPPING_RESYNC_WINDOW: usize = 4;
+/// Carrier-sense attempts before a half-duplex transmission gives up
+pub const MAX_CARRIER_SENSE_ATTEMPTS: u32 = 8;
+/// Times a half-duplex frame is sent before repeated collisions give up
+pub const MAX_COLLISION_ATTEMPTS: u32 = 4;
+
+/// Microphone tap used for carrier sense. Each call blocks until the next
+/// `samples` the microphone hears are available; reads must not take audio
+/// from the receive path.
+pub trait AudioCapture: Send + Sync {
+    fn capture(&self, samples: usize) -> Result<Vec<f32>, AudioError>;
+}
+
+/// Whether the engine arbitrates access to the shared acoustic channel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplexMode {
+    /// Transmit without listening first (point-to-point links)
+    FullDuplex,
+    /// Listen before talking: transmit only once the channel is quiet, backing off
+    /// for a random `[0, backoff_ms]` interval each time it is busy
+    HalfDuplex { backoff_ms: u32 },
+}
+
+/// Result of listening to the channel before a transmission
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CarrierSense {
+    /// RMS level of the captured audio over the sensing window, in dBFS
+    pub energy_db: f32,
+    /// Energy above the configured noise floor was heard
+    pub busy: bool,
+}
+
+/// Acceptance limits applied by the ultrasonic decoder
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodeTolerance {
+    /// Largest carrier offset from the nominal tones that is still accepted
+    pub max_freq_offset_hz: f32,
+    /// Minimum tone-to-noise ratio over the decoded symbols for binary FSK;
+    /// larger alphabets add `MARY_SNR_STEP_DB` per extra bit
+    pub min_snr_db: f32,
+}
+
+impl Default for DecodeTolerance {
+    fn default() -> Self {
+        Self {
+            max_freq_offset_hz: 150.0,
+            min_snr_db: 6.0,
+        }
+    }
+}
+
+/// What the decoder observed when a decode attempt failed
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodeDiagnostics {
+    /// Whether a carrier was acquired on the leading symbols (the stream has no
+    /// dedicated preamble, so the first symbols serve as one)
+    pub detected_preamble: bool,
+    /// Mean offset of the received tones from the nominal FSK frequencies
+    pub estimated_freq_offset_hz: f32,
+    /// Tone-to-noise ratio over all symbols
+    pub estimated_snr_db: f32,
+    /// Symbols demodulated with a tone above the SNR threshold
+    pub symbols_decoded: usize,
+}
+
+impl std::fmt::Display for DecodeDiagnostics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "preamble {}, frequency offset {:.1} Hz, SNR {:.1} dB, {} symbols decoded",
+            if self.detected_preamble { "detected" } else { "not detected" },
+            self.estimated_freq_offset_hz,
+            self.estimated_snr_db,
+            self.symbols_decoded
+        )
+    }
+}
+
+/// Local time window during which audible-band transmission is blocked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuietHours {
+    /// Start of the window, in minutes after local midnight
+    pub start_minute: u32,
+    /// End of the window (exclusive); earlier than the start when it wraps past midnight
+    pub end_minute: u32,
+    /// Local time offset from UTC
+    pub utc_offset_minutes: i32,
+}
+
+impl QuietHours {
+    /// Quiet from `start_hour:00` to `end_hour:00` local time
+    pub fn from_hours(start_hour: u32, end_hour: u32, utc_offset_minutes: i32) -> Self {
+        Self {
+            start_minute: (start_hour % 24) * 60,
+            end_minute: (end_hour % 24) * 60,
+            utc_offset_minutes,
+        }
+    }
+
+    /// Whether `minute` (after local midnight) falls inside the window
+    pub fn contains(&self, minute: u32) -> bool {
+        if self.start_minute <= self.end_minute {
+            minute >= self.start_minute && minute < self.end_minute
+        } else {
+            minute >= self.start_minute || minute < self.end_minute
+        }
+    }
+}
+
+impl Default for QuietHours {
+    fn default() -> Self {
+        Self::from_hours(22, 7, 0)
+    }
 }
 
 /// Audio configuration for different modes
@@ -36,6 +194,30 @@ pub struct AudioConfig {
     pub bits_per_sample: u16,
     pub buffer_size: usize,
     pub mode: AudioMode,
+    pub decode_tolerance: DecodeTolerance,
+    /// FSK alphabet size M in ultrasonic mode; each 10ms symbol carries log2(M) bits
+    pub tones_per_symbol: usize,
+    pub duplex_mode: DuplexMode,
+    /// Most recent captured audio that must be quiet before a half-duplex transmission
+    pub silence_threshold_ms: u32,
+    /// Level (dBFS) above which the channel counts as busy
+    pub noise_floor_db: f32,
+}
+
+impl AudioConfig {
+    /// Bits carried per ultrasonic symbol, if `tones_per_symbol` is supported
+    pub fn bits_per_symbol(&self) -> Option<usize> {
+        SUPPORTED_TONES_PER_SYMBOL
+            .contains(&self.tones_per_symbol)
+            .then(|| self.tones_per_symbol.trailing_zeros() as usize)
+    }
+
+    /// Minimum SNR for the configured alphabet: the binary threshold plus
+    /// `MARY_SNR_STEP_DB` per extra bit per symbol
+    pub fn required_snr_db(&self) -> f32 {
+        let extra_bits = self.bits_per_symbol().unwrap_or(1).saturating_sub(1);
+        self.decode_tolerance.min_snr_db + MARY_SNR_STEP_DB * extra_bits as f32
+    }
 }
 
 impl Default for AudioConfig {
@@ -46,22 +228,72 @@ impl Default for AudioConfig {
             bits_per_sample: 16,
             buffer_size: 1024,
             mode: AudioMode::Ultrasonic,
+            decode_tolerance: DecodeTolerance::default(),
+            tones_per_symbol: 2,
+            duplex_mode: DuplexMode::FullDuplex,
+            silence_threshold_ms: 20,
+            noise_floor_db: -40.0,
+        }
+    }
+}
+
+/// Pseudo-random channel sequence for frequency hopping, generated by a 128-bit
+/// maximal-length LFSR (taps 128, 126, 101, 99) seeded with the shared secret
+#[derive(Debug, Clone)]
+pub struct HoppingSequence {
+    register: u128,
+    previous: Option<usize>,
+}
+
+impl HoppingSequence {
+    /// An all-zero seed would lock the register, so its lowest bit is forced on
+    pub fn new(seed: [u8; 16]) -> Self {
+        Self {
+            register: u128::from_be_bytes(seed).max(1),
+            previous: None,
+        }
+    }
+
+    fn next_bit(&mut self) -> u128 {
+        let feedback = (self.register >> 127) ^ (self.register >> 125) ^ (self.register >> 100) ^ (self.register >> 98);
+        self.register = (self.register << 1) | (feedback & 1);
+        feedback & 1
+    }
+
+    /// Channel for the next packet; never the same channel twice in a row
+    pub fn next_channel(&mut self) -> usize {
+        loop {
+            let channel = (0..4).fold(0, |channel, _| (channel << 1) | self.next_bit() as usize);
+            if self.previous != Some(channel) {
+                self.previous = Some(channel);
+                return channel;
+            }
         }
     }
 }
 
+/// Transmit and receive positions in the shared hopping sequence
+#[derive(Debug)]
+struct HoppingState {
+    transmit: HoppingSequence,
+    receive: HoppingSequence,
+}
+
 /// Audio buffer for managing transmission/reception
 #[derive(Clone)]
 struct AudioBuffer {
     data: VecDeque<f32>,
     max_size: usize,
+    /// Samples ever pushed, so readers can tell which ones are new
+    pushed: u64,
 }
 
 impl AudioBuffer {
     fn new(max_size: usize) -> Self {
         Self {
-            data: VecDeque::with_capacity(max_size),
+            data: VecDeque::with_capacity(max_size.min(65536)),
             max_size,
+            pushed: 0,
         }
     }
 
@@ -70,6 +302,7 @@ impl AudioBuffer {
             return Err(AudioError::BufferOverflow);
         }
         self.data.push_back(sample);
+        self.pushed += 1;
         Ok(())
     }
 
@@ -94,6 +327,14 @@ pub struct AudioEngine {
     is_initialized: bool,
     last_transmission: Instant,
     transmission_timeout: Duration,
+    output_gain: f32,
+    quiet_hours: Option<QuietHours>,
+    clock: Arc<dyn Clock>,
+    demodulator: Arc<dyn Demodulator>,
+    hopping: Option<std::sync::Mutex<HoppingState>>,
+    capture: Option<Arc<dyn AudioCapture>>,
+    /// `AudioBuffer::pushed` of the receive buffer at the last carrier sense
+    sensed_through: std::sync::atomic::AtomicU64,
 }
 
 impl AudioEngine {
@@ -106,6 +347,11 @@ impl AudioEngine {
     pub fn with_config(config: AudioConfig) -> Self {
         // Use larger buffer sizes for data transmission
         let buffer_size = config.buffer_size.max(65536); // At least 64KB buffer
+        // Room for the longest tone transmission in either band
+        let buffer_size = [ToneProtocol::Ultrasonic, ToneProtocol::Audible]
+            .into_iter()
+            .map(|protocol| tone_modem::max_transmission_samples(protocol, config.sample_rate))
+            .fold(buffer_size, usize::max);
         Self {
             config,
             transmit_buffer: Arc::new(Mutex::new(AudioBuffer::new(buffer_size))),
@@ -113,6 +359,13 @@ impl AudioEngine {
             is_initialized: false,
             last_transmission: Instant::now(),
             transmission_timeout: Duration::from_millis(100),
+            output_gain: 1.0,
+            quiet_hours: None,
+            clock: Arc::new(SystemClock),
+            demodulator: detect_demodulator(),
+            hopping: None,
+            capture: None,
+            sensed_through: std::sync::atomic::AtomicU64::new(0),
         }
     }
 
@@ -124,7 +377,7 @@ impl AudioEngine {
         match self.config.mode {
             AudioMode::Ultrasonic => {
                 // Validate ultrasonic parameters
-                if self.config.sample_rate < 44100 {
+                if self.config.sample_rate < 44100 || self.config.bits_per_symbol().is_none() {
                     return Err(AudioError::InvalidParameters);
                 }
             }
@@ -158,16 +411,16 @@ impl AudioEngine {
             return Err(AudioError::DeviceUnavailable);
         }
 
-        // Convert data to audio samples
-        let audio_samples = self.encode_data_to_audio(data).await?;
-
-        // Queue samples for transmission
-        let mut buffer = self.transmit_buffer.lock().await;
-        for sample in audio_samples {
-            buffer.push(sample)?;
+        // Ultrasonic transmission is inaudible and always allowed
+        if self.is_audible_band() && self.quiet_hours_active() {
+            return Err(AudioError::QuietHoursActive);
         }
 
-        self.last_transmission = Instant::now();
+        // Convert data to audio samples
+        let audio_samples = self.encode_data_to_audio(data).await?;
+        // Earlier sends returned only after their frame played out
+        self.transmit_buffer.lock().await.clear();
+        self.queue_arbitrated(&audio_samples).await?;
 
         // In a real implementation, this would trigger actual audio playback
         // For now, we simulate transmission timing
@@ -210,6 +463,124 @@ impl AudioEngine {
         self.decode_audio_to_data(&samples).await
     }
 
+    /// Tone layout for the configured mode
+    pub fn tone_protocol(&self) -> ToneProtocol {
+        match self.config.mode {
+            AudioMode::Ultrasonic => ToneProtocol::Ultrasonic,
+            AudioMode::Standard => ToneProtocol::Audible,
+        }
+    }
+
+    /// Multi-tone modulate `data` into normalized samples at the configured sample rate
+    pub fn encode_tones(&self, data: &[u8]) -> Result<Vec<f32>, AudioError> {
+        let mut samples = tone_modem::encode(self.tone_protocol(), data, self.config.sample_rate)?;
+        self.condition_output(&mut samples);
+        Ok(samples)
+    }
+
+    /// Queue a multi-tone transmission of `data` for playback
+    pub async fn transmit(&mut self, data: &[u8]) -> Result<(), AudioError> {
+        if !self.is_initialized {
+            return Err(AudioError::DeviceUnavailable);
+        }
+        if self.is_audible_band() && self.quiet_hours_active() {
+            return Err(AudioError::QuietHoursActive);
+        }
+
+        let samples = self.encode_tones(data)?;
+        self.queue_arbitrated(&samples).await
+    }
+
+    /// Look for a multi-tone transmission in captured `samples`; `Ok(None)` when there is none
+    pub fn receive(&self, samples: &[f32], sample_rate: u32) -> Result<Option<Vec<u8>>, AudioError> {
+        tone_modem::decode(self.tone_protocol(), samples, sample_rate, self.demodulator.as_ref())
+    }
+
+    /// Listen to `silence_threshold_ms` of fresh audio. With a capture device set
+    /// every sense reads new samples from it; without one only audio that reached
+    /// the receive buffer since the previous sense counts. A failed capture, or a
+    /// capture in progress holding the receive buffer, counts as busy.
+    pub fn carrier_sense(&self) -> CarrierSense {
+        const BUSY: CarrierSense = CarrierSense { energy_db: 0.0, busy: true };
+        let window = (self.config.sample_rate as usize * self.config.silence_threshold_ms as usize / 1000).max(1);
+        let power = match &self.capture {
+            Some(capture) => match capture.capture(window) {
+                Ok(samples) => samples.iter().map(|s| s * s).sum::<f32>() / samples.len().max(1) as f32,
+                Err(_) => return BUSY,
+            },
+            None => match self.receive_buffer.try_lock() {
+                Ok(buffer) => {
+                    let sensed = self.sensed_through.swap(buffer.pushed, std::sync::atomic::Ordering::Relaxed);
+                    let fresh = (buffer.pushed.saturating_sub(sensed) as usize).min(buffer.data.len()).min(window);
+                    buffer.data.iter().rev().take(fresh).map(|s| s * s).sum::<f32>() / fresh.max(1) as f32
+                }
+                Err(_) => return BUSY,
+            },
+        };
+        let energy_db = 10.0 * power.max(f32::MIN_POSITIVE).log10();
+        CarrierSense {
+            energy_db,
+            busy: energy_db > self.config.noise_floor_db,
+        }
+    }
+
+    /// Listen through `capture` for carrier sense instead of the receive buffer
+    /// (`None` returns to the buffer)
+    pub fn set_capture(&mut self, capture: Option<Arc<dyn AudioCapture>>) {
+        self.capture = capture;
+    }
+
+    /// Whether another station is currently heard on the channel
+    pub fn channel_busy(&self) -> bool {
+        self.carrier_sense().busy
+    }
+
+    /// CSMA: in half-duplex mode, wait for a quiet channel, backing off a random
+    /// interval after each busy sense
+    async fn wait_for_clear_channel(&self) -> Result<(), AudioError> {
+        let DuplexMode::HalfDuplex { backoff_ms } = self.config.duplex_mode else {
+            return Ok(());
+        };
+        for _ in 0..MAX_CARRIER_SENSE_ATTEMPTS {
+            if !self.channel_busy() {
+                return Ok(());
+            }
+            let backoff = rand::thread_rng().gen_range(0..=backoff_ms);
+            tokio::time::sleep(Duration::from_millis(backoff as u64)).await;
+        }
+        Err(AudioError::ChannelBusy(MAX_CARRIER_SENSE_ATTEMPTS))
+    }
+
+    /// Queue `samples` for playback once the channel is clear. In half-duplex
+    /// mode the frame's airtime is then waited out and the channel sensed again:
+    /// a peer that obeys carrier sense stays quiet for `silence_threshold_ms`
+    /// after a frame, so energy heard then means a station talked over ours.
+    /// Collided frames are resent after a random backoff.
+    async fn queue_arbitrated(&mut self, samples: &[f32]) -> Result<(), AudioError> {
+        for _ in 0..MAX_COLLISION_ATTEMPTS {
+            self.wait_for_clear_channel().await?;
+            {
+                let mut buffer = self.transmit_buffer.lock().await;
+                for &sample in samples {
+                    buffer.push(sample)?;
+                }
+            }
+            self.last_transmission = Instant::now();
+
+            let DuplexMode::HalfDuplex { backoff_ms } = self.config.duplex_mode else {
+                return Ok(());
+            };
+            let airtime = Duration::from_secs_f64(samples.len() as f64 / self.config.sample_rate.max(1) as f64);
+            tokio::time::sleep(airtime).await;
+            if !self.channel_busy() {
+                return Ok(());
+            }
+            let backoff = rand::thread_rng().gen_range(0..=backoff_ms);
+            tokio::time::sleep(Duration::from_millis(backoff as u64)).await;
+        }
+        Err(AudioError::Collision(MAX_COLLISION_ATTEMPTS))
+    }
+
     /// Check if currently receiving audio data
     pub async fn is_receiving(&self) -> bool {
         if !self.is_initialized {
@@ -220,11 +591,92 @@ impl AudioEngine {
         !buffer.data.is_empty()
     }
 
+    /// Set the output gain applied after normalization (1.0 = `NORMALIZED_PEAK`);
+    /// levels pushed past full scale are caught by the peak limiter
+    pub fn set_output_gain(&mut self, gain: f32) {
+        self.output_gain = if gain.is_nan() { 0.0 } else { gain.clamp(0.0, MAX_OUTPUT_GAIN) };
+    }
+
+    pub fn get_output_gain(&self) -> f32 {
+        self.output_gain
+    }
+
+    /// Block audible-band transmission during `quiet_hours` (`None` disables the policy)
+    pub fn set_quiet_hours(&mut self, quiet_hours: Option<QuietHours>) {
+        self.quiet_hours = quiet_hours;
+    }
+
+    pub fn get_quiet_hours(&self) -> Option<QuietHours> {
+        self.quiet_hours
+    }
+
+    /// Replace the wall clock used by the quiet-hours policy
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    /// Replace the demodulation backend chosen by capability detection
+    pub fn set_demodulator(&mut self, demodulator: Arc<dyn Demodulator>) {
+        self.demodulator = demodulator;
+    }
+
+    pub fn demodulator_name(&self) -> &'static str {
+        self.demodulator.name()
+    }
+
+    /// Hop every ultrasonic packet to a channel drawn from the sequence `seed`
+    /// generates. Both peers must set the same seed (exchanged over the visual
+    /// channel) before decoding; hopping packets use binary FSK within the channel.
+    pub fn set_hopping_sequence(&mut self, seed: [u8; 16]) {
+        self.hopping = Some(std::sync::Mutex::new(HoppingState {
+            transmit: HoppingSequence::new(seed),
+            receive: HoppingSequence::new(seed),
+        }));
+    }
+
+    /// Return to the fixed 18-20 kHz tones
+    pub fn clear_hopping_sequence(&mut self) {
+        self.hopping = None;
+    }
+
+    pub fn is_hopping(&self) -> bool {
+        self.hopping.is_some()
+    }
+
+    /// Whether the current mode transmits in the audible range
+    pub fn is_audible_band(&self) -> bool {
+        matches!(self.config.mode, AudioMode::Standard)
+    }
+
+    /// Whether the clock is currently inside the configured quiet hours
+    pub fn quiet_hours_active(&self) -> bool {
+        self.quiet_hours.map_or(false, |quiet| {
+            quiet.contains(minutes_since_midnight(self.clock.now(), quiet.utc_offset_minutes))
+        })
+    }
+
     /// Get current audio configuration
     pub fn get_config(&self) -> &AudioConfig {
         &self.config
     }
 
+    /// Largest supported FSK alphabet whose SNR requirement `snr_db` meets,
+    /// falling back to binary FSK
+    pub fn select_tones_per_symbol(&self, snr_db: f32) -> usize {
+        SUPPORTED_TONES_PER_SYMBOL
+            .iter()
+            .copied()
+            .filter(|&tones| AudioConfig { tones_per_symbol: tones, ..self.config.clone() }.required_snr_db() <= snr_db)
+            .max()
+            .unwrap_or(SUPPORTED_TONES_PER_SYMBOL[0])
+    }
+
+    /// Raise or lower the FSK alphabet to suit a measured SNR; returns the new size
+    pub fn adapt_tones_per_symbol(&mut self, snr_db: f32) -> usize {
+        self.config.tones_per_symbol = self.select_tones_per_symbol(snr_db);
+        self.config.tones_per_symbol
+    }
+
     /// Update audio configuration
     pub async fn update_config(&mut self, config: AudioConfig) -> Result<(), AudioError> {
         self.config = config;
@@ -247,26 +699,43 @@ impl AudioEngine {
 
     /// Encode binary data to audio samples
     async fn encode_data_to_audio(&self, data: &[u8]) -> Result<Vec<f32>, AudioError> {
+        self.encode_to_samples(data)
+    }
+
+    /// Modulate `data` into raw samples for the configured mode
+    pub fn encode_to_samples(&self, data: &[u8]) -> Result<Vec<f32>, AudioError> {
         let mut samples = Vec::new();
 
         match self.config.mode {
             AudioMode::Ultrasonic => {
-                // Encode data using ultrasonic frequency modulation
-                for &byte in data {
-                    // Convert each bit to ultrasonic tone
-                    for bit in 0..8 {
-                        let bit_value = (byte >> (7 - bit)) & 1;
-                        let frequency = if bit_value == 1 { 20000.0 } else { 18000.0 }; // 18-20kHz
-
-                        // Generate tone samples
-                        let samples_per_bit = (self.config.sample_rate as f32 / 100.0) as usize; // 10ms per bit
-                        for i in 0..samples_per_bit {
-                            let t = i as f32 / self.config.sample_rate as f32;
-                            let sample = (t * frequency * 2.0 * std::f32::consts::PI).sin() * 0.5;
-                            samples.push(sample);
-                        }
+                // Encode data using M-ary frequency shift keying, log2(M) bits per tone
+                let bits_per_symbol = self.config.bits_per_symbol().ok_or(AudioError::InvalidParameters)?;
+                let (bits_per_symbol, tones) = match &self.hopping {
+                    Some(hopping) => (1, hopping_tones(hopping.lock().unwrap().transmit.next_channel())),
+                    None => (bits_per_symbol, fsk_tones(self.config.tones_per_symbol)),
+                };
+                let total_bits = data.len() * 8;
+                let samples_per_symbol = (self.config.sample_rate as f32 / 100.0) as usize; // 10ms per symbol
+
+                // The last symbol is zero-padded when log2(M) doesn't divide the bit count
+                let mut bit_index = 0;
+                while bit_index < total_bits {
+                    let mut symbol = 0usize;
+                    for _ in 0..bits_per_symbol {
+                        let bit = if bit_index < total_bits { (data[bit_index / 8] >> (7 - bit_index % 8)) & 1 } else { 0 };
+                        symbol = (symbol << 1) | bit as usize;
+                        bit_index += 1;
+                    }
+
+                    // Generate tone samples
+                    let frequency = tones[symbol];
+                    for i in 0..samples_per_symbol {
+                        let t = i as f32 / self.config.sample_rate as f32;
+                        let sample = (t * frequency * 2.0 * std::f32::consts::PI).sin() * 0.5;
+                        samples.push(sample);
                     }
                 }
+                self.condition_output(&mut samples);
             }
             AudioMode::Standard => {
                 // Simple amplitude modulation for standard audio
@@ -288,35 +757,38 @@ impl AudioEngine {
         Ok(samples)
     }
 
+    /// Normalize FSK samples to `NORMALIZED_PEAK`, apply the output gain, then limit peaks
+    fn condition_output(&self, samples: &mut [f32]) {
+        let peak = samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+        if peak <= f32::EPSILON {
+            return;
+        }
+
+        let scale = NORMALIZED_PEAK / peak * self.output_gain;
+        for sample in samples.iter_mut() {
+            *sample = peak_limit(*sample * scale);
+        }
+    }
+
     /// Decode audio samples back to binary data
     async fn decode_audio_to_data(&self, samples: &[f32]) -> Result<Vec<u8>, AudioError> {
+        self.decode_from_samples(samples)
+    }
+
+    /// Decode raw samples, reporting `AudioError::DecodeFailed` with diagnostics
+    /// when the ultrasonic signal is outside the configured tolerance
+    pub fn decode_from_samples(&self, samples: &[f32]) -> Result<Vec<u8>, AudioError> {
         let mut data = Vec::new();
         let mut current_byte = 0u8;
         let mut bit_count = 0;
 
         match self.config.mode {
             AudioMode::Ultrasonic => {
-                // Decode ultrasonic frequency modulation
-                let chunk_size = self.config.sample_rate as usize / 100; // 10ms chunks
-
-                for chunk in samples.chunks(chunk_size) {
-                    if chunk.is_empty() {
-                        continue;
-                    }
-
-                    // Simple frequency detection (in real implementation, use FFT)
-                    let avg_amplitude = chunk.iter().map(|s| s.abs()).sum::<f32>() / chunk.len() as f32;
-                    let bit = if avg_amplitude > 0.3 { 1 } else { 0 };
-
-                    current_byte = (current_byte << 1) | bit;
-                    bit_count += 1;
-
-                    if bit_count == 8 {
-                        data.push(current_byte);
-                        current_byte = 0;
-                        bit_count = 0;
-                    }
+                let bits_per_symbol = self.config.bits_per_symbol().ok_or(AudioError::InvalidParameters)?;
+                if let Some(hopping) = &self.hopping {
+                    return self.decode_hopping(samples, hopping);
                 }
+                return self.decode_fsk(samples, &fsk_tones(self.config.tones_per_symbol), bits_per_symbol);
             }
             AudioMode::Standard => {
                 // Decode amplitude modulation
@@ -345,6 +817,110 @@ impl AudioEngine {
         Ok(data)
     }
 
+    /// Decode one hopping packet on the next channel of the receive sequence,
+    /// looking a few channels ahead in case earlier packets were lost. The
+    /// sequence only advances past the channel that decoded.
+    fn decode_hopping(&self, samples: &[f32], hopping: &std::sync::Mutex<HoppingState>) -> Result<Vec<u8>, AudioError> {
+        if samples.len() < self.config.sample_rate as usize / 100 {
+            return Ok(Vec::new());
+        }
+
+        let mut state = hopping.lock().unwrap();
+        let mut candidate = state.receive.clone();
+        let mut first_error = None;
+        for _ in 0..HOPPING_RESYNC_WINDOW {
+            match self.decode_fsk(samples, &hopping_tones(candidate.next_channel()), 1) {
+                Ok(data) => {
+                    state.receive = candidate;
+                    return Ok(data);
+                }
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        Err(first_error.expect("the resync window is not empty"))
+    }
+
+    /// Demodulate `bits_per_symbol`-bit FSK symbols against `nominal_tones`
+    fn decode_fsk(&self, samples: &[f32], nominal_tones: &[f32], bits_per_symbol: usize) -> Result<Vec<u8>, AudioError> {
+        let mut data = Vec::new();
+        let mut current_byte = 0u8;
+        let mut bit_count = 0;
+
+        let sample_rate = self.config.sample_rate as f32;
+        let chunk_size = self.config.sample_rate as usize / 100; // 10ms symbols
+        if samples.len() < chunk_size {
+            return Ok(data);
+        }
+
+        // Acquire the carrier offset on the leading symbols
+        let acquisition: Vec<f32> = samples
+            .chunks_exact(chunk_size)
+            .take(ACQUISITION_SYMBOLS)
+            .map(|chunk| estimate_tone_offset(self.demodulator.as_ref(), chunk, sample_rate, nominal_tones))
+            .collect();
+        let offset_hz = acquisition.iter().sum::<f32>() / acquisition.len() as f32;
+
+        // Demodulate against the offset-compensated tones
+        let required_snr_db = self.config.decode_tolerance.min_snr_db + MARY_SNR_STEP_DB * (bits_per_symbol - 1) as f32;
+        let min_snr = db_to_ratio(required_snr_db);
+        let mut tone_energy = 0.0f32;
+        let mut noise_energy = 0.0f32;
+        let mut symbols_decoded = 0;
+        let mut leading_snr = 0.0f32;
+
+        let tones: Vec<f32> = nominal_tones.iter().map(|tone| tone + offset_hz).collect();
+        let mut powers = vec![0.0f32; tones.len()];
+        for (index, chunk) in samples.chunks_exact(chunk_size).enumerate() {
+            self.demodulator.goertzel_powers(chunk, &tones, sample_rate, &mut powers);
+
+            // The strongest tone wins; ties go to the lower symbol
+            let (symbol, tone) = powers.iter().copied().enumerate().fold((0, f32::MIN), |best, (symbol, power)| {
+                if power > best.1 { (symbol, power) } else { best }
+            });
+            let total = chunk.iter().map(|s| s * s).sum::<f32>() / chunk.len() as f32;
+            let noise = (total - tone).max(total * 1e-6).max(f32::MIN_POSITIVE);
+
+            tone_energy += tone;
+            noise_energy += noise;
+            if index == 0 {
+                leading_snr = tone / noise;
+            }
+            if tone / noise >= min_snr {
+                symbols_decoded += 1;
+            }
+
+            // Trailing padding bits never complete a byte and are dropped
+            for shift in (0..bits_per_symbol).rev() {
+                current_byte = (current_byte << 1) | ((symbol >> shift) & 1) as u8;
+                bit_count += 1;
+
+                if bit_count == 8 {
+                    data.push(current_byte);
+                    current_byte = 0;
+                    bit_count = 0;
+                }
+            }
+        }
+
+        let diagnostics = DecodeDiagnostics {
+            detected_preamble: leading_snr >= min_snr,
+            estimated_freq_offset_hz: offset_hz,
+            estimated_snr_db: 10.0 * (tone_energy / noise_energy).log10(),
+            symbols_decoded,
+        };
+
+        if !diagnostics.detected_preamble
+            || offset_hz.abs() > self.config.decode_tolerance.max_freq_offset_hz
+            || diagnostics.estimated_snr_db < required_snr_db
+        {
+            return Err(AudioError::DecodeFailed(diagnostics));
+        }
+
+        Ok(data)
+    }
+
     /// Get audio engine status
     pub fn get_status(&self) -> AudioEngineStatus {
         AudioEngineStatus {
@@ -357,6 +933,65 @@ impl AudioEngine {
     }
 }
 
+/// Nominal tones of an M-ary FSK alphabet, evenly spaced from the `0` to the `1` tone
+fn fsk_tones(tones_per_symbol: usize) -> Vec<f32> {
+    let spacing = (ULTRASONIC_TONE_ONE_HZ - ULTRASONIC_TONE_ZERO_HZ) / (tones_per_symbol - 1) as f32;
+    (0..tones_per_symbol).map(|symbol| ULTRASONIC_TONE_ZERO_HZ + symbol as f32 * spacing).collect()
+}
+
+/// Binary FSK tones centred in hopping channel `channel`
+fn hopping_tones(channel: usize) -> Vec<f32> {
+    let low = ULTRASONIC_TONE_ZERO_HZ
+        + channel as f32 * HOPPING_CHANNEL_SPACING_HZ
+        + (HOPPING_CHANNEL_SPACING_HZ - HOPPING_TONE_SPACING_HZ) / 2.0;
+    vec![low, low + HOPPING_TONE_SPACING_HZ]
+}
+
+/// Offset of the strongest tone in `chunk` from the nearest of `nominal_tones`
+fn estimate_tone_offset(demodulator: &dyn Demodulator, chunk: &[f32], sample_rate: f32, nominal_tones: &[f32]) -> f32 {
+    // Keep each search window clear of the neighbouring tones
+    let spacing = nominal_tones[1] - nominal_tones[0];
+    let span = FREQ_SEARCH_SPAN_HZ.min(spacing / 2.0);
+    let steps = (span / FREQ_SEARCH_STEP_HZ) as i32;
+    let offsets: Vec<f32> = (-steps..=steps).map(|step| step as f32 * FREQ_SEARCH_STEP_HZ).collect();
+    let frequencies: Vec<f32> = nominal_tones
+        .iter()
+        .flat_map(|&nominal| offsets.iter().map(move |offset| nominal + offset))
+        .collect();
+
+    // Evaluate the whole search grid in one backend call
+    let mut powers = vec![0.0f32; frequencies.len()];
+    demodulator.goertzel_powers(chunk, &frequencies, sample_rate, &mut powers);
+
+    let mut best_offset = 0.0;
+    let mut best_power = f32::MIN;
+    for (index, &power) in powers.iter().enumerate() {
+        if power > best_power {
+            best_power = power;
+            best_offset = offsets[index % offsets.len()];
+        }
+    }
+
+    best_offset
+}
+
+/// Soft-knee limiter: transparent up to `LIMITER_THRESHOLD`, then compresses
+/// smoothly so the output never reaches full scale
+fn peak_limit(sample: f32) -> f32 {
+    let magnitude = sample.abs();
+    if magnitude <= LIMITER_THRESHOLD {
+        return sample;
+    }
+
+    let headroom = 1.0 - LIMITER_THRESHOLD;
+    let limited = LIMITER_THRESHOLD + headroom * ((magnitude - LIMITER_THRESHOLD) / headroom).tanh();
+    limited.min(1.0 - f32::EPSILON).copysign(sample)
+}
+
+fn db_to_ratio(db: f32) -> f32 {
+    10f32.powf(db / 10.0)
+}
+
 /// Audio engine status information
 #[derive(Debug, Clone)]
 pub struct AudioEngineStatus {
@@ -365,4 +1000,327 @@ pub struct AudioEngineStatus {
     pub transmit_buffer_size: usize,
     pub receive_buffer_size: usize,
     pub last_transmission: Instant,
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// FSK-modulate `data` with both tones shifted by `offset_hz`
+    fn fsk_samples(data: &[u8], offset_hz: f32, sample_rate: u32) -> Vec<f32> {
+        let samples_per_bit = sample_rate as usize / 100;
+        let mut samples = Vec::new();
+        for &byte in data {
+            for bit in 0..8 {
+                let tone = if (byte >> (7 - bit)) & 1 == 1 { ULTRASONIC_TONE_ONE_HZ } else { ULTRASONIC_TONE_ZERO_HZ };
+                let frequency = tone + offset_hz;
+                for i in 0..samples_per_bit {
+                    let t = i as f32 / sample_rate as f32;
+                    samples.push((t * frequency * 2.0 * std::f32::consts::PI).sin() * 0.5);
+                }
+            }
+        }
+        samples
+    }
+
+    #[tokio::test]
+    async fn test_decode_round_trip() {
+        let mut engine = AudioEngine::new();
+        engine.force_initialize_for_testing();
+
+        let data = b"gibberlink";
+        engine.simulate_receive(data).await.unwrap();
+        assert_eq!(engine.receive_data().await.unwrap(), data.to_vec());
+
+        // Small offsets within tolerance are compensated
+        let samples = fsk_samples(&[0xA5, 0x3C], 60.0, engine.get_config().sample_rate);
+        assert_eq!(engine.decode_from_samples(&samples).unwrap(), vec![0xA5, 0x3C]);
+    }
+
+    #[test]
+    fn test_decode_diagnostics_report_frequency_offset() {
+        let engine = AudioEngine::new();
+        let samples = fsk_samples(&[0xA5, 0x3C, 0x0F], 400.0, engine.get_config().sample_rate);
+
+        match engine.decode_from_samples(&samples) {
+            Err(AudioError::DecodeFailed(diagnostics)) => {
+                assert!(diagnostics.detected_preamble);
+                assert!(
+                    (diagnostics.estimated_freq_offset_hz - 400.0).abs() < 25.0,
+                    "offset estimate {}",
+                    diagnostics.estimated_freq_offset_hz
+                );
+                assert!(diagnostics.estimated_snr_db > engine.get_config().decode_tolerance.min_snr_db);
+                assert_eq!(diagnostics.symbols_decoded, 24);
+            }
+            other => panic!("expected DecodeFailed, got {:?}", other),
+        }
+
+        // Silence never acquires a carrier
+        let silence = vec![0.0f32; 441 * 8];
+        match engine.decode_from_samples(&silence) {
+            Err(AudioError::DecodeFailed(diagnostics)) => {
+                assert!(!diagnostics.detected_preamble);
+                assert_eq!(diagnostics.symbols_decoded, 0);
+            }
+            other => panic!("expected DecodeFailed, got {:?}", other),
+        }
+    }
+
+    #[cfg(feature = "simd")]
+    #[test]
+    fn test_simd_and_scalar_demodulators_decode_identically() {
+        use crate::demod::{ScalarDemodulator, SimdDemodulator};
+
+        let mut scalar = AudioEngine::new();
+        scalar.set_demodulator(Arc::new(ScalarDemodulator));
+        let mut simd = AudioEngine::new();
+        simd.set_demodulator(Arc::new(SimdDemodulator));
+        assert_eq!(simd.demodulator_name(), "simd");
+
+        let sample_rate = scalar.get_config().sample_rate;
+        for (data, offset_hz) in [(&[0xA5u8, 0x3C][..], 60.0), (&[0xA5, 0x3C, 0x0F][..], 400.0)] {
+            let samples = fsk_samples(data, offset_hz, sample_rate);
+            assert_eq!(
+                format!("{:?}", scalar.decode_from_samples(&samples)),
+                format!("{:?}", simd.decode_from_samples(&samples))
+            );
+
+            let frequencies: Vec<f32> = (0..7).map(|i| ULTRASONIC_TONE_ZERO_HZ + i as f32 * 150.0).collect();
+            let chunk = &samples[..sample_rate as usize / 100];
+            let mut scalar_powers = vec![0.0f32; frequencies.len()];
+            let mut simd_powers = vec![0.0f32; frequencies.len()];
+            ScalarDemodulator.goertzel_powers(chunk, &frequencies, sample_rate as f32, &mut scalar_powers);
+            SimdDemodulator.goertzel_powers(chunk, &frequencies, sample_rate as f32, &mut simd_powers);
+            assert_eq!(scalar_powers, simd_powers);
+        }
+    }
+
+    fn peak(samples: &[f32]) -> f32 {
+        samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()))
+    }
+
+    #[tokio::test]
+    async fn test_output_normalized_to_target_peak() {
+        let mut engine = AudioEngine::new();
+        let samples = engine.encode_data_to_audio(b"level").await.unwrap();
+        assert!((peak(&samples) - NORMALIZED_PEAK).abs() < 1e-3);
+
+        engine.set_output_gain(0.5);
+        let samples = engine.encode_data_to_audio(b"level").await.unwrap();
+        assert!((peak(&samples) - NORMALIZED_PEAK * 0.5).abs() < 1e-3);
+    }
+
+    #[tokio::test]
+    async fn test_limiter_prevents_clipping() {
+        let mut engine = AudioEngine::new();
+        engine.force_initialize_for_testing();
+        engine.set_output_gain(10.0);
+
+        let samples = engine.encode_data_to_audio(b"loud").await.unwrap();
+        assert!(samples.iter().all(|s| s.abs() < 1.0));
+        assert!(peak(&samples) > NORMALIZED_PEAK);
+
+        // Limited output still decodes
+        assert_eq!(engine.decode_from_samples(&samples).unwrap(), b"loud".to_vec());
+
+        // Out-of-range gains are clamped
+        engine.set_output_gain(-3.0);
+        assert_eq!(engine.get_output_gain(), 0.0);
+        engine.set_output_gain(f32::INFINITY);
+        assert_eq!(engine.get_output_gain(), MAX_OUTPUT_GAIN);
+    }
+
+    #[tokio::test]
+    async fn test_quiet_hours_block_audible_band_only() {
+        let clock = crate::clock::MockClock::at_utc(23, 30);
+        let mut engine = AudioEngine::with_config(AudioConfig {
+            mode: AudioMode::Standard,
+            ..Default::default()
+        });
+        engine.force_initialize_for_testing();
+        engine.set_clock(Arc::new(clock.clone()));
+        engine.set_quiet_hours(Some(QuietHours::from_hours(22, 7, 0)));
+
+        assert!(engine.quiet_hours_active());
+        assert!(matches!(engine.send_data(b"night").await, Err(AudioError::QuietHoursActive)));
+
+        // Ultrasonic transmission is permitted inside quiet hours
+        engine.update_config(AudioConfig::default()).await.unwrap();
+        assert!(!engine.is_audible_band());
+        engine.send_data(b"night").await.unwrap();
+
+        // Audible transmission resumes once quiet hours end
+        engine.update_config(AudioConfig { mode: AudioMode::Standard, ..Default::default() }).await.unwrap();
+        clock.advance(Duration::from_secs(8 * 60 * 60));
+        assert!(!engine.quiet_hours_active());
+        engine.send_data(b"morning").await.unwrap();
+    }
+
+    /// `samples` with deterministic uniform noise of the given peak amplitude added
+    fn with_noise(samples: &[f32], amplitude: f32) -> Vec<f32> {
+        use rand::{Rng, SeedableRng};
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0x5eed);
+        samples.iter().map(|s| s + rng.gen_range(-amplitude..amplitude)).collect()
+    }
+
+    #[test]
+    fn test_mary_fsk_round_trip_and_low_snr_fallback() {
+        let data = b"m-ary fsk".to_vec();
+        let binary = AudioEngine::new();
+        let mut quad = AudioEngine::with_config(AudioConfig { tones_per_symbol: 4, ..Default::default() });
+
+        // 4-FSK halves the airtime at high SNR
+        let clean = quad.encode_to_samples(&data).unwrap();
+        assert_eq!(clean.len() * 2, binary.encode_to_samples(&data).unwrap().len());
+        assert_eq!(quad.decode_from_samples(&clean).unwrap(), data);
+
+        // 8-FSK pads the final symbol and still recovers whole bytes
+        let octal = AudioEngine::with_config(AudioConfig { tones_per_symbol: 8, ..Default::default() });
+        assert_eq!(octal.decode_from_samples(&octal.encode_to_samples(&data).unwrap()).unwrap(), data);
+
+        // Around 7.5 dB SNR binary FSK still decodes, but 4-FSK reports the failure
+        let noisy_binary = with_noise(&binary.encode_to_samples(&data).unwrap(), 0.465);
+        assert_eq!(binary.decode_from_samples(&noisy_binary).unwrap(), data);
+
+        let noisy_quad = with_noise(&clean, 0.465);
+        let snr_db = match quad.decode_from_samples(&noisy_quad) {
+            Err(AudioError::DecodeFailed(diagnostics)) => diagnostics.estimated_snr_db,
+            other => panic!("expected DecodeFailed, got {:?}", other),
+        };
+
+        // The adaptive path falls back to binary at that SNR and raises M when it improves
+        assert_eq!(quad.adapt_tones_per_symbol(snr_db), 2);
+        assert_eq!(quad.decode_from_samples(&noisy_binary).unwrap(), data);
+        assert_eq!(quad.adapt_tones_per_symbol(40.0), 8);
+
+        let unsupported = AudioEngine::with_config(AudioConfig { tones_per_symbol: 3, ..Default::default() });
+        assert!(matches!(unsupported.encode_to_samples(&data), Err(AudioError::InvalidParameters)));
+    }
+
+
+    #[tokio::test]
+    async fn test_tone_loopback_in_both_bands() {
+        for mode in [AudioMode::Ultrasonic, AudioMode::Standard] {
+            let mut engine = AudioEngine::with_config(AudioConfig { mode: mode.clone(), ..Default::default() });
+            engine.force_initialize_for_testing();
+
+            let payload = b"pairing over tones";
+            engine.transmit(payload).await.unwrap();
+            let played = engine.encode_tones(payload).unwrap();
+            assert_eq!(engine.get_status().transmit_buffer_size, played.len());
+            assert!(peak(&played) <= NORMALIZED_PEAK + 1e-6);
+
+            // Loop the playback back into the receiver after some silence
+            let mut captured = vec![0.0f32; 777];
+            captured.extend(with_noise(&played, 0.05));
+            let sample_rate = engine.get_config().sample_rate;
+            assert_eq!(engine.receive(&captured, sample_rate).unwrap(), Some(payload.to_vec()), "{:?}", mode);
+            assert_eq!(engine.receive(&captured[..777], sample_rate).unwrap(), None);
+        }
+    }
+
+    #[test]
+    fn test_hopping_packets_follow_the_shared_seed() {
+        let seed = *b"visual-chan-seed";
+        let mut sequence = HoppingSequence::new(seed);
+        let channels: Vec<usize> = (0..64).map(|_| sequence.next_channel()).collect();
+        assert!(channels.iter().all(|&channel| channel < HOPPING_CHANNELS));
+        assert!(channels.windows(2).all(|pair| pair[0] != pair[1]));
+        assert!(channels.iter().collect::<std::collections::HashSet<_>>().len() > HOPPING_CHANNELS / 2);
+
+        let mut sender = AudioEngine::new();
+        let mut receiver = AudioEngine::new();
+        sender.set_hopping_sequence(seed);
+        receiver.set_hopping_sequence(seed);
+
+        let packets: Vec<Vec<f32>> = (0..4u8).map(|i| sender.encode_to_samples(&[i, 0x5A]).unwrap()).collect();
+        assert_ne!(packets[0], packets[1]);
+
+        assert_eq!(receiver.decode_from_samples(&packets[0]).unwrap(), [0, 0x5A]);
+        assert_eq!(receiver.decode_from_samples(&packets[1]).unwrap(), [1, 0x5A]);
+        // Packet 2 is lost; the receiver catches up on packet 3
+        assert_eq!(receiver.decode_from_samples(&packets[3]).unwrap(), [3, 0x5A]);
+
+        // Without the seed, or with one bit of it wrong, the hop sequence agrees
+        // with the shared one about as often as chance (1 in 15) over many hops
+        let mut near_seed = seed;
+        near_seed[15] ^= 1;
+        for other_seed in [*b"guessed-the-seed", near_seed] {
+            let mut shared = HoppingSequence::new(seed);
+            let mut other = HoppingSequence::new(other_seed);
+            let hops = 4096;
+            let (shared_hops, other_hops): (Vec<usize>, Vec<usize>) =
+                (0..hops).map(|_| (shared.next_channel(), other.next_channel())).unzip();
+            assert_ne!(shared_hops, other_hops);
+            let agreeing = shared_hops.iter().zip(&other_hops).filter(|(a, b)| a == b).count();
+            assert!(agreeing < hops / 10, "{} of {} hops agree", agreeing, hops);
+        }
+    }
+
+    /// Microphone tap hearing one scripted level per capture, then silence
+    struct ScriptedCapture(std::sync::Mutex<VecDeque<f32>>);
+
+    impl ScriptedCapture {
+        fn new(levels: &[f32]) -> Arc<Self> {
+            Arc::new(Self(std::sync::Mutex::new(levels.iter().copied().collect())))
+        }
+    }
+
+    impl AudioCapture for ScriptedCapture {
+        fn capture(&self, samples: usize) -> Result<Vec<f32>, AudioError> {
+            let level = self.0.lock().unwrap().pop_front().unwrap_or(0.0);
+            Ok((0..samples).map(|i| level * (i as f32 * 0.7).sin()).collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_half_duplex_waits_for_a_quiet_channel() {
+        let half_duplex = || {
+            let mut engine = AudioEngine::with_config(AudioConfig {
+                duplex_mode: DuplexMode::HalfDuplex { backoff_ms: 5 },
+                ..Default::default()
+            });
+            engine.force_initialize_for_testing();
+            engine
+        };
+        let frame_len = AudioEngine::new().encode_to_samples(b"ours").unwrap().len();
+
+        // Without a capture device only newly received audio is heard; samples
+        // already sensed are not heard again on the next attempt
+        let engine = half_duplex();
+        assert!(!engine.channel_busy());
+        engine.simulate_receive(b"peer").await.unwrap();
+        let sense = engine.carrier_sense();
+        assert!(sense.busy);
+        assert!(sense.energy_db > engine.get_config().noise_floor_db);
+        assert!(!engine.channel_busy());
+
+        // A peer transmitting throughout is heard by every fresh capture
+        let mut engine = half_duplex();
+        engine.set_capture(Some(ScriptedCapture::new(&[0.5; MAX_CARRIER_SENSE_ATTEMPTS as usize])));
+        assert!(matches!(
+            engine.send_data(b"ours").await,
+            Err(AudioError::ChannelBusy(MAX_CARRIER_SENSE_ATTEMPTS))
+        ));
+        assert_eq!(engine.transmit_buffer.lock().await.len(), 0);
+
+        // Energy right after our frame is a collision: the frame goes out again
+        let mut engine = half_duplex();
+        engine.set_capture(Some(ScriptedCapture::new(&[0.0, 0.5, 0.0, 0.0])));
+        engine.send_data(b"ours").await.unwrap();
+        assert_eq!(engine.transmit_buffer.lock().await.len(), 2 * frame_len);
+
+        let mut engine = half_duplex();
+        engine.set_capture(Some(ScriptedCapture::new(&[0.0, 0.5].repeat(MAX_COLLISION_ATTEMPTS as usize))));
+        assert!(matches!(engine.send_data(b"ours").await, Err(AudioError::Collision(MAX_COLLISION_ATTEMPTS))));
+
+        // Full duplex transmits regardless
+        let mut full = AudioEngine::new();
+        full.force_initialize_for_testing();
+        full.set_capture(Some(ScriptedCapture::new(&[0.5; 4])));
+        assert!(full.channel_busy());
+        full.send_data(b"ours").await.unwrap();
+        assert_eq!(full.transmit_buffer.lock().await.len(), frame_len);
+    }
+}
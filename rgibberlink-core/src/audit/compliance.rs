@@ -12,7 +12,7 @@ use serde::{Deserialize, Serialize};
 use std::time::SystemTime;
 use std::collections::HashMap;
 
-use super::events::{AuditEntry, AuditEventType, SecurityAlert, AlertType, AlertStatus, AuditSeverity};
+use super::events::{AuditEntry, AuditError, AuditEventType, SecurityAlert, AlertType, AlertStatus, AuditSeverity};
 use crate::weather::ViolationSeverity;
 
 /// Compliance engine for regulatory and policy validation
@@ -243,7 +243,7 @@ impl ComplianceEngine {
                     CompliancePriority::Critical => {
                         if !matches!(severity, AuditSeverity::Critical) {
                             flags.push(ComplianceFlag::Violation {
-                                severity: ViolationSeverity::High,
+                                severity: ViolationSeverity::Critical,
                                 code: "CRITICAL_SEVERITY_REQUIRED".to_string(),
                                 message: "Critical operations require critical severity".to_string(),
                             });
@@ -262,23 +262,6 @@ impl ComplianceEngine {
     }
 }
 
-/// Audit errors
-#[derive(Debug, thiserror::Error)]
-pub enum AuditError {
-    #[error("Invalid audit entry: {0}")]
-    InvalidEntry(String),
-    #[error("Storage limit exceeded")]
-    StorageLimitExceeded,
-    #[error("Report generation failed: {0}")]
-    ReportGenerationError(String),
-    #[error("Alert not found")]
-    AlertNotFound,
-    #[error("Query execution failed")]
-    QueryError,
-    #[error("Compliance validation failed: {0}")]
-    ComplianceError(String),
-}
-
 /// Compliance validation flags
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum ComplianceFlag {
@@ -296,7 +279,7 @@ mod tests {
     #[tokio::test]
     async fn test_compliance_engine_creation() {
         let compliance_engine = ComplianceEngine::new();
-        assert!(!compliance_engine.regulatory_frameworks.is_empty()); // Should have default rules
+        assert!(!compliance_engine.compliance_rules.is_empty()); // Should have default rules
     }
 
     #[tokio::test]
@@ -5,9 +5,11 @@
 use serde::{Deserialize, Serialize};
 use std::time::{SystemTime, Duration};
 use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
 use crate::mission::{MissionId, MissionPriority};
 use crate::weather::{RiskLevel, ViolationSeverity};
-use super::compliance::{ComplianceEngine, SecurityAlert};
+use super::compliance::ComplianceEngine;
 
 /// Comprehensive audit system for drone mission operations
 pub struct AuditSystem {
@@ -49,8 +51,18 @@ pub enum AuditEventType {
     EmergencyAction,
     SystemHealthEvent,
     ComplianceAudit,
+    PinLockout,
+    PermissionGranted,
+    PermissionRevoked,
+    ModeDowngrade,
+    TamperDetected,
+    TamperReset,
+    KeyRotation,
 }
 
+/// Audit trail shared by the engines that emit security-relevant events
+pub type SharedAuditSystem = Arc<Mutex<AuditSystem>>;
+
 /// Audit severity levels
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, PartialOrd)]
 pub enum AuditSeverity {
@@ -512,6 +524,42 @@ pub enum AuditError {
     QueryError,
 }
 
+/// Record a security-relevant state transition reported by one of the engines.
+/// Auditing is best effort: a failure to record never blocks the transition.
+pub async fn record_security_event(
+    audit: &SharedAuditSystem,
+    event_type: AuditEventType,
+    severity: AuditSeverity,
+    component: &str,
+    operation_name: &str,
+    details: Option<String>,
+) {
+    let actor = AuditActor::System {
+        component: component.to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        subsystem: "security".to_string(),
+    };
+    let operation = AuditOperation {
+        operation_type: "state_transition".to_string(),
+        operation_name: operation_name.to_string(),
+        parameters: HashMap::new(),
+        execution_context: OperationContext::default(),
+        expected_duration: None,
+        resource_consumption: ResourceConsumption::default(),
+    };
+    let result = OperationResult {
+        success: true,
+        error_code: None,
+        error_message: None,
+        duration_ms: 0,
+        performance_metrics: PerformanceMetrics::default(),
+        side_effects: details.into_iter().collect(),
+    };
+
+    let entry = create_audit_entry(event_type, severity, actor, operation, result, AuditContext::default());
+    let _ = audit.lock().await.record_event(entry);
+}
+
 /// Quick audit entry creation helper
 pub fn create_audit_entry(
     event_type: AuditEventType,
@@ -22,6 +22,8 @@ pub use events::{
     AuditActor,
     AuditOperation,
     create_audit_entry,
+    record_security_event,
+    SharedAuditSystem,
     AuditQuery,
     ActorFilter,
     ReportRequest,
@@ -6,6 +6,7 @@
 //! state tracking.
 
 use crate::crypto::CryptoEngine;
+use crate::range_detector::RangeEnvironmentalConditions;
 use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -30,7 +31,14 @@ pub struct ChannelData {
     pub channel_type: ChannelType,
     pub data: Vec<u8>,
     pub timestamp: Instant,
+    /// Pairs the laser and ultrasound legs of one coupled exchange
     pub sequence_id: u64,
+    /// Per-channel transmit counter, checked against the replay window
+    pub sequence_number: u64,
+    /// Receiver samples holding this leg's preamble, captured from the start
+    /// both receivers share for the exchange. With both legs captured, the
+    /// arrival delta is their correlated offset rather than the timestamps
+    pub preamble_capture: Option<Vec<f32>>,
 }
 
 /// Types of communication channels
@@ -57,37 +65,283 @@ pub struct ChannelQuality {
     pub latency_ms: u64,
 }
 
+/// Number of recent laser/ultrasound arrival deltas kept for calibration
+const COUPLING_HISTORY_LEN: usize = 256;
+/// Narrowest window calibration will set, to absorb scheduling jitter
+const MIN_CALIBRATED_TOLERANCE_MS: u64 = 10;
+/// Default half-width of the fixed temporal coupling window
+pub const DEFAULT_COUPLING_WINDOW_MS: u64 = 100;
+/// Speed of light in air, m/s
+const SPEED_OF_LIGHT_MPS: f64 = 299_702_547.0;
+/// Coupled exchanges observed before a sliding window narrows below its maximum
+const SLIDING_WINDOW_MIN_SAMPLES: u64 = 8;
+/// Sequence numbers tracked per channel by the replay window
+const REPLAY_WINDOW_LEN: u64 = 1024;
+const REPLAY_WINDOW_WORDS: usize = (REPLAY_WINDOW_LEN / 64) as usize;
+/// Symbols in the synchronization preamble both channels prepend
+pub const PREAMBLE_LEN: usize = 32;
+/// Zadoff-Chu root of the default preamble; must be coprime with its length
+pub const PREAMBLE_ZADOFF_CHU_ROOT: u32 = 1;
+/// Default preamble symbol rate
+pub const PREAMBLE_SYMBOL_RATE_HZ: f32 = 1000.0;
+/// Drive and capture samples per preamble symbol
+pub const PREAMBLE_SAMPLES_PER_SYMBOL: usize = 8;
+/// Samples per subcarrier cycle; symbols hold whole cycles so each one's phase
+/// is its Zadoff-Chu phase
+pub const PREAMBLE_SAMPLES_PER_CYCLE: usize = 4;
+/// Normalized correlation, 0.0-1.0, a capture needs to count as holding the preamble
+const PREAMBLE_DETECTION_THRESHOLD: f64 = 0.5;
+
+/// Zadoff-Chu sequence of `len` symbols, each quantized to a phase in 1/256ths
+/// of a turn. Its flat cyclic autocorrelation gives a single sharp peak when
+/// correlated against a received preamble
+pub fn zadoff_chu_preamble(root: u32, len: usize) -> Vec<u8> {
+    let cf = (len % 2) as u64;
+    (0..len as u64)
+        .map(|n| {
+            // Phase -pi*u*n*(n+cf)/N, as a fraction of a turn
+            let turns = (root as u64 * n * (n + cf)) as f64 / (2 * len) as f64;
+            (256 - (turns.fract() * 256.0).round() as u32 % 256) as u8
+        })
+        .collect()
+}
+
+/// Drive waveform of a preamble, `PREAMBLE_SAMPLES_PER_SYMBOL` samples per
+/// symbol: a subcarrier whose phase steps through the Zadoff-Chu phases, as an
+/// intensity or amplitude envelope between 0.0 and 1.0
+pub fn preamble_waveform(sequence: &[u8]) -> Vec<f32> {
+    preamble_template(sequence).into_iter().map(|(re, _)| (0.5 + 0.5 * re) as f32).collect()
+}
+
+/// Unit phasors of the subcarrier carrying `sequence`, one per sample. A real
+/// capture correlated against these recovers the complex Zadoff-Chu symbols,
+/// whose correlation peak a real-part-only template would blur
+fn preamble_template(sequence: &[u8]) -> Vec<(f64, f64)> {
+    sequence.iter()
+        .flat_map(|&phase| (0..PREAMBLE_SAMPLES_PER_SYMBOL).map(move |n| (phase, n)))
+        .map(|(phase, n)| {
+            let angle = std::f64::consts::TAU * (n as f64 / PREAMBLE_SAMPLES_PER_CYCLE as f64 + phase as f64 / 256.0);
+            (angle.cos(), angle.sin())
+        })
+        .collect()
+}
+
+/// Sliding-window temporal coupling: the window is centered on the rolling mean
+/// of arrival deltas from validated exchanges and spans `k` rolling standard
+/// deviations either side, so it follows slow drifts such as wind-driven
+/// ultrasound delay
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlidingWindowCouplingConfig {
+    pub min_window_ms: u32,
+    pub max_window_ms: u32,
+    /// Weight of each new delta in the rolling mean and variance, 0.0-1.0
+    pub adaptation_rate: f32,
+    pub k: f32,
+    /// Furthest the center may follow observed deltas away from the
+    /// propagation estimate plus `coupling_offset_ms`
+    pub max_center_shift_ms: u32,
+}
+
+impl Default for SlidingWindowCouplingConfig {
+    fn default() -> Self {
+        Self {
+            min_window_ms: 10,
+            max_window_ms: 100,
+            adaptation_rate: 0.1,
+            k: 3.0,
+            max_center_shift_ms: 100,
+        }
+    }
+}
+
+/// Exponentially weighted mean and variance of arrival deltas beyond propagation
+#[derive(Debug, Clone, Copy, Default)]
+struct CouplingStats {
+    mean_ms: f64,
+    variance_ms2: f64,
+    samples: u64,
+}
+
+impl CouplingStats {
+    fn observe(&mut self, delta_ms: f64, adaptation_rate: f64) {
+        if self.samples == 0 {
+            self.mean_ms = delta_ms;
+        } else {
+            let deviation = delta_ms - self.mean_ms;
+            let increment = adaptation_rate * deviation;
+            self.mean_ms += increment;
+            self.variance_ms2 = (1.0 - adaptation_rate) * (self.variance_ms2 + deviation * increment);
+        }
+        self.samples += 1;
+    }
+}
+
+/// Bitmap of the last `REPLAY_WINDOW_LEN` sequence numbers seen on a channel;
+/// bit `i` marks `highest - i`
+#[derive(Debug, Clone, Default)]
+struct ReplayWindow {
+    highest: Option<u64>,
+    seen: [u64; REPLAY_WINDOW_WORDS],
+}
+
+impl ReplayWindow {
+    /// Record `sequence_number`, or return false if it was already seen or is
+    /// more than `out_of_order_tolerance` behind the newest. Counters compare
+    /// modulo 2^64, so a wrap from `u64::MAX` to 0 counts as moving forward
+    fn accept(&mut self, sequence_number: u64, out_of_order_tolerance: u64) -> bool {
+        let Some(highest) = self.highest else {
+            self.highest = Some(sequence_number);
+            self.mark(0);
+            return true;
+        };
+
+        let ahead = sequence_number.wrapping_sub(highest);
+        if ahead != 0 && ahead < 1 << 63 {
+            self.advance(ahead);
+            self.highest = Some(sequence_number);
+            self.mark(0);
+            return true;
+        }
+
+        let behind = highest.wrapping_sub(sequence_number);
+        if behind > out_of_order_tolerance.min(REPLAY_WINDOW_LEN - 1) || self.is_marked(behind) {
+            return false;
+        }
+        self.mark(behind);
+        true
+    }
+
+    /// Age every entry by `by` sequence numbers
+    fn advance(&mut self, by: u64) {
+        if by >= REPLAY_WINDOW_LEN {
+            self.seen = [0; REPLAY_WINDOW_WORDS];
+            return;
+        }
+        let (words, bits) = ((by / 64) as usize, by % 64);
+        for i in (0..REPLAY_WINDOW_WORDS).rev() {
+            let mut word = if i >= words { self.seen[i - words] << bits } else { 0 };
+            if bits > 0 && i > words {
+                word |= self.seen[i - words - 1] >> (64 - bits);
+            }
+            self.seen[i] = word;
+        }
+    }
+
+    fn mark(&mut self, behind: u64) {
+        self.seen[(behind / 64) as usize] |= 1 << (behind % 64);
+    }
+
+    fn is_marked(&self, behind: u64) -> bool {
+        self.seen[(behind / 64) as usize] & (1 << (behind % 64)) != 0
+    }
+}
+
 /// Validation configuration
 #[derive(Debug, Clone)]
 pub struct ValidationConfig {
+    /// Half-width of the temporal coupling window around the expected skew
+    pub coupling_window_ms: u64,
+    /// Former name of `coupling_window_ms`; a value off the default still
+    /// takes precedence, so existing configurations keep their window
+    #[deprecated(since = "0.3.0", note = "use `coupling_window_ms`")]
     pub temporal_tolerance_ms: u64,
+    /// Ultrasound-minus-laser arrival delta not explained by propagation,
+    /// e.g. processing latency; added to the expected skew
+    pub coupling_offset_ms: i64,
+    /// Peer range used to predict the ultrasound propagation lag; `None`
+    /// treats the peer as co-located
+    pub range_estimate_m: Option<f32>,
+    /// Set from `RangeEnvironmentalConditions` by `set_environment`
+    pub speed_of_sound_mps: f32,
+    /// Track observed deltas instead of the fixed offset and window; `None` keeps them fixed
+    pub sliding_window: Option<SlidingWindowCouplingConfig>,
+    /// Preamble symbol phases prepended on both channels, see `zadoff_chu_preamble`
+    pub preamble_sequence: Vec<u8>,
+    /// Preamble symbol rate; captures hold `PREAMBLE_SAMPLES_PER_SYMBOL` samples per symbol
+    pub preamble_symbol_rate_hz: f32,
     pub quality_threshold: f32,
     pub max_replay_window_ms: u64,
+    /// How far behind the newest sequence number a late frame may arrive
+    pub out_of_order_tolerance: u64,
     pub fallback_enabled: bool,
     pub min_coupling_quality: f32,
 }
 
 impl Default for ValidationConfig {
+    #[allow(deprecated)]
     fn default() -> Self {
         Self {
-            temporal_tolerance_ms: 100, // 100ms default window
+            coupling_window_ms: DEFAULT_COUPLING_WINDOW_MS, // ±100ms around the expected skew
+            temporal_tolerance_ms: DEFAULT_COUPLING_WINDOW_MS,
+            coupling_offset_ms: 0,
+            range_estimate_m: None,
+            speed_of_sound_mps: RangeEnvironmentalConditions::default().speed_of_sound(),
+            sliding_window: None,
+            preamble_sequence: zadoff_chu_preamble(PREAMBLE_ZADOFF_CHU_ROOT, PREAMBLE_LEN),
+            preamble_symbol_rate_hz: PREAMBLE_SYMBOL_RATE_HZ, // 1ms symbols
             quality_threshold: 0.7,     // 70% quality threshold
             max_replay_window_ms: 5000, // 5 second replay window
+            out_of_order_tolerance: 64,
             fallback_enabled: true,
             min_coupling_quality: 0.6,  // 60% minimum coupling quality
         }
     }
 }
 
+impl ValidationConfig {
+    /// Take the speed of sound, hence the expected ultrasound lag, from `conditions`
+    pub fn set_environment(&mut self, conditions: &RangeEnvironmentalConditions) {
+        self.speed_of_sound_mps = conditions.speed_of_sound();
+    }
+
+    /// Half-width of the fixed temporal window: `coupling_window_ms`, unless the
+    /// deprecated `temporal_tolerance_ms` was moved off the default
+    #[allow(deprecated)]
+    pub fn effective_coupling_window_ms(&self) -> u64 {
+        if self.temporal_tolerance_ms != DEFAULT_COUPLING_WINDOW_MS {
+            self.temporal_tolerance_ms
+        } else {
+            self.coupling_window_ms
+        }
+    }
+
+    /// Set the fixed window half-width under both its names
+    #[allow(deprecated)]
+    fn set_coupling_window_ms(&mut self, window_ms: u64) {
+        self.coupling_window_ms = window_ms;
+        self.temporal_tolerance_ms = window_ms;
+    }
+
+    /// Ultrasound-minus-laser propagation delay over `range_m`; at 200m the
+    /// sound leg lags by roughly 580ms at 20°C
+    pub fn propagation_skew_ms(&self, range_m: f32) -> f64 {
+        let range_m = range_m.max(0.0) as f64;
+        (range_m / self.speed_of_sound_mps.max(1.0) as f64 - range_m / SPEED_OF_LIGHT_MPS) * 1000.0
+    }
+
+    /// Propagation lag at the range estimate, zero without one
+    pub fn expected_propagation_ms(&self) -> f64 {
+        self.range_estimate_m.map_or(0.0, |range| self.propagation_skew_ms(range))
+    }
+
+    /// Expected ultrasound-minus-laser arrival delta; the fixed temporal window
+    /// is centered here
+    pub fn expected_skew_ms(&self) -> i64 {
+        self.coupling_offset_ms + self.expected_propagation_ms().round() as i64
+    }
+}
+
 /// Comprehensive validation errors
 #[derive(Debug, thiserror::Error)]
 pub enum ValidationError {
-    #[error("Temporal coupling failed: channels arrived {0}ms apart (tolerance: {1}ms)")]
+    #[error("Temporal coupling failed: arrival {0}ms off the expected skew (window: ±{1}ms)")]
     TemporalCouplingFailed(u64, u64),
     #[error("Cross-channel signature verification failed")]
     CrossChannelSignatureFailed,
     #[error("Anti-replay check failed: nonce already used")]
     AntiReplayFailed,
+    #[error("Replay detected: {0:?} sequence number {1} already seen or too old")]
+    ReplayDetected(ChannelType, u64),
     #[error("Channel quality below threshold: {0} < {1}")]
     QualityThresholdFailed(f32, f32),
     #[error("Invalid validation phase transition")]
@@ -98,6 +352,18 @@ pub enum ValidationError {
     Timeout,
     #[error("Fallback validation failed")]
     FallbackFailed,
+    #[error("Not enough coupled exchanges to calibrate: {0} of {1}")]
+    InsufficientCalibrationSamples(usize, usize),
+    #[error("Preamble missing from a coupled exchange or not found in its capture")]
+    PreambleNotFound,
+}
+
+/// Result of a coupling window calibration
+#[derive(Debug, Clone, PartialEq)]
+pub struct CouplingCalibration {
+    pub offset_ms: i64,
+    pub tolerance_ms: u64,
+    pub samples: usize,
 }
 
 /// Channel validator for coupled authentication
@@ -111,6 +377,9 @@ pub struct ChannelValidator {
     session_start: Instant,
     validation_metrics: Arc<Mutex<ValidationMetrics>>,
     session_key: Option<[u8; 32]>, // Session key for cross-channel signatures
+    coupling_deltas: Arc<Mutex<VecDeque<i64>>>, // Ultrasound minus laser arrival (ms) per validated exchange
+    coupling_stats: Arc<Mutex<CouplingStats>>,
+    replay_windows: Arc<Mutex<HashMap<ChannelType, ReplayWindow>>>,
 }
 
 /// Validation performance metrics
@@ -153,11 +422,16 @@ impl ChannelValidator {
                 average_validation_time_ms: 0.0,
             })),
             session_key: None,
+            coupling_deltas: Arc::new(Mutex::new(VecDeque::with_capacity(COUPLING_HISTORY_LEN))),
+            coupling_stats: Arc::new(Mutex::new(CouplingStats::default())),
+            replay_windows: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
     /// Receive data from a channel with timestamp
     pub async fn receive_channel_data(&self, data: ChannelData) -> Result<(), ValidationError> {
+        self.check_sequence_number(&data).await?;
+
         match data.channel_type {
             ChannelType::Laser => {
                 let mut buffer = self.laser_buffer.lock().await;
@@ -202,35 +476,41 @@ impl ChannelValidator {
         };
 
         if let (Some(laser), Some(ultrasound)) = (laser_data, ultrasound_data) {
-            self.perform_full_validation(laser, ultrasound).await
+            self.perform_full_validation(&laser, &ultrasound).await?;
+            // Only an exchange that passed every check may steer the window
+            if laser.sequence_id == ultrasound.sequence_id {
+                self.observe_validated_delta(&laser, &ultrasound).await;
+            }
+            Ok(())
         } else {
             Ok(()) // Not enough data yet
         }
     }
 
     /// Perform complete coupled channel validation
-    async fn perform_full_validation(&self, laser_data: ChannelData, ultrasound_data: ChannelData) -> Result<(), ValidationError> {
+    async fn perform_full_validation(&self, laser_data: &ChannelData, ultrasound_data: &ChannelData) -> Result<(), ValidationError> {
         let validation_start = Instant::now();
-        let mut metrics = self.validation_metrics.lock().await;
-        metrics.total_validations += 1;
+        // Don't hold the metrics lock across phases: failing phases record into it
+        self.validation_metrics.lock().await.total_validations += 1;
 
         // Phase 1: Temporal coupling validation
-        self.validate_temporal_coupling(&laser_data, &ultrasound_data).await?;
+        self.validate_temporal_coupling(laser_data, ultrasound_data).await?;
         self.update_phase(ValidationPhase::TemporalCouplingValidated).await;
 
         // Phase 2: Cross-channel signature verification
-        self.validate_cross_channel_signature(&laser_data, &ultrasound_data).await?;
+        self.validate_cross_channel_signature(laser_data, ultrasound_data).await?;
         self.update_phase(ValidationPhase::CrossChannelSignatureVerified).await;
 
         // Phase 3: Anti-replay protection
-        self.validate_anti_replay(&laser_data, &ultrasound_data).await?;
+        self.validate_anti_replay(laser_data, ultrasound_data).await?;
         self.update_phase(ValidationPhase::AntiReplayCheckPassed).await;
 
         // Phase 4: Channel quality validation
-        self.validate_channel_quality(&laser_data, &ultrasound_data).await?;
+        self.validate_channel_quality(laser_data, ultrasound_data).await?;
         self.update_phase(ValidationPhase::FullyValidated).await;
 
         // Update metrics
+        let mut metrics = self.validation_metrics.lock().await;
         metrics.successful_validations += 1;
         let validation_time = validation_start.elapsed().as_millis() as f64;
         metrics.average_validation_time_ms =
@@ -240,18 +520,202 @@ impl ChannelValidator {
         Ok(())
     }
 
+    /// Feed the arrival delta of a validated exchange, less propagation, to the
+    /// sliding-window statistics and the calibration history
+    async fn observe_validated_delta(&self, laser: &ChannelData, ultrasound: &ChannelData) {
+        let Some(delta_ms) = self.arrival_delta_ms(laser, ultrasound) else {
+            return;
+        };
+        // Store the lag beyond propagation so a range change doesn't skew calibration
+        let latency_ms = delta_ms - self.config.expected_propagation_ms().round() as i64;
+        {
+            let mut deltas = self.coupling_deltas.lock().await;
+            if deltas.len() >= COUPLING_HISTORY_LEN {
+                deltas.pop_front();
+            }
+            deltas.push_back(latency_ms);
+        }
+        let adaptation_rate = self.config.sliding_window.as_ref()
+            .map_or(SlidingWindowCouplingConfig::default().adaptation_rate, |sliding| sliding.adaptation_rate);
+        self.coupling_stats.lock().await.observe(latency_ms as f64, adaptation_rate.clamp(0.0, 1.0) as f64);
+    }
+
+    /// Observed latency beyond propagation: the rolling mean, held within
+    /// `max_center_shift_ms` of `coupling_offset_ms`, or the offset itself
+    /// before any exchange validated
+    fn observed_latency_ms(&self, stats: &CouplingStats) -> f64 {
+        let offset_ms = self.config.coupling_offset_ms as f64;
+        if stats.samples == 0 {
+            return offset_ms;
+        }
+        let max_shift = self.config.sliding_window.as_ref()
+            .map_or(SlidingWindowCouplingConfig::default().max_center_shift_ms, |sliding| sliding.max_center_shift_ms) as f64;
+        stats.mean_ms.clamp(offset_ms - max_shift, offset_ms + max_shift)
+    }
+
+    /// Ultrasound arrival minus laser arrival in milliseconds: the correlated
+    /// preamble offset when both legs carry a capture, the arrival timestamps
+    /// only when neither does. `None` if just one leg carries a capture or a
+    /// preamble is not found
+    fn arrival_delta_ms(&self, laser: &ChannelData, ultrasound: &ChannelData) -> Option<i64> {
+        match (&laser.preamble_capture, &ultrasound.preamble_capture) {
+            (Some(laser_buf), Some(ultrasound_buf)) => {
+                let offset_ms = self.correlate_channel_preambles(laser_buf, ultrasound_buf);
+                offset_ms.is_finite().then(|| offset_ms.round() as i64)
+            }
+            (None, None) if ultrasound.timestamp >= laser.timestamp => {
+                Some((ultrasound.timestamp - laser.timestamp).as_millis() as i64)
+            }
+            (None, None) => Some(-((laser.timestamp - ultrasound.timestamp).as_millis() as i64)),
+            _ => None,
+        }
+    }
+
+    /// Center and half-width of the temporal window, in ms
+    async fn coupling_window(&self) -> (f64, u64) {
+        let Some(sliding) = &self.config.sliding_window else {
+            return (self.config.expected_skew_ms() as f64, self.config.effective_coupling_window_ms());
+        };
+        let stats = *self.coupling_stats.lock().await;
+        let center = self.config.expected_propagation_ms().round() + self.observed_latency_ms(&stats);
+
+        // Too few deltas for a meaningful spread: stay wide open
+        let half_width = if stats.samples < SLIDING_WINDOW_MIN_SAMPLES {
+            sliding.max_window_ms
+        } else {
+            let spread = (sliding.k as f64 * stats.variance_ms2.sqrt()).ceil() as u32;
+            spread.clamp(sliding.min_window_ms, sliding.max_window_ms.max(sliding.min_window_ms))
+        };
+        (center, half_width as u64)
+    }
+
+    /// Distance of the arrival delta from the window center, and the window
+    /// half-width; the distance is `None` without a usable arrival delta
+    async fn coupling_deviation_ms(&self, laser: &ChannelData, ultrasound: &ChannelData) -> (Option<u64>, u64) {
+        let (center, half_width) = self.coupling_window().await;
+        let deviation = self.arrival_delta_ms(laser, ultrasound).map(|delta| (delta as f64 - center).abs().round() as u64);
+        (deviation, half_width)
+    }
+
+    /// Reject a frame whose sequence number its channel has already delivered
+    async fn check_sequence_number(&self, data: &ChannelData) -> Result<(), ValidationError> {
+        let fresh = self.replay_windows.lock().await
+            .entry(data.channel_type.clone())
+            .or_default()
+            .accept(data.sequence_number, self.config.out_of_order_tolerance);
+        if !fresh {
+            self.validation_metrics.lock().await.anti_replay_failures += 1;
+            return Err(ValidationError::ReplayDetected(data.channel_type.clone(), data.sequence_number));
+        }
+        Ok(())
+    }
+
+    /// Estimated ultrasound-minus-laser arrival skew: the propagation lag at the
+    /// range estimate plus the rolling mean of the observed remainder
+    pub async fn get_channel_skew_ms(&self) -> f32 {
+        let stats = *self.coupling_stats.lock().await;
+        (self.config.expected_propagation_ms() + self.observed_latency_ms(&stats)) as f32
+    }
+
+    /// Ultrasound-minus-laser timing offset in milliseconds, measured by
+    /// cross-correlating each channel's captured samples against the known
+    /// preamble waveform (see `preamble_waveform`). Needs no shared clock
+    /// between the transmitters; the peaks are refined to a fraction of a
+    /// sample. NaN if either buffer is shorter than the preamble or holds no
+    /// preamble
+    pub fn correlate_channel_preambles(&self, laser_buf: &[f32], ultrasound_buf: &[f32]) -> f32 {
+        let template = preamble_template(&self.config.preamble_sequence);
+        match (Self::preamble_peak(laser_buf, &template), Self::preamble_peak(ultrasound_buf, &template)) {
+            (Some(laser), Some(ultrasound)) => {
+                let sample_rate_hz = self.config.preamble_symbol_rate_hz.max(f32::EPSILON) as f64 * PREAMBLE_SAMPLES_PER_SYMBOL as f64;
+                ((ultrasound - laser) / sample_rate_hz * 1000.0) as f32
+            }
+            _ => f32::NAN,
+        }
+    }
+
+    /// Sample index where `template` best matches `buf`, with parabolic
+    /// interpolation around the correlation peak. `None` unless the best match
+    /// reaches `PREAMBLE_DETECTION_THRESHOLD` of a clean preamble's
+    fn preamble_peak(buf: &[f32], template: &[(f64, f64)]) -> Option<f64> {
+        if template.is_empty() || buf.len() < template.len() {
+            return None;
+        }
+        let correlation: Vec<f64> = buf.windows(template.len())
+            .map(|window| {
+                let (re, im) = window.iter().zip(template)
+                    .fold((0.0, 0.0), |(re, im), (&x, &(t_re, t_im))| (re + x as f64 * t_re, im - x as f64 * t_im));
+                (re * re + im * im).sqrt()
+            })
+            .collect();
+        let peak = correlation.iter().enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(lag, _)| lag)?;
+
+        // A real capture meets only half the phasor's energy, hence the factor 2
+        let window = &buf[peak..peak + template.len()];
+        let mean = window.iter().map(|&x| x as f64).sum::<f64>() / window.len() as f64;
+        let energy: f64 = window.iter().map(|&x| (x as f64 - mean).powi(2)).sum();
+        let score = correlation[peak] * 2.0_f64.sqrt() / (energy * template.len() as f64).sqrt().max(f64::EPSILON);
+        if score < PREAMBLE_DETECTION_THRESHOLD {
+            return None;
+        }
+        if peak == 0 || peak + 1 == correlation.len() {
+            return Some(peak as f64);
+        }
+        let (before, at, after) = (correlation[peak - 1], correlation[peak], correlation[peak + 1]);
+        let curvature = before - 2.0 * at + after;
+        let refinement = if curvature < 0.0 { 0.5 * (before - after) / curvature } else { 0.0 };
+        Some(peak as f64 + refinement)
+    }
+
+    /// Update the peer range the expected ultrasound lag is derived from
+    pub fn set_range_estimate(&mut self, range_m: Option<f32>) {
+        self.config.range_estimate_m = range_m;
+    }
+
+    /// Re-center the temporal window on the typical arrival delta of the last
+    /// `samples` validated coupled exchanges and narrow it to their observed
+    /// spread.
+    /// Propagation lag at the range estimate of each exchange is excluded, so
+    /// the offset only captures fixed latency
+    pub async fn calibrate_coupling(&mut self, samples: usize) -> Result<CouplingCalibration, ValidationError> {
+        let deltas = self.coupling_deltas.lock().await;
+        if samples == 0 || deltas.len() < samples {
+            return Err(ValidationError::InsufficientCalibrationSamples(deltas.len(), samples));
+        }
+
+        let mut recent: Vec<i64> = deltas.iter().rev().take(samples).copied().collect();
+        drop(deltas);
+        recent.sort_unstable();
+
+        // Median is robust against the odd delayed exchange
+        let offset_ms = recent[recent.len() / 2];
+        let max_deviation = recent.iter().map(|d| (d - offset_ms).unsigned_abs()).max().unwrap_or(0);
+        let tolerance_ms = (max_deviation * 2).max(MIN_CALIBRATED_TOLERANCE_MS);
+
+        self.config.coupling_offset_ms = offset_ms;
+        self.config.set_coupling_window_ms(tolerance_ms);
+
+        Ok(CouplingCalibration {
+            offset_ms,
+            tolerance_ms,
+            samples,
+        })
+    }
+
     /// Validate temporal coupling between channels
     async fn validate_temporal_coupling(&self, laser: &ChannelData, ultrasound: &ChannelData) -> Result<(), ValidationError> {
-        let time_diff = if laser.timestamp > ultrasound.timestamp {
-            (laser.timestamp - ultrasound.timestamp).as_millis() as u64
-        } else {
-            (ultrasound.timestamp - laser.timestamp).as_millis() as u64
+        let (time_diff, window_ms) = self.coupling_deviation_ms(laser, ultrasound).await;
+        let Some(time_diff) = time_diff else {
+            self.validation_metrics.lock().await.temporal_coupling_failures += 1;
+            return Err(ValidationError::PreambleNotFound);
         };
 
-        if time_diff > self.config.temporal_tolerance_ms {
+        if time_diff > window_ms {
             let mut metrics = self.validation_metrics.lock().await;
             metrics.temporal_coupling_failures += 1;
-            return Err(ValidationError::TemporalCouplingFailed(time_diff, self.config.temporal_tolerance_ms));
+            return Err(ValidationError::TemporalCouplingFailed(time_diff, window_ms));
         }
 
         Ok(())
@@ -345,14 +809,10 @@ impl ChannelValidator {
         // In real implementation, this would analyze signal correlation,
         // alignment quality, error rates, etc.
 
-        let time_diff = if laser.timestamp > ultrasound.timestamp {
-            (laser.timestamp - ultrasound.timestamp).as_millis() as f32
-        } else {
-            (ultrasound.timestamp - laser.timestamp).as_millis() as f32
-        };
+        let (time_diff, window_ms) = self.coupling_deviation_ms(laser, ultrasound).await;
 
-        // Quality decreases with time difference
-        let temporal_quality = 1.0 - (time_diff / self.config.temporal_tolerance_ms as f32).min(1.0);
+        // Quality decreases with distance from the expected arrival delta
+        let temporal_quality = time_diff.map_or(0.0, |time_diff| 1.0 - (time_diff as f32 / window_ms.max(1) as f32).min(1.0));
 
         // Simulate other quality factors
         let signal_quality = 0.8; // Would be measured from actual signals
@@ -412,6 +872,8 @@ impl ChannelValidator {
 
         let mut used_nonces = self.used_nonces.lock().await;
         used_nonces.clear();
+
+        self.replay_windows.lock().await.clear();
     }
 
     /// Attempt fallback validation when one channel is degraded
@@ -455,6 +917,11 @@ impl ChannelValidator {
         self.config = config;
     }
 
+    /// Follow the speed of sound in `conditions` when predicting the ultrasound lag
+    pub fn update_environment(&mut self, conditions: &RangeEnvironmentalConditions) {
+        self.config.set_environment(conditions);
+    }
+
     /// Set session key for cross-channel signature verification
     pub fn set_session_key(&mut self, key: [u8; 32]) {
         self.session_key = Some(key);
@@ -503,6 +970,8 @@ mod tests {
             data: vec![1, 2, 3],
             timestamp: Instant::now(),
             sequence_id: 1,
+            sequence_number: 1,
+            preamble_capture: None,
         };
 
         let ultrasound_data = ChannelData {
@@ -510,6 +979,8 @@ mod tests {
             data: vec![4, 5, 6],
             timestamp: Instant::now(),
             sequence_id: 1,
+            sequence_number: 1,
+            preamble_capture: None,
         };
 
         // Should pass with simultaneous timestamps
@@ -526,6 +997,8 @@ mod tests {
             data: vec![1, 2, 3],
             timestamp: Instant::now(),
             sequence_id: 1,
+            sequence_number: 1,
+            preamble_capture: None,
         };
 
         // Create ultrasound data with large time difference
@@ -534,6 +1007,8 @@ mod tests {
             data: vec![4, 5, 6],
             timestamp: Instant::now() + Duration::from_millis(200), // 200ms difference
             sequence_id: 1,
+            sequence_number: 1,
+            preamble_capture: None,
         };
 
         let result = validator.validate_temporal_coupling(&laser_data, &ultrasound_data).await;
@@ -549,6 +1024,8 @@ mod tests {
             data: vec![1, 2, 3],
             timestamp: Instant::now(),
             sequence_id: 1,
+            sequence_number: 1,
+            preamble_capture: None,
         };
 
         let ultrasound_data = ChannelData {
@@ -556,6 +1033,8 @@ mod tests {
             data: vec![4, 5, 6],
             timestamp: Instant::now(),
             sequence_id: 1,
+            sequence_number: 1,
+            preamble_capture: None,
         };
 
         // First validation should pass
@@ -576,6 +1055,8 @@ mod tests {
             data: vec![1, 2, 3],
             timestamp: Instant::now(),
             sequence_id: 1,
+            sequence_number: 1,
+            preamble_capture: None,
         };
 
         let ultrasound_data = ChannelData {
@@ -583,9 +1064,332 @@ mod tests {
             data: vec![4, 5, 6],
             timestamp: Instant::now(),
             sequence_id: 1,
+            sequence_number: 1,
+            preamble_capture: None,
         };
 
         let quality = validator.calculate_coupling_quality(&laser_data, &ultrasound_data).await;
         assert!(quality > 0.0 && quality <= 1.0);
     }
+
+    fn channel_data(channel_type: ChannelType, timestamp: Instant, sequence_id: u64) -> ChannelData {
+        ChannelData {
+            channel_type,
+            data: vec![sequence_id as u8],
+            timestamp,
+            sequence_id,
+            sequence_number: sequence_id,
+            preamble_capture: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_calibration_recenters_window_on_offset() {
+        let mut validator = ChannelValidator::new();
+        let base = Instant::now();
+
+        // Ultrasound consistently arrives 40ms (+/- 2ms) after the laser
+        for i in 0..10u64 {
+            let laser_time = base + Duration::from_millis(i * 5);
+            let jitter = [38, 40, 42, 39, 41][i as usize % 5];
+            let _ = validator.receive_channel_data(channel_data(ChannelType::Laser, laser_time, i)).await;
+            let _ = validator.receive_channel_data(
+                channel_data(ChannelType::Ultrasound, laser_time + Duration::from_millis(jitter), i),
+            ).await;
+        }
+
+        let calibration = validator.calibrate_coupling(10).await.unwrap();
+        assert!((calibration.offset_ms - 40).abs() <= 1);
+        assert!(calibration.tolerance_ms >= MIN_CALIBRATED_TOLERANCE_MS && calibration.tolerance_ms < 100);
+        assert_eq!(validator.get_config().coupling_offset_ms, calibration.offset_ms);
+
+        // Pairs at the characteristic offset pass; simultaneous arrival is now off-center
+        let now = Instant::now();
+        let laser = channel_data(ChannelType::Laser, now, 100);
+        let coupled = channel_data(ChannelType::Ultrasound, now + Duration::from_millis(40), 100);
+        let simultaneous = channel_data(ChannelType::Ultrasound, now, 100);
+        assert!(validator.validate_temporal_coupling(&laser, &coupled).await.is_ok());
+        assert!(matches!(
+            validator.validate_temporal_coupling(&laser, &simultaneous).await,
+            Err(ValidationError::TemporalCouplingFailed(40, _))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_calibration_requires_enough_samples() {
+        let mut validator = ChannelValidator::new();
+        let now = Instant::now();
+        let _ = validator.receive_channel_data(channel_data(ChannelType::Laser, now, 1)).await;
+        let _ = validator.receive_channel_data(channel_data(ChannelType::Ultrasound, now, 1)).await;
+
+        assert!(matches!(
+            validator.calibrate_coupling(5).await,
+            Err(ValidationError::InsufficientCalibrationSamples(1, 5))
+        ));
+        assert_eq!(validator.get_config().coupling_window_ms, 100);
+    }
+
+    #[tokio::test]
+    async fn test_calibration_ignores_exchanges_that_fail_validation() {
+        let mut validator = ChannelValidator::new();
+        let base = Instant::now();
+
+        // Pairs far outside the window fail validation and must not be sampled
+        for i in 0..5u64 {
+            let laser_time = base + Duration::from_millis(i * 5);
+            let _ = validator.receive_channel_data(channel_data(ChannelType::Laser, laser_time, i)).await;
+            let result = validator.receive_channel_data(
+                channel_data(ChannelType::Ultrasound, laser_time + Duration::from_millis(400), i),
+            ).await;
+            assert!(result.is_err());
+        }
+        assert!(matches!(
+            validator.calibrate_coupling(1).await,
+            Err(ValidationError::InsufficientCalibrationSamples(0, 1))
+        ));
+
+        // Validated exchanges are the only samples; the laser leg alone pairs
+        // with the previous, late ultrasound leg and fails
+        for i in 5..8u64 {
+            let laser_time = base + Duration::from_millis(i * 5);
+            let _ = validator.receive_channel_data(channel_data(ChannelType::Laser, laser_time, i)).await;
+            validator.receive_channel_data(
+                channel_data(ChannelType::Ultrasound, laser_time + Duration::from_millis(20), i),
+            ).await.unwrap();
+        }
+        let calibration = validator.calibrate_coupling(3).await.unwrap();
+        assert_eq!(calibration.offset_ms, 20);
+        assert!(validator.calibrate_coupling(4).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_coupling_window_tracks_range_skew() {
+        let mut validator = ChannelValidator::new();
+        let now = Instant::now();
+        let laser = channel_data(ChannelType::Laser, now, 1);
+        // Sound over 200m lags the laser by ~581ms at 20°C
+        let ultrasound = channel_data(ChannelType::Ultrasound, now + Duration::from_millis(581), 1);
+        assert!(matches!(
+            validator.validate_temporal_coupling(&laser, &ultrasound).await,
+            Err(ValidationError::TemporalCouplingFailed(581, 100))
+        ));
+
+        validator.set_range_estimate(Some(200.0));
+        assert_eq!(validator.get_config().expected_skew_ms(), 581);
+        assert!(validator.validate_temporal_coupling(&laser, &ultrasound).await.is_ok());
+
+        // Simultaneous arrival at 200m means the ultrasound leg didn't come from the peer
+        let simultaneous = channel_data(ChannelType::Ultrasound, now, 1);
+        assert!(validator.validate_temporal_coupling(&laser, &simultaneous).await.is_err());
+
+        // The window half-width is independent of the skew
+        validator.update_config(ValidationConfig { coupling_window_ms: 5, range_estimate_m: Some(200.0), ..Default::default() });
+        let late = channel_data(ChannelType::Ultrasound, now + Duration::from_millis(588), 1);
+        assert!(matches!(
+            validator.validate_temporal_coupling(&laser, &late).await,
+            Err(ValidationError::TemporalCouplingFailed(7, 5))
+        ));
+
+        // Colder air slows sound and stretches the lag
+        validator.update_environment(&RangeEnvironmentalConditions { temperature_celsius: -20.0, ..Default::default() });
+        assert_eq!(validator.get_config().expected_skew_ms(), 625);
+
+        // Configurations still setting the old field keep their window
+        #[allow(deprecated)]
+        let legacy = ValidationConfig { temporal_tolerance_ms: 5, range_estimate_m: Some(200.0), ..Default::default() };
+        validator.update_config(legacy);
+        assert!(matches!(
+            validator.validate_temporal_coupling(&laser, &late).await,
+            Err(ValidationError::TemporalCouplingFailed(7, 5))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_sliding_window_follows_drifting_skew() {
+        let validator = ChannelValidator::with_config(ValidationConfig {
+            sliding_window: Some(SlidingWindowCouplingConfig { min_window_ms: 10, max_window_ms: 100, adaptation_rate: 0.2, k: 3.0, max_center_shift_ms: 60 }),
+            coupling_offset_ms: 40,
+            ..ValidationConfig::default()
+        });
+        let base = Instant::now();
+        let mut sequence_id = 0u64;
+        let mut exchange = |skew_ms: u64| {
+            sequence_id += 1;
+            let laser_time = base + Duration::from_millis(sequence_id * 5);
+            (
+                channel_data(ChannelType::Laser, laser_time, sequence_id),
+                channel_data(ChannelType::Ultrasound, laser_time + Duration::from_millis(skew_ms), sequence_id),
+            )
+        };
+
+        // Calm air: ultrasound trails by 40ms (+/- 2ms)
+        for i in 0..30 {
+            let (laser, ultrasound) = exchange(38 + i % 5);
+            let _ = validator.receive_channel_data(laser).await;
+            let _ = validator.receive_channel_data(ultrasound).await;
+        }
+        assert!((validator.get_channel_skew_ms().await - 40.0).abs() < 2.0);
+        let (laser, ultrasound) = exchange(40);
+        assert!(validator.validate_temporal_coupling(&laser, &ultrasound).await.is_ok());
+        let (laser, simultaneous) = exchange(0);
+        assert!(matches!(
+            validator.validate_temporal_coupling(&laser, &simultaneous).await,
+            Err(ValidationError::TemporalCouplingFailed(diff, window)) if diff >= 38 && window <= 20
+        ));
+
+        // Injected frames far outside the window fail validation and never move it
+        for _ in 0..30 {
+            let (laser, ultrasound) = exchange(300);
+            let _ = validator.receive_channel_data(laser).await;
+            assert!(validator.receive_channel_data(ultrasound).await.is_err());
+        }
+        assert!((validator.get_channel_skew_ms().await - 40.0).abs() < 2.0);
+
+        // Wind builds up 50ms over many exchanges; the window re-centers as
+        // validated deltas arrive
+        for skew in (80..=180).map(|half_ms| half_ms / 2) {
+            let (laser, ultrasound) = exchange(skew);
+            let _ = validator.receive_channel_data(laser).await;
+            validator.receive_channel_data(ultrasound).await.unwrap();
+        }
+        for _ in 0..20 {
+            let (laser, ultrasound) = exchange(90);
+            let _ = validator.receive_channel_data(laser).await;
+            validator.receive_channel_data(ultrasound).await.unwrap();
+        }
+        assert!((validator.get_channel_skew_ms().await - 90.0).abs() < 2.0);
+        let (laser, ultrasound) = exchange(90);
+        assert!(validator.validate_temporal_coupling(&laser, &ultrasound).await.is_ok());
+
+        // However patiently it is walked, the center stays within 60ms of the
+        // configured offset
+        for skew in (182..=220).map(|half_ms| half_ms / 2) {
+            let (laser, ultrasound) = exchange(skew);
+            let _ = validator.receive_channel_data(laser).await;
+            let _ = validator.receive_channel_data(ultrasound).await;
+        }
+        assert!(validator.get_channel_skew_ms().await <= 100.0);
+        let (laser, ultrasound) = exchange(130);
+        assert!(validator.validate_temporal_coupling(&laser, &ultrasound).await.is_err());
+    }
+
+    /// Capture of `waveform` after `lead` samples of noise, at `gain`
+    fn received_preamble(waveform: &[f32], lead: usize, gain: f32) -> Vec<f32> {
+        let mut buf: Vec<f32> = (0..lead).map(|i| 0.05 * ((i * 7) % 5) as f32).collect();
+        buf.extend(waveform.iter().map(|&w| w * gain));
+        buf.extend((0..40).map(|i| 0.05 * ((i * 3) % 5) as f32));
+        buf
+    }
+
+    #[test]
+    fn test_preamble_correlation_measures_channel_offset() {
+        let validator = ChannelValidator::new();
+        let waveform = preamble_waveform(&validator.get_config().preamble_sequence);
+        assert_eq!(waveform.len(), PREAMBLE_LEN * PREAMBLE_SAMPLES_PER_SYMBOL);
+        assert!(waveform.iter().all(|w| (0.0..=1.0).contains(w)));
+
+        // Ultrasound preamble lands 23 symbols (1ms each) after the laser one
+        let per_ms = PREAMBLE_SAMPLES_PER_SYMBOL;
+        let offset = validator.correlate_channel_preambles(&received_preamble(&waveform, 12 * per_ms, 1.0), &received_preamble(&waveform, 35 * per_ms, 0.3));
+        assert!((offset - 23.0).abs() < 0.1, "offset {}", offset);
+        let offset = validator.correlate_channel_preambles(&received_preamble(&waveform, 30, 0.8), &received_preamble(&waveform, 30 + per_ms / 2, 0.5));
+        assert!((offset - 0.5).abs() < 0.1, "offset {}", offset);
+
+        // Too short, or no preamble in the capture at all
+        assert!(validator.correlate_channel_preambles(&waveform[..10], &received_preamble(&waveform, 5, 1.0)).is_nan());
+        let noise: Vec<f32> = (0..600).map(|i| 0.05 * ((i * 7) % 5) as f32).collect();
+        assert!(validator.correlate_channel_preambles(&noise, &received_preamble(&waveform, 5, 1.0)).is_nan());
+
+        // The receiver's subcarrier phase is unknown: a quarter-cycle rotation
+        // leaves the complex correlation peak intact, where the real part alone
+        // would collapse to nothing
+        let sequence = &validator.get_config().preamble_sequence;
+        let template = preamble_template(sequence);
+        let rotated: Vec<u8> = sequence.iter().map(|&phase| phase.wrapping_add(64)).collect();
+        let correlate = |capture: &[f32], lag: usize| {
+            capture[lag..].iter().zip(&template).fold((0.0, 0.0), |(re, im), (&x, &(t_re, t_im))| (re + x as f64 * t_re, im - x as f64 * t_im))
+        };
+        let (re, im) = correlate(&preamble_waveform(sequence), 0);
+        let (rotated_re, rotated_im) = correlate(&preamble_waveform(&rotated), 0);
+        let peak = (re * re + im * im).sqrt();
+        assert!(((rotated_re * rotated_re + rotated_im * rotated_im).sqrt() - peak).abs() < 1e-6 * peak);
+        assert!(rotated_re.abs() < 1e-6 * peak);
+        let rotated_capture = received_preamble(&preamble_waveform(&rotated), 7 * per_ms, 0.6);
+        let offset = validator.correlate_channel_preambles(&received_preamble(&waveform, 0, 1.0), &rotated_capture);
+        assert!((offset - 7.0).abs() < 0.1, "offset {}", offset);
+
+        // Zadoff-Chu sidelobes stay low at every other symbol alignment
+        let capture = received_preamble(&waveform, 0, 1.0);
+        let magnitude = |lag: usize| {
+            let (re, im) = correlate(&capture, lag);
+            (re * re + im * im).sqrt()
+        };
+        assert!((1..PREAMBLE_LEN).all(|symbols| magnitude(symbols * per_ms) < 0.25 * peak));
+    }
+
+    #[tokio::test]
+    async fn test_temporal_coupling_uses_correlated_preamble_offset() {
+        let validator = ChannelValidator::new();
+        let waveform = preamble_waveform(&validator.get_config().preamble_sequence);
+        let per_ms = PREAMBLE_SAMPLES_PER_SYMBOL;
+        let captured = |channel_type: ChannelType, timestamp: Instant, lead_ms: usize| ChannelData {
+            preamble_capture: Some(received_preamble(&waveform, lead_ms * per_ms, 0.7)),
+            ..channel_data(channel_type, timestamp, 1)
+        };
+
+        // Timestamps 500ms apart, e.g. from processing delay, no longer decide coupling
+        let now = Instant::now();
+        let laser = captured(ChannelType::Laser, now, 10);
+        let ultrasound = captured(ChannelType::Ultrasound, now + Duration::from_millis(500), 40);
+        assert!(validator.validate_temporal_coupling(&laser, &ultrasound).await.is_ok());
+
+        let late = captured(ChannelType::Ultrasound, now, 210);
+        assert!(matches!(validator.validate_temporal_coupling(&laser, &late).await, Err(ValidationError::TemporalCouplingFailed(200, 100))));
+
+        // A capture on only one leg, or one without the preamble, cannot be timed
+        let uncaptured = channel_data(ChannelType::Ultrasound, now, 1);
+        assert!(matches!(validator.validate_temporal_coupling(&laser, &uncaptured).await, Err(ValidationError::PreambleNotFound)));
+        let silent = ChannelData { preamble_capture: Some(vec![0.0; 600]), ..uncaptured };
+        assert!(matches!(validator.validate_temporal_coupling(&laser, &silent).await, Err(ValidationError::PreambleNotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_replay_window_rejects_seen_sequence_numbers() {
+        let validator = ChannelValidator::with_config(ValidationConfig { out_of_order_tolerance: 16, ..Default::default() });
+        // Later validation phases may still fail; only the replay verdict matters here
+        let replayed = |channel_type: ChannelType, sequence_number: u64| {
+            let data = ChannelData { sequence_number, ..channel_data(channel_type, Instant::now(), 1) };
+            let validator = &validator;
+            async move { matches!(validator.receive_channel_data(data).await, Err(ValidationError::ReplayDetected(_, _))) }
+        };
+
+        for sequence_number in [10, 11, 13] {
+            assert!(!replayed(ChannelType::Laser, sequence_number).await);
+        }
+        assert!(replayed(ChannelType::Laser, 11).await);
+        // Late but within tolerance is accepted once
+        assert!(!replayed(ChannelType::Laser, 12).await);
+        assert!(replayed(ChannelType::Laser, 12).await);
+        // Each channel counts independently
+        assert!(!replayed(ChannelType::Ultrasound, 11).await);
+
+        // Beyond the out-of-order tolerance the frame cannot be proven fresh
+        assert!(!replayed(ChannelType::Laser, 40).await);
+        assert!(replayed(ChannelType::Laser, 20).await);
+        assert!(!replayed(ChannelType::Laser, 30).await);
+
+        // A jump past the window forgets everything older
+        assert!(!replayed(ChannelType::Laser, 40 + REPLAY_WINDOW_LEN + 70).await);
+        assert!(replayed(ChannelType::Laser, 40).await);
+
+        // Counters wrap from u64::MAX to 0
+        assert!(!replayed(ChannelType::Ultrasound, u64::MAX - 1).await);
+        assert!(!replayed(ChannelType::Ultrasound, 1).await);
+        assert!(!replayed(ChannelType::Ultrasound, u64::MAX).await);
+        assert!(replayed(ChannelType::Ultrasound, u64::MAX - 1).await);
+        assert!(!replayed(ChannelType::Ultrasound, 0).await);
+
+        validator.reset().await;
+        assert!(!replayed(ChannelType::Laser, 11).await);
+    }
 }
@@ -0,0 +1,68 @@
+//! # Clock Module
+//!
+//! Wall-clock source used by time-based policies. Engines hold an `Arc<dyn Clock>`
+//! so that policies depending on the time of day or on absolute lifetimes can be
+//! driven deterministically in tests with a `MockClock`.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// Source of wall-clock time
+pub trait Clock: Send + Sync + std::fmt::Debug {
+    fn now(&self) -> SystemTime;
+}
+
+/// The system clock (assumed to be kept in sync by the platform)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// Manually driven clock; clones share the same time
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    now: Arc<Mutex<SystemTime>>,
+}
+
+impl MockClock {
+    pub fn new(now: SystemTime) -> Self {
+        Self {
+            now: Arc::new(Mutex::new(now)),
+        }
+    }
+
+    /// Clock set to `hour:minute` UTC on the first day of the epoch
+    pub fn at_utc(hour: u32, minute: u32) -> Self {
+        Self::new(UNIX_EPOCH + Duration::from_secs((hour as u64 * 60 + minute as u64) * 60))
+    }
+
+    pub fn set(&self, now: SystemTime) {
+        *self.now.lock().unwrap() = now;
+    }
+
+    pub fn advance(&self, by: Duration) {
+        *self.now.lock().unwrap() += by;
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> SystemTime {
+        *self.now.lock().unwrap()
+    }
+}
+
+/// Minutes since local midnight for `time`, given the local offset from UTC
+pub fn minutes_since_midnight(time: SystemTime, utc_offset_minutes: i32) -> u32 {
+    let unix_secs = match time.duration_since(UNIX_EPOCH) {
+        Ok(elapsed) => elapsed.as_secs() as i64,
+        Err(before) => -(before.duration().as_secs() as i64),
+    };
+    let local_secs = unix_secs + utc_offset_minutes as i64 * 60;
+    (local_secs.rem_euclid(SECONDS_PER_DAY) / 60) as u32
+}
@@ -0,0 +1,166 @@
+//! # Configuration Module
+//!
+//! Single deployment configuration covering every engine. A `GibberConfig` is
+//! read from a TOML or JSON file and turned into a fully wired link with
+//! `RgibberLink::from_config`. Every section and every field is optional; anything
+//! left out keeps the engine's default.
+
+use std::path::Path;
+use serde::{Serialize, Deserialize};
+use crate::laser::{LaserConfig, ReceptionConfig};
+use crate::ultrasonic_beam::BeamConfig;
+use crate::security::SecurityConfig;
+use crate::range_detector::{RangeEnvironmentalConditions, RangingConfig};
+use crate::optical_ecc::{AdaptiveECCConfig, InterleavingConfig, ReedSolomonConfig};
+use crate::security::WeatherCondition;
+use crate::audio::AudioMode;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("Failed to read config file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid TOML config: {0}")]
+    Toml(String),
+    #[error("Invalid JSON config: {0}")]
+    Json(String),
+    #[error("Unsupported config format: {0}")]
+    UnsupportedFormat(String),
+    #[error("Invalid engine configuration: {0}")]
+    InvalidEngineConfig(String),
+}
+
+/// Configuration for all engines of a link
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GibberConfig {
+    pub laser: LaserConfig,
+    pub reception: ReceptionConfig,
+    pub beam: BeamConfig,
+    pub security: SecurityConfig,
+    pub ranging: RangingConfig,
+    pub optical_ecc: AdaptiveECCConfig,
+    /// Let measured range drive laser power, modulation and ECC
+    pub adaptive_laser: bool,
+}
+
+impl GibberConfig {
+    pub fn from_toml_str(input: &str) -> Result<Self, ConfigError> {
+        toml::from_str(input).map_err(|e| ConfigError::Toml(e.to_string()))
+    }
+
+    pub fn from_json_str(input: &str) -> Result<Self, ConfigError> {
+        serde_json::from_str(input).map_err(|e| ConfigError::Json(e.to_string()))
+    }
+
+    /// Load a `.toml` or `.json` file, chosen by extension
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Self::from_toml_str(&contents),
+            Some("json") => Self::from_json_str(&contents),
+            other => Err(ConfigError::UnsupportedFormat(other.unwrap_or("none").to_string())),
+        }
+    }
+
+    pub fn to_toml_string(&self) -> Result<String, ConfigError> {
+        toml::to_string(self).map_err(|e| ConfigError::Toml(e.to_string()))
+    }
+
+    /// Fold an environment preset into the ECC and ranging sections
+    pub fn apply_environment_profile(&mut self, profile: EnvironmentProfile) {
+        let settings = profile.settings();
+        self.optical_ecc = settings.optical_ecc;
+        self.ranging.max_range_m = settings.max_range_m.max(self.ranging.min_range_m);
+        self.ranging.temperature_celsius = settings.temperature_celsius;
+        self.ranging.speed_of_sound_mps = 331.3 + 0.606 * settings.temperature_celsius;
+        self.laser.range_meters = self.laser.range_meters.min(settings.max_range_m);
+    }
+}
+
+/// Recurring deployment environments, each bundling weather compensation, laser
+/// power, ECC strength, range ceiling and audio band
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EnvironmentProfile {
+    ClearOutdoor,
+    CoastalFog,
+    DesertHeat,
+    UrbanIndoor,
+}
+
+/// What an `EnvironmentProfile` sets across the engines
+#[derive(Debug, Clone)]
+pub struct EnvironmentSettings {
+    /// Weather and visibility fed to the laser power compensation
+    pub weather: WeatherCondition,
+    pub visibility_m: f32,
+    pub temperature_celsius: f32,
+    pub optical_ecc: AdaptiveECCConfig,
+    /// Ceiling for range measurements
+    pub max_range_m: f32,
+    pub audio_mode: AudioMode,
+}
+
+impl EnvironmentSettings {
+    /// Factor the laser power profile is scaled by under this preset
+    pub fn laser_power_multiplier(&self) -> f32 {
+        crate::laser::weather_power_multiplier(&self.weather, self.visibility_m)
+    }
+
+    /// Ranging conditions under this preset; fields it doesn't set keep their defaults
+    pub fn range_conditions(&self) -> RangeEnvironmentalConditions {
+        RangeEnvironmentalConditions {
+            temperature_celsius: self.temperature_celsius,
+            visibility_meters: self.visibility_m,
+            ..Default::default()
+        }
+    }
+}
+
+impl EnvironmentProfile {
+    pub fn settings(&self) -> EnvironmentSettings {
+        let ecc = |parity_shards: usize, depth: usize| AdaptiveECCConfig {
+            reed_solomon: ReedSolomonConfig { data_shards: 16, parity_shards },
+            interleaving: InterleavingConfig { depth, ..Default::default() },
+            ..Default::default()
+        };
+
+        match self {
+            EnvironmentProfile::ClearOutdoor => EnvironmentSettings {
+                weather: WeatherCondition::Clear,
+                visibility_m: 10_000.0,
+                temperature_celsius: 20.0,
+                optical_ecc: AdaptiveECCConfig::default(),
+                max_range_m: 200.0,
+                audio_mode: AudioMode::Ultrasonic,
+            },
+            // Droplet scattering: more power, heavy parity, shorter reach
+            EnvironmentProfile::CoastalFog => EnvironmentSettings {
+                weather: WeatherCondition::Fog,
+                visibility_m: 300.0,
+                temperature_celsius: 12.0,
+                optical_ecc: ecc(12, 8),
+                max_range_m: 100.0,
+                audio_mode: AudioMode::Ultrasonic,
+            },
+            // Scintillation causes burst errors; hot dry air absorbs ultrasound
+            EnvironmentProfile::DesertHeat => EnvironmentSettings {
+                weather: WeatherCondition::Clear,
+                visibility_m: 5_000.0,
+                temperature_celsius: 40.0,
+                optical_ecc: ecc(8, 16),
+                max_range_m: 150.0,
+                audio_mode: AudioMode::Standard,
+            },
+            // Short, clean paths: light parity keeps throughput up
+            EnvironmentProfile::UrbanIndoor => EnvironmentSettings {
+                weather: WeatherCondition::Clear,
+                visibility_m: 1_000.0,
+                temperature_celsius: 22.0,
+                optical_ecc: ecc(4, 4),
+                max_range_m: 30.0,
+                audio_mode: AudioMode::Ultrasonic,
+            },
+        }
+    }
+}
@@ -0,0 +1,181 @@
+//! # Protocol Conformance Vectors
+//!
+//! Deterministic test vectors for checking other implementations against this
+//! core. Every secret in a vector (nonces, session ids, ECDH and Ed25519 keys,
+//! the AEAD nonce) is expanded from a per-party seed with HKDF-SHA256, so a port
+//! only needs the two seeds and the plaintext to reproduce the QR payload bytes,
+//! the ECDH shared secret and the AES-256-GCM ciphertext. The published vectors
+//! live in `test-vectors/protocol_v1.json` and are checked by the test below.
+
+use crate::crypto::{CryptoEngine, CryptoError};
+use crate::session_id::SessionId;
+use crate::visual::{VisualEngine, VisualError, VisualPayload};
+use hkdf::Hkdf;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use thiserror::Error;
+
+/// Prefix of every HKDF `info` label used to expand seeds
+pub const CONFORMANCE_LABEL: &str = "gibberlink-conformance-v1";
+
+/// Published vector file, embedded at build time
+const PROTOCOL_V1_VECTORS: &str = include_str!("../test-vectors/protocol_v1.json");
+
+#[derive(Debug, Error)]
+pub enum ConformanceError {
+    #[error("Crypto error: {0}")]
+    Crypto(#[from] CryptoError),
+    #[error("Visual error: {0}")]
+    Visual(#[from] VisualError),
+    #[error("Malformed vector field {0}")]
+    MalformedField(&'static str),
+    #[error("Vector {name}: {field} mismatch")]
+    Mismatch { name: String, field: &'static str },
+}
+
+/// One published vector; byte fields are lowercase hex
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConformanceVector {
+    pub name: String,
+    /// Initiator seed: scanning side, issues the nonce and the session id
+    pub seed_a: String,
+    /// Responder seed: displays the signed QR payload
+    pub seed_b: String,
+    pub plaintext: String,
+    pub nonce: String,
+    pub session_id: String,
+    pub initiator_public_key: String,
+    pub responder_public_key: String,
+    pub responder_signing_key: String,
+    /// RS-encoded CBOR bytes carried by the responder's QR code
+    pub qr_payload: String,
+    pub shared_secret: String,
+    pub aead_nonce: String,
+    /// AES-256-GCM output with the AEAD nonce prepended, as `encrypt_aes_gcm` emits it
+    pub ciphertext: String,
+}
+
+/// Expand `seed` into `len` bytes for the given purpose (e.g. "nonce", "ecdh")
+pub fn seeded_bytes(seed: &[u8], label: &str, len: usize) -> Vec<u8> {
+    let info = format!("{}/{}", CONFORMANCE_LABEL, label);
+    let mut output = vec![0u8; len];
+    Hkdf::<Sha256>::new(None, seed)
+        .expand(info.as_bytes(), &mut output)
+        .expect("conformance outputs are far below the HKDF length limit");
+    output
+}
+
+fn seeded_array<const N: usize>(seed: &[u8], label: &str) -> [u8; N] {
+    let mut output = [0u8; N];
+    output.copy_from_slice(&seeded_bytes(seed, label, N));
+    output
+}
+
+/// Crypto engine whose ECDH and Ed25519 keys are derived from `seed`
+pub fn seeded_engine(seed: &[u8]) -> CryptoEngine {
+    CryptoEngine::from_key_material(seeded_array(seed, "ecdh"), seeded_array(seed, "ed25519"))
+}
+
+/// Run the handshake and encryption for one pair of seeds and record every output
+pub fn compute_vector(name: &str, seed_a: &[u8], seed_b: &[u8], plaintext: &[u8]) -> Result<ConformanceVector, ConformanceError> {
+    let mut initiator = seeded_engine(seed_a);
+    let responder = seeded_engine(seed_b);
+    let initiator_public_key = initiator.public_key().to_vec();
+    let responder_public_key = responder.public_key().to_vec();
+
+    let nonce: [u8; 16] = seeded_array(seed_a, "nonce");
+    let session_id = SessionId::new(seeded_array(seed_a, "session"));
+
+    // Ed25519 signatures are deterministic, so the signed QR bytes are too
+    let mut payload = VisualPayload {
+        session_id,
+        public_key: responder_public_key.clone(),
+        nonce,
+        signature: Vec::new(),
+        version: None,
+        version_offer: None,
+        psk: false,
+    };
+    payload.sign(&responder)?;
+    let qr_payload = VisualEngine::new().encode_payload_bytes(&payload)?;
+
+    let shared_secret = initiator.derive_shared_secret(&responder_public_key)?;
+    let aead_nonce: [u8; 12] = seeded_array(seed_a, "aead-nonce");
+    let ciphertext = CryptoEngine::encrypt_data_with_nonce(&shared_secret, &aead_nonce, plaintext)?;
+
+    Ok(ConformanceVector {
+        name: name.to_string(),
+        seed_a: hex::encode(seed_a),
+        seed_b: hex::encode(seed_b),
+        plaintext: hex::encode(plaintext),
+        nonce: hex::encode(nonce),
+        session_id: session_id.to_hex(),
+        initiator_public_key: hex::encode(initiator_public_key),
+        responder_public_key: hex::encode(responder_public_key),
+        responder_signing_key: hex::encode(responder.ed25519_public_key()),
+        qr_payload: hex::encode(qr_payload),
+        shared_secret: hex::encode(shared_secret),
+        aead_nonce: hex::encode(aead_nonce),
+        ciphertext: hex::encode(ciphertext),
+    })
+}
+
+/// Recompute `vector` from its inputs and report the first field that differs
+pub fn verify_vector(vector: &ConformanceVector) -> Result<(), ConformanceError> {
+    let decode = |field: &'static str, value: &str| hex::decode(value).map_err(|_| ConformanceError::MalformedField(field));
+    let computed = compute_vector(
+        &vector.name,
+        &decode("seed_a", &vector.seed_a)?,
+        &decode("seed_b", &vector.seed_b)?,
+        &decode("plaintext", &vector.plaintext)?,
+    )?;
+
+    let fields: [(&'static str, &String, &String); 9] = [
+        ("nonce", &vector.nonce, &computed.nonce),
+        ("session_id", &vector.session_id, &computed.session_id),
+        ("initiator_public_key", &vector.initiator_public_key, &computed.initiator_public_key),
+        ("responder_public_key", &vector.responder_public_key, &computed.responder_public_key),
+        ("responder_signing_key", &vector.responder_signing_key, &computed.responder_signing_key),
+        ("qr_payload", &vector.qr_payload, &computed.qr_payload),
+        ("shared_secret", &vector.shared_secret, &computed.shared_secret),
+        ("aead_nonce", &vector.aead_nonce, &computed.aead_nonce),
+        ("ciphertext", &vector.ciphertext, &computed.ciphertext),
+    ];
+    match fields.iter().find(|(_, expected, actual)| !expected.eq_ignore_ascii_case(actual)) {
+        Some((field, _, _)) => Err(ConformanceError::Mismatch { name: vector.name.clone(), field: *field }),
+        None => Ok(()),
+    }
+}
+
+/// The published protocol v1 vectors
+pub fn load_vectors() -> Result<Vec<ConformanceVector>, serde_json::Error> {
+    serde_json::from_str(PROTOCOL_V1_VECTORS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_core_reproduces_published_vectors() {
+        let vectors = load_vectors().unwrap();
+        assert!(!vectors.is_empty());
+
+        for vector in &vectors {
+            verify_vector(vector).unwrap();
+
+            // Both sides reach the same secret, and the ciphertext opens to the plaintext
+            let mut responder = seeded_engine(&hex::decode(&vector.seed_b).unwrap());
+            let initiator_key = hex::decode(&vector.initiator_public_key).unwrap();
+            let shared_secret = responder.derive_shared_secret(&initiator_key).unwrap();
+            assert_eq!(hex::encode(shared_secret), vector.shared_secret);
+
+            let ciphertext = hex::decode(&vector.ciphertext).unwrap();
+            let plaintext = CryptoEngine::decrypt_aes_gcm(&shared_secret, &ciphertext).unwrap();
+            assert_eq!(hex::encode(plaintext), vector.plaintext);
+
+            let payload = VisualEngine::new().decode_payload(&hex::decode(&vector.qr_payload).unwrap()).unwrap();
+            assert_eq!(hex::encode(payload.verify_signature().unwrap()), vector.responder_signing_key);
+        }
+    }
+}
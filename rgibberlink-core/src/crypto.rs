@@ -1,20 +1,55 @@
-use aes_gcm::{aead::Aead, Aes256Gcm, KeyInit, Nonce};
-use rand::RngCore;
+use aes_gcm::{aead::{Aead, Payload}, Aes256Gcm, KeyInit, Nonce};
+use chacha20poly1305::ChaCha20Poly1305;
+use rand::{CryptoRng, RngCore};
 use x25519_dalek::{EphemeralSecret, PublicKey};
 use ed25519_dalek::{SigningKey, VerifyingKey, Signer, Verifier, Signature};
 use std::time::{Instant, Duration};
-use zeroize::{Zeroize, ZeroizeOnDrop};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+use argon2::{Algorithm, Argon2, Params, Version};
 use hkdf::Hkdf;
 use sha2::{Sha256, Digest};
 use hmac::Mac;
 
 #[cfg(feature = "post-quantum")]
 use crate::post_quantum::{PostQuantumEngine, KyberKEM, DilithiumSign, KyberKeypair, DilithiumKeypair, KyberCiphertextData};
+#[cfg(feature = "post-quantum")]
+use pqcrypto::prelude::*;
+
+/// AES-GCM nonce length prefixed to every ciphertext
+const AES_GCM_NONCE_LEN: usize = 12;
+/// AES-GCM authentication tag length appended to every ciphertext
+const AES_GCM_TAG_LEN: usize = 16;
+/// Cipher suite id prefixed to suite-tagged ciphertexts (ChaCha20-Poly1305 shares
+/// AES-GCM's nonce and tag lengths, so the rest of the layout is the same)
+const CIPHER_SUITE_ID_LEN: usize = 1;
+/// Format version leading an exported private key
+const PRIVATE_KEY_EXPORT_VERSION: u8 = 0x01;
+/// Argon2id salt stored after the version byte of an exported private key
+const PRIVATE_KEY_SALT_LEN: usize = 16;
+/// Argon2id cost for passphrase-sealed keys (19 MiB, 2 passes, 1 lane)
+const PRIVATE_KEY_KDF_MEMORY_KIB: u32 = 19 * 1024;
+const PRIVATE_KEY_KDF_ITERATIONS: u32 = 2;
+/// HKDF info binding a hybrid session key to the X25519 + Kyber-768 combination
+const HYBRID_KEM_INFO: &[u8] = b"gibberlink-hybrid-x25519-kyber768-v1";
+/// HKDF info deriving the Ed25519 signing seed from the ECDH identity scalar
+const ED25519_SEED_INFO: &[u8] = b"gibberlink-ed25519-from-ecdh-v1";
+/// HKDF info for the sequenced-frame key of each direction, so the two peers never
+/// seal under the same key and counter
+const SEQUENCED_INITIATOR_INFO: &[u8] = b"gibberlink-sequenced-initiator-v1";
+const SEQUENCED_RESPONDER_INFO: &[u8] = b"gibberlink-sequenced-responder-v1";
+/// Sequenced frames use the whole AES-GCM nonce as their counter
+const SEQUENCED_COUNTER_LIMIT: u128 = 1 << (AES_GCM_NONCE_LEN * 8);
+/// Frames behind the newest accepted counter that may still arrive out of order
+const REPLAY_WINDOW: u128 = 64;
 
 #[derive(Debug, thiserror::Error)]
 pub enum CryptoError {
     #[error("AES-GCM encryption error")]
     AeadError,
+    #[error("Authentication tag verification failed")]
+    AuthenticationFailed,
+    #[error("Malformed ciphertext")]
+    MalformedCiphertext,
     #[error("HMAC verification failed")]
     HmacError,
     #[error("Invalid key length")]
@@ -25,10 +60,77 @@ pub enum CryptoError {
     SignatureError,
     #[error("Ed25519 signing error")]
     Ed25519Error,
+    #[error("Unsupported cipher suite: {0}")]
+    UnsupportedCipherSuite(String),
+    #[error("Decryption failed (wrong passphrase or tampered key)")]
+    DecryptionFailed,
+    #[error("Invalid secret shares: {0}")]
+    InvalidShares(String),
+    #[error("Replayed frame (counter {0})")]
+    ReplayDetected(u128),
     #[error("{0}")]
     GenericError(String),
 }
 
+/// AEAD used by `encrypt_data_suite`; its id leads every ciphertext so the
+/// receiver can pick the matching primitive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CipherSuite {
+    #[default]
+    Aes256Gcm,
+    /// Two to three times faster than AES-GCM on cores without AES instructions
+    ChaCha20Poly1305,
+}
+
+impl CipherSuite {
+    /// One-byte discriminator written ahead of the nonce
+    pub const fn id(self) -> u8 {
+        match self {
+            CipherSuite::Aes256Gcm => 0x01,
+            CipherSuite::ChaCha20Poly1305 => 0x02,
+        }
+    }
+
+    pub fn from_id(id: u8) -> Option<Self> {
+        match id {
+            0x01 => Some(CipherSuite::Aes256Gcm),
+            0x02 => Some(CipherSuite::ChaCha20Poly1305),
+            _ => None,
+        }
+    }
+
+    /// Name as written in `CryptoAlgorithmConfig::encryption_algorithm`
+    pub const fn name(self) -> &'static str {
+        match self {
+            CipherSuite::Aes256Gcm => "AES-256-GCM",
+            CipherSuite::ChaCha20Poly1305 => "ChaCha20-Poly1305",
+        }
+    }
+}
+
+impl std::str::FromStr for CipherSuite {
+    type Err = CryptoError;
+
+    /// Accepts the config spellings, ignoring case, dashes and underscores
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        let normalized: String = name.chars()
+            .filter(|c| !matches!(c, '-' | '_'))
+            .map(|c| c.to_ascii_lowercase())
+            .collect();
+        match normalized.as_str() {
+            "aes256gcm" => Ok(CipherSuite::Aes256Gcm),
+            "chacha20poly1305" => Ok(CipherSuite::ChaCha20Poly1305),
+            _ => Err(CryptoError::UnsupportedCipherSuite(name.to_string())),
+        }
+    }
+}
+
+impl std::fmt::Display for CipherSuite {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
 #[derive(Clone)]
 pub struct EphemeralKeySession {
     key: [u8; 32],
@@ -70,24 +172,184 @@ impl EphemeralKeySession {
 
 pub struct CryptoEngine {
     ecdh_secret: EphemeralSecret,
+    /// Scalar the engine was created from; exported as its persistent identity
+    ecdh_identity: Zeroizing<[u8; 32]>,
     ecdh_public: PublicKey,
     ed25519_keypair: SigningKey,
     ed25519_public: VerifyingKey,
+    cipher_suite: CipherSuite,
+    sequenced: Option<SequencedChannel>,
     #[cfg(feature = "post-quantum")]
     pq_engine: Option<PostQuantumEngine>,
 }
 
+/// Per-session state behind `encrypt_sequenced` / `decrypt_sequenced`
+struct SequencedChannel {
+    initiator: bool,
+    send_key: Zeroizing<[u8; 32]>,
+    receive_key: Zeroizing<[u8; 32]>,
+    next_send_counter: u128,
+    replay_window: ReplayWindow,
+}
+
+/// Where a sequenced session stands, for persisting it across restarts. The
+/// direction keys are not included; they are rederived from the session key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SequencedChannelState {
+    pub initiator: bool,
+    pub next_send_counter: u128,
+    /// Newest counter accepted from the peer, and a bitmap of those before it
+    pub newest_received: Option<u128>,
+    pub received_window: u64,
+}
+
+/// Sliding-window record of received counters: the newest one plus a bitmap of
+/// the `REPLAY_WINDOW` counters before it
+#[derive(Default)]
+struct ReplayWindow {
+    newest: Option<u128>,
+    seen: u64,
+}
+
+impl ReplayWindow {
+    /// Whether `counter` may be accepted. Counters older than the window cannot be
+    /// told apart from replays and are refused too.
+    fn check(&self, counter: u128) -> Result<(), CryptoError> {
+        let Some(newest) = self.newest else { return Ok(()) };
+        if counter > newest {
+            return Ok(());
+        }
+        let age = newest - counter;
+        if age >= REPLAY_WINDOW || self.seen & (1 << age) != 0 {
+            return Err(CryptoError::ReplayDetected(counter));
+        }
+        Ok(())
+    }
+
+    /// Record an authenticated counter previously passed by `check`
+    fn mark(&mut self, counter: u128) {
+        match self.newest {
+            Some(newest) if counter <= newest => self.seen |= 1 << (newest - counter),
+            Some(newest) => {
+                let shift = counter - newest;
+                self.seen = if shift >= REPLAY_WINDOW { 0 } else { self.seen << shift };
+                self.seen |= 1;
+                self.newest = Some(counter);
+            }
+            None => {
+                self.seen = 1;
+                self.newest = Some(counter);
+            }
+        }
+    }
+}
+
+/// Multiply in GF(2^8) with the AES reduction polynomial x^8 + x^4 + x^3 + x + 1
+fn gf256_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0u8;
+    while b != 0 {
+        if b & 1 != 0 {
+            product ^= a;
+        }
+        let carry = a & 0x80;
+        a <<= 1;
+        if carry != 0 {
+            a ^= 0x1B;
+        }
+        b >>= 1;
+    }
+    product
+}
+
+/// Divide in GF(2^8); `b` must be non-zero (a^254 is the inverse of a)
+fn gf256_div(a: u8, b: u8) -> u8 {
+    let inverse = (0..7).fold((b, 1u8), |(power, acc), _| {
+        let power = gf256_mul(power, power);
+        (power, gf256_mul(acc, power))
+    }).1;
+    gf256_mul(a, inverse)
+}
+
+/// Hands fixed key material to the RNG-based key constructors
+struct FixedKeyMaterial([u8; 32]);
+
+impl RngCore for FixedKeyMaterial {
+    fn next_u32(&mut self) -> u32 {
+        let mut bytes = [0u8; 4];
+        self.fill_bytes(&mut bytes);
+        u32::from_le_bytes(bytes)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut bytes = [0u8; 8];
+        self.fill_bytes(&mut bytes);
+        u64::from_le_bytes(bytes)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for (byte, material) in dest.iter_mut().zip(self.0.iter().cycle()) {
+            *byte = *material;
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl CryptoRng for FixedKeyMaterial {}
+
+impl Drop for FixedKeyMaterial {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
 impl CryptoEngine {
     pub fn new() -> Self {
+        let mut ecdh_secret = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut ecdh_secret);
+
+        let engine = Self::from_ecdh_seed(ecdh_secret);
+        ecdh_secret.zeroize();
+        engine
+    }
+
+    /// Build an engine whose Ed25519 signing key is derived from the ECDH scalar,
+    /// so persisting the ECDH scalar alone restores the whole identity
+    pub fn from_ecdh_seed(ecdh_secret: [u8; 32]) -> Self {
+        let ed25519_secret = Self::derive_signing_seed(&ecdh_secret);
+        Self::from_key_material(ecdh_secret, *ed25519_secret)
+    }
+
+    /// HKDF-SHA256 of the ECDH scalar into an Ed25519 seed
+    fn derive_signing_seed(ecdh_secret: &[u8; 32]) -> Zeroizing<[u8; 32]> {
+        let mut seed = Zeroizing::new([0u8; 32]);
+        Hkdf::<Sha256>::new(None, ecdh_secret)
+            .expand(ED25519_SEED_INFO, seed.as_mut())
+            .expect("32 bytes is a valid HKDF-SHA256 output length");
+        seed
+    }
+
+    /// Engine with fresh keys that seals with `suite`
+    pub fn new_with_suite(suite: CipherSuite) -> Self {
+        let mut engine = Self::new();
+        engine.cipher_suite = suite;
+        engine
+    }
+
+    /// Build an engine from caller-supplied secrets rather than the system RNG, for
+    /// reproducible outputs such as the conformance vectors. Keys regenerated later
+    /// (after each ECDH derivation) are random again.
+    pub fn from_key_material(ecdh_secret: [u8; 32], ed25519_secret: [u8; 32]) -> Self {
         // ECDH for key exchange
-        let ecdh_secret = EphemeralSecret::random_from_rng(rand::thread_rng());
+        let ecdh_identity = Zeroizing::new(ecdh_secret);
+        let ecdh_secret = EphemeralSecret::random_from_rng(FixedKeyMaterial(ecdh_secret));
         let ecdh_public = PublicKey::from(&ecdh_secret);
 
         // Ed25519 for signing logs
-        let mut csprng = rand::thread_rng();
-        let mut secret_key = [0u8; 32];
-        csprng.fill_bytes(&mut secret_key);
-        let ed25519_keypair = SigningKey::from_bytes(&secret_key);
+        let ed25519_keypair = SigningKey::from_bytes(&ed25519_secret);
         let ed25519_public = ed25519_keypair.verifying_key();
 
         #[cfg(feature = "post-quantum")]
@@ -95,14 +357,264 @@ impl CryptoEngine {
 
         Self {
             ecdh_secret,
+            ecdh_identity,
             ecdh_public,
             ed25519_keypair,
             ed25519_public,
+            cipher_suite: CipherSuite::default(),
+            sequenced: None,
             #[cfg(feature = "post-quantum")]
             pq_engine,
         }
     }
 
+    /// Engine holding a Kyber-768 keypair alongside its X25519 key, for hybrid
+    /// key exchange. Fails when the `post-quantum` feature is not built in.
+    pub fn new_kyber() -> Result<Self, CryptoError> {
+        #[cfg(feature = "post-quantum")]
+        {
+            let mut engine = Self::new();
+            if engine.pq_engine.is_none() {
+                engine.pq_engine = Some(PostQuantumEngine::new()?);
+            }
+            Ok(engine)
+        }
+        #[cfg(not(feature = "post-quantum"))]
+        {
+            Err(CryptoError::GenericError("Post-quantum cryptography not enabled".to_string()))
+        }
+    }
+
+    /// Export the ECDH scalar this engine was created from, sealed under `passphrase`.
+    ///
+    /// Layout: `version || salt || nonce || AES-256-GCM(scalar) || tag`, keyed by
+    /// Argon2id over the passphrase and salt. Keys rotated in after a derivation are
+    /// ephemeral and never exported.
+    pub fn export_private_key(&self, passphrase: &str) -> Result<Vec<u8>, CryptoError> {
+        let mut salt = [0u8; PRIVATE_KEY_SALT_LEN];
+        rand::thread_rng().fill_bytes(&mut salt);
+        let key = Self::derive_passphrase_key(passphrase, &salt)?;
+
+        let mut scalar = *self.ecdh_identity;
+        let sealed = Self::encrypt_aes_gcm(key.as_ref(), &scalar);
+        scalar.zeroize();
+
+        let mut exported = Vec::with_capacity(1 + PRIVATE_KEY_SALT_LEN + AES_GCM_NONCE_LEN + 32 + AES_GCM_TAG_LEN);
+        exported.push(PRIVATE_KEY_EXPORT_VERSION);
+        exported.extend_from_slice(&salt);
+        exported.extend_from_slice(&sealed?);
+        Ok(exported)
+    }
+
+    /// Rebuild an engine from `export_private_key` output.
+    ///
+    /// A wrong passphrase (or a tampered blob) is `DecryptionFailed`; input of the
+    /// wrong shape or version is `MalformedCiphertext`.
+    pub fn from_encrypted_private_key(bytes: &[u8], passphrase: &str) -> Result<Self, CryptoError> {
+        if bytes.len() != 1 + PRIVATE_KEY_SALT_LEN + AES_GCM_NONCE_LEN + 32 + AES_GCM_TAG_LEN
+            || bytes[0] != PRIVATE_KEY_EXPORT_VERSION
+        {
+            return Err(CryptoError::MalformedCiphertext);
+        }
+
+        let (salt, sealed) = bytes[1..].split_at(PRIVATE_KEY_SALT_LEN);
+        let key = Self::derive_passphrase_key(passphrase, salt)?;
+        let plaintext = Zeroizing::new(
+            Self::decrypt_aes_gcm(key.as_ref(), sealed).map_err(|_| CryptoError::DecryptionFailed)?,
+        );
+
+        let mut scalar = [0u8; 32];
+        scalar.copy_from_slice(&plaintext);
+
+        let engine = Self::from_ecdh_seed(scalar);
+        scalar.zeroize();
+        Ok(engine)
+    }
+
+    /// Stretch a passphrase into an AES-256 key with Argon2id
+    fn derive_passphrase_key(passphrase: &str, salt: &[u8]) -> Result<Zeroizing<[u8; 32]>, CryptoError> {
+        let params = Params::new(PRIVATE_KEY_KDF_MEMORY_KIB, PRIVATE_KEY_KDF_ITERATIONS, 1, Some(32))
+            .map_err(|e| CryptoError::GenericError(e.to_string()))?;
+        let mut key = Zeroizing::new([0u8; 32]);
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(passphrase.as_bytes(), salt, key.as_mut())
+            .map_err(|e| CryptoError::GenericError(e.to_string()))?;
+        Ok(key)
+    }
+
+    /// Split `secret` into `n` Shamir shares over GF(2^8), any `k` of which rebuild it.
+    ///
+    /// Each share is its x-coordinate (1..=n) followed by the 32 evaluated bytes.
+    /// No shares are produced when `k` is zero or exceeds `n`.
+    pub fn split_secret(secret: &[u8; 32], n: u8, k: u8) -> Vec<[u8; 33]> {
+        if k == 0 || k > n {
+            return Vec::new();
+        }
+
+        let mut shares: Vec<[u8; 33]> = (1..=n).map(|x| {
+            let mut share = [0u8; 33];
+            share[0] = x;
+            share
+        }).collect();
+
+        let mut coefficients = vec![0u8; k as usize];
+        for (position, &secret_byte) in secret.iter().enumerate() {
+            // Random polynomial of degree k - 1 whose constant term is the secret byte
+            coefficients[0] = secret_byte;
+            rand::thread_rng().fill_bytes(&mut coefficients[1..]);
+
+            for share in shares.iter_mut() {
+                // Horner evaluation at the share's x-coordinate
+                share[1 + position] = coefficients.iter().rev()
+                    .fold(0u8, |acc, &coefficient| gf256_mul(acc, share[0]) ^ coefficient);
+            }
+        }
+        coefficients.zeroize();
+        shares
+    }
+
+    /// Rebuild a secret from Shamir shares by Lagrange interpolation at zero.
+    ///
+    /// Fewer than the threshold number of shares yields an unrelated value rather
+    /// than an error; the shares themselves carry no threshold.
+    pub fn reconstruct_secret(shares: &[[u8; 33]]) -> Result<[u8; 32], CryptoError> {
+        if shares.is_empty() {
+            return Err(CryptoError::InvalidShares("no shares supplied".to_string()));
+        }
+        for (i, share) in shares.iter().enumerate() {
+            if share[0] == 0 {
+                return Err(CryptoError::InvalidShares("share index 0 is reserved for the secret".to_string()));
+            }
+            if shares[..i].iter().any(|other| other[0] == share[0]) {
+                return Err(CryptoError::InvalidShares(format!("duplicate share index {}", share[0])));
+            }
+        }
+
+        let mut secret = [0u8; 32];
+        for share in shares {
+            // Lagrange basis polynomial for this share evaluated at x = 0
+            let basis = shares.iter()
+                .filter(|other| other[0] != share[0])
+                .fold(1u8, |acc, other| gf256_mul(acc, gf256_div(other[0], other[0] ^ share[0])));
+            for (byte, &value) in secret.iter_mut().zip(&share[1..]) {
+                *byte ^= gf256_mul(value, basis);
+            }
+        }
+        Ok(secret)
+    }
+
+    /// Cipher `seal` encrypts with
+    pub fn cipher_suite(&self) -> CipherSuite {
+        self.cipher_suite
+    }
+
+    pub fn set_cipher_suite(&mut self, suite: CipherSuite) {
+        self.cipher_suite = suite;
+    }
+
+    /// Start a sequenced session under `session_key`, resetting both counters.
+    ///
+    /// Each direction gets its own key derived from `session_key`, so the peer that
+    /// initiated the handshake must pass `initiator = true` and the other `false`.
+    pub fn set_session_key(&mut self, session_key: &[u8; 32], initiator: bool) {
+        let hkdf = Hkdf::<Sha256>::new(None, session_key);
+        let mut initiator_key = Zeroizing::new([0u8; 32]);
+        let mut responder_key = Zeroizing::new([0u8; 32]);
+        hkdf.expand(SEQUENCED_INITIATOR_INFO, initiator_key.as_mut())
+            .expect("32 bytes is a valid HKDF-SHA256 output length");
+        hkdf.expand(SEQUENCED_RESPONDER_INFO, responder_key.as_mut())
+            .expect("32 bytes is a valid HKDF-SHA256 output length");
+
+        let (send_key, receive_key) = if initiator {
+            (initiator_key, responder_key)
+        } else {
+            (responder_key, initiator_key)
+        };
+        self.sequenced = Some(SequencedChannel {
+            initiator,
+            send_key,
+            receive_key,
+            next_send_counter: 0,
+            replay_window: ReplayWindow::default(),
+        });
+    }
+
+    /// Drop the sequenced session, e.g. when its key is wiped
+    pub fn clear_session_key(&mut self) {
+        self.sequenced = None;
+    }
+
+    /// Direction and counters of the sequenced session, if one is running
+    pub fn sequenced_state(&self) -> Option<SequencedChannelState> {
+        self.sequenced.as_ref().map(|channel| SequencedChannelState {
+            initiator: channel.initiator,
+            next_send_counter: channel.next_send_counter,
+            newest_received: channel.replay_window.newest,
+            received_window: channel.replay_window.seen,
+        })
+    }
+
+    /// Resume a sequenced session under `session_key` from `state`. Frames the
+    /// peer already delivered stay replays. The state must be the latest one
+    /// captured: resuming an older one reuses send counters, and so nonces.
+    pub fn restore_sequenced_state(&mut self, session_key: &[u8; 32], state: SequencedChannelState) {
+        self.set_session_key(session_key, state.initiator);
+        if let Some(channel) = self.sequenced.as_mut() {
+            channel.next_send_counter = state.next_send_counter;
+            channel.replay_window = ReplayWindow { newest: state.newest_received, seen: state.received_window };
+        }
+    }
+
+    /// Seal `data` under the session key with this engine's cipher suite and the
+    /// next value of a 96-bit counter as the nonce, producing
+    /// `suite id || counter (12 bytes BE) || ciphertext || tag`. Nonces are never
+    /// reused; once the counter is exhausted the session key must be replaced.
+    pub fn encrypt_sequenced(&mut self, data: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        let suite = self.cipher_suite;
+        let channel = self.sequenced.as_mut()
+            .ok_or_else(|| CryptoError::GenericError("No session key set".to_string()))?;
+        let counter = channel.next_send_counter;
+        if counter >= SEQUENCED_COUNTER_LIMIT {
+            return Err(CryptoError::KeyExpired);
+        }
+
+        let frame = Self::encrypt_data_suite_with_nonce(suite, channel.send_key.as_ref(), &Self::counter_nonce(counter), data)?;
+        channel.next_send_counter += 1;
+        Ok(frame)
+    }
+
+    /// Open a frame from the peer's `encrypt_sequenced`. A counter that was already
+    /// accepted, or that is more than `REPLAY_WINDOW` frames behind the newest one,
+    /// fails with `ReplayDetected`; frames that fail authentication leave the replay
+    /// state untouched.
+    pub fn decrypt_sequenced(&mut self, frame: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        let channel = self.sequenced.as_mut()
+            .ok_or_else(|| CryptoError::GenericError("No session key set".to_string()))?;
+        if frame.len() < CIPHER_SUITE_ID_LEN + AES_GCM_NONCE_LEN + AES_GCM_TAG_LEN {
+            return Err(CryptoError::MalformedCiphertext);
+        }
+
+        let counter = frame[CIPHER_SUITE_ID_LEN..CIPHER_SUITE_ID_LEN + AES_GCM_NONCE_LEN].iter()
+            .fold(0u128, |acc, &byte| (acc << 8) | byte as u128);
+        channel.replay_window.check(counter)?;
+        let plaintext = Self::decrypt_data_suite(channel.receive_key.as_ref(), frame)?;
+        channel.replay_window.mark(counter);
+        Ok(plaintext)
+    }
+
+    /// Big-endian nonce carrying a sequenced-frame counter
+    fn counter_nonce(counter: u128) -> [u8; AES_GCM_NONCE_LEN] {
+        let mut nonce = [0u8; AES_GCM_NONCE_LEN];
+        nonce.copy_from_slice(&counter.to_be_bytes()[16 - AES_GCM_NONCE_LEN..]);
+        nonce
+    }
+
+    /// Replace the ECDH keypair with a fresh ephemeral one; the Ed25519 identity is kept
+    pub fn regenerate_ecdh_keypair(&mut self) {
+        self.ecdh_secret = EphemeralSecret::random_from_rng(rand::thread_rng());
+        self.ecdh_public = PublicKey::from(&self.ecdh_secret);
+    }
+
     pub fn ecdh_public_key(&self) -> &[u8] {
         self.ecdh_public.as_bytes()
     }
@@ -134,24 +646,99 @@ impl CryptoEngine {
         key.copy_from_slice(shared_secret.as_bytes());
 
         // Regenerate ECDH keypair for forward secrecy
-        self.ecdh_secret = EphemeralSecret::random_from_rng(rand::thread_rng());
-        self.ecdh_public = PublicKey::from(&self.ecdh_secret);
+        self.regenerate_ecdh_keypair();
 
         // Default TTL ≤ 5 seconds as per specs
         Ok(EphemeralKeySession::new(key, Duration::from_secs(5)))
     }
 
+    /// AES-256-GCM only, whatever the configured suite; its output carries no suite id
+    #[deprecated(since = "0.3.0", note = "use `seal` or `encrypt_data_suite`, which honor the configured cipher suite")]
     pub fn encrypt_data(key: &[u8], data: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        Self::encrypt_aes_gcm(key, data)
+    }
+
+    /// Seal `data` as `nonce || ciphertext || tag` with AES-256-GCM, for stored
+    /// formats that predate cipher suites
+    pub(crate) fn encrypt_aes_gcm(key: &[u8], data: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        let nonce_full = Self::generate_nonce();
+        let mut nonce = [0u8; AES_GCM_NONCE_LEN];
+        nonce.copy_from_slice(&nonce_full[..AES_GCM_NONCE_LEN]);
+        Self::encrypt_data_with_nonce(key, &nonce, data)
+    }
+
+    /// `encrypt_aes_gcm` with a caller-chosen nonce, for reproducible test vectors.
+    /// Reusing a nonce under the same key breaks AES-GCM; live traffic goes through
+    /// `seal`.
+    pub fn encrypt_data_with_nonce(key: &[u8], nonce: &[u8; AES_GCM_NONCE_LEN], data: &[u8]) -> Result<Vec<u8>, CryptoError> {
         let cipher = Aes256Gcm::new_from_slice(key).map_err(|_| CryptoError::InvalidKeyLength)?;
+
+        let mut ciphertext = cipher.encrypt(Nonce::from_slice(nonce), data).map_err(|_| CryptoError::AeadError)?;
+        ciphertext.splice(0..0, nonce.iter().cloned());
+        Ok(ciphertext)
+    }
+
+    /// Seal `data` with `suite`, producing `suite id || nonce || ciphertext || tag`.
+    /// The suite id is authenticated as associated data.
+    pub fn encrypt_data_suite(suite: CipherSuite, key: &[u8], data: &[u8]) -> Result<Vec<u8>, CryptoError> {
         let nonce_full = Self::generate_nonce();
-        let nonce_bytes = &nonce_full[..12];
-        let nonce = Nonce::from_slice(nonce_bytes);
+        let mut nonce = [0u8; AES_GCM_NONCE_LEN];
+        nonce.copy_from_slice(&nonce_full[..AES_GCM_NONCE_LEN]);
+        Self::encrypt_data_suite_with_nonce(suite, key, &nonce, data)
+    }
 
-        let mut ciphertext = cipher.encrypt(nonce, data).map_err(|_| CryptoError::AeadError)?;
-        ciphertext.splice(0..0, nonce_bytes.iter().cloned());
+    /// `encrypt_data_suite` with a caller-chosen nonce, which must never repeat
+    /// under `key`
+    fn encrypt_data_suite_with_nonce(suite: CipherSuite, key: &[u8], nonce: &[u8; AES_GCM_NONCE_LEN], data: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        let aad = [suite.id()];
+        let payload = Payload { msg: data, aad: &aad };
+
+        let sealed = match suite {
+            CipherSuite::Aes256Gcm => Aes256Gcm::new_from_slice(key)
+                .map_err(|_| CryptoError::InvalidKeyLength)?
+                .encrypt(Nonce::from_slice(nonce), payload),
+            CipherSuite::ChaCha20Poly1305 => ChaCha20Poly1305::new_from_slice(key)
+                .map_err(|_| CryptoError::InvalidKeyLength)?
+                .encrypt(chacha20poly1305::Nonce::from_slice(nonce), payload),
+        }
+        .map_err(|_| CryptoError::AeadError)?;
+
+        let mut ciphertext = Vec::with_capacity(CIPHER_SUITE_ID_LEN + AES_GCM_NONCE_LEN + sealed.len());
+        ciphertext.push(suite.id());
+        ciphertext.extend_from_slice(nonce);
+        ciphertext.extend_from_slice(&sealed);
         Ok(ciphertext)
     }
 
+    /// `encrypt_data_suite` with this engine's cipher suite. Open the result with
+    /// `decrypt_data_suite`, which needs no configuration.
+    pub fn seal(&self, key: &[u8], data: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        Self::encrypt_data_suite(self.cipher_suite, key, data)
+    }
+
+    /// Open output of `encrypt_data_suite`, using whichever cipher its first byte names
+    pub fn decrypt_data_suite(key: &[u8], encrypted_data: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        if encrypted_data.len() < CIPHER_SUITE_ID_LEN + AES_GCM_NONCE_LEN + AES_GCM_TAG_LEN {
+            return Err(CryptoError::MalformedCiphertext);
+        }
+
+        let suite = CipherSuite::from_id(encrypted_data[0])
+            .ok_or_else(|| CryptoError::UnsupportedCipherSuite(format!("id {:#04x}", encrypted_data[0])))?;
+        let (nonce, sealed) = encrypted_data[CIPHER_SUITE_ID_LEN..].split_at(AES_GCM_NONCE_LEN);
+        let aad = [suite.id()];
+        let payload = Payload { msg: sealed, aad: &aad };
+
+        match suite {
+            CipherSuite::Aes256Gcm => Aes256Gcm::new_from_slice(key)
+                .map_err(|_| CryptoError::InvalidKeyLength)?
+                .decrypt(Nonce::from_slice(nonce), payload),
+            CipherSuite::ChaCha20Poly1305 => ChaCha20Poly1305::new_from_slice(key)
+                .map_err(|_| CryptoError::InvalidKeyLength)?
+                .decrypt(chacha20poly1305::Nonce::from_slice(nonce), payload),
+        }
+        .map_err(|_| CryptoError::AuthenticationFailed)
+    }
+
     /// Cryptographically secure random generation with timing attack protection
     pub fn generate_secure_random_bytes(len: usize) -> Vec<u8> {
         let mut bytes = vec![0u8; len];
@@ -179,14 +766,24 @@ impl CryptoEngine {
         hasher.finalize().into()
     }
 
+    /// Decrypt `nonce || ciphertext || tag` produced by `encrypt_data`
+    #[deprecated(since = "0.3.0", note = "use `decrypt_data_suite`, which opens any supported cipher suite")]
     pub fn decrypt_data(key: &[u8], encrypted_data: &[u8]) -> Result<Vec<u8>, CryptoError> {
-        if encrypted_data.len() < 12 {
-            return Err(CryptoError::AeadError);
+        Self::decrypt_aes_gcm(key, encrypted_data)
+    }
+
+    /// Open `nonce || ciphertext || tag` produced by `encrypt_aes_gcm`
+    ///
+    /// Input too short to hold a nonce and tag is `MalformedCiphertext`; a well-formed
+    /// input whose tag does not verify (wrong key or tampering) is `AuthenticationFailed`.
+    pub(crate) fn decrypt_aes_gcm(key: &[u8], encrypted_data: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        if encrypted_data.len() < AES_GCM_NONCE_LEN + AES_GCM_TAG_LEN {
+            return Err(CryptoError::MalformedCiphertext);
         }
 
         let cipher = Aes256Gcm::new_from_slice(key).map_err(|_| CryptoError::InvalidKeyLength)?;
-        let nonce = Nonce::from_slice(&encrypted_data[..12]);
-        cipher.decrypt(nonce, &encrypted_data[12..]).map_err(|_| CryptoError::AeadError)
+        let nonce = Nonce::from_slice(&encrypted_data[..AES_GCM_NONCE_LEN]);
+        cipher.decrypt(nonce, &encrypted_data[AES_GCM_NONCE_LEN..]).map_err(|_| CryptoError::AuthenticationFailed)
     }
 
     /// Encrypt IR payload (high-bandwidth channel) using AES-GCM
@@ -207,7 +804,7 @@ impl CryptoEngine {
 
     /// Decrypt IR payload
     pub fn decrypt_ir_payload(key: &[u8], encrypted_payload: &[u8]) -> Result<Vec<u8>, CryptoError> {
-        Self::decrypt_data(key, encrypted_payload)
+        Self::decrypt_aes_gcm(key, encrypted_payload)
     }
 
     /// Encrypt ultrasonic frame (low-bandwidth control channel) using HMAC-SHA256
@@ -244,10 +841,21 @@ impl CryptoEngine {
         }
     }
 
+    /// Ed25519 signature over `data` with this engine's signing key
+    pub fn sign(&self, data: &[u8]) -> [u8; 64] {
+        self.ed25519_keypair.sign(data).to_bytes()
+    }
+
+    /// Check an Ed25519 signature; a malformed public key verifies nothing
+    pub fn verify(public_key: &[u8; 32], data: &[u8], signature: &[u8; 64]) -> bool {
+        VerifyingKey::from_bytes(public_key)
+            .map(|key| key.verify(data, &Signature::from_bytes(signature)).is_ok())
+            .unwrap_or(false)
+    }
+
     /// Sign log entry with Ed25519
     pub fn sign_log_entry(&self, log_data: &[u8]) -> Result<Vec<u8>, CryptoError> {
-        let signature = self.ed25519_keypair.sign(log_data);
-        Ok(signature.to_bytes().to_vec())
+        Ok(self.sign(log_data).to_vec())
     }
 
     /// Verify log signature
@@ -357,23 +965,67 @@ impl CryptoEngine {
             .verify_signature(data, signature, public_key)
     }
 
-    /// Hybrid key exchange: Combine classical ECDH with post-quantum Kyber
+    /// Kyber-768 public key bytes a peer passes to `encapsulate`
     #[cfg(feature = "post-quantum")]
-    pub fn hybrid_key_exchange(&mut self, peer_ecdh_key: &[u8], peer_kyber_key: &crate::post_quantum::KyberPublicKey) -> Result<[u8; 32], CryptoError> {
-        // Perform classical ECDH
-        let classical_session = self.derive_ephemeral_shared_secret(peer_ecdh_key)?;
+    pub fn kem_public_key(&self) -> Option<&[u8]> {
+        self.pq_engine.as_ref().map(|pq| pq.kyber_public_key().as_bytes())
+    }
+
+    /// Encapsulate a fresh Kyber-768 secret to a peer's KEM public key.
+    /// Returns the ciphertext to send and the shared secret it carries.
+    #[cfg(feature = "post-quantum")]
+    pub fn encapsulate(&self, peer_kem_public_key: &[u8]) -> Result<(Vec<u8>, Zeroizing<[u8; 32]>), CryptoError> {
+        let peer_pk = crate::post_quantum::KyberPublicKey::from_bytes(peer_kem_public_key)
+            .map_err(|_| CryptoError::InvalidKeyLength)?;
+        let encapsulated = KyberKEM::encapsulate(&peer_pk)?;
+
+        let mut shared_secret = Zeroizing::new([0u8; 32]);
+        shared_secret.copy_from_slice(encapsulated.shared_secret.as_bytes());
+        Ok((encapsulated.ciphertext.as_bytes().to_vec(), shared_secret))
+    }
+
+    /// Recover the shared secret from a Kyber-768 ciphertext sent to this engine
+    #[cfg(feature = "post-quantum")]
+    pub fn decapsulate(&self, kem_ciphertext: &[u8]) -> Result<Zeroizing<[u8; 32]>, CryptoError> {
+        let ciphertext = crate::post_quantum::KyberCiphertext::from_bytes(kem_ciphertext)
+            .map_err(|_| CryptoError::MalformedCiphertext)?;
+        let recovered = self.pq_decapsulate_secret(&ciphertext)?;
+
+        let mut shared_secret = Zeroizing::new([0u8; 32]);
+        shared_secret.copy_from_slice(recovered.as_bytes());
+        Ok(shared_secret)
+    }
 
-        // Perform post-quantum key exchange
-        let pq_ciphertext = self.pq_encapsulate_secret(peer_kyber_key)?;
-        let pq_shared_secret = self.pq_decapsulate_secret(&pq_ciphertext.ciphertext)?;
+    /// HKDF-SHA256 over both shared secrets, so the session key stays safe while
+    /// either X25519 or Kyber-768 holds
+    pub fn combine_hybrid_secrets(classical: &[u8; 32], post_quantum: &[u8; 32]) -> [u8; 32] {
+        let mut ikm = Zeroizing::new([0u8; 64]);
+        ikm[..32].copy_from_slice(classical);
+        ikm[32..].copy_from_slice(post_quantum);
+
+        let mut session_key = [0u8; 32];
+        Hkdf::<Sha256>::new(None, ikm.as_ref())
+            .expand(HYBRID_KEM_INFO, &mut session_key)
+            .expect("32 bytes is a valid HKDF-SHA256 output length");
+        session_key
+    }
 
-        // Combine secrets using HKDF
-        let mut combined_secret = [0u8; 64];
-        combined_secret[..32].copy_from_slice(classical_session.key());
-        combined_secret[32..].copy_from_slice(pq_shared_secret.as_bytes());
+    /// Initiator side of the hybrid exchange: X25519 with the peer's ECDH key plus a
+    /// Kyber-768 encapsulation to its KEM key. Returns the KEM ciphertext for the
+    /// peer and the combined session key.
+    #[cfg(feature = "post-quantum")]
+    pub fn hybrid_key_exchange(&mut self, peer_ecdh_key: &[u8], peer_kem_public_key: &[u8]) -> Result<(Vec<u8>, [u8; 32]), CryptoError> {
+        let (kem_ciphertext, pq_secret) = self.encapsulate(peer_kem_public_key)?;
+        let classical_session = self.derive_ephemeral_shared_secret(peer_ecdh_key)?;
+        Ok((kem_ciphertext, Self::combine_hybrid_secrets(classical_session.key(), &pq_secret)))
+    }
 
-        // Derive final key
-        self.hkdf_derive_key(&combined_secret, b"hybrid-key-exchange", 32)
+    /// Responder side of `hybrid_key_exchange`, decapsulating the initiator's ciphertext
+    #[cfg(feature = "post-quantum")]
+    pub fn hybrid_key_exchange_respond(&mut self, peer_ecdh_key: &[u8], kem_ciphertext: &[u8]) -> Result<[u8; 32], CryptoError> {
+        let pq_secret = self.decapsulate(kem_ciphertext)?;
+        let classical_session = self.derive_ephemeral_shared_secret(peer_ecdh_key)?;
+        Ok(Self::combine_hybrid_secrets(classical_session.key(), &pq_secret))
     }
 
     /// Hybrid signature: Sign with both Ed25519 and Dilithium
@@ -397,3 +1049,241 @@ impl CryptoEngine {
         Ok(classical_valid && pq_valid)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decrypt_truncated_ciphertext_is_malformed() {
+        let key = [3u8; 32];
+        let sealed = CryptoEngine::encrypt_aes_gcm(&key, b"truncate me").unwrap();
+
+        for len in [0, 11, AES_GCM_NONCE_LEN + AES_GCM_TAG_LEN - 1] {
+            let result = CryptoEngine::decrypt_aes_gcm(&key, &sealed[..len]);
+            assert!(matches!(result, Err(CryptoError::MalformedCiphertext)), "len {}", len);
+        }
+    }
+
+    #[test]
+    fn test_decrypt_bit_flip_fails_authentication() {
+        let key = [3u8; 32];
+        let sealed = CryptoEngine::encrypt_aes_gcm(&key, b"flip a bit").unwrap();
+        assert_eq!(CryptoEngine::decrypt_aes_gcm(&key, &sealed).unwrap(), b"flip a bit");
+
+        let mut tampered = sealed.clone();
+        tampered[AES_GCM_NONCE_LEN] ^= 0x01;
+        assert!(matches!(CryptoEngine::decrypt_aes_gcm(&key, &tampered), Err(CryptoError::AuthenticationFailed)));
+
+        assert!(matches!(CryptoEngine::decrypt_aes_gcm(&[4u8; 32], &sealed), Err(CryptoError::AuthenticationFailed)));
+    }
+
+    #[test]
+    fn test_cipher_suites_are_tagged_and_auto_detected() {
+        let key = [9u8; 32];
+        for suite in [CipherSuite::Aes256Gcm, CipherSuite::ChaCha20Poly1305] {
+            let sealed = CryptoEngine::encrypt_data_suite(suite, &key, b"either cipher").unwrap();
+            assert_eq!(sealed[0], suite.id());
+            assert_eq!(sealed.len(), CIPHER_SUITE_ID_LEN + AES_GCM_NONCE_LEN + 13 + AES_GCM_TAG_LEN);
+            assert_eq!(CryptoEngine::decrypt_data_suite(&key, &sealed).unwrap(), b"either cipher");
+            assert_eq!(suite.name().parse::<CipherSuite>().unwrap(), suite);
+
+            // Relabelling the ciphertext as the other suite fails authentication
+            let mut relabelled = sealed.clone();
+            relabelled[0] = if suite == CipherSuite::Aes256Gcm { 0x02 } else { 0x01 };
+            assert!(matches!(CryptoEngine::decrypt_data_suite(&key, &relabelled), Err(CryptoError::AuthenticationFailed)));
+            assert!(matches!(CryptoEngine::decrypt_data_suite(&[1u8; 32], &sealed), Err(CryptoError::AuthenticationFailed)));
+        }
+
+        let mut unknown = CryptoEngine::encrypt_data_suite(CipherSuite::ChaCha20Poly1305, &key, b"x").unwrap();
+        unknown[0] = 0x7F;
+        assert!(matches!(CryptoEngine::decrypt_data_suite(&key, &unknown), Err(CryptoError::UnsupportedCipherSuite(_))));
+        assert!(matches!("chacha20_poly1305".parse::<CipherSuite>(), Ok(CipherSuite::ChaCha20Poly1305)));
+        assert!("DES".parse::<CipherSuite>().is_err());
+
+        let engine = CryptoEngine::new_with_suite(CipherSuite::ChaCha20Poly1305);
+        assert_eq!(engine.cipher_suite(), CipherSuite::ChaCha20Poly1305);
+        assert_eq!(CryptoEngine::new().cipher_suite(), CipherSuite::Aes256Gcm);
+    }
+
+    #[test]
+    fn test_private_key_export_round_trips_identity() {
+        let engine = CryptoEngine::new();
+        let exported = engine.export_private_key("correct horse battery staple").unwrap();
+
+        let restored = CryptoEngine::from_encrypted_private_key(&exported, "correct horse battery staple").unwrap();
+        assert_eq!(restored.ecdh_public_key(), engine.ecdh_public_key());
+        assert!(!exported.windows(32).any(|window| window == engine.ecdh_identity.as_slice()));
+
+        assert!(matches!(
+            CryptoEngine::from_encrypted_private_key(&exported, "wrong passphrase"),
+            Err(CryptoError::DecryptionFailed)
+        ));
+        assert!(matches!(
+            CryptoEngine::from_encrypted_private_key(&exported[..exported.len() - 1], "correct horse battery staple"),
+            Err(CryptoError::MalformedCiphertext)
+        ));
+    }
+
+    #[test]
+    fn test_hybrid_secret_depends_on_both_inputs() {
+        let classical = [1u8; 32];
+        let post_quantum = [2u8; 32];
+        let key = CryptoEngine::combine_hybrid_secrets(&classical, &post_quantum);
+
+        assert_eq!(key, CryptoEngine::combine_hybrid_secrets(&classical, &post_quantum));
+        assert_ne!(key, CryptoEngine::combine_hybrid_secrets(&[3u8; 32], &post_quantum));
+        assert_ne!(key, CryptoEngine::combine_hybrid_secrets(&classical, &[3u8; 32]));
+        assert_ne!(key, CryptoEngine::combine_hybrid_secrets(&post_quantum, &classical));
+    }
+
+    #[cfg(feature = "post-quantum")]
+    #[test]
+    fn test_hybrid_kyber_exchange_agrees_on_session_key() {
+        let mut alice = CryptoEngine::new_kyber().unwrap();
+        let mut bob = CryptoEngine::new_kyber().unwrap();
+        let alice_ecdh = alice.ecdh_public_key().to_vec();
+        let bob_ecdh = bob.ecdh_public_key().to_vec();
+        let bob_kem = bob.kem_public_key().unwrap().to_vec();
+
+        let (ciphertext, secret) = alice.encapsulate(&bob_kem).unwrap();
+        assert_eq!(*bob.decapsulate(&ciphertext).unwrap(), *secret);
+
+        let (kem_ciphertext, alice_key) = alice.hybrid_key_exchange(&bob_ecdh, &bob_kem).unwrap();
+        let bob_key = bob.hybrid_key_exchange_respond(&alice_ecdh, &kem_ciphertext).unwrap();
+        assert_eq!(alice_key, bob_key);
+
+        assert!(matches!(bob.decapsulate(&kem_ciphertext[1..]), Err(CryptoError::MalformedCiphertext)));
+    }
+
+    #[test]
+    fn test_signing_key_is_derived_from_ecdh_seed() {
+        let engine = CryptoEngine::from_ecdh_seed([5u8; 32]);
+        assert_eq!(engine.ed25519_public_key(), CryptoEngine::from_ecdh_seed([5u8; 32]).ed25519_public_key());
+        assert_ne!(engine.ed25519_public_key(), CryptoEngine::from_ecdh_seed([6u8; 32]).ed25519_public_key());
+
+        let signature = engine.sign(b"log entry");
+        assert!(CryptoEngine::verify(engine.ed25519_public_key(), b"log entry", &signature));
+        assert!(!CryptoEngine::verify(engine.ed25519_public_key(), b"log entrY", &signature));
+        assert!(CryptoEngine::verify_log_signature(engine.ed25519_public_key(), b"log entry", &signature).is_ok());
+
+        // Restoring an exported identity also restores the signing key
+        let exported = engine.export_private_key("pass").unwrap();
+        let restored = CryptoEngine::from_encrypted_private_key(&exported, "pass").unwrap();
+        assert!(CryptoEngine::verify(engine.ed25519_public_key(), b"from restored", &restored.sign(b"from restored")));
+    }
+
+    #[test]
+    fn test_shamir_shares_reconstruct_from_any_threshold_subset() {
+        let secret = [0x5Au8; 32];
+        let shares = CryptoEngine::split_secret(&secret, 5, 3);
+        assert_eq!(shares.len(), 5);
+        assert!(shares.iter().all(|share| share[1..] != secret[..]));
+
+        for subset in [[0, 1, 2], [4, 2, 0], [1, 3, 4]] {
+            let picked: Vec<[u8; 33]> = subset.iter().map(|&i| shares[i]).collect();
+            assert_eq!(CryptoEngine::reconstruct_secret(&picked).unwrap(), secret);
+        }
+        assert_eq!(CryptoEngine::reconstruct_secret(&shares).unwrap(), secret);
+        assert_ne!(CryptoEngine::reconstruct_secret(&shares[..2]).unwrap(), secret);
+
+        assert!(matches!(CryptoEngine::reconstruct_secret(&[shares[0], shares[0]]), Err(CryptoError::InvalidShares(_))));
+        assert!(matches!(CryptoEngine::reconstruct_secret(&[]), Err(CryptoError::InvalidShares(_))));
+        assert!(CryptoEngine::split_secret(&secret, 2, 3).is_empty());
+    }
+
+    #[test]
+    fn test_sequenced_frames_reject_replays() {
+        let session_key = [0x42u8; 32];
+        let mut alice = CryptoEngine::new();
+        let mut bob = CryptoEngine::new();
+        alice.set_session_key(&session_key, true);
+        bob.set_session_key(&session_key, false);
+
+        let frames: Vec<Vec<u8>> = (0..3u8).map(|i| alice.encrypt_sequenced(&[i; 4]).unwrap()).collect();
+        assert_eq!(frames[2][0], CipherSuite::Aes256Gcm.id());
+        assert_eq!(frames[2][CIPHER_SUITE_ID_LEN..][..AES_GCM_NONCE_LEN], [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2]);
+
+        // Out-of-order delivery is fine, a second delivery is not
+        assert_eq!(bob.decrypt_sequenced(&frames[1]).unwrap(), [1; 4]);
+        assert_eq!(bob.decrypt_sequenced(&frames[0]).unwrap(), [0; 4]);
+        assert!(matches!(bob.decrypt_sequenced(&frames[1]), Err(CryptoError::ReplayDetected(1))));
+
+        // A forged frame claiming counter 2 must not burn the genuine one
+        let mut forged = frames[2].clone();
+        *forged.last_mut().unwrap() ^= 1;
+        assert!(matches!(bob.decrypt_sequenced(&forged), Err(CryptoError::AuthenticationFailed)));
+        assert_eq!(bob.decrypt_sequenced(&frames[2]).unwrap(), [2; 4]);
+
+        // Directions use separate keys, so a reflected frame does not open
+        assert!(alice.decrypt_sequenced(&frames[0]).is_err());
+        bob.set_cipher_suite(CipherSuite::ChaCha20Poly1305);
+        let reply = bob.encrypt_sequenced(b"ack").unwrap();
+        assert_eq!(reply[0], CipherSuite::ChaCha20Poly1305.id());
+        assert_eq!(alice.decrypt_sequenced(&reply).unwrap(), b"ack");
+
+        // Frames that fell out of the window are refused
+        for i in 0..REPLAY_WINDOW as u8 {
+            bob.decrypt_sequenced(&alice.encrypt_sequenced(&[i]).unwrap()).unwrap();
+        }
+        assert!(matches!(bob.decrypt_sequenced(&frames[2]), Err(CryptoError::ReplayDetected(2))));
+    }
+}
+
+#[cfg(test)]
+mod proptests {
+    use super::*;
+    use proptest::prelude::*;
+
+    /// Cases per property; AES-GCM is fast, so this stays well under a second
+    const PROPTEST_CASES: u32 = 256;
+    /// Largest plaintext generated (covers multi-block and partial-block tails)
+    const MAX_PLAINTEXT_LEN: usize = 16 * 1024;
+
+    /// Arbitrary keys, with the all-zero key drawn often enough to always be covered
+    fn key_strategy() -> impl Strategy<Value = [u8; 32]> {
+        prop_oneof![
+            1 => Just([0u8; 32]),
+            4 => any::<[u8; 32]>(),
+        ]
+    }
+
+    /// Plaintexts biased toward the edges: empty, single block boundary, maximum size
+    fn plaintext_strategy() -> impl Strategy<Value = Vec<u8>> {
+        prop_oneof![
+            1 => Just(Vec::new()),
+            1 => prop::collection::vec(any::<u8>(), 16),
+            1 => prop::collection::vec(any::<u8>(), MAX_PLAINTEXT_LEN),
+            6 => prop::collection::vec(any::<u8>(), 0..=MAX_PLAINTEXT_LEN),
+        ]
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(PROPTEST_CASES))]
+
+        #[test]
+        fn prop_encrypt_then_decrypt_is_identity(key in key_strategy(), plaintext in plaintext_strategy()) {
+            let sealed = CryptoEngine::encrypt_aes_gcm(&key, &plaintext).unwrap();
+            prop_assert_eq!(CryptoEngine::decrypt_aes_gcm(&key, &sealed).unwrap(), plaintext);
+        }
+
+        #[test]
+        fn prop_any_bit_flip_fails_authentication(
+            key in key_strategy(),
+            plaintext in plaintext_strategy(),
+            bit in any::<prop::sample::Index>(),
+        ) {
+            let mut sealed = CryptoEngine::encrypt_aes_gcm(&key, &plaintext).unwrap();
+            // Nonce, ciphertext and tag are all covered
+            let bit = bit.index(sealed.len() * 8);
+            sealed[bit / 8] ^= 1 << (bit % 8);
+            prop_assert!(matches!(CryptoEngine::decrypt_aes_gcm(&key, &sealed), Err(CryptoError::AuthenticationFailed)));
+        }
+
+        #[test]
+        fn prop_ciphertext_length_tracks_plaintext_length(key in key_strategy(), plaintext in plaintext_strategy()) {
+            let sealed = CryptoEngine::encrypt_aes_gcm(&key, &plaintext).unwrap();
+            prop_assert_eq!(sealed.len(), plaintext.len() + AES_GCM_NONCE_LEN + AES_GCM_TAG_LEN);
+        }
+    }
+}
@@ -0,0 +1,126 @@
+//! # Demodulation Backends
+//!
+//! Hot loops of the tone demodulators behind a pluggable `Demodulator` backend.
+//! The portable scalar backend is always available; with the `simd` feature a
+//! vectorized backend runs four Goertzel filters per lane group and is picked at
+//! runtime when the CPU supports it. Both backends evaluate the same f64
+//! recurrence in the same order, so their results are bit-identical.
+
+use std::sync::Arc;
+
+/// Backend evaluating Goertzel filters over a block of samples
+pub trait Demodulator: Send + Sync + std::fmt::Debug {
+    /// Short backend name for diagnostics
+    fn name(&self) -> &'static str;
+
+    /// Normalized power of each of `frequencies` in `chunk` (a full-scale sine of
+    /// amplitude A yields A²/2), written to `powers`
+    fn goertzel_powers(&self, chunk: &[f32], frequencies: &[f32], sample_rate: f32, powers: &mut [f32]);
+
+    /// Normalized power of a single frequency
+    fn goertzel_power(&self, chunk: &[f32], frequency: f32, sample_rate: f32) -> f32 {
+        let mut power = [0.0f32];
+        self.goertzel_powers(chunk, &[frequency], sample_rate, &mut power);
+        power[0]
+    }
+}
+
+fn goertzel_coeff(frequency: f32, sample_rate: f32) -> f64 {
+    let omega = 2.0 * std::f64::consts::PI * frequency as f64 / sample_rate as f64;
+    2.0 * omega.cos()
+}
+
+fn normalized_power(s_prev: f64, s_prev2: f64, coeff: f64, n: usize) -> f32 {
+    let power = s_prev * s_prev + s_prev2 * s_prev2 - coeff * s_prev * s_prev2;
+    let n = n as f64;
+    (2.0 * power / (n * n)) as f32
+}
+
+/// Portable one-filter-at-a-time backend
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ScalarDemodulator;
+
+impl Demodulator for ScalarDemodulator {
+    fn name(&self) -> &'static str {
+        "scalar"
+    }
+
+    fn goertzel_powers(&self, chunk: &[f32], frequencies: &[f32], sample_rate: f32, powers: &mut [f32]) {
+        for (&frequency, power) in frequencies.iter().zip(powers.iter_mut()) {
+            let coeff = goertzel_coeff(frequency, sample_rate);
+            let (mut s_prev, mut s_prev2) = (0.0f64, 0.0f64);
+
+            for &sample in chunk {
+                let s = sample as f64 + coeff * s_prev - s_prev2;
+                s_prev2 = s_prev;
+                s_prev = s;
+            }
+
+            *power = normalized_power(s_prev, s_prev2, coeff, chunk.len());
+        }
+    }
+}
+
+/// Four filters per step in SIMD lanes (SSE2/AVX on x86_64, NEON on aarch64)
+#[cfg(feature = "simd")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SimdDemodulator;
+
+#[cfg(feature = "simd")]
+impl Demodulator for SimdDemodulator {
+    fn name(&self) -> &'static str {
+        "simd"
+    }
+
+    fn goertzel_powers(&self, chunk: &[f32], frequencies: &[f32], sample_rate: f32, powers: &mut [f32]) {
+        use wide::f64x4;
+
+        for (frequencies, powers) in frequencies.chunks(4).zip(powers.chunks_mut(4)) {
+            let mut coeffs = [0.0f64; 4];
+            for (coeff, &frequency) in coeffs.iter_mut().zip(frequencies) {
+                *coeff = goertzel_coeff(frequency, sample_rate);
+            }
+            let coeff = f64x4::from(coeffs);
+            let (mut s_prev, mut s_prev2) = (f64x4::ZERO, f64x4::ZERO);
+
+            for &sample in chunk {
+                let s = f64x4::splat(sample as f64) + coeff * s_prev - s_prev2;
+                s_prev2 = s_prev;
+                s_prev = s;
+            }
+
+            let (s_prev, s_prev2) = (s_prev.to_array(), s_prev2.to_array());
+            for (lane, power) in powers.iter_mut().enumerate() {
+                *power = normalized_power(s_prev[lane], s_prev2[lane], coeffs[lane], chunk.len());
+            }
+        }
+    }
+}
+
+/// Whether the running CPU has the vector unit the SIMD backend is built for
+#[cfg(feature = "simd")]
+fn simd_supported() -> bool {
+    #[cfg(target_arch = "x86_64")]
+    {
+        std::arch::is_x86_feature_detected!("sse2")
+    }
+    #[cfg(target_arch = "aarch64")]
+    {
+        std::arch::is_aarch64_feature_detected!("neon")
+    }
+    #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+    {
+        false
+    }
+}
+
+/// Fastest backend available on this build and CPU
+pub fn detect_demodulator() -> Arc<dyn Demodulator> {
+    #[cfg(feature = "simd")]
+    {
+        if simd_supported() {
+            return Arc::new(SimdDemodulator);
+        }
+    }
+    Arc::new(ScalarDemodulator)
+}
@@ -0,0 +1,303 @@
+//! # Discovery Module
+//!
+//! Unauthenticated presence beacons used before pairing. A beacon advertises a
+//! device identifier and its capabilities so that a peer in an unconfigured
+//! environment knows who is around and which handshake to start. Beacons carry
+//! no key material; everything they claim must be re-established by the handshake.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tokio::time::{Duration, Instant};
+use rand::RngCore;
+
+const BEACON_MAGIC: &[u8; 4] = b"GLBC";
+const BEACON_VERSION: u8 = 1;
+/// Magic + version + device id + capability flags + name length
+const BEACON_HEADER_LEN: usize = 4 + 1 + 16 + 2 + 1;
+
+const CAP_SHORT_RANGE: u16 = 1 << 0;
+const CAP_LONG_RANGE: u16 = 1 << 1;
+const CAP_LASER: u16 = 1 << 2;
+const CAP_ULTRASONIC_BEAM: u16 = 1 << 3;
+const CAP_QR_DISPLAY: u16 = 1 << 4;
+const CAP_QR_SCAN: u16 = 1 << 5;
+
+#[derive(Debug, thiserror::Error)]
+pub enum DiscoveryError {
+    #[error("No beacon transport configured")]
+    NoTransport,
+    #[error("Beacon transport closed")]
+    TransportClosed,
+    #[error("Malformed beacon frame")]
+    MalformedBeacon,
+    #[error("Beacon name too long (max 255 bytes)")]
+    NameTooLong,
+}
+
+/// Capabilities advertised in a beacon
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BeaconCapabilities {
+    pub short_range: bool,
+    pub long_range: bool,
+    pub laser: bool,
+    pub ultrasonic_beam: bool,
+    pub qr_display: bool,
+    pub qr_scan: bool,
+}
+
+impl BeaconCapabilities {
+    fn to_flags(self) -> u16 {
+        let mut flags = 0;
+        for (set, flag) in [
+            (self.short_range, CAP_SHORT_RANGE),
+            (self.long_range, CAP_LONG_RANGE),
+            (self.laser, CAP_LASER),
+            (self.ultrasonic_beam, CAP_ULTRASONIC_BEAM),
+            (self.qr_display, CAP_QR_DISPLAY),
+            (self.qr_scan, CAP_QR_SCAN),
+        ] {
+            if set {
+                flags |= flag;
+            }
+        }
+        flags
+    }
+
+    fn from_flags(flags: u16) -> Self {
+        Self {
+            short_range: flags & CAP_SHORT_RANGE != 0,
+            long_range: flags & CAP_LONG_RANGE != 0,
+            laser: flags & CAP_LASER != 0,
+            ultrasonic_beam: flags & CAP_ULTRASONIC_BEAM != 0,
+            qr_display: flags & CAP_QR_DISPLAY != 0,
+            qr_scan: flags & CAP_QR_SCAN != 0,
+        }
+    }
+}
+
+/// Content of a discovery beacon
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BeaconPayload {
+    /// Random per-beacon identifier, not tied to any long-term key
+    pub device_id: [u8; 16],
+    pub capabilities: BeaconCapabilities,
+    /// Optional human-readable label
+    pub name: String,
+}
+
+impl BeaconPayload {
+    /// Create a beacon payload with a fresh random device identifier
+    pub fn new(capabilities: BeaconCapabilities, name: impl Into<String>) -> Self {
+        let mut device_id = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut device_id);
+        Self {
+            device_id,
+            capabilities,
+            name: name.into(),
+        }
+    }
+
+    /// Encode as a compact beacon frame
+    pub fn to_bytes(&self) -> Result<Vec<u8>, DiscoveryError> {
+        let name = self.name.as_bytes();
+        if name.len() > u8::MAX as usize {
+            return Err(DiscoveryError::NameTooLong);
+        }
+
+        let mut frame = Vec::with_capacity(BEACON_HEADER_LEN + name.len());
+        frame.extend_from_slice(BEACON_MAGIC);
+        frame.push(BEACON_VERSION);
+        frame.extend_from_slice(&self.device_id);
+        frame.extend_from_slice(&self.capabilities.to_flags().to_be_bytes());
+        frame.push(name.len() as u8);
+        frame.extend_from_slice(name);
+        Ok(frame)
+    }
+
+    /// Decode a beacon frame
+    pub fn from_bytes(frame: &[u8]) -> Result<Self, DiscoveryError> {
+        if frame.len() < BEACON_HEADER_LEN || &frame[..4] != BEACON_MAGIC || frame[4] != BEACON_VERSION {
+            return Err(DiscoveryError::MalformedBeacon);
+        }
+
+        let mut device_id = [0u8; 16];
+        device_id.copy_from_slice(&frame[5..21]);
+        let flags = u16::from_be_bytes([frame[21], frame[22]]);
+        let name_len = frame[23] as usize;
+        let name = frame.get(BEACON_HEADER_LEN..BEACON_HEADER_LEN + name_len)
+            .filter(|_| frame.len() == BEACON_HEADER_LEN + name_len)
+            .ok_or(DiscoveryError::MalformedBeacon)?;
+
+        Ok(Self {
+            device_id,
+            capabilities: BeaconCapabilities::from_flags(flags),
+            name: String::from_utf8(name.to_vec()).map_err(|_| DiscoveryError::MalformedBeacon)?,
+        })
+    }
+}
+
+/// A peer heard while listening for beacons
+#[derive(Debug, Clone)]
+pub struct DiscoveredPeer {
+    pub device_id: [u8; 16],
+    pub capabilities: BeaconCapabilities,
+    pub name: String,
+    pub first_seen: Instant,
+    pub last_seen: Instant,
+    pub beacon_count: u32,
+}
+
+/// Broadcast medium carrying beacon frames (ultrasound, laser, loopback, ...)
+pub trait BeaconTransport: Send + Sync {
+    /// Emit one beacon frame
+    fn broadcast(&self, frame: &[u8]) -> Result<(), DiscoveryError>;
+
+    /// Receive every frame emitted on the medium from now on
+    fn subscribe(&self) -> broadcast::Receiver<Vec<u8>>;
+}
+
+/// In-process transport; clones share the same medium
+#[derive(Debug, Clone)]
+pub struct LoopbackBeaconTransport {
+    medium: broadcast::Sender<Vec<u8>>,
+}
+
+impl LoopbackBeaconTransport {
+    pub fn new() -> Self {
+        let (medium, _) = broadcast::channel(64);
+        Self { medium }
+    }
+}
+
+impl Default for LoopbackBeaconTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BeaconTransport for LoopbackBeaconTransport {
+    fn broadcast(&self, frame: &[u8]) -> Result<(), DiscoveryError> {
+        // No listeners is not an error for a beacon
+        let _ = self.medium.send(frame.to_vec());
+        Ok(())
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<Vec<u8>> {
+        self.medium.subscribe()
+    }
+}
+
+/// Running beacon; the beacon stops when the handle is stopped or dropped
+#[derive(Debug)]
+pub struct BeaconHandle {
+    device_id: [u8; 16],
+    task: JoinHandle<()>,
+}
+
+impl BeaconHandle {
+    pub fn device_id(&self) -> &[u8; 16] {
+        &self.device_id
+    }
+
+    pub fn stop(self) {
+        self.task.abort();
+    }
+}
+
+impl Drop for BeaconHandle {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Emit `payload` on `transport` every `interval` until the handle is dropped
+pub fn spawn_beacon(
+    transport: Arc<dyn BeaconTransport>,
+    interval: Duration,
+    payload: &BeaconPayload,
+) -> Result<BeaconHandle, DiscoveryError> {
+    let frame = payload.to_bytes()?;
+    let task = tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            if transport.broadcast(&frame).is_err() {
+                break;
+            }
+        }
+    });
+
+    Ok(BeaconHandle {
+        device_id: payload.device_id,
+        task,
+    })
+}
+
+/// Collect beacons heard on `receiver` for `duration`, skipping `ignore`d device ids
+pub async fn collect_beacons(
+    mut receiver: broadcast::Receiver<Vec<u8>>,
+    duration: Duration,
+    ignore: &[[u8; 16]],
+) -> Vec<DiscoveredPeer> {
+    let deadline = Instant::now() + duration;
+    let mut peers: HashMap<[u8; 16], DiscoveredPeer> = HashMap::new();
+
+    loop {
+        let frame = match tokio::time::timeout_at(deadline, receiver.recv()).await {
+            Ok(Ok(frame)) => frame,
+            Ok(Err(broadcast::error::RecvError::Lagged(_))) => continue,
+            Ok(Err(broadcast::error::RecvError::Closed)) | Err(_) => break,
+        };
+
+        // Beacons are unauthenticated; silently drop anything that does not parse
+        let Ok(payload) = BeaconPayload::from_bytes(&frame) else {
+            continue;
+        };
+        if ignore.contains(&payload.device_id) {
+            continue;
+        }
+
+        let now = Instant::now();
+        peers.entry(payload.device_id)
+            .and_modify(|peer| {
+                peer.capabilities = payload.capabilities;
+                peer.name = payload.name.clone();
+                peer.last_seen = now;
+                peer.beacon_count += 1;
+            })
+            .or_insert_with(|| DiscoveredPeer {
+                device_id: payload.device_id,
+                capabilities: payload.capabilities,
+                name: payload.name.clone(),
+                first_seen: now,
+                last_seen: now,
+                beacon_count: 1,
+            });
+    }
+
+    let mut peers: Vec<DiscoveredPeer> = peers.into_values().collect();
+    peers.sort_by_key(|peer| peer.first_seen);
+    peers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_beacon_frame_round_trip() {
+        let payload = BeaconPayload::new(
+            BeaconCapabilities { long_range: true, laser: true, ..Default::default() },
+            "station-7",
+        );
+        let frame = payload.to_bytes().unwrap();
+        assert_eq!(BeaconPayload::from_bytes(&frame).unwrap(), payload);
+
+        assert!(matches!(BeaconPayload::from_bytes(&frame[..frame.len() - 1]), Err(DiscoveryError::MalformedBeacon)));
+        let mut bad_magic = frame.clone();
+        bad_magic[0] ^= 0xFF;
+        assert!(matches!(BeaconPayload::from_bytes(&bad_magic), Err(DiscoveryError::MalformedBeacon)));
+    }
+}
@@ -0,0 +1,408 @@
+//! # Duplex Session Module
+//!
+//! Concurrent laser data transmission with ultrasound flow control for long-range mode.
+//! The laser carries numbered data chunks while the focused ultrasound channel carries
+//! ACK/NAK control frames back, which drive selective retransmission (ARQ).
+
+use crate::laser::{LaserEngine, LaserError};
+use crate::ultrasonic_beam::{BeamSignal, UltrasonicBeamEngine};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+use tokio::sync::{mpsc, Mutex};
+use tokio::time::Duration;
+
+const CONTROL_ACK: u8 = 0x01;
+const CONTROL_NAK: u8 = 0x02;
+
+/// Flow-control message carried over the ultrasound control channel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlMessage {
+    Ack(u32),
+    Nak(u32),
+}
+
+impl ControlMessage {
+    /// Encode as a 5-byte control frame (fits the 32-byte ultrasound control limit)
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let (kind, sequence) = match self {
+            ControlMessage::Ack(seq) => (CONTROL_ACK, *seq),
+            ControlMessage::Nak(seq) => (CONTROL_NAK, *seq),
+        };
+        let mut frame = Vec::with_capacity(5);
+        frame.push(kind);
+        frame.extend_from_slice(&sequence.to_be_bytes());
+        frame
+    }
+
+    /// Decode a control frame
+    pub fn from_bytes(data: &[u8]) -> Result<Self, DuplexError> {
+        if data.len() != 5 {
+            return Err(DuplexError::InvalidFrame);
+        }
+        let sequence = u32::from_be_bytes([data[1], data[2], data[3], data[4]]);
+        match data[0] {
+            CONTROL_ACK => Ok(ControlMessage::Ack(sequence)),
+            CONTROL_NAK => Ok(ControlMessage::Nak(sequence)),
+            _ => Err(DuplexError::InvalidFrame),
+        }
+    }
+}
+
+/// Numbered data chunk carried over the laser channel
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataChunk {
+    pub sequence: u32,
+    pub payload: Vec<u8>,
+}
+
+impl DataChunk {
+    /// Encode as a laser frame: sequence (u32 BE) followed by the payload
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut frame = Vec::with_capacity(4 + self.payload.len());
+        frame.extend_from_slice(&self.sequence.to_be_bytes());
+        frame.extend_from_slice(&self.payload);
+        frame
+    }
+
+    /// Decode a laser frame
+    pub fn from_bytes(data: &[u8]) -> Result<Self, DuplexError> {
+        if data.len() < 4 {
+            return Err(DuplexError::InvalidFrame);
+        }
+        Ok(Self {
+            sequence: u32::from_be_bytes([data[0], data[1], data[2], data[3]]),
+            payload: data[4..].to_vec(),
+        })
+    }
+}
+
+/// Duplex session configuration
+#[derive(Debug, Clone)]
+pub struct DuplexConfig {
+    pub chunk_size: usize,
+    pub max_retransmissions: u32,  // Per chunk
+    pub ack_timeout_ms: u64,       // Resend unacknowledged chunks after this long without feedback
+    pub control_poll_interval_ms: u64,
+}
+
+impl Default for DuplexConfig {
+    fn default() -> Self {
+        Self {
+            chunk_size: 1024,
+            max_retransmissions: 5,
+            ack_timeout_ms: 500,
+            control_poll_interval_ms: 10,
+        }
+    }
+}
+
+/// Duplex session errors
+#[derive(Debug, thiserror::Error)]
+pub enum DuplexError {
+    #[error("Laser transmission error: {0}")]
+    LaserError(#[from] LaserError),
+    #[error("Laser data channel closed")]
+    DataChannelClosed,
+    #[error("Ultrasound control channel closed")]
+    ControlChannelClosed,
+    #[error("Chunk {0} exceeded retransmission limit")]
+    RetransmissionLimitExceeded(u32),
+    #[error("Invalid duplex frame")]
+    InvalidFrame,
+}
+
+/// Transfer statistics
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DuplexStats {
+    pub chunks_total: u32,
+    pub chunks_sent: u32,
+    pub retransmissions: u32,
+    pub acks_received: u32,
+    pub naks_received: u32,
+}
+
+/// Runs laser transmit and ultrasound control receive concurrently
+pub struct DuplexSession {
+    config: DuplexConfig,
+    stats: Arc<Mutex<DuplexStats>>,
+}
+
+impl DuplexSession {
+    /// Create a duplex session with default configuration
+    pub fn new() -> Self {
+        Self::with_config(DuplexConfig::default())
+    }
+
+    /// Create a duplex session with custom configuration
+    pub fn with_config(config: DuplexConfig) -> Self {
+        Self {
+            config,
+            stats: Arc::new(Mutex::new(DuplexStats::default())),
+        }
+    }
+
+    /// Transfer `data` over a laser data channel, driven by control feedback.
+    ///
+    /// Control frames are consumed on a separate task while chunks are sent, so a
+    /// NAK arriving mid-transfer requeues its chunk ahead of any timeout.
+    pub async fn run_transfer(
+        &self,
+        data: &[u8],
+        laser_tx: mpsc::Sender<DataChunk>,
+        mut control_rx: mpsc::Receiver<ControlMessage>,
+    ) -> Result<DuplexStats, DuplexError> {
+        let chunks: Vec<DataChunk> = data
+            .chunks(self.config.chunk_size.max(1))
+            .enumerate()
+            .map(|(i, chunk)| DataChunk { sequence: i as u32, payload: chunk.to_vec() })
+            .collect();
+
+        *self.stats.lock().await = DuplexStats {
+            chunks_total: chunks.len() as u32,
+            ..DuplexStats::default()
+        };
+
+        // Control receive task: forward ultrasound feedback into the ARQ loop
+        let (feedback_tx, mut feedback_rx) = mpsc::unbounded_channel();
+        let stats = self.stats.clone();
+        let control_task = tokio::spawn(async move {
+            while let Some(message) = control_rx.recv().await {
+                {
+                    let mut stats = stats.lock().await;
+                    match message {
+                        ControlMessage::Ack(_) => stats.acks_received += 1,
+                        ControlMessage::Nak(_) => stats.naks_received += 1,
+                    }
+                }
+                if feedback_tx.send(message).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let result = self.arq_loop(&chunks, &laser_tx, &mut feedback_rx).await;
+        control_task.abort();
+
+        result?;
+        Ok(self.stats.lock().await.clone())
+    }
+
+    /// Selective-repeat ARQ over the laser channel
+    async fn arq_loop(
+        &self,
+        chunks: &[DataChunk],
+        laser_tx: &mpsc::Sender<DataChunk>,
+        feedback_rx: &mut mpsc::UnboundedReceiver<ControlMessage>,
+    ) -> Result<(), DuplexError> {
+        let mut queue: VecDeque<u32> = (0..chunks.len() as u32).collect();
+        let mut sent: HashSet<u32> = HashSet::new();
+        let mut acked: HashSet<u32> = HashSet::new();
+        let mut attempts: HashMap<u32, u32> = HashMap::new();
+        let ack_timeout = Duration::from_millis(self.config.ack_timeout_ms);
+
+        while acked.len() < chunks.len() {
+            tokio::select! {
+                biased;
+
+                feedback = feedback_rx.recv() => match feedback {
+                    // Feedback only counts for chunks inside the window that went out
+                    Some(ControlMessage::Ack(seq)) => {
+                        if sent.contains(&seq) {
+                            acked.insert(seq);
+                        }
+                    }
+                    Some(ControlMessage::Nak(seq)) => {
+                        if sent.contains(&seq) && !acked.contains(&seq) && !queue.contains(&seq) {
+                            self.schedule_retransmission(seq, &mut attempts).await?;
+                            queue.push_front(seq);
+                        }
+                    }
+                    None => return Err(DuplexError::ControlChannelClosed),
+                },
+
+                permit = laser_tx.reserve(), if !queue.is_empty() => {
+                    let permit = permit.map_err(|_| DuplexError::DataChannelClosed)?;
+                    let seq = queue.pop_front().expect("queue checked non-empty");
+                    permit.send(chunks[seq as usize].clone());
+                    sent.insert(seq);
+                    self.stats.lock().await.chunks_sent += 1;
+                }
+
+                _ = tokio::time::sleep(ack_timeout), if queue.is_empty() => {
+                    // No feedback in time: resend everything still outstanding
+                    for seq in 0..chunks.len() as u32 {
+                        if !acked.contains(&seq) {
+                            self.schedule_retransmission(seq, &mut attempts).await?;
+                            queue.push_back(seq);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn schedule_retransmission(&self, seq: u32, attempts: &mut HashMap<u32, u32>) -> Result<(), DuplexError> {
+        let count = attempts.entry(seq).or_insert(0);
+        *count += 1;
+        if *count > self.config.max_retransmissions {
+            return Err(DuplexError::RetransmissionLimitExceeded(seq));
+        }
+        self.stats.lock().await.retransmissions += 1;
+        Ok(())
+    }
+
+    /// Transfer `data` using the laser engine for data and the ultrasonic beam for control
+    pub async fn run_with_engines(
+        &self,
+        data: &[u8],
+        laser: Arc<Mutex<LaserEngine>>,
+        beam: Arc<Mutex<UltrasonicBeamEngine>>,
+    ) -> Result<DuplexStats, DuplexError> {
+        let (laser_tx, mut laser_rx) = mpsc::channel::<DataChunk>(4);
+        let (control_tx, control_rx) = mpsc::channel::<ControlMessage>(16);
+
+        // Laser transmit task
+        let laser_task = tokio::spawn(async move {
+            while let Some(chunk) = laser_rx.recv().await {
+                laser.lock().await.transmit_data(&chunk.to_bytes()).await?;
+            }
+            Ok::<(), LaserError>(())
+        });
+
+        // Ultrasound control receive task
+        let poll_interval = Duration::from_millis(self.config.control_poll_interval_ms);
+        let control_task = tokio::spawn(async move {
+            loop {
+                if let Ok(signals) = beam.lock().await.receive_beam_signals().await {
+                    for signal in signals {
+                        if let BeamSignal::ControlData { data, .. } = signal.signal_type {
+                            if let Ok(message) = ControlMessage::from_bytes(&data) {
+                                if control_tx.send(message).await.is_err() {
+                                    return;
+                                }
+                            }
+                        }
+                    }
+                }
+                tokio::time::sleep(poll_interval).await;
+            }
+        });
+
+        let result = self.run_transfer(data, laser_tx, control_rx).await;
+        control_task.abort();
+
+        // Surface laser failures in preference to the resulting channel closure
+        match laser_task.await {
+            Ok(Err(e)) => Err(DuplexError::LaserError(e)),
+            _ => result,
+        }
+    }
+
+    /// Get statistics for the current or last transfer
+    pub async fn get_stats(&self) -> DuplexStats {
+        self.stats.lock().await.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_round_trip() {
+        let chunk = DataChunk { sequence: 7, payload: vec![1, 2, 3] };
+        assert_eq!(DataChunk::from_bytes(&chunk.to_bytes()).unwrap(), chunk);
+
+        for message in [ControlMessage::Ack(3), ControlMessage::Nak(u32::MAX)] {
+            assert_eq!(ControlMessage::from_bytes(&message.to_bytes()).unwrap(), message);
+        }
+        assert!(ControlMessage::from_bytes(&[0x09, 0, 0, 0, 1]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_nak_triggers_retransmission() {
+        let session = DuplexSession::with_config(DuplexConfig {
+            chunk_size: 16,
+            ..DuplexConfig::default()
+        });
+        let data: Vec<u8> = (0..80u8).collect();
+
+        let (laser_tx, mut laser_rx) = mpsc::channel::<DataChunk>(2);
+        let (control_tx, control_rx) = mpsc::channel::<ControlMessage>(8);
+
+        // Simulated peer: the first copy of chunk 2 arrives corrupted and is NAK'd
+        let peer = tokio::spawn(async move {
+            let mut received = Vec::new();
+            let mut chunks: HashMap<u32, Vec<u8>> = HashMap::new();
+            let mut corrupted_once = false;
+            while let Some(chunk) = laser_rx.recv().await {
+                received.push(chunk.sequence);
+                let reply = if chunk.sequence == 2 && !corrupted_once {
+                    corrupted_once = true;
+                    ControlMessage::Nak(2)
+                } else {
+                    chunks.insert(chunk.sequence, chunk.payload);
+                    ControlMessage::Ack(chunk.sequence)
+                };
+                if control_tx.send(reply).await.is_err() {
+                    break;
+                }
+            }
+            (received, chunks)
+        });
+
+        let stats = session.run_transfer(&data, laser_tx, control_rx).await.unwrap();
+        let (received, chunks) = peer.await.unwrap();
+
+        assert_eq!(stats.chunks_total, 5);
+        assert_eq!(stats.naks_received, 1);
+        assert_eq!(stats.retransmissions, 1);
+        assert_eq!(received.iter().filter(|&&seq| seq == 2).count(), 2);
+
+        let mut reassembled = Vec::new();
+        for seq in 0..5u32 {
+            reassembled.extend_from_slice(&chunks[&seq]);
+        }
+        assert_eq!(reassembled, data);
+    }
+
+    #[tokio::test]
+    async fn test_acks_outside_the_send_window_are_ignored() {
+        let session = DuplexSession::with_config(DuplexConfig {
+            chunk_size: 16,
+            ..DuplexConfig::default()
+        });
+        let data: Vec<u8> = (0..80u8).collect();
+
+        let (laser_tx, mut laser_rx) = mpsc::channel::<DataChunk>(1);
+        let (control_tx, control_rx) = mpsc::channel::<ControlMessage>(16);
+
+        // Peer answers the first chunk by ACKing every sequence, including ones
+        // that were never sent and one past the end of the transfer
+        let peer = tokio::spawn(async move {
+            let mut received = HashSet::new();
+            while let Some(chunk) = laser_rx.recv().await {
+                let replies: Vec<u32> = if received.is_empty() {
+                    (0..5).chain([1000]).collect()
+                } else {
+                    vec![chunk.sequence]
+                };
+                received.insert(chunk.sequence);
+                for seq in replies {
+                    if control_tx.send(ControlMessage::Ack(seq)).await.is_err() {
+                        return received;
+                    }
+                }
+            }
+            received
+        });
+
+        let stats = session.run_transfer(&data, laser_tx, control_rx).await.unwrap();
+        let received = peer.await.unwrap();
+
+        assert_eq!(stats.chunks_total, 5);
+        assert_eq!(received, (0..5u32).collect::<HashSet<_>>());
+    }
+}
@@ -7,11 +7,17 @@
 use crate::laser::{LaserEngine, LaserError, AlignmentStatus};
 use crate::ultrasonic_beam::{UltrasonicBeamEngine, UltrasonicBeamError};
 use crate::protocol::{ProtocolEngine, ProtocolState, ProtocolError, CommunicationMode};
-use crate::crypto::CryptoEngine;
+use crate::crypto::{CryptoEngine, SequencedChannelState};
+use crate::session_id::SessionId;
+use crate::channel_validator::ChannelType;
+use crate::audit::{AuditEventType, AuditSeverity, SharedAuditSystem, record_security_event};
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use std::time::SystemTime;
 use tokio::sync::Mutex;
 use tokio::time::{Duration, Instant};
 use std::collections::VecDeque;
+use zeroize::Zeroizing;
 
 /// Types of channel failures that can trigger fallback
 #[derive(Debug, Clone, PartialEq)]
@@ -45,6 +51,41 @@ pub struct FallbackStatus {
     pub recovery_attempts: u32,
     pub last_recovery_attempt: Option<Instant>,
     pub session_snapshot: Option<SessionSnapshot>,
+    /// What caused the most recent fallback
+    pub transition_reason: Option<TransitionReason>,
+    /// Since when the long-range channel has stayed above the recovery threshold
+    pub recovery_stable_since: Option<Instant>,
+    /// Fallbacks plus recoveries since the manager was created
+    pub mode_switch_count: u32,
+}
+
+/// Diagnosis of a fallback: what failed and the channel metrics at that moment
+#[derive(Debug, Clone)]
+pub struct TransitionReason {
+    /// Failure that triggered the transition
+    pub trigger: ChannelFailure,
+    /// Every failure condition the channel metrics met at the time, trigger included
+    pub failures: Vec<ChannelFailure>,
+    /// Channel metrics when the transition happened
+    pub health: ChannelHealth,
+    pub occurred_at: Instant,
+}
+
+impl std::fmt::Display for TransitionReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:?} (conditions {:?}): laser signal {:.2}, laser aligned {}, ultrasound signal {:.2}, \
+             ultrasound presence {}, health score {:.2}",
+            self.trigger,
+            self.failures,
+            self.health.laser_signal_strength,
+            self.health.laser_alignment_status,
+            self.health.ultrasound_signal_strength,
+            self.health.ultrasound_presence_detected,
+            self.health.overall_health_score
+        )
+    }
 }
 
 /// Channel health metrics
@@ -58,6 +99,48 @@ pub struct ChannelHealth {
     pub last_update: Instant,
 }
 
+/// How the dual-channel transmitter chooses its primary path and redundancy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PathPolicy {
+    #[default]
+    MaxReliability,   // Send on every usable channel, strongest first
+    MinDetectability, // Only the usable channel least likely to be observed
+    MinPower,         // Only the usable channel with the lowest transmit power
+}
+
+/// Observability and cost of each channel in the current environment
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PathConditions {
+    pub laser_visibility: f32,          // 0.0-1.0 chance the beam is seen (haze scatter, observers)
+    pub ultrasound_detectability: f32,  // 0.0-1.0 chance the beam is picked up
+    pub laser_power_mw: f32,
+    pub ultrasound_power_mw: f32,
+}
+
+impl Default for PathConditions {
+    fn default() -> Self {
+        Self {
+            laser_visibility: 0.2,
+            ultrasound_detectability: 0.3,
+            laser_power_mw: 5.0,
+            ultrasound_power_mw: 50.0,
+        }
+    }
+}
+
+/// Channels selected for the next transmission
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathSelection {
+    pub primary: ChannelType,
+    pub redundant: Option<ChannelType>,
+}
+
+impl PathSelection {
+    pub fn uses(&self, channel: &ChannelType) -> bool {
+        &self.primary == channel || self.redundant.as_ref() == Some(channel)
+    }
+}
+
 /// Fallback configuration
 #[derive(Debug, Clone)]
 pub struct FallbackConfig {
@@ -69,6 +152,12 @@ pub struct FallbackConfig {
     pub graceful_degradation_timeout_ms: u64,
     pub session_preservation_enabled: bool,
     pub user_notifications_enabled: bool,
+    pub path_policy: PathPolicy,
+    /// Margin above `failure_threshold` the health score must regain before
+    /// recovery, treating the score as a linear power ratio
+    pub recovery_hysteresis_db: f32,
+    /// How long health must stay above the recovery threshold before switching back
+    pub min_stable_duration_secs: f32,
 }
 
 impl Default for FallbackConfig {
@@ -82,10 +171,21 @@ impl Default for FallbackConfig {
             graceful_degradation_timeout_ms: 2000, // 2 seconds
             session_preservation_enabled: true,
             user_notifications_enabled: true,
+            path_policy: PathPolicy::MaxReliability,
+            recovery_hysteresis_db: 3.7,    // 0.3 -> ~0.7 health to recover
+            min_stable_duration_secs: 10.0,
         }
     }
 }
 
+impl FallbackConfig {
+    /// Health score the long-range channel must hold before recovery:
+    /// `failure_threshold` raised by `recovery_hysteresis_db`
+    pub fn recovery_threshold(&self) -> f32 {
+        self.failure_threshold * 10f32.powf(self.recovery_hysteresis_db / 10.0)
+    }
+}
+
 /// Comprehensive fallback errors
 #[derive(Debug, thiserror::Error)]
 pub enum FallbackError {
@@ -103,18 +203,144 @@ pub enum FallbackError {
     MaxRecoveryAttemptsExceeded,
     #[error("Invalid fallback state transition")]
     InvalidStateTransition,
+    #[error("Session snapshot encoding failed: {0}")]
+    SnapshotEncodingFailed(String),
+    #[error("Session snapshot is malformed or sealed under a different key")]
+    SnapshotRejected,
+    #[error("No usable transmit path")]
+    NoUsablePath,
+    #[error("Transmission on {0:?} failed: {1}")]
+    TransmissionFailed(ChannelType, String),
 }
 
+/// Format version of `SessionSnapshot::to_bytes` output
+const SESSION_SNAPSHOT_VERSION: u8 = 2;
+
 /// Session state snapshot for preservation during fallback
 #[derive(Debug, Clone)]
 pub struct SessionSnapshot {
-    pub session_id: [u8; 16],
+    pub session_id: SessionId,
     pub shared_secret: Option<[u8; 32]>,
     pub peer_public_key: Option<Vec<u8>>,
     pub protocol_state: ProtocolState,
     pub communication_mode: CommunicationMode,
     pub crypto_state: Vec<u8>, // Serialized crypto state
     pub timestamp: Instant,
+    /// Last keepalive sequence numbers sent and accepted from the peer
+    pub keepalive_sequence: u64,
+    pub peer_keepalive_sequence: u64,
+    /// When the session key was established, so a restore cannot extend its lifetime
+    pub session_started_at: Option<SystemTime>,
+    /// Message channel counters, so messages opened before the snapshot stay replays
+    pub message_channel: Option<SequencedChannelState>,
+}
+
+/// Session parameters carried in `SessionSnapshot::crypto_state`
+#[derive(Serialize, Deserialize)]
+struct CryptoStateSnapshot {
+    session_id: SessionId,
+    shared_secret: Option<[u8; 32]>,
+    peer_public_key: Option<Vec<u8>>,
+    protocol_state: ProtocolState,
+    communication_mode: CommunicationMode,
+}
+
+/// What `SessionSnapshot::to_bytes` persists, before sealing
+#[derive(Serialize, Deserialize)]
+struct PersistedSessionSnapshot {
+    session_id: SessionId,
+    shared_secret: Option<[u8; 32]>,
+    peer_public_key: Option<Vec<u8>>,
+    protocol_state: ProtocolState,
+    communication_mode: CommunicationMode,
+    keepalive_sequence: u64,
+    peer_keepalive_sequence: u64,
+    session_started_at: Option<SystemTime>,
+    message_channel: Option<SequencedChannelState>,
+}
+
+impl SessionSnapshot {
+    /// Snapshot the session `protocol` currently holds
+    pub async fn capture(protocol: &ProtocolEngine) -> Self {
+        let (keepalive_sequence, peer_keepalive_sequence) = protocol.get_keepalive_sequences();
+        Self {
+            session_id: *protocol.get_session_id(),
+            shared_secret: protocol.get_shared_secret().copied(),
+            peer_public_key: protocol.get_peer_public_key().cloned(),
+            protocol_state: protocol.get_state().await,
+            communication_mode: protocol.get_mode().clone(),
+            crypto_state: FallbackManager::serialize_crypto_state(protocol).await,
+            timestamp: Instant::now(),
+            keepalive_sequence,
+            peer_keepalive_sequence,
+            session_started_at: protocol.get_session_started_at(),
+            message_channel: protocol.get_message_channel_state(),
+        }
+    }
+
+    /// Persist the snapshot as `version || AES-256-GCM(CBOR)` under `storage_key`.
+    ///
+    /// Everything is sealed, the shared secret included, so the blob can sit in
+    /// app storage; keep `storage_key` in the platform keystore, not beside it.
+    pub fn to_bytes(&self, storage_key: &[u8; 32]) -> Result<Vec<u8>, FallbackError> {
+        let persisted = PersistedSessionSnapshot {
+            session_id: self.session_id,
+            shared_secret: self.shared_secret,
+            peer_public_key: self.peer_public_key.clone(),
+            protocol_state: self.protocol_state.clone(),
+            communication_mode: self.communication_mode.clone(),
+            keepalive_sequence: self.keepalive_sequence,
+            peer_keepalive_sequence: self.peer_keepalive_sequence,
+            session_started_at: self.session_started_at,
+            message_channel: self.message_channel,
+        };
+        let encoded = Zeroizing::new(
+            serde_cbor::to_vec(&persisted).map_err(|e| FallbackError::SnapshotEncodingFailed(e.to_string()))?,
+        );
+        let sealed = CryptoEngine::encrypt_aes_gcm(storage_key, &encoded)
+            .map_err(|e| FallbackError::SnapshotEncodingFailed(e.to_string()))?;
+
+        let mut bytes = Vec::with_capacity(1 + sealed.len());
+        bytes.push(SESSION_SNAPSHOT_VERSION);
+        bytes.extend_from_slice(&sealed);
+        Ok(bytes)
+    }
+
+    /// Load a snapshot written by `to_bytes`. A wrong key, a tampered blob or an
+    /// unknown version is `SnapshotRejected`; `timestamp` is the load time.
+    pub fn from_bytes(bytes: &[u8], storage_key: &[u8; 32]) -> Result<Self, FallbackError> {
+        let (&version, sealed) = bytes.split_first().ok_or(FallbackError::SnapshotRejected)?;
+        if version != SESSION_SNAPSHOT_VERSION {
+            return Err(FallbackError::SnapshotRejected);
+        }
+        let encoded = Zeroizing::new(
+            CryptoEngine::decrypt_aes_gcm(storage_key, sealed).map_err(|_| FallbackError::SnapshotRejected)?,
+        );
+        let persisted: PersistedSessionSnapshot =
+            serde_cbor::from_slice(&encoded).map_err(|_| FallbackError::SnapshotRejected)?;
+
+        let crypto_state = serde_cbor::to_vec(&CryptoStateSnapshot {
+            session_id: persisted.session_id,
+            shared_secret: persisted.shared_secret,
+            peer_public_key: persisted.peer_public_key.clone(),
+            protocol_state: persisted.protocol_state.clone(),
+            communication_mode: persisted.communication_mode.clone(),
+        }).map_err(|e| FallbackError::SnapshotEncodingFailed(e.to_string()))?;
+
+        Ok(Self {
+            session_id: persisted.session_id,
+            shared_secret: persisted.shared_secret,
+            peer_public_key: persisted.peer_public_key,
+            protocol_state: persisted.protocol_state,
+            communication_mode: persisted.communication_mode,
+            crypto_state,
+            timestamp: Instant::now(),
+            keepalive_sequence: persisted.keepalive_sequence,
+            peer_keepalive_sequence: persisted.peer_keepalive_sequence,
+            session_started_at: persisted.session_started_at,
+            message_channel: persisted.message_channel,
+        })
+    }
 }
 
 /// Fallback manager for automatic channel switching
@@ -129,6 +355,7 @@ pub struct FallbackManager {
     failure_history: Arc<Mutex<VecDeque<(ChannelFailure, Instant)>>>,
     recovery_task_handle: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
     health_monitor_handle: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
+    audit_system: Option<SharedAuditSystem>,
 }
 
 impl FallbackManager {
@@ -162,14 +389,23 @@ impl FallbackManager {
                 recovery_attempts: 0,
                 last_recovery_attempt: None,
                 session_snapshot: None,
+                transition_reason: None,
+                recovery_stable_since: None,
+                mode_switch_count: 0,
             })),
             session_snapshot: Arc::new(Mutex::new(None)),
             failure_history: Arc::new(Mutex::new(VecDeque::with_capacity(10))),
             recovery_task_handle: Arc::new(Mutex::new(None)),
             health_monitor_handle: Arc::new(Mutex::new(None)),
+            audit_system: None,
         }
     }
 
+    /// Record channel failures that trigger a fallback in a shared audit trail
+    pub fn set_audit_system(&mut self, audit: SharedAuditSystem) {
+        self.audit_system = Some(audit);
+    }
+
     /// Initialize fallback manager with channel engines
     pub fn initialize_engines(
         &mut self,
@@ -223,6 +459,7 @@ impl FallbackManager {
         let ultrasound_engine = self.ultrasound_engine.clone();
         let protocol_engine = Arc::clone(&self.protocol_engine);
         let failure_history = Arc::clone(&self.failure_history);
+        let audit_system = self.audit_system.clone();
 
         let handle = tokio::spawn(async move {
             let mut interval = tokio::time::interval(Duration::from_millis(config.health_check_interval_ms));
@@ -239,39 +476,22 @@ impl FallbackManager {
 
                 match health_result {
                     Ok(health) => {
-                        *health_arc.lock().await = health.clone();
-
-                        // Check if fallback is needed
-                        if health.overall_health_score < config.failure_threshold {
-                            let failure_reason = Self::determine_failure_reason(&health);
-                            if let Some(reason) = failure_reason {
-                                // Record failure
-                                let mut history = failure_history.lock().await;
-                                history.push_back((reason.clone(), Instant::now()));
-                                if history.len() > 10 {
-                                    history.pop_front();
-                                }
-
-                                // Trigger fallback if not already active
-                                let mut status = fallback_status_arc.lock().await;
-                                if !status.active && config.mode == FallbackMode::Automatic {
-                                    drop(status);
-                                    if let Err(e) = Self::trigger_fallback(
-                                        &protocol_engine,
-                                        reason,
-                                        &config,
-                                        &fallback_status_arc,
-                                        &laser_engine,
-                                        &ultrasound_engine,
-                                    ).await {
-                                        eprintln!("Fallback trigger failed: {:?}", e);
-                                    }
-                                }
-                            }
+                        if let Err(_e) = Self::process_health(
+                            health,
+                            &health_arc,
+                            &failure_history,
+                            &protocol_engine,
+                            &config,
+                            &fallback_status_arc,
+                            &laser_engine,
+                            &ultrasound_engine,
+                            &audit_system,
+                        ).await {
+                            trace_error!(error = %_e, "fallback trigger failed");
                         }
                     }
-                    Err(e) => {
-                        eprintln!("Health assessment failed: {:?}", e);
+                    Err(_e) => {
+                        trace_warn!(error = %_e, "channel health assessment failed");
                     }
                 }
             }
@@ -281,6 +501,63 @@ impl FallbackManager {
         Ok(())
     }
 
+    /// Record a health assessment and fall back automatically when it shows a failure
+    #[allow(clippy::too_many_arguments)]
+    async fn process_health(
+        health: ChannelHealth,
+        current_health: &Arc<Mutex<ChannelHealth>>,
+        failure_history: &Arc<Mutex<VecDeque<(ChannelFailure, Instant)>>>,
+        protocol_engine: &Arc<Mutex<ProtocolEngine>>,
+        config: &FallbackConfig,
+        fallback_status: &Arc<Mutex<FallbackStatus>>,
+        laser_engine: &Option<Arc<Mutex<LaserEngine>>>,
+        ultrasound_engine: &Option<Arc<Mutex<UltrasonicBeamEngine>>>,
+        audit_system: &Option<SharedAuditSystem>,
+    ) -> Result<Option<ChannelFailure>, FallbackError> {
+        *current_health.lock().await = health.clone();
+
+        // While fallen back, good health counts towards recovery
+        if fallback_status.lock().await.active {
+            Self::observe_recovery_health(protocol_engine, config, fallback_status, &health).await?;
+        }
+
+        // Check if fallback is needed
+        if health.overall_health_score >= config.failure_threshold {
+            return Ok(None);
+        }
+        let reason = match Self::determine_failure_reason(&health) {
+            Some(reason) => reason,
+            None => return Ok(None),
+        };
+
+        // Record failure
+        {
+            let mut history = failure_history.lock().await;
+            history.push_back((reason.clone(), Instant::now()));
+            if history.len() > 10 {
+                history.pop_front();
+            }
+        }
+
+        // Trigger fallback if not already active
+        let active = fallback_status.lock().await.active;
+        if active || config.mode != FallbackMode::Automatic {
+            return Ok(None);
+        }
+
+        Self::trigger_fallback(
+            protocol_engine,
+            reason.clone(),
+            health,
+            config,
+            fallback_status,
+            laser_engine,
+            ultrasound_engine,
+            audit_system,
+        ).await?;
+        Ok(Some(reason))
+    }
+
     /// Assess current channel health
     async fn assess_channel_health(
         laser_engine: &Option<Arc<Mutex<LaserEngine>>>,
@@ -382,6 +659,27 @@ impl FallbackManager {
         Ok(health)
     }
 
+    /// Every failure condition `health` meets, most severe first
+    fn contributing_failures(health: &ChannelHealth) -> Vec<ChannelFailure> {
+        let mut failures = Vec::new();
+        if health.laser_signal_strength < 0.3 && !health.laser_alignment_status {
+            failures.push(ChannelFailure::LaserAlignmentLost);
+        }
+        if health.laser_signal_strength < 0.2 {
+            failures.push(ChannelFailure::LaserBlocked);
+        }
+        if health.ultrasound_signal_strength < 0.3 && !health.ultrasound_presence_detected {
+            failures.push(ChannelFailure::UltrasoundObstructed);
+        }
+        if health.ultrasound_signal_strength < 0.2 {
+            failures.push(ChannelFailure::UltrasoundInterference);
+        }
+        if health.overall_health_score < 0.4 {
+            failures.push(ChannelFailure::EnvironmentalConditions);
+        }
+        failures
+    }
+
     /// Determine the primary failure reason from health assessment
     fn determine_failure_reason(health: &ChannelHealth) -> Option<ChannelFailure> {
         // Prioritize failures by severity
@@ -401,14 +699,34 @@ impl FallbackManager {
     }
 
     /// Trigger fallback to short-range mode
+    #[allow(clippy::too_many_arguments)]
     async fn trigger_fallback(
         protocol_engine: &Arc<Mutex<ProtocolEngine>>,
         failure_reason: ChannelFailure,
+        health: ChannelHealth,
         config: &FallbackConfig,
         fallback_status: &Arc<Mutex<FallbackStatus>>,
         laser_engine: &Option<Arc<Mutex<LaserEngine>>>,
         ultrasound_engine: &Option<Arc<Mutex<UltrasonicBeamEngine>>>,
+        audit_system: &Option<SharedAuditSystem>,
     ) -> Result<(), FallbackError> {
+        let mut failures = Self::contributing_failures(&health);
+        if !failures.contains(&failure_reason) {
+            failures.insert(0, failure_reason.clone());
+        }
+        let transition_reason = TransitionReason {
+            trigger: failure_reason.clone(),
+            failures,
+            health,
+            occurred_at: Instant::now(),
+        };
+        trace_warn!(reason = %transition_reason, "falling back to short-range mode");
+
+        if let Some(audit) = audit_system {
+            record_security_event(audit, AuditEventType::SystemHealthEvent, AuditSeverity::Medium, "FallbackManager",
+                "trigger_fallback", Some(transition_reason.to_string())).await;
+        }
+
         // Preserve session state before fallback
         Self::preserve_session_state(protocol_engine, fallback_status).await?;
 
@@ -425,8 +743,11 @@ impl FallbackManager {
             status.active = true;
             status.current_mode = CommunicationMode::ShortRange;
             status.failure_reason = Some(failure_reason.clone());
-            status.fallback_time = Some(Instant::now());
+            status.fallback_time = Some(transition_reason.occurred_at);
+            status.transition_reason = Some(transition_reason);
             status.recovery_attempts = 0;
+            status.recovery_stable_since = None;
+            status.mode_switch_count += 1;
         }
 
         // Send user notification if enabled
@@ -445,20 +766,7 @@ impl FallbackManager {
         protocol_engine: &Arc<Mutex<ProtocolEngine>>,
         fallback_status: &Arc<Mutex<FallbackStatus>>,
     ) -> Result<(), FallbackError> {
-        let protocol = protocol_engine.lock().await;
-
-        // Serialize crypto state for preservation
-        let crypto_state = Self::serialize_crypto_state(&protocol).await;
-
-        let snapshot = SessionSnapshot {
-            session_id: *protocol.get_session_id(),
-            shared_secret: protocol.get_shared_secret().copied(),
-            peer_public_key: protocol.get_peer_public_key().cloned(),
-            protocol_state: protocol.get_state().await,
-            communication_mode: protocol.get_mode().clone(),
-            crypto_state,
-            timestamp: Instant::now(),
-        };
+        let snapshot = SessionSnapshot::capture(&*protocol_engine.lock().await).await;
 
         {
             let mut status = fallback_status.lock().await;
@@ -469,17 +777,6 @@ impl FallbackManager {
 
     /// Serialize crypto state for session preservation
     async fn serialize_crypto_state(protocol: &ProtocolEngine) -> Vec<u8> {
-        use serde::Serialize;
-
-        #[derive(Serialize)]
-        struct CryptoStateSnapshot {
-            session_id: [u8; 16],
-            shared_secret: Option<[u8; 32]>,
-            peer_public_key: Option<Vec<u8>>,
-            protocol_state: crate::protocol::ProtocolState,
-            communication_mode: crate::protocol::CommunicationMode,
-        }
-
         let snapshot = CryptoStateSnapshot {
             session_id: *protocol.get_session_id(),
             shared_secret: protocol.get_shared_secret().copied(),
@@ -497,23 +794,12 @@ impl FallbackManager {
         protocol_engine: &Arc<Mutex<ProtocolEngine>>,
         snapshot: &SessionSnapshot,
     ) -> Result<(), FallbackError> {
-        use serde::Deserialize;
-
-        #[derive(Deserialize)]
-        struct CryptoStateSnapshot {
-            session_id: [u8; 16],
-            shared_secret: Option<[u8; 32]>,
-            peer_public_key: Option<Vec<u8>>,
-            protocol_state: crate::protocol::ProtocolState,
-            communication_mode: crate::protocol::CommunicationMode,
-        }
-
         // Deserialize crypto state
         if let Ok(state) = serde_cbor::from_slice::<CryptoStateSnapshot>(&snapshot.crypto_state) {
             let mut protocol = protocol_engine.lock().await;
 
             // Restore session parameters using setter methods
-            protocol.set_session_id(state.session_id);
+            protocol.set_session_id(state.session_id).await;
             protocol.set_shared_secret(state.shared_secret);
             protocol.set_peer_public_key(state.peer_public_key);
             protocol.set_state(state.protocol_state).await;
@@ -524,6 +810,7 @@ impl FallbackManager {
     }
 
     /// Send user notification about fallback event
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
     async fn send_fallback_notification(failure_reason: &ChannelFailure) {
         // In a real implementation, this would send notifications through
         // the application's notification system (Android notifications, UI updates, etc.)
@@ -535,7 +822,7 @@ impl FallbackManager {
             _ => "Communication channel failure - switched to short-range mode",
         };
 
-        println!("FALLBACK NOTIFICATION: {}", message);
+        trace_warn!(reason = ?failure_reason, "{}", message);
         // TODO: Integrate with actual notification system
     }
 
@@ -548,8 +835,8 @@ impl FallbackManager {
         let ultrasound_engine = self.ultrasound_engine.clone();
 
         let handle = tokio::spawn(async move {
-            Self::start_recovery_monitoring_internal(&protocol_engine, &config, &fallback_status, &laser_engine, &ultrasound_engine).await.unwrap_or_else(|e| {
-                eprintln!("Recovery monitoring failed to start: {:?}", e);
+            Self::start_recovery_monitoring_internal(&protocol_engine, &config, &fallback_status, &laser_engine, &ultrasound_engine).await.unwrap_or_else(|_e| {
+                trace_error!(error = %_e, "recovery monitoring failed to start");
             });
         });
 
@@ -577,6 +864,7 @@ impl FallbackManager {
 
             status.recovery_attempts += 1;
             status.last_recovery_attempt = Some(Instant::now());
+            drop(status);
 
             // Attempt to assess if long-range channels are now healthy
             let health_result = Self::assess_channel_health(
@@ -586,13 +874,11 @@ impl FallbackManager {
             ).await;
 
             if let Ok(health) = health_result {
-                if health.overall_health_score >= 0.7 { // Recovery threshold
-                    // Attempt to restore long-range mode
-                    drop(status);
-                    if let Err(e) = Self::attempt_recovery(protocol_engine, config, fallback_status).await {
-                        eprintln!("Recovery attempt failed: {:?}", e);
-                    } else {
-                        break; // Recovery successful
+                match Self::observe_recovery_health(protocol_engine, config, fallback_status, &health).await {
+                    Ok(true) => break, // Recovery successful
+                    Ok(false) => {}
+                    Err(_e) => {
+                        trace_warn!(error = %_e, "recovery attempt failed");
                     }
                 }
             }
@@ -601,6 +887,35 @@ impl FallbackManager {
         Ok(())
     }
 
+    /// Track how long health has stayed above the recovery threshold and recover
+    /// once it has held for `min_stable_duration_secs`; any dip restarts the
+    /// clock. Returns whether long-range mode was restored.
+    async fn observe_recovery_health(
+        protocol_engine: &Arc<Mutex<ProtocolEngine>>,
+        config: &FallbackConfig,
+        fallback_status: &Arc<Mutex<FallbackStatus>>,
+        health: &ChannelHealth,
+    ) -> Result<bool, FallbackError> {
+        {
+            let mut status = fallback_status.lock().await;
+            if !status.active {
+                return Ok(false);
+            }
+            if health.overall_health_score < config.recovery_threshold() {
+                status.recovery_stable_since = None;
+                return Ok(false);
+            }
+            let since = *status.recovery_stable_since.get_or_insert(health.last_update);
+            let stable_for = health.last_update.saturating_duration_since(since);
+            if stable_for < Duration::from_secs_f32(config.min_stable_duration_secs.max(0.0)) {
+                return Ok(false);
+            }
+        }
+
+        Self::attempt_recovery(protocol_engine, config, fallback_status).await?;
+        Ok(true)
+    }
+
     /// Attempt to recover to long-range mode
     async fn attempt_recovery(
         protocol_engine: &Arc<Mutex<ProtocolEngine>>,
@@ -629,11 +944,13 @@ impl FallbackManager {
             status.current_mode = CommunicationMode::LongRange;
             status.failure_reason = None;
             status.fallback_time = None;
+            status.recovery_stable_since = None;
+            status.mode_switch_count += 1;
         }
 
         // Send recovery notification
         if config.user_notifications_enabled {
-            println!("RECOVERY NOTIFICATION: Restored long-range communication");
+            trace_info!("restored long-range communication");
         }
 
         Ok(())
@@ -655,16 +972,46 @@ impl FallbackManager {
             return Err(FallbackError::FallbackDisabled);
         }
 
+        let health = self.current_health.lock().await.clone();
         Self::trigger_fallback(
             &self.protocol_engine,
             reason,
+            health,
             &self.config,
             &self.fallback_status,
             &self.laser_engine,
             &self.ultrasound_engine,
+            &self.audit_system,
         ).await
     }
 
+    /// Feed a health assessment from an external monitor; falls back like the
+    /// built-in monitor would and returns the triggering failure, if any
+    pub async fn report_channel_health(&self, health: ChannelHealth) -> Result<Option<ChannelFailure>, FallbackError> {
+        Self::process_health(
+            health,
+            &self.current_health,
+            &self.failure_history,
+            &self.protocol_engine,
+            &self.config,
+            &self.fallback_status,
+            &self.laser_engine,
+            &self.ultrasound_engine,
+            &self.audit_system,
+        ).await
+    }
+
+    /// Why the most recent fallback happened
+    pub async fn last_transition_reason(&self) -> Option<TransitionReason> {
+        self.fallback_status.lock().await.transition_reason.clone()
+    }
+
+    /// Fallbacks plus recoveries so far; a count that keeps climbing means the
+    /// link is flapping around the thresholds
+    pub async fn mode_switch_count(&self) -> u32 {
+        self.fallback_status.lock().await.mode_switch_count
+    }
+
     /// Get failure history
     pub async fn get_failure_history(&self) -> Vec<(ChannelFailure, Instant)> {
         self.failure_history.lock().await.iter().cloned().collect()
@@ -679,6 +1026,67 @@ impl FallbackManager {
     pub async fn is_fallback_active(&self) -> bool {
         self.fallback_status.lock().await.active
     }
+
+    /// Choose transmit paths from current channel health under the configured policy
+    pub async fn select_transmit_paths(&self, conditions: &PathConditions) -> Option<PathSelection> {
+        let health = self.current_health.lock().await.clone();
+        Self::select_paths(self.config.path_policy, &health, conditions, self.config.failure_threshold)
+    }
+
+    /// Send `data` on the paths `select_transmit_paths` picks, primary first; every
+    /// selected path must accept it. Ultrasound carries at most 32 bytes.
+    pub async fn transmit(&self, data: &[u8], conditions: &PathConditions) -> Result<PathSelection, FallbackError> {
+        let selection = self.select_transmit_paths(conditions).await.ok_or(FallbackError::NoUsablePath)?;
+        for channel in std::iter::once(&selection.primary).chain(selection.redundant.as_ref()) {
+            self.transmit_on(channel, data).await
+                .map_err(|e| FallbackError::TransmissionFailed(channel.clone(), e))?;
+        }
+        Ok(selection)
+    }
+
+    async fn transmit_on(&self, channel: &ChannelType, data: &[u8]) -> Result<(), String> {
+        match channel {
+            ChannelType::Laser => match &self.laser_engine {
+                Some(laser) => laser.lock().await.transmit_data(data).await.map_err(|e| e.to_string()),
+                None => Err("no laser engine".to_string()),
+            },
+            ChannelType::Ultrasound => match &self.ultrasound_engine {
+                Some(ultrasound) => ultrasound.lock().await.transmit_control_data(data, 1).await.map_err(|e| e.to_string()),
+                None => Err("no ultrasound engine".to_string()),
+            },
+        }
+    }
+
+    /// Pick the primary and redundant channels among those with signal above `min_signal`
+    pub fn select_paths(
+        policy: PathPolicy,
+        health: &ChannelHealth,
+        conditions: &PathConditions,
+        min_signal: f32,
+    ) -> Option<PathSelection> {
+        // (channel, signal strength, detectability, power)
+        let mut usable = Vec::with_capacity(2);
+        if health.laser_alignment_status && health.laser_signal_strength >= min_signal {
+            usable.push((ChannelType::Laser, health.laser_signal_strength, conditions.laser_visibility, conditions.laser_power_mw));
+        }
+        if health.ultrasound_presence_detected && health.ultrasound_signal_strength >= min_signal {
+            usable.push((ChannelType::Ultrasound, health.ultrasound_signal_strength, conditions.ultrasound_detectability, conditions.ultrasound_power_mw));
+        }
+
+        match policy {
+            PathPolicy::MaxReliability => {
+                usable.sort_by(|a, b| b.1.total_cmp(&a.1));
+                let mut channels = usable.into_iter().map(|(channel, ..)| channel);
+                channels.next().map(|primary| PathSelection { primary, redundant: channels.next() })
+            }
+            PathPolicy::MinDetectability => usable.into_iter()
+                .min_by(|a, b| a.2.total_cmp(&b.2))
+                .map(|(primary, ..)| PathSelection { primary, redundant: None }),
+            PathPolicy::MinPower => usable.into_iter()
+                .min_by(|a, b| a.3.total_cmp(&b.3))
+                .map(|(primary, ..)| PathSelection { primary, redundant: None }),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -723,4 +1131,170 @@ mod tests {
         let reason = FallbackManager::determine_failure_reason(&health);
         assert_eq!(reason, Some(ChannelFailure::LaserAlignmentLost));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_path_policy_selection() {
+        let health = ChannelHealth {
+            laser_signal_strength: 0.9,
+            laser_alignment_status: true,
+            ultrasound_signal_strength: 0.7,
+            ultrasound_presence_detected: true,
+            overall_health_score: 0.8,
+            last_update: Instant::now(),
+        };
+        // Clear night air with observers: the beam is easy to spot
+        let observed = PathConditions { laser_visibility: 0.9, ultrasound_detectability: 0.2, ..Default::default() };
+
+        let stealthy = FallbackManager::select_paths(PathPolicy::MinDetectability, &health, &observed, 0.3).unwrap();
+        assert_eq!(stealthy, PathSelection { primary: ChannelType::Ultrasound, redundant: None });
+        assert!(!stealthy.uses(&ChannelType::Laser));
+
+        let reliable = FallbackManager::select_paths(PathPolicy::MaxReliability, &health, &observed, 0.3).unwrap();
+        assert_eq!(reliable, PathSelection { primary: ChannelType::Laser, redundant: Some(ChannelType::Ultrasound) });
+        assert!(reliable.uses(&ChannelType::Laser) && reliable.uses(&ChannelType::Ultrasound));
+
+        let frugal = FallbackManager::select_paths(PathPolicy::MinPower, &health, &observed, 0.3).unwrap();
+        assert_eq!(frugal.primary, ChannelType::Laser);
+
+        // With ultrasound unusable the laser is the only option left
+        let deaf = ChannelHealth { ultrasound_presence_detected: false, ..health.clone() };
+        let fallback = FallbackManager::select_paths(PathPolicy::MinDetectability, &deaf, &observed, 0.3).unwrap();
+        assert_eq!(fallback.primary, ChannelType::Laser);
+        let dead = ChannelHealth { laser_alignment_status: false, ..deaf };
+        assert!(FallbackManager::select_paths(PathPolicy::MaxReliability, &dead, &observed, 0.3).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_transmit_follows_the_path_policy() {
+        // The laser is never powered up, so any attempt to use it fails
+        let laser = Arc::new(Mutex::new(LaserEngine::new(crate::laser::LaserConfig::default(), crate::laser::ReceptionConfig::default())));
+        let mut ultrasound = UltrasonicBeamEngine::new();
+        ultrasound.initialize().await.unwrap();
+        let observed = PathConditions { laser_visibility: 0.9, ultrasound_detectability: 0.2, ..Default::default() };
+
+        let config = FallbackConfig { path_policy: PathPolicy::MinDetectability, ..Default::default() };
+        let mut manager = FallbackManager::with_config(config.clone(), Arc::new(Mutex::new(ProtocolEngine::new())));
+        manager.initialize_engines(Some(laser), Some(Arc::new(Mutex::new(ultrasound))));
+        let sent = manager.transmit(b"stay quiet", &observed).await.unwrap();
+        assert_eq!(sent, PathSelection { primary: ChannelType::Ultrasound, redundant: None });
+
+        manager.update_config(FallbackConfig { path_policy: PathPolicy::MaxReliability, ..config });
+        assert!(matches!(
+            manager.transmit(b"stay quiet", &observed).await,
+            Err(FallbackError::TransmissionFailed(ChannelType::Laser, _))
+        ));
+
+        let unequipped = FallbackManager::new(Arc::new(Mutex::new(ProtocolEngine::new())));
+        assert!(matches!(
+            unequipped.transmit(b"x", &observed).await,
+            Err(FallbackError::TransmissionFailed(ChannelType::Laser, _))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_transition_reason_reports_alignment_loss() {
+        let protocol_engine = Arc::new(Mutex::new(ProtocolEngine::new()));
+        let manager = FallbackManager::new(protocol_engine);
+        assert!(manager.last_transition_reason().await.is_none());
+
+        // Laser diagnostics reported AlignmentLost: signal collapsed and the beam is off target
+        let health = ChannelHealth {
+            laser_signal_strength: 0.12,
+            laser_alignment_status: false,
+            ultrasound_signal_strength: 0.8,
+            ultrasound_presence_detected: true,
+            overall_health_score: 0.25,
+            last_update: Instant::now(),
+        };
+        let trigger = manager.report_channel_health(health).await.unwrap();
+        assert_eq!(trigger, Some(ChannelFailure::LaserAlignmentLost));
+
+        let reason = manager.last_transition_reason().await.expect("fallback records its reason");
+        assert_eq!(reason.trigger, ChannelFailure::LaserAlignmentLost);
+        assert_eq!(reason.failures[0], ChannelFailure::LaserAlignmentLost);
+        assert!(reason.failures.contains(&ChannelFailure::LaserBlocked));
+        assert!(!reason.failures.contains(&ChannelFailure::UltrasoundObstructed));
+        assert_eq!(reason.health.laser_signal_strength, 0.12);
+        assert!(!reason.health.laser_alignment_status);
+        assert_eq!(reason.health.overall_health_score, 0.25);
+        assert!(reason.to_string().contains("LaserAlignmentLost"));
+
+        let status = manager.get_fallback_status().await;
+        assert!(status.active);
+        assert_eq!(status.fallback_time, Some(reason.occurred_at));
+        assert_eq!(status.transition_reason.unwrap().trigger, ChannelFailure::LaserAlignmentLost);
+    }
+
+    #[tokio::test]
+    async fn test_recovery_requires_stable_margin() {
+        let protocol_engine = Arc::new(Mutex::new(ProtocolEngine::new()));
+        let config = FallbackConfig {
+            failure_threshold: 0.3,
+            recovery_hysteresis_db: 3.0,
+            min_stable_duration_secs: 0.2,
+            max_recovery_attempts: 0,
+            ..FallbackConfig::default()
+        };
+        assert!((config.recovery_threshold() - 0.6).abs() < 0.01);
+        let manager = FallbackManager::with_config(config, protocol_engine);
+        let health = |score: f32| ChannelHealth {
+            laser_signal_strength: score,
+            laser_alignment_status: score >= 0.3,
+            ultrasound_signal_strength: 0.8,
+            ultrasound_presence_detected: true,
+            overall_health_score: score,
+            last_update: Instant::now(),
+        };
+
+        manager.report_channel_health(health(0.1)).await.unwrap();
+        assert!(manager.is_fallback_active().await);
+        assert_eq!(manager.mode_switch_count().await, 1);
+
+        // Back above the failure threshold but inside the hysteresis band
+        manager.report_channel_health(health(0.5)).await.unwrap();
+        assert!(manager.is_fallback_active().await);
+
+        // Above the recovery threshold, but a dip restarts the stability clock
+        manager.report_channel_health(health(0.8)).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        manager.report_channel_health(health(0.5)).await.unwrap();
+        manager.report_channel_health(health(0.8)).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        manager.report_channel_health(health(0.8)).await.unwrap();
+        assert!(manager.is_fallback_active().await);
+        assert_eq!(manager.mode_switch_count().await, 1);
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        manager.report_channel_health(health(0.8)).await.unwrap();
+        assert!(!manager.is_fallback_active().await);
+        assert_eq!(manager.mode_switch_count().await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_session_snapshot_round_trips_sealed() {
+        let mut protocol = ProtocolEngine::new();
+        protocol.set_shared_secret(Some([0x5A; 32]));
+        protocol.set_peer_public_key(Some(vec![9; 32]));
+        protocol.set_keepalive_sequences(7, 3);
+        protocol.set_state(ProtocolState::Connected).await;
+        let snapshot = SessionSnapshot::capture(&protocol).await;
+
+        let storage_key = [0x11; 32];
+        let bytes = snapshot.to_bytes(&storage_key).unwrap();
+        assert!(!bytes.windows(32).any(|window| window == [0x5A; 32]));
+
+        let restored = SessionSnapshot::from_bytes(&bytes, &storage_key).unwrap();
+        assert_eq!(restored.session_id, snapshot.session_id);
+        assert_eq!(restored.shared_secret, Some([0x5A; 32]));
+        assert_eq!(restored.peer_public_key, Some(vec![9; 32]));
+        assert_eq!(restored.communication_mode, snapshot.communication_mode);
+        assert_eq!((restored.keepalive_sequence, restored.peer_keepalive_sequence), (7, 3));
+        assert_eq!(restored.session_started_at, snapshot.session_started_at);
+
+        let mut tampered = bytes.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(matches!(SessionSnapshot::from_bytes(&tampered, &storage_key), Err(FallbackError::SnapshotRejected)));
+        assert!(matches!(SessionSnapshot::from_bytes(&bytes, &[0x12; 32]), Err(FallbackError::SnapshotRejected)));
+        assert!(matches!(SessionSnapshot::from_bytes(&[], &storage_key), Err(FallbackError::SnapshotRejected)));
+    }
+}
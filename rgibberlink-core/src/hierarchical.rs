@@ -353,6 +353,27 @@ impl HierarchicalProtocolEngine {
         Ok(())
     }
 
+    /// Escrow `secret` (e.g. this commander's session key) among the active
+    /// subordinates so any `threshold` of them can recover it. Returns one Shamir
+    /// share per subordinate device.
+    pub async fn escrow_secret(&self, secret: &[u8; 32], threshold: u8) -> Result<Vec<([u8; 16], [u8; 33])>, ProtocolError> {
+        let subordinates: Vec<[u8; 16]> = self.presence_list.lock().await.iter()
+            .filter(|p| p.active && p.rank < self.my_rank)
+            .map(|p| p.device_id)
+            .collect();
+
+        let trustees = u8::try_from(subordinates.len())
+            .map_err(|_| ProtocolError::CryptoError("too many subordinates for secret sharing".into()))?;
+        if threshold == 0 || threshold > trustees {
+            return Err(ProtocolError::CryptoError(format!(
+                "escrow threshold {} needs between 1 and {} active subordinates", threshold, trustees
+            )));
+        }
+
+        let shares = CryptoEngine::split_secret(secret, trustees, threshold);
+        Ok(subordinates.into_iter().zip(shares).collect())
+    }
+
     pub async fn get_highest_rank_present(&self) -> Option<MilitaryRank> {
         let list = self.presence_list.lock().await;
         list.iter()
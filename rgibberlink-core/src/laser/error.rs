@@ -1,7 +1,5 @@
 //! Laser error types and definitions
 
-use thiserror::Error;
-
 #[derive(Debug, Clone, thiserror::Error)]
 pub enum LaserError {
     #[error("Hardware not available")]
@@ -20,6 +18,14 @@ pub enum LaserError {
     DataCorruption,
     #[error("Timeout")]
     Timeout,
+    #[error("Transmitter busy with another emission")]
+    TransmitterBusy,
+    #[error("Invalid stream: {0}")]
+    InvalidStream(String),
     #[error("Visual engine error: {0}")]
     VisualError(#[from] crate::visual::VisualError),
+    #[error("Servo fault: {0}")]
+    ServoFault(String),
+    #[error("Invalid configuration: {0}")]
+    InvalidConfiguration(String),
 }
\ No newline at end of file
@@ -1,5 +1,11 @@
 //! Hardware interface for laser communication
 
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use super::error::LaserError;
+
 #[cfg(target_os = "android")]
 use std::os::raw::{c_char, c_int};
 
@@ -12,6 +18,7 @@ extern "C" {
     fn laser_set_alignment(x: f32, y: f32) -> c_int;
 }
 
+#[derive(Default)]
 pub struct HardwareInterface;
 
 impl HardwareInterface {
@@ -115,4 +122,218 @@ impl HardwareInterface {
             false
         }
     }
-}
\ No newline at end of file
+}
+
+/// Size of a camera frame: one 640x480 greyscale image
+pub const CAMERA_FRAME_BYTES: usize = 640 * 480;
+
+/// Highest sample rate the default, software-paced `emit_samples` accepts.
+///
+/// Each sample costs one `set_power` round trip through the driver plus a
+/// spin-wait for its deadline, which keeps up to about 10 us per sample.
+pub const MAX_PACED_SAMPLE_RATE_HZ: u32 = 100_000;
+
+/// Remaining wait above which the pacing loop sleeps rather than spins
+const PACING_SPIN_THRESHOLD: Duration = Duration::from_millis(2);
+
+/// Hardware driven by `LaserEngine`.
+///
+/// `HardwareInterface` talks to the Android drivers; `MockLaserHardware` lets the
+/// engine run against scripted readings on any platform.
+pub trait LaserHardware: Send + Sync {
+    fn initialize(&self) -> Result<(), LaserError> {
+        Ok(())
+    }
+
+    fn set_power(&self, power_mw: f32) -> Result<(), LaserError>;
+
+    /// Emit a buffer of power levels (mW) clocked out at `sample_rate` Hz.
+    ///
+    /// Drivers with a clocked output (DMA into a DAC or PWM peripheral) should
+    /// override this. The default is [`pace_samples`], which blocks the calling
+    /// thread for the buffer's airtime; `LaserEngine` calls it on the blocking pool.
+    fn emit_samples(&self, samples: &[f32], sample_rate: u32) -> Result<(), LaserError> {
+        pace_samples(self, samples, sample_rate)
+    }
+
+    fn read_photodiode(&self) -> Result<f32, LaserError>;
+
+    fn capture_frame(&self) -> Result<Vec<u8>, LaserError>;
+
+    fn set_alignment(&self, x: f32, y: f32) -> Result<(), LaserError>;
+}
+
+/// Software-paced emission: `set_power` for each sample against a per-sample
+/// `Instant` deadline, blocking the calling thread for the buffer's airtime.
+///
+/// Limited to [`MAX_PACED_SAMPLE_RATE_HZ`]. Fails with `TransmissionFailed` if a
+/// write lands more than one sample period late, since the receiver could not
+/// recover the timing of such a frame.
+pub fn pace_samples<H: LaserHardware + ?Sized>(hardware: &H, samples: &[f32], sample_rate: u32) -> Result<(), LaserError> {
+    if sample_rate == 0 || sample_rate > MAX_PACED_SAMPLE_RATE_HZ {
+        return Err(LaserError::TransmissionFailed);
+    }
+
+    let sample_period = Duration::from_secs_f64(1.0 / sample_rate as f64);
+    let start = Instant::now();
+    for (index, &power_mw) in samples.iter().enumerate() {
+        let deadline = start + sample_period.mul_f64(index as f64);
+        loop {
+            let now = Instant::now();
+            if now >= deadline {
+                if now - deadline > sample_period {
+                    return Err(LaserError::TransmissionFailed);
+                }
+                break;
+            }
+            let remaining = deadline - now;
+            if remaining > PACING_SPIN_THRESHOLD {
+                std::thread::sleep(remaining - PACING_SPIN_THRESHOLD / 2);
+            } else {
+                std::hint::spin_loop();
+            }
+        }
+        hardware.set_power(power_mw)?;
+    }
+    Ok(())
+}
+
+impl LaserHardware for HardwareInterface {
+    fn initialize(&self) -> Result<(), LaserError> {
+        HardwareInterface::initialize(self)
+    }
+
+    fn set_power(&self, power_mw: f32) -> Result<(), LaserError> {
+        HardwareInterface::set_power(self, power_mw)
+    }
+
+    fn emit_samples(&self, samples: &[f32], sample_rate: u32) -> Result<(), LaserError> {
+        // Without the drivers there is no diode to clock; the engine still waits out the airtime
+        if !self.is_hardware_available() {
+            if sample_rate == 0 || sample_rate > MAX_PACED_SAMPLE_RATE_HZ {
+                return Err(LaserError::TransmissionFailed);
+            }
+            return Ok(());
+        }
+        pace_samples(self, samples, sample_rate)
+    }
+
+    fn read_photodiode(&self) -> Result<f32, LaserError> {
+        // Without the drivers there is no photodiode to read
+        if !self.is_hardware_available() {
+            return Err(LaserError::ReceptionFailed);
+        }
+        Ok(self.get_photodiode_reading())
+    }
+
+    fn capture_frame(&self) -> Result<Vec<u8>, LaserError> {
+        if !self.is_hardware_available() {
+            return Err(LaserError::ReceptionFailed);
+        }
+        let mut frame = vec![0u8; CAMERA_FRAME_BYTES];
+        self.get_camera_frame(&mut frame)?;
+        Ok(frame)
+    }
+
+    fn set_alignment(&self, x: f32, y: f32) -> Result<(), LaserError> {
+        HardwareInterface::set_alignment(self, x, y)
+    }
+}
+
+impl<T: LaserHardware + ?Sized> LaserHardware for Arc<T> {
+    fn initialize(&self) -> Result<(), LaserError> {
+        (**self).initialize()
+    }
+
+    fn set_power(&self, power_mw: f32) -> Result<(), LaserError> {
+        (**self).set_power(power_mw)
+    }
+
+    fn emit_samples(&self, samples: &[f32], sample_rate: u32) -> Result<(), LaserError> {
+        (**self).emit_samples(samples, sample_rate)
+    }
+
+    fn read_photodiode(&self) -> Result<f32, LaserError> {
+        (**self).read_photodiode()
+    }
+
+    fn capture_frame(&self) -> Result<Vec<u8>, LaserError> {
+        (**self).capture_frame()
+    }
+
+    fn set_alignment(&self, x: f32, y: f32) -> Result<(), LaserError> {
+        (**self).set_alignment(x, y)
+    }
+}
+
+/// Scriptable hardware for tests.
+///
+/// Photodiode readings and camera frames are replayed in the order they were
+/// queued; once a queue runs dry reads fail with `ReceptionFailed`, as if no
+/// signal were present. Power levels, whether set singly or emitted as a sample
+/// buffer, and alignment commands are recorded for inspection.
+#[derive(Default)]
+pub struct MockLaserHardware {
+    photodiode_readings: Mutex<VecDeque<f32>>,
+    frames: Mutex<VecDeque<Vec<u8>>>,
+    power_history: Mutex<Vec<f32>>,
+    sample_rates: Mutex<Vec<u32>>,
+    alignment_history: Mutex<Vec<(f32, f32)>>,
+}
+
+impl MockLaserHardware {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue readings to be returned by subsequent photodiode reads
+    pub fn push_photodiode_readings(&self, readings: impl IntoIterator<Item = f32>) {
+        self.photodiode_readings.lock().unwrap().extend(readings);
+    }
+
+    /// Queue a frame to be returned by the next camera capture
+    pub fn push_frame(&self, frame: Vec<u8>) {
+        self.frames.lock().unwrap().push_back(frame);
+    }
+
+    /// Every power level set so far, in mW
+    pub fn power_history(&self) -> Vec<f32> {
+        self.power_history.lock().unwrap().clone()
+    }
+
+    /// Sample rate of every buffer passed to `emit_samples`, in Hz
+    pub fn sample_rates(&self) -> Vec<u32> {
+        self.sample_rates.lock().unwrap().clone()
+    }
+
+    /// Every alignment adjustment requested so far
+    pub fn alignment_history(&self) -> Vec<(f32, f32)> {
+        self.alignment_history.lock().unwrap().clone()
+    }
+}
+
+impl LaserHardware for MockLaserHardware {
+    fn set_power(&self, power_mw: f32) -> Result<(), LaserError> {
+        self.power_history.lock().unwrap().push(power_mw);
+        Ok(())
+    }
+
+    fn emit_samples(&self, samples: &[f32], sample_rate: u32) -> Result<(), LaserError> {
+        self.power_history.lock().unwrap().extend_from_slice(samples);
+        self.sample_rates.lock().unwrap().push(sample_rate);
+        Ok(())
+    }
+
+    fn read_photodiode(&self) -> Result<f32, LaserError> {
+        self.photodiode_readings.lock().unwrap().pop_front().ok_or(LaserError::ReceptionFailed)
+    }
+
+    fn capture_frame(&self) -> Result<Vec<u8>, LaserError> {
+        self.frames.lock().unwrap().pop_front().ok_or(LaserError::ReceptionFailed)
+    }
+
+    fn set_alignment(&self, x: f32, y: f32) -> Result<(), LaserError> {
+        self.alignment_history.lock().unwrap().push((x, y));
+        Ok(())
+    }
+}
@@ -1,26 +1,4409 @@
-//! # Laser Module
-//!
-//! Modular laser communication system with safety monitoring and power management.
-//!
-//! ## Modules
-//! - `control`: Core laser operations and modulation
-//! - `safety`: Safety monitoring and compliance
-//! - `power`: Power management and efficiency
-//! - `alignment`: Beam alignment and tracking
-//! - `types`: Common types and structures
-//! - `error`: Error types for laser operations
-
-pub mod control;
-pub mod safety;
-pub mod power;
-pub mod alignment;
-pub mod types;
-pub mod error;
-
-// Re-export main types for backward compatibility
-pub use control::LaserEngine;
-pub use error::LaserError;
-pub use types::{LaserConfig, LaserType, ModulationScheme, ReceptionConfig, AlignmentStatus, PowerProfile, BatteryState, PowerManagementConfig, PowerStatistics, PowerBudget, BeamAlignment};
-pub use safety::SafetyMonitor;
-pub use power::PowerManagement;
-pub use alignment::AlignmentManager;
+//! # Laser Engine Module
+//!
+//! High-speed optical data transmission using laser diodes with multiple modulation schemes.
+//! Supports visible and IR lasers, OOK/PWM modulation, QR projection, and photodiode/camera reception.
+//!
+//! This module has been refactored into focused submodules:
+//! - `alignment`: Beam alignment and tracking
+//! - `error`: Error types and handling
+//! - `hardware`: Hardware interface abstraction
+//! - `safety`: Energy and eye-safety accounting
+//! - `servo`: Pan/tilt beam steering
+//! - `types`: Core data types and configurations
+
+mod alignment;
+mod error;
+pub mod hardware;
+mod safety;
+pub mod servo;
+mod types;
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tokio::time::{Duration, Instant};
+use reed_solomon_erasure::galois_8::ReedSolomon;
+
+use crate::optical_ecc::{AdaptiveECCConfig, OpticalECC, OpticalQualityMetrics};
+use crate::range_detector::{RangeDetector, RangeDetectorCategory, RangeEnvironmentalConditions, RangeMeasurement};
+use crate::security::WeatherCondition;
+use crate::visual::{VisualEngine, VisualPayload};
+use alignment::{AlignmentTracker, KalmanFilter};
+use safety::SafetyMonitor;
+
+pub use alignment::AlignmentStatus;
+pub use error::LaserError;
+pub use types::{BatteryState, LaserChannelDiagnostics, LaserConfig, LaserType, ModulationScheme, PowerProfile, PowerStatistics, ReceptionConfig};
+
+/// Laser transmitter/receiver driving one optical link
+pub struct LaserEngine {
+    config: LaserConfig,
+    rx_config: ReceptionConfig,
+    visual_engine: VisualEngine,
+    rs_codec: ReedSolomon,
+    hardware: Arc<dyn hardware::LaserHardware>,
+    optical_ecc: Option<OpticalECC>,
+    is_active: Arc<Mutex<bool>>,
+    safety_monitor: Arc<Mutex<SafetyMonitor>>,
+    alignment_tracker: Arc<Mutex<AlignmentTracker>>,
+    range_detector: Option<Arc<Mutex<RangeDetector>>>,
+    current_power_profile: Arc<Mutex<PowerProfile>>,
+    weather_base_power_mw: Arc<Mutex<Option<f32>>>,
+    power_ramp: PowerRampConfig,
+    monitoring_task: Arc<Mutex<Option<MonitoringTask>>>,
+    prediction_horizon: PredictionHorizonConfig,
+    acquisition: AcquisitionConfig,
+    interference_avoidance: std::sync::atomic::AtomicBool,
+    interference_bands: Vec<SpectralBand>,
+    last_adaptation: Arc<std::sync::Mutex<AdaptationExplanation>>,
+    transmit_guard: Arc<Mutex<()>>,
+    busy_policy: TransmitBusyPolicy,
+    sample_sink: Option<Arc<dyn SampleSink>>,
+    ack_callback: Option<ChunkAckCallback>,
+    ack_rtt: Option<AckRttEstimate>,
+    power_log: Arc<Mutex<VecDeque<PowerLogEntry>>>,
+    power_log_config: PowerLogConfig,
+    adaptive_mode: bool,
+    adaptive_rs: Option<RsParityController>,
+    servo: Option<Arc<Mutex<Box<dyn servo::ServoController>>>>,
+    sync_preamble: Option<Vec<u8>>,
+}
+
+impl LaserEngine {
+    pub fn new(config: LaserConfig, rx_config: ReceptionConfig) -> Self {
+        Self::new_with_hardware(config, rx_config, Box::new(hardware::HardwareInterface::new()))
+    }
+
+    /// Engine driving `hardware` instead of the platform drivers, e.g. a
+    /// [`hardware::MockLaserHardware`] to exercise transmit and receive off-device
+    pub fn new_with_hardware(config: LaserConfig, rx_config: ReceptionConfig, hardware: Box<dyn hardware::LaserHardware>) -> Self {
+        let visual_engine = VisualEngine::new();
+        // Reed-Solomon for error correction (16 data, 4 parity)
+        let rs_codec = ReedSolomon::new(16, 4).expect("Failed to create RS codec");
+
+        let tolerance_px = rx_config.alignment_tolerance_px as f32;
+
+        Self {
+            config,
+            rx_config,
+            visual_engine,
+            rs_codec,
+            hardware: Arc::from(hardware),
+            optical_ecc: None,
+            is_active: Arc::new(Mutex::new(false)),
+            safety_monitor: Arc::new(Mutex::new(SafetyMonitor {
+                last_activity: Instant::now(),
+                total_energy_joules: 0.0,
+                eye_safety_violations: 0,
+            })),
+            alignment_tracker: Arc::new(Mutex::new(AlignmentTracker {
+                target_position: (0.0, 0.0),
+                current_position: (0.0, 0.0),
+                tolerance_px,
+                last_alignment_check: Instant::now(),
+                alignment_attempts: 0,
+                position_history: VecDeque::with_capacity(20),
+                velocity_estimate: (0.0, 0.0),
+                prediction_enabled: true,
+                kalman_filter: Some(KalmanFilter::new()),
+                pixel_to_angle: DEFAULT_PIXEL_TO_ANGLE,
+                pan_tilt_deg: (0.0, 0.0),
+            })),
+            range_detector: None,
+            current_power_profile: Arc::new(Mutex::new(PowerProfile::default())),
+            weather_base_power_mw: Arc::new(Mutex::new(None)),
+            power_ramp: PowerRampConfig::default(),
+            monitoring_task: Arc::new(Mutex::new(None)),
+            prediction_horizon: PredictionHorizonConfig::default(),
+            acquisition: AcquisitionConfig::default(),
+            interference_avoidance: std::sync::atomic::AtomicBool::new(false),
+            interference_bands: ultrasound_interference_bands(&crate::ultrasonic_beam::BeamConfig::default()),
+            last_adaptation: Arc::new(std::sync::Mutex::new(AdaptationExplanation::default())),
+            transmit_guard: Arc::new(Mutex::new(())),
+            busy_policy: TransmitBusyPolicy::default(),
+            sample_sink: None,
+            ack_callback: None,
+            ack_rtt: None,
+            power_log: Arc::new(Mutex::new(VecDeque::new())),
+            power_log_config: PowerLogConfig::default(),
+            adaptive_mode: false,
+            adaptive_rs: None,
+            servo: None,
+            sync_preamble: None,
+        }
+    }
+
+    pub async fn initialize(&mut self) -> Result<(), LaserError> {
+        self.hardware.initialize()?;
+
+        // Set active state
+        *self.is_active.lock().await = true;
+        Ok(())
+    }
+
+    pub async fn shutdown(&mut self) -> Result<(), LaserError> {
+        *self.is_active.lock().await = false;
+        Ok(())
+    }
+
+    pub async fn is_active(&self) -> bool {
+        *self.is_active.lock().await
+    }
+
+    /// Transmit data using the configured modulation scheme
+    pub async fn transmit_data(&mut self, data: &[u8]) -> Result<(), LaserError> {
+        if !self.is_active().await {
+            return Err(LaserError::HardwareUnavailable);
+        }
+
+        // Only one emission at a time per transmitter
+        let transmit_guard = self.transmit_guard.clone();
+        let _emission = match self.busy_policy {
+            TransmitBusyPolicy::Queue => transmit_guard.lock().await,
+            TransmitBusyPolicy::Reject => transmit_guard.try_lock()
+                .map_err(|_| LaserError::TransmitterBusy)?,
+        };
+
+        // Check safety before transmission
+        self.check_safety().await?;
+
+        // Monitor power safety
+        self.monitor_power_safety().await?;
+
+        // Update power profile, modulation scheme, and ECC if in adaptive mode
+        if self.adaptive_mode {
+            // Measure range and update power profile dynamically
+            self.measure_range_and_update_power().await?;
+            self.update_modulation_scheme().await?;
+            self.update_ecc_for_range().await?;
+        }
+
+        // Use optimal modulation scheme in adaptive mode, otherwise use configured
+        let modulation_scheme = if self.adaptive_mode {
+            self.select_optimal_modulation().await
+        } else {
+            self.config.modulation
+        };
+
+        // Full-intensity power for this emission, recorded for compliance
+        let emitted_power_mw = self.get_effective_power_limit().await;
+        let emission_start = Instant::now();
+
+        let result = match self.emit_sync_preamble().await {
+            Err(e) => Err(e),
+            Ok(()) => match modulation_scheme {
+                ModulationScheme::Ook => self.transmit_ook(data).await,
+                ModulationScheme::Pwm => self.transmit_pwm(data).await,
+                ModulationScheme::QrProjection => self.transmit_qr_projection(data).await,
+                ModulationScheme::Fsk => self.transmit_fsk(data).await,
+                ModulationScheme::Manchester => self.transmit_manchester(data).await,
+                ModulationScheme::Dpsk => self.transmit_dpsk(data).await,
+            },
+        };
+
+        // Power was emitted even if the transmission failed part-way
+        self.record_power_sample(emission_start, emitted_power_mw, emission_start.elapsed()).await;
+
+        // Final power safety check after transmission
+        self.monitor_power_safety().await?;
+
+        result
+    }
+
+    /// Receive data using configured reception method
+    pub async fn receive_data(&mut self, timeout_ms: u64) -> Result<Vec<u8>, LaserError> {
+        if !self.is_active().await {
+            return Err(LaserError::HardwareUnavailable);
+        }
+
+        let timeout = Duration::from_millis(timeout_ms);
+        let start = Instant::now();
+
+        // Use optimal modulation scheme in adaptive mode, otherwise use configured
+        let modulation_scheme = if self.adaptive_mode {
+            self.select_optimal_modulation().await
+        } else {
+            self.config.modulation
+        };
+
+        loop {
+            if start.elapsed() > timeout {
+                return Err(LaserError::Timeout);
+            }
+
+            match modulation_scheme {
+                ModulationScheme::Ook => {
+                    if let Ok(data) = self.receive_ook().await {
+                        return Ok(data);
+                    }
+                }
+                ModulationScheme::Pwm => {
+                    if let Ok(data) = self.receive_pwm().await {
+                        return Ok(data);
+                    }
+                }
+                ModulationScheme::QrProjection => {
+                    if let Ok(data) = self.receive_qr_projection().await {
+                        return Ok(data);
+                    }
+                }
+                ModulationScheme::Fsk => {
+                    if let Ok(data) = self.receive_fsk().await {
+                        return Ok(data);
+                    }
+                }
+                ModulationScheme::Manchester => {
+                    if let Ok(data) = self.receive_manchester().await {
+                        return Ok(data);
+                    }
+                }
+                ModulationScheme::Dpsk => {
+                    if let Ok(data) = self.receive_dpsk().await {
+                        return Ok(data);
+                    }
+                }
+            }
+
+            // Small delay to prevent busy waiting
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }
+
+    /// Transmit a payload larger than one RS block as a stream of framed chunks.
+    ///
+    /// Each chunk carries a sequence number, the chunk count and a CRC16, and is
+    /// sent back-to-back through [`Self::transmit_data`], so the header is covered
+    /// by the same ECC as the payload.
+    pub async fn transmit_stream(&mut self, data: &[u8], chunk_size: usize) -> Result<(), LaserError> {
+        for frame in encode_stream_frames(data, chunk_size)? {
+            self.transmit_data(&frame).await?;
+        }
+        Ok(())
+    }
+
+    /// Receive and reassemble a stream sent with [`Self::transmit_stream`].
+    ///
+    /// Chunks are ordered by sequence number. Frames failing their CRC are
+    /// dropped; if chunks are still missing after `STREAM_RECEIVE_RETRIES`
+    /// extra receptions (or the timeout) the stream is reported as corrupt.
+    pub async fn receive_stream(&mut self, timeout_ms: u64) -> Result<Vec<u8>, LaserError> {
+        let timeout = Duration::from_millis(timeout_ms);
+        let start = Instant::now();
+        let mut stream = StreamReassembler::new();
+        let mut attempts = 0usize;
+
+        while !stream.is_complete() {
+            let remaining = timeout.saturating_sub(start.elapsed());
+            if remaining.is_zero() {
+                break;
+            }
+
+            attempts += 1;
+            match self.receive_data(remaining.as_millis() as u64).await {
+                Ok(frame) => {
+                    if let Err(_e) = stream.push(&frame) {
+                        trace_warn!(error = %_e, "dropping corrupt laser stream frame");
+                    }
+                }
+                Err(LaserError::Timeout) => break,
+                Err(e @ LaserError::HardwareUnavailable) => return Err(e),
+                Err(_) => {}
+            }
+
+            if stream.total_chunks().is_some_and(|total| attempts >= total + STREAM_RECEIVE_RETRIES) {
+                break;
+            }
+        }
+
+        if stream.total_chunks().is_none() {
+            return Err(LaserError::Timeout);
+        }
+        if !stream.is_complete() {
+            trace_warn!(missing = ?stream.missing(), "laser stream incomplete after retries");
+        }
+        stream.finish()
+    }
+
+    /// Transmit `data` as stream chunks, waiting for the receiver's ACK of each
+    /// chunk on the ultrasound control channel before sending the next.
+    ///
+    /// A chunk that is NAKed or not acknowledged within the ACK timeout is
+    /// resent up to `max_retries` times; the timeout follows the measured ACK
+    /// round trip and doubles on every retry of a chunk. Needs an ACK callback
+    /// (see [`Self::set_ack_callback`]).
+    pub async fn transmit_reliable(&mut self, data: &[u8], max_retries: u32) -> Result<(), LaserError> {
+        let ack_callback = self.ack_callback.clone()
+            .ok_or_else(|| LaserError::InvalidConfiguration("reliable transmission needs an ACK callback".to_string()))?;
+
+        for (sequence, frame) in encode_stream_frames(data, RELIABLE_CHUNK_SIZE)?.iter().enumerate() {
+            let sequence = sequence as u16;
+            let mut timeout = self.ack_timeout();
+            let mut attempt = 0;
+            loop {
+                self.transmit_data(frame).await?;
+                let sent_at = Instant::now();
+                match tokio::time::timeout(timeout, ack_callback(sequence)).await {
+                    Ok(true) => {
+                        // Karn's rule: a retransmitted chunk's ACK is ambiguous
+                        if attempt == 0 {
+                            self.ack_rtt.get_or_insert_with(AckRttEstimate::default).observe(sent_at.elapsed());
+                        }
+                        break;
+                    }
+                    Ok(false) => trace_warn!(sequence, attempt, "laser chunk NAKed"),
+                    Err(_) => trace_warn!(sequence, attempt, "laser chunk ACK timed out"),
+                }
+                if attempt >= max_retries {
+                    return Err(LaserError::TransmissionFailed);
+                }
+                attempt += 1;
+                timeout = (timeout * 2).min(RELIABLE_MAX_ACK_TIMEOUT);
+            }
+        }
+        Ok(())
+    }
+
+    /// Current ACK timeout for reliable transmission: smoothed round trip plus
+    /// four deviations once ACKs have been timed, `RELIABLE_INITIAL_ACK_TIMEOUT` before
+    pub fn ack_timeout(&self) -> Duration {
+        self.ack_rtt.as_ref().map_or(RELIABLE_INITIAL_ACK_TIMEOUT, AckRttEstimate::timeout)
+    }
+
+    /// Source of per-chunk ACKs for [`Self::transmit_reliable`] (`None` detaches)
+    pub fn set_ack_callback(&mut self, callback: Option<ChunkAckCallback>) {
+        self.ack_callback = callback;
+    }
+
+    /// Transmit using On-Off Keying modulation
+    ///
+    /// The whole frame is rendered into one sample buffer at the profile's data
+    /// rate and handed to `LaserHardware::emit_samples`, then the task sleeps
+    /// once for the frame's airtime. Sleeping per bit cannot work: tokio timers
+    /// resolve to about a millisecond, which capped OOK near 1 kbps whatever rate
+    /// was configured. The achievable rate is set by the driver's output clock;
+    /// the default `emit_samples` paces `set_power` in software, up to
+    /// `hardware::MAX_PACED_SAMPLE_RATE_HZ`, on the blocking pool. Only the
+    /// airtime left after `emit_samples` returns is slept. Buffers cost 4 bytes
+    /// per sample, i.e. per bit, or per carrier half-period with a lock-in carrier.
+    async fn transmit_ook(&mut self, data: &[u8]) -> Result<(), LaserError> {
+        // Encode data with error correction
+        let encoded = self.encode_with_ecc(data).await?;
+
+        // Get data rate from current power profile
+        let data_rate_bps = self.current_power_profile.lock().await.data_rate_bps;
+
+        let (samples, sample_rate) = ook_samples(&encoded, data_rate_bps, self.rx_config.lock_in_carrier_hz);
+        let airtime = Duration::from_secs_f64(samples.len() as f64 / sample_rate as f64);
+        let emit_started = Instant::now();
+        self.emit_intensities(&samples, sample_rate).await?;
+        tokio::time::sleep(airtime.saturating_sub(emit_started.elapsed())).await;
+
+        Ok(())
+    }
+
+    /// Receive using On-Off Keying modulation
+    async fn receive_ook(&mut self) -> Result<Vec<u8>, LaserError> {
+        // Check alignment first
+        let alignment = self.get_alignment_status().await;
+        if !alignment.is_aligned {
+            return Err(LaserError::AlignmentLost);
+        }
+
+        // Photodiode reception keeps per-byte confidence so weak bytes become erasures
+        if self.rx_config.use_photodiode {
+            let symbols = self.receive_photodiode_frame().await?;
+            return self.decode_with_ecc_erasures(&symbols).await;
+        }
+
+        // Receive raw signal
+        let raw_data = if self.rx_config.use_camera {
+            self.receive_camera().await?
+        } else {
+            return Err(LaserError::ReceptionFailed);
+        };
+
+        // Decode with error correction
+        self.decode_with_ecc(&raw_data).await
+    }
+
+    /// Transmit using Pulse Width Modulation
+    async fn transmit_pwm(&mut self, data: &[u8]) -> Result<(), LaserError> {
+        let encoded = self.encode_with_ecc(data).await?;
+
+        for byte in encoded {
+            // PWM: duty cycle represents data value
+            let duty_cycle = byte as f32 / 255.0;
+            self.transmit_pwm_byte(duty_cycle).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Transmit a single PWM byte
+    async fn transmit_pwm_byte(&self, duty_cycle: f32) -> Result<(), LaserError> {
+        let period_us = 1_000_000 / self.config.data_rate_bps as u64;
+        let on_time_us = (period_us as f32 * duty_cycle) as u64;
+        let off_time_us = period_us - on_time_us;
+
+        self.set_laser_intensity(1.0).await?;
+        tokio::time::sleep(Duration::from_micros(on_time_us)).await;
+
+        self.set_laser_intensity(0.0).await?;
+        tokio::time::sleep(Duration::from_micros(off_time_us)).await;
+
+        Ok(())
+    }
+
+    /// Receive using Pulse Width Modulation
+    async fn receive_pwm(&mut self) -> Result<Vec<u8>, LaserError> {
+        // Check alignment first
+        let alignment = self.get_alignment_status().await;
+        if !alignment.is_aligned {
+            return Err(LaserError::AlignmentLost);
+        }
+
+        // Receive raw signal
+        let raw_data = if self.rx_config.use_photodiode {
+            self.receive_photodiode().await?
+        } else if self.rx_config.use_camera {
+            self.receive_camera().await?
+        } else {
+            return Err(LaserError::ReceptionFailed);
+        };
+
+        // Decode with error correction
+        self.decode_with_ecc(&raw_data).await
+    }
+
+    /// Transmit using dynamic QR code projection
+    async fn transmit_qr_projection(&mut self, data: &[u8]) -> Result<(), LaserError> {
+        // Encode data with error correction first
+        let encoded_data = self.encode_with_ecc(data).await?;
+
+        // Create visual payload from encoded data
+        let payload = VisualPayload {
+            session_id: crate::session_id::SessionId::default(), // Would be set properly in real implementation
+            public_key: encoded_data,
+            nonce: [0; 16],
+            signature: vec![],
+            version: None,
+            version_offer: None,
+            psk: false,
+        };
+
+        // Generate QR code using VisualEngine
+        let qr_svg = self.visual_engine.encode_payload(&payload)?;
+
+        // Project the QR code (would control laser projector)
+        self.project_qr_code(&qr_svg).await?;
+
+        Ok(())
+    }
+
+    /// Receive using QR code scanning
+    async fn receive_qr_projection(&mut self) -> Result<Vec<u8>, LaserError> {
+        // Check alignment first
+        let alignment = self.get_alignment_status().await;
+        if !alignment.is_aligned {
+            return Err(LaserError::AlignmentLost);
+        }
+
+        // Capture QR code from camera
+        let raw_data = self.receive_camera().await?;
+
+        // Decode QR data using VisualEngine
+        let payload = self.visual_engine.decode_payload(&raw_data)?;
+
+        // Decode with error correction
+        self.decode_with_ecc(&payload.public_key).await
+    }
+
+    /// Transmit using Frequency Shift Keying
+    async fn transmit_fsk(&mut self, data: &[u8]) -> Result<(), LaserError> {
+        let encoded = self.encode_with_ecc(data).await?;
+
+        // FSK: Use two different frequencies for 0 and 1
+        // Frequency 1: base frequency, Frequency 2: base + offset
+        let base_freq = LASER_FSK_BASE_HZ;
+        let freq_offset = LASER_FSK_OFFSET_HZ;
+
+        for byte in encoded {
+            for bit in 0..8 {
+                let is_high = (byte & (1 << (7 - bit))) != 0;
+                let frequency = if is_high { base_freq + freq_offset } else { base_freq };
+
+                // Transmit at the selected frequency for one bit period
+                self.transmit_frequency(frequency, Duration::from_micros(1_000_000 / self.config.data_rate_bps as u64)).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Transmit using Manchester encoding
+    async fn transmit_manchester(&mut self, data: &[u8]) -> Result<(), LaserError> {
+        let encoded = self.encode_with_ecc(data).await?;
+
+        // Manchester encoding: 0 = 01, 1 = 10
+        // Self-clocking, good for noisy channels
+        for byte in encoded {
+            for bit in 0..8 {
+                let bit_value = (byte & (1 << (7 - bit))) != 0;
+
+                // Manchester: transition in middle of bit period
+                let half_bit_duration = Duration::from_micros(500_000 / self.config.data_rate_bps as u64);
+
+                if bit_value {
+                    // 1: high-low
+                    self.set_laser_intensity(1.0).await?;
+                    tokio::time::sleep(half_bit_duration).await;
+                    self.set_laser_intensity(0.0).await?;
+                    tokio::time::sleep(half_bit_duration).await;
+                } else {
+                    // 0: low-high
+                    self.set_laser_intensity(0.0).await?;
+                    tokio::time::sleep(half_bit_duration).await;
+                    self.set_laser_intensity(1.0).await?;
+                    tokio::time::sleep(half_bit_duration).await;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Transmit using Differential Phase-Shift Keying on the intensity subcarrier.
+    ///
+    /// A reference symbol is followed by one symbol per bit; a `1` flips the
+    /// subcarrier phase by half a cycle, a `0` keeps it. The drive samples stand
+    /// in for a DAC clocking the waveform out, so only whole symbols are paced.
+    pub async fn transmit_dpsk(&mut self, data: &[u8]) -> Result<(), LaserError> {
+        let encoded = self.encode_with_ecc(data).await?;
+        let symbol_period = Duration::from_secs_f32(LASER_DPSK_CYCLES_PER_SYMBOL as f32 / LASER_DPSK_SUBCARRIER_HZ);
+
+        for symbol in modulate_dpsk(&encoded).chunks(LASER_DPSK_SAMPLES_PER_SYMBOL) {
+            for &intensity in symbol {
+                self.set_laser_intensity(intensity).await?;
+            }
+            tokio::time::sleep(symbol_period).await;
+        }
+
+        Ok(())
+    }
+
+    /// Transmit at a specific frequency for a duration
+    async fn transmit_frequency(&self, _frequency: f32, duration: Duration) -> Result<(), LaserError> {
+        // In a real implementation, this would modulate the laser at the specified frequency
+        // For now, simulate with on/off patterns
+        self.set_laser_intensity(1.0).await?;
+        tokio::time::sleep(duration).await;
+        Ok(())
+    }
+
+    /// Receive using Frequency Shift Keying
+    async fn receive_fsk(&mut self) -> Result<Vec<u8>, LaserError> {
+        // Check alignment first
+        let alignment = self.get_alignment_status().await;
+        if !alignment.is_aligned {
+            return Err(LaserError::AlignmentLost);
+        }
+
+        // Receive raw signal - would detect frequency shifts
+        let raw_data = if self.rx_config.use_photodiode {
+            self.receive_photodiode().await?
+        } else if self.rx_config.use_camera {
+            self.receive_camera().await?
+        } else {
+            return Err(LaserError::ReceptionFailed);
+        };
+
+        // Decode FSK signal (simplified - would analyze frequency content)
+        self.decode_fsk_signal(&raw_data).await
+    }
+
+    /// Receive using Manchester encoding
+    async fn receive_manchester(&mut self) -> Result<Vec<u8>, LaserError> {
+        // Check alignment first
+        let alignment = self.get_alignment_status().await;
+        if !alignment.is_aligned {
+            return Err(LaserError::AlignmentLost);
+        }
+
+        // Receive raw signal
+        let raw_data = if self.rx_config.use_photodiode {
+            self.receive_photodiode().await?
+        } else if self.rx_config.use_camera {
+            self.receive_camera().await?
+        } else {
+            return Err(LaserError::ReceptionFailed);
+        };
+
+        // Recover the ECC-encoded bytes from the transitions, then correct them
+        let encoded = self.decode_manchester_signal(&raw_data).await?;
+        self.decode_with_ecc(&encoded).await
+    }
+
+    /// Receive using Differential Phase-Shift Keying. Only a photodiode samples
+    /// fast enough to follow the subcarrier.
+    pub async fn receive_dpsk(&mut self) -> Result<Vec<u8>, LaserError> {
+        let alignment = self.get_alignment_status().await;
+        if !alignment.is_aligned {
+            return Err(LaserError::AlignmentLost);
+        }
+        if !self.rx_config.use_photodiode {
+            return Err(LaserError::ReceptionFailed);
+        }
+
+        let symbols = self.receive_dpsk_frame().await?;
+        self.decode_with_ecc_erasures(&symbols).await
+    }
+
+    /// Receive one DPSK frame, sized from its protected frame header like
+    /// `receive_photodiode_frame`; every frame starts with its reference symbol.
+    async fn receive_dpsk_frame(&self) -> Result<Vec<(u8, f32)>, LaserError> {
+        let read_symbols = |count: usize| {
+            (0..count * LASER_DPSK_SAMPLES_PER_SYMBOL)
+                .map(|_| self.hardware.read_photodiode())
+                .collect::<Result<Vec<f32>, LaserError>>()
+        };
+
+        let mut samples = read_symbols(1 + 8 * crate::optical_ecc::FRAME_HEADER_LEN)?;
+        let symbols = pack_soft_ook_bits(&demodulate_dpsk_soft(&samples)?);
+
+        let header: Vec<u8> = symbols.iter().map(|&(byte, _)| byte).collect();
+        let frame_len = received_frame_len(&header, self.optical_ecc.is_some())?;
+        samples.extend(read_symbols(8 * (frame_len - symbols.len()))?);
+        Ok(pack_soft_ook_bits(&demodulate_dpsk_soft(&samples)?))
+    }
+
+    /// Decode FSK signal (simplified implementation)
+    async fn decode_fsk_signal(&self, _raw_data: &[u8]) -> Result<Vec<u8>, LaserError> {
+        // In a real implementation, this would perform FFT analysis
+        // to detect frequency shifts and decode the data
+        // For now, return mock decoded data
+        Ok(vec![0xAA, 0xBB, 0xCC]) // Mock data
+    }
+
+    /// Decode a raw Manchester sample buffer into the bytes `transmit_manchester` sent
+    async fn decode_manchester_signal(&self, raw_data: &[u8]) -> Result<Vec<u8>, LaserError> {
+        decode_manchester_samples(raw_data)
+    }
+
+    /// Prepend the coupling preamble with these Zadoff-Chu phases to every
+    /// transmission (`None` stops), so a coupled receiver can time this leg
+    /// against the ultrasound one by correlation
+    pub fn set_sync_preamble(&mut self, sequence: Option<Vec<u8>>) {
+        self.sync_preamble = sequence;
+    }
+
+    /// Emit the configured preamble waveform at its sample rate and hold for
+    /// its airtime
+    async fn emit_sync_preamble(&self) -> Result<(), LaserError> {
+        let Some(sequence) = &self.sync_preamble else {
+            return Ok(());
+        };
+        let samples = crate::channel_validator::preamble_waveform(sequence);
+        let sample_rate = crate::channel_validator::PREAMBLE_SYMBOL_RATE_HZ as u32
+            * crate::channel_validator::PREAMBLE_SAMPLES_PER_SYMBOL as u32;
+        let airtime = Duration::from_secs_f64(samples.len() as f64 / sample_rate as f64);
+        let emit_started = Instant::now();
+        self.emit_intensities(&samples, sample_rate).await?;
+        tokio::time::sleep(airtime.saturating_sub(emit_started.elapsed())).await;
+        Ok(())
+    }
+
+    /// Observe every intensity level the emitter is driven to (`None` detaches)
+    pub fn set_sample_sink(&mut self, sink: Option<Arc<dyn SampleSink>>) {
+        self.sample_sink = sink;
+    }
+
+    /// Set laser intensity (0.0 to 1.0)
+    async fn set_laser_intensity(&self, intensity: f32) -> Result<(), LaserError> {
+        // Safety check
+        if !(0.0..=1.0).contains(&intensity) {
+            return Err(LaserError::SafetyViolation);
+        }
+
+        // Get effective power limit from current profile
+        let effective_limit = self.get_effective_power_limit().await;
+        let power = intensity * effective_limit;
+
+        // Additional safety check against profile limits
+        let profile = self.current_power_profile.lock().await;
+        if power > profile.max_power_mw {
+            trace_error!(power_mw = power, max_power_mw = profile.max_power_mw, "laser safety violation: intensity above profile limit");
+            return Err(LaserError::SafetyViolation);
+        }
+
+        // Update safety monitor
+        let mut monitor = self.safety_monitor.lock().await;
+        let energy = power as f64 * 0.001; // Convert mW to Joules for 1ms pulse
+        monitor.total_energy_joules += energy;
+        monitor.last_activity = Instant::now();
+
+        // Hardware control
+        self.hardware.set_power(power)?;
+
+        if let Some(sink) = &self.sample_sink {
+            sink.record(intensity);
+        }
+
+        Ok(())
+    }
+
+    /// Batched counterpart of `set_laser_intensity`: check every level against the
+    /// profile, account the energy of the whole buffer and emit it in one call.
+    ///
+    /// `emit_samples` may block for the buffer's whole airtime, so it runs on the
+    /// blocking pool after the profile and safety monitor locks are released.
+    async fn emit_intensities(&self, intensities: &[f32], sample_rate: u32) -> Result<(), LaserError> {
+        if intensities.iter().any(|intensity| !(0.0..=1.0).contains(intensity)) {
+            return Err(LaserError::SafetyViolation);
+        }
+
+        let effective_limit = self.get_effective_power_limit().await;
+        let powers: Vec<f32> = intensities.iter().map(|intensity| intensity * effective_limit).collect();
+        let peak = powers.iter().copied().fold(0.0f32, f32::max);
+
+        let max_power_mw = self.current_power_profile.lock().await.max_power_mw;
+        if peak > max_power_mw {
+            trace_error!(power_mw = peak, max_power_mw, "laser safety violation: intensity above profile limit");
+            return Err(LaserError::SafetyViolation);
+        }
+
+        // Each sample holds its level for one sample period
+        {
+            let mut monitor = self.safety_monitor.lock().await;
+            let sample_period_s = 1.0 / sample_rate.max(1) as f64;
+            monitor.total_energy_joules += powers.iter().map(|&power| power as f64 * 0.001 * sample_period_s).sum::<f64>();
+            monitor.last_activity = Instant::now();
+        }
+
+        let hardware = self.hardware.clone();
+        tokio::task::spawn_blocking(move || hardware.emit_samples(&powers, sample_rate))
+            .await
+            .map_err(|_| LaserError::TransmissionFailed)??;
+
+        if let Some(sink) = &self.sample_sink {
+            intensities.iter().for_each(|&intensity| sink.record(intensity));
+        }
+
+        Ok(())
+    }
+
+    /// Encode data with error correction (OpticalECC if enabled, otherwise Reed-Solomon)
+    async fn encode_with_ecc(&mut self, data: &[u8]) -> Result<Vec<u8>, LaserError> {
+        if let Some(optical_ecc) = &mut self.optical_ecc {
+            // Use enhanced optical ECC
+            optical_ecc.encode(data).await
+                .map_err(|_| LaserError::DataCorruption)
+        } else {
+            // Fall back to basic Reed-Solomon behind a protected frame header
+            crate::optical_ecc::rs_encode_framed(&self.rs_codec, data)
+                .map_err(|_| LaserError::DataCorruption)
+        }
+    }
+
+    /// Decode data with error correction (OpticalECC if enabled, otherwise Reed-Solomon)
+    async fn decode_with_ecc(&mut self, data: &[u8]) -> Result<Vec<u8>, LaserError> {
+        if let Some(optical_ecc) = &mut self.optical_ecc {
+            // Use enhanced optical ECC
+            optical_ecc.decode(data).await
+                .map_err(|_| LaserError::DataCorruption)
+        } else {
+            // Fall back to basic Reed-Solomon, stripping shard padding via the frame header.
+            // Hard decisions locate no errors, so they tell the parity controller nothing.
+            crate::optical_ecc::rs_decode_framed(data, &vec![false; data.len()])
+                .map(|(payload, _)| payload)
+                .map_err(|_| LaserError::DataCorruption)
+        }
+    }
+
+    /// Decode demodulated `(byte, confidence)` symbols, passing bytes below the
+    /// configured confidence threshold to Reed-Solomon as erasures.
+    ///
+    /// Erasures are located, so the codec recovers one erased shard per parity
+    /// shard, where unflagged corruption goes uncorrected. Under optical ECC the
+    /// erasures reach its Reed-Solomon stage the same way.
+    pub async fn decode_with_ecc_erasures(&mut self, symbols: &[(u8, f32)]) -> Result<Vec<u8>, LaserError> {
+        let data: Vec<u8> = symbols.iter().map(|&(byte, _)| byte).collect();
+        let threshold = self.rx_config.erasure_confidence_threshold;
+        let erasures: Vec<bool> = symbols.iter().map(|&(_, confidence)| confidence < threshold).collect();
+        if let Some(optical_ecc) = &mut self.optical_ecc {
+            return optical_ecc.decode_with_erasures(&data, &erasures).await
+                .map_err(|_| LaserError::DataCorruption);
+        }
+
+        self.decode_rs_adapting(&data, &erasures)
+    }
+
+    /// Reed-Solomon decode with the shard layout the frame header announces,
+    /// then feed the fraction of shards flagged as erased to the adaptive parity
+    /// controller. A frame the code could not recover counts as one shard more
+    /// than the local parity covers.
+    fn decode_rs_adapting(&mut self, data: &[u8], erasures: &[bool]) -> Result<Vec<u8>, LaserError> {
+        let result = crate::optical_ecc::rs_decode_framed(data, erasures);
+
+        if self.adaptive_rs.is_some() {
+            let fraction = match &result {
+                Ok((_, header)) => {
+                    let total_shards = header.rs_shards.0 + header.rs_shards.1;
+                    erased_shard_count(erasures, total_shards) as f64 / total_shards as f64
+                }
+                Err(_) => (self.rs_codec.parity_shard_count() + 1) as f64 / self.rs_codec.total_shard_count() as f64,
+            };
+            self.record_corrected_fraction(fraction);
+        }
+        result.map(|(payload, _)| payload).map_err(|_| LaserError::DataCorruption)
+    }
+
+    /// Adapt the Reed-Solomon parity this engine transmits with to the erased
+    /// fraction seen on each soft-decision receive (a transmitter can also feed
+    /// in the fractions its peer reports via `record_corrected_fraction`). Each
+    /// frame announces its layout in its header, so the peer decodes whatever
+    /// parity was chosen without running a controller of its own.
+    pub fn set_adaptive_rs(&mut self, config: AdaptiveRsConfig) {
+        self.adaptive_rs = Some(RsParityController::new(config, self.rs_codec.parity_shard_count()));
+    }
+
+    /// Stop adapting, keeping the current Reed-Solomon parameters
+    pub fn clear_adaptive_rs(&mut self) {
+        self.adaptive_rs = None;
+    }
+
+    /// Current Reed-Solomon `(data_shards, parity_shards)`
+    pub fn rs_parameters(&self) -> (usize, usize) {
+        (self.rs_codec.data_shard_count(), self.rs_codec.parity_shard_count())
+    }
+
+    /// Feed one corrected-symbol measurement to the parity controller, re-creating
+    /// the codec when the parity count changes
+    pub fn record_corrected_fraction(&mut self, fraction: f64) {
+        let Some(controller) = &mut self.adaptive_rs else {
+            return;
+        };
+        let parity = controller.update(fraction, self.rs_codec.total_shard_count());
+        if parity == self.rs_codec.parity_shard_count() {
+            return;
+        }
+
+        let data = ADAPTIVE_RS_TOTAL_SHARDS - parity;
+        if let Ok(codec) = ReedSolomon::new(data, parity) {
+            trace_info!(data_shards = data, parity_shards = parity, fraction, "laser Reed-Solomon parity adapted");
+            self.rs_codec = codec;
+        } else {
+            trace_warn!(data_shards = data, parity_shards = parity, "rejected adaptive Reed-Solomon parameters");
+        }
+    }
+
+    /// Project QR code (laser projector control)
+    async fn project_qr_code(&self, _qr_svg: &str) -> Result<(), LaserError> {
+        // Would control laser projector to display QR code
+        // For now, just simulate
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        Ok(())
+    }
+
+    /// Check safety constraints
+    async fn check_safety(&self) -> Result<(), LaserError> {
+        let monitor = self.safety_monitor.lock().await;
+        let profile = self.current_power_profile.lock().await;
+
+        // Check eye safety limits based on current profile
+        let safe_limit = profile.safe_power_limit(&self.config.laser_type);
+        if profile.optimal_power_mw > safe_limit {
+            trace_error!(power_mw = profile.optimal_power_mw, safe_limit_mw = safe_limit, "laser safety violation: power above eye-safe limit");
+            return Err(LaserError::SafetyViolation);
+        }
+
+        // Check total energy usage
+        if monitor.total_energy_joules > 1000.0 { // 1kJ limit
+            trace_error!(energy_j = monitor.total_energy_joules, "laser safety violation: energy budget exceeded");
+            return Err(LaserError::SafetyViolation);
+        }
+
+        Ok(())
+    }
+
+    /// Get current alignment status
+    pub async fn get_alignment_status(&self) -> AlignmentStatus {
+        let tracker = self.alignment_tracker.lock().await;
+        let distance = ((tracker.target_position.0 - tracker.current_position.0).powi(2)
+                       + (tracker.target_position.1 - tracker.current_position.1).powi(2)).sqrt();
+
+        AlignmentStatus {
+            is_aligned: distance <= tracker.tolerance_px,
+            beam_position_x: tracker.current_position.0,
+            beam_position_y: tracker.current_position.1,
+            signal_strength: self.measure_signal_strength().await,
+            last_update: tracker.last_alignment_check,
+        }
+    }
+
+    /// Steer the beam with `servo` instead of the hardware's alignment offsets
+    pub fn set_servo_controller(&mut self, servo: Box<dyn servo::ServoController>) {
+        self.servo = Some(Arc::new(Mutex::new(servo)));
+    }
+
+    /// Set the 2x2 matrix mapping a camera-pixel alignment error `(x, y)` to the
+    /// `(pan, tilt)` correction in degrees
+    pub async fn set_alignment_calibration(&self, pixel_to_angle: [[f32; 2]; 2]) {
+        self.alignment_tracker.lock().await.pixel_to_angle = pixel_to_angle;
+    }
+
+    /// Set target alignment position
+    pub async fn set_alignment_target(&self, x: f32, y: f32) -> Result<(), LaserError> {
+        let mut tracker = self.alignment_tracker.lock().await;
+        tracker.target_position = (x, y);
+        tracker.last_alignment_check = Instant::now();
+        Ok(())
+    }
+
+    /// Perform automatic alignment with predictive tracking.
+    ///
+    /// When no signal is received the beam is considered lost. If the range
+    /// detector reports a bearing to the peer the beam is first panned onto it;
+    /// otherwise, or if that finds no signal, an acquisition search sweeps the
+    /// steering range. Tracking starts from wherever the peer is found.
+    pub async fn auto_align(&self, max_attempts: u32) -> Result<(), LaserError> {
+        let mut tracker = self.alignment_tracker.lock().await;
+
+        if self.measure_signal_strength().await < self.acquisition.signal_threshold
+            && (!self.steer_to_bearing(&mut tracker).await?
+                || self.measure_signal_strength().await < self.acquisition.signal_threshold)
+        {
+            self.acquire_beam(&mut tracker).await?;
+        }
+
+        for attempt in 0..max_attempts {
+            tracker.alignment_attempts = attempt + 1;
+
+            // Measure current position (would use camera feedback)
+            let current_pos = self.detect_beam_position().await?;
+            let measurement_time = Instant::now();
+
+            // Update position history
+            tracker.position_history.push_back((current_pos, measurement_time));
+            if tracker.position_history.len() > 20 {
+                tracker.position_history.pop_front();
+            }
+
+            // Update Kalman filter if enabled
+            if let Some(kalman) = &mut tracker.kalman_filter {
+                // Update with measurement
+                kalman.update(current_pos);
+
+                // Use filtered position
+                tracker.current_position = (kalman.state[0], kalman.state[1]);
+            } else {
+                tracker.current_position = current_pos;
+            }
+
+            // Estimate velocity from recent measurements (after position update)
+            if tracker.position_history.len() >= 2 {
+                self.update_velocity_estimate(&mut tracker).await;
+            }
+
+            tracker.last_alignment_check = measurement_time;
+
+            let distance = ((tracker.target_position.0 - tracker.current_position.0).powi(2)
+                           + (tracker.target_position.1 - tracker.current_position.1).powi(2)).sqrt();
+
+            if distance <= tracker.tolerance_px {
+                return Ok(());
+            }
+
+            // Predictive adjustment using velocity estimate
+            let adjustment = if tracker.prediction_enabled && tracker.position_history.len() >= 3 {
+                self.calculate_predictive_adjustment(&tracker).await
+            } else {
+                // Simple proportional adjustment
+                (tracker.target_position.0 - tracker.current_position.0,
+                 tracker.target_position.1 - tracker.current_position.1)
+            };
+
+            self.adjust_beam_position(&mut tracker, adjustment.0, adjustment.1).await?;
+
+            // Update Kalman filter prediction
+            if let Some(kalman) = &mut tracker.kalman_filter {
+                // Predict next position (50ms ahead)
+                kalman.predict(0.05);
+            }
+
+            // Small delay for stabilization
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+
+        Err(LaserError::AlignmentLost)
+    }
+
+    /// Pan the beam onto the bearing the range detector measures to the peer.
+    ///
+    /// The bearing is an azimuth, so the second transducer is assumed to sit on
+    /// the positive-pan side. Returns false when no bearing is available.
+    async fn steer_to_bearing(&self, tracker: &mut AlignmentTracker) -> Result<bool, LaserError> {
+        let Some(range_detector) = &self.range_detector else {
+            return Ok(false);
+        };
+        let Some(bearing) = range_detector.lock().await.measure_distance().await.ok()
+            .and_then(|measurement| measurement.angle_of_arrival_degrees)
+        else {
+            return Ok(false);
+        };
+
+        // Invert the pixel-to-angle calibration to move by (bearing - pan, 0) degrees
+        let [[a, b], [c, d]] = tracker.pixel_to_angle;
+        let determinant = a * d - b * c;
+        if determinant.abs() < f32::EPSILON {
+            return Ok(false);
+        }
+        let pan_error = bearing.clamp(-MAX_STEERING_DEG, MAX_STEERING_DEG) - tracker.pan_tilt_deg.0;
+        let (delta_x, delta_y) = (d * pan_error / determinant, -c * pan_error / determinant);
+        self.adjust_beam_position(tracker, delta_x, delta_y).await?;
+        tracker.current_position = (tracker.current_position.0 + delta_x, tracker.current_position.1 + delta_y);
+        Ok(true)
+    }
+
+    /// Sweep the configured search pattern until the peer's signal is detected
+    async fn acquire_beam(&self, tracker: &mut AlignmentTracker) -> Result<(), LaserError> {
+        for waypoint in acquisition_waypoints(&self.acquisition) {
+            let (delta_x, delta_y) = (waypoint.0 - tracker.current_position.0, waypoint.1 - tracker.current_position.1);
+            self.adjust_beam_position(tracker, delta_x, delta_y).await?;
+            tracker.current_position = waypoint;
+
+            if self.measure_signal_strength().await >= self.acquisition.signal_threshold {
+                // Motion estimates from before the loss no longer describe the peer
+                tracker.position_history.clear();
+                tracker.velocity_estimate = (0.0, 0.0);
+                if tracker.kalman_filter.is_some() {
+                    tracker.kalman_filter = Some(KalmanFilter::new());
+                }
+                tracker.last_alignment_check = Instant::now();
+                return Ok(());
+            }
+        }
+
+        trace_warn!(pattern = ?self.acquisition.pattern, range_px = self.acquisition.steering_range_px, "beam acquisition search found no signal");
+        Err(LaserError::AlignmentLost)
+    }
+
+    /// Update velocity estimate from position history
+    async fn update_velocity_estimate(&self, tracker: &mut AlignmentTracker) {
+        if tracker.position_history.len() < 2 {
+            return;
+        }
+
+        // Calculate velocity from recent measurements
+        let _len = tracker.position_history.len();
+        let recent_positions: Vec<&((f32, f32), Instant)> = tracker.position_history.iter().rev().take(3).collect();
+
+        if recent_positions.len() >= 2 {
+            let (pos1, time1) = recent_positions[0];
+            let (pos2, time2) = recent_positions[1];
+
+            let dt = time1.duration_since(*time2).as_secs_f32();
+            if dt > 0.0 {
+                let vx = (pos1.0 - pos2.0) / dt;
+                let vy = (pos1.1 - pos2.1) / dt;
+
+                // Smooth velocity estimate
+                tracker.velocity_estimate.0 = 0.7 * tracker.velocity_estimate.0 + 0.3 * vx;
+                tracker.velocity_estimate.1 = 0.7 * tracker.velocity_estimate.1 + 0.3 * vy;
+            }
+        }
+    }
+
+    /// Calculate predictive adjustment using velocity and Kalman prediction
+    async fn calculate_predictive_adjustment(&self, tracker: &AlignmentTracker) -> (f32, f32) {
+        // Look ahead further for fast-moving peers and less for static ones
+        let (vx, vy) = match &tracker.kalman_filter {
+            Some(kalman) => (kalman.state[2], kalman.state[3]),
+            None => tracker.velocity_estimate,
+        };
+        let dt = adaptive_prediction_horizon((vx * vx + vy * vy).sqrt(), &self.prediction_horizon);
+
+        // Use Kalman prediction if available
+        if let Some(kalman) = &tracker.kalman_filter {
+            let predicted_pos = kalman.predict_position(dt);
+            return (tracker.target_position.0 - predicted_pos.0,
+                    tracker.target_position.1 - predicted_pos.1);
+        }
+
+        // Fallback to velocity-based prediction
+        let predicted_x = tracker.current_position.0 + tracker.velocity_estimate.0 * dt;
+        let predicted_y = tracker.current_position.1 + tracker.velocity_estimate.1 * dt;
+
+        (tracker.target_position.0 - predicted_x,
+         tracker.target_position.1 - predicted_y)
+    }
+
+    /// Detect beam position using camera feedback
+    async fn detect_beam_position(&self) -> Result<(f32, f32), LaserError> {
+        // Would analyze camera frame to detect laser spot
+        // For now, return mock position
+        Ok((0.0, 0.0))
+    }
+
+    /// Move the beam by a pixel-space offset: through the servos, converted to
+    /// pan/tilt angles by the tracker's calibration, or as a hardware alignment offset
+    async fn adjust_beam_position(&self, tracker: &mut AlignmentTracker, delta_x: f32, delta_y: f32) -> Result<(), LaserError> {
+        let Some(servo) = &self.servo else {
+            return self.hardware.set_alignment(delta_x, delta_y);
+        };
+
+        let [[pan_x, pan_y], [tilt_x, tilt_y]] = tracker.pixel_to_angle;
+        let pan = (tracker.pan_tilt_deg.0 + pan_x * delta_x + pan_y * delta_y).clamp(-MAX_STEERING_DEG, MAX_STEERING_DEG);
+        let tilt = (tracker.pan_tilt_deg.1 + tilt_x * delta_x + tilt_y * delta_y).clamp(-MAX_STEERING_DEG, MAX_STEERING_DEG);
+        servo.lock().await.set_pan_tilt(pan, tilt)?;
+        tracker.pan_tilt_deg = (pan, tilt);
+        Ok(())
+    }
+
+    /// Fire the coded challenge and report whether the return is a passive
+    /// retroreflector (road sign, cat-eye) rather than an active receiver.
+    ///
+    /// The challenge is an m-sequence followed by its complement. A reflector's
+    /// echo follows every chip with one fixed gain, so it correlates with the
+    /// challenge; a receiver answers with its own modulation, which does not.
+    /// A flat return counts as no reflector. An inactive engine, or a chip that
+    /// cannot be emitted or read back, is an error: the check did not happen.
+    pub async fn detect_retroreflector(&self) -> Result<bool, LaserError> {
+        if !self.is_active().await {
+            return Err(LaserError::HardwareUnavailable);
+        }
+        let _emission = self.transmit_guard.lock().await;
+
+        let challenge = retroreflector_challenge();
+        let mut echo = Vec::with_capacity(challenge.len());
+        let mut fired = Ok(());
+        for &chip in &challenge {
+            let intensity = if chip { RETROREFLECTOR_CHALLENGE_INTENSITY } else { 0.0 };
+            fired = self.set_laser_intensity(intensity).await
+                .and_then(|()| self.hardware.read_photodiode())
+                .map(|reading| echo.push(reading));
+            if fired.is_err() {
+                break;
+            }
+        }
+        // The beam goes dark whether or not the challenge completed
+        let switched_off = self.set_laser_intensity(0.0).await;
+        fired?;
+        switched_off?;
+
+        let chips: Vec<f32> = challenge.iter().map(|&chip| if chip { 1.0 } else { 0.0 }).collect();
+        let mean_echo = |lit: bool| {
+            let readings: Vec<f32> = echo.iter().zip(&challenge).filter(|&(_, &chip)| chip == lit).map(|(&reading, _)| reading).collect();
+            readings.iter().sum::<f32>() / readings.len() as f32
+        };
+        let swing = mean_echo(true) - mean_echo(false);
+        let correlation = pearson_correlation(&chips, &echo);
+
+        let reflector = swing >= RETROREFLECTOR_MIN_SWING && correlation >= RETROREFLECTOR_CORRELATION_THRESHOLD;
+        if reflector {
+            trace_warn!(correlation, swing, "laser return mirrors the challenge; passive retroreflector");
+        }
+        Ok(reflector)
+    }
+
+    /// Measure signal strength
+    async fn measure_signal_strength(&self) -> f32 {
+        // Would measure received signal strength
+        // For now, return mock value
+        0.8
+    }
+
+    /// Read `count` photodiode samples, passed through the lock-in filter when a
+    /// carrier is configured. The sample rate follows from the bit rate and
+    /// `samples_per_bit`.
+    async fn read_photodiode_samples(&self, count: usize) -> Result<Vec<f32>, LaserError> {
+        let readings = (0..count)
+            .map(|_| self.hardware.read_photodiode())
+            .collect::<Result<Vec<f32>, LaserError>>()?;
+        let Some(carrier_hz) = self.rx_config.lock_in_carrier_hz else {
+            return Ok(readings);
+        };
+
+        let data_rate_bps = self.current_power_profile.lock().await.data_rate_bps;
+        let sample_rate_hz = data_rate_bps.saturating_mul(self.rx_config.samples_per_bit.max(1));
+        let mut filter = LockInFilter::new(carrier_hz, self.rx_config.bandpass_bandwidth_hz, sample_rate_hz)?;
+        Ok(filter.demodulate(&readings))
+    }
+
+    /// Receive using photodiode
+    async fn receive_photodiode(&self) -> Result<Vec<u8>, LaserError> {
+        // Integrate several analog readings per bit before deciding
+        let samples_per_bit = self.rx_config.samples_per_bit.max(1) as usize;
+        let readings = self.read_photodiode_samples(samples_per_bit).await?;
+        let digital_value = integrate_ook_bits(&readings, samples_per_bit, self.rx_config.sensitivity_threshold)[0];
+        Ok(vec![digital_value as u8])
+    }
+
+    /// Receive `byte_count` bytes via photodiode, each with its demodulation confidence
+    async fn receive_photodiode_soft(&self, byte_count: usize) -> Result<Vec<(u8, f32)>, LaserError> {
+        let samples_per_bit = self.rx_config.samples_per_bit.max(1) as usize;
+        let readings = self.read_photodiode_samples(samples_per_bit * 8 * byte_count).await?;
+        let bits = integrate_ook_bits_soft(&readings, samples_per_bit, self.rx_config.sensitivity_threshold);
+        Ok(pack_soft_ook_bits(&bits))
+    }
+
+    /// Receive one ECC frame via photodiode.
+    ///
+    /// Every frame opens with a protected header announcing its length, which
+    /// fixes how many more bytes to read.
+    async fn receive_photodiode_frame(&self) -> Result<Vec<(u8, f32)>, LaserError> {
+        let mut symbols = self.receive_photodiode_soft(crate::optical_ecc::FRAME_HEADER_LEN).await?;
+        let header: Vec<u8> = symbols.iter().map(|&(byte, _)| byte).collect();
+        let frame_len = received_frame_len(&header, self.optical_ecc.is_some())?;
+        symbols.extend(self.receive_photodiode_soft(frame_len - symbols.len()).await?);
+        Ok(symbols)
+    }
+
+    /// Receive using camera
+    async fn receive_camera(&self) -> Result<Vec<u8>, LaserError> {
+        self.hardware.capture_frame()
+    }
+
+    /// Power management: reduce power when not transmitting
+    pub async fn set_standby_mode(&self, standby: bool) -> Result<(), LaserError> {
+        if standby {
+            self.set_laser_intensity(0.0).await?;
+        }
+        Ok(())
+    }
+
+    /// Get safety statistics
+    pub async fn get_safety_stats(&self) -> (f64, u32, Duration) {
+        let monitor = self.safety_monitor.lock().await;
+        let _uptime = monitor.last_activity.elapsed();
+        (monitor.total_energy_joules, monitor.eye_safety_violations, _uptime)
+    }
+
+    /// Get current power consumption
+    pub async fn get_current_power_consumption(&self) -> f32 {
+        let profile = self.current_power_profile.lock().await;
+        profile.optimal_power_mw
+    }
+
+    /// Check if power usage is within safe limits
+    pub async fn is_power_safe(&self) -> bool {
+        let monitor = self.safety_monitor.lock().await;
+        let profile = self.current_power_profile.lock().await;
+
+        // Check energy limits
+        if monitor.total_energy_joules > 1000.0 {
+            return false;
+        }
+
+        // Check power limits
+        let safe_limit = profile.safe_power_limit(&self.config.laser_type);
+        if profile.optimal_power_mw > safe_limit {
+            return false;
+        }
+
+        true
+    }
+
+    /// Reset energy monitoring (for new sessions)
+    pub async fn reset_energy_monitoring(&self) {
+        let mut monitor = self.safety_monitor.lock().await;
+        monitor.total_energy_joules = 0.0;
+        monitor.eye_safety_violations = 0;
+        monitor.last_activity = Instant::now();
+    }
+
+    /// Get power efficiency metrics
+    pub async fn get_power_efficiency(&self) -> f32 {
+        let monitor = self.safety_monitor.lock().await;
+        let uptime_seconds = monitor.last_activity.elapsed().as_secs_f32();
+
+        if uptime_seconds > 0.0 {
+            // Efficiency as energy per second (lower is better)
+            (monitor.total_energy_joules as f32) / uptime_seconds
+        } else {
+            0.0
+        }
+    }
+
+    /// Emergency power shutdown
+    pub async fn emergency_shutdown(&self) -> Result<(), LaserError> {
+        // Force laser off immediately
+        self.set_laser_intensity(0.0).await?;
+
+        // Update safety monitor
+        let mut monitor = self.safety_monitor.lock().await;
+        monitor.eye_safety_violations += 1;
+
+        Ok(())
+    }
+
+    /// Monitor power usage and trigger safety actions if needed
+    pub async fn monitor_power_safety(&self) -> Result<(), LaserError> {
+        if !self.is_power_safe().await {
+            // Log safety violation
+            let mut monitor = self.safety_monitor.lock().await;
+            monitor.eye_safety_violations += 1;
+
+            // Emergency shutdown if violations exceed threshold
+            if monitor.eye_safety_violations > 3 {
+                return self.emergency_shutdown().await;
+            }
+
+            // Reduce power to safe levels
+            let profile = self.current_power_profile.lock().await;
+            let safe_limit = profile.safe_power_limit(&self.config.laser_type);
+
+            if profile.optimal_power_mw > safe_limit {
+                // Would adjust power profile here
+                // For now, just return error
+                return Err(LaserError::SafetyViolation);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Enable enhanced optical ECC with atmospheric adaptation
+    pub fn enable_optical_ecc(&mut self, config: AdaptiveECCConfig) -> Result<(), LaserError> {
+        self.optical_ecc = Some(OpticalECC::new(config));
+        Ok(())
+    }
+
+    /// Configuration of the enhanced optical ECC, if enabled
+    pub fn get_optical_ecc_config(&self) -> Option<&AdaptiveECCConfig> {
+        self.optical_ecc.as_ref().map(|ecc| ecc.get_config())
+    }
+
+    /// Disable optical ECC (fall back to basic Reed-Solomon)
+    pub fn disable_optical_ecc(&mut self) {
+        self.optical_ecc = None;
+    }
+
+    /// Check if optical ECC is enabled
+    pub fn is_optical_ecc_enabled(&self) -> bool {
+        self.optical_ecc.is_some()
+    }
+
+    /// Update optical quality metrics for adaptive ECC
+    pub async fn update_optical_quality(&mut self, metrics: OpticalQualityMetrics) -> Result<(), LaserError> {
+        if let Some(optical_ecc) = &mut self.optical_ecc {
+            optical_ecc.update_quality_metrics(metrics).await
+                .map_err(|_| LaserError::DataCorruption)?;
+        }
+        Ok(())
+    }
+
+    /// Update ECC strength based on current range detection
+    pub async fn update_ecc_for_range(&mut self) -> Result<(), LaserError> {
+        if self.optical_ecc.is_none() || self.range_detector.is_none() {
+            return Ok(());
+        }
+
+        let range_measurement = self.range_detector.as_ref().unwrap().lock().await
+            .measure_distance_averaged().await
+            .map_err(|_| LaserError::TransmissionFailed)?;
+
+        // Create optical quality metrics based on range
+        let metrics = OpticalQualityMetrics {
+            ber: 0.0, // Would be measured from actual transmission
+            per: 0.0,
+            signal_strength: 0.8, // Default good signal
+            atmospheric_attenuation: self.calculate_attenuation_for_range(range_measurement.distance_m),
+            turbulence_index: 0.1, // Low turbulence assumed
+            background_noise: 0.1,
+            range_meters: range_measurement.distance_m,
+            timestamp: Instant::now(),
+        };
+
+        self.update_optical_quality(metrics).await?;
+
+        if let Some(state) = self.get_optical_ecc_state().await {
+            let deciding_factor = if *state.current_condition() == crate::optical_ecc::AtmosphericCondition::Clear {
+                AdaptationFactor::RangeCategory
+            } else {
+                AdaptationFactor::Attenuation
+            };
+            let mut explanation = self.last_adaptation.lock().unwrap();
+            explanation.range_category = Some(RangeDetectorCategory::from_distance(range_measurement.distance_m));
+            explanation.ecc_strength = Some(AdaptationDecision { choice: state.ecc_strength(), deciding_factor });
+        }
+        Ok(())
+    }
+
+    /// Calculate atmospheric attenuation based on range
+    fn calculate_attenuation_for_range(&self, distance_m: f32) -> f32 {
+        // Simplified attenuation calculation
+        // In clear air, attenuation increases with distance and frequency
+        // This is a rough approximation
+        let base_attenuation = 0.1; // dB per 100m
+        let frequency_factor = (self.config.wavelength_nm as f32 / 650.0).powi(2); // Higher frequency = more attenuation
+
+        base_attenuation * (distance_m / 100.0) * frequency_factor
+    }
+
+    /// Start continuous range monitoring and profile switching
+    ///
+    /// A task left over from an earlier call is stopped first, so sequential
+    /// sessions never run more than one monitor.
+    pub async fn start_continuous_monitoring(&self) -> Result<(), LaserError> {
+        if !self.adaptive_mode || self.range_detector.is_none() {
+            return Err(LaserError::HardwareUnavailable);
+        }
+        self.stop_continuous_monitoring().await?;
+
+        // Spawn a background task for continuous monitoring
+        let range_detector = self.range_detector.as_ref().unwrap().clone();
+        let current_profile = self.current_power_profile.clone();
+        let power_ramp = self.power_ramp;
+        let (stop, mut stop_requested) = tokio::sync::watch::channel(false);
+
+        let handle = tokio::spawn(async move {
+            let mut last_range_category: Option<RangeDetectorCategory> = None;
+
+            while !*stop_requested.borrow() {
+                // Measure distance
+                let measurement_result = range_detector.lock().await.measure_distance_averaged().await;
+
+                match measurement_result {
+                    Ok(measurement) => {
+                        let current_category = RangeDetectorCategory::from_distance(measurement.distance_m);
+
+                        // Check if range category changed
+                        if last_range_category != Some(current_category) {
+                            trace_info!(from = ?last_range_category, to = ?current_category,
+                                        distance_m = measurement.distance_m, "range category changed");
+
+                            // Ramp power profile towards the new range
+                            let new_profile = PowerProfile::for_range_category(&current_category);
+                            ramp_power_profile(&current_profile, new_profile, power_ramp).await;
+
+                            last_range_category = Some(current_category);
+                        }
+                    }
+                    Err(_e) => {
+                        trace_warn!(error = %_e, "range measurement failed");
+                        // Continue monitoring despite errors
+                    }
+                }
+
+                // Monitor every 2 seconds, waking early when asked to stop
+                tokio::select! {
+                    _ = tokio::time::sleep(Duration::from_secs(2)) => {}
+                    _ = stop_requested.changed() => {}
+                }
+            }
+        });
+
+        *self.monitoring_task.lock().await = Some(MonitoringTask { stop, handle });
+        Ok(())
+    }
+
+    /// Signal the monitoring task to exit and wait for it to finish
+    pub async fn stop_continuous_monitoring(&self) -> Result<(), LaserError> {
+        let Some(task) = self.monitoring_task.lock().await.take() else {
+            return Ok(());
+        };
+
+        // The task may already have exited, in which case nobody is listening
+        let _ = task.stop.send(true);
+        if let Err(_e) = task.handle.await {
+            trace_warn!(error = %_e, "range monitoring task ended abnormally");
+        }
+        Ok(())
+    }
+
+    /// Whether a continuous monitoring task is currently running
+    pub async fn is_monitoring_active(&self) -> bool {
+        self.monitoring_task.lock().await.as_ref().is_some_and(|task| !task.handle.is_finished())
+    }
+
+    /// Get current monitoring status
+    pub async fn get_monitoring_status(&self) -> (bool, Option<RangeDetectorCategory>) {
+        let is_adaptive = self.adaptive_mode;
+        let current_category = if let Some(range_detector) = &self.range_detector {
+            range_detector.lock().await.get_current_range_category().await
+        } else {
+            None
+        };
+
+        (is_adaptive, current_category)
+    }
+
+    /// Update environmental conditions for compensation
+    pub async fn update_environmental_conditions(&self, weather: WeatherCondition, visibility_m: f32) -> Result<(), LaserError> {
+        if self.range_detector.is_none() {
+            return Err(LaserError::HardwareUnavailable);
+        }
+
+        // Convert weather condition to environmental parameters
+        let (temperature, humidity, pressure, wind_speed) = match weather {
+            WeatherCondition::Clear => (20.0, 50.0, 1013.25, 2.0),
+            WeatherCondition::Rain => (15.0, 85.0, 1008.0, 5.0),
+            WeatherCondition::Fog => (10.0, 98.0, 1010.0, 1.0),
+            WeatherCondition::Storm => (12.0, 95.0, 1005.0, 8.0),
+            WeatherCondition::Snow => (0.0, 90.0, 1012.0, 3.0),
+            WeatherCondition::HeavyRain => (14.0, 95.0, 1006.0, 7.0),
+            WeatherCondition::LightRain => (16.0, 80.0, 1009.0, 4.0),
+            WeatherCondition::Cloudy => (18.0, 60.0, 1012.0, 2.5),
+        };
+
+        let conditions = RangeEnvironmentalConditions {
+            temperature_celsius: temperature,
+            humidity_percent: humidity,
+            pressure_hpa: pressure,
+            wind_speed_mps: wind_speed,
+            visibility_meters: visibility_m,
+        };
+
+        self.range_detector.as_ref().unwrap().lock().await
+            .update_environmental_conditions(conditions).await;
+
+        // Update power profile based on environmental conditions
+        self.adjust_power_for_weather(weather, visibility_m).await?;
+
+        Ok(())
+    }
+
+    /// Adjust power profile based on weather conditions
+    async fn adjust_power_for_weather(&self, weather: WeatherCondition, visibility_m: f32) -> Result<(), LaserError> {
+        let mut base = self.weather_base_power_mw.lock().await;
+        let mut profile = self.current_power_profile.lock().await;
+
+        // Compensate from the uncompensated power, so repeated updates replace
+        // rather than compound each other
+        let base_mw = *base.get_or_insert(profile.optimal_power_mw);
+        profile.optimal_power_mw = base_mw * weather_power_multiplier(&weather, visibility_m);
+
+        // Ensure we don't exceed absolute safety limits
+        let laser_type_limit = profile.safe_power_limit(&self.config.laser_type);
+        profile.optimal_power_mw = profile.optimal_power_mw.min(laser_type_limit);
+
+        Ok(())
+    }
+
+    /// Get environmental impact on transmission
+    pub async fn get_environmental_impact(&self) -> Option<(WeatherCondition, f32, f32)> {
+        let range_detector = self.range_detector.as_ref()?;
+        let conditions = range_detector.lock().await.get_environmental_conditions().await;
+
+        // Infer weather condition from environmental data
+        let weather = self.infer_weather_from_conditions(&conditions);
+        let visibility = conditions.visibility_meters;
+        let attenuation_factor = self.calculate_environmental_attenuation(&conditions);
+
+        Some((weather, visibility, attenuation_factor))
+    }
+
+    /// Infer weather condition from environmental parameters
+    fn infer_weather_from_conditions(&self, conditions: &RangeEnvironmentalConditions) -> WeatherCondition {
+        if conditions.humidity_percent > 95.0 && conditions.temperature_celsius < 15.0 {
+            WeatherCondition::Fog
+        } else if conditions.temperature_celsius < 5.0 && conditions.humidity_percent > 80.0 {
+            WeatherCondition::Snow
+        } else if conditions.humidity_percent > 90.0 && conditions.pressure_hpa < 1010.0 {
+            WeatherCondition::HeavyRain
+        } else if conditions.humidity_percent > 75.0 && conditions.pressure_hpa < 1012.0 {
+            WeatherCondition::LightRain
+        } else if conditions.humidity_percent > 60.0 {
+            WeatherCondition::Cloudy
+        } else {
+            WeatherCondition::Clear
+        }
+    }
+
+    /// Calculate environmental attenuation factor
+    fn calculate_environmental_attenuation(&self, conditions: &RangeEnvironmentalConditions) -> f32 {
+        // Simplified environmental attenuation model
+        let humidity_factor = conditions.humidity_percent / 100.0;
+        let temperature_factor = (20.0 - conditions.temperature_celsius).abs() / 20.0; // Deviation from 20°C
+        let pressure_factor = (1013.25 - conditions.pressure_hpa).abs() / 10.0; // Deviation from standard pressure
+
+        // Combine factors (simplified model)
+        1.0 + humidity_factor * 0.5 + temperature_factor * 0.3 + pressure_factor * 0.2
+    }
+
+    /// Power budget for a link of `distance_m` at the full effective power
+    /// under the range detector's current conditions (standard clear air
+    /// without a detector).
+    ///
+    /// The beam spreads from `beam_diameter_mm` at `LASER_BEAM_DIVERGENCE_RAD`
+    /// and only the share falling on the receiver aperture is collected;
+    /// atmospheric loss follows the visibility (Kim model) scaled by the
+    /// humidity/temperature/pressure factor of `calculate_environmental_attenuation`.
+    pub async fn estimate_link_budget(&self, distance_m: f32) -> LinkBudget {
+        let conditions = match &self.range_detector {
+            Some(detector) => detector.lock().await.get_environmental_conditions().await,
+            None => RangeEnvironmentalConditions::default(),
+        };
+        let distance_m = distance_m.max(0.0);
+
+        let transmit_power_dbm = 10.0 * self.get_effective_power_limit().await.max(f32::MIN_POSITIVE).log10();
+        let beam_diameter_mm = self.config.beam_diameter_mm + LASER_BEAM_DIVERGENCE_RAD * distance_m * 1000.0;
+        let geometric_loss_db = (20.0 * (beam_diameter_mm / RECEIVER_APERTURE_MM).log10()).max(0.0);
+        let atmospheric_loss_db = kim_attenuation_db_per_km(conditions.visibility_meters, self.config.wavelength_nm)
+            * distance_m / 1000.0
+            * self.calculate_environmental_attenuation(&conditions);
+
+        let received_power_dbm = transmit_power_dbm - geometric_loss_db - atmospheric_loss_db;
+        let sensitivity_margin_db = received_power_dbm - RECEIVER_SENSITIVITY_DBM;
+        LinkBudget {
+            distance_m,
+            transmit_power_dbm,
+            geometric_loss_db,
+            atmospheric_loss_db,
+            received_power_dbm,
+            sensitivity_margin_db,
+            link_closes: sensitivity_margin_db >= 0.0,
+        }
+    }
+
+    /// Get recommended safety margins for current conditions
+    pub async fn get_safety_margins(&self) -> (f32, f32, f32) {
+        // Return (power_margin, range_margin, alignment_margin)
+        if let Some((weather, visibility, attenuation)) = self.get_environmental_impact().await {
+            let power_margin = match weather {
+                WeatherCondition::Clear => 1.0,
+                WeatherCondition::Rain => 1.5,
+                WeatherCondition::Fog => 3.0,
+                WeatherCondition::Storm => 2.0,
+                WeatherCondition::Snow => 2.5,
+                WeatherCondition::HeavyRain => 2.0,
+                WeatherCondition::LightRain => 1.3,
+                WeatherCondition::Cloudy => 1.1,
+            };
+
+            let range_margin = if visibility < 500.0 { 0.8 } else { 1.0 };
+            let alignment_margin = attenuation.clamp(1.0, 2.0);
+
+            (power_margin, range_margin, alignment_margin)
+        } else {
+            (1.0, 1.0, 1.0) // Default margins
+        }
+    }
+
+    /// Get current optical ECC adaptation state
+    pub async fn get_optical_ecc_state(&self) -> Option<crate::optical_ecc::AdaptationState> {
+        if let Some(optical_ecc) = &self.optical_ecc {
+            Some(optical_ecc.get_adaptation_state().await)
+        } else {
+            None
+        }
+    }
+
+    /// Detect specific laser channel failures
+    pub async fn detect_channel_failures(&self) -> Vec<LaserError> {
+        let mut failures = Vec::new();
+
+        // Check if laser is active
+        if !self.is_active().await {
+            failures.push(LaserError::HardwareUnavailable);
+            return failures;
+        }
+
+        // Check alignment status
+        let alignment = self.get_alignment_status().await;
+        if !alignment.is_aligned {
+            failures.push(LaserError::AlignmentLost);
+        }
+
+        // Check signal strength
+        if alignment.signal_strength < 0.2 {
+            failures.push(LaserError::TransmissionFailed);
+        }
+
+        // Check safety violations
+        if !self.is_power_safe().await {
+            failures.push(LaserError::SafetyViolation);
+        }
+
+        // Check for beam obstruction (low signal despite good alignment)
+        if alignment.is_aligned && alignment.signal_strength < 0.3 {
+            failures.push(LaserError::DataCorruption); // Indicates obstruction
+        }
+
+        failures
+    }
+
+    /// Get detailed channel diagnostics
+    pub async fn get_channel_diagnostics(&self) -> LaserChannelDiagnostics {
+        let alignment = self.get_alignment_status().await;
+        let power_stats = (self.get_current_power_consumption().await,
+                          self.get_power_efficiency().await,
+                          self.is_power_safe().await);
+        let failures = self.detect_channel_failures().await;
+
+        LaserChannelDiagnostics {
+            is_active: self.is_active().await,
+            alignment_status: alignment,
+            power_consumption_mw: power_stats.0,
+            power_efficiency: power_stats.1,
+            power_safe: power_stats.2,
+            battery_state: None, // Would be populated by battery monitoring system
+            power_statistics: PowerStatistics {
+                total_energy_consumed_joules: self.safety_monitor.lock().await.total_energy_joules,
+                average_power_mw: power_stats.0,
+                peak_power_mw: self.current_power_profile.lock().await.max_power_mw,
+                duty_cycle_percent: 50.0, // Would be calculated from actual usage
+                efficiency_rating: power_stats.1,
+            },
+            detected_failures: failures,
+            optical_ecc_enabled: self.is_optical_ecc_enabled(),
+            adaptive_mode: self.is_adaptive_mode(),
+        }
+    }
+
+    /// Enable adaptive power mode with range detector
+    pub fn enable_adaptive_mode(&mut self, range_detector: Arc<Mutex<RangeDetector>>) {
+        self.range_detector = Some(range_detector);
+        self.adaptive_mode = true;
+    }
+
+    /// Attach a range detector for on-demand measurements without enabling adaptive mode
+    pub fn set_range_detector(&mut self, range_detector: Arc<Mutex<RangeDetector>>) {
+        self.range_detector = Some(range_detector);
+    }
+
+    /// The attached range detector, if any
+    pub fn range_detector(&self) -> Option<Arc<Mutex<RangeDetector>>> {
+        self.range_detector.clone()
+    }
+
+    /// Get the transmitter configuration
+    pub fn get_config(&self) -> &LaserConfig {
+        &self.config
+    }
+
+    /// Get the receiver configuration
+    pub fn get_reception_config(&self) -> &ReceptionConfig {
+        &self.rx_config
+    }
+
+    /// Perform range measurement and update power profile
+    pub async fn measure_range_and_update_power(&self) -> Result<(), LaserError> {
+        if !self.adaptive_mode || self.range_detector.is_none() {
+            return Err(LaserError::HardwareUnavailable);
+        }
+
+        let range_detector = self.range_detector.as_ref().unwrap();
+        let measurement = range_detector.lock().await.measure_distance_averaged().await
+            .map_err(|_| LaserError::TransmissionFailed)?;
+
+        // Update power profile based on measured range
+        let category = RangeDetectorCategory::from_distance(measurement.distance_m);
+        let new_profile = PowerProfile::for_range_category(&category);
+        *self.weather_base_power_mw.lock().await = Some(new_profile.optimal_power_mw);
+
+        // Apply environmental compensation if available
+        if let Some((weather, visibility, attenuation)) = self.get_environmental_impact().await {
+            let mut adjusted_profile = new_profile;
+            let weather_multiplier = match weather {
+                WeatherCondition::Clear => 1.0,
+                WeatherCondition::Rain => 1.5,
+                WeatherCondition::Fog => 3.0,
+                WeatherCondition::Storm => 2.0,
+                WeatherCondition::Snow => 2.5,
+                WeatherCondition::HeavyRain => 2.0,
+                WeatherCondition::LightRain => 1.3,
+                WeatherCondition::Cloudy => 1.1,
+            };
+
+            let visibility_multiplier = if visibility < 100.0 {
+                3.0
+            } else if visibility < 500.0 {
+                2.0
+            } else if visibility < 1000.0 {
+                1.5
+            } else {
+                1.0
+            };
+
+            let environmental_factor = weather_multiplier * visibility_multiplier;
+            adjusted_profile.optimal_power_mw *= environmental_factor;
+            let deciding_factor = if adjusted_profile.optimal_power_mw > adjusted_profile.max_power_mw {
+                AdaptationFactor::PowerLimit
+            } else if environmental_factor <= 1.0 {
+                AdaptationFactor::RangeCategory
+            } else if visibility_multiplier >= weather_multiplier {
+                AdaptationFactor::Visibility
+            } else {
+                AdaptationFactor::Weather
+            };
+            adjusted_profile.optimal_power_mw = adjusted_profile.optimal_power_mw.min(adjusted_profile.max_power_mw);
+
+            {
+                let mut explanation = self.last_adaptation.lock().unwrap();
+                explanation.range_category = Some(category);
+                explanation.environmental_impact = Some((weather, visibility, attenuation));
+                explanation.power_mw = Some(AdaptationDecision { choice: adjusted_profile.optimal_power_mw, deciding_factor });
+            }
+            ramp_power_profile(&self.current_power_profile, adjusted_profile, self.power_ramp).await;
+        } else {
+            {
+                let mut explanation = self.last_adaptation.lock().unwrap();
+                explanation.range_category = Some(category);
+                explanation.power_mw = Some(AdaptationDecision {
+                    choice: new_profile.optimal_power_mw,
+                    deciding_factor: AdaptationFactor::RangeCategory,
+                });
+            }
+            ramp_power_profile(&self.current_power_profile, new_profile, self.power_ramp).await;
+        }
+
+        Ok(())
+    }
+
+    /// Configure how adaptive profile changes ramp between power levels
+    pub fn set_power_ramp_config(&mut self, config: PowerRampConfig) {
+        self.power_ramp = config;
+    }
+
+    /// Get the current power ramp configuration
+    pub fn get_power_ramp_config(&self) -> PowerRampConfig {
+        self.power_ramp
+    }
+
+    /// Configure transmit power logging and the regulatory class it is checked against
+    pub fn set_power_log_config(&mut self, config: PowerLogConfig) {
+        self.power_log_config = config;
+    }
+
+    /// Get the current power logging configuration
+    pub fn get_power_log_config(&self) -> PowerLogConfig {
+        self.power_log_config
+    }
+
+    /// Snapshot of the logged emissions, oldest first
+    pub async fn power_log(&self) -> Vec<PowerLogEntry> {
+        self.power_log.lock().await.iter().cloned().collect()
+    }
+
+    /// Clear the power log (e.g. after a report has been archived)
+    pub async fn clear_power_log(&self) {
+        self.power_log.lock().await.clear();
+    }
+
+    /// Append an emission to the power log, evicting the oldest beyond capacity
+    async fn record_power_sample(&self, timestamp: Instant, power_mw: f32, duration: Duration) {
+        let config = self.power_log_config;
+        if !config.enabled || config.max_entries == 0 {
+            return;
+        }
+
+        let class_limit_mw = config.regulatory_class.max_power_mw();
+        if power_mw > class_limit_mw {
+            trace_warn!(power_mw, class_limit_mw, "laser emission above regulatory class limit");
+        }
+
+        let mut log = self.power_log.lock().await;
+        while log.len() >= config.max_entries {
+            log.pop_front();
+        }
+        log.push_back(PowerLogEntry {
+            timestamp,
+            power_mw,
+            duration,
+            wavelength_nm: self.config.wavelength_nm,
+        });
+    }
+
+    /// Summarize the emissions logged within the last `window`
+    ///
+    /// Average power is weighted by emission duration. Every sample above the
+    /// configured regulatory class limit is listed in `exceedances`.
+    pub async fn compliance_report(&self, window: Duration) -> ComplianceReport {
+        let regulatory_class = self.power_log_config.regulatory_class;
+        let limit_mw = regulatory_class.max_power_mw();
+        let now = Instant::now();
+
+        let log = self.power_log.lock().await;
+        let samples: Vec<&PowerLogEntry> = log.iter()
+            .filter(|entry| now.saturating_duration_since(entry.timestamp) <= window)
+            .collect();
+
+        let peak_power_mw = samples.iter().map(|entry| entry.power_mw).fold(0.0f32, f32::max);
+        let total_emission_time: Duration = samples.iter().map(|entry| entry.duration).sum();
+        let weighted_mw_s: f64 = samples.iter()
+            .map(|entry| entry.power_mw as f64 * entry.duration.as_secs_f64())
+            .sum();
+        let average_power_mw = if total_emission_time.is_zero() {
+            // Instantaneous samples only: fall back to a plain mean
+            if samples.is_empty() {
+                0.0
+            } else {
+                samples.iter().map(|entry| entry.power_mw).sum::<f32>() / samples.len() as f32
+            }
+        } else {
+            (weighted_mw_s / total_emission_time.as_secs_f64()) as f32
+        };
+
+        let exceedances: Vec<PowerLogEntry> = samples.iter()
+            .filter(|entry| entry.power_mw > limit_mw)
+            .map(|entry| (*entry).clone())
+            .collect();
+        let time_above_threshold = exceedances.iter().map(|entry| entry.duration).sum();
+
+        ComplianceReport {
+            window,
+            regulatory_class,
+            limit_mw,
+            sample_count: samples.len(),
+            peak_power_mw,
+            average_power_mw,
+            total_emission_time,
+            time_above_threshold,
+            compliant: exceedances.is_empty(),
+            exceedances,
+        }
+    }
+
+    /// Set how overlapping transmissions on the same transmitter are handled
+    pub fn set_transmit_busy_policy(&mut self, policy: TransmitBusyPolicy) {
+        self.busy_policy = policy;
+    }
+
+    /// Get the emission guard for this engine's transmitter
+    pub fn transmitter_guard(&self) -> Arc<Mutex<()>> {
+        self.transmit_guard.clone()
+    }
+
+    /// Share an emission guard with other engines driving the same laser diode
+    pub fn set_transmitter_guard(&mut self, guard: Arc<Mutex<()>>) {
+        self.transmit_guard = guard;
+    }
+
+    /// Configure the velocity-dependent look-ahead used by predictive alignment
+    pub fn set_prediction_horizon_config(&mut self, config: PredictionHorizonConfig) {
+        self.prediction_horizon = config;
+    }
+
+    /// Get the current prediction horizon configuration
+    pub fn get_prediction_horizon_config(&self) -> PredictionHorizonConfig {
+        self.prediction_horizon
+    }
+
+    /// Configure the search used to re-acquire a lost beam
+    pub fn set_acquisition_config(&mut self, config: AcquisitionConfig) {
+        self.acquisition = config;
+    }
+
+    /// Get the current acquisition search configuration
+    pub fn get_acquisition_config(&self) -> AcquisitionConfig {
+        self.acquisition
+    }
+
+    /// Get current range measurement from detector
+    pub async fn get_current_range_measurement(&self) -> Option<RangeMeasurement> {
+        if let Some(range_detector) = &self.range_detector {
+            range_detector.lock().await.get_measurement_history().await.last().cloned()
+        } else {
+            None
+        }
+    }
+
+    /// Update range detector with current environmental conditions
+    pub async fn update_range_detector_environment(&self, weather: WeatherCondition, visibility_m: f32) -> Result<(), LaserError> {
+        if self.range_detector.is_none() {
+            return Err(LaserError::HardwareUnavailable);
+        }
+
+        // Convert weather to environmental parameters
+        let (temperature, humidity, pressure, wind_speed) = match weather {
+            WeatherCondition::Clear => (20.0, 50.0, 1013.25, 2.0),
+            WeatherCondition::Rain => (15.0, 85.0, 1008.0, 5.0),
+            WeatherCondition::Fog => (10.0, 98.0, 1010.0, 1.0),
+            WeatherCondition::Storm => (12.0, 95.0, 1005.0, 8.0),
+            WeatherCondition::Snow => (0.0, 90.0, 1012.0, 3.0),
+            WeatherCondition::HeavyRain => (14.0, 95.0, 1006.0, 7.0),
+            WeatherCondition::LightRain => (16.0, 80.0, 1009.0, 4.0),
+            WeatherCondition::Cloudy => (18.0, 60.0, 1012.0, 2.5),
+        };
+
+        let conditions = RangeEnvironmentalConditions {
+            temperature_celsius: temperature,
+            humidity_percent: humidity,
+            pressure_hpa: pressure,
+            wind_speed_mps: wind_speed,
+            visibility_meters: visibility_m,
+        };
+
+        self.range_detector.as_ref().unwrap().lock().await
+            .update_environmental_conditions(conditions).await;
+
+        Ok(())
+    }
+
+    /// Disable adaptive power mode
+    pub async fn disable_adaptive_mode(&mut self) {
+        self.range_detector = None;
+        self.adaptive_mode = false;
+        // Reset to default profile
+        *self.current_power_profile.lock().await = PowerProfile::default();
+    }
+
+    /// Check if adaptive mode is enabled
+    pub fn is_adaptive_mode(&self) -> bool {
+        self.adaptive_mode
+    }
+
+    /// Update power profile based on current range measurement
+    pub async fn update_power_profile(&self) -> Result<(), LaserError> {
+        if !self.adaptive_mode || self.range_detector.is_none() {
+            return Ok(());
+        }
+
+        let range_detector = self.range_detector.as_ref().unwrap();
+        let range_category = range_detector.lock().await.get_current_range_category().await;
+
+        if let Some(category) = range_category {
+            let new_profile = PowerProfile::for_range_category(&category);
+            {
+                let mut explanation = self.last_adaptation.lock().unwrap();
+                explanation.range_category = Some(category);
+                explanation.power_mw = Some(AdaptationDecision {
+                    choice: new_profile.optimal_power_mw,
+                    deciding_factor: AdaptationFactor::RangeCategory,
+                });
+            }
+            *self.current_power_profile.lock().await = new_profile;
+        }
+
+        Ok(())
+    }
+
+    /// Get current power profile
+    pub async fn get_current_power_profile(&self) -> PowerProfile {
+        self.current_power_profile.lock().await.clone()
+    }
+
+    /// Manually set power profile
+    pub async fn set_power_profile(&self, profile: PowerProfile) -> Result<(), LaserError> {
+        // Validate profile against laser type safety limits
+        let safe_limit = profile.safe_power_limit(&self.config.laser_type);
+        if profile.optimal_power_mw > safe_limit {
+            trace_warn!(power_mw = profile.optimal_power_mw, safe_limit_mw = safe_limit, "rejected unsafe power profile");
+            return Err(LaserError::SafetyViolation);
+        }
+
+        *self.weather_base_power_mw.lock().await = Some(profile.optimal_power_mw);
+        *self.current_power_profile.lock().await = profile;
+        Ok(())
+    }
+
+    /// Get effective power limit considering current profile and safety
+    pub async fn get_effective_power_limit(&self) -> f32 {
+        let profile = self.current_power_profile.lock().await;
+        let safe_limit = profile.safe_power_limit(&self.config.laser_type);
+        profile.max_power_mw.min(safe_limit)
+    }
+
+    /// Select optimal modulation scheme based on range, conditions, and performance metrics.
+    ///
+    /// With interference avoidance enabled, a scheme whose spectral lines land in
+    /// the coupled ultrasound bands is swapped for the next one that stays clear.
+    pub async fn select_optimal_modulation(&self) -> ModulationScheme {
+        let (preferred, mut deciding_factor) = self.preferred_modulation().await;
+        let mut scheme = preferred;
+        if self.interference_avoidance.load(std::sync::atomic::Ordering::Relaxed) {
+            scheme = avoid_band_interference(preferred, self.config.data_rate_bps, &self.interference_bands);
+            if interferes_with_bands(preferred, self.config.data_rate_bps, &self.interference_bands)
+                && !interferes_with_bands(scheme, self.config.data_rate_bps, &self.interference_bands)
+            {
+                deciding_factor = AdaptationFactor::UltrasoundInterference;
+            }
+        }
+
+        self.last_adaptation.lock().unwrap().modulation = Some(AdaptationDecision { choice: scheme, deciding_factor });
+        scheme
+    }
+
+    /// Inputs and outcomes of the latest modulation, power and ECC adaptations,
+    /// each with the factor that settled it
+    pub fn explain_last_adaptation(&self) -> AdaptationExplanation {
+        self.last_adaptation.lock().unwrap().clone()
+    }
+
+    /// Keep (or stop keeping) laser modulation out of the ultrasound control and
+    /// self-demodulation bands
+    pub fn set_interference_avoidance(&self, enabled: bool) {
+        self.interference_avoidance.store(enabled, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    pub fn is_interference_avoidance_enabled(&self) -> bool {
+        self.interference_avoidance.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// Replace the bands avoided, e.g. after retuning the ultrasound beam
+    pub fn set_interference_bands(&mut self, bands: Vec<SpectralBand>) {
+        self.interference_bands = bands;
+    }
+
+    /// Scheme picked for range and conditions alone, with the input that decided it
+    async fn preferred_modulation(&self) -> (ModulationScheme, AdaptationFactor) {
+        if !self.adaptive_mode || self.range_detector.is_none() {
+            return (self.config.modulation, AdaptationFactor::Configuration);
+        }
+
+        let range_category = self.range_detector.as_ref().unwrap().lock().await
+            .get_current_range_category().await;
+
+        // Get environmental conditions for modulation selection
+        let environmental_impact = self.get_environmental_impact().await;
+        let signal_quality = self.measure_signal_strength().await;
+
+        // Advanced modulation selection based on multiple factors
+        let attenuation = environmental_impact.as_ref().map(|(_, _, att)| *att);
+        let visibility = environmental_impact.as_ref().map(|(_, vis, _)| *vis);
+        let choice = match range_category {
+            Some(RangeDetectorCategory::Close) => {
+                // Close range (<50m): Prioritize speed
+                if signal_quality <= 0.8 {
+                    (ModulationScheme::Manchester, AdaptationFactor::SignalQuality) // Better noise immunity
+                } else if attenuation.is_some_and(|att| att >= 1.2) {
+                    (ModulationScheme::Manchester, AdaptationFactor::Attenuation)
+                } else {
+                    (ModulationScheme::Ook, AdaptationFactor::RangeCategory) // Highest speed
+                }
+            }
+            Some(RangeDetectorCategory::Medium) => {
+                // Medium range (50-100m): Balance speed and reliability
+                if signal_quality >= DPSK_MIN_SIGNAL_QUALITY {
+                    (ModulationScheme::Dpsk, AdaptationFactor::SignalQuality) // Spectrally efficient on a clean link
+                } else if signal_quality > 0.6 {
+                    (ModulationScheme::Pwm, AdaptationFactor::RangeCategory) // Good balance
+                } else {
+                    (ModulationScheme::Fsk, AdaptationFactor::SignalQuality) // Better for moderate interference
+                }
+            }
+            Some(RangeDetectorCategory::Far) => {
+                // Far range (100-150m): Prioritize reliability
+                if visibility.is_some_and(|vis| vis < 300.0) {
+                    // Poor visibility: Use most robust scheme
+                    (ModulationScheme::QrProjection, AdaptationFactor::Visibility)
+                } else {
+                    (ModulationScheme::Manchester, AdaptationFactor::RangeCategory) // Good robustness for distance
+                }
+            }
+            Some(RangeDetectorCategory::Extreme) => {
+                // Extreme range (150-200m): Maximum robustness
+                (ModulationScheme::QrProjection, AdaptationFactor::RangeCategory) // Best error correction and robustness
+            }
+            None => {
+                // No range data: Use environmental conditions to decide
+                if signal_quality < 0.5 {
+                    (ModulationScheme::QrProjection, AdaptationFactor::SignalQuality)
+                } else if attenuation.is_some_and(|att| att > 1.5) {
+                    (ModulationScheme::Manchester, AdaptationFactor::Attenuation)
+                } else {
+                    (self.config.modulation, AdaptationFactor::Configuration)
+                }
+            }
+        };
+
+        let mut explanation = self.last_adaptation.lock().unwrap();
+        explanation.range_category = range_category;
+        explanation.signal_quality = Some(signal_quality);
+        explanation.environmental_impact = environmental_impact;
+        choice
+    }
+
+    /// Update modulation scheme based on current conditions
+    pub async fn update_modulation_scheme(&self) -> Result<(), LaserError> {
+        if !self.adaptive_mode {
+            return Ok(());
+        }
+
+        let optimal_scheme = self.select_optimal_modulation().await;
+        // Note: In a real implementation, this would update the modulation scheme
+        // For now, we just validate that the scheme is supported
+        match optimal_scheme {
+            ModulationScheme::Ook | ModulationScheme::Pwm | ModulationScheme::QrProjection |
+            ModulationScheme::Fsk | ModulationScheme::Manchester | ModulationScheme::Dpsk => Ok(()),
+        }
+    }
+
+    /// Advanced power management: optimize power usage based on battery state and requirements
+    pub async fn optimize_power_usage(&mut self, battery_state: Option<&BatteryState>) -> Result<(), LaserError> {
+        let mut profile = self.current_power_profile.lock().await;
+
+        if let Some(battery) = battery_state {
+            // Adaptive power scaling based on battery level
+            let power_multiplier = if battery.capacity_percent < 20.0 {
+                // Emergency power mode - reduce power significantly
+                0.3
+            } else if battery.capacity_percent < 50.0 {
+                // Power saving mode
+                0.6
+            } else if battery.estimated_runtime_hours < 2.0 {
+                // Low runtime - moderate power reduction
+                0.8
+            } else {
+                // Normal operation
+                1.0
+            };
+
+            // Apply temperature compensation
+            let temp_multiplier = if battery.temperature_celsius > 40.0 {
+                // High temperature - reduce power to prevent overheating
+                0.7
+            } else if battery.temperature_celsius < 0.0 {
+                // Cold temperature - may need more power for efficiency
+                1.1
+            } else {
+                1.0
+            };
+
+            let total_multiplier = power_multiplier * temp_multiplier;
+            profile.optimal_power_mw *= total_multiplier;
+            profile.optimal_power_mw = profile.optimal_power_mw.min(profile.max_power_mw);
+            profile.optimal_power_mw = profile.optimal_power_mw.max(profile.min_power_mw);
+        }
+
+        Ok(())
+    }
+
+    /// Calculate optimal duty cycle for power efficiency
+    pub async fn calculate_optimal_duty_cycle(&self, data_rate_bps: u32, required_power_mw: f32) -> f32 {
+        // Duty cycle optimization for pulsed operation
+        // Higher data rates may require higher duty cycles
+        let base_duty_cycle = (data_rate_bps as f32 / 1_000_000.0).min(1.0); // Max 100% at 1Mbps
+
+        // Adjust based on power requirements
+        let power_factor = (required_power_mw / self.current_power_profile.lock().await.max_power_mw).min(1.0);
+
+        (base_duty_cycle * power_factor).clamp(0.1, 1.0) // Keep between 10% and 100%
+    }
+
+    /// Implement burst transmission mode for power saving
+    pub async fn enable_burst_mode(&mut self, burst_duration_ms: u32, idle_duration_ms: u32) -> Result<(), LaserError> {
+        // Configure burst transmission pattern
+        // This would modify the transmission timing to use short bursts with idle periods
+        // to reduce average power consumption
+
+        // Calculate burst efficiency
+        let total_cycle = burst_duration_ms + idle_duration_ms;
+        let duty_cycle = burst_duration_ms as f32 / total_cycle as f32;
+
+        // Adjust power profile for burst mode
+        let mut profile = self.current_power_profile.lock().await;
+        profile.optimal_power_mw /= duty_cycle.sqrt(); // Compensate for burst power requirements
+
+        Ok(())
+    }
+
+    /// Monitor and predict battery drain
+    pub async fn predict_battery_drain(&self, operation_duration_seconds: f32) -> f32 {
+        let current_power = self.get_current_power_consumption().await;
+        let energy_consumed_joules = current_power as f32 * operation_duration_seconds / 1000.0;
+
+        // Convert to battery percentage (simplified model)
+        // Assuming 3000mAh battery at 3.7V = ~11.1Wh = 40,000J
+        const BATTERY_CAPACITY_JOULES: f32 = 40_000.0;
+        (energy_consumed_joules / BATTERY_CAPACITY_JOULES) * 100.0
+    }
+
+    /// Get power management recommendations
+    pub async fn get_power_recommendations(&self, battery_state: Option<&BatteryState>) -> Vec<String> {
+        let mut recommendations = Vec::new();
+
+        if let Some(battery) = battery_state {
+            if battery.capacity_percent < 15.0 {
+                recommendations.push("Battery critically low. Switching to emergency power mode.".to_string());
+            } else if battery.capacity_percent < 30.0 {
+                recommendations.push("Battery low. Consider reducing transmission power.".to_string());
+            }
+
+            if battery.estimated_runtime_hours < 1.0 {
+                recommendations.push("Estimated runtime very low. Enable burst mode for power saving.".to_string());
+            }
+
+            if battery.temperature_celsius > 45.0 {
+                recommendations.push("High battery temperature detected. Reducing power to prevent damage.".to_string());
+            }
+        }
+
+        let efficiency = self.get_power_efficiency().await;
+        if efficiency < 0.5 {
+            recommendations.push("Low power efficiency detected. Consider duty cycle optimization.".to_string());
+        }
+
+        recommendations
+    }
+
+    /// Emergency power shutdown with graceful degradation
+    pub async fn emergency_power_shutdown(&self) -> Result<(), LaserError> {
+        // Reduce power to minimum safe level first
+        self.set_laser_intensity(0.0).await?;
+
+        // Log emergency shutdown
+        let mut monitor = self.safety_monitor.lock().await;
+        monitor.eye_safety_violations += 1;
+
+        // In a real implementation, this would:
+        // 1. Save current state
+        // 2. Notify application of emergency
+        // 3. Switch to ultra-low power mode
+        // 4. Prepare for graceful recovery
+
+        Ok(())
+    }
+
+    /// Calculate power budget for a given operation
+    pub async fn calculate_power_budget(&self, operation: &str, duration_seconds: f32) -> PowerBudget {
+        let current_power = self.get_current_power_consumption().await;
+        let energy_required = current_power as f64 * duration_seconds as f64 / 1000.0; // Joules
+
+        let battery_capacity = 40_000.0; // 40kJ typical battery capacity
+        let available_energy = battery_capacity * 0.8; // 80% usable capacity
+
+        let can_complete = energy_required <= available_energy;
+        let estimated_drain_percent = (energy_required / battery_capacity * 100.0) as f32;
+
+        PowerBudget {
+            operation: operation.to_string(),
+            energy_required_joules: energy_required,
+            estimated_duration_seconds: duration_seconds,
+            can_complete_operation: can_complete,
+            estimated_battery_drain_percent: estimated_drain_percent,
+            recommended_power_level_mw: if can_complete {
+                current_power
+            } else {
+                (available_energy / duration_seconds as f64 * 1000.0) as f32
+            },
+        }
+    }
+}
+
+/// Power budget analysis for operations
+#[derive(Debug, Clone)]
+pub struct PowerBudget {
+    pub operation: String,
+    pub energy_required_joules: f64,
+    pub estimated_duration_seconds: f32,
+    pub can_complete_operation: bool,
+    pub estimated_battery_drain_percent: f32,
+    pub recommended_power_level_mw: f32,
+}
+
+/// Laser hazard class an installation is certified for (IEC 60825-1)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RegulatoryClass {
+    /// Safe under all conditions of normal use
+    Class1,
+    /// Visible, protected by the blink reflex
+    Class2,
+    /// Low risk for direct intrabeam viewing
+    #[default]
+    Class3R,
+    /// Hazardous for direct intrabeam viewing
+    Class3B,
+}
+
+impl RegulatoryClass {
+    /// Continuous-wave accessible emission limit in mW
+    pub fn max_power_mw(&self) -> f32 {
+        match self {
+            RegulatoryClass::Class1 => 0.39,
+            RegulatoryClass::Class2 => 1.0,
+            RegulatoryClass::Class3R => 5.0,
+            RegulatoryClass::Class3B => 500.0,
+        }
+    }
+}
+
+/// Transmit power logging settings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PowerLogConfig {
+    pub enabled: bool,
+    /// Oldest entries are evicted beyond this many samples
+    pub max_entries: usize,
+    /// Class every logged sample is checked against
+    pub regulatory_class: RegulatoryClass,
+}
+
+impl Default for PowerLogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_entries: 4096,
+            regulatory_class: RegulatoryClass::default(),
+        }
+    }
+}
+
+/// One logged laser emission
+#[derive(Debug, Clone, PartialEq)]
+pub struct PowerLogEntry {
+    pub timestamp: Instant,
+    /// Full-intensity optical power during the emission
+    pub power_mw: f32,
+    pub duration: Duration,
+    pub wavelength_nm: u32,
+}
+
+/// Optical power budget of a laser link, from `LaserEngine::estimate_link_budget`
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkBudget {
+    pub distance_m: f32,
+    pub transmit_power_dbm: f32,
+    /// Beam spread past the receiver aperture
+    pub geometric_loss_db: f32,
+    pub atmospheric_loss_db: f32,
+    pub received_power_dbm: f32,
+    /// Received power above `RECEIVER_SENSITIVITY_DBM`; negative when the link fails
+    pub sensitivity_margin_db: f32,
+    pub link_closes: bool,
+}
+
+/// Emitted-power summary for a reporting window
+#[derive(Debug, Clone)]
+pub struct ComplianceReport {
+    pub window: Duration,
+    pub regulatory_class: RegulatoryClass,
+    pub limit_mw: f32,
+    pub sample_count: usize,
+    pub peak_power_mw: f32,
+    /// Duration-weighted average over the logged emissions
+    pub average_power_mw: f32,
+    pub total_emission_time: Duration,
+    pub time_above_threshold: Duration,
+    /// Samples above the regulatory class limit
+    pub exceedances: Vec<PowerLogEntry>,
+    pub compliant: bool,
+}
+
+/// Background range monitor started by `start_continuous_monitoring`
+struct MonitoringTask {
+    /// Set to `true` to ask the task to leave its loop
+    stop: tokio::sync::watch::Sender<bool>,
+    handle: tokio::task::JoinHandle<()>,
+}
+
+/// Handling of a transmission requested while another is emitting
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TransmitBusyPolicy {
+    /// Wait for the current emission to finish
+    #[default]
+    Queue,
+    /// Fail immediately with `LaserError::TransmitterBusy`
+    Reject,
+}
+
+/// Ramp settings for adaptive power-profile transitions
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PowerRampConfig {
+    /// Total time taken to move from the old to the new optimal power
+    pub duration: Duration,
+    /// Number of intermediate power levels applied during the ramp
+    pub steps: u32,
+}
+
+impl Default for PowerRampConfig {
+    fn default() -> Self {
+        Self {
+            duration: Duration::from_millis(200),
+            steps: 8,
+        }
+    }
+}
+
+/// Look-ahead settings for predictive beam alignment
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PredictionHorizonConfig {
+    /// Horizon used for a static peer (seconds)
+    pub min_horizon_s: f32,
+    /// Upper bound on the horizon for very fast peers (seconds)
+    pub max_horizon_s: f32,
+    /// Extra look-ahead per px/s of estimated peer speed (seconds per px/s)
+    pub velocity_gain: f32,
+}
+
+impl Default for PredictionHorizonConfig {
+    fn default() -> Self {
+        Self {
+            min_horizon_s: 0.02,
+            max_horizon_s: 0.3,
+            velocity_gain: 0.001,
+        }
+    }
+}
+
+/// Prediction horizon for a peer moving at `speed_px_s`
+pub fn adaptive_prediction_horizon(speed_px_s: f32, config: &PredictionHorizonConfig) -> f32 {
+    (config.min_horizon_s + config.velocity_gain * speed_px_s.abs())
+        .clamp(config.min_horizon_s, config.max_horizon_s)
+}
+
+/// Scan order used to search the steering range for a lost beam
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SearchPattern {
+    /// Square spiral outward from the center, finding near-center peers first
+    #[default]
+    Spiral,
+    /// Row-by-row sweep from one corner, alternating direction each row
+    Raster,
+}
+
+/// Degrees of beam deflection per camera pixel: a 60 degree field of view over
+/// 640 pixels, with image y pointing down and positive tilt up
+pub const DEFAULT_PIXEL_TO_ANGLE: [[f32; 2]; 2] = [[60.0 / 640.0, 0.0], [0.0, -60.0 / 640.0]];
+/// Furthest the servos are commanded from center on either axis
+pub const MAX_STEERING_DEG: f32 = 90.0;
+
+/// 15-chip maximal-length sequence (x^4 + x + 1) used as the retroreflector challenge
+const RETROREFLECTOR_SEQUENCE: [bool; 15] = [
+    false, false, false, true, false, false, true, true, false, true, false, true, true, true, true,
+];
+/// Relative intensity of the challenge's on chips
+pub const RETROREFLECTOR_CHALLENGE_INTENSITY: f32 = 0.2;
+/// Correlation with the challenge above which a return is a passive mirror
+pub const RETROREFLECTOR_CORRELATION_THRESHOLD: f32 = 0.9;
+/// Smallest on/off difference in the return that counts as an echo at all
+pub const RETROREFLECTOR_MIN_SWING: f32 = 0.05;
+
+/// The m-sequence followed by its complement, so a peer repeating one fixed
+/// pattern cannot correlate with the whole challenge
+fn retroreflector_challenge() -> Vec<bool> {
+    RETROREFLECTOR_SEQUENCE.iter().copied().chain(RETROREFLECTOR_SEQUENCE.iter().map(|&chip| !chip)).collect()
+}
+
+/// Pearson correlation of two equally long series; 0 when either is constant
+fn pearson_correlation(a: &[f32], b: &[f32]) -> f32 {
+    let n = a.len().min(b.len());
+    if n == 0 {
+        return 0.0;
+    }
+    let mean_a = a[..n].iter().sum::<f32>() / n as f32;
+    let mean_b = b[..n].iter().sum::<f32>() / n as f32;
+    let (mut covariance, mut var_a, mut var_b) = (0.0, 0.0, 0.0);
+    for (&x, &y) in a[..n].iter().zip(&b[..n]) {
+        covariance += (x - mean_a) * (y - mean_b);
+        var_a += (x - mean_a).powi(2);
+        var_b += (y - mean_b).powi(2);
+    }
+    if var_a <= f32::EPSILON || var_b <= f32::EPSILON {
+        return 0.0;
+    }
+    covariance / (var_a * var_b).sqrt()
+}
+
+/// Acquisition search settings for `auto_align`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AcquisitionConfig {
+    pub pattern: SearchPattern,
+    /// Half-width of the square steering range around the center (px)
+    pub steering_range_px: f32,
+    /// Spacing between probed positions; keep below the beam spot diameter (px)
+    pub step_px: f32,
+    /// Signal strength at which the peer counts as found
+    pub signal_threshold: f32,
+}
+
+impl Default for AcquisitionConfig {
+    fn default() -> Self {
+        Self {
+            pattern: SearchPattern::Spiral,
+            steering_range_px: 100.0,
+            step_px: 5.0,
+            signal_threshold: 0.3,
+        }
+    }
+}
+
+/// Beam positions probed by the acquisition search, in scan order
+pub fn acquisition_waypoints(config: &AcquisitionConfig) -> Vec<(f32, f32)> {
+    if config.step_px <= 0.0 || config.steering_range_px < 0.0 {
+        return Vec::new();
+    }
+    let steps = (config.steering_range_px / config.step_px).floor() as i32;
+    let at = |ix: i32, iy: i32| (ix as f32 * config.step_px, iy as f32 * config.step_px);
+
+    match config.pattern {
+        SearchPattern::Spiral => {
+            // Legs of length 1, 1, 2, 2, 3, 3, ... turning left after each leg
+            let side = (2 * steps + 1) as usize;
+            let mut waypoints = Vec::with_capacity(side * side);
+            let (mut ix, mut iy) = (0i32, 0i32);
+            let (mut dx, mut dy) = (1i32, 0i32);
+            let mut leg = 1;
+            waypoints.push(at(ix, iy));
+            while waypoints.len() < side * side {
+                for _ in 0..2 {
+                    for _ in 0..leg {
+                        ix += dx;
+                        iy += dy;
+                        if ix.abs() <= steps && iy.abs() <= steps {
+                            waypoints.push(at(ix, iy));
+                        }
+                    }
+                    (dx, dy) = (-dy, dx);
+                }
+                leg += 1;
+            }
+            waypoints
+        }
+        SearchPattern::Raster => (-steps..=steps)
+            .flat_map(|iy| {
+                let row: Vec<i32> = if (iy + steps) % 2 == 0 {
+                    (-steps..=steps).collect()
+                } else {
+                    (-steps..=steps).rev().collect()
+                };
+                row.into_iter().map(move |ix| (ix, iy))
+            })
+            .map(|(ix, iy)| at(ix, iy))
+            .collect(),
+    }
+}
+
+/// Run the acquisition search against `probe`, which steers the beam to a position
+/// and returns the signal strength received there; yields the first position at
+/// which the signal reaches the threshold
+pub fn search_for_beam<F: FnMut((f32, f32)) -> f32>(config: &AcquisitionConfig, mut probe: F) -> Option<(f32, f32)> {
+    acquisition_waypoints(config)
+        .into_iter()
+        .find(|&position| probe(position) >= config.signal_threshold)
+}
+
+/// Input that settled an adaptive choice
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdaptationFactor {
+    /// Adaptive mode off or nothing to adapt on: the configured value stands
+    Configuration,
+    RangeCategory,
+    SignalQuality,
+    Visibility,
+    Weather,
+    Attenuation,
+    /// The preferred scheme overlapped the ultrasound bands
+    UltrasoundInterference,
+    /// Compensation would have exceeded the profile's maximum power
+    PowerLimit,
+}
+
+/// One adaptive choice and the factor that decided it
+#[derive(Debug, Clone)]
+pub struct AdaptationDecision<T> {
+    pub choice: T,
+    pub deciding_factor: AdaptationFactor,
+}
+
+/// What the adaptive selectors last saw and chose
+#[derive(Debug, Clone, Default)]
+pub struct AdaptationExplanation {
+    pub range_category: Option<RangeDetectorCategory>,
+    /// Measured signal quality, 0.0 to 1.0
+    pub signal_quality: Option<f32>,
+    /// Inferred weather, visibility in meters and attenuation factor
+    pub environmental_impact: Option<(WeatherCondition, f32, f32)>,
+    pub modulation: Option<AdaptationDecision<ModulationScheme>>,
+    /// Optimal transmit power in mW
+    pub power_mw: Option<AdaptationDecision<f32>>,
+    /// Optical ECC strength, 0.0 to 1.0
+    pub ecc_strength: Option<AdaptationDecision<f32>>,
+}
+
+/// Tone for a 0 bit in laser FSK
+pub const LASER_FSK_BASE_HZ: f32 = 1000.0;
+/// Tone spacing for a 1 bit in laser FSK
+pub const LASER_FSK_OFFSET_HZ: f32 = 500.0;
+/// Frame rate of dynamic QR projection
+pub const QR_PROJECTION_FRAME_RATE_HZ: f32 = 30.0;
+/// Intensity subcarrier DPSK symbols are phase-modulated onto
+pub const LASER_DPSK_SUBCARRIER_HZ: f32 = 10_000.0;
+/// Drive samples per subcarrier cycle
+pub const LASER_DPSK_SAMPLES_PER_CYCLE: usize = 8;
+/// Whole subcarrier cycles per DPSK symbol, so every symbol starts on the same
+/// carrier phase (2.5 kbaud at 10 kHz)
+pub const LASER_DPSK_CYCLES_PER_SYMBOL: usize = 4;
+/// Drive and photodiode samples per DPSK symbol
+pub const LASER_DPSK_SAMPLES_PER_SYMBOL: usize = LASER_DPSK_SAMPLES_PER_CYCLE * LASER_DPSK_CYCLES_PER_SYMBOL;
+/// Medium-range signal quality from which adaptive mode prefers DPSK
+pub const DPSK_MIN_SIGNAL_QUALITY: f32 = 0.75;
+/// Harmonics of each modulation line checked against the avoided bands
+pub const INTERFERENCE_HARMONICS: u32 = 5;
+
+/// Frequency range the laser drive should keep its spectral lines out of
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpectralBand {
+    pub low_hz: f32,
+    pub high_hz: f32,
+}
+
+impl SpectralBand {
+    pub fn contains(&self, frequency_hz: f32) -> bool {
+        frequency_hz >= self.low_hz && frequency_hz <= self.high_hz
+    }
+}
+
+/// Bands used by the coupled parametric ultrasound link: the audible product it
+/// self-demodulates at the modulation frequency (±20%), and each ultrasonic
+/// carrier (±2 kHz) the control channel is detected on
+pub fn ultrasound_interference_bands(beam: &crate::ultrasonic_beam::BeamConfig) -> Vec<SpectralBand> {
+    let product = beam.modulation_frequency;
+    std::iter::once(SpectralBand { low_hz: product * 0.8, high_hz: product * 1.2 })
+        .chain(beam.fundamental_bands.iter().map(|&carrier| SpectralBand {
+            low_hz: carrier - 2000.0,
+            high_hz: carrier + 2000.0,
+        }))
+        .collect()
+}
+
+/// Fundamental frequencies of the intensity waveform a scheme produces at
+/// `data_rate_bps`; harmonics are integer multiples of these
+pub fn modulation_spectral_lines(scheme: ModulationScheme, data_rate_bps: u32) -> Vec<f32> {
+    let rate = data_rate_bps as f32;
+    match scheme {
+        // Alternating bits toggle at half the bit rate
+        ModulationScheme::Ook | ModulationScheme::Manchester => vec![rate / 2.0],
+        ModulationScheme::Pwm => vec![rate],
+        ModulationScheme::Fsk => vec![LASER_FSK_BASE_HZ, LASER_FSK_BASE_HZ + LASER_FSK_OFFSET_HZ],
+        ModulationScheme::QrProjection => vec![QR_PROJECTION_FRAME_RATE_HZ],
+        ModulationScheme::Dpsk => vec![LASER_DPSK_SUBCARRIER_HZ],
+    }
+}
+
+/// Whether any of the first `INTERFERENCE_HARMONICS` harmonics of the scheme's
+/// lines falls inside one of `bands`
+pub fn interferes_with_bands(scheme: ModulationScheme, data_rate_bps: u32, bands: &[SpectralBand]) -> bool {
+    modulation_spectral_lines(scheme, data_rate_bps).iter().any(|&line| {
+        (1..=INTERFERENCE_HARMONICS).any(|n| bands.iter().any(|band| band.contains(line * n as f32)))
+    })
+}
+
+/// `preferred` if it stays clear of `bands`, otherwise the first clear scheme in
+/// order of decreasing throughput; `preferred` when nothing is clear
+pub fn avoid_band_interference(preferred: ModulationScheme, data_rate_bps: u32, bands: &[SpectralBand]) -> ModulationScheme {
+    const FALLBACK_ORDER: [ModulationScheme; 6] = [
+        ModulationScheme::Ook,
+        ModulationScheme::Pwm,
+        ModulationScheme::Dpsk,
+        ModulationScheme::Manchester,
+        ModulationScheme::Fsk,
+        ModulationScheme::QrProjection,
+    ];
+
+    if !interferes_with_bands(preferred, data_rate_bps, bands) {
+        return preferred;
+    }
+    match FALLBACK_ORDER.into_iter().find(|&scheme| !interferes_with_bands(scheme, data_rate_bps, bands)) {
+        Some(scheme) => scheme,
+        None => {
+            trace_warn!(data_rate_bps, "every modulation scheme overlaps the ultrasound bands");
+            preferred
+        }
+    }
+}
+
+/// Decide OOK bits from photodiode readings, `samples_per_bit` readings per bit.
+///
+/// Each bit is the mean of its readings (the matched filter for a rectangular
+/// pulse) compared against `threshold`, so white noise on the decision shrinks
+/// by the square root of `samples_per_bit`. A trailing partial bit is ignored.
+pub fn integrate_ook_bits(readings: &[f32], samples_per_bit: usize, threshold: f32) -> Vec<bool> {
+    integrate_ook_bits_soft(readings, samples_per_bit, threshold)
+        .into_iter()
+        .map(|(bit, _)| bit)
+        .collect()
+}
+
+/// Like `integrate_ook_bits`, also returning a confidence per bit.
+///
+/// Readings are normalized to 0.0 (off) and 1.0 (on); confidence is the distance of
+/// the integrated level from `threshold` relative to the distance to the decided
+/// level, so 0.0 is a coin toss and 1.0 a clean pulse.
+pub fn integrate_ook_bits_soft(readings: &[f32], samples_per_bit: usize, threshold: f32) -> Vec<(bool, f32)> {
+    let samples_per_bit = samples_per_bit.max(1);
+    readings.chunks_exact(samples_per_bit)
+        .map(|bit| {
+            let level = bit.iter().sum::<f32>() / samples_per_bit as f32;
+            let on = level > threshold;
+            let span = if on { 1.0 - threshold } else { threshold };
+            let confidence = ((level - threshold).abs() / span.max(f32::EPSILON)).min(1.0);
+            (on, confidence)
+        })
+        .collect()
+}
+
+/// Fractional bits of the lock-in filter coefficients
+const LOCK_IN_COEFF_SHIFT: u32 = 30;
+/// Photodiode readings (0.0..=1.0) become Q15 samples
+const LOCK_IN_SAMPLE_SCALE: f32 = 32768.0;
+/// Maps the rectified fundamental of a 0/1 square wave back to 1.0: the
+/// fundamental has amplitude 4/π·½ and its rectified mean is 2/π of that
+const LOCK_IN_ENVELOPE_GAIN: f32 = std::f32::consts::PI * std::f32::consts::PI / 4.0;
+
+/// Lock-in receiver for a chopped OOK carrier.
+///
+/// A second-order IIR bandpass (RBJ, 0 dB peak) centered on the carrier removes
+/// sunlight (DC) and lamp flicker (100/120 Hz and harmonics); the rectified output
+/// is the carrier envelope, scaled so a fully lit bit integrates to ~1.0 and the
+/// usual OOK thresholds apply. Samples and state are Q15 and coefficients Q30, so
+/// the per-sample work is five integer multiplies.
+pub struct LockInFilter {
+    b0: i64,
+    a1: i64,
+    a2: i64,
+    x: [i32; 2],
+    y: [i32; 2],
+}
+
+impl LockInFilter {
+    pub fn new(carrier_hz: u32, bandwidth_hz: u32, sample_rate_hz: u32) -> Result<Self, LaserError> {
+        if carrier_hz == 0 || bandwidth_hz == 0 || 2 * carrier_hz as u64 >= sample_rate_hz as u64 {
+            return Err(LaserError::InvalidConfiguration(format!(
+                "lock-in carrier {carrier_hz} Hz (bandwidth {bandwidth_hz} Hz) needs a sample rate above twice the carrier, got {sample_rate_hz} Hz"
+            )));
+        }
+
+        let w0 = 2.0 * std::f64::consts::PI * carrier_hz as f64 / sample_rate_hz as f64;
+        let alpha = w0.sin() * bandwidth_hz as f64 / (2.0 * carrier_hz as f64);
+        let a0 = 1.0 + alpha;
+        let fixed = |coefficient: f64| (coefficient / a0 * (1u64 << LOCK_IN_COEFF_SHIFT) as f64).round() as i64;
+        Ok(Self {
+            b0: fixed(alpha),
+            a1: fixed(-2.0 * w0.cos()),
+            a2: fixed(1.0 - alpha),
+            x: [0; 2],
+            y: [0; 2],
+        })
+    }
+
+    /// Filter one reading and return the carrier envelope at that sample
+    pub fn process(&mut self, reading: f32) -> f32 {
+        let x0 = (reading * LOCK_IN_SAMPLE_SCALE) as i32;
+        // b1 = 0 and b2 = -b0, so DC cancels exactly
+        let acc = self.b0 * (x0 as i64 - self.x[1] as i64) - self.a1 * self.y[0] as i64 - self.a2 * self.y[1] as i64;
+        let y0 = (acc >> LOCK_IN_COEFF_SHIFT) as i32;
+        self.x = [x0, self.x[0]];
+        self.y = [y0, self.y[0]];
+        (y0 as f32 / LOCK_IN_SAMPLE_SCALE).abs() * LOCK_IN_ENVELOPE_GAIN
+    }
+
+    pub fn demodulate(&mut self, readings: &[f32]) -> Vec<f32> {
+        readings.iter().map(|&reading| self.process(reading)).collect()
+    }
+}
+
+/// Pack OOK bits into bytes, most significant bit first
+pub fn pack_ook_bits(bits: &[bool]) -> Vec<u8> {
+    bits.chunks(8)
+        .map(|byte| byte.iter().enumerate().fold(0u8, |acc, (i, &bit)| acc | ((bit as u8) << (7 - i))))
+        .collect()
+}
+
+/// Decode IEEE 802.3 Manchester samples (`01` = 0, `10` = 1, MSB first) into bytes.
+///
+/// Samples are sliced at the midpoint of their range and grouped into runs. Every
+/// bit cell has a mid-bit transition, so the first run is one half-bit long and
+/// sets the sample rate; longer runs are rounded to whole half-bits. A cell with
+/// no transition (`00` or `11`) means the clock slipped and is `DataCorruption`.
+pub fn decode_manchester_samples(samples: &[u8]) -> Result<Vec<u8>, LaserError> {
+    if samples.is_empty() {
+        return Ok(Vec::new());
+    }
+    let (low, high) = samples.iter().fold((u8::MAX, u8::MIN), |(lo, hi), &s| (lo.min(s), hi.max(s)));
+    if low == high {
+        return Err(LaserError::DataCorruption);
+    }
+    let threshold = (low as u16 + high as u16) / 2;
+
+    let mut runs: Vec<(bool, usize)> = Vec::new();
+    for &sample in samples {
+        let level = sample as u16 > threshold;
+        match runs.last_mut() {
+            Some((last, len)) if *last == level => *len += 1,
+            _ => runs.push((level, 1)),
+        }
+    }
+
+    let half_bit = runs[0].1 as f32;
+    let half_cells: Vec<bool> = runs
+        .into_iter()
+        .flat_map(|(level, len)| std::iter::repeat_n(level, ((len as f32 / half_bit).round() as usize).max(1)))
+        .collect();
+    if !half_cells.len().is_multiple_of(16) {
+        return Err(LaserError::DataCorruption);
+    }
+
+    let bits = half_cells
+        .chunks(2)
+        .map(|cell| match cell {
+            [true, false] => Ok(true),
+            [false, true] => Ok(false),
+            _ => Err(LaserError::DataCorruption),
+        })
+        .collect::<Result<Vec<bool>, LaserError>>()?;
+    Ok(pack_ook_bits(&bits))
+}
+
+/// DPSK intensity waveform for `data`: a reference symbol, then one symbol per
+/// bit (MSB first) whose subcarrier phase flips for a `1`. Intensity swings
+/// between 0.0 and 1.0 around a half-power mean.
+pub fn modulate_dpsk(data: &[u8]) -> Vec<f32> {
+    let mut phase = 0.0f32;
+    let mut samples = Vec::with_capacity((1 + 8 * data.len()) * LASER_DPSK_SAMPLES_PER_SYMBOL);
+    let bits = data.iter().flat_map(|&byte| (0..8).map(move |bit| byte & (1 << (7 - bit)) != 0));
+    for flip in std::iter::once(false).chain(bits) {
+        if flip {
+            phase += std::f32::consts::PI;
+        }
+        samples.extend((0..LASER_DPSK_SAMPLES_PER_SYMBOL).map(|n| {
+            let angle = 2.0 * std::f32::consts::PI * n as f32 / LASER_DPSK_SAMPLES_PER_CYCLE as f32 + phase;
+            0.5 + 0.5 * angle.cos()
+        }));
+    }
+    samples
+}
+
+/// Differentially demodulate DPSK samples into bits with a confidence each.
+///
+/// Each symbol, with its mean removed, is correlated against the previous one
+/// held in a one-symbol delay line: a negative correlation is a phase flip (`1`).
+/// Confidence is the normalized correlation magnitude, so 1.0 is a clean symbol
+/// pair and 0.0 a coin toss. A symbol with no subcarrier is `DataCorruption`.
+pub fn demodulate_dpsk_soft(samples: &[f32]) -> Result<Vec<(bool, f32)>, LaserError> {
+    if samples.is_empty() || !samples.len().is_multiple_of(LASER_DPSK_SAMPLES_PER_SYMBOL) {
+        return Err(LaserError::DataCorruption);
+    }
+
+    let symbols: Vec<Vec<f32>> = samples.chunks_exact(LASER_DPSK_SAMPLES_PER_SYMBOL)
+        .map(|symbol| {
+            let mean = symbol.iter().sum::<f32>() / symbol.len() as f32;
+            symbol.iter().map(|s| s - mean).collect()
+        })
+        .collect();
+    let energy = |symbol: &[f32]| symbol.iter().map(|s| s * s).sum::<f32>();
+    if symbols.iter().any(|symbol| energy(symbol) <= f32::EPSILON) {
+        return Err(LaserError::DataCorruption);
+    }
+
+    Ok(symbols.windows(2)
+        .map(|pair| {
+            let correlation: f32 = pair[0].iter().zip(&pair[1]).map(|(a, b)| a * b).sum();
+            let confidence = (correlation.abs() / (energy(&pair[0]) * energy(&pair[1])).sqrt()).min(1.0);
+            (correlation < 0.0, confidence)
+        })
+        .collect())
+}
+
+/// Waits for the receiver's verdict on stream chunk `sequence`, typically an
+/// ACK/NAK frame on the ultrasound control channel: resolves `true` on ACK and
+/// `false` on NAK. [`LaserEngine::transmit_reliable`] bounds the wait itself
+pub type ChunkAckCallback = Arc<dyn Fn(u16) -> std::pin::Pin<Box<dyn std::future::Future<Output = bool> + Send>> + Send + Sync>;
+
+/// Smoothed ACK round trip and its mean deviation, per RFC 6298
+#[derive(Debug, Clone, Copy, Default)]
+struct AckRttEstimate {
+    smoothed: Option<Duration>,
+    deviation: Duration,
+}
+
+impl AckRttEstimate {
+    fn observe(&mut self, rtt: Duration) {
+        match self.smoothed {
+            None => {
+                self.smoothed = Some(rtt);
+                self.deviation = rtt / 2;
+            }
+            Some(smoothed) => {
+                let error = rtt.abs_diff(smoothed);
+                self.deviation = (self.deviation * 3 + error) / 4;
+                self.smoothed = Some((smoothed * 7 + rtt) / 8);
+            }
+        }
+    }
+
+    fn timeout(&self) -> Duration {
+        self.smoothed.map_or(RELIABLE_INITIAL_ACK_TIMEOUT, |smoothed| smoothed + self.deviation * 4)
+            .clamp(RELIABLE_MIN_ACK_TIMEOUT, RELIABLE_MAX_ACK_TIMEOUT)
+    }
+}
+
+/// Render `encoded` as OOK intensity samples, returning them with their sample
+/// rate in Hz.
+///
+/// Without a carrier each bit is one sample at the data rate. With a lock-in
+/// carrier an "on" bit is a square wave of at least one full cycle, so the
+/// carrier never drops below the data rate, and an "off" bit is dark.
+fn ook_samples(encoded: &[u8], data_rate_bps: u32, carrier_hz: Option<u32>) -> (Vec<f32>, u32) {
+    let data_rate_bps = data_rate_bps.max(1);
+    let cycles_per_bit = carrier_hz.map(|carrier_hz| (carrier_hz / data_rate_bps).max(1));
+    let samples_per_bit = cycles_per_bit.map_or(1, |cycles| 2 * cycles) as usize;
+
+    let mut samples = Vec::with_capacity(encoded.len() * 8 * samples_per_bit);
+    for byte in encoded {
+        for bit in 0..8 {
+            let is_on = (byte & (1 << (7 - bit))) != 0;
+            match cycles_per_bit {
+                Some(cycles) if is_on => (0..cycles).for_each(|_| samples.extend_from_slice(&[1.0, 0.0])),
+                _ => samples.extend(std::iter::repeat_n(if is_on { 1.0 } else { 0.0 }, samples_per_bit)),
+            }
+        }
+    }
+    (samples, data_rate_bps.saturating_mul(samples_per_bit as u32))
+}
+
+/// Observer for emitter intensity levels, e.g. to capture a transmitted waveform
+pub trait SampleSink: Send + Sync {
+    fn record(&self, intensity: f32);
+}
+
+impl SampleSink for std::sync::Mutex<Vec<f32>> {
+    fn record(&self, intensity: f32) {
+        if let Ok(mut samples) = self.lock() {
+            samples.push(intensity);
+        }
+    }
+}
+
+/// Full-angle divergence of the collimated transmit beam
+pub const LASER_BEAM_DIVERGENCE_RAD: f32 = 1e-3;
+/// Diameter of the photodiode's collecting lens
+pub const RECEIVER_APERTURE_MM: f32 = 25.0;
+/// Weakest optical power the photodiode front end demodulates reliably
+pub const RECEIVER_SENSITIVITY_DBM: f32 = -40.0;
+
+/// Atmospheric attenuation in dB/km at `visibility_m` for `wavelength_nm`
+/// (Kim model): 3.91/V · (λ/550nm)^-q, where the size-distribution exponent q
+/// falls to 0 in fog so every wavelength is scattered alike
+pub fn kim_attenuation_db_per_km(visibility_m: f32, wavelength_nm: u32) -> f32 {
+    let visibility_km = (visibility_m / 1000.0).max(1e-3);
+    let q = if visibility_km > 50.0 {
+        1.6
+    } else if visibility_km > 6.0 {
+        1.3
+    } else if visibility_km > 1.0 {
+        0.16 * visibility_km + 0.34
+    } else if visibility_km > 0.5 {
+        visibility_km - 0.5
+    } else {
+        0.0
+    };
+    let extinction_per_km = 3.91 / visibility_km * (wavelength_nm as f32 / 550.0).powf(-q);
+    // Extinction coefficient to dB: 10·log10(e)
+    extinction_per_km * 4.343
+}
+
+/// Laser power compensation for `weather` at `visibility_m` of visibility
+pub fn weather_power_multiplier(weather: &WeatherCondition, visibility_m: f32) -> f32 {
+    // Calculate weather-based power multiplier
+    let weather_multiplier = match weather {
+        WeatherCondition::Clear => 1.0,
+        WeatherCondition::Rain => 1.5,
+        WeatherCondition::Fog => 3.0,  // Significant attenuation in fog
+        WeatherCondition::Storm => 2.0,
+        WeatherCondition::Snow => 2.5,
+        WeatherCondition::HeavyRain => 2.0,
+        WeatherCondition::LightRain => 1.3,
+        WeatherCondition::Cloudy => 1.1,
+    };
+
+    // Calculate visibility-based multiplier
+    let visibility_multiplier = if visibility_m < 100.0 {
+        3.0  // Very poor visibility
+    } else if visibility_m < 500.0 {
+        2.0  // Poor visibility
+    } else if visibility_m < 1000.0 {
+        1.5  // Moderate visibility
+    } else {
+        1.0  // Good visibility
+    };
+
+    weather_multiplier * visibility_multiplier
+}
+
+/// Pack soft OOK bits into `(byte, confidence)` symbols, most significant bit
+/// first; a byte is only as reliable as its weakest bit
+pub fn pack_soft_ook_bits(bits: &[(bool, f32)]) -> Vec<(u8, f32)> {
+    bits.chunks(8)
+        .map(|byte| {
+            byte.iter().enumerate().fold((0u8, 1.0f32), |(acc, confidence), (i, &(bit, bit_confidence))| {
+                (acc | ((bit as u8) << (7 - i)), confidence.min(bit_confidence))
+            })
+        })
+        .collect()
+}
+
+/// Compute the power levels for a linear ramp from `from_mw` to `to_mw`.
+///
+/// The start level is excluded and the final entry is always exactly `to_mw`,
+/// so a ramp with zero steps degenerates to an immediate switch. Every level
+/// lies between the two endpoints, so rounding never steps past `to_mw`.
+pub fn interpolate_power_ramp(from_mw: f32, to_mw: f32, steps: u32) -> Vec<f32> {
+    let steps = steps.max(1);
+    let (low_mw, high_mw) = (from_mw.min(to_mw), from_mw.max(to_mw));
+    (1..=steps)
+        .map(|i| {
+            if i == steps {
+                to_mw
+            } else {
+                (from_mw + (to_mw - from_mw) * (i as f32 / steps as f32)).clamp(low_mw, high_mw)
+            }
+        })
+        .collect()
+}
+
+/// Largest payload a photodiode frame header may announce before it is treated as corrupt
+pub const MAX_PHOTODIODE_PAYLOAD_BYTES: usize = 64 * 1024;
+/// Largest optical ECC frame body accepted from a frame header: room for a
+/// maximum payload under rate-1/3 convolutional coding and a low-rate block code
+pub const MAX_OPTICAL_FRAME_BODY_BYTES: usize = 16 * MAX_PHOTODIODE_PAYLOAD_BYTES;
+
+/// Shards per adaptive Reed-Solomon codeword; data shards give way to parity
+/// until `min_data_shards` is reached
+pub const ADAPTIVE_RS_TOTAL_SHARDS: usize = 20;
+/// Gains of the incremental PID controller driving the parity count
+const RS_PID_KP: f64 = 0.2;
+const RS_PID_KI: f64 = 0.25;
+const RS_PID_KD: f64 = 0.05;
+
+/// Limits for adapting the laser Reed-Solomon code to the measured channel
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdaptiveRsConfig {
+    pub min_data_shards: u8,
+    pub max_parity_shards: u8,
+    /// Acceptable probability that a codeword has more bad shards than parity
+    pub target_ber: f64,
+}
+
+impl Default for AdaptiveRsConfig {
+    fn default() -> Self {
+        Self {
+            min_data_shards: 8,
+            max_parity_shards: 12,
+            target_ber: 1e-6,
+        }
+    }
+}
+
+/// Incremental PID steering the parity count toward the smallest one whose
+/// failure probability at the measured shard error rate meets `target_ber`.
+/// The controller smooths single noisy measurements instead of jumping to them.
+#[derive(Debug, Clone)]
+struct RsParityController {
+    config: AdaptiveRsConfig,
+    /// Unrounded parity count, clamped to the allowed range (anti-windup)
+    output: f64,
+    /// Errors from the previous two updates
+    errors: [f64; 2],
+}
+
+impl RsParityController {
+    fn new(config: AdaptiveRsConfig, parity_shards: usize) -> Self {
+        Self { config, output: parity_shards as f64, errors: [0.0; 2] }
+    }
+
+    /// Parity count for the next transmission after observing `fraction` of
+    /// `total_shards` corrected
+    fn update(&mut self, fraction: f64, total_shards: usize) -> usize {
+        let max_parity = self.max_parity_shards();
+        let required = required_parity_shards(total_shards, fraction.clamp(0.0, 1.0), self.config.target_ber, max_parity);
+
+        let error = required as f64 - self.output;
+        let [previous, before] = self.errors;
+        self.output += RS_PID_KP * (error - previous) + RS_PID_KI * error + RS_PID_KD * (error - 2.0 * previous + before);
+        self.output = self.output.clamp(1.0, max_parity as f64);
+        self.errors = [error, previous];
+        self.output.round() as usize
+    }
+
+    /// Parity ceiling, lowered so at least `min_data_shards` remain per codeword
+    fn max_parity_shards(&self) -> usize {
+        let data_floor = (self.config.min_data_shards as usize).clamp(1, ADAPTIVE_RS_TOTAL_SHARDS - 1);
+        (self.config.max_parity_shards as usize).clamp(1, ADAPTIVE_RS_TOTAL_SHARDS - data_floor)
+    }
+}
+
+/// Smallest parity count (at most `max_parity`) for which more than that many of
+/// `total_shards` shards, each bad with probability `shard_error_rate`, happens
+/// with probability at most `target`
+fn required_parity_shards(total_shards: usize, shard_error_rate: f64, target: f64, max_parity: usize) -> usize {
+    let mut tail = 1.0;
+    let mut term = (1.0 - shard_error_rate).powi(total_shards as i32);
+    for parity in 0..max_parity {
+        // P(X > parity) for X ~ Binomial(total_shards, shard_error_rate)
+        tail -= term;
+        if parity >= 1 && tail <= target {
+            return parity;
+        }
+        term *= (total_shards - parity) as f64 / (parity + 1) as f64 * shard_error_rate / (1.0 - shard_error_rate).max(f64::MIN_POSITIVE);
+    }
+    max_parity
+}
+
+/// Length of the frame announced by a received frame header. A plain
+/// Reed-Solomon frame must carry at most `MAX_PHOTODIODE_PAYLOAD_BYTES` in the
+/// announced shard layout; an optical ECC frame, whose payload is already
+/// convolutionally coded, must stay within `MAX_OPTICAL_FRAME_BODY_BYTES`.
+fn received_frame_len(header: &[u8], optical_ecc: bool) -> Result<usize, LaserError> {
+    let header = crate::optical_ecc::FrameHeader::from_bytes(header).map_err(|_| LaserError::DataCorruption)?;
+    let valid = if optical_ecc {
+        header.payload_len <= MAX_OPTICAL_FRAME_BODY_BYTES && header.body_len <= MAX_OPTICAL_FRAME_BODY_BYTES
+    } else {
+        header.scheme == crate::optical_ecc::EccScheme::ReedSolomon
+            && header.payload_len <= MAX_PHOTODIODE_PAYLOAD_BYTES
+            && header.rs_body_len() == Some(header.body_len)
+    };
+    if !valid {
+        return Err(LaserError::DataCorruption);
+    }
+    Ok(header.frame_len())
+}
+
+/// Shards of an `rs_encode_framed` frame holding at least one erased byte
+fn erased_shard_count(erasures: &[bool], total_shards: usize) -> usize {
+    let body = erasures.get(crate::optical_ecc::FRAME_HEADER_LEN..).unwrap_or_default();
+    if body.is_empty() || !body.len().is_multiple_of(total_shards) {
+        return 0;
+    }
+    body.chunks(body.len() / total_shards).filter(|shard| shard.iter().any(|&erased| erased)).count()
+}
+
+/// Length of the stream frame header: sequence, total chunks and CRC16 (all u16 big-endian)
+pub const STREAM_FRAME_HEADER_LEN: usize = 6;
+/// Receptions allowed beyond the chunk count before a stream is declared corrupt
+pub const STREAM_RECEIVE_RETRIES: usize = 3;
+/// Payload bytes per chunk sent by `transmit_reliable`
+pub const RELIABLE_CHUNK_SIZE: usize = 64;
+/// ACK timeout before any round trip has been measured
+pub const RELIABLE_INITIAL_ACK_TIMEOUT: Duration = Duration::from_millis(200);
+/// Bounds on the adaptive ACK timeout
+pub const RELIABLE_MIN_ACK_TIMEOUT: Duration = Duration::from_millis(20);
+pub const RELIABLE_MAX_ACK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// CRC-16/CCITT-FALSE (poly 0x1021, init 0xFFFF) used to check stream frames.
+pub fn crc16_ccitt(bytes: &[u8]) -> u16 {
+    bytes.iter().fold(0xFFFF, |crc, &byte| {
+        (0..8).fold(crc ^ ((byte as u16) << 8), |crc, _| {
+            if crc & 0x8000 != 0 { (crc << 1) ^ 0x1021 } else { crc << 1 }
+        })
+    })
+}
+
+fn stream_frame_crc(sequence: u16, total: u16, payload: &[u8]) -> u16 {
+    let mut covered = Vec::with_capacity(4 + payload.len());
+    covered.extend_from_slice(&sequence.to_be_bytes());
+    covered.extend_from_slice(&total.to_be_bytes());
+    covered.extend_from_slice(payload);
+    crc16_ccitt(&covered)
+}
+
+/// Split `data` into frames of `sequence | total | crc16 | payload`.
+///
+/// An empty payload still produces a single frame so the receiver can complete.
+pub fn encode_stream_frames(data: &[u8], chunk_size: usize) -> Result<Vec<Vec<u8>>, LaserError> {
+    if chunk_size == 0 {
+        return Err(LaserError::InvalidStream("chunk size must be non-zero".to_string()));
+    }
+    let chunk_count = data.len().div_ceil(chunk_size).max(1);
+    let total = u16::try_from(chunk_count).map_err(|_| {
+        LaserError::InvalidStream(format!("{} chunks exceed the {} chunk limit", chunk_count, u16::MAX))
+    })?;
+
+    let chunks: Vec<&[u8]> = if data.is_empty() { vec![&[]] } else { data.chunks(chunk_size).collect() };
+    Ok(chunks
+        .into_iter()
+        .enumerate()
+        .map(|(index, payload)| {
+            let sequence = index as u16;
+            let mut frame = Vec::with_capacity(STREAM_FRAME_HEADER_LEN + payload.len());
+            frame.extend_from_slice(&sequence.to_be_bytes());
+            frame.extend_from_slice(&total.to_be_bytes());
+            frame.extend_from_slice(&stream_frame_crc(sequence, total, payload).to_be_bytes());
+            frame.extend_from_slice(payload);
+            frame
+        })
+        .collect())
+}
+
+/// Collects stream frames in any order and yields the payload once all chunks arrived.
+#[derive(Debug, Default)]
+pub struct StreamReassembler {
+    total: Option<u16>,
+    chunks: std::collections::BTreeMap<u16, Vec<u8>>,
+}
+
+impl StreamReassembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accept one frame. Frames with a bad CRC or a chunk count that disagrees
+    /// with earlier frames are rejected and not stored.
+    pub fn push(&mut self, frame: &[u8]) -> Result<(), LaserError> {
+        if frame.len() < STREAM_FRAME_HEADER_LEN {
+            return Err(LaserError::DataCorruption);
+        }
+        let sequence = u16::from_be_bytes([frame[0], frame[1]]);
+        let total = u16::from_be_bytes([frame[2], frame[3]]);
+        let crc = u16::from_be_bytes([frame[4], frame[5]]);
+        let payload = &frame[STREAM_FRAME_HEADER_LEN..];
+
+        if crc != stream_frame_crc(sequence, total, payload) || sequence >= total {
+            return Err(LaserError::DataCorruption);
+        }
+        if self.total.is_some_and(|expected| expected != total) {
+            return Err(LaserError::DataCorruption);
+        }
+        self.total = Some(total);
+        self.chunks.insert(sequence, payload.to_vec());
+        Ok(())
+    }
+
+    /// Chunk count announced by the first valid frame
+    pub fn total_chunks(&self) -> Option<usize> {
+        self.total.map(usize::from)
+    }
+
+    /// Sequence numbers not yet received
+    pub fn missing(&self) -> Vec<u16> {
+        (0..self.total.unwrap_or(0)).filter(|sequence| !self.chunks.contains_key(sequence)).collect()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.total.is_some_and(|total| self.chunks.len() == total as usize)
+    }
+
+    /// Concatenate the chunks in sequence order, failing if any are missing
+    pub fn finish(self) -> Result<Vec<u8>, LaserError> {
+        if !self.is_complete() {
+            return Err(LaserError::DataCorruption);
+        }
+        Ok(self.chunks.into_values().flatten().collect())
+    }
+}
+
+/// Move `profile` to `target`, interpolating `optimal_power_mw` over the ramp interval.
+///
+/// The target's limits are applied up front so intermediate levels are always
+/// checked against the profile being switched to; a ramp down from above the
+/// target's `max_power_mw` drops to that ceiling on its first step.
+async fn ramp_power_profile(profile: &Arc<Mutex<PowerProfile>>, target: PowerProfile, ramp: PowerRampConfig) {
+    let start_mw = profile.lock().await.optimal_power_mw;
+    if ramp.steps == 0 || ramp.duration.is_zero() || (start_mw - target.optimal_power_mw).abs() < f32::EPSILON {
+        *profile.lock().await = target;
+        return;
+    }
+
+    let step_delay = ramp.duration / ramp.steps;
+    for level_mw in interpolate_power_ramp(start_mw, target.optimal_power_mw, ramp.steps) {
+        {
+            let mut current = profile.lock().await;
+            *current = target.clone();
+            current.optimal_power_mw = level_mw.min(target.max_power_mw);
+        }
+        tokio::time::sleep(step_delay).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::range_detector::RangingConfig;
+
+    #[tokio::test]
+    async fn test_laser_engine_creation() {
+        let config = LaserConfig::default();
+        let rx_config = ReceptionConfig::default();
+        let engine = LaserEngine::new(config, rx_config);
+
+        assert!(!engine.is_active().await);
+    }
+
+    #[tokio::test]
+    async fn test_laser_engine_initialization() {
+        let config = LaserConfig::default();
+        let rx_config = ReceptionConfig::default();
+        let mut engine = LaserEngine::new(config, rx_config);
+
+        // Initialization should succeed (even with mock hardware)
+        let result = engine.initialize().await;
+        assert!(result.is_ok());
+        assert!(engine.is_active().await);
+    }
+
+    #[tokio::test]
+    async fn test_alignment_tracking() {
+        let config = LaserConfig::default();
+        let rx_config = ReceptionConfig::default();
+        let engine = LaserEngine::new(config, rx_config);
+
+        // Set alignment target
+        let result = engine.set_alignment_target(100.0, 200.0).await;
+        assert!(result.is_ok());
+
+        // Check alignment status
+        let status = engine.get_alignment_status().await;
+        assert!(!status.is_aligned); // Should not be aligned initially
+    }
+
+    #[tokio::test]
+    async fn test_error_correction() {
+        let config = LaserConfig::default();
+        let rx_config = ReceptionConfig::default();
+        let mut engine = LaserEngine::new(config, rx_config);
+
+        let test_data = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16];
+
+        // Encode data
+        let encoded = engine.encode_with_ecc(&test_data).await;
+        assert!(encoded.is_ok());
+        let encoded_data = encoded.unwrap();
+
+        // Decode data
+        let decoded = engine.decode_with_ecc(&encoded_data).await;
+        assert!(decoded.is_ok());
+        let decoded_data = decoded.unwrap();
+
+        assert_eq!(test_data, decoded_data);
+    }
+
+    #[tokio::test]
+    async fn test_safety_limits() {
+        let config = LaserConfig::default();
+        let rx_config = ReceptionConfig::default();
+        let engine = LaserEngine::new(config, rx_config);
+
+        // Test invalid intensity values
+        let result = engine.set_laser_intensity(1.5).await;
+        assert!(matches!(result, Err(LaserError::SafetyViolation)));
+
+        let result = engine.set_laser_intensity(-0.1).await;
+        assert!(matches!(result, Err(LaserError::SafetyViolation)));
+    }
+
+    #[tokio::test]
+    async fn test_power_management() {
+        let config = LaserConfig::default();
+        let rx_config = ReceptionConfig::default();
+        let engine = LaserEngine::new(config, rx_config);
+
+        // Test standby mode
+        let result = engine.set_standby_mode(true).await;
+        assert!(result.is_ok());
+
+        // Check safety stats
+        let (energy, violations, uptime) = engine.get_safety_stats().await;
+        assert!(energy >= 0.0);
+        assert_eq!(violations, 0);
+    }
+
+    #[tokio::test]
+    async fn test_power_ramp_on_range_category_change() {
+        let close = PowerProfile::for_range_category(&RangeDetectorCategory::Close);
+        let far = PowerProfile::for_range_category(&RangeDetectorCategory::Far);
+        let target_mw = far.optimal_power_mw;
+        assert!(target_mw > close.optimal_power_mw);
+
+        let levels = interpolate_power_ramp(close.optimal_power_mw, target_mw, 8);
+        assert_eq!(levels.len(), 8);
+        assert!(levels[0] < target_mw); // No instantaneous jump
+        assert!(levels.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(*levels.last().unwrap(), target_mw);
+
+        // Sample the shared profile while the ramp is applied
+        let profile = Arc::new(Mutex::new(close));
+        let ramp = PowerRampConfig { duration: Duration::from_millis(40), steps: 4 };
+        let ramp_task = tokio::spawn({
+            let profile = profile.clone();
+            async move { ramp_power_profile(&profile, far, ramp).await }
+        });
+
+        let mut samples = Vec::new();
+        while !ramp_task.is_finished() {
+            samples.push(profile.lock().await.optimal_power_mw);
+            tokio::time::sleep(Duration::from_millis(2)).await;
+        }
+        samples.push(profile.lock().await.optimal_power_mw);
+
+        assert!(samples.windows(2).all(|w| w[0] <= w[1]));
+        assert!(samples.iter().any(|&mw| mw < target_mw));
+        assert_eq!(*samples.last().unwrap(), target_mw);
+    }
+
+    #[tokio::test]
+    async fn test_power_ramp_down_stays_within_target_limits() {
+        let far = PowerProfile::for_range_category(&RangeDetectorCategory::Far);
+        let mut close = PowerProfile::for_range_category(&RangeDetectorCategory::Close);
+        assert!(far.optimal_power_mw > close.optimal_power_mw);
+        // The close profile may not drive anywhere near the far power
+        close.max_power_mw = (far.optimal_power_mw + close.optimal_power_mw) / 2.0;
+        let (target_mw, ceiling_mw) = (close.optimal_power_mw, close.max_power_mw);
+
+        let levels = interpolate_power_ramp(far.optimal_power_mw, target_mw, 8);
+        assert!(levels.iter().all(|&mw| mw >= target_mw && mw <= far.optimal_power_mw));
+        assert_eq!(*levels.last().unwrap(), target_mw);
+
+        let profile = Arc::new(Mutex::new(far));
+        let ramp = PowerRampConfig { duration: Duration::from_millis(40), steps: 4 };
+        let ramp_task = tokio::spawn({
+            let profile = profile.clone();
+            async move { ramp_power_profile(&profile, close, ramp).await }
+        });
+
+        let mut samples = Vec::new();
+        while !ramp_task.is_finished() {
+            let current = profile.lock().await.clone();
+            if current.max_power_mw == ceiling_mw {
+                samples.push(current.optimal_power_mw);
+            }
+            tokio::time::sleep(Duration::from_millis(2)).await;
+        }
+        samples.push(profile.lock().await.optimal_power_mw);
+
+        assert!(samples.iter().all(|&mw| mw >= target_mw && mw <= ceiling_mw));
+        assert!(samples.windows(2).all(|w| w[0] >= w[1]));
+        assert_eq!(*samples.last().unwrap(), target_mw);
+    }
+
+    #[test]
+    fn test_adaptive_prediction_horizon_reduces_tracking_error() {
+        const FIXED_HORIZON_S: f32 = 0.1;
+        let config = PredictionHorizonConfig::default();
+
+        // Mean absolute error between where the peer actually is after the steering
+        // lag and where the beam was aimed, for a noisy velocity estimate
+        let tracking_error = |speed: f32, steering_lag_s: f32, horizon: &dyn Fn(f32) -> f32| {
+            let noise = [4.0, -3.0, 5.0, -6.0, 2.0, -1.0, 3.0, -4.0];
+            noise.iter().map(|n| {
+                let estimated_speed = speed + n;
+                let actual = speed * steering_lag_s;
+                let predicted = estimated_speed * horizon(estimated_speed);
+                (actual - predicted).abs()
+            }).sum::<f32>() / noise.len() as f32
+        };
+        let fixed = |_: f32| FIXED_HORIZON_S;
+        let adaptive = |speed: f32| adaptive_prediction_horizon(speed, &config);
+
+        // Static peer: the beam settles quickly, so a long horizon only amplifies noise
+        let slow_fixed = tracking_error(0.0, 0.02, &fixed);
+        let slow_adaptive = tracking_error(0.0, 0.02, &adaptive);
+        assert!(slow_adaptive < slow_fixed);
+
+        // Fast peer: the steering lag grows, so a short horizon trails the target
+        let fast_fixed = tracking_error(200.0, 0.25, &fixed);
+        let fast_adaptive = tracking_error(200.0, 0.25, &adaptive);
+        assert!(fast_adaptive < fast_fixed);
+
+        assert!(adaptive(0.0) < FIXED_HORIZON_S);
+        assert!(adaptive(200.0) > FIXED_HORIZON_S);
+        assert_eq!(adaptive(1.0e6), config.max_horizon_s);
+    }
+
+    #[tokio::test]
+    async fn test_error_correction_round_trip_arbitrary_lengths() {
+        use rand::{Rng, RngCore};
+
+        let mut engine = LaserEngine::new(LaserConfig::default(), ReceptionConfig::default());
+        let mut rng = <rand::rngs::StdRng as rand::SeedableRng>::seed_from_u64(1938);
+
+        let mut lengths = vec![0, 1, 15, 16, 17, 100, 9999];
+        lengths.extend((0..200).map(|_| rng.gen_range(0..10000)));
+
+        for len in lengths {
+            let mut data = vec![0u8; len];
+            rng.fill_bytes(&mut data);
+
+            let encoded = engine.encode_with_ecc(&data).await.unwrap();
+            let decoded = engine.decode_with_ecc(&encoded).await.unwrap();
+            assert_eq!(decoded, data, "round trip failed for {} bytes", len);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_overlapping_transmissions_do_not_interleave() {
+        let payload = vec![0x5Au8; 16];
+
+        let mut first = LaserEngine::new(LaserConfig::default(), ReceptionConfig::default());
+        let mut second = LaserEngine::new(LaserConfig::default(), ReceptionConfig::default());
+        first.initialize().await.unwrap();
+        second.initialize().await.unwrap();
+
+        // Both engines drive the same diode
+        let guard = first.transmitter_guard();
+        second.set_transmitter_guard(guard.clone());
+
+        // Baseline duration of a single emission
+        let solo_start = Instant::now();
+        first.transmit_data(&payload).await.unwrap();
+        let solo = solo_start.elapsed();
+
+        // Reject policy: an overlapping call fails fast
+        second.set_transmit_busy_policy(TransmitBusyPolicy::Reject);
+        let first_task = tokio::spawn({
+            let payload = payload.clone();
+            async move {
+                first.transmit_data(&payload).await.unwrap();
+                first
+            }
+        });
+        while guard.try_lock().is_ok() {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        assert!(matches!(second.transmit_data(&payload).await, Err(LaserError::TransmitterBusy)));
+        let mut first = first_task.await.unwrap();
+
+        // Queue policy: the second emission starts only after the first completes
+        second.set_transmit_busy_policy(TransmitBusyPolicy::Queue);
+        let first_task = tokio::spawn({
+            let payload = payload.clone();
+            async move {
+                first.transmit_data(&payload).await.unwrap();
+                Instant::now()
+            }
+        });
+        while guard.try_lock().is_ok() {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        second.transmit_data(&payload).await.unwrap();
+        let second_done = Instant::now();
+        let first_done = first_task.await.unwrap();
+
+        assert!(second_done > first_done);
+        assert!(second_done.duration_since(first_done) >= solo.mul_f32(0.8));
+    }
+
+    #[tokio::test]
+    async fn test_compliance_report_flags_samples_above_class() {
+        let mut engine = LaserEngine::new(LaserConfig::default(), ReceptionConfig::default());
+        engine.initialize().await.unwrap();
+        engine.set_power_log_config(PowerLogConfig {
+            regulatory_class: RegulatoryClass::Class2,
+            ..Default::default()
+        });
+
+        let payload = vec![0x3Cu8; 4];
+        for power_mw in [0.5f32, 2.0, 2.0] {
+            let mut profile = engine.get_current_power_profile().await;
+            profile.max_power_mw = power_mw;
+            profile.optimal_power_mw = power_mw;
+            profile.min_power_mw = profile.min_power_mw.min(power_mw);
+            engine.set_power_profile(profile).await.unwrap();
+            assert_eq!(engine.get_effective_power_limit().await, power_mw);
+
+            engine.transmit_data(&payload).await.unwrap();
+        }
+
+        let log = engine.power_log().await;
+        assert_eq!(log.len(), 3);
+        assert!(log.iter().all(|entry| entry.wavelength_nm == engine.config.wavelength_nm));
+
+        let report = engine.compliance_report(Duration::from_secs(60)).await;
+        assert_eq!(report.sample_count, 3);
+        assert_eq!(report.peak_power_mw, 2.0);
+        assert_eq!(report.limit_mw, 1.0);
+        assert_eq!(report.exceedances.len(), 2);
+        assert!(!report.compliant);
+        assert_eq!(report.time_above_threshold, log[1].duration + log[2].duration);
+
+        let weighted: f64 = log.iter().map(|e| e.power_mw as f64 * e.duration.as_secs_f64()).sum();
+        let expected_average = weighted / report.total_emission_time.as_secs_f64();
+        assert!((report.average_power_mw as f64 - expected_average).abs() < 1e-3);
+        assert!(report.average_power_mw > 0.5 && report.average_power_mw < 2.0);
+
+        // Samples outside the window are not reported
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let empty = engine.compliance_report(Duration::from_millis(1)).await;
+        assert_eq!(empty.sample_count, 0);
+        assert!(empty.compliant);
+    }
+
+    /// Photodiode readings for `data` sent with OOK through additive Gaussian noise
+    fn simulated_ook_channel(data: &[u8], samples_per_bit: usize, noise_std: f32, seed: u64) -> Vec<f32> {
+        use rand::{Rng, SeedableRng};
+        let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+        let mut readings = Vec::with_capacity(data.len() * 8 * samples_per_bit);
+        for byte in data {
+            for bit in 0..8 {
+                let level = if byte & (1 << (7 - bit)) != 0 { 1.0 } else { 0.0 };
+                for _ in 0..samples_per_bit {
+                    // Box-Muller
+                    let u1: f32 = rng.gen_range(f32::EPSILON..1.0);
+                    let u2: f32 = rng.gen();
+                    let noise = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f32::consts::PI * u2).cos();
+                    readings.push(level + noise * noise_std);
+                }
+            }
+        }
+        readings
+    }
+
+    #[test]
+    fn test_ook_integration_recovers_low_snr_bits() {
+        let data: Vec<u8> = (0..32u8).map(|i| i.wrapping_mul(37) ^ 0xA5).collect();
+        let noise_std = 0.6;
+        let threshold = 0.5;
+
+        // One reading per bit: roughly one bit in five is flipped
+        let single = simulated_ook_channel(&data, 1, noise_std, 7);
+        assert_ne!(pack_ook_bits(&integrate_ook_bits(&single, 1, threshold)), data);
+
+        // Integrating 32 readings per bit cuts the noise by ~5.7x
+        let config = ReceptionConfig { samples_per_bit: 32, ..Default::default() };
+        let samples_per_bit = config.samples_per_bit as usize;
+        let oversampled = simulated_ook_channel(&data, samples_per_bit, noise_std, 7);
+        assert_eq!(pack_ook_bits(&integrate_ook_bits(&oversampled, samples_per_bit, threshold)), data);
+    }
+
+    #[test]
+    fn test_lock_in_filter_rejects_ambient_light() {
+        let data = [0xA5u8, 0x3C, 0x0F, 0xF0];
+        let (sample_rate_hz, carrier_hz, samples_per_bit) = (160_000u32, 10_000u32, 64usize);
+        let threshold = 0.5;
+
+        // Chopped carrier on top of sunlight and full-wave rectified 100 Hz lamp flicker
+        let mut readings = Vec::new();
+        for (bit_index, bit) in data.iter().flat_map(|&byte| (0..8).map(move |bit| byte & (1 << (7 - bit)) != 0)).enumerate() {
+            for sample in 0..samples_per_bit {
+                let n = bit_index * samples_per_bit + sample;
+                let t = n as f32 / sample_rate_hz as f32;
+                let carrier = if bit && (n * 2 * carrier_hz as usize / sample_rate_hz as usize) % 2 == 0 { 1.0 } else { 0.0 };
+                let ambient = 1.5 + 0.5 * (2.0 * std::f32::consts::PI * 100.0 * t).sin().abs();
+                readings.push(carrier + ambient);
+            }
+        }
+
+        // Plain thresholding sees nothing but "on"
+        assert_eq!(pack_ook_bits(&integrate_ook_bits(&readings, samples_per_bit, threshold)), vec![0xFF; data.len()]);
+
+        let mut filter = LockInFilter::new(carrier_hz, 2_000, sample_rate_hz).unwrap();
+        let envelope = filter.demodulate(&readings);
+        assert_eq!(pack_ook_bits(&integrate_ook_bits(&envelope, samples_per_bit, threshold)), data);
+
+        // The carrier must sit below Nyquist
+        assert!(matches!(LockInFilter::new(carrier_hz, 2_000, 2 * carrier_hz), Err(LaserError::InvalidConfiguration(_))));
+    }
+
+    #[tokio::test]
+    async fn test_flagged_erasures_recover_corrupted_symbols() {
+        let mut engine = LaserEngine::new(LaserConfig::default(), ReceptionConfig::default());
+        let data: Vec<u8> = (0..64u8).map(|i| i.wrapping_mul(29) ^ 0x5A).collect();
+        let encoded = engine.encode_with_ecc(&data).await.unwrap();
+
+        // 64 bytes over 16 data shards: 4-byte shards after the frame header.
+        // Interference hits one bit in each of four shards, as many as there are parity shards.
+        let mut readings = simulated_ook_channel(&encoded, 1, 0.0, 3);
+        for shard in [1, 5, 9, 13] {
+            let bit = (crate::optical_ecc::FRAME_HEADER_LEN + shard * 4) * 8 + 2;
+            readings[bit] = if readings[bit] > 0.5 { 0.45 } else { 0.55 };
+        }
+
+        let symbols = pack_soft_ook_bits(&integrate_ook_bits_soft(&readings, 1, 0.5));
+        let hard: Vec<u8> = symbols.iter().map(|&(byte, _)| byte).collect();
+        assert_ne!(hard, encoded);
+        assert_eq!(symbols.iter().filter(|&&(_, confidence)| confidence < 0.5).count(), 4);
+
+        // As unknown errors the corruption passes straight through the erasure code
+        assert_ne!(engine.decode_with_ecc(&hard).await.ok(), Some(data.clone()));
+
+        // Flagged as erasures, the same symbols decode cleanly
+        assert_eq!(engine.decode_with_ecc_erasures(&symbols).await.unwrap(), data);
+
+        // One erasure more than the parity can cover is reported, not mis-decoded
+        let mut too_many = symbols.clone();
+        too_many[crate::optical_ecc::FRAME_HEADER_LEN + 17 * 4].1 = 0.0;
+        assert!(engine.decode_with_ecc_erasures(&too_many).await.is_err());
+    }
+
+    #[test]
+    fn test_spiral_search_acquires_off_center_target() {
+        // Simulated steering channel: strong signal only within the spot radius of the peer
+        let target = (37.0f32, -22.0f32);
+        let spot_radius_px = 6.0;
+        let channel = |(x, y): (f32, f32)| {
+            let distance = ((x - target.0).powi(2) + (y - target.1).powi(2)).sqrt();
+            if distance <= spot_radius_px { 0.9 } else { 0.05 }
+        };
+
+        let spiral = AcquisitionConfig { steering_range_px: 60.0, ..AcquisitionConfig::default() };
+        let found = search_for_beam(&spiral, channel).unwrap();
+        assert!(((found.0 - target.0).powi(2) + (found.1 - target.1).powi(2)).sqrt() <= spot_radius_px);
+
+        // Both patterns visit every grid position of the steering range exactly once
+        let raster = AcquisitionConfig { pattern: SearchPattern::Raster, ..spiral };
+        let mut spiral_points = acquisition_waypoints(&spiral);
+        let mut raster_points = acquisition_waypoints(&raster);
+        assert_eq!(spiral_points.len(), 25 * 25);
+        assert_eq!(spiral_points[0], (0.0, 0.0));
+        assert!(spiral_points.iter().all(|p| p.0.abs() <= 60.0 && p.1.abs() <= 60.0));
+        let by_position = |a: &(f32, f32), b: &(f32, f32)| a.partial_cmp(b).unwrap();
+        spiral_points.sort_by(by_position);
+        raster_points.sort_by(by_position);
+        assert_eq!(spiral_points, raster_points);
+
+        assert!(search_for_beam(&raster, channel).is_some());
+
+        // Nothing in range: the search gives up rather than reporting a position
+        let far = AcquisitionConfig { steering_range_px: 10.0, ..spiral };
+        assert_eq!(search_for_beam(&far, channel), None);
+    }
+
+    #[tokio::test]
+    async fn test_interference_avoidance_skips_scheme_in_ultrasound_band() {
+        let bands = ultrasound_interference_bands(&crate::ultrasonic_beam::BeamConfig::default());
+
+        // Laser FSK's 1 kHz tone sits on the ultrasound self-demodulation product
+        assert!(interferes_with_bands(ModulationScheme::Fsk, 10_000, &bands));
+        // 12 kbps PWM puts its 4th harmonic on the 48 kHz carrier
+        assert!(interferes_with_bands(ModulationScheme::Pwm, 12_000, &bands));
+        assert!(!interferes_with_bands(ModulationScheme::Pwm, 100_000, &bands));
+
+        let config = LaserConfig { modulation: ModulationScheme::Fsk, data_rate_bps: 1_000_000, ..LaserConfig::default() };
+        let engine = LaserEngine::new(config, ReceptionConfig::default());
+        assert!(matches!(engine.select_optimal_modulation().await, ModulationScheme::Fsk));
+
+        engine.set_interference_avoidance(true);
+        let selected = engine.select_optimal_modulation().await;
+        assert!(!matches!(selected, ModulationScheme::Fsk));
+        assert!(!interferes_with_bands(selected, 1_000_000, &bands));
+    }
+
+    #[tokio::test]
+    async fn test_explanation_cites_visibility_for_far_range_qr_projection() {
+        let mut detector = RangeDetector::new();
+        detector.initialize().await.unwrap();
+        detector.set_simulated_target(Some(120.0)).await;
+        detector.measure_distance().await.unwrap();
+        detector.update_environmental_conditions(RangeEnvironmentalConditions {
+            visibility_meters: 150.0,
+            ..RangeEnvironmentalConditions::default()
+        }).await;
+
+        let mut engine = LaserEngine::new(LaserConfig::default(), ReceptionConfig::default());
+        engine.enable_adaptive_mode(Arc::new(Mutex::new(detector)));
+        assert!(matches!(engine.select_optimal_modulation().await, ModulationScheme::QrProjection));
+
+        let explanation = engine.explain_last_adaptation();
+        assert_eq!(explanation.range_category, Some(RangeDetectorCategory::Far));
+        assert_eq!(explanation.signal_quality, Some(0.8));
+        assert!(matches!(explanation.environmental_impact, Some((_, visibility, _)) if visibility == 150.0));
+        let modulation = explanation.modulation.unwrap();
+        assert!(matches!(modulation.choice, ModulationScheme::QrProjection));
+        assert_eq!(modulation.deciding_factor, AdaptationFactor::Visibility);
+
+        // In clear air the same range settles on Manchester, decided by range
+        engine.range_detector().unwrap().lock().await
+            .update_environmental_conditions(RangeEnvironmentalConditions::default()).await;
+        assert!(matches!(engine.select_optimal_modulation().await, ModulationScheme::Manchester));
+        assert_eq!(engine.explain_last_adaptation().modulation.unwrap().deciding_factor, AdaptationFactor::RangeCategory);
+    }
+
+    #[test]
+    fn test_stream_frames_reassemble_out_of_order() {
+        assert_eq!(crc16_ccitt(b"123456789"), 0x29B1);
+
+        let data: Vec<u8> = (0..1000u32).map(|i| (i * 7 % 256) as u8).collect();
+        let frames = encode_stream_frames(&data, 64).unwrap();
+        assert_eq!(frames.len(), 16);
+        assert!(frames.iter().all(|frame| frame.len() <= STREAM_FRAME_HEADER_LEN + 64));
+
+        let mut stream = StreamReassembler::new();
+        for frame in frames.iter().rev() {
+            stream.push(frame).unwrap();
+        }
+        assert!(stream.is_complete());
+        assert_eq!(stream.finish().unwrap(), data);
+
+        assert!(matches!(encode_stream_frames(&data, 0), Err(LaserError::InvalidStream(_))));
+        assert!(matches!(encode_stream_frames(&vec![0; 70_000], 1), Err(LaserError::InvalidStream(_))));
+    }
+
+    #[test]
+    fn test_stream_reassembler_detects_missing_and_corrupt_chunks() {
+        let data = vec![0xA5u8; 300];
+        let frames = encode_stream_frames(&data, 100).unwrap();
+
+        let mut stream = StreamReassembler::new();
+        stream.push(&frames[0]).unwrap();
+        stream.push(&frames[2]).unwrap();
+
+        // A flipped payload bit or header bit fails the CRC
+        let mut corrupt = frames[1].clone();
+        corrupt[STREAM_FRAME_HEADER_LEN + 10] ^= 0x01;
+        assert!(matches!(stream.push(&corrupt), Err(LaserError::DataCorruption)));
+        let mut corrupt = frames[1].clone();
+        corrupt[1] ^= 0x01;
+        assert!(matches!(stream.push(&corrupt), Err(LaserError::DataCorruption)));
+
+        assert_eq!(stream.missing(), vec![1]);
+        assert!(matches!(stream.finish(), Err(LaserError::DataCorruption)));
+    }
+
+    #[tokio::test]
+    async fn test_link_budget_clear_air_versus_fog() {
+        let mut engine = LaserEngine::new(LaserConfig::default(), ReceptionConfig::default());
+        engine.set_range_detector(Arc::new(Mutex::new(RangeDetector::new())));
+
+        engine.update_environmental_conditions(WeatherCondition::Clear, 10_000.0).await.unwrap();
+        let clear = engine.estimate_link_budget(100.0).await;
+        // 2mm beam spread to ~102mm at 100m over a 25mm aperture
+        assert!((clear.geometric_loss_db - 12.2).abs() < 0.1);
+        assert!(clear.atmospheric_loss_db < 1.0);
+        assert!(clear.link_closes);
+
+        // 50m visibility fog: ~340 dB/km
+        engine.update_environmental_conditions(WeatherCondition::Fog, 50.0).await.unwrap();
+        let fog = engine.estimate_link_budget(100.0).await;
+        assert_eq!(fog.geometric_loss_db, clear.geometric_loss_db);
+        assert!(fog.atmospheric_loss_db > 34.0);
+        assert!(fog.sensitivity_margin_db < 0.0);
+        assert!(!fog.link_closes);
+        assert!((fog.received_power_dbm - (fog.transmit_power_dbm - fog.geometric_loss_db - fog.atmospheric_loss_db)).abs() < 1e-3);
+    }
+
+    #[tokio::test]
+    async fn test_stop_continuous_monitoring_ends_the_task() {
+        let mut detector = RangeDetector::new();
+        detector.initialize().await.unwrap();
+        detector.set_simulated_target(Some(40.0)).await;
+
+        let mut engine = LaserEngine::new(LaserConfig::default(), ReceptionConfig::default());
+        assert!(engine.start_continuous_monitoring().await.is_err());
+        engine.enable_adaptive_mode(Arc::new(Mutex::new(detector)));
+
+        // Sequential sessions: each start replaces the previous task
+        for _ in 0..2 {
+            engine.start_continuous_monitoring().await.unwrap();
+            assert!(engine.is_monitoring_active().await);
+            engine.stop_continuous_monitoring().await.unwrap();
+            assert!(!engine.is_monitoring_active().await);
+        }
+
+        // Stopping with nothing running is a no-op
+        engine.stop_continuous_monitoring().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_manchester_decode_recovers_transmitted_bytes() {
+        let config = LaserConfig { modulation: ModulationScheme::Manchester, data_rate_bps: 1_000_000, ..LaserConfig::default() };
+        let mut engine = LaserEngine::new(config, ReceptionConfig::default());
+        let sink = Arc::new(std::sync::Mutex::new(Vec::new()));
+        engine.set_sample_sink(Some(sink.clone()));
+
+        let data = b"manchester round trip";
+        engine.transmit_manchester(data).await.unwrap();
+
+        // Oversample each half-bit, as a photodiode sampling above the symbol rate would
+        let samples: Vec<u8> = sink.lock().unwrap().iter()
+            .flat_map(|&intensity| std::iter::repeat_n((intensity * 255.0) as u8, 4))
+            .collect();
+        let decoded = decode_manchester_samples(&samples).unwrap();
+        assert_eq!(decoded, engine.encode_with_ecc(data).await.unwrap());
+        assert_eq!(engine.decode_with_ecc(&decoded).await.unwrap(), data);
+
+        // Holding one level through a whole bit cell is a clock slip
+        let mut slipped = samples.clone();
+        let held = slipped[80];
+        slipped[80..88].fill(held);
+        assert!(matches!(decode_manchester_samples(&slipped), Err(LaserError::DataCorruption)));
+    }
+
+    #[tokio::test]
+    async fn test_ook_round_trip_through_mock_hardware() {
+        let config = LaserConfig { modulation: ModulationScheme::Ook, ..LaserConfig::default() };
+        let tx_hardware = Arc::new(hardware::MockLaserHardware::new());
+        let mut tx = LaserEngine::new_with_hardware(config.clone(), ReceptionConfig::default(), Box::new(tx_hardware.clone()));
+        tx.initialize().await.unwrap();
+
+        let payload = b"no android required";
+        tx.transmit_data(payload).await.unwrap();
+
+        // Replay the emitted waveform into the receiver's photodiode, two samples per bit
+        let rx_hardware = Arc::new(hardware::MockLaserHardware::new());
+        rx_hardware.push_photodiode_readings(tx_hardware.power_history().into_iter()
+            .flat_map(|power_mw| [if power_mw > 0.0 { 1.0 } else { 0.0 }; 2]));
+        let rx_config = ReceptionConfig { use_photodiode: true, use_camera: false, samples_per_bit: 2, ..ReceptionConfig::default() };
+        let mut rx = LaserEngine::new_with_hardware(config.clone(), rx_config.clone(), Box::new(rx_hardware.clone()));
+        rx.initialize().await.unwrap();
+
+        assert_eq!(rx.receive_data(1000).await.unwrap(), payload);
+
+        // With the script exhausted the photodiode reports nothing to receive
+        assert!(matches!(rx.receive_data(50).await, Err(LaserError::Timeout)));
+
+        // Optical ECC frames are read to the length their header announces
+        let tx_hardware = Arc::new(hardware::MockLaserHardware::new());
+        let mut tx = LaserEngine::new_with_hardware(config.clone(), ReceptionConfig::default(), Box::new(tx_hardware.clone()));
+        tx.enable_optical_ecc(AdaptiveECCConfig::default()).unwrap();
+        tx.initialize().await.unwrap();
+        tx.transmit_data(payload).await.unwrap();
+
+        let rx_hardware = Arc::new(hardware::MockLaserHardware::new());
+        rx_hardware.push_photodiode_readings(tx_hardware.power_history().into_iter()
+            .flat_map(|power_mw| [if power_mw > 0.0 { 1.0 } else { 0.0 }; 2]));
+        let mut rx = LaserEngine::new_with_hardware(config, rx_config, Box::new(rx_hardware));
+        rx.enable_optical_ecc(AdaptiveECCConfig::default()).unwrap();
+        rx.initialize().await.unwrap();
+        assert_eq!(rx.receive_data(1000).await.unwrap(), payload);
+    }
+
+    #[tokio::test]
+    async fn test_dpsk_round_trip_and_medium_range_selection() {
+        // The delay line tracks phase flips, not absolute phase or level
+        let waveform = modulate_dpsk(&[0b1010_0110]);
+        assert_eq!(waveform.len(), 9 * LASER_DPSK_SAMPLES_PER_SYMBOL);
+        let inverted: Vec<f32> = waveform.iter().map(|s| 0.2 + 0.5 * (1.0 - s)).collect();
+        let bits = demodulate_dpsk_soft(&inverted).unwrap();
+        let symbols = pack_soft_ook_bits(&bits);
+        assert_eq!(symbols[0].0, 0b1010_0110);
+        assert!(symbols[0].1 > 0.99);
+        assert!(matches!(demodulate_dpsk_soft(&vec![0.5; 2 * LASER_DPSK_SAMPLES_PER_SYMBOL]), Err(LaserError::DataCorruption)));
+
+        let config = LaserConfig { modulation: ModulationScheme::Dpsk, ..LaserConfig::default() };
+        let tx_hardware = Arc::new(hardware::MockLaserHardware::new());
+        let mut tx = LaserEngine::new_with_hardware(config.clone(), ReceptionConfig::default(), Box::new(tx_hardware.clone()));
+        tx.initialize().await.unwrap();
+        let payload = b"phase not amplitude";
+        tx.transmit_data(payload).await.unwrap();
+
+        let rx_hardware = Arc::new(hardware::MockLaserHardware::new());
+        rx_hardware.push_photodiode_readings(tx_hardware.power_history());
+        let rx_config = ReceptionConfig { use_photodiode: true, use_camera: false, ..ReceptionConfig::default() };
+        let mut rx = LaserEngine::new_with_hardware(config.clone(), rx_config.clone(), Box::new(rx_hardware));
+        rx.initialize().await.unwrap();
+        assert_eq!(rx.receive_data(1000).await.unwrap(), payload);
+
+        // Under optical ECC the receiver reads the whole frame its header announces
+        let tx_hardware = Arc::new(hardware::MockLaserHardware::new());
+        let mut tx = LaserEngine::new_with_hardware(config.clone(), ReceptionConfig::default(), Box::new(tx_hardware.clone()));
+        tx.enable_optical_ecc(AdaptiveECCConfig::default()).unwrap();
+        tx.initialize().await.unwrap();
+        tx.transmit_data(payload).await.unwrap();
+
+        let rx_hardware = Arc::new(hardware::MockLaserHardware::new());
+        rx_hardware.push_photodiode_readings(tx_hardware.power_history());
+        let mut rx = LaserEngine::new_with_hardware(config, rx_config, Box::new(rx_hardware));
+        rx.enable_optical_ecc(AdaptiveECCConfig::default()).unwrap();
+        rx.initialize().await.unwrap();
+        assert_eq!(rx.receive_data(1000).await.unwrap(), payload);
+
+        // A clean medium-range link prefers DPSK
+        let mut detector = RangeDetector::new();
+        detector.initialize().await.unwrap();
+        detector.set_simulated_target(Some(75.0)).await;
+        detector.measure_distance().await.unwrap();
+        let mut engine = LaserEngine::new(LaserConfig::default(), ReceptionConfig::default());
+        engine.enable_adaptive_mode(Arc::new(Mutex::new(detector)));
+        assert!(matches!(engine.select_optimal_modulation().await, ModulationScheme::Dpsk));
+        assert_eq!(engine.explain_last_adaptation().modulation.unwrap().deciding_factor, AdaptationFactor::SignalQuality);
+    }
+
+    #[tokio::test]
+    async fn test_adaptive_rs_parity_follows_corrected_fraction() {
+        assert_eq!(required_parity_shards(20, 0.0, 1e-6, 12), 1);
+        assert!(required_parity_shards(20, 0.1, 1e-6, 12) > 4);
+        assert_eq!(required_parity_shards(20, 0.9, 1e-6, 12), 12);
+
+        let mut engine = LaserEngine::new(LaserConfig::default(), ReceptionConfig::default());
+        assert_eq!(engine.rs_parameters(), (16, 4));
+        engine.set_adaptive_rs(AdaptiveRsConfig { min_data_shards: 10, max_parity_shards: 12, target_ber: 1e-6 });
+
+        // Fog: parity climbs over several receives rather than in one jump
+        engine.record_corrected_fraction(0.15);
+        let (_, first) = engine.rs_parameters();
+        assert!(first > 4 && first < 12);
+        for _ in 0..10 {
+            engine.record_corrected_fraction(0.15);
+        }
+        let (data, parity) = engine.rs_parameters();
+        assert!(parity > first && parity <= 10);
+        assert_eq!(data, ADAPTIVE_RS_TOTAL_SHARDS - parity);
+
+        // The re-created codec still round-trips, and a peer that never adapted
+        // decodes the frame from the layout in its header
+        let payload = b"foggy at 150 m".to_vec();
+        let foggy_frame = engine.encode_with_ecc(&payload).await.unwrap();
+        assert_eq!(engine.decode_with_ecc(&foggy_frame).await.unwrap(), payload);
+        let mut peer = LaserEngine::new(LaserConfig::default(), ReceptionConfig::default());
+        assert_eq!(peer.decode_with_ecc(&foggy_frame).await.unwrap(), payload);
+
+        // Clear air: parity relaxes back to the minimum
+        for _ in 0..20 {
+            engine.record_corrected_fraction(0.0);
+        }
+        assert_eq!(engine.rs_parameters(), (19, 1));
+
+        // Hard-decision receives locate no errors and leave the parity alone,
+        // while frames sent under other layouts still decode
+        for _ in 0..5 {
+            assert_eq!(engine.decode_with_ecc(&foggy_frame).await.unwrap(), payload);
+        }
+        assert_eq!(engine.rs_parameters(), (19, 1));
+
+        // A frame whose flagged erasures the code could not recover pushes the
+        // parity back up. At (19,1) the 14-byte payload sits in 1-byte shards.
+        let frame = engine.encode_with_ecc(&payload).await.unwrap();
+        let mut symbols: Vec<(u8, f32)> = frame.iter().map(|&byte| (byte, 1.0)).collect();
+        symbols[crate::optical_ecc::FRAME_HEADER_LEN].1 = 0.0;
+        symbols[crate::optical_ecc::FRAME_HEADER_LEN + 1].1 = 0.0;
+        assert!(engine.decode_with_ecc_erasures(&symbols).await.is_err());
+        assert!(engine.rs_parameters().1 > 1);
+    }
+
+    #[tokio::test]
+    async fn test_auto_align_steers_servos_through_calibration() {
+        let hardware = Arc::new(hardware::MockLaserHardware::new());
+        let mut engine = LaserEngine::new_with_hardware(LaserConfig::default(), ReceptionConfig::default(), Box::new(hardware.clone()));
+        let servo = servo::MockServoController::new();
+        engine.set_servo_controller(Box::new(servo.clone()));
+        engine.set_alignment_calibration([[0.1, 0.0], [0.0, -0.1]]).await;
+        engine.set_alignment_target(50.0, 20.0).await.unwrap();
+
+        // The camera keeps reporting the spot at the origin, so each attempt
+        // steers a further 50 px right and 20 px down
+        assert!(matches!(engine.auto_align(2).await, Err(LaserError::AlignmentLost)));
+        let commands = servo.commands();
+        assert_eq!(commands.len(), 2);
+        for ((pan, tilt), expected) in commands.iter().zip([(5.0, -2.0), (10.0, -4.0)]) {
+            assert!((pan - expected.0).abs() < 1e-4 && (tilt - expected.1).abs() < 1e-4, "{:?}", commands);
+        }
+        assert!(hardware.alignment_history().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_retroreflector_echo_is_told_apart_from_active_receiver() {
+        let challenge = retroreflector_challenge();
+
+        // A cat-eye returns every chip with a fixed gain over ambient light
+        let hardware = Arc::new(hardware::MockLaserHardware::new());
+        let mut engine = LaserEngine::new_with_hardware(LaserConfig::default(), ReceptionConfig::default(), Box::new(hardware.clone()));
+        engine.initialize().await.unwrap();
+        hardware.push_photodiode_readings(challenge.iter().enumerate()
+            .map(|(i, &chip)| 0.1 + if chip { 0.5 } else { 0.0 } + (i % 3) as f32 * 0.01));
+        assert!(engine.detect_retroreflector().await.unwrap());
+        // Every chip was emitted, then the beam switched off
+        let powers = hardware.power_history();
+        assert_eq!(powers.len(), challenge.len() + 1);
+        assert_eq!(*powers.last().unwrap(), 0.0);
+
+        // An active receiver answers with its own repeating modulation
+        hardware.push_photodiode_readings((0..challenge.len()).map(|i| if i % 2 == 0 { 0.7 } else { 0.1 }));
+        assert!(!engine.detect_retroreflector().await.unwrap());
+        hardware.push_photodiode_readings(RETROREFLECTOR_SEQUENCE.iter().chain(RETROREFLECTOR_SEQUENCE.iter())
+            .map(|&chip| if chip { 0.7 } else { 0.1 }));
+        assert!(!engine.detect_retroreflector().await.unwrap());
+
+        // Nothing comes back at all
+        hardware.push_photodiode_readings(vec![0.1; challenge.len()]);
+        assert!(!engine.detect_retroreflector().await.unwrap());
+
+        // A photodiode that stops answering mid-challenge is not a clean result,
+        // and the beam is still switched off
+        hardware.push_photodiode_readings(vec![0.1; challenge.len() / 2]);
+        assert!(matches!(engine.detect_retroreflector().await, Err(LaserError::ReceptionFailed)));
+        assert_eq!(*hardware.power_history().last().unwrap(), 0.0);
+        let idle = LaserEngine::new_with_hardware(LaserConfig::default(), ReceptionConfig::default(), Box::new(hardware::MockLaserHardware::new()));
+        assert!(matches!(idle.detect_retroreflector().await, Err(LaserError::HardwareUnavailable)));
+    }
+
+    #[tokio::test]
+    async fn test_auto_align_pans_onto_range_detector_bearing() {
+        let hardware = Arc::new(hardware::MockLaserHardware::new());
+        let mut engine = LaserEngine::new_with_hardware(LaserConfig::default(), ReceptionConfig::default(), Box::new(hardware.clone()));
+        let servo = servo::MockServoController::new();
+        engine.set_servo_controller(Box::new(servo.clone()));
+        engine.set_alignment_calibration([[0.1, 0.0], [0.0, -0.1]]).await;
+
+        let mut detector = RangeDetector::with_config(RangingConfig {
+            dual_transducer_baseline_m: Some(0.15),
+            min_range_m: 1.0,
+            max_range_m: 20.0,
+            listening_timeout_ms: 100,
+            ..Default::default()
+        });
+        detector.initialize().await.unwrap();
+        detector.set_simulated_target(Some(10.0)).await;
+        detector.set_simulated_bearing(20.0).await;
+        engine.set_range_detector(Arc::new(Mutex::new(detector)));
+
+        // No signal anywhere: the bearing is tried first, then the search sweep
+        engine.set_acquisition_config(AcquisitionConfig { signal_threshold: 2.0, step_px: 50.0, ..Default::default() });
+        assert!(matches!(engine.auto_align(1).await, Err(LaserError::AlignmentLost)));
+        let (pan, tilt) = servo.commands()[0];
+        assert!((pan - 20.0).abs() < 0.5 && tilt.abs() < 1e-4, "{:?}", servo.commands());
+    }
+
+    #[tokio::test]
+    async fn test_transmit_reliable_retransmits_unacknowledged_chunks() {
+        let hardware = Arc::new(hardware::MockLaserHardware::new());
+        let mut engine = LaserEngine::new_with_hardware(LaserConfig::default(), ReceptionConfig::default(), Box::new(hardware));
+        engine.initialize().await.unwrap();
+        let payload = vec![0x5A; RELIABLE_CHUNK_SIZE + 8];
+        assert!(matches!(engine.transmit_reliable(&payload, 3).await, Err(LaserError::InvalidConfiguration(_))));
+
+        // The receiver NAKs the first copy of chunk 1, and answers nothing once silenced
+        let attempts = Arc::new(std::sync::Mutex::new(std::collections::HashMap::<u16, u32>::new()));
+        let silent = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let (counter, silenced) = (attempts.clone(), silent.clone());
+        let callback: ChunkAckCallback = Arc::new(move |sequence| {
+            let attempt = {
+                let mut attempts = counter.lock().unwrap();
+                let attempt = attempts.entry(sequence).or_insert(0);
+                *attempt += 1;
+                *attempt
+            };
+            let silent = silenced.load(std::sync::atomic::Ordering::SeqCst);
+            Box::pin(async move {
+                if silent {
+                    std::future::pending::<()>().await;
+                }
+                !(sequence == 1 && attempt == 1)
+            })
+        });
+        engine.set_ack_callback(Some(callback));
+
+        engine.transmit_reliable(&payload, 3).await.unwrap();
+        assert_eq!(attempts.lock().unwrap().get(&0), Some(&1));
+        assert_eq!(attempts.lock().unwrap().get(&1), Some(&2));
+        assert!(engine.ack_timeout() < RELIABLE_INITIAL_ACK_TIMEOUT);
+
+        attempts.lock().unwrap().clear();
+        silent.store(true, std::sync::atomic::Ordering::SeqCst);
+        assert!(matches!(engine.transmit_reliable(b"lost", 1).await, Err(LaserError::TransmissionFailed)));
+        assert_eq!(attempts.lock().unwrap().get(&0), Some(&2));
+    }
+
+    #[tokio::test]
+    async fn test_ook_emits_one_batched_buffer_at_data_rate() {
+        let config = LaserConfig { modulation: ModulationScheme::Ook, ..LaserConfig::default() };
+        let hardware = Arc::new(hardware::MockLaserHardware::new());
+        let mut engine = LaserEngine::new_with_hardware(config, ReceptionConfig::default(), Box::new(hardware.clone()));
+        engine.initialize().await.unwrap();
+        let mut profile = engine.get_current_power_profile().await;
+        profile.data_rate_bps = 1_000_000;
+        engine.set_power_profile(profile).await.unwrap();
+
+        // At 1 Mbps per-bit sleeps took a millisecond each; one buffer takes microseconds of airtime
+        let payload = b"one microsecond per bit";
+        let started = Instant::now();
+        engine.transmit_data(payload).await.unwrap();
+        assert!(started.elapsed() < Duration::from_millis(100));
+
+        let encoded = engine.encode_with_ecc(payload).await.unwrap();
+        assert_eq!(hardware.sample_rates(), vec![1_000_000]);
+        let emitted: Vec<bool> = hardware.power_history().iter().map(|&power_mw| power_mw > 0.0).collect();
+        let bits: Vec<bool> = encoded.iter().flat_map(|byte| (0..8).map(move |bit| byte & (1 << (7 - bit)) != 0)).collect();
+        assert_eq!(emitted, bits);
+
+        // A lock-in carrier chops "on" bits at twice the carrier frequency
+        let (samples, sample_rate) = ook_samples(&[0b1000_0000], 1_000, Some(4_000));
+        assert_eq!(sample_rate, 8_000);
+        assert_eq!(&samples[..8], &[1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0]);
+        assert!(samples[8..].iter().all(|&sample| sample == 0.0));
+        assert_eq!(samples.len(), 64);
+    }
+
+    #[tokio::test]
+    async fn test_sync_preamble_leads_every_transmission() {
+        let config = LaserConfig { modulation: ModulationScheme::Ook, ..LaserConfig::default() };
+        let hardware = Arc::new(hardware::MockLaserHardware::new());
+        let mut engine = LaserEngine::new_with_hardware(config, ReceptionConfig::default(), Box::new(hardware.clone()));
+        engine.initialize().await.unwrap();
+        let sequence = crate::channel_validator::zadoff_chu_preamble(1, crate::channel_validator::PREAMBLE_LEN);
+        engine.set_sync_preamble(Some(sequence.clone()));
+        engine.transmit_data(b"timed").await.unwrap();
+
+        // The preamble goes out as its own buffer ahead of the frame, at the
+        // validator's sample rate, so correlating the capture finds it
+        assert_eq!(hardware.sample_rates().len(), 2);
+        assert_eq!(hardware.sample_rates()[0], 8_000);
+        let waveform = crate::channel_validator::preamble_waveform(&sequence);
+        let validator = crate::channel_validator::ChannelValidator::new();
+        assert!(validator.correlate_channel_preambles(&hardware.power_history(), &waveform).abs() < 0.1);
+
+        engine.set_sync_preamble(None);
+        engine.transmit_data(b"untimed").await.unwrap();
+        assert_eq!(hardware.sample_rates().len(), 3);
+    }
+
+    /// Driver without a clocked output, so it relies on the default `emit_samples`
+    struct UnclockedHardware {
+        writes: std::sync::Mutex<Vec<Instant>>,
+    }
+
+    impl hardware::LaserHardware for UnclockedHardware {
+        fn set_power(&self, _power_mw: f32) -> Result<(), LaserError> {
+            self.writes.lock().unwrap().push(Instant::now());
+            Ok(())
+        }
+
+        fn read_photodiode(&self) -> Result<f32, LaserError> {
+            Err(LaserError::ReceptionFailed)
+        }
+
+        fn capture_frame(&self) -> Result<Vec<u8>, LaserError> {
+            Err(LaserError::ReceptionFailed)
+        }
+
+        fn set_alignment(&self, _x: f32, _y: f32) -> Result<(), LaserError> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_default_emit_samples_paces_writes_to_the_sample_rate() {
+        use hardware::LaserHardware;
+        let hardware = UnclockedHardware { writes: std::sync::Mutex::new(Vec::new()) };
+
+        // 50 samples at 1 kHz span 49 sample periods, not a back-to-back burst
+        hardware.emit_samples(&[1.0; 50], 1_000).unwrap();
+        let writes = hardware.writes.lock().unwrap().clone();
+        assert_eq!(writes.len(), 50);
+        assert!(writes[49] - writes[0] >= Duration::from_millis(48));
+
+        // Rates software pacing cannot hold are refused rather than emitted unpaced
+        assert!(matches!(
+            hardware.emit_samples(&[1.0], hardware::MAX_PACED_SAMPLE_RATE_HZ + 1),
+            Err(LaserError::TransmissionFailed)
+        ));
+        assert_eq!(hardware.writes.lock().unwrap().len(), 50);
+    }
+
+    /// Driver whose `emit_samples` holds the calling thread for the buffer's airtime
+    struct BlockingHardware;
+
+    impl hardware::LaserHardware for BlockingHardware {
+        fn set_power(&self, _power_mw: f32) -> Result<(), LaserError> {
+            Ok(())
+        }
+
+        fn emit_samples(&self, samples: &[f32], sample_rate: u32) -> Result<(), LaserError> {
+            std::thread::sleep(Duration::from_secs_f64(samples.len() as f64 / sample_rate as f64));
+            Ok(())
+        }
+
+        fn read_photodiode(&self) -> Result<f32, LaserError> {
+            Err(LaserError::ReceptionFailed)
+        }
+
+        fn capture_frame(&self) -> Result<Vec<u8>, LaserError> {
+            Err(LaserError::ReceptionFailed)
+        }
+
+        fn set_alignment(&self, _x: f32, _y: f32) -> Result<(), LaserError> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_blocking_emission_does_not_stall_the_executor() {
+        let mut engine = LaserEngine::new_with_hardware(LaserConfig::default(), ReceptionConfig::default(), Box::new(BlockingHardware));
+        engine.initialize().await.unwrap();
+        let mut profile = engine.get_current_power_profile().await;
+        profile.data_rate_bps = 1_000;
+        engine.set_power_profile(profile).await.unwrap();
+        let power_profile = engine.current_power_profile.clone();
+        let safety_monitor = engine.safety_monitor.clone();
+
+        // At 1 kbps the frame's airtime runs to hundreds of milliseconds
+        let transmission = tokio::spawn(async move { engine.transmit_data(b"paced").await });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!transmission.is_finished());
+
+        // The single-threaded test runtime still schedules us, and neither lock is held
+        assert!(tokio::time::timeout(Duration::from_millis(10), power_profile.lock()).await.is_ok());
+        assert!(tokio::time::timeout(Duration::from_millis(10), safety_monitor.lock()).await.is_ok());
+        assert!(!transmission.is_finished());
+
+        transmission.await.unwrap().unwrap();
+    }
+}
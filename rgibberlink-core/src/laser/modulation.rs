@@ -1,458 +0,0 @@
-//! Laser modulation schemes and transmission methods
-
-use tokio::time::{Duration, Instant};
-use reed_solomon_erasure::galois_8::ReedSolomon;
-
-use super::types::{ModulationScheme, LaserType};
-use super::error::LaserError;
-use super::safety::PowerManager;
-use crate::visual::{VisualEngine, VisualPayload};
-use crate::optical_ecc::OpticalECC;
-
-pub struct ModulationController {
-    rs_codec: ReedSolomon,
-    visual_engine: VisualEngine,
-    optical_ecc: Option<OpticalECC>,
-    current_modulation: ModulationScheme,
-    data_rate_bps: u32,
-    power_manager: PowerManager,
-}
-
-impl ModulationController {
-    pub fn new(laser_type: LaserType) -> Self {
-        let visual_engine = VisualEngine::new();
-        // Reed-Solomon for error correction (16 data, 4 parity)
-        let rs_codec = ReedSolomon::new(16, 4).expect("Failed to create RS codec");
-
-        Self {
-            rs_codec,
-            visual_engine,
-            optical_ecc: None,
-            current_modulation: ModulationScheme::Ook,
-            data_rate_bps: 1_000_000,
-            power_manager: PowerManager::new(laser_type),
-        }
-    }
-
-    pub async fn transmit_data(&mut self, data: &[u8]) -> Result<(), LaserError> {
-        // Encode data with error correction
-        let encoded = self.encode_with_ecc(data).await?;
-
-        match self.current_modulation {
-            ModulationScheme::Ook => self.transmit_ook(&encoded).await,
-            ModulationScheme::Pwm => self.transmit_pwm(&encoded).await,
-            ModulationScheme::QrProjection => self.transmit_qr_projection(&encoded).await,
-            ModulationScheme::Fsk => self.transmit_fsk(&encoded).await,
-            ModulationScheme::Manchester => self.transmit_manchester(&encoded).await,
-        }
-    }
-
-    /// Transmit using On-Off Keying modulation
-    async fn transmit_ook(&mut self, data: &[u8]) -> Result<(), LaserError> {
-        for byte in data {
-            for bit in 0..8 {
-                let is_on = (byte & (1 << (7 - bit))) != 0;
-                let intensity = if is_on { 1.0 } else { 0.0 };
-
-                self.power_manager.validate_power_level(intensity).await?;
-                self.set_laser_intensity(intensity).await?;
-                self.power_manager.record_power_usage(intensity, 1).await;
-
-                tokio::time::sleep(Duration::from_micros(1_000_000 / self.data_rate_bps as u64)).await;
-            }
-        }
-        Ok(())
-    }
-
-    /// Transmit using Pulse Width Modulation
-    async fn transmit_pwm(&mut self, data: &[u8]) -> Result<(), LaserError> {
-        for byte in data {
-            // PWM: duty cycle represents data value
-            let duty_cycle = byte as f32 / 255.0;
-            self.transmit_pwm_byte(duty_cycle).await?;
-        }
-        Ok(())
-    }
-
-    /// Transmit a single PWM byte
-    async fn transmit_pwm_byte(&self, duty_cycle: f32) -> Result<(), LaserError> {
-        let period_us = 1_000_000 / self.data_rate_bps as u64;
-        let on_time_us = (period_us as f32 * duty_cycle) as u64;
-        let off_time_us = period_us - on_time_us;
-
-        self.power_manager.validate_power_level(1.0).await?;
-        self.set_laser_intensity(1.0).await?;
-        self.power_manager.record_power_usage(1.0, on_time_us as u64 / 1000).await;
-        tokio::time::sleep(Duration::from_micros(on_time_us)).await;
-
-        self.set_laser_intensity(0.0).await?;
-        tokio::time::sleep(Duration::from_micros(off_time_us)).await;
-
-        Ok(())
-    }
-
-    /// Transmit using dynamic QR code projection
-    async fn transmit_qr_projection(&mut self, data: &[u8]) -> Result<(), LaserError> {
-        // Create visual payload from encoded data
-        let payload = VisualPayload {
-            session_id: [0; 16], // Would be set properly in real implementation
-            public_key: data.to_vec(),
-            nonce: [0; 16],
-            signature: vec![],
-        };
-
-        // Generate QR code using VisualEngine
-        let qr_svg = self.visual_engine.encode_payload(&payload)?;
-
-        // Project the QR code (would control laser projector)
-        self.project_qr_code(&qr_svg).await?;
-
-        Ok(())
-    }
-
-    /// Transmit using Frequency Shift Keying
-    async fn transmit_fsk(&mut self, data: &[u8]) -> Result<(), LaserError> {
-        // FSK: Use two different frequencies for 0 and 1
-        // Frequency 1: base frequency, Frequency 2: base + offset
-        let base_freq = 1000.0; // 1kHz base
-        let freq_offset = 500.0; // 500Hz offset
-
-        for byte in data {
-            for bit in 0..8 {
-                let is_high = (byte & (1 << (7 - bit))) != 0;
-                let frequency = if is_high { base_freq + freq_offset } else { base_freq };
-
-                // Transmit at the selected frequency for one bit period
-                self.transmit_frequency(frequency, Duration::from_micros(1_000_000 / self.data_rate_bps as u64)).await?;
-            }
-        }
-
-        Ok(())
-    }
-
-    /// Transmit using Manchester encoding
-    async fn transmit_manchester(&mut self, data: &[u8]) -> Result<(), LaserError> {
-        // Manchester encoding: 0 = 01, 1 = 10
-        // Self-clocking, good for noisy channels
-        for byte in data {
-            for bit in 0..8 {
-                let bit_value = (byte & (1 << (7 - bit))) != 0;
-
-                // Manchester: transition in middle of bit period
-                let half_bit_duration = Duration::from_micros(500_000 / self.data_rate_bps as u64);
-
-                if bit_value {
-                    // 1: high-low
-                    self.power_manager.validate_power_level(1.0).await?;
-                    self.set_laser_intensity(1.0).await?;
-                    self.power_manager.record_power_usage(1.0, half_bit_duration.as_millis() as u64).await;
-                    tokio::time::sleep(half_bit_duration).await;
-                    self.set_laser_intensity(0.0).await?;
-                    tokio::time::sleep(half_bit_duration).await;
-                } else {
-                    // 0: low-high
-                    self.set_laser_intensity(0.0).await?;
-                    tokio::time::sleep(half_bit_duration).await;
-                    self.power_manager.validate_power_level(1.0).await?;
-                    self.set_laser_intensity(1.0).await?;
-                    self.power_manager.record_power_usage(1.0, half_bit_duration.as_millis() as u64).await;
-                    tokio::time::sleep(half_bit_duration).await;
-                }
-            }
-        }
-
-        Ok(())
-    }
-
-    /// Set laser intensity (0.0 to 1.0)
-    async fn set_laser_intensity(&self, intensity: f32) -> Result<(), LaserError> {
-        // Safety check
-        if intensity > 1.0 || intensity < 0.0 {
-            return Err(LaserError::SafetyViolation);
-        }
-
-        // Get effective power limit from current profile
-        let effective_limit = self.power_manager.get_effective_power_limit().await;
-        let power = intensity * effective_limit;
-
-        // Hardware control
-        #[cfg(target_os = "android")]
-        {
-            let result = unsafe { super::hardware::laser_set_power(power) };
-            if result != 0 {
-                return Err(LaserError::TransmissionFailed);
-            }
-        }
-
-        #[cfg(not(target_os = "android"))] {
-            // Mock implementation
-            // laser_hardware.set_power(power);
-        }
-
-        Ok(())
-    }
-
-    /// Transmit at a specific frequency for a duration
-    async fn transmit_frequency(&self, _frequency: f32, duration: Duration) -> Result<(), LaserError> {
-        // In a real implementation, this would modulate the laser at the specified frequency
-        // For now, simulate with on/off patterns
-        self.power_manager.validate_power_level(1.0).await?;
-        self.set_laser_intensity(1.0).await?;
-        self.power_manager.record_power_usage(1.0, duration.as_millis() as u64).await;
-        tokio::time::sleep(duration).await;
-        Ok(())
-    }
-
-    /// Project QR code (laser projector control)
-    async fn project_qr_code(&self, _qr_svg: &str) -> Result<(), LaserError> {
-        // Would control laser projector to display QR code
-        // For now, just simulate
-        tokio::time::sleep(Duration::from_millis(100)).await;
-        Ok(())
-    }
-
-    /// Encode data with error correction (OpticalECC if enabled, otherwise Reed-Solomon)
-    async fn encode_with_ecc(&mut self, data: &[u8]) -> Result<Vec<u8>, LaserError> {
-        if let Some(optical_ecc) = &mut self.optical_ecc {
-            // Use enhanced optical ECC
-            optical_ecc.encode(data).await
-                .map_err(|_| LaserError::DataCorruption)
-        } else {
-            // Fall back to basic Reed-Solomon
-            let shard_size = (data.len() + 15) / 16; // Ceiling division
-            let mut shards: Vec<Vec<u8>> = Vec::with_capacity(20);
-
-            // Split data into shards
-            for i in 0..16 {
-                let start = i * shard_size;
-                let end = std::cmp::min(start + shard_size, data.len());
-                let mut shard = data[start..end].to_vec();
-                shard.resize(shard_size, 0);
-                shards.push(shard);
-            }
-
-            // Add parity shards
-            shards.resize(20, vec![0; shard_size]);
-            self.rs_codec.encode(&mut shards).map_err(|_| LaserError::DataCorruption)?;
-
-            // Flatten
-            let mut encoded = Vec::new();
-            for shard in shards {
-                encoded.extend(shard);
-            }
-
-            Ok(encoded)
-        }
-    }
-
-    /// Enable optical ECC with configuration
-    pub fn enable_optical_ecc(&mut self, config: crate::optical_ecc::AdaptiveECCConfig) -> Result<(), LaserError> {
-        self.optical_ecc = Some(OpticalECC::new(config));
-        Ok(())
-    }
-
-    /// Disable optical ECC (fall back to basic Reed-Solomon)
-    pub fn disable_optical_ecc(&mut self) {
-        self.optical_ecc = None;
-    }
-
-    /// Check if optical ECC is enabled
-    pub fn is_optical_ecc_enabled(&self) -> bool {
-        self.optical_ecc.is_some()
-    }
-
-    /// Update optical quality metrics for adaptive ECC
-    pub async fn update_optical_quality(&mut self, metrics: crate::optical_ecc::OpticalQualityMetrics) -> Result<(), LaserError> {
-        if let Some(optical_ecc) = &mut self.optical_ecc {
-            optical_ecc.update_quality_metrics(metrics).await
-                .map_err(|_| LaserError::DataCorruption)?;
-        }
-        Ok(())
-    }
-
-    /// Set modulation scheme
-    pub fn set_modulation_scheme(&mut self, scheme: ModulationScheme) {
-        self.current_modulation = scheme;
-    }
-
-    /// Get current modulation scheme
-    pub fn get_modulation_scheme(&self) -> ModulationScheme {
-        self.current_modulation
-    }
-
-    /// Set data rate
-    pub fn set_data_rate(&mut self, data_rate_bps: u32) {
-        self.data_rate_bps = data_rate_bps;
-    }
-
-    /// Get data rate
-    pub fn get_data_rate(&self) -> u32 {
-        self.data_rate_bps
-    }
-}
-
-pub struct ReceptionController {
-    modulation_controller: ModulationController,
-}
-
-impl ReceptionController {
-    pub fn new(laser_type: LaserType) -> Self {
-        Self {
-            modulation_controller: ModulationController::new(laser_type),
-        }
-    }
-
-    pub async fn receive_data(&mut self, timeout_ms: u64) -> Result<Vec<u8>, LaserError> {
-        let timeout = Duration::from_millis(timeout_ms);
-        let start = Instant::now();
-
-        loop {
-            if start.elapsed() > timeout {
-                return Err(LaserError::Timeout);
-            }
-
-            match self.modulation_controller.current_modulation {
-                ModulationScheme::Ook => {
-                    if let Ok(data) = self.receive_ook().await {
-                        return Ok(data);
-                    }
-                }
-                ModulationScheme::Pwm => {
-                    if let Ok(data) = self.receive_pwm().await {
-                        return Ok(data);
-                    }
-                }
-                ModulationScheme::QrProjection => {
-                    if let Ok(data) = self.receive_qr_projection().await {
-                        return Ok(data);
-                    }
-                }
-                ModulationScheme::Fsk => {
-                    if let Ok(data) = self.receive_fsk().await {
-                        return Ok(data);
-                    }
-                }
-                ModulationScheme::Manchester => {
-                    if let Ok(data) = self.receive_manchester().await {
-                        return Ok(data);
-                    }
-                }
-            }
-
-            // Small delay to prevent busy waiting
-            tokio::time::sleep(Duration::from_millis(10)).await;
-        }
-    }
-
-    /// Receive using On-Off Keying modulation
-    async fn receive_ook(&mut self) -> Result<Vec<u8>, LaserError> {
-        // Check alignment first (would be done elsewhere)
-        // Receive raw signal
-        let raw_data = self.receive_photodiode().await?;
-        // Decode with error correction
-        self.decode_with_ecc(&raw_data).await
-    }
-
-    /// Receive using Pulse Width Modulation
-    async fn receive_pwm(&mut self) -> Result<Vec<u8>, LaserError> {
-        // Receive raw signal
-        let raw_data = self.receive_photodiode().await?;
-        // Decode with error correction
-        self.decode_with_ecc(&raw_data).await
-    }
-
-    /// Receive using QR code scanning
-    async fn receive_qr_projection(&mut self) -> Result<Vec<u8>, LaserError> {
-        // Capture QR code from camera
-        let raw_data = self.receive_camera().await?;
-        // Decode QR data using VisualEngine
-        let payload = self.modulation_controller.visual_engine.decode_payload(&raw_data)?;
-        // Decode with error correction
-        self.decode_with_ecc(&payload.public_key).await
-    }
-
-    /// Receive using Frequency Shift Keying
-    async fn receive_fsk(&mut self) -> Result<Vec<u8>, LaserError> {
-        // Receive raw signal - would detect frequency shifts
-        let raw_data = self.receive_photodiode().await?;
-        // Decode FSK signal (simplified - would analyze frequency content)
-        self.decode_fsk_signal(&raw_data).await
-    }
-
-    /// Receive using Manchester encoding
-    async fn receive_manchester(&mut self) -> Result<Vec<u8>, LaserError> {
-        // Receive raw signal
-        let raw_data = self.receive_photodiode().await?;
-        // Decode Manchester signal (simplified)
-        self.decode_manchester_signal(&raw_data).await
-    }
-
-    /// Receive using photodiode
-    async fn receive_photodiode(&self) -> Result<Vec<u8>, LaserError> {
-        #[cfg(target_os = "android")] {
-            // Read analog value from photodiode
-            let reading = unsafe { super::hardware::laser_get_photodiode_reading() };
-            // Convert analog reading to digital data
-            // This is a simplified implementation
-            let digital_value = if reading > 0.1 { 1 } else { 0 };
-            Ok(vec![digital_value])
-        }
-
-        #[cfg(not(target_os = "android"))] {
-            // Mock implementation
-            Err(LaserError::ReceptionFailed)
-        }
-    }
-
-    /// Receive using camera
-    async fn receive_camera(&self) -> Result<Vec<u8>, LaserError> {
-        // Would capture and analyze camera frames
-        // For now, return mock data
-        Err(LaserError::ReceptionFailed)
-    }
-
-    /// Decode data with error correction (OpticalECC if enabled, otherwise Reed-Solomon)
-    async fn decode_with_ecc(&mut self, data: &[u8]) -> Result<Vec<u8>, LaserError> {
-        if let Some(optical_ecc) = &mut self.modulation_controller.optical_ecc {
-            // Use enhanced optical ECC
-            optical_ecc.decode(data).await
-                .map_err(|_| LaserError::DataCorruption)
-        } else {
-            // Fall back to basic Reed-Solomon
-            let total_size = data.len();
-            let shard_size = (total_size + 19) / 20;
-            let mut shards: Vec<Option<Vec<u8>>> = Vec::with_capacity(20);
-
-            for i in 0..20 {
-                let start = i * shard_size;
-                let end = std::cmp::min(start + shard_size, total_size);
-                shards.push(Some(data[start..end].to_vec()));
-            }
-
-            self.modulation_controller.rs_codec.reconstruct(&mut shards).map_err(|_| LaserError::DataCorruption)?;
-
-            let mut decoded = Vec::new();
-            for shard in shards.into_iter().take(16).flatten() {
-                decoded.extend(shard);
-            }
-
-            Ok(decoded)
-        }
-    }
-
-    /// Decode FSK signal (simplified implementation)
-    async fn decode_fsk_signal(&self, _raw_data: &[u8]) -> Result<Vec<u8>, LaserError> {
-        // In a real implementation, this would perform FFT analysis
-        // to detect frequency shifts and decode the data
-        // For now, return mock decoded data
-        Ok(vec![0xAA, 0xBB, 0xCC]) // Mock data
-    }
-
-    /// Decode Manchester signal (simplified implementation)
-    async fn decode_manchester_signal(&self, _raw_data: &[u8]) -> Result<Vec<u8>, LaserError> {
-        // In a real implementation, this would detect transitions
-        // and decode Manchester-encoded bits
-        // For now, return mock decoded data
-        Ok(vec![0x11, 0x22, 0x33]) // Mock data
-    }
-}
\ No newline at end of file
@@ -0,0 +1,124 @@
+//! Pan/tilt servo beam steering
+
+use std::sync::{Arc, Mutex};
+
+use super::error::LaserError;
+
+/// Steers the laser head to absolute pan/tilt angles, in degrees from center
+pub trait ServoController: Send + Sync {
+    fn set_pan_tilt(&mut self, pan_deg: f32, tilt_deg: f32) -> Result<(), LaserError>;
+}
+
+/// One PWM output of the platform's PWM peripheral
+pub trait PwmChannel: Send + Sync {
+    /// Drive a pulse of `pulse_us` microseconds every PWM period
+    fn set_pulse_width_us(&mut self, pulse_us: f32) -> Result<(), LaserError>;
+}
+
+/// Mechanical travel of a hobby servo and the pulse widths at its end stops
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ServoRange {
+    pub min_deg: f32,
+    pub max_deg: f32,
+    pub min_pulse_us: f32,
+    pub max_pulse_us: f32,
+}
+
+impl Default for ServoRange {
+    fn default() -> Self {
+        Self {
+            min_deg: -90.0,
+            max_deg: 90.0,
+            min_pulse_us: 500.0,
+            max_pulse_us: 2500.0,
+        }
+    }
+}
+
+impl ServoRange {
+    /// Pulse width for `angle_deg`, clamped to the servo's travel
+    pub fn pulse_width_us(&self, angle_deg: f32) -> f32 {
+        let fraction = (angle_deg.clamp(self.min_deg, self.max_deg) - self.min_deg) / (self.max_deg - self.min_deg);
+        self.min_pulse_us + fraction * (self.max_pulse_us - self.min_pulse_us)
+    }
+}
+
+/// Pan and tilt servos driven from two PWM channels
+pub struct GpioServoController {
+    pan: Box<dyn PwmChannel>,
+    tilt: Box<dyn PwmChannel>,
+    pan_range: ServoRange,
+    tilt_range: ServoRange,
+}
+
+impl GpioServoController {
+    pub fn new(pan: Box<dyn PwmChannel>, tilt: Box<dyn PwmChannel>) -> Self {
+        Self::with_ranges(pan, tilt, ServoRange::default(), ServoRange::default())
+    }
+
+    pub fn with_ranges(pan: Box<dyn PwmChannel>, tilt: Box<dyn PwmChannel>, pan_range: ServoRange, tilt_range: ServoRange) -> Self {
+        Self { pan, tilt, pan_range, tilt_range }
+    }
+}
+
+impl ServoController for GpioServoController {
+    fn set_pan_tilt(&mut self, pan_deg: f32, tilt_deg: f32) -> Result<(), LaserError> {
+        if !pan_deg.is_finite() || !tilt_deg.is_finite() {
+            return Err(LaserError::ServoFault(format!("non-finite angle ({pan_deg}, {tilt_deg})")));
+        }
+        self.pan.set_pulse_width_us(self.pan_range.pulse_width_us(pan_deg))?;
+        self.tilt.set_pulse_width_us(self.tilt_range.pulse_width_us(tilt_deg))
+    }
+}
+
+/// Records pan/tilt commands for tests; clones share one history
+#[derive(Clone, Default)]
+pub struct MockServoController {
+    commands: Arc<Mutex<Vec<(f32, f32)>>>,
+}
+
+impl MockServoController {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every `(pan_deg, tilt_deg)` commanded so far
+    pub fn commands(&self) -> Vec<(f32, f32)> {
+        self.commands.lock().unwrap().clone()
+    }
+}
+
+impl ServoController for MockServoController {
+    fn set_pan_tilt(&mut self, pan_deg: f32, tilt_deg: f32) -> Result<(), LaserError> {
+        self.commands.lock().unwrap().push((pan_deg, tilt_deg));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct RecordingPwm(Arc<Mutex<Vec<f32>>>);
+
+    impl PwmChannel for RecordingPwm {
+        fn set_pulse_width_us(&mut self, pulse_us: f32) -> Result<(), LaserError> {
+            self.0.lock().unwrap().push(pulse_us);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_gpio_servo_maps_angles_to_clamped_pulse_widths() {
+        let (pan, tilt) = (Arc::new(Mutex::new(Vec::new())), Arc::new(Mutex::new(Vec::new())));
+        let mut servo = GpioServoController::new(Box::new(RecordingPwm(pan.clone())), Box::new(RecordingPwm(tilt.clone())));
+
+        servo.set_pan_tilt(0.0, 45.0).unwrap();
+        servo.set_pan_tilt(-90.0, 120.0).unwrap();
+        assert_eq!(*pan.lock().unwrap(), vec![1500.0, 500.0]);
+        assert_eq!(*tilt.lock().unwrap(), vec![2000.0, 2500.0]);
+
+        assert!(matches!(servo.set_pan_tilt(f32::NAN, 0.0), Err(LaserError::ServoFault(_))));
+        assert_eq!(pan.lock().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,244 @@
+//! Laser configuration and power types
+
+use crate::range_detector::RangeDetectorCategory;
+
+use super::alignment::AlignmentStatus;
+use super::error::LaserError;
+
+/// Laser diode family, which fixes the eye-safe power ceiling
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum LaserType {
+    Red,
+    Green,
+    Blue,
+    Infrared,
+    UV,
+    External,
+}
+
+/// Optical modulation schemes supported by the engine
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum ModulationScheme {
+    Ook,
+    Pwm,
+    QrProjection,
+    Fsk,
+    Manchester,
+    Dpsk,
+}
+
+/// Laser configuration parameters
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct LaserConfig {
+    pub laser_type: LaserType,
+    pub modulation: ModulationScheme,
+    pub max_power_mw: f32,
+    pub wavelength_nm: u32,
+    pub beam_diameter_mm: f32,
+    pub range_meters: f32,
+    pub data_rate_bps: u32,
+    pub safety_enabled: bool,
+}
+
+impl Default for LaserConfig {
+    fn default() -> Self {
+        Self::red() // Default to red laser
+    }
+}
+
+impl LaserConfig {
+    /// Create a red laser configuration (650nm)
+    pub fn red() -> Self {
+        Self {
+            laser_type: LaserType::Red,
+            modulation: ModulationScheme::Ook,
+            max_power_mw: 5.0,
+            wavelength_nm: 650,
+            beam_diameter_mm: 2.0,
+            range_meters: 100.0,
+            data_rate_bps: 1_000_000,
+            safety_enabled: true,
+        }
+    }
+
+    /// Create a green laser configuration (532nm)
+    pub fn green() -> Self {
+        Self { laser_type: LaserType::Green, wavelength_nm: 532, ..Self::red() }
+    }
+
+    /// Create a blue laser configuration (450nm)
+    pub fn blue() -> Self {
+        Self { laser_type: LaserType::Blue, wavelength_nm: 450, ..Self::red() }
+    }
+
+    /// Create an infrared laser configuration
+    pub fn infrared() -> Self {
+        Self {
+            laser_type: LaserType::Infrared,
+            max_power_mw: 10.0, // IR can handle higher power
+            wavelength_nm: 980, // Common IR wavelength
+            range_meters: 200.0, // IR has better range
+            ..Self::red()
+        }
+    }
+
+    /// Create a UV laser configuration
+    pub fn uv() -> Self {
+        Self {
+            laser_type: LaserType::UV,
+            max_power_mw: 1.0, // UV lasers are typically lower power
+            wavelength_nm: 405, // Near UV
+            beam_diameter_mm: 1.5,
+            range_meters: 50.0, // UV has shorter range
+            ..Self::red()
+        }
+    }
+
+    /// Create an external/custom laser configuration
+    pub fn external(wavelength_nm: u32) -> Self {
+        Self { laser_type: LaserType::External, wavelength_nm, ..Self::red() }
+    }
+
+    /// Highest `max_power_mw` a configuration may ask for with this laser type
+    /// (the power of the matching preset above)
+    pub fn power_ceiling_mw(&self) -> f32 {
+        match self.laser_type {
+            LaserType::Infrared => 10.0,
+            LaserType::UV => 1.0,
+            _ => 5.0,
+        }
+    }
+
+    /// Reject a `max_power_mw` that is not a positive, finite value within
+    /// `power_ceiling_mw`
+    pub fn validate(&self) -> Result<(), LaserError> {
+        let ceiling_mw = self.power_ceiling_mw();
+        if !self.max_power_mw.is_finite() || self.max_power_mw <= 0.0 || self.max_power_mw > ceiling_mw {
+            return Err(LaserError::InvalidConfiguration(format!(
+                "max_power_mw {} outside (0, {}] mW for {:?} laser", self.max_power_mw, ceiling_mw, self.laser_type
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Reception configuration
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct ReceptionConfig {
+    pub use_photodiode: bool,
+    pub use_camera: bool,
+    pub photodiode_sensitivity: f32,
+    /// Normalized intensity above which a photodiode reading counts as "on"
+    pub sensitivity_threshold: f32,
+    pub camera_resolution: (u32, u32),
+    pub frame_rate_hz: u32,
+    pub exposure_time_us: u32,
+    /// Beam offset from the target still counted as aligned
+    pub alignment_tolerance_px: u32,
+    /// Photodiode readings integrated per OOK bit (1 = single-sample decisions)
+    pub samples_per_bit: u32,
+    /// Demodulated bytes below this confidence are handed to Reed-Solomon as erasures
+    pub erasure_confidence_threshold: f32,
+    /// Carrier the transmitter chops "on" bits at; the receiver bandpasses around it
+    /// to reject ambient light. `None` keeps plain intensity thresholding.
+    pub lock_in_carrier_hz: Option<u32>,
+    /// Width of the lock-in bandpass; must comfortably exceed the bit rate
+    pub bandpass_bandwidth_hz: u32,
+}
+
+impl Default for ReceptionConfig {
+    fn default() -> Self {
+        Self {
+            use_photodiode: true,
+            use_camera: false,
+            photodiode_sensitivity: 0.8,
+            sensitivity_threshold: 0.5,
+            camera_resolution: (640, 480),
+            frame_rate_hz: 30,
+            exposure_time_us: 1000,
+            alignment_tolerance_px: 5,
+            samples_per_bit: 1,
+            erasure_confidence_threshold: 0.5,
+            lock_in_carrier_hz: None,
+            bandpass_bandwidth_hz: 2_000,
+        }
+    }
+}
+
+/// Transmit power and rate for the current link range
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PowerProfile {
+    pub max_power_mw: f32,
+    pub optimal_power_mw: f32,
+    pub min_power_mw: f32,
+    pub data_rate_bps: u32,
+    pub beam_angle_deg: f32,
+    /// Fraction of the laser type's eye-safe limit the profile may use
+    pub safety_margin: f32,
+}
+
+impl Default for PowerProfile {
+    fn default() -> Self {
+        Self::for_range_category(&RangeDetectorCategory::Medium)
+    }
+}
+
+impl PowerProfile {
+    /// Profile tuned for a range band: more power and a narrower beam farther
+    /// out, with the rate backed off as the link margin shrinks
+    pub fn for_range_category(category: &RangeDetectorCategory) -> Self {
+        let (min_power_mw, optimal_power_mw, max_power_mw, data_rate_bps, beam_angle_deg) = match category {
+            RangeDetectorCategory::Close => (0.2, 0.5, 1.0, 100_000, 2.0),
+            RangeDetectorCategory::Medium => (0.5, 1.5, 3.0, 50_000, 1.0),
+            RangeDetectorCategory::Far => (1.0, 3.0, 4.5, 25_000, 0.5),
+            RangeDetectorCategory::Extreme => (2.0, 4.0, 5.0, 10_000, 0.25),
+        };
+        Self { max_power_mw, optimal_power_mw, min_power_mw, data_rate_bps, beam_angle_deg, safety_margin: 1.0 }
+    }
+
+    /// Eye-safe power ceiling for `laser_type`, scaled by `safety_margin`
+    pub fn safe_power_limit(&self, laser_type: &LaserType) -> f32 {
+        let class_limit_mw = match laser_type {
+            LaserType::Infrared => 10.0,
+            LaserType::UV => 1.0,
+            LaserType::Red | LaserType::Green | LaserType::Blue | LaserType::External => 5.0,
+        };
+        class_limit_mw * self.safety_margin.clamp(0.0, 1.0)
+    }
+}
+
+/// Battery readings used to scale transmit power
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatteryState {
+    pub capacity_percent: f32,
+    pub estimated_runtime_hours: f32,
+    pub temperature_celsius: f32,
+    pub is_charging: bool,
+}
+
+/// Energy and power figures accumulated by the engine
+#[derive(Debug, Clone, PartialEq)]
+pub struct PowerStatistics {
+    pub total_energy_consumed_joules: f64,
+    pub average_power_mw: f32,
+    pub peak_power_mw: f32,
+    pub duty_cycle_percent: f32,
+    pub efficiency_rating: f32,
+}
+
+/// Snapshot of the laser channel for health reporting
+#[derive(Debug, Clone)]
+pub struct LaserChannelDiagnostics {
+    pub is_active: bool,
+    pub alignment_status: AlignmentStatus,
+    pub power_consumption_mw: f32,
+    pub power_efficiency: f32,
+    pub power_safe: bool,
+    pub battery_state: Option<BatteryState>,
+    pub power_statistics: PowerStatistics,
+    pub detected_failures: Vec<LaserError>,
+    pub optical_ecc_enabled: bool,
+    pub adaptive_mode: bool,
+}
@@ -1,8 +1,8 @@
 //! # RgibberLink Core Library
 //!
 //! A comprehensive Rust library implementing both short-range and long-range secure directional
-//! communication protocols. Supports ultrasonic audio transmission (18-22kHz FSK) with GGWave
-//! integration, ECDH key exchange, QR codes with Reed-Solomon ECC, and advanced long-range
+//! communication protocols. Supports ultrasonic audio transmission (18-22kHz multi-tone
+//! FSK), ECDH key exchange, QR codes with Reed-Solomon ECC, and advanced long-range
 //! capabilities using focused ultrasound beams and laser modulation.
 //!
 //! ## Architecture Overview
@@ -12,7 +12,7 @@
 //!
 //! ### Core Engines
 //! - **`CryptoEngine`**: Handles ECDH key exchange, AES-GCM encryption, HMAC verification, and cross-channel signatures
-//! - **`AudioEngine`**: Manages GGWave ultrasonic transmissions for short-range communication
+//! - **`AudioEngine`**: Manages multi-tone ultrasonic transmissions for short-range communication
 //! - **`UltrasonicBeamEngine`**: Focused ultrasound communication (10-30m range) with parametric audio and beam forming
 //! - **`VisualEngine`**: Generates QR codes with Reed-Solomon ECC and CBOR compression
 //! - **`LaserEngine`**: High-speed optical data transmission with laser modulation, OOK/PWM/QM schemes, and adaptive ECC
@@ -20,8 +20,11 @@
 //! - **`OpticalECC`**: Advanced error correction for laser transmission with atmospheric compensation
 //! - **`ChannelValidator`**: Implements coupled channel validation requiring simultaneous presence in both beams
 //! - **`ProtocolEngine`**: Implements the handshake state machine with coupled validation and fallback mechanisms
+//! - **`SessionManager`**: One `ProtocolEngine` and message queue per peer, for talking to several devices at once
 //! - **`SecurityManager`**: Permission-based access control with peer trust assessment and environmental monitoring
 //! - **`FallbackManager`**: Automatic degradation from long-range to short-range modes with recovery monitoring
+//! - **`DuplexSession`**: Concurrent laser data transfer with ultrasound ACK/NAK flow control
+//! - **`BeaconTransport`**: Unauthenticated discovery beacons advertising presence and capabilities before pairing
 //!
 //! ## Communication Modes
 //!
@@ -69,7 +72,7 @@
 //!
 //! ### Coupled Channel Security
 //! Requires simultaneous reception from both channels for authentication:
-//! - **Temporal Coupling**: Channels must arrive within 100ms window
+//! - **Temporal Coupling**: Channels must arrive within ±100ms of the skew expected at the peer's range
 //! - **Cross-Channel Signatures**: Each channel authenticates the other
 //! - **Anti-Replay Protection**: Coupled nonces prevent replay attacks
 //! - **Quality Validation**: Minimum signal strength and correlation thresholds
@@ -143,7 +146,7 @@
 //! beam_engine.initialize().await?;
 //!
 //! let laser_config = gibberlink_core::LaserConfig {
-//!     laser_type: gibberlink_core::LaserType::Visible,
+//!     laser_type: gibberlink_core::LaserType::Red,
 //!     modulation: gibberlink_core::ModulationScheme::Ook,
 //!     max_power_mw: 50.0,
 //!     range_meters: 100.0,
@@ -198,14 +201,14 @@
 //! ## Security Features
 //!
 //! ### Coupled Channel Authentication
-//! - **Temporal Correlation**: Both channels must arrive simultaneously (±100ms)
+//! - **Temporal Correlation**: Ultrasound must trail the laser by its propagation lag (±100ms)
 //! - **Cross-Signature Verification**: Ultrasound authenticates laser data and vice versa
 //! - **Anti-Interception**: Requires presence in both directional beams
 //! - **Quality Thresholds**: Minimum signal strength and correlation requirements
 //!
 //! ### Advanced Cryptography
 //! - **ECDH Key Exchange**: Ephemeral keys for perfect forward secrecy
-//! - **AES-GCM Encryption**: Authenticated encryption with integrity
+//! - **AEAD Encryption**: AES-256-GCM or ChaCha20-Poly1305, as configured in `crypto_algorithms`
 //! - **HMAC Verification**: Message authentication codes
 //! - **Anti-Replay Protection**: Timestamp and nonce-based replay prevention
 //!
@@ -313,8 +316,16 @@
 
 extern crate serde;
 
+#[macro_use]
+mod telemetry;
+
+pub mod clock;
+pub mod config;
+pub mod conformance;
 pub mod crypto;
 pub mod audio;
+pub mod demod;
+pub mod tone_modem;
 pub mod ultrasonic_beam;
 pub mod visual;
 pub mod laser;
@@ -323,12 +334,17 @@ pub mod optical_ecc;
 pub mod protocol;
 pub mod channel_validator;
 pub mod security;
+pub mod session;
+pub mod session_id;
 pub mod fallback;
 pub mod performance_monitor;
 pub mod mission;
 pub mod weather;
 pub mod audit;
 pub mod hierarchical;
+pub mod duplex;
+pub mod discovery;
+pub mod loopback;
 
 #[cfg(feature = "python")]
 pub mod python_bindings;
@@ -336,19 +352,33 @@ pub mod python_bindings;
 #[cfg(feature = "wasm")]
 pub mod wasm;
 
-pub use crypto::{CryptoEngine, CryptoError};
-pub use audio::{AudioEngine, AudioError};
-pub use ultrasonic_beam::{UltrasonicBeamEngine, UltrasonicBeamError, BeamConfig, BeamSignal, BeamReception};
+pub use crypto::{CipherSuite, CryptoEngine, CryptoError};
+pub use conformance::{ConformanceVector, ConformanceError};
+pub use clock::{Clock, SystemClock, MockClock};
+pub use demod::{Demodulator, ScalarDemodulator, detect_demodulator};
+pub use tone_modem::ToneProtocol;
+pub use config::{GibberConfig, ConfigError, EnvironmentProfile, EnvironmentSettings};
+pub use audio::{AudioEngine, AudioError, DecodeDiagnostics, DecodeTolerance, QuietHours, HoppingSequence, DuplexMode, CarrierSense, AudioCapture};
+pub use ultrasonic_beam::{UltrasonicBeamEngine, UltrasonicBeamError, BeamConfig, BeamSignal, BeamReception, PhasedArraySteering};
 pub use visual::{VisualEngine, VisualError, VisualPayload};
-pub use laser::{LaserEngine, LaserError, LaserConfig, ReceptionConfig, AlignmentStatus, LaserType, ModulationScheme};
-pub use range_detector::{RangeDetector, RangeDetectorError, RangingConfig, RangeMeasurement, RangeDetectorCategory, RangeEnvironmentalConditions};
-pub use optical_ecc::{OpticalECC, OpticalECCError, OpticalQualityMetrics, AdaptiveECCConfig, AtmosphericCondition, RangeCategory};
-pub use protocol::{ProtocolEngine, ProtocolError, ProtocolState, ChannelQuality};
-pub use channel_validator::{ChannelValidator, ValidationError, ValidationPhase, ChannelData, ChannelType, ValidationConfig, ValidationMetrics};
-pub use security::{SecurityManager, SecurityError, SecurityConfig, SecurityLevel, PermissionType, PermissionGrant, PermissionScope, PeerIdentity, TrustLevel, EnvironmentalConditions, WeatherCondition, TimeOfDay, CommandExecution};
-pub use fallback::{FallbackManager, FallbackError, FallbackConfig, FallbackMode, FallbackStatus, ChannelFailure, ChannelHealth, SessionSnapshot};
+pub use session::{SessionManager, PeerSession};
+pub use session_id::{SessionId, SessionIdError, SESSION_ID_LEN};
+#[cfg(feature = "qr-scan")]
+pub use visual::{QrScanner, BuiltinQrScanner};
+pub use laser::{LaserEngine, LaserError, LaserConfig, ReceptionConfig, AlignmentStatus, LaserType, ModulationScheme, RegulatoryClass, PowerLogConfig, PowerLogEntry, ComplianceReport, AcquisitionConfig, SearchPattern, SpectralBand, AdaptationExplanation, AdaptationDecision, AdaptationFactor, StreamReassembler, SampleSink, ChunkAckCallback, AdaptiveRsConfig, LinkBudget};
+pub use laser::hardware::{LaserHardware, MockLaserHardware};
+pub use laser::servo::{ServoController, PwmChannel, ServoRange, GpioServoController, MockServoController};
+pub use range_detector::{RangeDetector, RangeDetectorError, RangingConfig, RangeMeasurement, RangeDetectorCategory, RangeEnvironmentalConditions, RangeCancelHandle, ReverbDecayModel, DirectPathEcho};
+pub use optical_ecc::{OpticalECC, OpticalECCError, OpticalQualityMetrics, AdaptiveECCConfig, AtmosphericCondition, RangeCategory, EccScheme, LdpcCodec, FountainCodec, FountainEncoder, FountainDecoder};
+pub use protocol::{ProtocolEngine, ProtocolError, ProtocolState, ProtocolVersion, SessionTicket, ChannelQuality, NonceRegistry, SessionRegistry, SessionPolicy, SignedCoupledAck, ConnectionInfo, KeyEstablishment, PskFallback, TranscriptEntry, HandshakeFrame, HandshakeSink, TRANSCRIPT_CAPACITY, DEFAULT_KEEPALIVE_INTERVAL, DEFAULT_MAX_MISSED_KEEPALIVES, DEFAULT_SESSION_TICKET_LIFETIME};
+pub use channel_validator::{ChannelValidator, ValidationError, ValidationPhase, ChannelData, ChannelType, ValidationConfig, ValidationMetrics, CouplingCalibration, SlidingWindowCouplingConfig, zadoff_chu_preamble, preamble_waveform, PREAMBLE_LEN, PREAMBLE_SAMPLES_PER_SYMBOL, PREAMBLE_SYMBOL_RATE_HZ};
+pub use security::{SecurityManager, SecurityError, SecurityConfig, PinPolicy, PinPolicyRule, SecurityLevel, PermissionType, PermissionGrant, PermissionScope, PeerIdentity, TrustLevel, EnvironmentalConditions, WeatherCondition, TimeOfDay, CommandExecution, CrossChannelSignature};
+pub use fallback::{FallbackManager, FallbackError, FallbackConfig, FallbackMode, FallbackStatus, ChannelFailure, ChannelHealth, SessionSnapshot, PathPolicy, PathConditions, PathSelection, TransitionReason};
 pub use performance_monitor::{PerformanceMonitor, PerformanceError, PerformanceMetrics, PerformanceConfig, PerformancePreset, BenchmarkResult, EnvironmentalFactors};
-pub use audit::{AuditSystem, AuditEntry, SecurityAlert, AuditEventType, AuditSeverity, AuditActor, AuditOperation, create_audit_entry};
+pub use audit::{AuditSystem, AuditEntry, SecurityAlert, AuditEventType, AuditSeverity, AuditActor, AuditOperation, create_audit_entry, SharedAuditSystem};
+pub use duplex::{DuplexSession, DuplexConfig, DuplexError, DuplexStats, DataChunk, ControlMessage};
+pub use loopback::{LoopbackChannel, LoopbackEndpoint};
+pub use discovery::{BeaconTransport, LoopbackBeaconTransport, BeaconPayload, BeaconCapabilities, BeaconHandle, DiscoveredPeer, DiscoveryError};
 pub use hierarchical::{HierarchicalProtocolEngine, MilitaryRank, CommandType, HierarchicalMessage, HierarchicalState, HierarchyPresence};
 
 use std::sync::Arc;
@@ -365,6 +395,28 @@ pub struct Message {
     pub timestamp: std::time::SystemTime,
     pub priority: MessagePriority,
     pub ttl_seconds: u32,
+    /// Set when this message carries one piece of a larger payload
+    #[serde(default)]
+    pub fragment: Option<FragmentInfo>,
+}
+
+impl Message {
+    /// Time after which the message is stale and no longer delivered. Both
+    /// fields come from the peer; an expiry past the end of the clock is treated
+    /// as already expired rather than overflowing
+    pub fn expires_at(&self) -> std::time::SystemTime {
+        self.timestamp
+            .checked_add(std::time::Duration::from_secs(self.ttl_seconds as u64))
+            .unwrap_or(std::time::UNIX_EPOCH)
+    }
+}
+
+/// Position of a fragment within its logical message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FragmentInfo {
+    pub fragment_group_id: u64,
+    pub index: u32,
+    pub total: u32,
 }
 
 /// Supported message types
@@ -378,8 +430,8 @@ pub enum MessageType {
     Notification { title: String, body: String },
 }
 
-/// Message priority levels
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Message priority levels, ordered from lowest to highest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum MessagePriority {
     Low,
     Normal,
@@ -396,6 +448,46 @@ pub struct ApiResponse {
     pub data: Option<serde_json::Value>,
 }
 
+/// Plaintext bytes carried by each chunk of an encrypted stream
+pub const STREAM_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Per-chunk header inside the encrypted payload: chunk index (u64) + final flag (u8)
+const STREAM_CHUNK_HEADER_LEN: usize = 9;
+
+/// Content bytes per fragment; JSON spells each byte with up to four characters,
+/// so a fragment stays under the 64KB message limit
+pub const FRAGMENT_PAYLOAD_SIZE: usize = 12 * 1024;
+
+/// Largest fragment count accepted for one logical message (48MB)
+pub const MAX_FRAGMENTS: u32 = 4096;
+
+/// Partial fragment groups held at once for one peer
+pub const MAX_PENDING_FRAGMENT_GROUPS: usize = 16;
+
+/// Fragment content held at once for one peer, across its partial groups;
+/// enough for one message of `MAX_FRAGMENTS` full fragments
+pub const MAX_PENDING_FRAGMENT_BYTES: usize = MAX_FRAGMENTS as usize * FRAGMENT_PAYLOAD_SIZE;
+
+/// Longest a partial group is held, whatever TTL the peer puts on its fragments
+pub const MAX_FRAGMENT_GROUP_TTL: std::time::Duration = std::time::Duration::from_secs(600);
+
+/// Fragments received so far for one logical message
+pub(crate) struct FragmentGroup {
+    message: Message,
+    chunks: Vec<Option<Vec<u8>>>,
+    received: u32,
+    bytes: usize,
+    expires_at: std::time::SystemTime,
+}
+
+/// Progress report for streaming encryption/decryption
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamProgress {
+    pub chunk_index: u64,
+    pub chunk_bytes: usize,
+    pub total_bytes: u64,
+}
+
 /// Messaging API error types
 #[derive(Debug, thiserror::Error)]
 pub enum MessagingError {
@@ -411,6 +503,88 @@ pub enum MessagingError {
     ConnectionNotEstablished,
     #[error("Message expired")]
     MessageExpired,
+    #[error("Message timestamp outside the allowed clock skew")]
+    ClockSkewExceeded,
+    #[error("Unexpected object {distance_m:.1}m along the beam path")]
+    ProximityThreat { distance_m: f32 },
+    #[error("Proximity scan failed: {0}")]
+    ProximityScanFailed(RangeDetectorError),
+    #[error("No session with peer {0}")]
+    UnknownPeer(String),
+    #[error("Too many partial fragment groups pending reassembly")]
+    ReassemblyLimitExceeded,
+    #[error("Security manager is locked after tamper detection")]
+    TamperLocked,
+}
+
+/// Default tolerance between a message timestamp and the local clock
+pub const DEFAULT_MAX_CLOCK_SKEW: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Ranging gate that holds back sensitive sends while someone could be listening
+///
+/// Any return from `detector` other than the peer (its latest measurement) that
+/// is within `threat_distance_m` blocks High and Critical priority messages.
+#[derive(Clone)]
+pub struct ProximityGate {
+    pub detector: Arc<Mutex<RangeDetector>>,
+    pub threat_distance_m: f32,
+}
+
+/// Bit error rate at which the laser channel contributes nothing to link quality
+const LINK_QUALITY_BER_CEILING: f32 = 0.01;
+
+/// Snapshot of the channel metrics behind the link quality score
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct LinkMetrics {
+    pub laser_aligned: bool,
+    pub laser_signal_strength: f32, // 0.0 to 1.0
+    pub bit_error_rate: f32,
+    pub beam_signal_quality: f32,   // 0.0 to 1.0
+    pub range_confidence: f32,      // 0.0 to 1.0
+    pub coupling_validated: bool,
+}
+
+/// Relative weight of each channel in the link quality score
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LinkQualityWeights {
+    pub laser: f32,
+    pub beam: f32,
+    pub range: f32,
+    pub coupling: f32,
+}
+
+impl Default for LinkQualityWeights {
+    fn default() -> Self {
+        Self {
+            laser: 0.4,
+            beam: 0.25,
+            range: 0.15,
+            coupling: 0.2,
+        }
+    }
+}
+
+impl LinkQualityWeights {
+    /// Weighted mean of the per-channel scores, in 0.0-1.0
+    ///
+    /// The laser scores its signal strength derated linearly by BER (zero at 1%)
+    /// and counts for nothing while misaligned; coupling is all or nothing.
+    pub fn score(&self, metrics: &LinkMetrics) -> f32 {
+        let laser = if metrics.laser_aligned {
+            let ber_penalty = (metrics.bit_error_rate / LINK_QUALITY_BER_CEILING).clamp(0.0, 1.0);
+            metrics.laser_signal_strength.clamp(0.0, 1.0) * (1.0 - ber_penalty)
+        } else {
+            0.0
+        };
+        let coupling = if metrics.coupling_validated { 1.0 } else { 0.0 };
+
+        let weighted = self.laser * laser
+            + self.beam * metrics.beam_signal_quality.clamp(0.0, 1.0)
+            + self.range * metrics.range_confidence.clamp(0.0, 1.0)
+            + self.coupling * coupling;
+        let total = self.laser + self.beam + self.range + self.coupling;
+        if total > 0.0 { (weighted / total).clamp(0.0, 1.0) } else { 0.0 }
+    }
 }
 
 /// Main RgibberLink session manager
@@ -422,25 +596,195 @@ pub struct RgibberLink {
     pending_responses: Arc<Mutex<std::collections::HashMap<String, tokio::sync::oneshot::Sender<ApiResponse>>>>,
     last_activity: Arc<Mutex<std::time::Instant>>,
     performance_monitor: Arc<Mutex<Option<PerformanceMonitor>>>,
+    beacon_transport: Arc<Mutex<Option<Arc<dyn BeaconTransport>>>>,
+    local_beacon_ids: Arc<Mutex<Vec<[u8; 16]>>>,
+    security_manager: Arc<Mutex<Option<SecurityManager>>>,
+    clock: Arc<dyn Clock>,
+    max_clock_skew: std::time::Duration,
+    link_metrics: Arc<std::sync::Mutex<LinkMetrics>>,
+    link_quality_weights: LinkQualityWeights,
+    proximity_gate: Arc<Mutex<Option<ProximityGate>>>,
+    fragment_groups: Arc<Mutex<std::collections::HashMap<u64, FragmentGroup>>>,
+    /// Per-peer engines and queues, alongside the default `protocol` engine
+    sessions: Arc<Mutex<SessionManager>>,
 }
 
 impl RgibberLink {
     /// Create a new RgibberLink session
     pub fn new() -> Self {
+        let protocol = ProtocolEngine::new();
+        let sessions = SessionManager::with_registry(protocol.session_registry());
         Self {
-            protocol: Arc::new(Mutex::new(ProtocolEngine::new())),
+            protocol: Arc::new(Mutex::new(protocol)),
             message_queue: Arc::new(Mutex::new(Vec::new())),
             pending_responses: Arc::new(Mutex::new(std::collections::HashMap::new())),
             last_activity: Arc::new(Mutex::new(std::time::Instant::now())),
             performance_monitor: Arc::new(Mutex::new(None)),
+            beacon_transport: Arc::new(Mutex::new(None)),
+            local_beacon_ids: Arc::new(Mutex::new(Vec::new())),
+            security_manager: Arc::new(Mutex::new(None)),
+            clock: Arc::new(SystemClock),
+            max_clock_skew: DEFAULT_MAX_CLOCK_SKEW,
+            link_metrics: Arc::new(std::sync::Mutex::new(LinkMetrics::default())),
+            link_quality_weights: LinkQualityWeights::default(),
+            proximity_gate: Arc::new(Mutex::new(None)),
+            fragment_groups: Arc::new(Mutex::new(std::collections::HashMap::new())),
+            sessions: Arc::new(Mutex::new(sessions)),
         }
     }
 
+    /// Replace the (synced) wall clock used to timestamp and check messages
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    /// Reject incoming messages whose timestamp is further than `skew` from the local clock
+    pub fn set_max_clock_skew(&mut self, skew: std::time::Duration) {
+        self.max_clock_skew = skew;
+    }
+
+    /// Single 0.0-1.0 health indicator from the latest channel metrics
+    pub fn link_quality(&self) -> f32 {
+        self.link_quality_weights.score(&self.link_metrics())
+    }
+
+    /// Latest channel metrics behind `link_quality`
+    pub fn link_metrics(&self) -> LinkMetrics {
+        *self.link_metrics.lock().unwrap()
+    }
+
+    /// Replace the channel metrics, e.g. from platform-side measurements
+    pub fn update_link_metrics(&self, metrics: LinkMetrics) {
+        *self.link_metrics.lock().unwrap() = metrics;
+    }
+
+    /// Poll the engines for fresh channel metrics
+    pub async fn refresh_link_metrics(&self) -> LinkMetrics {
+        let mut metrics = LinkMetrics::default();
+        {
+            let mut protocol = self.protocol.lock().await;
+            if let Some(laser) = protocol.get_laser_engine_mut() {
+                let alignment = laser.get_alignment_status().await;
+                metrics.laser_aligned = alignment.is_aligned;
+                metrics.laser_signal_strength = alignment.signal_strength;
+                metrics.range_confidence = laser.get_current_range_measurement().await
+                    .map(|measurement| measurement.quality_score)
+                    .unwrap_or(0.0);
+            }
+            if protocol.get_ultrasonic_beam_engine_mut().is_some() {
+                if let Ok(quality) = protocol.get_channel_quality().await {
+                    metrics.beam_signal_quality = quality.ultrasonic_signal_strength;
+                }
+            }
+            metrics.coupling_validated = protocol.is_coupling_validated().await;
+        }
+        if let Some(status) = self.get_performance_metrics().await {
+            metrics.bit_error_rate = status.bit_error_rate as f32;
+        }
+        self.update_link_metrics(metrics);
+        metrics
+    }
+
+    pub fn set_link_quality_weights(&mut self, weights: LinkQualityWeights) {
+        self.link_quality_weights = weights;
+    }
+
+    pub fn link_quality_weights(&self) -> &LinkQualityWeights {
+        &self.link_quality_weights
+    }
+
+    /// Create a session with all engines built from a deployment configuration
+    pub fn from_config(config: GibberConfig) -> Result<Self, ConfigError> {
+        let protocol = ProtocolEngine::from_config(&config)
+            .map_err(|e| ConfigError::InvalidEngineConfig(e.to_string()))?;
+
+        let link = Self::new();
+        let sessions = SessionManager::with_registry(protocol.session_registry());
+        Ok(Self {
+            sessions: Arc::new(Mutex::new(sessions)),
+            protocol: Arc::new(Mutex::new(protocol)),
+            security_manager: Arc::new(Mutex::new(Some(SecurityManager::new(config.security)))),
+            ..link
+        })
+    }
+
+    /// Apply a bundled environment preset to the live engines, including the
+    /// security manager's channel validation, and return what it set
+    pub async fn apply_environment_profile(&self, profile: EnvironmentProfile) -> Result<EnvironmentSettings, ProtocolError> {
+        let settings = profile.settings();
+        self.protocol.lock().await.apply_environment(&settings).await?;
+        if let Some(security) = self.security_manager.lock().await.as_ref() {
+            security.update_channel_environment(&settings.range_conditions()).await;
+        }
+        Ok(settings)
+    }
+
+    /// Security manager configured for this session, if any. While it is tamper
+    /// locked the link refuses to send.
+    pub fn security_manager(&self) -> Arc<Mutex<Option<SecurityManager>>> {
+        self.security_manager.clone()
+    }
+
+    /// Scan for intruders with `detector` before every High or Critical priority send
+    pub async fn set_proximity_gate(&self, detector: Arc<Mutex<RangeDetector>>, threat_distance_m: f32) {
+        *self.proximity_gate.lock().await = Some(ProximityGate { detector, threat_distance_m });
+    }
+
+    /// Send sensitive messages without checking for intruders
+    pub async fn clear_proximity_gate(&self) {
+        *self.proximity_gate.lock().await = None;
+    }
+
+    /// Set the medium used for discovery beacons
+    pub async fn set_beacon_transport(&self, transport: Arc<dyn BeaconTransport>) {
+        *self.beacon_transport.lock().await = Some(transport);
+    }
+
+    /// Periodically broadcast a discovery beacon until the returned handle is dropped
+    pub async fn start_beacon(&self, interval: std::time::Duration, payload: BeaconPayload) -> Result<BeaconHandle, DiscoveryError> {
+        let transport = self.beacon_transport.lock().await.clone().ok_or(DiscoveryError::NoTransport)?;
+        let handle = discovery::spawn_beacon(transport, interval, &payload)?;
+        self.local_beacon_ids.lock().await.push(payload.device_id);
+        Ok(handle)
+    }
+
+    /// Listen for beacons from other devices for `duration`
+    pub async fn listen_for_beacons(&self, duration: std::time::Duration) -> Result<Vec<DiscoveredPeer>, DiscoveryError> {
+        let receiver = self.beacon_transport.lock().await.as_ref().ok_or(DiscoveryError::NoTransport)?.subscribe();
+        let own_ids = self.local_beacon_ids.lock().await.clone();
+        Ok(discovery::collect_beacons(receiver, duration, &own_ids).await)
+    }
+
     /// Initiate the handshake as the sender
     pub async fn initiate_handshake(&mut self) -> Result<(), ProtocolError> {
         self.protocol.lock().await.initiate_handshake().await
     }
 
+    /// Start a handshake with `peer_id` on that peer's own engine, creating its
+    /// session on first use. Its engine takes the cipher suite, session lifetime
+    /// and handshake requirements of the link's own engine.
+    pub async fn connect_peer(&self, peer_id: &str) -> Result<(), ProtocolError> {
+        let policy = self.protocol.lock().await.session_policy();
+        let session = self.sessions.lock().await.get_or_create(peer_id, &policy).await?;
+        let mut protocol = session.protocol.lock().await;
+        protocol.initiate_handshake().await
+    }
+
+    /// Engine for `peer_id`, to drive the remaining handshake steps with that peer
+    pub async fn peer_protocol(&self, peer_id: &str) -> Option<Arc<Mutex<ProtocolEngine>>> {
+        self.sessions.lock().await.get(peer_id).map(|session| session.protocol)
+    }
+
+    /// Close the session with `peer_id`; false if there was none
+    pub async fn disconnect_peer(&self, peer_id: &str) -> bool {
+        self.sessions.lock().await.remove(peer_id).await
+    }
+
+    /// Ids of peers with a session
+    pub async fn peer_ids(&self) -> Vec<String> {
+        self.sessions.lock().await.peer_ids()
+    }
+
     /// Receive nonce and generate QR code as the receiver
     pub async fn receive_nonce(&self, nonce: &[u8]) -> Result<String, ProtocolError> {
         self.protocol.lock().await.receive_nonce(nonce).await
@@ -451,9 +795,9 @@ impl RgibberLink {
         self.protocol.lock().await.process_qr_payload(qr_data).await
     }
 
-    /// Receive ACK from sender
-    pub async fn receive_ack(&self) -> Result<(), ProtocolError> {
-        self.protocol.lock().await.receive_ack().await
+    /// Receive the sender's ACK frame, deriving and confirming the session key
+    pub async fn receive_ack_frame(&self, ack: &[u8]) -> Result<(), ProtocolError> {
+        self.protocol.lock().await.receive_ack_frame(ack).await
     }
 
     /// Get current protocol state
@@ -461,6 +805,26 @@ impl RgibberLink {
         self.protocol.lock().await.get_state().await
     }
 
+    /// Recent handshake state transitions and failed steps, oldest first
+    pub async fn get_handshake_transcript(&self) -> Vec<TranscriptEntry> {
+        self.protocol.lock().await.get_transcript()
+    }
+
+    /// Session summary, including whether it was keyed from the pre-shared key
+    pub async fn connection_info(&self) -> ConnectionInfo {
+        self.protocol.lock().await.connection_info().await
+    }
+
+    /// Hold `psk` for peers that announce pre-shared-key keying
+    pub async fn set_psk_fallback(&self, psk: Option<PskFallback>) {
+        self.protocol.lock().await.set_psk_fallback(psk);
+    }
+
+    /// As responder, key handshakes from the pre-shared key instead of ECDH
+    pub async fn set_psk_mode(&self, enabled: bool) -> Result<(), ProtocolError> {
+        self.protocol.lock().await.set_psk_mode(enabled)
+    }
+
     /// Encrypt a message using the established session key
     pub async fn encrypt_message(&self, data: &[u8]) -> Result<Vec<u8>, ProtocolError> {
         self.protocol.lock().await.encrypt_message(data).await
@@ -471,11 +835,188 @@ impl RgibberLink {
         self.protocol.lock().await.decrypt_message(encrypted_data).await
     }
 
+    /// Encrypt a stream in authenticated chunks for large transfers
+    ///
+    /// Each chunk is framed as a big-endian u32 length followed by the sealed chunk.
+    /// The chunk index and a final-chunk flag are sealed with the data so chunks
+    /// cannot be reordered, dropped or truncated without detection. Chunks are
+    /// sealed with the engine's cipher suite.
+    pub async fn encrypt_stream<R, W>(&self, mut reader: R, mut writer: W) -> Result<u64, ProtocolError>
+    where
+        R: tokio::io::AsyncRead + Unpin,
+        W: tokio::io::AsyncWrite + Unpin,
+    {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let key = self.stream_key().await?;
+        let suite = self.protocol.lock().await.cipher_suite();
+        let mut buffer = vec![0u8; STREAM_CHUNK_SIZE];
+        let mut chunk_index = 0u64;
+        let mut total_bytes = 0u64;
+
+        loop {
+            // Fill a whole chunk unless the reader is exhausted
+            let mut filled = 0;
+            while filled < STREAM_CHUNK_SIZE {
+                let read = reader.read(&mut buffer[filled..]).await
+                    .map_err(|e| ProtocolError::CryptoError(format!("Stream read failed: {}", e)))?;
+                if read == 0 {
+                    break;
+                }
+                filled += read;
+            }
+            let is_final = filled < STREAM_CHUNK_SIZE;
+
+            let mut plaintext = Vec::with_capacity(STREAM_CHUNK_HEADER_LEN + filled);
+            plaintext.extend_from_slice(&chunk_index.to_be_bytes());
+            plaintext.push(is_final as u8);
+            plaintext.extend_from_slice(&buffer[..filled]);
+
+            let sealed = CryptoEngine::encrypt_data_suite(suite, &key, &plaintext)
+                .map_err(|e| ProtocolError::CryptoError(e.to_string()))?;
+            let mut frame = Vec::with_capacity(4 + sealed.len());
+            frame.extend_from_slice(&(sealed.len() as u32).to_be_bytes());
+            frame.extend_from_slice(&sealed);
+            writer.write_all(&frame).await
+                .map_err(|e| ProtocolError::CryptoError(format!("Stream write failed: {}", e)))?;
+
+            total_bytes += filled as u64;
+            chunk_index += 1;
+            if is_final {
+                break;
+            }
+        }
+
+        writer.flush().await
+            .map_err(|e| ProtocolError::CryptoError(format!("Stream write failed: {}", e)))?;
+        Ok(total_bytes)
+    }
+
+    /// Decrypt a stream produced by `encrypt_stream`, reporting progress per chunk
+    ///
+    /// Every chunk is authenticated before any of its plaintext is written, so
+    /// output emitted before an error or cancellation is always authentic.
+    /// Returning `false` from `on_progress` stops decryption with `ProtocolError::Cancelled`.
+    pub async fn decrypt_stream_with_progress<R, W, F>(&self, mut reader: R, mut writer: W, mut on_progress: F) -> Result<u64, ProtocolError>
+    where
+        R: tokio::io::AsyncRead + Unpin,
+        W: tokio::io::AsyncWrite + Unpin,
+        F: FnMut(StreamProgress) -> bool,
+    {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // Suite id + nonce + header + full chunk + AEAD tag
+        const MAX_SEALED_CHUNK: usize = 1 + 12 + STREAM_CHUNK_HEADER_LEN + STREAM_CHUNK_SIZE + 16;
+
+        let key = self.stream_key().await?;
+        let mut chunk_index = 0u64;
+        let mut total_bytes = 0u64;
+
+        loop {
+            let mut len_bytes = [0u8; 4];
+            reader.read_exact(&mut len_bytes).await
+                .map_err(|_| ProtocolError::CryptoError("Truncated stream".to_string()))?;
+            let sealed_len = u32::from_be_bytes(len_bytes) as usize;
+            if sealed_len > MAX_SEALED_CHUNK {
+                return Err(ProtocolError::CryptoError("Stream chunk too large".to_string()));
+            }
+
+            let mut sealed = vec![0u8; sealed_len];
+            reader.read_exact(&mut sealed).await
+                .map_err(|_| ProtocolError::CryptoError("Truncated stream".to_string()))?;
+
+            let plaintext = CryptoEngine::decrypt_data_suite(&key, &sealed)
+                .map_err(ProtocolError::from_decrypt)?;
+            if plaintext.len() < STREAM_CHUNK_HEADER_LEN {
+                return Err(ProtocolError::CryptoError("Malformed stream chunk".to_string()));
+            }
+
+            let mut index_bytes = [0u8; 8];
+            index_bytes.copy_from_slice(&plaintext[..8]);
+            if u64::from_be_bytes(index_bytes) != chunk_index {
+                return Err(ProtocolError::CryptoError("Stream chunk out of order".to_string()));
+            }
+            let is_final = plaintext[8] != 0;
+            let data = &plaintext[STREAM_CHUNK_HEADER_LEN..];
+
+            writer.write_all(data).await
+                .map_err(|e| ProtocolError::CryptoError(format!("Stream write failed: {}", e)))?;
+            total_bytes += data.len() as u64;
+
+            let keep_going = on_progress(StreamProgress {
+                chunk_index,
+                chunk_bytes: data.len(),
+                total_bytes,
+            });
+            chunk_index += 1;
+
+            if is_final {
+                break;
+            }
+            if !keep_going {
+                writer.flush().await
+                    .map_err(|e| ProtocolError::CryptoError(format!("Stream write failed: {}", e)))?;
+                return Err(ProtocolError::Cancelled);
+            }
+        }
+
+        writer.flush().await
+            .map_err(|e| ProtocolError::CryptoError(format!("Stream write failed: {}", e)))?;
+        Ok(total_bytes)
+    }
+
+    /// Session key for streaming, available once the channel is connected
+    async fn stream_key(&self) -> Result<[u8; 32], ProtocolError> {
+        let protocol = self.protocol.lock().await;
+        protocol.require_established_session().await?;
+        protocol.get_shared_secret().copied()
+            .ok_or(ProtocolError::CryptoError("No shared secret".to_string()))
+    }
+
     /// Get the shared secret (for debugging/testing only)
     pub async fn get_shared_secret(&self) -> Option<[u8; 32]> {
         self.protocol.lock().await.get_shared_secret().copied()
     }
 
+    /// Snapshot the current session, e.g. to persist with `SessionSnapshot::to_bytes`
+    /// before the app is backgrounded
+    pub async fn session_snapshot(&self) -> SessionSnapshot {
+        SessionSnapshot::capture(&*self.protocol.lock().await).await
+    }
+
+    /// Rehydrate an established session from a snapshot, back into the state it
+    /// was captured in, without a new handshake. Only snapshots of a completed
+    /// handshake qualify. The session keeps its original start time, so an expired
+    /// one still has to be renegotiated, and its message counters, so messages
+    /// opened before the snapshot are still replays. Restore a snapshot once:
+    /// an older one would reuse message nonces.
+    pub async fn restore_session(&self, snapshot: &SessionSnapshot) -> Result<(), ProtocolError> {
+        let established = matches!(
+            snapshot.protocol_state,
+            ProtocolState::Connected | ProtocolState::SecureChannelEstablished | ProtocolState::LongRangeConnected
+        );
+        let (Some(shared_secret), Some(message_channel)) = (snapshot.shared_secret, snapshot.message_channel) else {
+            return Err(ProtocolError::InvalidState);
+        };
+        if !established || snapshot.peer_public_key.is_none() {
+            return Err(ProtocolError::InvalidState);
+        }
+
+        let mut protocol = self.protocol.lock().await;
+        protocol.set_session_id(snapshot.session_id).await;
+        protocol.set_session_started_at(snapshot.session_started_at);
+        protocol.set_shared_secret(Some(shared_secret));
+        protocol.set_message_channel_state(message_channel)?;
+        protocol.set_peer_public_key(snapshot.peer_public_key.clone());
+        protocol.set_communication_mode(snapshot.communication_mode.clone());
+        protocol.set_keepalive_sequences(snapshot.keepalive_sequence, snapshot.peer_keepalive_sequence);
+        protocol.set_state(snapshot.protocol_state.clone()).await;
+        drop(protocol);
+
+        *self.last_activity.lock().await = std::time::Instant::now();
+        Ok(())
+    }
+
     /// Send a text message to the connected peer
     pub async fn send_text_message(&self, content: &str) -> Result<String, MessagingError> {
         self.check_connection().await?;
@@ -489,6 +1030,19 @@ impl RgibberLink {
         self.send_message_internal(message).await
     }
 
+    /// Send a text message to `peer_id` over that peer's session
+    pub async fn send_text_message_to(&self, peer_id: &str, content: &str) -> Result<String, MessagingError> {
+        let session = self.check_peer_connection(peer_id).await?;
+
+        let message = self.create_message(
+            MessageType::Text(content.to_string()),
+            MessagePriority::Normal,
+            300, // 5 minute TTL
+        );
+
+        self.send_message_via(&session.protocol, message).await
+    }
+
     /// Request authorization from the peer for specific permissions
     pub async fn request_authorization(&self, permissions: Vec<String>) -> Result<String, MessagingError> {
         self.check_connection().await?;
@@ -566,17 +1120,171 @@ impl RgibberLink {
         self.send_message_internal(message).await
     }
 
-    /// Get pending messages for the application to process
+    /// Send a payload of any size, split into fragments that are reassembled into
+    /// one message on the receiving side
+    pub async fn send_large_message(&self, content: &[u8], message_type: MessageType) -> Result<String, MessagingError> {
+        self.check_connection().await?;
+
+        let fragments = self.fragment_message(content, message_type, MessagePriority::Normal, 300)?;
+        let message_id = fragments[0].id.clone();
+        for fragment in fragments {
+            self.send_message_internal(fragment).await?;
+        }
+        Ok(message_id)
+    }
+
+    /// Split `content` into messages of at most `FRAGMENT_PAYLOAD_SIZE` bytes that
+    /// share one id and fragment group
+    fn fragment_message(&self, content: &[u8], message_type: MessageType, priority: MessagePriority, ttl_seconds: u32) -> Result<Vec<Message>, MessagingError> {
+        let total = content.len().div_ceil(FRAGMENT_PAYLOAD_SIZE).max(1);
+        if total > MAX_FRAGMENTS as usize {
+            return Err(MessagingError::MessageTooLarge);
+        }
+
+        let message = self.create_message(message_type, priority, ttl_seconds);
+        let fragment_group_id = rand::random::<u64>();
+        Ok((0..total)
+            .map(|index| {
+                let start = (index * FRAGMENT_PAYLOAD_SIZE).min(content.len());
+                let end = (start + FRAGMENT_PAYLOAD_SIZE).min(content.len());
+                Message {
+                    content: content[start..end].to_vec(),
+                    fragment: Some(FragmentInfo { fragment_group_id, index: index as u32, total: total as u32 }),
+                    ..message.clone()
+                }
+            })
+            .collect())
+    }
+
+    /// Buffer a fragment, returning the logical message once every fragment of its
+    /// group has arrived, in any order. Each peer reassembles into its own
+    /// `groups`, so peers cannot complete or collide with each other's groups.
+    /// Groups are dropped once past their TTL, which is clamped to
+    /// `MAX_FRAGMENT_GROUP_TTL`; a peer holds at most
+    /// `MAX_PENDING_FRAGMENT_GROUPS` groups and `MAX_PENDING_FRAGMENT_BYTES` of
+    /// content at once.
+    async fn reassemble_fragments(
+        &self,
+        groups: &Mutex<std::collections::HashMap<u64, FragmentGroup>>,
+        fragment: Message,
+    ) -> Result<Option<Message>, MessagingError> {
+        let info = fragment.fragment.ok_or(MessagingError::InvalidFormat)?;
+        if info.total == 0 || info.total > MAX_FRAGMENTS || info.index >= info.total || fragment.content.len() > FRAGMENT_PAYLOAD_SIZE {
+            return Err(MessagingError::InvalidFormat);
+        }
+
+        let now = self.clock.now();
+        let mut groups = groups.lock().await;
+        groups.retain(|_, group| group.expires_at > now);
+
+        let expires_at = fragment.expires_at();
+        if expires_at <= now {
+            return Err(MessagingError::MessageExpired);
+        }
+        let expires_at = now.checked_add(MAX_FRAGMENT_GROUP_TTL).map_or(expires_at, |latest| expires_at.min(latest));
+
+        if !groups.contains_key(&info.fragment_group_id) && groups.len() >= MAX_PENDING_FRAGMENT_GROUPS {
+            return Err(MessagingError::ReassemblyLimitExceeded);
+        }
+        let pending_bytes: usize = groups.values().map(|group| group.bytes).sum();
+
+        let group = groups.entry(info.fragment_group_id).or_insert_with(|| FragmentGroup {
+            message: Message { content: Vec::new(), fragment: None, ..fragment.clone() },
+            chunks: vec![None; info.total as usize],
+            received: 0,
+            bytes: 0,
+            expires_at,
+        });
+        if group.chunks.len() != info.total as usize {
+            return Err(MessagingError::InvalidFormat);
+        }
+        if group.chunks[info.index as usize].is_none() {
+            if pending_bytes + fragment.content.len() > MAX_PENDING_FRAGMENT_BYTES {
+                if group.received == 0 {
+                    groups.remove(&info.fragment_group_id);
+                }
+                return Err(MessagingError::ReassemblyLimitExceeded);
+            }
+            group.bytes += fragment.content.len();
+            group.chunks[info.index as usize] = Some(fragment.content);
+            group.received += 1;
+        }
+        if group.received < info.total {
+            return Ok(None);
+        }
+
+        let group = groups.remove(&info.fragment_group_id).expect("group is present");
+        let content = group.chunks.into_iter().flatten().flatten().collect();
+        Ok(Some(Message { content, ..group.message }))
+    }
+
+    /// Get every pending message for the application to process, in priority order
     pub async fn get_pending_messages(&self) -> Vec<Message> {
-        let mut queue = self.message_queue.lock().await;
-        let messages = queue.clone();
-        queue.clear(); // Clear processed messages
-        messages
+        self.drain_messages(usize::MAX).await
+    }
+
+    /// Take up to `max` pending messages, Critical first down to Low and oldest
+    /// first within a priority; the rest stay queued. Messages past their TTL
+    /// are dropped.
+    pub async fn drain_messages(&self, max: usize) -> Vec<Message> {
+        Self::drain_by_priority(&mut *self.message_queue.lock().await, self.clock.now(), max)
+    }
+
+    /// Get pending messages received from `peer_id`, in priority order
+    pub async fn get_pending_messages_from(&self, peer_id: &str) -> Vec<Message> {
+        let session = self.sessions.lock().await.get(peer_id);
+        match session {
+            Some(session) => Self::drain_by_priority(&mut *session.message_queue.lock().await, self.clock.now(), usize::MAX),
+            None => Vec::new(),
+        }
+    }
+
+    fn drain_by_priority(queue: &mut Vec<Message>, now: std::time::SystemTime, max: usize) -> Vec<Message> {
+        queue.retain(|message| message.expires_at() > now);
+        // Stable, so arrival order holds within a priority across drains
+        queue.sort_by_key(|message| std::cmp::Reverse(message.priority));
+        let rest = queue.split_off(max.min(queue.len()));
+        std::mem::replace(queue, rest)
     }
 
-    /// Check if there are pending messages
+    /// Remove queued messages and partial fragment groups past their TTL,
+    /// across the default and per-peer queues; returns the number of queued
+    /// messages removed. Draining already skips expired messages, so this only
+    /// bounds memory held by an idle application and can run on a timer
+    pub async fn purge_expired(&self) -> usize {
+        let now = self.clock.now();
+        let mut removed = Self::purge_queue(&mut *self.message_queue.lock().await, now);
+        let sessions = {
+            let sessions = self.sessions.lock().await;
+            sessions.peer_ids().iter().filter_map(|peer_id| sessions.get(peer_id)).collect::<Vec<_>>()
+        };
+        for session in sessions {
+            removed += Self::purge_queue(&mut *session.message_queue.lock().await, now);
+            session.fragment_groups.lock().await.retain(|_, group| group.expires_at > now);
+        }
+        self.fragment_groups.lock().await.retain(|_, group| group.expires_at > now);
+        removed
+    }
+
+    fn purge_queue(queue: &mut Vec<Message>, now: std::time::SystemTime) -> usize {
+        let before = queue.len();
+        queue.retain(|message| message.expires_at() > now);
+        before - queue.len()
+    }
+
+    /// Fails with `MessageExpired` once `message` is past its TTL; check before
+    /// acting on a message held since it was drained
+    pub fn check_message_expiry(&self, message: &Message) -> Result<(), MessagingError> {
+        if message.expires_at() <= self.clock.now() {
+            return Err(MessagingError::MessageExpired);
+        }
+        Ok(())
+    }
+
+    /// Check if there are pending messages that have not expired
     pub async fn has_pending_messages(&self) -> bool {
-        !self.message_queue.lock().await.is_empty()
+        let now = self.clock.now();
+        self.message_queue.lock().await.iter().any(|message| message.expires_at() > now)
     }
 
     /// Get recent activity timestamp
@@ -653,15 +1361,55 @@ impl RgibberLink {
 
     /// Process incoming encrypted message data
     pub async fn process_incoming_message(&self, encrypted_data: &[u8]) -> Result<(), MessagingError> {
-        let decrypted = self.decrypt_message(encrypted_data).await
+        self.process_incoming_via(&self.protocol, &self.message_queue, &self.fragment_groups, encrypted_data).await
+    }
+
+    /// Process encrypted message data received from `peer_id`, decrypting with
+    /// that peer's session key and queueing for `get_pending_messages_from`
+    pub async fn process_incoming_message_from(&self, peer_id: &str, encrypted_data: &[u8]) -> Result<(), MessagingError> {
+        let session = self.sessions.lock().await.get(peer_id)
+            .ok_or_else(|| MessagingError::UnknownPeer(peer_id.to_string()))?;
+        self.process_incoming_via(&session.protocol, &session.message_queue, &session.fragment_groups, encrypted_data).await
+    }
+
+    async fn process_incoming_via(
+        &self,
+        protocol: &Mutex<ProtocolEngine>,
+        queue: &Mutex<Vec<Message>>,
+        fragment_groups: &Mutex<std::collections::HashMap<u64, FragmentGroup>>,
+        encrypted_data: &[u8],
+    ) -> Result<(), MessagingError> {
+        let decrypted = protocol.lock().await.decrypt_message(encrypted_data).await
             .map_err(|_| MessagingError::InvalidFormat)?;
 
         let message: Message = serde_json::from_slice(&decrypted)
             .map_err(|_| MessagingError::InvalidFormat)?;
 
+        // A future-dated message would otherwise outlive its TTL
+        let now = self.clock.now();
+        let skew = match message.timestamp.duration_since(now) {
+            Ok(ahead) => ahead,
+            Err(behind) => behind.duration(),
+        };
+        if skew > self.max_clock_skew {
+            return Err(MessagingError::ClockSkewExceeded);
+        }
+        if message.fragment.is_none() {
+            self.check_message_expiry(&message)?;
+        }
+
         // Update activity timestamp
         *self.last_activity.lock().await = std::time::Instant::now();
 
+        let message = if message.fragment.is_some() {
+            match self.reassemble_fragments(fragment_groups, message).await? {
+                Some(message) => message,
+                None => return Ok(()),
+            }
+        } else {
+            message
+        };
+
         // Handle special message types
         match &message.message_type {
             MessageType::AuthorizationRequest { .. } => {
@@ -669,19 +1417,19 @@ impl RgibberLink {
             }
             MessageType::AuthorizationResponse { granted: false, reason } => {
                 // Handle rejected authorization - could trigger notification
-                self.handle_rejected_authorization(&message, reason.clone()).await?;
+                self.handle_rejected_authorization(queue, &message, reason.clone()).await?;
             }
             _ => {}
         }
 
         // Add to message queue for application processing
-        self.message_queue.lock().await.push(message);
+        queue.lock().await.push(message);
 
         Ok(())
     }
 
     /// Handle rejected authorization attempts with notifications
-    async fn handle_rejected_authorization(&self, _message: &Message, reason: Option<String>) -> Result<(), MessagingError> {
+    async fn handle_rejected_authorization(&self, queue: &Mutex<Vec<Message>>, _message: &Message, reason: Option<String>) -> Result<(), MessagingError> {
         // Log the rejection for audit purposes
         // In a full implementation, this would trigger system notifications
         // and potentially escalate security measures
@@ -699,20 +1447,69 @@ impl RgibberLink {
         );
 
         // This notification would be processed by the application UI
-        self.message_queue.lock().await.push(notification);
+        queue.lock().await.push(notification);
 
         Ok(())
     }
 
-    /// Check if we have an established connection
+    /// Check if the default engine has an established connection
     async fn check_connection(&self) -> Result<(), MessagingError> {
-        let state = self.get_state().await;
+        Self::check_engine_connection(&self.protocol).await
+    }
+
+    /// Session for `peer_id`, provided that peer's own handshake has completed
+    async fn check_peer_connection(&self, peer_id: &str) -> Result<PeerSession, MessagingError> {
+        let session = self.sessions.lock().await.get(peer_id)
+            .ok_or_else(|| MessagingError::UnknownPeer(peer_id.to_string()))?;
+        Self::check_engine_connection(&session.protocol).await?;
+        Ok(session)
+    }
+
+    async fn check_engine_connection(protocol: &Mutex<ProtocolEngine>) -> Result<(), MessagingError> {
+        let state = protocol.lock().await.get_state().await;
         match state {
             ProtocolState::Connected | ProtocolState::SecureChannelEstablished | ProtocolState::LongRangeSecureChannel => Ok(()),
             _ => Err(MessagingError::ConnectionNotEstablished),
         }
     }
 
+    /// Refuse the send if the gate's detector sees an unexpected return inside the threat distance
+    async fn check_proximity(&self) -> Result<(), MessagingError> {
+        let gate = match self.proximity_gate.lock().await.clone() {
+            Some(gate) => gate,
+            None => return Ok(()),
+        };
+
+        let detector = gate.detector.lock().await;
+        let peer_range = detector.get_measurement_history().await
+            .last()
+            .map(|measurement| measurement.distance_m)
+            .unwrap_or(f32::NAN);
+        // A scan that could not run proves nothing, so it blocks like a threat
+        let intruders = detector.scan_for_intruders(peer_range).await.map_err(|e| {
+            trace_warn!(error = %e, "sensitive send blocked: proximity scan failed");
+            MessagingError::ProximityScanFailed(e)
+        })?;
+        match intruders.first() {
+            Some(&distance_m) if distance_m <= gate.threat_distance_m => {
+                trace_warn!(distance_m, "sensitive send blocked by proximity gate");
+                Err(MessagingError::ProximityThreat { distance_m })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Refuse the send while the configured security manager is tamper locked
+    async fn check_tamper_lock(&self) -> Result<(), MessagingError> {
+        if let Some(security) = self.security_manager.lock().await.as_ref() {
+            if security.is_tamper_locked().await {
+                trace_warn!("send blocked: security manager is tamper locked");
+                return Err(MessagingError::TamperLocked);
+            }
+        }
+        Ok(())
+    }
+
     /// Create a new message with proper metadata
     fn create_message(&self, message_type: MessageType, priority: MessagePriority, ttl_seconds: u32) -> Message {
         let message_id = format!("msg_{}", std::time::SystemTime::now()
@@ -725,14 +1522,20 @@ impl RgibberLink {
             sender_fingerprint: [0u8; 32], // Would be set from device fingerprint
             content: Vec::new(), // Content is stored in message_type
             message_type,
-            timestamp: std::time::SystemTime::now(),
+            timestamp: self.clock.now(),
             priority,
             ttl_seconds,
+            fragment: None,
         }
     }
 
     /// Send message internally (encrypt and queue for transmission)
     async fn send_message_internal(&self, message: Message) -> Result<String, MessagingError> {
+        self.send_message_via(&self.protocol, message).await
+    }
+
+    /// Encrypt `message` under `protocol`'s session key and queue it for transmission
+    async fn send_message_via(&self, protocol: &Mutex<ProtocolEngine>, message: Message) -> Result<String, MessagingError> {
         // Check message size (64KB limit)
         let message_size = serde_json::to_vec(&message)
             .map_err(|_| MessagingError::InvalidFormat)?
@@ -742,11 +1545,16 @@ impl RgibberLink {
             return Err(MessagingError::MessageTooLarge);
         }
 
+        self.check_tamper_lock().await?;
+        if matches!(message.priority, MessagePriority::High | MessagePriority::Critical) {
+            self.check_proximity().await?;
+        }
+
         // Encrypt the message
         let message_bytes = serde_json::to_vec(&message)
             .map_err(|_| MessagingError::InvalidFormat)?;
 
-        let _encrypted = self.encrypt_message(&message_bytes).await
+        let _encrypted = protocol.lock().await.encrypt_message(&message_bytes).await
             .map_err(|_| MessagingError::ConnectionNotEstablished)?;
 
         // In a full implementation, this would queue the message for transmission
@@ -769,6 +1577,406 @@ mod tests {
         assert!(matches!(link.get_state().await, ProtocolState::Idle));
     }
 
+    async fn connected_link(key: [u8; 32]) -> RgibberLink {
+        let link = RgibberLink::new();
+        {
+            let mut protocol = link.protocol.lock().await;
+            protocol.set_shared_secret(Some(key));
+            protocol.set_state(ProtocolState::Connected).await;
+        }
+        link
+    }
+
+    /// The other end of a `connected_link` keyed with `key`: what it seals, the
+    /// link opens
+    fn link_peer(key: [u8; 32]) -> CryptoEngine {
+        let mut peer = CryptoEngine::new();
+        peer.set_session_key(&key, false);
+        peer
+    }
+
+    #[tokio::test]
+    async fn test_decrypt_stream_with_progress() {
+        let link = connected_link([7u8; 32]).await;
+        let data: Vec<u8> = (0..STREAM_CHUNK_SIZE * 3 + 1234).map(|i| (i % 251) as u8).collect();
+
+        let mut encrypted = Vec::new();
+        let written = link.encrypt_stream(&data[..], &mut encrypted).await.unwrap();
+        assert_eq!(written, data.len() as u64);
+
+        let mut decrypted = Vec::new();
+        let mut reports = Vec::new();
+        let total = link.decrypt_stream_with_progress(&encrypted[..], &mut decrypted, |progress| {
+            reports.push(progress);
+            true
+        }).await.unwrap();
+
+        assert_eq!(total, data.len() as u64);
+        assert_eq!(decrypted, data);
+        assert_eq!(reports.len(), 4);
+        assert_eq!(reports.last().unwrap().total_bytes, data.len() as u64);
+        assert!(reports.windows(2).all(|w| w[1].chunk_index == w[0].chunk_index + 1));
+
+        // Chunks follow the configured suite and open without configuration
+        link.protocol.lock().await.set_cipher_suite(crate::crypto::CipherSuite::ChaCha20Poly1305);
+        let mut encrypted = Vec::new();
+        link.encrypt_stream(&data[..], &mut encrypted).await.unwrap();
+        assert_eq!(encrypted[4], crate::crypto::CipherSuite::ChaCha20Poly1305.id());
+        let mut decrypted = Vec::new();
+        connected_link([7u8; 32]).await.decrypt_stream_with_progress(&encrypted[..], &mut decrypted, |_| true).await.unwrap();
+        assert_eq!(decrypted, data);
+    }
+
+    #[tokio::test]
+    async fn test_decrypt_stream_cancellation_and_tampering() {
+        let link = connected_link([7u8; 32]).await;
+        let data = vec![0xA5u8; STREAM_CHUNK_SIZE * 4];
+
+        let mut encrypted = Vec::new();
+        link.encrypt_stream(&data[..], &mut encrypted).await.unwrap();
+
+        // Cancelling after two chunks leaves exactly two authenticated chunks of output
+        let mut partial = Vec::new();
+        let result = link.decrypt_stream_with_progress(&encrypted[..], &mut partial, |progress| {
+            progress.chunk_index < 1
+        }).await;
+        assert!(matches!(result, Err(ProtocolError::Cancelled)));
+        assert_eq!(partial, data[..STREAM_CHUNK_SIZE * 2]);
+
+        // A corrupted third chunk is rejected before any of it is written
+        let sealed_chunk_len = 4 + 1 + 12 + STREAM_CHUNK_HEADER_LEN + STREAM_CHUNK_SIZE + 16;
+        encrypted[sealed_chunk_len * 2 + 100] ^= 0xFF;
+        let mut output = Vec::new();
+        let result = link.decrypt_stream_with_progress(&encrypted[..], &mut output, |_| true).await;
+        assert!(matches!(result, Err(ProtocolError::AuthenticationFailed)));
+        assert_eq!(output, data[..STREAM_CHUNK_SIZE * 2]);
+    }
+
+    #[tokio::test]
+    async fn test_beacon_discovery_over_loopback() {
+        let medium = LoopbackBeaconTransport::new();
+        let beacon_link = RgibberLink::new();
+        let listener_link = RgibberLink::new();
+        beacon_link.set_beacon_transport(Arc::new(medium.clone())).await;
+        listener_link.set_beacon_transport(Arc::new(medium)).await;
+
+        let capabilities = BeaconCapabilities { long_range: true, laser: true, ultrasonic_beam: true, ..Default::default() };
+        let payload = BeaconPayload::new(capabilities, "ground-station");
+        let handle = beacon_link.start_beacon(std::time::Duration::from_millis(20), payload.clone()).await.unwrap();
+
+        let peers = listener_link.listen_for_beacons(std::time::Duration::from_millis(150)).await.unwrap();
+        assert_eq!(peers.len(), 1);
+        assert_eq!(peers[0].device_id, payload.device_id);
+        assert_eq!(peers[0].capabilities, capabilities);
+        assert_eq!(peers[0].name, "ground-station");
+        assert!(peers[0].beacon_count >= 2);
+
+        // A device does not discover its own beacon
+        let own = beacon_link.listen_for_beacons(std::time::Duration::from_millis(60)).await.unwrap();
+        assert!(own.is_empty());
+
+        handle.stop();
+        assert!(matches!(
+            RgibberLink::new().listen_for_beacons(std::time::Duration::from_millis(10)).await,
+            Err(DiscoveryError::NoTransport)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_from_config_wires_all_engines() {
+        let toml = r#"
+            adaptive_laser = false
+
+            [laser]
+            wavelength_nm = 532
+            max_power_mw = 3.5
+            beam_diameter_mm = 1.5
+            range_meters = 150.0
+
+            [reception]
+            samples_per_bit = 8
+            frame_rate_hz = 60
+
+            [beam]
+            range = 25.0
+            beam_angle = 10.0
+            fundamental_bands = [40000.0, 44000.0]
+
+            [security]
+            max_pin_attempts = 5
+            session_timeout_secs = 600
+
+            [ranging]
+            max_range_m = 150.0
+            temperature_celsius = 5.0
+
+            [optical_ecc]
+            adaptation_enabled = false
+
+            [optical_ecc.reed_solomon]
+            data_shards = 12
+            parity_shards = 6
+        "#;
+        let path = std::env::temp_dir().join(format!("gibber-config-{}.toml", std::process::id()));
+        std::fs::write(&path, toml).unwrap();
+        let config = GibberConfig::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let link = RgibberLink::from_config(config.clone()).unwrap();
+        let mut protocol = link.protocol.lock().await;
+
+        let laser = protocol.get_laser_engine_mut().unwrap();
+        assert_eq!(laser.get_config().wavelength_nm, 532);
+        assert_eq!(laser.get_config().max_power_mw, 3.5);
+        assert_eq!(laser.get_reception_config().samples_per_bit, 8);
+        assert_eq!(laser.get_reception_config().frame_rate_hz, 60);
+        // Fields left out keep their defaults
+        assert_eq!(laser.get_reception_config().exposure_time_us, ReceptionConfig::default().exposure_time_us);
+        let ecc = laser.get_optical_ecc_config().unwrap();
+        assert!(!ecc.adaptation_enabled);
+        assert_eq!((ecc.reed_solomon.data_shards, ecc.reed_solomon.parity_shards), (12, 6));
+        assert!(!laser.is_adaptive_mode());
+        let ranging = laser.range_detector().unwrap();
+        assert_eq!(ranging.lock().await.get_config().max_range_m, 150.0);
+        assert_eq!(ranging.lock().await.get_config().temperature_celsius, 5.0);
+
+        let beam = protocol.get_ultrasonic_beam_engine_mut().unwrap();
+        assert_eq!(beam.get_config().range, 25.0);
+        assert_eq!(beam.get_config().fundamental_bands, vec![40000.0, 44000.0]);
+        drop(protocol);
+
+        let security = link.security_manager();
+        let security = security.lock().await;
+        let security = security.as_ref().unwrap().get_config();
+        assert_eq!(security.max_pin_attempts, 5);
+        assert_eq!(security.session_timeout_secs, 600);
+
+        // The same settings load from JSON
+        let json = serde_json::to_string(&config).unwrap();
+        let from_json = GibberConfig::from_json_str(&json).unwrap();
+        assert_eq!(from_json.laser.wavelength_nm, 532);
+        assert_eq!(from_json.reception.samples_per_bit, 8);
+
+        // Invalid engine parameters are reported rather than silently clamped
+        let bad = GibberConfig::from_toml_str("[beam]\nrange = 80.0").unwrap();
+        assert!(matches!(RgibberLink::from_config(bad), Err(ConfigError::InvalidEngineConfig(_))));
+        for power in ["250.0", "0.0", "-1.0"] {
+            let bad = GibberConfig::from_toml_str(&format!("[laser]\nmax_power_mw = {}", power)).unwrap();
+            assert!(matches!(RgibberLink::from_config(bad), Err(ConfigError::InvalidEngineConfig(_))), "{}", power);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_configured_security_manager_blocks_sends_after_tamper() {
+        let link = RgibberLink::from_config(GibberConfig::default()).unwrap();
+        {
+            let mut protocol = link.protocol.lock().await;
+            protocol.set_shared_secret(Some([7u8; 32]));
+            protocol.set_state(ProtocolState::Connected).await;
+        }
+        link.send_text_message("before").await.unwrap();
+
+        let security = link.security_manager();
+        assert!(!security.lock().await.as_ref().unwrap().report_tamper().await.unwrap());
+        assert!(matches!(link.send_text_message("after").await, Err(MessagingError::TamperLocked)));
+    }
+
+    #[tokio::test]
+    async fn test_fog_environment_profile_bundles_settings() {
+        let link = RgibberLink::from_config(GibberConfig::default()).unwrap();
+        let power_before = {
+            let mut protocol = link.protocol.lock().await;
+            protocol.get_laser_engine_mut().unwrap().get_current_power_profile().await
+        };
+
+        let settings = link.apply_environment_profile(EnvironmentProfile::CoastalFog).await.unwrap();
+        assert_eq!(settings.laser_power_multiplier(), 6.0);
+
+        let mut protocol = link.protocol.lock().await;
+        let laser = protocol.get_laser_engine_mut().unwrap();
+        let power = laser.get_current_power_profile().await;
+        let safe_limit = power.safe_power_limit(&laser.get_config().laser_type);
+        assert_eq!(power.optimal_power_mw, (power_before.optimal_power_mw * 6.0).min(safe_limit));
+
+        let ecc = laser.get_optical_ecc_config().unwrap();
+        assert_eq!(ecc.reed_solomon.parity_shards, 12);
+        assert!(ecc.reed_solomon.parity_shards > crate::optical_ecc::ReedSolomonConfig::default().parity_shards);
+        assert_eq!(ecc.interleaving.depth, 8);
+
+        let ranging = laser.range_detector().unwrap();
+        assert_eq!(ranging.lock().await.get_config().max_range_m, 100.0);
+        assert_eq!(ranging.lock().await.get_config().temperature_celsius, 12.0);
+        assert_eq!(ranging.lock().await.get_environmental_conditions().await.temperature_celsius, 12.0);
+        let speed_of_sound = ranging.lock().await.speed_of_sound().await;
+        assert_eq!(ranging.lock().await.get_config().speed_of_sound_mps, speed_of_sound);
+        assert!(speed_of_sound < RangingConfig::default().speed_of_sound_mps);
+        assert_eq!(protocol.get_audio_engine_mut().get_config().mode, audio::AudioMode::Ultrasonic);
+        drop(protocol);
+
+        // Channel validation expects the ultrasound leg at the same speed of sound
+        let security = link.security_manager();
+        let validation = security.lock().await.as_ref().unwrap().channel_validation_config().await;
+        assert_eq!(validation.speed_of_sound_mps, settings.range_conditions().speed_of_sound());
+        assert!(validation.speed_of_sound_mps < channel_validator::ValidationConfig::default().speed_of_sound_mps);
+
+        // Re-applying recomputes from the uncompensated power instead of compounding
+        link.apply_environment_profile(EnvironmentProfile::CoastalFog).await.unwrap();
+        let mut protocol = link.protocol.lock().await;
+        let laser = protocol.get_laser_engine_mut().unwrap();
+        assert_eq!(laser.get_current_power_profile().await.optimal_power_mw, power.optimal_power_mw);
+        drop(protocol);
+        link.apply_environment_profile(EnvironmentProfile::ClearOutdoor).await.unwrap();
+        let mut protocol = link.protocol.lock().await;
+        let laser = protocol.get_laser_engine_mut().unwrap();
+        assert_eq!(laser.get_current_power_profile().await.optimal_power_mw, power_before.optimal_power_mw.min(safe_limit));
+        drop(protocol);
+
+        // The same preset folds into a deployment config
+        let mut config = GibberConfig::default();
+        config.apply_environment_profile(EnvironmentProfile::CoastalFog);
+        assert_eq!(config.optical_ecc.reed_solomon.parity_shards, 12);
+        assert_eq!(config.ranging.max_range_m, 100.0);
+        assert!(config.laser.range_meters <= 100.0);
+    }
+
+    #[tokio::test]
+    async fn test_proximity_gate_blocks_sensitive_send_with_intruder() {
+        let link = connected_link([5u8; 32]).await;
+        let mut detector = RangeDetector::with_config(RangingConfig {
+            min_range_m: 1.0,
+            max_range_m: 20.0,
+            ..RangingConfig::default()
+        });
+        detector.initialize().await.unwrap();
+
+        // Range the peer at 15m, then a second return appears 3m out
+        detector.set_simulated_target(Some(15.0)).await;
+        detector.measure_distance().await.unwrap();
+        detector.set_simulated_returns(vec![3.0]).await;
+        let detector = Arc::new(Mutex::new(detector));
+        link.set_proximity_gate(detector.clone(), 5.0).await;
+
+        let result = link.send_command("unlock", std::collections::HashMap::new()).await;
+        match result {
+            Err(MessagingError::ProximityThreat { distance_m }) => assert!((distance_m - 3.0).abs() < 0.5),
+            other => panic!("expected ProximityThreat, got {:?}", other),
+        }
+
+        // Routine traffic still goes out, and the command does once the path is clear
+        assert!(link.send_text_message("hello").await.is_ok());
+        detector.lock().await.set_simulated_returns(Vec::new()).await;
+        assert!(link.send_command("unlock", std::collections::HashMap::new()).await.is_ok());
+
+        // A detector that cannot scan blocks the command rather than waving it through
+        detector.lock().await.shutdown().await.unwrap();
+        assert!(matches!(
+            link.send_command("unlock", std::collections::HashMap::new()).await,
+            Err(MessagingError::ProximityScanFailed(RangeDetectorError::HardwareInitFailed))
+        ));
+        assert!(link.send_text_message("hello").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_clock_skew_rejects_future_dated_messages() {
+        let mut link = connected_link([9u8; 32]).await;
+        let mut peer = link_peer([9u8; 32]);
+        let clock = MockClock::new(std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000));
+        link.set_clock(Arc::new(clock.clone()));
+        link.set_max_clock_skew(std::time::Duration::from_secs(30));
+
+        let sealed_at = |offset_secs: i64| {
+            let mut message = link.create_message(MessageType::Text("ping".to_string()), MessagePriority::Normal, 60);
+            message.timestamp = if offset_secs >= 0 {
+                clock.now() + std::time::Duration::from_secs(offset_secs as u64)
+            } else {
+                clock.now() - std::time::Duration::from_secs(offset_secs.unsigned_abs())
+            };
+            serde_json::to_vec(&message).unwrap()
+        };
+
+        // A day in the future would bypass TTL expiry
+        let future = peer.encrypt_sequenced(&sealed_at(86_400)).unwrap();
+        assert!(matches!(link.process_incoming_message(&future).await, Err(MessagingError::ClockSkewExceeded)));
+        let stale = peer.encrypt_sequenced(&sealed_at(-3_600)).unwrap();
+        assert!(matches!(link.process_incoming_message(&stale).await, Err(MessagingError::ClockSkewExceeded)));
+        assert!(!link.has_pending_messages().await);
+
+        // Small drift between synced clocks is tolerated
+        let in_skew = peer.encrypt_sequenced(&sealed_at(10)).unwrap();
+        link.process_incoming_message(&in_skew).await.unwrap();
+        assert_eq!(link.get_pending_messages().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_link_quality_weighted_score() {
+        let mut link = RgibberLink::new();
+        // No engines attached: nothing contributes
+        link.refresh_link_metrics().await;
+        assert_eq!(link.link_quality(), 0.0);
+
+        let metrics = LinkMetrics {
+            laser_aligned: true,
+            laser_signal_strength: 0.9,
+            bit_error_rate: 0.001,
+            beam_signal_quality: 0.6,
+            range_confidence: 0.5,
+            coupling_validated: true,
+        };
+        link.update_link_metrics(metrics);
+
+        // Default weights 0.4/0.25/0.15/0.2; laser = 0.9 * (1 - 0.001 / 0.01) = 0.81
+        let expected = 0.4 * 0.81 + 0.25 * 0.6 + 0.15 * 0.5 + 0.2 * 1.0;
+        assert!((link.link_quality() - expected).abs() < 1e-5);
+
+        // Custom weights are normalized by their sum
+        link.set_link_quality_weights(LinkQualityWeights { laser: 2.0, beam: 1.0, range: 0.0, coupling: 1.0 });
+        let expected = (2.0 * 0.81 + 1.0 * 0.6 + 1.0) / 4.0;
+        assert!((link.link_quality() - expected).abs() < 1e-5);
+
+        // Misalignment zeroes the laser term
+        link.update_link_metrics(LinkMetrics { laser_aligned: false, ..metrics });
+        let expected = (1.0 * 0.6 + 1.0) / 4.0;
+        assert!((link.link_quality() - expected).abs() < 1e-5);
+    }
+
+    #[tokio::test]
+    async fn test_security_transitions_are_audited() {
+        let audit: SharedAuditSystem = Arc::new(Mutex::new(AuditSystem::new(100)));
+
+        // PIN lockout after the configured number of failures
+        let security = SecurityManager::new(SecurityConfig { max_pin_attempts: 2, ..SecurityConfig::default() });
+        security.set_audit_system(audit.clone()).await;
+        assert!(matches!(security.validate_pin("0000").await, Err(SecurityError::InvalidPin)));
+        assert!(matches!(security.validate_pin("0000").await, Err(SecurityError::AccountLocked)));
+
+        security.grant_permission(PermissionType::Pairing, PermissionScope::Session, "operator-7").await.unwrap();
+
+        // Fallback from long-range drops the protocol to short-range mode
+        let mut protocol = ProtocolEngine::new();
+        protocol.set_audit_system(audit.clone());
+        let protocol = Arc::new(Mutex::new(protocol));
+        let mut fallback = FallbackManager::new(protocol.clone());
+        fallback.set_audit_system(audit.clone());
+        fallback.manual_fallback(ChannelFailure::LaserBlocked).await.unwrap();
+        fallback.stop().await.unwrap();
+
+        let entries = audit.lock().await.query_audit(crate::audit::AuditQuery {
+            start_time: None,
+            end_time: None,
+            event_types: Vec::new(),
+            min_severity: None,
+            actor_filter: None,
+            compliance_flags: Vec::new(),
+            limit: None,
+        });
+        let event_types: Vec<AuditEventType> = entries.iter().map(|entry| entry.event_type.clone()).collect();
+        for expected in [AuditEventType::PinLockout, AuditEventType::PermissionGranted, AuditEventType::ModeDowngrade] {
+            assert_eq!(event_types.iter().filter(|event| **event == expected).count(), 1, "{:?} in {:?}", expected, event_types);
+        }
+        let lockout = entries.iter().find(|entry| entry.event_type == AuditEventType::PinLockout).unwrap();
+        assert_eq!(lockout.severity, AuditSeverity::High);
+    }
+
     #[tokio::test]
     async fn test_handshake_initiation() {
         let mut _link = RgibberLink::new();
@@ -1094,4 +2302,290 @@ pub extern "C" fn check_camera_hardware_available() -> bool {
 pub extern "C" fn gibberlink_free_data(_data: *mut u8) {
 }
     }
+
+    #[tokio::test]
+    async fn test_large_message_reassembled_from_shuffled_fragments() {
+        let mut link = connected_link([0x21; 32]).await;
+        let mut peer = link_peer([0x21; 32]);
+        let clock = MockClock::new(std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000));
+        link.set_clock(Arc::new(clock.clone()));
+
+        let content: Vec<u8> = (0..FRAGMENT_PAYLOAD_SIZE * 5 + 77).map(|i| (i % 253) as u8).collect();
+        assert!(link.send_large_message(&content, MessageType::Text("photo.png".to_string())).await.is_ok());
+
+        let seal = |message: &Message| serde_json::to_vec(message).unwrap();
+        let fragments = link.fragment_message(&content, MessageType::Text("photo.png".to_string()), MessagePriority::Normal, 10).unwrap();
+        assert_eq!(fragments.len(), 6);
+        assert!(fragments.iter().all(|fragment| seal(fragment).len() <= 65536));
+
+        // Out of order, with a duplicate
+        for index in [3, 0, 5, 3, 1, 4] {
+            let encrypted = peer.encrypt_sequenced(&seal(&fragments[index])).unwrap();
+            link.process_incoming_message(&encrypted).await.unwrap();
+            assert!(!link.has_pending_messages().await);
+        }
+        let last = peer.encrypt_sequenced(&seal(&fragments[2])).unwrap();
+        link.process_incoming_message(&last).await.unwrap();
+        let messages = link.get_pending_messages().await;
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].content, content);
+        assert_eq!(messages[0].id, fragments[0].id);
+        assert!(messages[0].fragment.is_none());
+
+        // A partial group is dropped once its TTL passes
+        let fragments = link.fragment_message(&content, MessageType::Text("late".to_string()), MessagePriority::Normal, 10).unwrap();
+        let first = peer.encrypt_sequenced(&seal(&fragments[0])).unwrap();
+        link.process_incoming_message(&first).await.unwrap();
+        assert_eq!(link.fragment_groups.lock().await.len(), 1);
+        clock.advance(std::time::Duration::from_secs(11));
+        let second = peer.encrypt_sequenced(&seal(&fragments[1])).unwrap();
+        assert!(matches!(link.process_incoming_message(&second).await, Err(MessagingError::MessageExpired)));
+        assert!(link.fragment_groups.lock().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_per_peer_sessions_keep_state_and_queues_apart() {
+        // The default engine is connected, which must not vouch for any peer
+        let link = connected_link([0x31; 32]).await;
+        let (alice, bob) = ("GL-AB12-CDEF", "GL-9876-5432");
+        assert!(matches!(link.send_text_message_to(alice, "hi").await, Err(MessagingError::UnknownPeer(_))));
+        assert!(matches!(link.connect_peer("not-a-peer").await, Err(ProtocolError::InvalidPeerIdentity(_))));
+
+        link.connect_peer(alice).await.unwrap();
+        link.connect_peer(bob).await.unwrap();
+        let alice_protocol = link.peer_protocol(alice).await.unwrap();
+        assert!(matches!(alice_protocol.lock().await.get_state().await, ProtocolState::WaitingForQr));
+        assert!(matches!(link.send_text_message_to(alice, "hi").await, Err(MessagingError::ConnectionNotEstablished)));
+
+        // Only alice's handshake completes
+        {
+            let mut protocol = alice_protocol.lock().await;
+            protocol.set_shared_secret(Some([0xA1; 32]));
+            protocol.set_state(ProtocolState::Connected).await;
+        }
+        assert!(link.send_text_message_to(alice, "hi alice").await.is_ok());
+        assert!(matches!(link.send_text_message_to(bob, "hi bob").await, Err(MessagingError::ConnectionNotEstablished)));
+
+        // Traffic from alice opens only with her key and lands only in her queue
+        let message = link.create_message(MessageType::Text("from alice".to_string()), MessagePriority::Normal, 300);
+        let sealed = link_peer([0xA1; 32]).encrypt_sequenced(&serde_json::to_vec(&message).unwrap()).unwrap();
+        assert!(link.process_incoming_message_from(bob, &sealed).await.is_err());
+        link.process_incoming_message_from(alice, &sealed).await.unwrap();
+        assert!(link.get_pending_messages_from(bob).await.is_empty());
+        assert!(!link.has_pending_messages().await);
+        let received = link.get_pending_messages_from(alice).await;
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0].id, message.id);
+        assert!(link.get_pending_messages_from(alice).await.is_empty());
+
+        assert!(link.disconnect_peer(alice).await);
+        assert!(link.peer_protocol(alice).await.is_none());
+        assert_eq!(link.peer_ids().await, vec![bob.to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_fragment_reassembly_is_bounded_per_peer() {
+        let mut link = connected_link([0x22; 32]).await;
+        let clock = MockClock::new(std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000));
+        link.set_clock(Arc::new(clock.clone()));
+        let groups = Mutex::new(std::collections::HashMap::new());
+        let fragment = |group: u64, index: u32, total: u32, content: Vec<u8>| {
+            let mut message = link.create_message(MessageType::Text("part".to_string()), MessagePriority::Normal, u32::MAX);
+            message.fragment = Some(FragmentInfo { fragment_group_id: group, index, total });
+            message.content = content;
+            message
+        };
+
+        // Oversized chunks are refused outright
+        let oversized = fragment(0, 0, 2, vec![0; FRAGMENT_PAYLOAD_SIZE + 1]);
+        assert!(matches!(link.reassemble_fragments(&groups, oversized).await, Err(MessagingError::InvalidFormat)));
+
+        // Group count is capped, but groups already open keep filling
+        for group in 0..MAX_PENDING_FRAGMENT_GROUPS as u64 {
+            assert!(link.reassemble_fragments(&groups, fragment(group, 0, 2, vec![1])).await.unwrap().is_none());
+        }
+        let extra = fragment(99, 0, 2, vec![1]);
+        assert!(matches!(link.reassemble_fragments(&groups, extra).await, Err(MessagingError::ReassemblyLimitExceeded)));
+        assert!(link.reassemble_fragments(&groups, fragment(0, 1, 2, vec![2])).await.unwrap().is_some());
+
+        // A TTL of u32::MAX seconds still lets the groups go after the clamp
+        clock.advance(MAX_FRAGMENT_GROUP_TTL + std::time::Duration::from_secs(1));
+        assert!(link.reassemble_fragments(&groups, fragment(99, 0, 2, vec![1])).await.unwrap().is_none());
+        assert_eq!(groups.lock().await.len(), 1);
+        groups.lock().await.clear();
+
+        // Buffered bytes are capped across groups
+        let chunk = vec![7; FRAGMENT_PAYLOAD_SIZE];
+        for index in 0..MAX_FRAGMENTS - 1 {
+            assert!(link.reassemble_fragments(&groups, fragment(1, index, MAX_FRAGMENTS, chunk.clone())).await.unwrap().is_none());
+        }
+        assert!(link.reassemble_fragments(&groups, fragment(2, 0, 2, chunk)).await.unwrap().is_none());
+        let over = fragment(3, 0, 2, vec![1]);
+        assert!(matches!(link.reassemble_fragments(&groups, over).await, Err(MessagingError::ReassemblyLimitExceeded)));
+        assert_eq!(groups.lock().await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_peer_sessions_inherit_policy_and_reassemble_apart() {
+        let link = connected_link([0x32; 32]).await;
+        link.protocol.lock().await.set_cipher_suite(crate::crypto::CipherSuite::ChaCha20Poly1305);
+        link.protocol.lock().await.set_session_lifetime(Some(std::time::Duration::from_secs(90)));
+        let (alice, bob) = ("GL-AB12-CDEF", "GL-9876-5432");
+        link.connect_peer(alice).await.unwrap();
+        link.connect_peer(bob).await.unwrap();
+        let policy = link.protocol.lock().await.session_policy();
+        for (peer_id, key) in [(alice, [0xA1; 32]), (bob, [0xB2; 32])] {
+            let protocol = link.peer_protocol(peer_id).await.unwrap();
+            let mut protocol = protocol.lock().await;
+            assert_eq!(protocol.session_policy(), policy);
+            protocol.set_shared_secret(Some(key));
+            protocol.set_state(ProtocolState::Connected).await;
+        }
+
+        // Bob sending the missing fragment of alice's group id must not complete it
+        let content: Vec<u8> = (0..FRAGMENT_PAYLOAD_SIZE * 2 + 5).map(|i| i as u8).collect();
+        let fragments = link.fragment_message(&content, MessageType::Text("split".to_string()), MessagePriority::Normal, 60).unwrap();
+        let seal = |message: &Message| serde_json::to_vec(message).unwrap();
+        let mut from_alice = link_peer([0xA1; 32]);
+        from_alice.set_cipher_suite(crate::crypto::CipherSuite::ChaCha20Poly1305);
+        let mut from_bob = link_peer([0xB2; 32]);
+        for fragment in &fragments[..2] {
+            link.process_incoming_message_from(alice, &from_alice.encrypt_sequenced(&seal(fragment)).unwrap()).await.unwrap();
+        }
+        link.process_incoming_message_from(bob, &from_bob.encrypt_sequenced(&seal(&fragments[2])).unwrap()).await.unwrap();
+        assert!(link.get_pending_messages_from(alice).await.is_empty());
+        assert!(link.get_pending_messages_from(bob).await.is_empty());
+
+        link.process_incoming_message_from(alice, &from_alice.encrypt_sequenced(&seal(&fragments[2])).unwrap()).await.unwrap();
+        let received = link.get_pending_messages_from(alice).await;
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0].content, content);
+        assert_eq!(link.sessions.lock().await.get(bob).unwrap().fragment_groups.lock().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_restore_session_from_persisted_snapshot() {
+        let storage_key = [0x42; 32];
+        let link = connected_link([0x24; 32]).await;
+        link.protocol.lock().await.set_peer_public_key(Some(vec![3; 32]));
+        let mut peer = link_peer([0x24; 32]);
+        let before = link.create_message(MessageType::Text("before restart".to_string()), MessagePriority::Normal, 300);
+        let sealed_before = peer.encrypt_sequenced(&serde_json::to_vec(&before).unwrap()).unwrap();
+        link.process_incoming_message(&sealed_before).await.unwrap();
+        let sent_before = link.encrypt_message(b"to the peer").await.unwrap();
+        assert_eq!(peer.decrypt_sequenced(&sent_before).unwrap(), b"to the peer");
+        let bytes = link.session_snapshot().await.to_bytes(&storage_key).unwrap();
+        drop(link);
+
+        // A fresh process: no handshake, just the persisted blob
+        let restarted = RgibberLink::new();
+        assert!(restarted.restore_session(&SessionSnapshot::from_bytes(&bytes, &storage_key).unwrap()).await.is_ok());
+        assert!(matches!(restarted.get_state().await, ProtocolState::Connected));
+        assert_eq!(restarted.get_shared_secret().await, Some([0x24; 32]));
+
+        // What was opened before the snapshot is still a replay after it
+        assert!(matches!(restarted.decrypt_message(&sealed_before).await, Err(ProtocolError::MessageReplayed)));
+        let after = restarted.create_message(MessageType::Text("after restart".to_string()), MessagePriority::Normal, 300);
+        restarted.process_incoming_message(&peer.encrypt_sequenced(&serde_json::to_vec(&after).unwrap()).unwrap()).await.unwrap();
+        assert_eq!(restarted.get_pending_messages().await[0].id, after.id);
+
+        // Our own counter carries on too, so the peer opens what we send next
+        let sent_after = restarted.encrypt_message(b"to the peer").await.unwrap();
+        assert_eq!(peer.decrypt_sequenced(&sent_after).unwrap(), b"to the peer");
+
+        // A snapshot taken before the handshake finished cannot be resumed, even
+        // once the key is derived and the peer key known
+        let idle = RgibberLink::new().session_snapshot().await;
+        assert!(matches!(RgibberLink::new().restore_session(&idle).await, Err(ProtocolError::InvalidState)));
+        let confirming = connected_link([0x25; 32]).await;
+        {
+            let mut protocol = confirming.protocol.lock().await;
+            protocol.set_peer_public_key(Some(vec![3; 32]));
+            protocol.set_state(ProtocolState::KeyConfirmation).await;
+        }
+        let mid_handshake = confirming.session_snapshot().await;
+        assert!(mid_handshake.shared_secret.is_some());
+        assert!(matches!(RgibberLink::new().restore_session(&mid_handshake).await, Err(ProtocolError::InvalidState)));
+    }
+
+    #[tokio::test]
+    async fn test_drain_messages_by_priority_and_ttl() {
+        let mut link = connected_link([0x31; 32]).await;
+        let mut peer = link_peer([0x31; 32]);
+        let clock = MockClock::new(std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000));
+        link.set_clock(Arc::new(clock.clone()));
+
+        for (name, priority, ttl_seconds) in [
+            ("normal-1", MessagePriority::Normal, 300),
+            ("low", MessagePriority::Low, 300),
+            ("critical", MessagePriority::Critical, 300),
+            ("normal-2", MessagePriority::Normal, 300),
+            ("high-stale", MessagePriority::High, 5),
+            ("high", MessagePriority::High, 300),
+        ] {
+            let message = link.create_message(MessageType::Text(name.to_string()), priority, ttl_seconds);
+            let sealed = peer.encrypt_sequenced(&serde_json::to_vec(&message).unwrap()).unwrap();
+            link.process_incoming_message(&sealed).await.unwrap();
+        }
+        clock.advance(std::time::Duration::from_secs(10));
+
+        let names = |messages: Vec<Message>| -> Vec<String> {
+            messages.into_iter().map(|message| match message.message_type {
+                MessageType::Text(name) => name,
+                other => panic!("unexpected {:?}", other),
+            }).collect()
+        };
+        assert_eq!(names(link.drain_messages(3).await), ["critical", "high", "normal-1"]);
+        assert!(link.has_pending_messages().await);
+        assert_eq!(names(link.get_pending_messages().await), ["normal-2", "low"]);
+        assert!(!link.has_pending_messages().await);
+    }
+
+    #[tokio::test]
+    async fn test_purge_expired_removes_stale_messages() {
+        let mut link = connected_link([0x32; 32]).await;
+        let mut peer = link_peer([0x32; 32]);
+        let clock = MockClock::new(std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000));
+        link.set_clock(Arc::new(clock.clone()));
+
+        for ttl_seconds in [1, 300] {
+            let message = link.create_message(MessageType::Text(format!("ttl {}", ttl_seconds)), MessagePriority::Normal, ttl_seconds);
+            let sealed = peer.encrypt_sequenced(&serde_json::to_vec(&message).unwrap()).unwrap();
+            link.process_incoming_message(&sealed).await.unwrap();
+        }
+        assert_eq!(link.purge_expired().await, 0);
+
+        clock.advance(std::time::Duration::from_secs(2));
+        assert_eq!(link.purge_expired().await, 1);
+        let pending = link.get_pending_messages().await;
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].ttl_seconds, 300);
+
+        let stale = link.create_message(MessageType::Text("stale".to_string()), MessagePriority::Normal, 1);
+        assert!(link.check_message_expiry(&stale).is_ok());
+        clock.advance(std::time::Duration::from_secs(1));
+        assert!(matches!(link.check_message_expiry(&stale), Err(MessagingError::MessageExpired)));
+        let sealed = peer.encrypt_sequenced(&serde_json::to_vec(&stale).unwrap()).unwrap();
+        assert!(matches!(link.process_incoming_message(&sealed).await, Err(MessagingError::MessageExpired)));
+    }
+
+    #[tokio::test]
+    async fn test_expiry_past_the_end_of_the_clock_counts_as_expired() {
+        let mut link = connected_link([0x33; 32]).await;
+        // Skew checks would turn the message away first; tolerate any
+        link.set_max_clock_skew(std::time::Duration::MAX);
+        let mut peer = link_peer([0x33; 32]);
+        let mut message = link.create_message(MessageType::Text("overflow".to_string()), MessagePriority::Normal, u32::MAX);
+        message.timestamp = std::time::UNIX_EPOCH + std::time::Duration::from_secs(i64::MAX as u64 - 10);
+        assert_eq!(message.expires_at(), std::time::UNIX_EPOCH);
+        assert!(matches!(link.check_message_expiry(&message), Err(MessagingError::MessageExpired)));
+
+        let sealed = peer.encrypt_sequenced(&serde_json::to_vec(&message).unwrap()).unwrap();
+        assert!(matches!(link.process_incoming_message(&sealed).await, Err(MessagingError::MessageExpired)));
+
+        // Fragments of such a message never open a group
+        message.fragment = Some(FragmentInfo { fragment_group_id: 7, index: 0, total: 2 });
+        let sealed = peer.encrypt_sequenced(&serde_json::to_vec(&message).unwrap()).unwrap();
+        assert!(matches!(link.process_incoming_message(&sealed).await, Err(MessagingError::MessageExpired)));
+    }
 }
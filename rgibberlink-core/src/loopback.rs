@@ -0,0 +1,345 @@
+//! # Loopback Channel
+//!
+//! In-memory stand-in for the audio and visual media between devices, so a full
+//! sender/receiver handshake can run in one process. Each `ProtocolEngine`
+//! attached to a `LoopbackChannel` has the handshake frames it emits delivered
+//! to every other attached engine's `LoopbackEndpoint`; the endpoint hands them
+//! to its engine when `deliver` is called.
+
+use crate::protocol::{HandshakeFrame, ProtocolEngine, ProtocolError};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+
+type Inboxes = Arc<Mutex<Vec<(usize, mpsc::UnboundedSender<HandshakeFrame>)>>>;
+
+/// Shared medium joining any number of engines
+#[derive(Clone, Default)]
+pub struct LoopbackChannel {
+    inboxes: Inboxes,
+}
+
+impl LoopbackChannel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Route `engine`'s emitted frames onto the channel and return the endpoint
+    /// receiving what the other engines emit
+    pub fn attach(&self, engine: &mut ProtocolEngine) -> LoopbackEndpoint {
+        let (sender, inbox) = mpsc::unbounded_channel();
+        let id = {
+            let mut inboxes = self.inboxes.lock().unwrap();
+            let id = inboxes.len();
+            inboxes.push((id, sender));
+            id
+        };
+
+        let inboxes = self.inboxes.clone();
+        engine.set_handshake_sink(Some(Arc::new(move |frame: HandshakeFrame| {
+            for (peer, inbox) in inboxes.lock().unwrap().iter() {
+                if *peer != id {
+                    // A dropped endpoint is a device that left; nothing to deliver to
+                    let _ = inbox.send(frame.clone());
+                }
+            }
+        })));
+
+        LoopbackEndpoint { inbox }
+    }
+}
+
+/// Frames waiting for one attached engine
+pub struct LoopbackEndpoint {
+    inbox: mpsc::UnboundedReceiver<HandshakeFrame>,
+}
+
+impl LoopbackEndpoint {
+    /// Next undelivered frame, if any
+    pub fn try_recv(&mut self) -> Option<HandshakeFrame> {
+        self.inbox.try_recv().ok()
+    }
+
+    /// Hand every waiting frame to `engine`, returning how many were delivered.
+    /// Stops at the first frame the engine rejects.
+    pub async fn deliver(&mut self, engine: &mut ProtocolEngine) -> Result<usize, ProtocolError> {
+        let mut delivered = 0;
+        while let Some(frame) = self.try_recv() {
+            engine.handle_handshake_frame(frame).await?;
+            delivered += 1;
+        }
+        Ok(delivered)
+    }
+}
+
+/// Deliver frames back and forth between two attached engines until neither
+/// has anything left to receive
+pub async fn run_until_quiet(
+    a: &mut ProtocolEngine,
+    a_endpoint: &mut LoopbackEndpoint,
+    b: &mut ProtocolEngine,
+    b_endpoint: &mut LoopbackEndpoint,
+) -> Result<(), ProtocolError> {
+    loop {
+        let delivered = b_endpoint.deliver(b).await? + a_endpoint.deliver(a).await?;
+        if delivered == 0 {
+            return Ok(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::CryptoEngine;
+    use crate::protocol::{KeyEstablishment, ProtocolState, ProtocolVersion, DEFAULT_SESSION_TICKET_LIFETIME};
+
+    #[tokio::test]
+    async fn test_handshake_runs_end_to_end_over_loopback() {
+        let channel = LoopbackChannel::new();
+        let mut a = ProtocolEngine::new();
+        let mut b = ProtocolEngine::new();
+        // The session id is agreed before the handshake starts
+        b.set_session_id(*a.get_session_id()).await;
+        let mut a_endpoint = channel.attach(&mut a);
+        let mut b_endpoint = channel.attach(&mut b);
+
+        a.initiate_handshake().await.unwrap();
+        run_until_quiet(&mut a, &mut a_endpoint, &mut b, &mut b_endpoint).await.unwrap();
+
+        // B verified A's tag from the ACK; A verified B's answering tag
+        assert_eq!(b.get_state().await, ProtocolState::Connected);
+        assert_eq!(a.get_state().await, ProtocolState::SecureChannelEstablished);
+        assert!(a.get_shared_secret().is_some());
+        assert_eq!(a.get_shared_secret(), b.get_shared_secret());
+        // A pinned B's identity key on first use
+        assert_eq!(a.pinned_peer_identity().as_ref(), Some(b.identity_public_key()));
+
+        let ciphertext = a.encrypt_message(b"over the loopback").await.unwrap();
+        assert_eq!(b.decrypt_message(&ciphertext).await.unwrap(), b"over the loopback");
+    }
+
+    #[tokio::test]
+    async fn test_keepalives_authenticate_after_real_handshake() {
+        let channel = LoopbackChannel::new();
+        let mut a = ProtocolEngine::new();
+        let mut b = ProtocolEngine::new();
+        b.set_session_id(*a.get_session_id()).await;
+        let mut a_endpoint = channel.attach(&mut a);
+        let mut b_endpoint = channel.attach(&mut b);
+
+        a.initiate_handshake().await.unwrap();
+        run_until_quiet(&mut a, &mut a_endpoint, &mut b, &mut b_endpoint).await.unwrap();
+
+        // Keepalives are keyed on the handshake keys, not on keys rotated since
+        let interval = std::time::Duration::from_millis(20);
+        a.set_keepalive_interval(interval);
+        b.set_keepalive_interval(interval);
+        tokio::time::sleep(interval).await;
+        let ping = a.poll_keepalive().await.unwrap().expect("idle session sends a ping");
+        let pong = b.handle_keepalive(&ping).await.unwrap().expect("ping is answered");
+        assert!(a.handle_keepalive(&pong).await.unwrap().is_none());
+        assert_eq!(a.missed_keepalives(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_mutual_authentication_challenges_initiator() {
+        let channel = LoopbackChannel::new();
+        let mut a = ProtocolEngine::new();
+        let mut b = ProtocolEngine::new();
+        b.set_session_id(*a.get_session_id()).await;
+        // Mutual authentication is on unless turned off
+        assert!(a.is_mutual_authentication_required() && b.is_mutual_authentication_required());
+        let mut a_endpoint = channel.attach(&mut a);
+        let mut b_endpoint = channel.attach(&mut b);
+
+        a.initiate_handshake().await.unwrap();
+        b_endpoint.deliver(&mut b).await.unwrap();
+        a_endpoint.deliver(&mut a).await.unwrap();
+        assert_eq!(a.get_state().await, ProtocolState::WaitingForChallenge);
+        b_endpoint.deliver(&mut b).await.unwrap();
+        assert_eq!(b.get_state().await, ProtocolState::WaitingForChallengeResponse);
+
+        run_until_quiet(&mut a, &mut a_endpoint, &mut b, &mut b_endpoint).await.unwrap();
+        assert_eq!(b.get_state().await, ProtocolState::Connected);
+        assert_eq!(a.get_state().await, ProtocolState::SecureChannelEstablished);
+        assert_eq!(a.get_shared_secret(), b.get_shared_secret());
+
+        // A responder insisting on mutual authentication needs the initiator's key
+        let mut c = ProtocolEngine::new();
+        assert!(matches!(c.receive_nonce(&CryptoEngine::generate_nonce()).await, Err(ProtocolError::InvalidPeerIdentity(_))));
+    }
+
+    #[tokio::test]
+    async fn test_mutual_authentication_rejects_foreign_signature() {
+        let channel = LoopbackChannel::new();
+        let mut a = ProtocolEngine::new();
+        let mut b = ProtocolEngine::new();
+        b.set_session_id(*a.get_session_id()).await;
+        a.set_mutual_authentication_required(true);
+        b.set_mutual_authentication_required(true);
+        let mut a_endpoint = channel.attach(&mut a);
+        let mut b_endpoint = channel.attach(&mut b);
+
+        a.initiate_handshake().await.unwrap();
+        b_endpoint.deliver(&mut b).await.unwrap();
+        a_endpoint.deliver(&mut a).await.unwrap();
+        b_endpoint.deliver(&mut b).await.unwrap();
+
+        // Someone holding the session key but not A's identity key answers instead
+        let sealed = match a_endpoint.try_recv() {
+            Some(HandshakeFrame::Challenge(sealed)) => sealed,
+            other => panic!("expected challenge, got {:?}", other),
+        };
+        let mut transcript = CryptoEngine::decrypt_data_suite(a.get_shared_secret().unwrap(), &sealed).unwrap();
+        transcript.extend_from_slice(a.get_session_id().as_bytes());
+        let forged = CryptoEngine::new().sign(&transcript);
+
+        assert!(matches!(
+            b.handle_handshake_frame(HandshakeFrame::ChallengeResponse(forged.to_vec())).await,
+            Err(ProtocolError::MutualAuthenticationFailed)
+        ));
+        assert!(matches!(b.get_state().await, ProtocolState::Error(_)));
+        assert!(b.get_shared_secret().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_handshake_negotiates_highest_common_version() {
+        let channel = LoopbackChannel::new();
+        let mut a = ProtocolEngine::new();
+        let mut b = ProtocolEngine::new();
+        b.set_session_id(*a.get_session_id()).await;
+        a.set_supported_versions(vec![ProtocolVersion::new(2, 0), ProtocolVersion::V1_0, ProtocolVersion::new(1, 1)]);
+        b.set_supported_versions(vec![ProtocolVersion::new(1, 1)]);
+        let mut a_endpoint = channel.attach(&mut a);
+        let mut b_endpoint = channel.attach(&mut b);
+
+        a.initiate_handshake().await.unwrap();
+        run_until_quiet(&mut a, &mut a_endpoint, &mut b, &mut b_endpoint).await.unwrap();
+        assert_eq!(a.get_state().await, ProtocolState::SecureChannelEstablished);
+        assert_eq!(a.negotiated_version(), Some(ProtocolVersion::new(1, 1)));
+        assert_eq!(b.negotiated_version(), Some(ProtocolVersion::new(1, 1)));
+
+        // A bare nonce comes from a peer that predates negotiation, and with it
+        // mutual authentication
+        let mut c = ProtocolEngine::new();
+        c.set_mutual_authentication_required(false);
+        c.receive_nonce(&CryptoEngine::generate_nonce()).await.unwrap();
+        assert_eq!(c.negotiated_version(), Some(ProtocolVersion::V1_0));
+
+        // ...which a 1.1-only responder cannot serve
+        let mut d = ProtocolEngine::new();
+        d.set_mutual_authentication_required(false);
+        d.set_supported_versions(vec![ProtocolVersion::new(1, 1)]);
+        assert!(matches!(d.receive_nonce(&CryptoEngine::generate_nonce()).await, Err(ProtocolError::UnsupportedProtocolVersion)));
+        assert_eq!(d.negotiated_version(), None);
+    }
+
+    #[tokio::test]
+    async fn test_trimmed_version_offer_fails_at_the_initiator() {
+        let channel = LoopbackChannel::new();
+        let mut a = ProtocolEngine::new();
+        let mut b = ProtocolEngine::new();
+        b.set_session_id(*a.get_session_id()).await;
+        for engine in [&mut a, &mut b] {
+            engine.set_supported_versions(vec![ProtocolVersion::V1_0, ProtocolVersion::new(1, 1)]);
+        }
+        let mut a_endpoint = channel.attach(&mut a);
+        let mut b_endpoint = channel.attach(&mut b);
+
+        a.initiate_handshake().await.unwrap();
+        let offer = match b_endpoint.try_recv() {
+            Some(HandshakeFrame::Nonce(frame)) => frame,
+            other => panic!("expected nonce, got {:?}", other),
+        };
+
+        // Unknown frame types are refused rather than guessed at
+        let mut unknown = offer.clone();
+        unknown[16] = 0x7F;
+        assert!(b.handle_handshake_frame(HandshakeFrame::Nonce(unknown)).await.is_err());
+
+        // Someone on the audio path strips 1.1 from the offer: B settles on 1.0,
+        // but signs what it saw and A notices
+        let mut trimmed = offer[..16].to_vec();
+        trimmed.extend_from_slice(&[0x01, 1, 1, 0]);
+        trimmed.extend_from_slice(&offer[offer.len() - 32..]);
+        b.handle_handshake_frame(HandshakeFrame::Nonce(trimmed)).await.unwrap();
+        assert_eq!(b.negotiated_version(), Some(ProtocolVersion::V1_0));
+        assert!(matches!(a_endpoint.deliver(&mut a).await, Err(ProtocolError::VersionOfferMismatch)));
+        assert_eq!(a.get_state().await, ProtocolState::WaitingForQr);
+    }
+
+    #[tokio::test]
+    async fn test_session_ticket_resumes_without_ecdh() {
+        let channel = LoopbackChannel::new();
+        let clock = Arc::new(crate::clock::MockClock::new(std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000)));
+        let mut a = ProtocolEngine::new();
+        let mut b = ProtocolEngine::new();
+        b.set_session_id(*a.get_session_id()).await;
+        for engine in [&mut a, &mut b] {
+            engine.set_clock(clock.clone());
+            engine.set_session_ticket_key(Some([0x5A; 32]));
+            // Resumption skips the identity challenge, so it is opt-in
+            engine.set_mutual_authentication_required(false);
+        }
+        let mut a_endpoint = channel.attach(&mut a);
+        let mut b_endpoint = channel.attach(&mut b);
+
+        a.initiate_handshake().await.unwrap();
+        run_until_quiet(&mut a, &mut a_endpoint, &mut b, &mut b_endpoint).await.unwrap();
+        let original_secret = *a.get_shared_secret().unwrap();
+        let ticket = b.export_session_ticket().unwrap();
+        a.close_session().await;
+        b.close_session().await;
+
+        // A ticket with a forged MAC is turned down without spending it
+        assert!(a.try_resume(&ticket).await);
+        assert_eq!(a.get_state().await, ProtocolState::KeyConfirmation);
+        let Some(HandshakeFrame::Nonce(mut forged)) = b_endpoint.try_recv() else {
+            panic!("expected a resumption nonce");
+        };
+        let genuine = forged.clone();
+        *forged.last_mut().unwrap() ^= 0x01;
+        assert!(matches!(
+            b.handle_handshake_frame(HandshakeFrame::Nonce(forged)).await,
+            Err(ProtocolError::SessionTicketRejected)
+        ));
+        assert_eq!(b.get_state().await, ProtocolState::Idle);
+
+        // The genuine MAC keys the responder, whose own MAC confirms the initiator
+        b.handle_handshake_frame(HandshakeFrame::Nonce(genuine.clone())).await.unwrap();
+        assert_eq!(b.get_state().await, ProtocolState::Connected);
+        a_endpoint.deliver(&mut a).await.unwrap();
+        assert_eq!(a.get_state().await, ProtocolState::SecureChannelEstablished);
+        assert_eq!(b.connection_info().await.key_establishment, Some(KeyEstablishment::SessionTicket));
+        assert_eq!(a.get_shared_secret(), b.get_shared_secret());
+        assert_ne!(a.get_shared_secret(), Some(&original_secret));
+        let ciphertext = a.encrypt_message(b"resumed").await.unwrap();
+        assert_eq!(b.decrypt_message(&ciphertext).await.unwrap(), b"resumed");
+
+        // Tickets are single-use, even with a fresh nonce and MAC
+        a.close_session().await;
+        b.close_session().await;
+        assert!(a.try_resume(&ticket).await);
+        assert!(matches!(b_endpoint.deliver(&mut b).await, Err(ProtocolError::SessionTicketRejected)));
+        assert_eq!(b.get_state().await, ProtocolState::Idle);
+
+        // Responders that require the identity challenge refuse resumption outright
+        a.close_session().await;
+        let mut strict = ProtocolEngine::new();
+        strict.set_clock(clock.clone());
+        strict.set_session_ticket_key(Some([0x5A; 32]));
+        assert!(matches!(
+            strict.handle_handshake_frame(HandshakeFrame::Nonce(genuine)).await,
+            Err(ProtocolError::SessionTicketRejected)
+        ));
+
+        // Tickets fail once expired or under another ticket key
+        let mut stranger = ProtocolEngine::new();
+        stranger.set_session_ticket_key(Some([0xA5; 32]));
+        stranger.set_mutual_authentication_required(false);
+        assert!(!stranger.try_resume(&ticket).await);
+        clock.advance(DEFAULT_SESSION_TICKET_LIFETIME);
+        assert!(!a.try_resume(&ticket).await);
+        assert_eq!(a.get_state().await, ProtocolState::Idle);
+    }
+}
@@ -1,11 +1,15 @@
 #[cfg(feature = "python")]
 use clap::{Parser, Subcommand};
 #[cfg(feature = "python")]
-use crate::crypto::CryptoEngine;
+use crate::crypto::{CipherSuite, CryptoEngine};
 #[cfg(feature = "python")]
 use crate::visual::{VisualEngine, VisualPayload};
 #[cfg(feature = "python")]
+use crate::session_id::SessionId;
+#[cfg(feature = "python")]
 use std::fs;
+#[cfg(feature = "python")]
+use std::io::Write;
 
 #[cfg(feature = "python")]
 /// RealGibber - Secure directional communication protocol CLI
@@ -34,6 +38,14 @@ enum Commands {
         /// Output format (svg or png, defaults to svg)
         #[arg(short, long, default_value = "svg")]
         format: String,
+
+        /// PNG pixels per QR module
+        #[arg(long, default_value_t = 8)]
+        scale: u32,
+
+        /// PNG light border width, in modules
+        #[arg(long, default_value_t = crate::visual::DEFAULT_QUIET_ZONE_MODULES)]
+        quiet_zone: u32,
     },
     /// Generate cryptographic keys
     Keygen {
@@ -44,6 +56,10 @@ enum Commands {
         /// Output file for public key
         #[arg(short, long)]
         public_key: Option<String>,
+
+        /// Passphrase sealing the private key file (required with --private-key)
+        #[arg(long)]
+        passphrase: Option<String>,
     },
     /// Encrypt data
     Encrypt {
@@ -81,11 +97,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let cli = Cli::parse();
 
     match cli.command {
-        Commands::Handshake { payload, output, format } => {
-            handle_handshake(payload, output, format).await?;
+        Commands::Handshake { payload, output, format, scale, quiet_zone } => {
+            handle_handshake(payload, output, format, scale, quiet_zone).await?;
         }
-        Commands::Keygen { private_key, public_key } => {
-            handle_keygen(private_key, public_key).await?;
+        Commands::Keygen { private_key, public_key, passphrase } => {
+            handle_keygen(private_key, public_key, passphrase).await?;
         }
         Commands::Encrypt { data, key_file, output } => {
             handle_encrypt(data, key_file, output).await?;
@@ -104,10 +120,10 @@ fn main() {
 }
 
 #[cfg(all(feature = "async", feature = "python"))]
-async fn handle_handshake(payload: String, output: Option<String>, format: String) -> Result<(), Box<dyn std::error::Error>> {
+async fn handle_handshake(payload: String, output: Option<String>, format: String, scale: u32, quiet_zone: u32) -> Result<(), Box<dyn std::error::Error>> {
     // Create crypto engine for key generation
     let crypto = CryptoEngine::new();
-    let session_id = CryptoEngine::generate_nonce();
+    let session_id = SessionId::random();
     let nonce = CryptoEngine::generate_nonce();
 
     // Create a dummy signature for demo purposes
@@ -119,32 +135,38 @@ async fn handle_handshake(payload: String, output: Option<String>, format: Strin
         public_key: crypto.public_key().to_vec(),
         nonce,
         signature: dummy_signature,
+        version: None,
+        version_offer: None,
+        psk: false,
     };
 
     // Create visual engine and encode
-    let visual_engine = VisualEngine::new();
-    let qr_svg = visual_engine.encode_payload(&visual_payload)?;
+    let mut visual_engine = VisualEngine::new();
+    visual_engine.set_quiet_zone_modules(quiet_zone);
 
-    // Handle output
-    match output {
-        Some(path) => {
-            if format == "svg" {
-                fs::write(&path, qr_svg)?;
-                println!("QR code saved to {}", path);
-            } else {
-                return Err("Only SVG format is currently supported".into());
-            }
+    match (format.as_str(), output) {
+        ("svg", Some(path)) => {
+            fs::write(&path, visual_engine.encode_payload(&visual_payload)?)?;
+            println!("QR code saved to {}", path);
         }
-        None => {
-            println!("{}", qr_svg);
+        ("svg", None) => {
+            println!("{}", visual_engine.encode_payload(&visual_payload)?);
+        }
+        ("png", Some(path)) => {
+            fs::write(&path, visual_engine.encode_payload_png(&visual_payload, scale)?)?;
+            println!("QR code saved to {}", path);
+        }
+        ("png", None) => {
+            std::io::stdout().write_all(&visual_engine.encode_payload_png(&visual_payload, scale)?)?;
         }
+        (other, _) => return Err(format!("Unsupported format '{}': expected svg or png", other).into()),
     }
 
     Ok(())
 }
 
 #[cfg(all(feature = "async", feature = "python"))]
-async fn handle_keygen(private_key_path: Option<String>, public_key_path: Option<String>) -> Result<(), Box<dyn std::error::Error>> {
+async fn handle_keygen(private_key_path: Option<String>, public_key_path: Option<String>, passphrase: Option<String>) -> Result<(), Box<dyn std::error::Error>> {
     let crypto = CryptoEngine::new();
 
     if let Some(path) = public_key_path {
@@ -154,9 +176,10 @@ async fn handle_keygen(private_key_path: Option<String>, public_key_path: Option
         println!("Public key: {}", hex::encode(crypto.public_key()));
     }
 
-    // Note: Private key handling would need secure key storage implementation
     if let Some(path) = private_key_path {
-        println!("Warning: Private key storage not implemented yet. Key not saved to {}", path);
+        let passphrase = passphrase.ok_or("--passphrase is required to save a private key")?;
+        fs::write(path, crypto.export_private_key(&passphrase)?)?;
+        println!("Encrypted private key saved");
     }
 
     Ok(())
@@ -172,7 +195,7 @@ async fn handle_encrypt(data: String, key_file: String, output: Option<String>)
     let mut key = [0u8; 32];
     key.copy_from_slice(&key_data);
 
-    let encrypted = CryptoEngine::encrypt_data(&key, data.as_bytes())?;
+    let encrypted = CryptoEngine::encrypt_data_suite(CipherSuite::default(), &key, data.as_bytes())?;
 
     match output {
         Some(path) => {
@@ -203,7 +226,7 @@ async fn handle_decrypt(input: String, key_file: String, output: Option<String>)
         hex::decode(&input)?
     };
 
-    let decrypted = CryptoEngine::decrypt_data(&key, &encrypted_data)?;
+    let decrypted = CryptoEngine::decrypt_data_suite(&key, &encrypted_data)?;
 
     match output {
         Some(path) => {
@@ -7,6 +7,8 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::time::{SystemTime, Duration};
+use crate::laser::LaserEngine;
+use crate::weather::{DroneSpecifications, WeatherData};
 
 /// Unique mission identifier (UUID-like format)
 pub type MissionId = [u8; 16];
@@ -697,6 +699,78 @@ pub struct WeatherSnapshot {
     pub source: String,
 }
 
+/// Energy consumer that makes a mission infeasible
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LimitingFactor {
+    /// Flight alone exceeds the usable battery energy
+    FlightEnergy,
+    /// Flight fits, but not together with the communication link
+    CommsEnergy,
+}
+
+/// Combined flight and communication energy check against the battery
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Feasibility {
+    pub feasible: bool,
+    pub limiting_factor: Option<LimitingFactor>,
+    /// Wind- and temperature-adjusted flight energy
+    pub flight_energy_wh: f32,
+    /// Laser link energy over the flight time
+    pub comms_energy_wh: f32,
+    /// Battery energy above the reserve margin
+    pub available_energy_wh: f32,
+}
+
+impl Feasibility {
+    /// Energy left after flight and comms; negative when infeasible
+    pub fn margin_wh(&self) -> f32 {
+        self.available_energy_wh - self.flight_energy_wh - self.comms_energy_wh
+    }
+}
+
+impl MissionPayload {
+    /// Planned flight time from the energy constraints
+    pub fn flight_duration(&self) -> Duration {
+        Duration::from_secs(self.constraints.energy.max_flight_time_minutes as u64 * 60)
+    }
+
+    /// Expected flight energy adjusted for wind drag and hot-weather battery losses
+    pub fn flight_energy_wh(&self, drone: &DroneSpecifications, weather: &WeatherData) -> f32 {
+        let hours = self.flight_duration().as_secs_f32() / 3600.0;
+        let wind_power_w = weather.wind_speed_mps * drone.power_wind_coefficient;
+        let temp_efficiency_loss = if weather.temperature_celsius > 30.0 { 0.1 } else { 0.0 };
+
+        self.constraints.energy.expected_consumption_wh * (1.0 + temp_efficiency_loss) + wind_power_w * hours
+    }
+
+    /// Check whether flight and the laser link together fit in the battery budget.
+    ///
+    /// Comms energy comes from the engine's power budget at its current power
+    /// level, run for the whole planned flight time.
+    pub async fn feasibility(&self, drone: &DroneSpecifications, comms: &LaserEngine, weather: &WeatherData) -> Feasibility {
+        let flight_energy_wh = self.flight_energy_wh(drone, weather);
+        let budget = comms.calculate_power_budget("mission_comms", self.flight_duration().as_secs_f32()).await;
+        let comms_energy_wh = (budget.energy_required_joules / 3600.0) as f32;
+        let available_energy_wh = drone.battery_capacity_wh * (1.0 - self.constraints.energy.reserve_margin_soc);
+
+        let limiting_factor = if flight_energy_wh > available_energy_wh {
+            Some(LimitingFactor::FlightEnergy)
+        } else if flight_energy_wh + comms_energy_wh > available_energy_wh {
+            Some(LimitingFactor::CommsEnergy)
+        } else {
+            None
+        };
+
+        Feasibility {
+            feasible: limiting_factor.is_none(),
+            limiting_factor,
+            flight_energy_wh,
+            comms_energy_wh,
+            available_energy_wh,
+        }
+    }
+}
+
 impl Default for MissionPayload {
     fn default() -> Self {
         Self {
@@ -775,3 +849,78 @@ impl Default for MissionPayload {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::laser::{LaserConfig, ReceptionConfig};
+    use crate::weather::WeatherSource;
+
+    fn windy_weather() -> WeatherData {
+        WeatherData {
+            timestamp: SystemTime::now(),
+            location: GeoCoordinate {
+                latitude: 45.0,
+                longitude: 2.0,
+                altitude_msl: 100.0,
+            },
+            temperature_celsius: 20.0,
+            humidity_percent: 60.0,
+            wind_speed_mps: 6.0,
+            wind_direction_degrees: 270.0,
+            gust_speed_mps: 9.0,
+            visibility_meters: 8000.0,
+            precipitation_type: None,
+            precipitation_rate_mmh: 0.0,
+            pressure_hpa: 1013.0,
+            cloud_cover_percent: 30.0,
+            lightning_probability: 0.0,
+            source: WeatherSource::LocalSensor,
+            forecast_horizon_hours: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_comms_power_tips_marginal_mission_into_infeasible() {
+        let mission = MissionPayload::default();
+        let weather = windy_weather();
+        let comms = LaserEngine::new(LaserConfig::default(), ReceptionConfig::default());
+        let mut profile = comms.get_current_power_profile().await;
+        profile.optimal_power_mw = 1.0;
+        comms.set_power_profile(profile.clone()).await.unwrap();
+
+        let mut drone = DroneSpecifications {
+            max_wind_speed_mps: 10.0,
+            max_speed_mps: 15.0,
+            abort_gust_threshold_mps: 15.0,
+            power_wind_coefficient: 5.0,
+            mass_kg: 2.5,
+            battery_capacity_wh: 0.0,
+            sensor_types: vec!["camera".to_string()],
+        };
+
+        // Size the battery so flight fits with only half the comms energy to spare
+        let flight_wh = mission.flight_energy_wh(&drone, &weather);
+        let comms_wh = (comms.calculate_power_budget("probe", mission.flight_duration().as_secs_f32()).await
+            .energy_required_joules / 3600.0) as f32;
+        assert!(flight_wh > mission.constraints.energy.expected_consumption_wh);
+        drone.battery_capacity_wh = (flight_wh + comms_wh / 2.0) / (1.0 - mission.constraints.energy.reserve_margin_soc);
+
+        let result = mission.feasibility(&drone, &comms, &weather).await;
+        assert!(!result.feasible);
+        assert_eq!(result.limiting_factor, Some(LimitingFactor::CommsEnergy));
+        assert!(result.margin_wh() < 0.0);
+
+        // The same mission with the link idle is feasible
+        profile.optimal_power_mw = 0.0;
+        comms.set_power_profile(profile.clone()).await.unwrap();
+        let idle = mission.feasibility(&drone, &comms, &weather).await;
+        assert!(idle.feasible);
+        assert_eq!(idle.limiting_factor, None);
+
+        // A battery too small for the flight itself blames the flight
+        drone.battery_capacity_wh = flight_wh / 2.0;
+        let grounded = mission.feasibility(&drone, &comms, &weather).await;
+        assert_eq!(grounded.limiting_factor, Some(LimitingFactor::FlightEnergy));
+    }
+}
@@ -87,7 +87,7 @@ impl MissionStation {
             .map_err(|e| MissionTransferError::SerializationError(e.to_string()))?;
 
         // Encrypt mission data
-        let encrypted_data = self.crypto.encrypt_data(&session_key, &mission_data)?;
+        let encrypted_data = self.crypto.seal(&session_key, &mission_data)?;
 
         // Create payload hash for binding
         let payload_hash = CryptoEngine::generate_device_fingerprint(&encrypted_data);
@@ -131,6 +131,9 @@ impl MissionStation {
             public_key: self.crypto.public_key().to_vec(),
             nonce: payload.session_nonce,
             signature: payload.signature.clone(),
+            version: None,
+            version_offer: None,
+            psk: false,
         };
 
         // Create extended payload with mission metadata and encrypted data
@@ -307,6 +310,12 @@ impl MissionDrone {
             data: binding_bytes.to_vec(),
             timestamp: std::time::Instant::now(),
             sequence_id,
+            // Binding frames carry no counter; the station's transmit time
+            // increases with every mission it binds
+            sequence_number: binding_data.timestamp.duration_since(std::time::UNIX_EPOCH)
+                .map(|since_epoch| since_epoch.as_millis() as u64)
+                .unwrap_or(0),
+            preamble_capture: None,
         };
 
         self.validator.receive_channel_data(channel_data).await
@@ -556,7 +565,7 @@ impl MissionDrone {
         // For now, we skip signature verification as the key exchange is implicit in the binding
 
         // Decrypt mission data with derived session key
-        let decrypted_data = self.crypto.decrypt_data(&session_key, &encrypted_payload.encrypted_data)?;
+        let decrypted_data = CryptoEngine::decrypt_data_suite(&session_key, &encrypted_payload.encrypted_data)?;
 
         // Deserialize mission payload
         let mission: MissionPayload = serde_cbor::from_slice(&decrypted_data)
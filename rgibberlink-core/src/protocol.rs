@@ -1,15 +1,74 @@
 use crate::audio::AudioEngine;
-use crate::crypto::CryptoEngine;
+use crate::clock::{Clock, SystemClock};
+use crate::crypto::{CipherSuite, CryptoEngine, CryptoError, SequencedChannelState};
 use crate::visual::{VisualEngine, VisualPayload};
+use crate::session_id::{SessionId, SessionIdError};
 use crate::ultrasonic_beam::{UltrasonicBeamEngine, UltrasonicBeamError};
 use crate::laser::{LaserEngine, LaserError, LaserConfig, ReceptionConfig};
 use crate::channel_validator::{ChannelValidator, ChannelData, ChannelType, ValidationError};
-use crate::fallback::{FallbackManager, FallbackConfig, FallbackStatus, ChannelHealth, ChannelFailure};
+use crate::fallback::{FallbackManager, FallbackConfig, FallbackStatus, ChannelHealth, ChannelFailure, PathConditions, PathSelection};
+use crate::range_detector::RangeDetector;
+use crate::security::{SecurityManager, CrossChannelSignature};
+use crate::config::{GibberConfig, EnvironmentSettings};
+use crate::audit::{AuditEventType, AuditSeverity, SharedAuditSystem, record_security_event};
 use crate::performance_monitor::{PerformanceMonitor, PerformanceMetrics, PerformanceConfig, PerformancePreset, EnvironmentalFactors};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use tokio::time::{Duration, Instant};
 use rand::RngCore;
+use zeroize::Zeroize;
+
+/// Number of state transitions kept in the handshake transcript
+pub const TRANSCRIPT_CAPACITY: usize = 64;
+
+/// Domain label mixed into key confirmation tags
+const KEY_CONFIRMATION_LABEL: &[u8] = b"gibberlink-key-confirmation-v1";
+/// Leading bytes of the handshake ACK
+const ACK_PREFIX: &[u8] = b"ACK";
+/// ACK byte after the prefix naming how the initiator keyed the session
+const ACK_KEYED_ECDH: u8 = 0x00;
+const ACK_KEYED_PSK: u8 = 0x01;
+/// Random bytes in a mutual authentication challenge
+const CHALLENGE_LEN: usize = 32;
+/// Length of the nonce opening every nonce frame
+const NONCE_LEN: usize = 16;
+/// Byte after the nonce naming the frame: a version offer for a full handshake,
+/// or one presenting a session ticket. A bare nonce has neither.
+const NONCE_FRAME_OFFER: u8 = 0x01;
+const NONCE_FRAME_RESUME: u8 = 0x02;
+/// Domain label for the digest of an offered version list
+const VERSION_OFFER_LABEL: &[u8] = b"gibberlink-version-offer-v1";
+
+/// Domain label for session keys derived from a pre-shared key
+const PSK_FALLBACK_LABEL: &[u8] = b"gibberlink-psk-fallback-v1";
+/// HKDF info prefix for session keys resumed from a ticket
+const SESSION_TICKET_LABEL: &[u8] = b"gibberlink-session-ticket-v1";
+/// Domain label for the MACs both sides send over a resumption nonce
+const RESUMPTION_CONFIRMATION_LABEL: &[u8] = b"gibberlink-resumption-confirmation-v1";
+/// Role bytes in resumption MACs, so neither side's tag can be reflected
+const RESUMPTION_INITIATOR: u8 = b'I';
+const RESUMPTION_RESPONDER: u8 = b'R';
+/// Sealed ticket contents: master secret, session id, expiry and version
+const SESSION_TICKET_PLAINTEXT_LEN: usize = 32 + 16 + 8 + 2;
+/// AES-GCM nonce and tag around the sealed master secret
+const SESSION_TICKET_SEAL_OVERHEAD: usize = 12 + 16;
+/// Validity of exported session tickets
+pub const DEFAULT_SESSION_TICKET_LIFETIME: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Domain label mixed into keepalive tags
+const KEEPALIVE_LABEL: &[u8] = b"gibberlink-keepalive-v1";
+/// First byte of every keepalive frame, keeping them apart from user data
+const KEEPALIVE_FRAME_MARKER: u8 = 0x4B;
+const KEEPALIVE_PING: u8 = 0x01;
+const KEEPALIVE_PONG: u8 = 0x02;
+/// Marker, kind, sequence number and HMAC-SHA256 tag
+const KEEPALIVE_FRAME_LEN: usize = 2 + 8 + 32;
+
+/// Idle time after which a keepalive is sent, and how long one may go unanswered
+pub const DEFAULT_KEEPALIVE_INTERVAL: Duration = Duration::from_secs(5);
+/// Consecutive unanswered keepalives after which the peer is declared unresponsive
+pub const DEFAULT_MAX_MISSED_KEEPALIVES: u32 = 3;
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum CommunicationMode {
@@ -26,6 +85,11 @@ pub enum ProtocolState {
     SendingNonce,
     WaitingForQr,
     SendingAck,
+    KeyConfirmation,
+    // Mutual authentication: the initiator awaits the responder's challenge,
+    // the responder awaits the signed answer
+    WaitingForChallenge,
+    WaitingForChallengeResponse,
     Connected,
     // Long-range states
     LongRangeSync,
@@ -47,6 +111,8 @@ pub enum ProtocolState {
     // Fallback states
     FallbackToShortRange,
     Error(String),
+    // Lifetime exceeded; only a fresh handshake leaves this state
+    SessionExpired,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -73,6 +139,478 @@ pub enum ProtocolError {
     LongRangeChannelUnavailable,
     #[error("Fallback to short-range mode")]
     FallbackToShortRange,
+    #[error("Operation cancelled")]
+    Cancelled,
+    #[error("Session key confirmation failed")]
+    KeyConfirmationFailed,
+    #[error("Nonce is unknown, expired, or already used")]
+    StaleNonce,
+    #[error("Message authentication failed")]
+    AuthenticationFailed,
+    #[error("Malformed ciphertext")]
+    MalformedCiphertext,
+    #[error("Handshake cannot be resumed: checkpoint expired or transcript mismatch")]
+    HandshakeResumeRejected,
+    #[error("Cross-channel signature verification failed")]
+    CrossChannelSignatureFailed,
+    #[error("QR payload signature verification failed")]
+    QrSignatureInvalid,
+    #[error("Peer unresponsive after {0} missed keepalives")]
+    PeerUnresponsive(u32),
+    #[error("Keepalive is malformed, replayed or unsolicited")]
+    InvalidKeepalive,
+    #[error("Invalid session ID: {0}")]
+    InvalidSessionId(#[from] SessionIdError),
+    #[error("No unused session ID after {0} attempts")]
+    SessionIdExhausted(u32),
+    #[error("Session lifetime exceeded; a new handshake is required")]
+    SessionExpired,
+    #[error("Invalid peer identity: {0}")]
+    InvalidPeerIdentity(String),
+    #[error("No protocol version in common with the peer")]
+    UnsupportedProtocolVersion,
+    #[error("Session ticket is expired, unknown or was not sealed with our ticket key")]
+    SessionTicketRejected,
+    #[error("Malformed handshake ACK")]
+    MalformedAck,
+    #[error("Laser return is a passive retroreflector, not a receiver")]
+    RetroreflectorDetected,
+    #[error("Initiator failed the mutual authentication challenge")]
+    MutualAuthenticationFailed,
+    #[error("Peer keyed the session differently than the signed QR payload announced")]
+    KeyEstablishmentMismatch,
+    #[error("Responder answered a different version offer than the one we sent")]
+    VersionOfferMismatch,
+    #[error("Message was already received or fell outside the replay window")]
+    MessageReplayed,
+}
+
+impl ProtocolError {
+    /// Map a decryption failure, keeping tampering distinct from malformed input
+    pub(crate) fn from_decrypt(error: CryptoError) -> Self {
+        match error {
+            CryptoError::AuthenticationFailed => ProtocolError::AuthenticationFailed,
+            CryptoError::MalformedCiphertext => ProtocolError::MalformedCiphertext,
+            CryptoError::ReplayDetected(_) => ProtocolError::MessageReplayed,
+            other => ProtocolError::CryptoError(other.to_string()),
+        }
+    }
+}
+
+/// How the session key was agreed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum KeyEstablishment {
+    /// Ephemeral X25519 exchange
+    Ecdh,
+    /// Pre-shared key mixed with the handshake nonce, announced by the
+    /// responder in its signed QR payload
+    PreSharedKey,
+    /// Master secret of an earlier session, resumed from its ticket and mixed
+    /// with the new nonce
+    SessionTicket,
+}
+
+/// Snapshot of the current session for status displays and policy checks
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ConnectionInfo {
+    pub state: ProtocolState,
+    pub mode: CommunicationMode,
+    pub session_id: SessionId,
+    /// `None` until a session key exists
+    pub key_establishment: Option<KeyEstablishment>,
+}
+
+impl ConnectionInfo {
+    /// The session key came from the pre-shared key rather than ECDH
+    pub fn is_psk_mode(&self) -> bool {
+        self.key_establishment == Some(KeyEstablishment::PreSharedKey)
+    }
+
+    /// Recorded traffic stays safe if long-term keys later leak; false in PSK
+    /// mode, where anyone holding the PSK and the nonce can rederive the key
+    pub fn has_forward_secrecy(&self) -> bool {
+        self.key_establishment == Some(KeyEstablishment::Ecdh)
+    }
+}
+
+/// Handshake and session settings of an engine, so engines for further peers
+/// can be built alike
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionPolicy {
+    pub cipher_suite: CipherSuite,
+    pub session_lifetime: Option<Duration>,
+    pub key_confirmation_required: bool,
+    pub mutual_authentication_required: bool,
+    pub supported_versions: Vec<ProtocolVersion>,
+}
+
+/// Degraded-mode keying for peers that cannot run ECDH
+///
+/// Both sides hold the same 32-byte key out of band; the session key is
+/// HKDF-SHA256 over it, salted with the initiator's handshake nonce and bound to
+/// the session id, so each handshake still gets its own key and AES-GCM keeps
+/// authenticating traffic. There is no forward secrecy: the PSK and a recorded
+/// nonce recover the session key.
+///
+/// The mode is never entered because a key exchange went wrong: the responder
+/// announces it in its signed QR payload (`set_psk_mode`), the initiator follows
+/// only if it holds a PSK too, and the ACK names the mode back.
+#[derive(Clone)]
+pub struct PskFallback {
+    psk: [u8; 32],
+}
+
+impl PskFallback {
+    pub fn new(psk: [u8; 32]) -> Self {
+        Self { psk }
+    }
+
+    /// Session key for the handshake that used `nonce` under `session_id`
+    pub fn derive_session_key(&self, nonce: &[u8; 16], session_id: &SessionId) -> [u8; 32] {
+        let mut info = PSK_FALLBACK_LABEL.to_vec();
+        info.extend_from_slice(session_id.as_bytes());
+        let mut key = [0u8; 32];
+        hkdf::Hkdf::<sha2::Sha256>::new(Some(nonce), &self.psk)
+            .expand(&info, &mut key)
+            .expect("32 bytes is within the HKDF output limit");
+        key
+    }
+}
+
+impl std::fmt::Debug for PskFallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PskFallback").finish_non_exhaustive()
+    }
+}
+
+impl Drop for PskFallback {
+    fn drop(&mut self) {
+        self.psk.zeroize();
+    }
+}
+/// Handshake protocol version; versions order by major, then minor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize)]
+pub struct ProtocolVersion {
+    pub major: u8,
+    pub minor: u8,
+}
+
+impl ProtocolVersion {
+    /// The version spoken by peers whose nonce carries no version list
+    pub const V1_0: ProtocolVersion = ProtocolVersion { major: 1, minor: 0 };
+
+    pub const fn new(major: u8, minor: u8) -> Self {
+        Self { major, minor }
+    }
+
+    /// Highest version both lists contain, if any
+    pub fn negotiate(ours: &[ProtocolVersion], theirs: &[ProtocolVersion]) -> Option<ProtocolVersion> {
+        ours.iter().filter(|version| theirs.contains(version)).max().copied()
+    }
+
+    /// Truncated SHA-256 of an offered version list, which the responder signs
+    /// into its QR payload
+    pub fn offer_digest(versions: &[ProtocolVersion]) -> [u8; 16] {
+        use sha2::{Digest, Sha256};
+
+        let mut hasher = Sha256::new();
+        hasher.update(VERSION_OFFER_LABEL);
+        hasher.update([versions.len() as u8]);
+        for version in versions {
+            hasher.update([version.major, version.minor]);
+        }
+        let mut digest = [0u8; 16];
+        digest.copy_from_slice(&hasher.finalize()[..16]);
+        digest
+    }
+}
+
+impl std::fmt::Display for ProtocolVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+/// Resumption ticket for a session keyed by an earlier handshake.
+///
+/// The master secret is sealed under the long-lived ticket key that paired
+/// devices share, together with the session id, expiry and negotiated version,
+/// so none of them can be swapped. Presenting the ticket in the nonce frame lets
+/// both sides skip ECDH; like PSK keying, resumed sessions have no forward
+/// secrecy against the ticket key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionTicket {
+    pub encrypted_master_secret: Vec<u8>,
+    pub session_id: [u8; 16],
+    pub expiry: std::time::SystemTime,
+}
+
+impl SessionTicket {
+    /// Whole seconds since the epoch; tickets are minted on second boundaries
+    fn expiry_secs(&self) -> u64 {
+        self.expiry.duration_since(std::time::UNIX_EPOCH).map_or(0, |age| age.as_secs())
+    }
+
+    /// Wire form: session id, expiry (u64 BE seconds) and the sealed secret
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(16 + 8 + self.encrypted_master_secret.len());
+        bytes.extend_from_slice(&self.session_id);
+        bytes.extend_from_slice(&self.expiry_secs().to_be_bytes());
+        bytes.extend_from_slice(&self.encrypted_master_secret);
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < 16 + 8 + SESSION_TICKET_SEAL_OVERHEAD {
+            return None;
+        }
+        let (session_id, rest) = bytes.split_at(16);
+        let (expiry, encrypted_master_secret) = rest.split_at(8);
+        Some(Self {
+            encrypted_master_secret: encrypted_master_secret.to_vec(),
+            session_id: session_id.try_into().ok()?,
+            // A far-future expiry from the wire must not overflow the clock
+            expiry: std::time::UNIX_EPOCH.checked_add(Duration::from_secs(u64::from_be_bytes(expiry.try_into().ok()?)))?,
+        })
+    }
+}
+
+/// A parsed `HandshakeFrame::Nonce`
+struct NonceFrame {
+    nonce: [u8; NONCE_LEN],
+    versions: Vec<ProtocolVersion>,
+    // Presented ticket and the initiator's MAC over the nonce
+    resume: Option<(SessionTicket, [u8; 32])>,
+    identity_key: Option<[u8; 32]>,
+}
+
+/// A handshake message one engine emits for its peer: the nonce and ACK travel
+/// over audio, the payload bytes over the displayed QR code
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HandshakeFrame {
+    /// The 16-byte nonce, a count-prefixed list of supported versions (two bytes
+    /// each), a session ticket to resume (u16 BE length prefix, padded to even
+    /// length) and, under mutual authentication, the initiator's Ed25519 key.
+    /// Frames without a version list have even length and mean version 1.0
+    Nonce(Vec<u8>),
+    QrPayload(Vec<u8>),
+    /// `"ACK"`, the sender's ECDH public key (u16 BE length prefix) and its key
+    /// confirmation tag when confirmation is required
+    Ack(Vec<u8>),
+    /// The receiver's key confirmation tag, answering the ACK
+    KeyConfirmation(Vec<u8>),
+    /// Mutual authentication challenge from the receiver, sealed under the session key
+    Challenge(Vec<u8>),
+    /// The initiator's Ed25519 signature over `challenge || session_id`
+    ChallengeResponse(Vec<u8>),
+}
+
+/// Called with every `HandshakeFrame` an engine emits
+pub type HandshakeSink = Arc<dyn Fn(HandshakeFrame) + Send + Sync>;
+
+/// One step of the handshake transcript: a state transition, or a failed step
+/// (`from == to`) that left the state unchanged
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TranscriptEntry {
+    pub from: ProtocolState,
+    pub to: ProtocolState,
+    pub timestamp: std::time::SystemTime,
+    /// Handshake step that triggered the entry
+    pub event: String,
+    pub error: Option<String>,
+}
+
+impl std::fmt::Display for TranscriptEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let millis = self.timestamp.duration_since(std::time::UNIX_EPOCH).map(|d| d.as_millis()).unwrap_or(0);
+        write!(f, "{} {}: {:?} -> {:?}", millis, self.event, self.from, self.to)?;
+        if let Some(error) = &self.error {
+            write!(f, " ({})", error)?;
+        }
+        Ok(())
+    }
+}
+
+/// Final long-range ACK: what the sender received on the laser channel and sent on
+/// the ultrasound channel, signed across both channels together with the
+/// sender's ultrasound sequence number
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SignedCoupledAck {
+    pub laser_data: Vec<u8>,
+    pub ultrasound_data: Vec<u8>,
+    /// Per-sender counter the receiver's replay window checks once the
+    /// signature verifies
+    pub sequence_number: u64,
+    pub signature: CrossChannelSignature,
+}
+
+impl SignedCoupledAck {
+    /// Ultrasound side of the signed data: the sync pattern, then the sequence number
+    fn signed_ultrasound_data(ultrasound_data: &[u8], sequence_number: u64) -> Vec<u8> {
+        let mut signed = ultrasound_data.to_vec();
+        signed.extend_from_slice(&sequence_number.to_be_bytes());
+        signed
+    }
+
+    /// Wire form sent over the ultrasound channel
+    pub fn to_bytes(&self) -> Vec<u8> {
+        serde_cbor::to_vec(self).unwrap_or_default()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        serde_cbor::from_slice(bytes).ok()
+    }
+}
+
+/// Progress saved after the last completed handshake phase, so a retry can
+/// resume there instead of redoing the ECDH
+#[derive(Debug, Clone)]
+struct HandshakeCheckpoint {
+    /// State the handshake resumes in
+    phase: ProtocolState,
+    /// Hash of the QR payload the checkpoint was derived from
+    transcript_hash: [u8; 32],
+    created_at: Instant,
+}
+
+/// Lifecycle of handshake nonces: nonces we issued stay outstanding until they are
+/// consumed once or expire, and nonces received from peers are remembered so a
+/// duplicate is rejected
+#[derive(Debug)]
+pub struct NonceRegistry {
+    ttl: Duration,
+    issued: HashMap<[u8; 16], Instant>,
+    consumed: HashMap<[u8; 16], Instant>,
+}
+
+impl NonceRegistry {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            issued: HashMap::new(),
+            consumed: HashMap::new(),
+        }
+    }
+
+    /// Record a nonce we sent, outstanding until consumed or expired
+    pub fn issue(&mut self, nonce: [u8; 16]) {
+        self.prune();
+        self.issued.insert(nonce, Instant::now());
+    }
+
+    /// Consume an outstanding nonce we issued; it cannot be consumed again
+    pub fn consume(&mut self, nonce: &[u8; 16]) -> Result<(), ProtocolError> {
+        self.prune();
+        self.issued.remove(nonce).ok_or(ProtocolError::StaleNonce)?;
+        self.consumed.insert(*nonce, Instant::now());
+        Ok(())
+    }
+
+    /// Accept a nonce issued by a peer, rejecting one already seen
+    pub fn accept_remote(&mut self, nonce: &[u8; 16]) -> Result<(), ProtocolError> {
+        self.prune();
+        if self.consumed.contains_key(nonce) {
+            return Err(ProtocolError::StaleNonce);
+        }
+        self.consumed.insert(*nonce, Instant::now());
+        Ok(())
+    }
+
+    /// Whether `nonce` was issued by us and is still waiting to be consumed
+    pub fn is_outstanding(&self, nonce: &[u8; 16]) -> bool {
+        self.issued.get(nonce).is_some_and(|issued_at| issued_at.elapsed() <= self.ttl)
+    }
+
+    pub fn outstanding_count(&self) -> usize {
+        self.issued.values().filter(|issued_at| issued_at.elapsed() <= self.ttl).count()
+    }
+
+    /// Drop expired outstanding nonces; used nonces are remembered for one more
+    /// lifetime so late duplicates are still caught
+    fn prune(&mut self) {
+        let ttl = self.ttl;
+        self.issued.retain(|_, issued_at| issued_at.elapsed() <= ttl);
+        self.consumed.retain(|_, used_at| used_at.elapsed() <= ttl * 2);
+    }
+}
+
+/// Default number of draws before session id generation gives up
+pub const DEFAULT_SESSION_ID_ATTEMPTS: u32 = 8;
+
+/// Session ids currently in use, so a new session never reuses the id of one
+/// still open. Engines serving several peers share one registry.
+pub struct SessionRegistry {
+    active: HashSet<SessionId>,
+    rng: Box<dyn RngCore + Send>,
+    max_attempts: u32,
+}
+
+impl std::fmt::Debug for SessionRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SessionRegistry")
+            .field("active", &self.active.len())
+            .field("max_attempts", &self.max_attempts)
+            .finish()
+    }
+}
+
+impl Default for SessionRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SessionRegistry {
+    /// Registry drawing ids from the operating system CSPRNG
+    pub fn new() -> Self {
+        Self::with_rng(Box::new(rand::rngs::OsRng))
+    }
+
+    /// Registry drawing ids from `rng`
+    pub fn with_rng(rng: Box<dyn RngCore + Send>) -> Self {
+        Self {
+            active: HashSet::new(),
+            rng,
+            max_attempts: DEFAULT_SESSION_ID_ATTEMPTS,
+        }
+    }
+
+    /// Bound the draws per id; a healthy RNG collides with probability ~n/2^128
+    pub fn set_max_attempts(&mut self, attempts: u32) {
+        self.max_attempts = attempts.max(1);
+    }
+
+    /// Draw an id not held by any active session and mark it active
+    pub fn generate(&mut self) -> Result<SessionId, ProtocolError> {
+        for _ in 0..self.max_attempts {
+            let mut bytes = [0u8; 16];
+            self.rng.fill_bytes(&mut bytes);
+            let session_id = SessionId::new(bytes);
+            if self.active.insert(session_id) {
+                return Ok(session_id);
+            }
+            trace_warn!(session_id = %session_id, "session id collided with an active session; regenerating");
+        }
+        Err(ProtocolError::SessionIdExhausted(self.max_attempts))
+    }
+
+    /// Mark an externally chosen id active; false if it already was
+    pub fn register(&mut self, session_id: SessionId) -> bool {
+        self.active.insert(session_id)
+    }
+
+    /// Reap the id of a closed session; false if it was not active
+    pub fn release(&mut self, session_id: &SessionId) -> bool {
+        self.active.remove(session_id)
+    }
+
+    pub fn is_active(&self, session_id: &SessionId) -> bool {
+        self.active.contains(session_id)
+    }
+
+    pub fn active_count(&self) -> usize {
+        self.active.len()
+    }
 }
 
 pub struct ProtocolEngine {
@@ -84,27 +622,74 @@ pub struct ProtocolEngine {
     ultrasonic_beam: Option<UltrasonicBeamEngine>,
     laser: Option<LaserEngine>,
     channel_validator: Option<ChannelValidator>,
+    // Sequence numbers of the last laser key received and the last coupled ACK sent
+    laser_key_sequence: u64,
+    coupled_ack_sequence: u64,
     fallback_manager: Option<FallbackManager>,
     performance_monitor: Option<PerformanceMonitor>,
-    session_id: [u8; 16],
+    session_id: SessionId,
     peer_public_key: Option<Vec<u8>>,
+    // ECDH public key we sent the peer; the keypair rotates once the secret is derived
+    handshake_public_key: Option<Vec<u8>>,
     shared_secret: Option<[u8; 32]>,
+    key_establishment: Option<KeyEstablishment>,
+    psk_fallback: Option<PskFallback>,
+    // Responder: key answered handshakes from the PSK, and the nonce it salts
+    psk_mode: bool,
+    handshake_nonce: Option<[u8; NONCE_LEN]>,
+    key_confirmation_required: bool,
+    // Mutual authentication: the initiator's Ed25519 key from its nonce, and
+    // the challenge it must sign
+    mutual_authentication_required: bool,
+    peer_identity_key: Option<[u8; 32]>,
+    pending_challenge: Option<[u8; CHALLENGE_LEN]>,
+    // Ed25519 key the responder's QR must be signed with; survives closed sessions
+    pinned_peer_identity: Option<[u8; 32]>,
+    // Versions we offer or accept, ascending, and the one agreed for this session
+    supported_versions: Vec<ProtocolVersion>,
+    negotiated_version: Option<ProtocolVersion>,
+    // Long-lived key sealing session tickets, shared by paired devices
+    session_ticket_key: Option<[u8; 32]>,
+    // Tickets already resumed from, by digest, until they expire
+    spent_tickets: HashMap<[u8; 32], std::time::SystemTime>,
+    // Nonce of a resumed session and our role in its confirmation MACs
+    resumption: Option<([u8; NONCE_LEN], u8)>,
+    // Absolute session lifetime, counted from key establishment
+    clock: Arc<dyn Clock>,
+    session_lifetime: Option<Duration>,
+    session_started_at: Option<std::time::SystemTime>,
+    nonces: Arc<Mutex<NonceRegistry>>,
+    sessions: Arc<Mutex<SessionRegistry>>,
+    checkpoint: Option<HandshakeCheckpoint>,
+    resume_timeout: Duration,
     // Long-range specific fields
     coupled_validation_required: bool,
     timeout_duration: Duration,
     retry_count: u32,
     max_retries: u32,
     last_activity: Instant,
+    // Keepalive for idle sessions
+    keepalive_interval: Duration,
+    max_missed_keepalives: u32,
+    keepalive_sequence: u64,
+    peer_keepalive_sequence: u64,
+    pending_keepalive: Option<(u64, Instant)>,
+    missed_keepalives: u32,
     // Performance monitoring
     performance_enabled: bool,
     last_performance_check: Instant,
     performance_check_interval: Duration,
+    audit_system: Option<SharedAuditSystem>,
+    // Last TRANSCRIPT_CAPACITY transitions, for diagnosing stalled handshakes
+    handshake_transcript: std::sync::Mutex<VecDeque<TranscriptEntry>>,
+    handshake_sink: Option<HandshakeSink>,
 }
 
 impl ProtocolEngine {
     pub fn new() -> Self {
-        let mut session_id = [0u8; 16];
-        rand::thread_rng().fill_bytes(&mut session_id);
+        let mut sessions = SessionRegistry::new();
+        let session_id = SessionId::random();
+        sessions.register(session_id);
 
         // Initialize audio engine
         let mut audio_engine = AudioEngine::new();
@@ -121,22 +706,125 @@ impl ProtocolEngine {
             ultrasonic_beam: None,
             laser: None,
             channel_validator: None,
+            laser_key_sequence: 0,
+            coupled_ack_sequence: 0,
             fallback_manager: None,
             performance_monitor: None,
             session_id,
             peer_public_key: None,
+            handshake_public_key: None,
             shared_secret: None,
+            key_establishment: None,
+            psk_fallback: None,
+            psk_mode: false,
+            handshake_nonce: None,
+            key_confirmation_required: true,
+            mutual_authentication_required: true,
+            peer_identity_key: None,
+            pending_challenge: None,
+            pinned_peer_identity: None,
+            supported_versions: vec![ProtocolVersion::V1_0],
+            negotiated_version: None,
+            session_ticket_key: None,
+            spent_tickets: HashMap::new(),
+            resumption: None,
+            clock: Arc::new(SystemClock),
+            session_lifetime: None,
+            session_started_at: None,
+            nonces: Arc::new(Mutex::new(NonceRegistry::new(Duration::from_secs(30)))),
+            sessions: Arc::new(Mutex::new(sessions)),
+            checkpoint: None,
+            resume_timeout: Duration::from_secs(10),
             coupled_validation_required: true,
             timeout_duration: Duration::from_secs(30),
             retry_count: 0,
             max_retries: 3,
             last_activity: Instant::now(),
+            keepalive_interval: DEFAULT_KEEPALIVE_INTERVAL,
+            max_missed_keepalives: DEFAULT_MAX_MISSED_KEEPALIVES,
+            keepalive_sequence: 0,
+            peer_keepalive_sequence: 0,
+            pending_keepalive: None,
+            missed_keepalives: 0,
             performance_enabled: false,
             last_performance_check: Instant::now(),
             performance_check_interval: Duration::from_millis(500), // Check every 500ms
+            audit_system: None,
+            handshake_transcript: std::sync::Mutex::new(VecDeque::with_capacity(TRANSCRIPT_CAPACITY)),
+            handshake_sink: None,
         }
     }
 
+    /// The most recent state transitions and failed handshake steps, oldest first
+    pub fn get_transcript(&self) -> Vec<TranscriptEntry> {
+        self.handshake_transcript.lock().unwrap().iter().cloned().collect()
+    }
+
+    fn record_transcript(&self, from: &ProtocolState, to: &ProtocolState, event: &str, error: Option<String>) {
+        let mut transcript = self.handshake_transcript.lock().unwrap();
+        if transcript.len() == TRANSCRIPT_CAPACITY {
+            transcript.pop_front();
+        }
+        transcript.push_back(TranscriptEntry {
+            from: from.clone(),
+            to: to.clone(),
+            timestamp: self.clock.now(),
+            event: event.to_string(),
+            error,
+        });
+    }
+
+    /// Move to `next`, recording the transition
+    fn transition(&self, state: &mut ProtocolState, next: ProtocolState, event: &str) {
+        self.record_transcript(state, &next, event, None);
+        *state = next;
+    }
+
+    /// Move to `next` because of `error`, recording both
+    fn fail_transition(&self, state: &mut ProtocolState, next: ProtocolState, event: &str, error: &ProtocolError) {
+        self.record_transcript(state, &next, event, Some(error.to_string()));
+        *state = next;
+    }
+
+    /// Record a handshake step that failed without changing state
+    fn record_failure(&self, state: &ProtocolState, event: &str, error: &dyn std::fmt::Display) {
+        self.record_transcript(state, state, event, Some(error.to_string()));
+    }
+
+    /// Send security-relevant state transitions (e.g. mode downgrades) to a shared audit trail
+    pub fn set_audit_system(&mut self, audit: SharedAuditSystem) {
+        self.audit_system = Some(audit);
+    }
+
+    /// Create a protocol engine with every engine built from `config`.
+    ///
+    /// Engines are constructed but not initialized; hardware comes up on first use
+    /// or through `set_mode`.
+    pub fn from_config(config: &GibberConfig) -> Result<Self, ProtocolError> {
+        let mut engine = Self::new();
+        let cipher_suite = config.security.crypto_algorithms.cipher_suite()
+            .map_err(|e| ProtocolError::CryptoError(e.to_string()))?;
+        engine.crypto.set_cipher_suite(cipher_suite);
+        if config.security.session_timeout_secs > 0 {
+            engine.session_lifetime = Some(Duration::from_secs(config.security.session_timeout_secs));
+        }
+
+        config.laser.validate()?;
+        let mut laser = LaserEngine::new(config.laser.clone(), config.reception.clone());
+        laser.enable_optical_ecc(config.optical_ecc.clone())?;
+        let range_detector = Arc::new(Mutex::new(RangeDetector::with_config(config.ranging.clone())));
+        if config.adaptive_laser {
+            laser.enable_adaptive_mode(range_detector);
+        } else {
+            laser.set_range_detector(range_detector);
+        }
+
+        engine.ultrasonic_beam = Some(UltrasonicBeamEngine::with_config(config.beam.clone())?);
+        engine.laser = Some(laser);
+        engine.channel_validator = Some(ChannelValidator::new());
+        Ok(engine)
+    }
+
     /// Create protocol engine with specific communication mode
     pub fn with_mode(mode: CommunicationMode) -> Self {
         let mut engine = Self::new();
@@ -144,11 +832,16 @@ impl ProtocolEngine {
         engine
     }
 
-    /// Initialize long-range engines if needed
+    /// Initialize long-range engines if needed. Both transmitters lead with the
+    /// validator's preamble so coupled exchanges are timed by correlation
     pub async fn initialize_long_range(&mut self) -> Result<(), ProtocolError> {
         if self.mode == CommunicationMode::LongRange || self.mode == CommunicationMode::Auto {
+            let validator = ChannelValidator::new();
+            let preamble = validator.get_config().preamble_sequence.clone();
+
             // Initialize ultrasonic beam engine
             let mut ultrasonic = UltrasonicBeamEngine::new();
+            ultrasonic.set_sync_preamble(Some(preamble.clone()));
             ultrasonic.initialize().await
                 .map_err(|e| ProtocolError::UltrasonicBeamError(e))?;
             self.ultrasonic_beam = Some(ultrasonic);
@@ -157,12 +850,13 @@ impl ProtocolEngine {
             let laser_config = LaserConfig::default();
             let rx_config = ReceptionConfig::default();
             let mut laser = LaserEngine::new(laser_config, rx_config);
+            laser.set_sync_preamble(Some(preamble));
             laser.initialize().await
                 .map_err(|e| ProtocolError::LaserError(e))?;
             self.laser = Some(laser);
 
             // Initialize channel validator for coupled validation
-            self.channel_validator = Some(ChannelValidator::new());
+            self.channel_validator = Some(validator);
         }
         Ok(())
     }
@@ -187,6 +881,12 @@ impl ProtocolEngine {
 
     /// Set communication mode
     pub async fn set_mode(&mut self, mode: CommunicationMode) -> Result<(), ProtocolError> {
+        if mode == CommunicationMode::ShortRange && self.mode != CommunicationMode::ShortRange {
+            if let Some(audit) = &self.audit_system {
+                record_security_event(audit, AuditEventType::ModeDowngrade, AuditSeverity::Medium, "ProtocolEngine",
+                    "set_mode", Some(format!("{:?} -> {:?}", self.mode, mode))).await;
+            }
+        }
         self.mode = mode;
         if self.mode == CommunicationMode::LongRange {
             self.initialize_long_range().await?;
@@ -205,82 +905,955 @@ impl ProtocolEngine {
         &self.mode
     }
 
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "handshake.initiate", skip_all, err))]
     pub async fn initiate_handshake(&mut self) -> Result<(), ProtocolError> {
+        if matches!(self.get_state().await, ProtocolState::SessionExpired) {
+            // Nothing of the expired session carries over, not even its id
+            self.close_session().await;
+            self.session_id = self.sessions.lock().await.generate()?;
+        }
+
         let mut state = self.state.lock().await;
         if !matches!(*state, ProtocolState::Idle) {
             return Err(ProtocolError::InvalidState);
         }
 
-        *state = ProtocolState::SendingNonce;
+        self.transition(&mut state, ProtocolState::SendingNonce, "initiate_handshake");
 
-        // Generate and send nonce via audio
+        // Generate and send nonce via audio, followed by our identity key when
+        // the responder is to authenticate us
         let nonce = CryptoEngine::generate_nonce();
-        self.audio.send_data(&nonce).await.map_err(|e| ProtocolError::AudioError(e.to_string()))?;
+        self.nonces.lock().await.issue(nonce);
+        let nonce_frame = self.nonce_frame(&nonce, None);
+        self.audio.send_data(&nonce_frame).await.map_err(|e| ProtocolError::AudioError(e.to_string()))?;
+        self.emit(HandshakeFrame::Nonce(nonce_frame));
 
-        *state = ProtocolState::WaitingForQr;
+        self.transition(&mut state, ProtocolState::WaitingForQr, "nonce_sent");
         Ok(())
     }
 
-    pub async fn receive_nonce(&self, nonce: &[u8]) -> Result<String, ProtocolError> {
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "handshake.receive_nonce", skip_all, err))]
+    pub async fn receive_nonce(&mut self, nonce: &[u8]) -> Result<String, ProtocolError> {
+        let payload = self.receive_nonce_payload(nonce).await?;
+        let qr_svg = self.visual.encode_payload(&payload).map_err(|e| ProtocolError::VisualError(e.to_string()))?;
+        Ok(qr_svg)
+    }
+
+    /// Receiver half of the short-range handshake: bind the initiator's nonce to a
+    /// fresh ECDH key in a signed payload to display as a QR code, then await the ACK
+    pub async fn receive_nonce_payload(&mut self, nonce: &[u8]) -> Result<VisualPayload, ProtocolError> {
+        if matches!(self.get_state().await, ProtocolState::SessionExpired) {
+            self.close_session().await;
+        }
+
         let mut state = self.state.lock().await;
         if !matches!(*state, ProtocolState::Idle) {
             return Err(ProtocolError::InvalidState);
         }
 
-        *state = ProtocolState::WaitingForQr;
-
-        // Generate QR payload
-        let payload = VisualPayload {
+        let NonceFrame { nonce, versions: peer_versions, identity_key, .. } = Self::parse_nonce_frame(nonce)?;
+        if self.mutual_authentication_required && identity_key.is_none() {
+            return Err(ProtocolError::InvalidPeerIdentity("initiator sent no identity key".to_string()));
+        }
+        let version = ProtocolVersion::negotiate(&self.supported_versions, &peer_versions)
+            .ok_or(ProtocolError::UnsupportedProtocolVersion)?;
+        self.nonces.lock().await.accept_remote(&nonce)?;
+        self.peer_identity_key = identity_key;
+        self.negotiated_version = Some(version);
+        self.handshake_nonce = Some(nonce);
+
+        // Every displayed QR carries its own ephemeral key
+        self.crypto.regenerate_ecdh_keypair();
+        let mut payload = VisualPayload {
             session_id: self.session_id,
             public_key: self.crypto.public_key().to_vec(),
-            nonce: nonce.try_into().map_err(|_| ProtocolError::CryptoError("Invalid nonce length".to_string()))?,
-            signature: vec![], // Simplified for prototype
+            nonce,
+            signature: Vec::new(),
+            version: Some(version),
+            version_offer: Some(ProtocolVersion::offer_digest(&peer_versions)),
+            psk: self.psk_mode,
         };
+        payload.sign(&self.crypto).map_err(|e| ProtocolError::VisualError(e.to_string()))?;
+        self.handshake_public_key = Some(payload.public_key.clone());
+        if self.handshake_sink.is_some() {
+            let qr_data = self.visual.encode_payload_bytes(&payload).map_err(|e| ProtocolError::VisualError(e.to_string()))?;
+            self.emit(HandshakeFrame::QrPayload(qr_data));
+        }
 
-        let qr_svg = self.visual.encode_payload(&payload).map_err(|e| ProtocolError::VisualError(e.to_string()))?;
-        Ok(qr_svg)
+        // Receiver side of WaitingForQr: our QR is on display until the ACK arrives
+        self.transition(&mut state, ProtocolState::WaitingForQr, "nonce_received");
+        Ok(payload)
     }
 
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "handshake.process_qr", skip_all, err))]
     pub async fn process_qr_payload(&mut self, qr_data: &[u8]) -> Result<(), ProtocolError> {
         let mut state = self.state.lock().await;
-        if !matches!(*state, ProtocolState::WaitingForQr) {
-            return Err(ProtocolError::InvalidState);
-        }
+        // A handshake left in SendingAck by a failed ACK may be resumed by the peer's retry
+        let resuming = match *state {
+            ProtocolState::WaitingForQr => false,
+            ProtocolState::SendingAck => true,
+            _ => return Err(ProtocolError::InvalidState),
+        };
 
         let payload = self.visual.decode_payload(qr_data).map_err(|e| ProtocolError::VisualError(e.to_string()))?;
 
+        // Nothing changes until the payload is signed by the responder we expect
+        let signer = payload.verify_signature().map_err(|_| ProtocolError::QrSignatureInvalid)?;
+        if let Some(pinned) = self.pinned_peer_identity {
+            if !CryptoEngine::constant_time_eq(&pinned, &signer) {
+                let error = ProtocolError::InvalidPeerIdentity("QR signed by an unpinned key".to_string());
+                self.record_failure(&state, "process_qr", &error);
+                return Err(error);
+            }
+        }
+
         // Verify session ID matches
         if payload.session_id != self.session_id {
             return Err(ProtocolError::CryptoError("Session ID mismatch".to_string()));
         }
 
-        // Derive shared secret first, then move the key
-        let shared_secret = self.crypto.derive_shared_secret(&payload.public_key)
-            .map_err(|e| ProtocolError::CryptoError(e.to_string()))?;
+        // The responder picks from the versions we offered, and signs what it
+        // saw of the offer; one that predates negotiation speaks 1.0
+        let version = payload.version.unwrap_or(ProtocolVersion::V1_0);
+        if !self.supported_versions.contains(&version) {
+            return Err(ProtocolError::UnsupportedProtocolVersion);
+        }
+        if payload.version.is_some()
+            && payload.version_offer != Some(ProtocolVersion::offer_digest(&self.supported_versions))
+        {
+            let error = ProtocolError::VersionOfferMismatch;
+            self.record_failure(&state, "process_qr", &error);
+            return Err(error);
+        }
 
-        self.peer_public_key = Some(payload.public_key);
-        self.shared_secret = Some(shared_secret);
+        let transcript_hash = Self::qr_transcript_hash(&payload);
+
+        if resuming {
+            let checkpoint_live = self.checkpoint.as_ref()
+                .map_or(false, |checkpoint| checkpoint.created_at.elapsed() <= self.resume_timeout);
+            let same_transcript = self.checkpoint.as_ref()
+                .map_or(false, |checkpoint| CryptoEngine::constant_time_eq(&checkpoint.transcript_hash, &transcript_hash));
+            if checkpoint_live && !same_transcript {
+                // Another payload, even a signed one, must not tear down the
+                // handshake the checkpoint is waiting to finish
+                let error = ProtocolError::HandshakeResumeRejected;
+                self.record_failure(&state, "resume_handshake", &error);
+                return Err(error);
+            }
+            if !checkpoint_live {
+                // Start over: nothing derived for the old transcript survives
+                self.checkpoint = None;
+                self.peer_public_key = None;
+                self.handshake_public_key = None;
+                if let Some(mut secret) = self.shared_secret.take() {
+                    secret.zeroize();
+                }
+                self.key_establishment = None;
+                self.session_started_at = None;
+                let error = ProtocolError::HandshakeResumeRejected;
+                self.fail_transition(&mut state, ProtocolState::Idle, "resume_handshake", &error);
+                trace_warn!("handshake checkpoint rejected; restarting from scratch");
+                return Err(error);
+            }
+            trace_info!("resuming handshake from QR-processed checkpoint");
+        } else {
+            // The QR must echo a nonce we sent and have not used yet
+            self.nonces.lock().await.consume(&payload.nonce)?;
+
+            // Derivation rotates our keypair, so keep the key the peer must use
+            let local_public_key = self.crypto.public_key().to_vec();
+
+            // Key the way the signed payload announced; a failed exchange is an
+            // error, never a reason to drop to the PSK
+            let (shared_secret, establishment) = if payload.psk {
+                let psk = self.psk_fallback.as_ref().ok_or(ProtocolError::KeyEstablishmentMismatch)?;
+                let secret = psk.derive_session_key(&payload.nonce, &self.session_id);
+                trace_warn!("responder negotiated pre-shared-key keying");
+                if let Some(audit) = &self.audit_system {
+                    record_security_event(audit, AuditEventType::ModeDowngrade, AuditSeverity::High, "ProtocolEngine",
+                        "process_qr_payload", Some("responder negotiated PSK keying".to_string())).await;
+                }
+                (secret, KeyEstablishment::PreSharedKey)
+            } else {
+                let secret = self.crypto.derive_shared_secret(&payload.public_key)
+                    .map_err(|e| ProtocolError::CryptoError(e.to_string()))?;
+                (secret, KeyEstablishment::Ecdh)
+            };
 
-        *state = ProtocolState::SendingAck;
+            self.peer_public_key = Some(payload.public_key);
+            self.handshake_public_key = Some(local_public_key);
+            self.crypto.set_session_key(&shared_secret, true);
+            self.shared_secret = Some(shared_secret);
+            self.key_establishment = Some(establishment);
+            self.session_started_at = Some(self.clock.now());
+
+            self.negotiated_version = Some(version);
+            if self.pinned_peer_identity.is_none() {
+                trace_info!("pinning the responder's identity key on first use");
+                self.pinned_peer_identity = Some(signer);
+            }
+
+            self.transition(&mut state, ProtocolState::SendingAck, "qr_processed");
+            self.checkpoint = Some(HandshakeCheckpoint {
+                phase: ProtocolState::SendingAck,
+                transcript_hash,
+                created_at: Instant::now(),
+            });
+        }
 
-        // Send ACK via audio
-        let ack_data = b"ACK";
-        self.audio.send_data(ack_data).await.map_err(|e| ProtocolError::AudioError(e.to_string()))?;
+        // Send ACK via audio with how we keyed the session and our public key, so
+        // the receiver can derive the same secret, and our key confirmation tag
+        // when required
+        let public_key = self.own_handshake_key();
+        let mut ack_data = ACK_PREFIX.to_vec();
+        ack_data.push(match self.key_establishment {
+            Some(KeyEstablishment::PreSharedKey) => ACK_KEYED_PSK,
+            _ => ACK_KEYED_ECDH,
+        });
+        ack_data.extend_from_slice(&(public_key.len() as u16).to_be_bytes());
+        ack_data.extend_from_slice(public_key);
+        if self.key_confirmation_required {
+            ack_data.extend(self.key_confirmation_tag()?);
+        }
+        self.audio.send_data(&ack_data).await.map_err(|e| ProtocolError::AudioError(e.to_string()))?;
+        self.emit(HandshakeFrame::Ack(ack_data));
 
-        *state = ProtocolState::Connected;
+        self.checkpoint = None;
+        let next = if self.mutual_authentication_required {
+            ProtocolState::WaitingForChallenge
+        } else {
+            self.after_initiator_authentication()
+        };
+        self.transition(&mut state, next, "ack_sent");
         Ok(())
     }
 
-    pub async fn receive_ack(&self) -> Result<(), ProtocolError> {
+    /// Our nonce frame for `nonce`: a version offer, or a ticket presentation
+    /// and its resumption MAC when resuming
+    fn nonce_frame(&self, nonce: &[u8; NONCE_LEN], resume: Option<(&SessionTicket, &[u8; 32])>) -> Vec<u8> {
+        let mut frame = nonce.to_vec();
+        frame.push(if resume.is_some() { NONCE_FRAME_RESUME } else { NONCE_FRAME_OFFER });
+        frame.push(self.supported_versions.len() as u8);
+        for version in &self.supported_versions {
+            frame.extend_from_slice(&[version.major, version.minor]);
+        }
+        if let Some((ticket, tag)) = resume {
+            let ticket = ticket.to_bytes();
+            frame.extend_from_slice(&(ticket.len() as u16).to_be_bytes());
+            frame.extend_from_slice(&ticket);
+            frame.extend_from_slice(tag);
+        }
+        if self.mutual_authentication_required {
+            frame.extend_from_slice(self.crypto.ed25519_public_key());
+        }
+        frame
+    }
+
+    /// Split a nonce frame into the nonce, the initiator's versions, the ticket
+    /// and MAC it presents and its optional identity key
+    fn parse_nonce_frame(frame: &[u8]) -> Result<NonceFrame, ProtocolError> {
+        let invalid = || ProtocolError::CryptoError("Invalid nonce length".to_string());
+        if frame.len() < NONCE_LEN {
+            return Err(invalid());
+        }
+        let (nonce, rest) = frame.split_at(NONCE_LEN);
+        let nonce: [u8; NONCE_LEN] = nonce.try_into().expect("split at nonce length");
+
+        // A bare nonce comes from a peer that predates negotiation
+        let Some((&frame_type, rest)) = rest.split_first() else {
+            return Ok(NonceFrame { nonce, versions: vec![ProtocolVersion::V1_0], resume: None, identity_key: None });
+        };
+        let (&count, rest) = rest.split_first().ok_or_else(invalid)?;
+        if rest.len() < 2 * count as usize {
+            return Err(invalid());
+        }
+        let (list, mut rest) = rest.split_at(2 * count as usize);
+        let versions = list.chunks_exact(2).map(|pair| ProtocolVersion::new(pair[0], pair[1])).collect();
+
+        let mut resume = None;
+        match frame_type {
+            NONCE_FRAME_OFFER => {}
+            NONCE_FRAME_RESUME => {
+                let (ticket_len, body) = rest.split_first_chunk::<2>().ok_or_else(invalid)?;
+                let ticket_len = u16::from_be_bytes(*ticket_len) as usize;
+                if body.len() < ticket_len + 32 {
+                    return Err(invalid());
+                }
+                let ticket = SessionTicket::from_bytes(&body[..ticket_len]).ok_or_else(invalid)?;
+                let tag = body[ticket_len..ticket_len + 32].try_into().expect("32-byte resumption MAC");
+                resume = Some((ticket, tag));
+                rest = &body[ticket_len + 32..];
+            }
+            _ => return Err(invalid()),
+        }
+
+        let identity_key = match rest.len() {
+            0 => None,
+            32 => Some(rest.try_into().expect("32-byte identity key")),
+            _ => return Err(invalid()),
+        };
+        Ok(NonceFrame { nonce, versions, resume, identity_key })
+    }
+
+    /// Versions this engine offers as initiator and accepts as responder, ascending
+    pub fn supported_versions(&self) -> Vec<ProtocolVersion> {
+        self.supported_versions.clone()
+    }
+
+    /// Settings an engine for another peer should share with this one
+    pub fn session_policy(&self) -> SessionPolicy {
+        SessionPolicy {
+            cipher_suite: self.crypto.cipher_suite(),
+            session_lifetime: self.session_lifetime,
+            key_confirmation_required: self.key_confirmation_required,
+            mutual_authentication_required: self.mutual_authentication_required,
+            supported_versions: self.supported_versions.clone(),
+        }
+    }
+
+    /// Adopt settings taken from another engine with `session_policy`
+    pub fn apply_session_policy(&mut self, policy: &SessionPolicy) {
+        self.crypto.set_cipher_suite(policy.cipher_suite);
+        self.session_lifetime = policy.session_lifetime;
+        self.key_confirmation_required = policy.key_confirmation_required;
+        self.mutual_authentication_required = policy.mutual_authentication_required;
+        self.set_supported_versions(policy.supported_versions.clone());
+    }
+
+    /// Replace the supported versions; at most 255 are carried in a nonce frame
+    pub fn set_supported_versions(&mut self, mut versions: Vec<ProtocolVersion>) {
+        versions.sort();
+        versions.dedup();
+        versions.truncate(u8::MAX as usize);
+        self.supported_versions = versions;
+    }
+
+    /// Version agreed for the current handshake, once the nonce (responder) or
+    /// the QR payload (initiator) has been processed
+    pub fn negotiated_version(&self) -> Option<ProtocolVersion> {
+        self.negotiated_version
+    }
+
+    /// Seal tickets with `key` so sessions can be resumed without ECDH (`None`
+    /// disables resumption). Both peers must hold the same key.
+    pub fn set_session_ticket_key(&mut self, key: Option<[u8; 32]>) {
+        self.session_ticket_key = key;
+    }
+
+    /// Ticket for resuming the current session, valid for
+    /// `DEFAULT_SESSION_TICKET_LIFETIME`; `None` without a ticket key or a live
+    /// session key
+    pub fn export_session_ticket(&self) -> Option<SessionTicket> {
+        let ticket_key = self.session_ticket_key?;
+        let master_secret = self.shared_secret?;
+        if self.is_session_expired() {
+            return None;
+        }
+        let version = self.negotiated_version.unwrap_or(ProtocolVersion::V1_0);
+
+        let expiry_secs = (self.clock.now() + DEFAULT_SESSION_TICKET_LIFETIME)
+            .duration_since(std::time::UNIX_EPOCH)
+            .ok()?
+            .as_secs();
+        let mut plaintext = zeroize::Zeroizing::new(Vec::with_capacity(SESSION_TICKET_PLAINTEXT_LEN));
+        plaintext.extend_from_slice(&master_secret);
+        plaintext.extend_from_slice(self.session_id.as_bytes());
+        plaintext.extend_from_slice(&expiry_secs.to_be_bytes());
+        plaintext.extend_from_slice(&[version.major, version.minor]);
+
+        Some(SessionTicket {
+            encrypted_master_secret: CryptoEngine::encrypt_aes_gcm(&ticket_key, &plaintext).ok()?,
+            session_id: *self.session_id.as_bytes(),
+            expiry: std::time::UNIX_EPOCH + Duration::from_secs(expiry_secs),
+        })
+    }
+
+    /// Initiator side of resumption: present `ticket` in a fresh nonce frame with
+    /// a MAC over the new nonce, proving we hold its master secret, and key the
+    /// session from both. The responder answers with its own MAC, which a
+    /// session waits for in `KeyConfirmation` when key confirmation is required.
+    /// False, leaving the engine untouched, when the ticket is expired or does
+    /// not open under our ticket key, when a handshake is under way, or when
+    /// mutual authentication is required, since resumption skips the challenge;
+    /// run `initiate_handshake` instead.
+    pub async fn try_resume(&mut self, ticket: &SessionTicket) -> bool {
+        if matches!(self.get_state().await, ProtocolState::SessionExpired) {
+            self.close_session().await;
+        }
+        if self.mutual_authentication_required || !matches!(self.get_state().await, ProtocolState::Idle) {
+            return false;
+        }
+        let Some((master_secret, version)) = self.open_session_ticket(ticket) else {
+            return false;
+        };
+
+        let nonce = CryptoEngine::generate_nonce();
+        let session_id = SessionId::new(ticket.session_id);
+        let session_key = Self::resumption_key(&master_secret, &session_id, &nonce);
+        let tag = Self::resumption_tag(&session_key, &session_id, &nonce, RESUMPTION_INITIATOR);
+        let nonce_frame = self.nonce_frame(&nonce, Some((ticket, &tag)));
+        if let Err(e) = self.audio.send_data(&nonce_frame).await {
+            trace_warn!(error = %e, "could not send resumption nonce");
+            return false;
+        }
+
+        let next = self.after_initiator_authentication();
+        self.resume_session(session_id, session_key, version, nonce, RESUMPTION_INITIATOR, next).await;
+        self.emit(HandshakeFrame::Nonce(nonce_frame));
+        true
+    }
+
+    /// Responder side of resumption: key the session from the ticket in a nonce
+    /// frame once the initiator's MAC shows it holds the master secret, and
+    /// answer with ours. `Ok(false)` when the frame carries no ticket and a full
+    /// handshake should follow. Tickets are single-use, and a responder that
+    /// requires mutual authentication turns them all down.
+    pub async fn receive_resumption_nonce(&mut self, frame: &[u8]) -> Result<bool, ProtocolError> {
+        let NonceFrame { nonce, resume, .. } = Self::parse_nonce_frame(frame)?;
+        let Some((ticket, initiator_tag)) = resume else {
+            return Ok(false);
+        };
+
+        if matches!(self.get_state().await, ProtocolState::SessionExpired) {
+            self.close_session().await;
+        }
+        if !matches!(self.get_state().await, ProtocolState::Idle) {
+            return Err(ProtocolError::InvalidState);
+        }
+        if self.mutual_authentication_required {
+            return Err(ProtocolError::SessionTicketRejected);
+        }
+        let (master_secret, version) = self.open_session_ticket(&ticket).ok_or(ProtocolError::SessionTicketRejected)?;
+        let now = self.clock.now();
+        self.spent_tickets.retain(|_, expiry| *expiry > now);
+        let ticket_id = Self::ticket_id(&ticket);
+        if self.spent_tickets.contains_key(&ticket_id) {
+            return Err(ProtocolError::SessionTicketRejected);
+        }
+
+        // A captured ticket alone does not resume: the MAC needs the master secret
+        let session_id = SessionId::new(ticket.session_id);
+        let session_key = Self::resumption_key(&master_secret, &session_id, &nonce);
+        let expected = Self::resumption_tag(&session_key, &session_id, &nonce, RESUMPTION_INITIATOR);
+        if !CryptoEngine::constant_time_eq(&expected, &initiator_tag) {
+            let error = ProtocolError::SessionTicketRejected;
+            self.record_failure(&self.get_state().await, "session_resumed", &error);
+            return Err(error);
+        }
+        self.nonces.lock().await.accept_remote(&nonce)?;
+        self.spent_tickets.insert(ticket_id, ticket.expiry);
+
+        let tag = Self::resumption_tag(&session_key, &session_id, &nonce, RESUMPTION_RESPONDER);
+        self.resume_session(session_id, session_key, version, nonce, RESUMPTION_RESPONDER, ProtocolState::Connected).await;
+        self.emit(HandshakeFrame::KeyConfirmation(tag.to_vec()));
+        Ok(true)
+    }
+
+    /// Master secret and version sealed in `ticket`, if it is unexpired, opens
+    /// under our ticket key and its clear fields match the sealed ones
+    fn open_session_ticket(&self, ticket: &SessionTicket) -> Option<(zeroize::Zeroizing<[u8; 32]>, ProtocolVersion)> {
+        let ticket_key = self.session_ticket_key?;
+        if self.clock.now() >= ticket.expiry {
+            return None;
+        }
+        let plaintext = zeroize::Zeroizing::new(CryptoEngine::decrypt_aes_gcm(&ticket_key, &ticket.encrypted_master_secret).ok()?);
+        if plaintext.len() != SESSION_TICKET_PLAINTEXT_LEN
+            || plaintext[32..48] != ticket.session_id
+            || plaintext[48..56] != ticket.expiry_secs().to_be_bytes()
+        {
+            return None;
+        }
+        let version = ProtocolVersion::new(plaintext[56], plaintext[57]);
+        if !self.supported_versions.contains(&version) {
+            return None;
+        }
+
+        let mut master_secret = zeroize::Zeroizing::new([0u8; 32]);
+        master_secret.copy_from_slice(&plaintext[..32]);
+        Some((master_secret, version))
+    }
+
+    /// Session key of a resumption: the ticket's master secret, salted with the
+    /// new nonce
+    fn resumption_key(master_secret: &[u8; 32], session_id: &SessionId, nonce: &[u8; NONCE_LEN]) -> [u8; 32] {
+        let mut info = SESSION_TICKET_LABEL.to_vec();
+        info.extend_from_slice(session_id.as_bytes());
+        let mut session_key = [0u8; 32];
+        hkdf::Hkdf::<sha2::Sha256>::new(Some(&nonce[..]), &master_secret[..])
+            .expand(&info, &mut session_key)
+            .expect("32 bytes is within the HKDF output limit");
+        session_key
+    }
+
+    /// MAC over the resumption nonce by `role`, under the resumed session key
+    fn resumption_tag(session_key: &[u8; 32], session_id: &SessionId, nonce: &[u8; NONCE_LEN], role: u8) -> [u8; 32] {
+        let mut transcript = RESUMPTION_CONFIRMATION_LABEL.to_vec();
+        transcript.extend_from_slice(session_id.as_bytes());
+        transcript.extend_from_slice(nonce);
+        transcript.push(role);
+        CryptoEngine::compute_hmac(session_key, &transcript)
+            .try_into()
+            .expect("HMAC-SHA256 tags are 32 bytes")
+    }
+
+    /// What a spent ticket is remembered by
+    fn ticket_id(ticket: &SessionTicket) -> [u8; 32] {
+        use sha2::{Digest, Sha256};
+
+        Sha256::digest(&ticket.encrypted_master_secret).into()
+    }
+
+    /// Take over the ticket's session under `session_key`, fresh to `nonce`
+    async fn resume_session(
+        &mut self,
+        session_id: SessionId,
+        session_key: [u8; 32],
+        version: ProtocolVersion,
+        nonce: [u8; NONCE_LEN],
+        role: u8,
+        next: ProtocolState,
+    ) {
+        self.set_session_id(session_id).await;
+        self.crypto.set_session_key(&session_key, role == RESUMPTION_INITIATOR);
+        self.shared_secret = Some(session_key);
+        self.key_establishment = Some(KeyEstablishment::SessionTicket);
+        self.session_started_at = Some(self.clock.now());
+        self.negotiated_version = Some(version);
+        self.resumption = Some((nonce, role));
+
+        let mut state = self.state.lock().await;
+        self.transition(&mut state, next, "session_resumed");
+    }
+
+    /// Where the initiator goes once the responder has nothing left to verify
+    fn after_initiator_authentication(&self) -> ProtocolState {
+        if self.key_confirmation_required {
+            ProtocolState::KeyConfirmation
+        } else {
+            ProtocolState::Connected
+        }
+    }
+
+    /// Require (the default) or skip the four-pass handshake: the initiator puts
+    /// its Ed25519 key in the nonce, and after the ACK the responder challenges it
+    /// to sign `challenge || session_id` before either side reaches `Connected`.
+    /// Skipping it leaves the responder unsure who holds the other end of the key.
+    pub fn set_mutual_authentication_required(&mut self, required: bool) {
+        self.mutual_authentication_required = required;
+    }
+
+    pub fn is_mutual_authentication_required(&self) -> bool {
+        self.mutual_authentication_required
+    }
+
+    /// Only accept QR payloads signed by the Ed25519 `key`. Without a pin the
+    /// first responder whose QR completes a handshake is pinned, so pin ahead of
+    /// time (e.g. from a pairing record) to authenticate the first one too.
+    pub fn set_pinned_peer_identity(&mut self, key: Option<[u8; 32]>) {
+        self.pinned_peer_identity = key;
+    }
+
+    pub fn pinned_peer_identity(&self) -> Option<[u8; 32]> {
+        self.pinned_peer_identity
+    }
+
+    /// Our Ed25519 identity key, for the peer to pin
+    pub fn identity_public_key(&self) -> &[u8; 32] {
+        self.crypto.ed25519_public_key()
+    }
+
+    /// Initiator side of the challenge: open it with the session key and answer
+    /// with our identity signature
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "handshake.challenge", skip_all, err))]
+    pub async fn receive_challenge(&mut self, sealed_challenge: &[u8]) -> Result<(), ProtocolError> {
+        let mut state = self.state.lock().await;
+        if !matches!(*state, ProtocolState::WaitingForChallenge) {
+            return Err(ProtocolError::InvalidState);
+        }
+
+        let key = self.shared_secret.ok_or(ProtocolError::CryptoError("No shared secret".to_string()))?;
+        let challenge = match CryptoEngine::decrypt_data_suite(&key, sealed_challenge) {
+            Ok(challenge) => challenge,
+            Err(e) => {
+                let error = ProtocolError::from_decrypt(e);
+                self.record_failure(&state, "challenge", &error);
+                return Err(error);
+            }
+        };
+
+        let signature = self.crypto.sign(&self.challenge_transcript(&challenge));
+        self.emit(HandshakeFrame::ChallengeResponse(signature.to_vec()));
+
+        let next = self.after_initiator_authentication();
+        self.transition(&mut state, next, "challenge_answered");
+        Ok(())
+    }
+
+    /// Responder side of the challenge: the initiator proved its identity key;
+    /// a bad signature wipes the session key
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "handshake.challenge_response", skip_all, err))]
+    pub async fn receive_challenge_response(&mut self, signature: &[u8]) -> Result<(), ProtocolError> {
+        let mut state = self.state.lock().await;
+        if !matches!(*state, ProtocolState::WaitingForChallengeResponse) {
+            return Err(ProtocolError::InvalidState);
+        }
+
+        let verified = match (self.pending_challenge.take(), self.peer_identity_key, <&[u8; 64]>::try_from(signature)) {
+            (Some(challenge), Some(identity_key), Ok(signature)) => {
+                CryptoEngine::verify(&identity_key, &self.challenge_transcript(&challenge), signature)
+            }
+            _ => false,
+        };
+        if !verified {
+            if let Some(mut secret) = self.shared_secret.take() {
+                secret.zeroize();
+            }
+            self.key_establishment = None;
+            self.session_started_at = None;
+            let error = ProtocolError::MutualAuthenticationFailed;
+            self.fail_transition(&mut state, ProtocolState::Error("Mutual authentication failed".to_string()), "challenge_response", &error);
+            trace_warn!("initiator failed the identity challenge; key wiped");
+            return Err(error);
+        }
+
+        self.transition(&mut state, ProtocolState::Connected, "challenge_response");
+        Ok(())
+    }
+
+    /// Bytes the initiator signs to answer `challenge`
+    fn challenge_transcript(&self, challenge: &[u8]) -> Vec<u8> {
+        let mut transcript = challenge.to_vec();
+        transcript.extend_from_slice(self.session_id.as_bytes());
+        transcript
+    }
+
+    /// Hash binding a checkpoint to the QR payload it was derived from
+    fn qr_transcript_hash(payload: &VisualPayload) -> [u8; 32] {
+        use sha2::{Digest, Sha256};
+
+        let mut hasher = Sha256::new();
+        hasher.update(payload.session_id.as_bytes());
+        hasher.update((payload.public_key.len() as u16).to_be_bytes());
+        hasher.update(&payload.public_key);
+        hasher.update(payload.nonce);
+        hasher.finalize().into()
+    }
+
+    /// Phase a failed handshake can currently be resumed from, if any
+    pub fn resumable_phase(&self) -> Option<ProtocolState> {
+        self.checkpoint.as_ref()
+            .filter(|checkpoint| checkpoint.created_at.elapsed() <= self.resume_timeout)
+            .map(|checkpoint| checkpoint.phase.clone())
+    }
+
+    /// How long after a failed phase a retry may still resume the handshake
+    pub fn set_resume_timeout(&mut self, timeout: Duration) {
+        self.resume_timeout = timeout;
+    }
+
+    /// Registry of issued and consumed handshake nonces
+    pub fn nonce_registry(&self) -> Arc<Mutex<NonceRegistry>> {
+        self.nonces.clone()
+    }
+
+    /// Registry of session ids in use
+    pub fn session_registry(&self) -> Arc<Mutex<SessionRegistry>> {
+        self.sessions.clone()
+    }
+
+    /// Share one session id registry between engines serving different peers
+    pub fn set_session_registry(&mut self, registry: Arc<Mutex<SessionRegistry>>) {
+        self.sessions = registry;
+    }
+
+    /// Generate a session id unused by any active session in the registry,
+    /// redrawing on a collision, and adopt it in place of the current one
+    pub async fn new_session_id(&mut self) -> Result<SessionId, ProtocolError> {
+        let session_id = {
+            let mut sessions = self.sessions.lock().await;
+            let session_id = sessions.generate()?;
+            // A closed session's id may be drawn again; keep that entry
+            if session_id != self.session_id {
+                sessions.release(&self.session_id);
+            }
+            session_id
+        };
+        self.session_id = session_id;
+        Ok(session_id)
+    }
+
+    /// Close the current session: wipe its keys, return to idle and reap its id
+    /// from the registry
+    pub async fn close_session(&mut self) {
+        if let Some(mut secret) = self.shared_secret.take() {
+            secret.zeroize();
+        }
+        self.crypto.clear_session_key();
+        self.key_establishment = None;
+        self.session_started_at = None;
+        self.peer_public_key = None;
+        self.handshake_public_key = None;
+        self.peer_identity_key = None;
+        self.pending_challenge = None;
+        self.negotiated_version = None;
+        self.handshake_nonce = None;
+        self.resumption = None;
+        self.checkpoint = None;
+        self.pending_keepalive = None;
+        self.missed_keepalives = 0;
+        self.sessions.lock().await.release(&self.session_id);
+        let mut state = self.state.lock().await;
+        self.transition(&mut state, ProtocolState::Idle, "close_session");
+    }
+
+    /// Hold `psk` for responders that announce PSK keying (`None` also turns
+    /// off our own PSK mode)
+    pub fn set_psk_fallback(&mut self, psk: Option<PskFallback>) {
+        if psk.is_none() {
+            self.psk_mode = false;
+        }
+        self.psk_fallback = psk;
+    }
+
+    pub fn has_psk_fallback(&self) -> bool {
+        self.psk_fallback.is_some()
+    }
+
+    /// As responder, key handshakes from the configured PSK instead of ECDH,
+    /// announced in the signed QR payload; fails without a PSK
+    pub fn set_psk_mode(&mut self, enabled: bool) -> Result<(), ProtocolError> {
+        if enabled && self.psk_fallback.is_none() {
+            return Err(ProtocolError::CryptoError("No pre-shared key configured".to_string()));
+        }
+        self.psk_mode = enabled;
+        Ok(())
+    }
+
+    pub fn is_psk_mode(&self) -> bool {
+        self.psk_mode
+    }
+
+    /// Replace the wall clock that session lifetimes are measured against
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    /// Require a full re-handshake once a session has been keyed for `lifetime`
+    /// (`None` lets sessions live until closed)
+    pub fn set_session_lifetime(&mut self, lifetime: Option<Duration>) {
+        self.session_lifetime = lifetime;
+    }
+
+    pub fn session_lifetime(&self) -> Option<Duration> {
+        self.session_lifetime
+    }
+
+    /// Whether the current session key has outlived the configured lifetime
+    pub fn is_session_expired(&self) -> bool {
+        match (self.session_lifetime, self.session_started_at) {
+            (Some(lifetime), Some(started_at)) => self.clock.now()
+                .duration_since(started_at)
+                .map_or(false, |age| age >= lifetime),
+            _ => false,
+        }
+    }
+
+    /// State, session id and keying of the current session
+    pub async fn connection_info(&self) -> ConnectionInfo {
+        ConnectionInfo {
+            state: self.get_state().await,
+            mode: self.mode.clone(),
+            session_id: self.session_id,
+            key_establishment: self.shared_secret.and(self.key_establishment),
+        }
+    }
+
+    /// Require (or skip) the key confirmation round after ECDH
+    pub fn set_key_confirmation_required(&mut self, required: bool) {
+        self.key_confirmation_required = required;
+    }
+
+    pub fn is_key_confirmation_required(&self) -> bool {
+        self.key_confirmation_required
+    }
+
+    /// HMAC over the handshake transcript under the derived key, proving to the
+    /// peer that we hold the same session key
+    pub fn key_confirmation_tag(&self) -> Result<Vec<u8>, ProtocolError> {
+        let peer_key = self.peer_public_key.as_ref().ok_or(ProtocolError::InvalidState)?;
+        self.transcript_tag(self.own_handshake_key(), peer_key)
+    }
+
+    /// The ECDH public key the peer saw from us during this handshake
+    fn own_handshake_key(&self) -> &[u8] {
+        self.handshake_public_key.as_deref().unwrap_or(self.crypto.public_key())
+    }
+
+    /// Whether `peer_tag` is the peer's transcript tag under our session key, or
+    /// its resumption MAC when the session was resumed from a ticket
+    fn peer_tag_matches(&self, peer_tag: &[u8]) -> bool {
+        if let (Some(KeyEstablishment::SessionTicket), Some((nonce, role)), Some(key)) =
+            (self.key_establishment, self.resumption, self.shared_secret.as_ref())
+        {
+            let peer_role = if role == RESUMPTION_INITIATOR { RESUMPTION_RESPONDER } else { RESUMPTION_INITIATOR };
+            let expected = Self::resumption_tag(key, &self.session_id, &nonce, peer_role);
+            return CryptoEngine::constant_time_eq(&expected, peer_tag);
+        }
+        match self.peer_public_key.as_ref() {
+            Some(peer_key) => self.transcript_tag(peer_key, self.own_handshake_key())
+                .map(|expected| CryptoEngine::constant_time_eq(&expected, peer_tag))
+                .unwrap_or(false),
+            None => false,
+        }
+    }
+
+    /// Verify the peer's key confirmation tag; a mismatch wipes the session key
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "handshake.key_confirmation", skip_all, err))]
+    pub async fn confirm_peer_key(&mut self, peer_tag: &[u8]) -> Result<(), ProtocolError> {
+        let mut state = self.state.lock().await;
+        if !matches!(*state, ProtocolState::KeyConfirmation | ProtocolState::Connected) {
+            return Err(ProtocolError::InvalidState);
+        }
+
+        if !self.peer_tag_matches(peer_tag) {
+            if let Some(mut secret) = self.shared_secret.take() {
+                secret.zeroize();
+            }
+            self.key_establishment = None;
+            self.session_started_at = None;
+            let error = ProtocolError::KeyConfirmationFailed;
+            self.fail_transition(&mut state, ProtocolState::Error("Key confirmation failed".to_string()), "key_confirmation", &error);
+            trace_warn!("session key confirmation failed; key wiped");
+            return Err(error);
+        }
+
+        self.transition(&mut state, ProtocolState::SecureChannelEstablished, "key_confirmation");
+        trace_info!("secure channel established");
+        Ok(())
+    }
+
+    /// Transcript tag as sent by `sender_key` to `receiver_key`
+    fn transcript_tag(&self, sender_key: &[u8], receiver_key: &[u8]) -> Result<Vec<u8>, ProtocolError> {
+        let key = self.shared_secret.ok_or(ProtocolError::CryptoError("No shared secret".to_string()))?;
+
+        let mut transcript = KEY_CONFIRMATION_LABEL.to_vec();
+        transcript.extend_from_slice(self.session_id.as_bytes());
+        transcript.push((self.key_establishment == Some(KeyEstablishment::PreSharedKey)) as u8);
+        transcript.extend_from_slice(&(sender_key.len() as u16).to_be_bytes());
+        transcript.extend_from_slice(sender_key);
+        transcript.extend_from_slice(&(receiver_key.len() as u16).to_be_bytes());
+        transcript.extend_from_slice(receiver_key);
+
+        Ok(CryptoEngine::compute_hmac(&key, &transcript))
+    }
+
+    /// Receiver side of the ACK: derive the session key from the initiator's
+    /// public key, check its confirmation tag and answer with our own
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "handshake.receive_ack_frame", skip_all, err))]
+    pub async fn receive_ack_frame(&mut self, ack: &[u8]) -> Result<(), ProtocolError> {
         let mut state = self.state.lock().await;
         if !matches!(*state, ProtocolState::WaitingForQr) {
             return Err(ProtocolError::InvalidState);
         }
 
-        *state = ProtocolState::Connected;
+        let body = ack.strip_prefix(ACK_PREFIX).ok_or(ProtocolError::MalformedAck)?;
+        let (&keyed, body) = body.split_first().ok_or(ProtocolError::MalformedAck)?;
+        let (key_len, body) = body.split_first_chunk::<2>().ok_or(ProtocolError::MalformedAck)?;
+        let key_len = u16::from_be_bytes(*key_len) as usize;
+        if body.len() < key_len {
+            return Err(ProtocolError::MalformedAck);
+        }
+        let (peer_key, peer_tag) = body.split_at(key_len);
+
+        // The initiator must have keyed the way our signed QR announced
+        let expected = if self.psk_mode { ACK_KEYED_PSK } else { ACK_KEYED_ECDH };
+        if keyed != expected {
+            let error = ProtocolError::KeyEstablishmentMismatch;
+            self.record_failure(&state, "ack_received", &error);
+            return Err(error);
+        }
+        let (shared_secret, establishment) = match (&self.psk_fallback, self.handshake_nonce) {
+            (Some(psk), Some(nonce)) if self.psk_mode => {
+                (psk.derive_session_key(&nonce, &self.session_id), KeyEstablishment::PreSharedKey)
+            }
+            _ if self.psk_mode => return Err(ProtocolError::InvalidState),
+            _ => {
+                let secret = self.crypto.derive_shared_secret(peer_key)
+                    .map_err(|e| ProtocolError::CryptoError(e.to_string()))?;
+                (secret, KeyEstablishment::Ecdh)
+            }
+        };
+        self.peer_public_key = Some(peer_key.to_vec());
+        self.crypto.set_session_key(&shared_secret, false);
+        self.shared_secret = Some(shared_secret);
+        self.key_establishment = Some(establishment);
+        self.session_started_at = Some(self.clock.now());
+
+        if self.key_confirmation_required || !peer_tag.is_empty() {
+            if !self.peer_tag_matches(peer_tag) {
+                if let Some(mut secret) = self.shared_secret.take() {
+                    secret.zeroize();
+                }
+                self.key_establishment = None;
+                self.session_started_at = None;
+                let error = ProtocolError::KeyConfirmationFailed;
+                self.fail_transition(&mut state, ProtocolState::Error("Key confirmation failed".to_string()), "ack_received", &error);
+                trace_warn!("initiator key confirmation failed; key wiped");
+                return Err(error);
+            }
+        }
+
+        // The challenge goes first: the initiator only takes our tag once it
+        // has left WaitingForChallenge
+        if self.mutual_authentication_required {
+            let mut challenge = [0u8; CHALLENGE_LEN];
+            challenge.copy_from_slice(&CryptoEngine::generate_secure_random_bytes(CHALLENGE_LEN));
+            let sealed = self.crypto.seal(&shared_secret, &challenge)
+                .map_err(|e| ProtocolError::CryptoError(e.to_string()))?;
+            self.pending_challenge = Some(challenge);
+            self.emit(HandshakeFrame::Challenge(sealed));
+        }
+        if self.key_confirmation_required || !peer_tag.is_empty() {
+            self.emit(HandshakeFrame::KeyConfirmation(self.key_confirmation_tag()?));
+        }
+
+        let next = if self.mutual_authentication_required {
+            ProtocolState::WaitingForChallengeResponse
+        } else {
+            ProtocolState::Connected
+        };
+        self.transition(&mut state, next, "ack_received");
         Ok(())
     }
 
+    /// Route a frame from the peer to the handshake step that consumes it
+    pub async fn handle_handshake_frame(&mut self, frame: HandshakeFrame) -> Result<(), ProtocolError> {
+        match frame {
+            HandshakeFrame::Nonce(nonce) => {
+                if self.receive_resumption_nonce(&nonce).await? {
+                    return Ok(());
+                }
+                self.receive_nonce_payload(&nonce).await.map(|_| ())
+            }
+            HandshakeFrame::QrPayload(qr_data) => self.process_qr_payload(&qr_data).await,
+            HandshakeFrame::Ack(ack) => self.receive_ack_frame(&ack).await,
+            HandshakeFrame::KeyConfirmation(tag) => self.confirm_peer_key(&tag).await,
+            HandshakeFrame::Challenge(challenge) => self.receive_challenge(&challenge).await,
+            HandshakeFrame::ChallengeResponse(signature) => self.receive_challenge_response(&signature).await,
+        }
+    }
+
+    /// Deliver every frame this engine emits to `sink` (`None` stops delivery)
+    pub fn set_handshake_sink(&mut self, sink: Option<HandshakeSink>) {
+        self.handshake_sink = sink;
+    }
+
+    fn emit(&self, frame: HandshakeFrame) {
+        if let Some(sink) = &self.handshake_sink {
+            sink(frame);
+        }
+    }
+
     pub async fn get_state(&self) -> ProtocolState {
         self.state.lock().await.clone()
     }
@@ -290,38 +1863,106 @@ impl ProtocolEngine {
     }
 
     /// Get session ID (for fallback manager)
-    pub fn get_session_id(&self) -> &[u8; 16] {
+    pub fn get_session_id(&self) -> &SessionId {
         &self.session_id
     }
 
+    /// Get our own public key as sent in the QR payload
+    pub fn get_local_public_key(&self) -> &[u8] {
+        self.crypto.public_key()
+    }
+
     /// Get peer public key (for fallback manager)
     pub fn get_peer_public_key(&self) -> Option<&Vec<u8>> {
         self.peer_public_key.as_ref()
     }
 
-    /// Set session ID (for fallback restoration)
-    pub fn set_session_id(&mut self, session_id: [u8; 16]) {
-        self.session_id = session_id;
+    /// Set session ID (for fallback restoration), moving its registry entry
+    /// from the previous id
+    pub async fn set_session_id(&mut self, session_id: SessionId) {
+        {
+            let mut sessions = self.sessions.lock().await;
+            sessions.release(&self.session_id);
+            sessions.register(session_id);
+        }
+        self.session_id = session_id;
+    }
+
+    /// Set shared secret (for fallback restoration).
+    ///
+    /// A new secret restarts the sequenced message channel. Without a handshake
+    /// to assign roles, the side with the lower handshake key takes the
+    /// initiator's direction, so set the peer key first. Re-setting the current
+    /// secret keeps the channel and its replay window.
+    pub fn set_shared_secret(&mut self, secret: Option<[u8; 32]>) {
+        match secret {
+            None => {
+                self.key_establishment = None;
+                self.session_started_at = None;
+                self.crypto.clear_session_key();
+            }
+            Some(key) => {
+                if self.session_started_at.is_none() {
+                    self.session_started_at = Some(self.clock.now());
+                }
+                if self.shared_secret != Some(key) {
+                    let initiator = self.peer_public_key.as_deref()
+                        .is_none_or(|peer_key| self.own_handshake_key() < peer_key);
+                    self.crypto.set_session_key(&key, initiator);
+                }
+            }
+        }
+        self.shared_secret = secret;
+    }
+
+    /// Set peer public key (for fallback restoration)
+    pub fn set_peer_public_key(&mut self, key: Option<Vec<u8>>) {
+        self.peer_public_key = key;
+    }
+
+    /// Set protocol state (for fallback restoration)
+    pub async fn set_state(&self, new_state: ProtocolState) {
+        let mut state = self.state.lock().await;
+        self.transition(&mut state, new_state, "set_state");
+    }
+
+    /// Set communication mode (for fallback restoration)
+    pub fn set_communication_mode(&mut self, mode: CommunicationMode) {
+        self.mode = mode;
+    }
+
+    /// Last keepalive sequence numbers sent and accepted from the peer (for session snapshots)
+    pub fn get_keepalive_sequences(&self) -> (u64, u64) {
+        (self.keepalive_sequence, self.peer_keepalive_sequence)
+    }
+
+    /// Resume keepalive numbering where a snapshot left off, so old pings stay replays
+    pub fn set_keepalive_sequences(&mut self, sequence: u64, peer_sequence: u64) {
+        self.keepalive_sequence = sequence;
+        self.peer_keepalive_sequence = peer_sequence;
     }
 
-    /// Set shared secret (for fallback restoration)
-    pub fn set_shared_secret(&mut self, secret: Option<[u8; 32]>) {
-        self.shared_secret = secret;
+    /// Direction and counters of the session message channel (for session snapshots)
+    pub fn get_message_channel_state(&self) -> Option<SequencedChannelState> {
+        self.crypto.sequenced_state()
     }
 
-    /// Set peer public key (for fallback restoration)
-    pub fn set_peer_public_key(&mut self, key: Option<Vec<u8>>) {
-        self.peer_public_key = key;
+    /// Resume the message channel where a snapshot left off, so messages opened
+    /// before it stay replays. The shared secret must already be set.
+    pub fn set_message_channel_state(&mut self, state: SequencedChannelState) -> Result<(), ProtocolError> {
+        let key = self.shared_secret.ok_or(ProtocolError::InvalidState)?;
+        self.crypto.restore_sequenced_state(&key, state);
+        Ok(())
     }
 
-    /// Set protocol state (for fallback restoration)
-    pub async fn set_state(&self, new_state: ProtocolState) {
-        *self.state.lock().await = new_state;
+    /// When the current session key was established (for session snapshots)
+    pub fn get_session_started_at(&self) -> Option<std::time::SystemTime> {
+        self.session_started_at
     }
 
-    /// Set communication mode (for fallback restoration)
-    pub fn set_communication_mode(&mut self, mode: CommunicationMode) {
-        self.mode = mode;
+    /// Carry over a restored session's start time, keeping its original lifetime
+    pub fn set_session_started_at(&mut self, started_at: Option<std::time::SystemTime>) {
+        self.session_started_at = started_at;
     }
 
     /// Enable fallback management with custom configuration
@@ -379,6 +2020,17 @@ impl ProtocolEngine {
         Ok(())
     }
 
+    /// Send `data` on the laser and ultrasound paths the fallback `PathPolicy`
+    /// selects; `None` without fallback management, which owns the engines
+    pub async fn transmit_on_selected_paths(&self, data: &[u8], conditions: &PathConditions) -> Result<Option<PathSelection>, ProtocolError> {
+        if let Some(fallback) = &self.fallback_manager {
+            let selection = fallback.transmit(data, conditions).await
+                .map_err(|e| ProtocolError::CryptoError(format!("Path transmission failed: {:?}", e)))?;
+            return Ok(Some(selection));
+        }
+        Ok(None)
+    }
+
     /// Check if fallback is currently active
     pub async fn is_fallback_active(&self) -> bool {
         if let Some(fallback) = &self.fallback_manager {
@@ -389,6 +2041,8 @@ impl ProtocolEngine {
     }
 
     /// Initiate optimized long-range handshake (initiator side) - target <500ms
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "handshake.long_range_sync", skip_all, err))]
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
     pub async fn initiate_long_range_handshake(&mut self) -> Result<(), ProtocolError> {
         let handshake_start = Instant::now();
         let mut state = self.state.lock().await;
@@ -401,8 +2055,9 @@ impl ProtocolEngine {
             return Err(ProtocolError::InvalidState);
         }
 
-        *state = ProtocolState::LongRangeSync;
+        self.transition(&mut state, ProtocolState::LongRangeSync, "initiate_long_range_handshake");
         self.last_activity = Instant::now();
+        self.reset_coupling().await;
 
         // OPTIMIZATION: Fast sequential sync with pre-computed data
         let _nonce = CryptoEngine::generate_nonce();
@@ -411,7 +2066,7 @@ impl ProtocolEngine {
         // Phase 1: Fast ultrasonic sync pulse (optimized for speed)
         if let Some(ultrasonic) = &self.ultrasonic_beam {
             // Use pre-optimized sync pattern for <50ms transmission
-            ultrasonic.transmit_sync_pulse(&session_id).await
+            ultrasonic.transmit_sync_pulse(session_id.as_bytes()).await
                 .map_err(|e| ProtocolError::UltrasonicBeamError(e))?;
         }
 
@@ -420,27 +2075,28 @@ impl ProtocolEngine {
         if let Some(laser) = &mut self.laser {
             let public_key = self.crypto.public_key();
             laser.transmit_data(public_key).await
-                .map_err(|e| ProtocolError::LaserError(e))?;
+                .map_err(|e| {
+                    self.record_failure(&state, "send_public_key", &e);
+                    ProtocolError::LaserError(e)
+                })?;
         }
 
-        *state = ProtocolState::LongRangeKeyExchange;
+        self.transition(&mut state, ProtocolState::LongRangeKeyExchange, "sync_sent");
 
         // Log timing for optimization
-        let sync_time = handshake_start.elapsed().as_millis() as f64;
         if self.performance_enabled {
-            // Would log to performance monitor
-            println!("Fast sync completed in {}ms", sync_time);
+            trace_info!(sync_time_ms = handshake_start.elapsed().as_millis() as u64, "fast sync completed");
         }
 
         Ok(())
     }
 
     /// Create optimized sync packet for fast handshake
-    fn create_fast_sync_packet(&self, nonce: &[u8], session_id: &[u8; 16]) -> Vec<u8> {
+    fn create_fast_sync_packet(&self, nonce: &[u8], session_id: &SessionId) -> Vec<u8> {
         // Compress sync data for faster transmission
         // Include: session_id (16B) + nonce (32B) + timestamp (8B) = 56B total
         let mut packet = Vec::with_capacity(64);
-        packet.extend_from_slice(session_id);
+        packet.extend_from_slice(session_id.as_bytes());
         packet.extend_from_slice(nonce);
         packet.extend_from_slice(&self.last_activity.elapsed().as_millis().to_le_bytes());
 
@@ -452,22 +2108,33 @@ impl ProtocolEngine {
     }
 
     /// Receive long-range sync pulse (receiver side)
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "handshake.receive_long_range_sync", skip_all, err))]
     pub async fn receive_long_range_sync(&mut self, sync_pattern: &[u8]) -> Result<(), ProtocolError> {
         let mut state = self.state.lock().await;
         if !matches!(*state, ProtocolState::Idle) {
             return Err(ProtocolError::InvalidState);
         }
 
-        // Verify sync pattern matches session ID
-        if sync_pattern != self.session_id {
+        // Verify sync pattern matches session ID; a pattern of the wrong length is
+        // malformed rather than merely someone else's session
+        if SessionId::from_slice(sync_pattern)? != self.session_id {
             return Err(ProtocolError::CryptoError("Invalid sync pattern".to_string()));
         }
 
-        *state = ProtocolState::LongRangeKeyExchange;
+        self.transition(&mut state, ProtocolState::LongRangeKeyExchange, "sync_received");
         self.last_activity = Instant::now();
+        self.reset_coupling().await;
         Ok(())
     }
 
+    /// Start a handshake with fresh replay windows: sequence numbers only have
+    /// to be unique within one handshake
+    async fn reset_coupling(&self) {
+        if let Some(validator) = &self.channel_validator {
+            validator.reset().await;
+        }
+    }
+
     /// Send public key via laser (receiver side)
     pub async fn send_public_key_via_laser(&mut self) -> Result<(), ProtocolError> {
         let state = self.state.lock().await;
@@ -478,7 +2145,12 @@ impl ProtocolEngine {
         if let Some(laser) = &mut self.laser {
             let public_key = self.crypto.public_key();
             laser.transmit_data(public_key).await
-                .map_err(|e| ProtocolError::LaserError(e))?;
+                .map_err(|e| {
+                    self.record_failure(&state, "send_public_key", &e);
+                    ProtocolError::LaserError(e)
+                })?;
+            // The signed ACK must cover this key even if ours rotates afterwards
+            self.handshake_public_key = Some(public_key.to_vec());
         } else {
             return Err(ProtocolError::LongRangeChannelUnavailable);
         }
@@ -495,19 +2167,25 @@ impl ProtocolEngine {
 
         if let Some(laser) = &mut self.laser {
             let public_key = laser.receive_data(timeout_ms).await
-                .map_err(|e| ProtocolError::LaserError(e))?;
+                .map_err(|e| {
+                    self.record_failure(&state, "receive_public_key", &e);
+                    ProtocolError::LaserError(e)
+                })?;
             Ok(public_key)
         } else {
             Err(ProtocolError::LongRangeChannelUnavailable)
         }
     }
 
-    /// Perform coupled channel validation and send ACK
-    pub async fn perform_coupled_validation(&mut self, laser_public_key: &[u8]) -> Result<(), ProtocolError> {
+    /// Perform coupled channel validation and send the ACK, signed across both
+    /// channels with `security`
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "handshake.coupled_validation", skip_all, err))]
+    pub async fn perform_coupled_validation(&mut self, laser_public_key: &[u8], security: &SecurityManager) -> Result<(), ProtocolError> {
         let mut state = self.state.lock().await;
         if !matches!(*state, ProtocolState::LongRangeKeyExchange) {
             return Err(ProtocolError::InvalidState);
         }
+        self.reject_retroreflector(&state, "coupled_validation").await?;
 
         // Store peer public key
         self.peer_public_key = Some(laser_public_key.to_vec());
@@ -515,9 +2193,15 @@ impl ProtocolEngine {
         // Derive shared secret
         let shared_secret = self.crypto.derive_shared_secret(laser_public_key)
             .map_err(|e| ProtocolError::CryptoError(e.to_string()))?;
+        self.crypto.set_session_key(&shared_secret, true);
         self.shared_secret = Some(shared_secret);
+        self.key_establishment = Some(KeyEstablishment::Ecdh);
+        self.session_started_at = Some(self.clock.now());
 
-        // Use ChannelValidator for coupled validation if available
+        // Use ChannelValidator for coupled validation if available. Each laser
+        // key received this handshake takes the next sequence number, so a retry
+        // is not mistaken for a replay; the signed ACK then binds the key.
+        self.laser_key_sequence += 1;
         if let Some(validator) = &self.channel_validator {
             // Create channel data for laser reception
             let laser_data = ChannelData {
@@ -525,17 +2209,24 @@ impl ProtocolEngine {
                 data: laser_public_key.to_vec(),
                 timestamp: Instant::now(),
                 sequence_id: 1, // Would be properly sequenced in real implementation
+                sequence_number: self.laser_key_sequence,
+                preamble_capture: None,
             };
 
             // Receive laser data into validator
             validator.receive_channel_data(laser_data).await
-                .map_err(|_| ProtocolError::CoupledChannelValidationFailed)?;
+                .map_err(|e| {
+                    self.record_failure(&state, "coupled_validation", &e);
+                    ProtocolError::CoupledChannelValidationFailed
+                })?;
 
             // Check if validation is complete
             if validator.is_validated().await {
-                *state = ProtocolState::LongRangeAuth;
+                self.transition(&mut state, ProtocolState::LongRangeAuth, "coupled_validation");
             } else {
-                return Err(ProtocolError::CoupledChannelValidationFailed);
+                let error = ProtocolError::CoupledChannelValidationFailed;
+                self.record_failure(&state, "coupled_validation", &error);
+                return Err(error);
             }
         } else if self.coupled_validation_required {
             // Fallback to basic presence detection if no validator
@@ -544,55 +2235,151 @@ impl ProtocolEngine {
                     .map_err(|e| ProtocolError::UltrasonicBeamError(e))?;
 
                 if !presence_detected {
-                    return Err(ProtocolError::CoupledChannelValidationFailed);
+                    let error = ProtocolError::CoupledChannelValidationFailed;
+                    self.record_failure(&state, "presence_detection", &error);
+                    return Err(error);
                 }
             }
-            *state = ProtocolState::LongRangeAuth;
+            self.transition(&mut state, ProtocolState::LongRangeAuth, "presence_detection");
         }
 
-        // Send ACK via ultrasonic beam (coupled with laser validation)
+        // Send the signed ACK via ultrasonic beam (coupled with laser validation)
         if let Some(ultrasonic) = &self.ultrasonic_beam {
-            let ack_data = b"LONG_RANGE_ACK";
-            ultrasonic.transmit_control_data(ack_data, 1).await
+            let sequence_number = self.coupled_ack_sequence + 1;
+            let ack = self.coupled_ack(security, sequence_number).await?;
+            self.coupled_ack_sequence = sequence_number;
+            ultrasonic.transmit_control_data(&ack.to_bytes(), 1).await
                 .map_err(|e| ProtocolError::UltrasonicBeamError(e))?;
         }
 
-        *state = ProtocolState::LongRangeConnected;
+        self.transition(&mut state, ProtocolState::LongRangeConnected, "coupled_ack_sent");
         self.last_activity = Instant::now();
         Ok(())
     }
 
-    /// Receive coupled ACK (receiver side)
-    pub async fn receive_coupled_ack(&mut self, ack_data: &[u8], sequence_id: u64) -> Result<(), ProtocolError> {
+    /// Refuse to couple with a laser return that merely mirrors our own beam, or
+    /// when the laser cannot tell
+    async fn reject_retroreflector(&self, state: &ProtocolState, event: &str) -> Result<(), ProtocolError> {
+        let Some(laser) = &self.laser else {
+            return Ok(());
+        };
+        let error = match laser.detect_retroreflector().await {
+            Ok(false) => return Ok(()),
+            Ok(true) => ProtocolError::RetroreflectorDetected,
+            Err(e) => ProtocolError::LaserError(e),
+        };
+        self.record_failure(state, event, &error);
+        Err(error)
+    }
+
+    /// Receive the coupled ACK off the ultrasound channel (receiver side). Only a
+    /// `SignedCoupledAck` whose cross-channel signature verifies reaches
+    /// `LongRangeConnected`; with a channel validator it must also complete the
+    /// laser/ultrasound coupling. The validator's replay window only sees the
+    /// ACK's sequence number once the signature covering it has verified.
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "handshake.coupled_ack", skip_all, err))]
+    pub async fn receive_coupled_ack(
+        &mut self,
+        ack_data: &[u8],
+        sequence_id: u64,
+        peer_signing_key: &[u8],
+        security: &SecurityManager,
+    ) -> Result<(), ProtocolError> {
+        let ack = {
+            let state = self.state.lock().await;
+            if !matches!(*state, ProtocolState::LongRangeAuth) {
+                return Err(ProtocolError::InvalidState);
+            }
+
+            let ack = SignedCoupledAck::from_bytes(ack_data);
+            let authentic = match &ack {
+                Some(ack) => self.coupled_ack_verifies(ack, peer_signing_key, security).await,
+                None => false,
+            };
+            let Some(ack) = ack.filter(|_| authentic) else {
+                let error = ProtocolError::CrossChannelSignatureFailed;
+                self.record_failure(&state, "coupled_ack", &error);
+                return Err(error);
+            };
+            if let Some(validator) = &self.channel_validator {
+                if let Err(e) = self.receive_ultrasonic_data(ack_data, sequence_id, ack.sequence_number).await {
+                    self.record_failure(&state, "coupled_ack", &e);
+                    return Err(e);
+                }
+                if !validator.is_validated().await {
+                    let error = ProtocolError::CoupledChannelValidationFailed;
+                    self.record_failure(&state, "coupled_ack", &error);
+                    return Err(error);
+                }
+            }
+            ack
+        };
+        self.receive_signed_coupled_ack(&ack, peer_signing_key, security).await
+    }
+
+    /// Build the signed coupled ACK over the peer key received by laser, the
+    /// session sync pattern sent by ultrasound and the next ACK sequence number
+    /// (initiator side)
+    pub async fn sign_coupled_ack(&mut self, security: &SecurityManager) -> Result<SignedCoupledAck, ProtocolError> {
+        let sequence_number = self.coupled_ack_sequence + 1;
+        let ack = self.coupled_ack(security, sequence_number).await?;
+        self.coupled_ack_sequence = sequence_number;
+        Ok(ack)
+    }
+
+    async fn coupled_ack(&self, security: &SecurityManager, sequence_number: u64) -> Result<SignedCoupledAck, ProtocolError> {
+        let laser_data = self.peer_public_key.clone().ok_or(ProtocolError::InvalidState)?;
+        let ultrasound_data = self.session_id.as_bytes().to_vec();
+        let signed = SignedCoupledAck::signed_ultrasound_data(&ultrasound_data, sequence_number);
+        let signature = security.sign_cross_channel_data(&laser_data, &signed).await
+            .map_err(|e| ProtocolError::CryptoError(e.to_string()))?;
+
+        Ok(SignedCoupledAck {
+            laser_data,
+            ultrasound_data,
+            sequence_number,
+            signature,
+        })
+    }
+
+    /// Whether `ack` carries our laser key and sync pattern, signed with its
+    /// sequence number by `peer_signing_key`
+    async fn coupled_ack_verifies(&self, ack: &SignedCoupledAck, peer_signing_key: &[u8], security: &SecurityManager) -> bool {
+        // The peer must have received our laser key and our ultrasound sync pattern
+        let bound_to_session = ack.laser_data == self.own_handshake_key()
+            && ack.ultrasound_data == self.session_id.as_bytes();
+        let signed = SignedCoupledAck::signed_ultrasound_data(&ack.ultrasound_data, ack.sequence_number);
+        bound_to_session
+            && security.verify_cross_channel_signature(&ack.signature, &ack.laser_data, &signed, peer_signing_key)
+                .await
+                .is_ok()
+    }
+
+    /// Receive the coupled ACK and verify its cross-channel signature against the
+    /// data this handshake actually put on each channel (receiver side)
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "handshake.signed_coupled_ack", skip_all, err))]
+    pub async fn receive_signed_coupled_ack(
+        &mut self,
+        ack: &SignedCoupledAck,
+        peer_signing_key: &[u8],
+        security: &SecurityManager,
+    ) -> Result<(), ProtocolError> {
         let mut state = self.state.lock().await;
         if !matches!(*state, ProtocolState::LongRangeAuth) {
             return Err(ProtocolError::InvalidState);
         }
+        self.reject_retroreflector(&state, "signed_coupled_ack").await?;
 
-        // Use ChannelValidator if available
-        if let Some(validator) = &self.channel_validator {
-            // Receive ultrasonic ACK data
-            self.receive_ultrasonic_data(ack_data, sequence_id).await?;
-
-            // Check if validation is complete
-            if validator.is_validated().await {
-                *state = ProtocolState::LongRangeConnected;
-                self.last_activity = Instant::now();
-                Ok(())
-            } else {
-                Err(ProtocolError::CoupledChannelValidationFailed)
-            }
-        } else {
-            // Fallback: basic ACK reception
-            if let Some(_ultrasonic) = &self.ultrasonic_beam {
-                // In real implementation, this would verify the ACK data
-                *state = ProtocolState::LongRangeConnected;
-                self.last_activity = Instant::now();
-                Ok(())
-            } else {
-                Err(ProtocolError::LongRangeChannelUnavailable)
-            }
+        if !self.coupled_ack_verifies(ack, peer_signing_key, security).await {
+            let error = ProtocolError::CrossChannelSignatureFailed;
+            self.fail_transition(&mut state, ProtocolState::Error("Cross-channel signature failed".to_string()), "signed_coupled_ack", &error);
+            trace_warn!("long-range ACK cross-channel signature rejected");
+            return Err(error);
         }
+
+        self.transition(&mut state, ProtocolState::LongRangeConnected, "signed_coupled_ack");
+        self.last_activity = Instant::now();
+        Ok(())
     }
 
     /// Check for timeout and handle retries/fallback
@@ -605,33 +2392,38 @@ impl ProtocolEngine {
                 self.retry_count += 1;
                 // Reset state to retry
                 let mut state = self.state.lock().await;
-                *state = match *state {
+                let next = match *state {
                     ProtocolState::LongRangeSync => ProtocolState::LongRangeSync,
                     ProtocolState::LongRangeKeyExchange => ProtocolState::LongRangeKeyExchange,
                     ProtocolState::LongRangeAuth => ProtocolState::LongRangeAuth,
                     _ => ProtocolState::Idle,
                 };
+                self.transition(&mut state, next, "timeout_retry");
                 self.last_activity = Instant::now();
                 Ok(())
             } else {
                 // Max retries exceeded, fallback to short-range
                 let mut state = self.state.lock().await;
-                *state = ProtocolState::FallbackToShortRange;
-                Err(ProtocolError::FallbackToShortRange)
+                let error = ProtocolError::FallbackToShortRange;
+                self.fail_transition(&mut state, ProtocolState::FallbackToShortRange, "timeout_retry", &error);
+                Err(error)
             }
         } else {
             Ok(())
         }
     }
 
-    /// Receive ultrasonic data for coupled validation
-    pub async fn receive_ultrasonic_data(&self, data: &[u8], sequence_id: u64) -> Result<(), ProtocolError> {
+    /// Receive ultrasonic data for coupled validation. `sequence_number` must come
+    /// from an authenticated frame: the replay window trusts it.
+    pub async fn receive_ultrasonic_data(&self, data: &[u8], sequence_id: u64, sequence_number: u64) -> Result<(), ProtocolError> {
         if let Some(validator) = &self.channel_validator {
             let ultrasonic_data = ChannelData {
                 channel_type: ChannelType::Ultrasound,
                 data: data.to_vec(),
                 timestamp: Instant::now(),
                 sequence_id,
+                sequence_number,
+                preamble_capture: None,
             };
 
             validator.receive_channel_data(ultrasonic_data).await?;
@@ -639,6 +2431,14 @@ impl ProtocolEngine {
         Ok(())
     }
 
+    /// Whether the channel validator has confirmed laser/ultrasound coupling
+    pub async fn is_coupling_validated(&self) -> bool {
+        match &self.channel_validator {
+            Some(validator) => validator.is_validated().await,
+            None => false,
+        }
+    }
+
     /// Get channel quality metrics
     pub async fn get_channel_quality(&mut self) -> Result<ChannelQuality, ProtocolError> {
         let mut quality = ChannelQuality {
@@ -859,19 +2659,49 @@ impl ProtocolEngine {
                 .map_err(|e| ProtocolError::LaserError(e))?;
         }
 
-        // Update performance monitor with environmental data
+        self.report_environment_to_monitor(weather, visibility_m, 20.0).await; // Would get actual temperature
+        Ok(())
+    }
+
+    /// Update performance monitor with environmental data
+    async fn report_environment_to_monitor(&self, weather: crate::security::WeatherCondition, visibility_m: f32, temperature_celsius: f32) {
         if let Some(monitor) = &self.performance_monitor {
             let conditions = EnvironmentalFactors {
                 weather,
-                temperature_celsius: 20.0, // Would get actual temperature
+                temperature_celsius,
                 humidity_percent: 50.0,
                 visibility_meters: visibility_m,
                 wind_speed_mps: 2.0,
             };
             monitor.update_environmental_factors(conditions).await;
         }
+    }
 
-        Ok(())
+    /// Apply an environment preset to the live engines: optical ECC, range
+    /// ceiling and temperature (hence speed of sound) on the laser's range
+    /// detector, weather compensation of the laser power profile, and the audio
+    /// band. The power compensation is recomputed from the uncompensated power,
+    /// so applying presets one after another does not compound.
+    pub async fn apply_environment(&mut self, settings: &EnvironmentSettings) -> Result<(), ProtocolError> {
+        if let Some(laser) = &mut self.laser {
+            laser.enable_optical_ecc(settings.optical_ecc.clone())?;
+            laser.update_environmental_conditions(settings.weather.clone(), settings.visibility_m).await?;
+            // After the weather update, which assumes a typical temperature
+            if let Some(range_detector) = laser.range_detector() {
+                let mut range_detector = range_detector.lock().await;
+                range_detector.set_max_range_m(settings.max_range_m);
+                range_detector.set_temperature_celsius(settings.temperature_celsius).await;
+            }
+        }
+
+        self.report_environment_to_monitor(settings.weather.clone(), settings.visibility_m, settings.temperature_celsius).await;
+
+        let audio_config = crate::audio::AudioConfig {
+            mode: settings.audio_mode.clone(),
+            ..self.audio.get_config().clone()
+        };
+        self.audio.update_config(audio_config).await
+            .map_err(|e| ProtocolError::AudioError(e.to_string()))
     }
 
     /// Get current performance status
@@ -958,24 +2788,176 @@ impl ProtocolEngine {
         hierarchical_engine.get_highest_rank_present().await
     }
 
-    pub async fn encrypt_message(&self, data: &[u8]) -> Result<Vec<u8>, ProtocolError> {
-        let state = self.state.lock().await;
-        if !matches!(*state, ProtocolState::Connected | ProtocolState::LongRangeConnected) {
-            return Err(ProtocolError::InvalidState);
+    /// AEAD outgoing messages are sealed with; incoming ones carry their own suite id
+    pub fn set_cipher_suite(&mut self, suite: CipherSuite) {
+        self.crypto.set_cipher_suite(suite);
+    }
+
+    pub fn cipher_suite(&self) -> CipherSuite {
+        self.crypto.cipher_suite()
+    }
+
+    /// Seal a session message on the sequenced channel: each direction has its
+    /// own key and a counter nonce, so the peer can refuse replays
+    pub async fn encrypt_message(&mut self, data: &[u8]) -> Result<Vec<u8>, ProtocolError> {
+        self.require_established_session().await?;
+        if self.shared_secret.is_none() {
+            return Err(ProtocolError::CryptoError("No shared secret".to_string()));
         }
+        self.crypto.encrypt_sequenced(data).map_err(|e| ProtocolError::CryptoError(e.to_string()))
+    }
 
-        let key = self.shared_secret.ok_or(ProtocolError::CryptoError("No shared secret".to_string()))?;
-        CryptoEngine::encrypt_data(&key, data).map_err(|e| ProtocolError::CryptoError(e.to_string()))
+    /// Open a message from the peer's `encrypt_message`; one already opened, or
+    /// too far behind the newest, is `MessageReplayed`
+    pub async fn decrypt_message(&mut self, encrypted_data: &[u8]) -> Result<Vec<u8>, ProtocolError> {
+        self.require_established_session().await?;
+        if self.shared_secret.is_none() {
+            return Err(ProtocolError::CryptoError("No shared secret".to_string()));
+        }
+        self.crypto.decrypt_sequenced(encrypted_data).map_err(ProtocolError::from_decrypt)
     }
 
-    pub async fn decrypt_message(&self, encrypted_data: &[u8]) -> Result<Vec<u8>, ProtocolError> {
-        let state = self.state.lock().await;
-        if !matches!(*state, ProtocolState::Connected | ProtocolState::LongRangeConnected) {
+    /// Idle time before a keepalive is sent; also the time a keepalive may go unanswered
+    pub fn set_keepalive_interval(&mut self, interval: Duration) {
+        self.keepalive_interval = interval;
+    }
+
+    /// Unanswered keepalives in a row before `PeerUnresponsive` is raised
+    pub fn set_max_missed_keepalives(&mut self, max_missed: u32) {
+        self.max_missed_keepalives = max_missed.max(1);
+    }
+
+    pub fn missed_keepalives(&self) -> u32 {
+        self.missed_keepalives
+    }
+
+    /// Time since the last authenticated traffic from the peer or handshake step
+    pub fn idle_time(&self) -> Duration {
+        self.last_activity.elapsed()
+    }
+
+    /// Whether `data` is a keepalive frame rather than a user message
+    pub fn is_keepalive_frame(data: &[u8]) -> bool {
+        data.len() == KEEPALIVE_FRAME_LEN && data[0] == KEEPALIVE_FRAME_MARKER
+    }
+
+    /// Drive the keepalive timer of an established session; call it periodically.
+    ///
+    /// Returns a ping to send once the session has been idle for the keepalive
+    /// interval, or a replacement ping when the previous one went unanswered.
+    /// After `max_missed_keepalives` unanswered pings in a row the session moves to
+    /// an error state and `PeerUnresponsive` is returned.
+    pub async fn poll_keepalive(&mut self) -> Result<Option<Vec<u8>>, ProtocolError> {
+        self.require_established_session().await?;
+
+        match self.pending_keepalive {
+            Some((_, sent_at)) if sent_at.elapsed() < self.keepalive_interval => return Ok(None),
+            Some((sequence, _)) => {
+                self.pending_keepalive = None;
+                self.missed_keepalives += 1;
+                trace_warn!(sequence, missed = self.missed_keepalives, "keepalive unanswered");
+
+                if self.missed_keepalives >= self.max_missed_keepalives {
+                    let error = ProtocolError::PeerUnresponsive(self.missed_keepalives);
+                    let mut state = self.state.lock().await;
+                    self.fail_transition(&mut state, ProtocolState::Error("Peer unresponsive".to_string()), "keepalive", &error);
+                    return Err(error);
+                }
+            }
+            None if self.last_activity.elapsed() < self.keepalive_interval => return Ok(None),
+            None => {}
+        }
+
+        self.keepalive_sequence += 1;
+        self.pending_keepalive = Some((self.keepalive_sequence, Instant::now()));
+        self.keepalive_frame(KEEPALIVE_PING, self.keepalive_sequence).map(Some)
+    }
+
+    /// Authenticate a keepalive frame from the peer and refresh session liveness.
+    ///
+    /// A ping is answered with the pong to send back; a pong settles the outstanding
+    /// ping. Frames with a bad tag, a replayed ping sequence number or a pong that
+    /// doesn't match the outstanding ping are rejected.
+    pub async fn handle_keepalive(&mut self, frame: &[u8]) -> Result<Option<Vec<u8>>, ProtocolError> {
+        self.require_established_session().await?;
+        if !Self::is_keepalive_frame(frame) {
+            return Err(ProtocolError::InvalidKeepalive);
+        }
+
+        let kind = frame[1];
+        let mut sequence_bytes = [0u8; 8];
+        sequence_bytes.copy_from_slice(&frame[2..10]);
+        let sequence = u64::from_be_bytes(sequence_bytes);
+
+        let peer_key = self.peer_public_key.as_ref().ok_or(ProtocolError::InvalidState)?;
+        let expected = self.keepalive_tag(peer_key, self.own_handshake_key(), kind, sequence)?;
+        if !CryptoEngine::constant_time_eq(&expected, &frame[10..]) {
+            return Err(ProtocolError::AuthenticationFailed);
+        }
+
+        let response = match kind {
+            KEEPALIVE_PING if sequence > self.peer_keepalive_sequence => {
+                self.peer_keepalive_sequence = sequence;
+                Some(self.keepalive_frame(KEEPALIVE_PONG, sequence)?)
+            }
+            KEEPALIVE_PONG if matches!(self.pending_keepalive, Some((pending, _)) if pending == sequence) => {
+                self.pending_keepalive = None;
+                self.missed_keepalives = 0;
+                None
+            }
+            _ => return Err(ProtocolError::InvalidKeepalive),
+        };
+
+        self.last_activity = Instant::now();
+        Ok(response)
+    }
+
+    /// Fail unless a session is established and within its lifetime; a session
+    /// found past its lifetime is moved to `SessionExpired` for good
+    pub(crate) async fn require_established_session(&self) -> Result<(), ProtocolError> {
+        let mut state = self.state.lock().await;
+        if matches!(*state, ProtocolState::SessionExpired) {
+            return Err(ProtocolError::SessionExpired);
+        }
+        if !matches!(*state, ProtocolState::Connected | ProtocolState::SecureChannelEstablished | ProtocolState::LongRangeConnected) {
             return Err(ProtocolError::InvalidState);
         }
+        if self.is_session_expired() {
+            let error = ProtocolError::SessionExpired;
+            self.fail_transition(&mut state, ProtocolState::SessionExpired, "session_lifetime", &error);
+            trace_warn!("session lifetime exceeded; re-handshake required");
+            return Err(error);
+        }
+        Ok(())
+    }
+
+    fn keepalive_frame(&self, kind: u8, sequence: u64) -> Result<Vec<u8>, ProtocolError> {
+        let peer_key = self.peer_public_key.as_ref().ok_or(ProtocolError::InvalidState)?;
+        let tag = self.keepalive_tag(self.own_handshake_key(), peer_key, kind, sequence)?;
+
+        let mut frame = Vec::with_capacity(KEEPALIVE_FRAME_LEN);
+        frame.push(KEEPALIVE_FRAME_MARKER);
+        frame.push(kind);
+        frame.extend_from_slice(&sequence.to_be_bytes());
+        frame.extend_from_slice(&tag);
+        Ok(frame)
+    }
 
+    /// Keepalive tag as sent by `sender_key` to `receiver_key`; binding the
+    /// direction stops our own pings from being reflected back as the peer's
+    fn keepalive_tag(&self, sender_key: &[u8], receiver_key: &[u8], kind: u8, sequence: u64) -> Result<Vec<u8>, ProtocolError> {
         let key = self.shared_secret.ok_or(ProtocolError::CryptoError("No shared secret".to_string()))?;
-        CryptoEngine::decrypt_data(&key, encrypted_data).map_err(|e| ProtocolError::CryptoError(e.to_string()))
+
+        let mut transcript = KEEPALIVE_LABEL.to_vec();
+        transcript.extend_from_slice(self.session_id.as_bytes());
+        transcript.extend_from_slice(&(sender_key.len() as u16).to_be_bytes());
+        transcript.extend_from_slice(sender_key);
+        transcript.extend_from_slice(&(receiver_key.len() as u16).to_be_bytes());
+        transcript.extend_from_slice(receiver_key);
+        transcript.push(kind);
+        transcript.extend_from_slice(&sequence.to_be_bytes());
+
+        Ok(CryptoEngine::compute_hmac(&key, &transcript))
     }
 }
 
@@ -992,8 +2974,805 @@ impl Clone for ProtocolEngine {
         // Create a new ProtocolEngine with the same configuration but fresh state
         let mut new_engine = Self::new();
         new_engine.mode = self.mode.clone();
+        new_engine.audit_system = self.audit_system.clone();
+        new_engine.psk_fallback = self.psk_fallback.clone();
+        new_engine.psk_mode = self.psk_mode;
+        new_engine.pinned_peer_identity = self.pinned_peer_identity;
+        new_engine.clock = self.clock.clone();
+        new_engine.session_lifetime = self.session_lifetime;
         // Note: We don't copy engines or session state for simplicity
         // In a real implementation, you might want to implement proper cloning
         new_engine
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two engines past ECDH, each holding the given secret and awaiting key confirmation
+    async fn confirming_pair(a_secret: [u8; 32], b_secret: [u8; 32]) -> (ProtocolEngine, ProtocolEngine) {
+        let session_id = SessionId::new([7u8; 16]);
+        let mut a = ProtocolEngine::new();
+        let mut b = ProtocolEngine::new();
+        let a_key = a.get_local_public_key().to_vec();
+        let b_key = b.get_local_public_key().to_vec();
+
+        for (engine, peer_key, secret) in [(&mut a, b_key, a_secret), (&mut b, a_key, b_secret)] {
+            engine.set_session_id(session_id).await;
+            engine.set_peer_public_key(Some(peer_key));
+            engine.set_shared_secret(Some(secret));
+            engine.set_state(ProtocolState::KeyConfirmation).await;
+        }
+        (a, b)
+    }
+
+    #[tokio::test]
+    async fn test_key_confirmation_establishes_secure_channel() {
+        let (mut a, mut b) = confirming_pair([0x11; 32], [0x11; 32]).await;
+
+        let a_tag = a.key_confirmation_tag().unwrap();
+        let b_tag = b.key_confirmation_tag().unwrap();
+        assert_ne!(a_tag, b_tag);
+
+        b.confirm_peer_key(&a_tag).await.unwrap();
+        a.confirm_peer_key(&b_tag).await.unwrap();
+        assert_eq!(a.get_state().await, ProtocolState::SecureChannelEstablished);
+        assert_eq!(b.get_state().await, ProtocolState::SecureChannelEstablished);
+
+        let ciphertext = a.encrypt_message(b"hello").await.unwrap();
+        assert_eq!(b.decrypt_message(&ciphertext).await.unwrap(), b"hello");
+
+        // Session messages are sequenced: a captured one cannot be delivered again
+        assert!(matches!(b.decrypt_message(&ciphertext).await, Err(ProtocolError::MessageReplayed)));
+        assert!(a.decrypt_message(&ciphertext).await.is_err());
+        let next = a.encrypt_message(b"hello").await.unwrap();
+        assert_ne!(next, ciphertext);
+        assert_eq!(b.decrypt_message(&next).await.unwrap(), b"hello");
+    }
+
+    #[tokio::test]
+    async fn test_messages_are_sealed_with_configured_cipher_suite() {
+        let (mut a, mut b) = confirming_pair([0x11; 32], [0x11; 32]).await;
+        a.set_cipher_suite(CipherSuite::ChaCha20Poly1305);
+        let (a_tag, b_tag) = (a.key_confirmation_tag().unwrap(), b.key_confirmation_tag().unwrap());
+        b.confirm_peer_key(&a_tag).await.unwrap();
+        a.confirm_peer_key(&b_tag).await.unwrap();
+
+        // Each side seals with its own preference and opens whatever the tag names
+        let ciphertext = a.encrypt_message(b"hello").await.unwrap();
+        assert_eq!(ciphertext[0], CipherSuite::ChaCha20Poly1305.id());
+        assert_eq!(b.decrypt_message(&ciphertext).await.unwrap(), b"hello");
+        let reply = b.encrypt_message(b"hi").await.unwrap();
+        assert_eq!(reply[0], CipherSuite::Aes256Gcm.id());
+        assert_eq!(a.decrypt_message(&reply).await.unwrap(), b"hi");
+
+        let mut config = GibberConfig::default();
+        assert_eq!(ProtocolEngine::from_config(&config).unwrap().cipher_suite(), CipherSuite::Aes256Gcm);
+        config.security.crypto_algorithms.encryption_algorithm = "ChaCha20-Poly1305".to_string();
+        assert_eq!(ProtocolEngine::from_config(&config).unwrap().cipher_suite(), CipherSuite::ChaCha20Poly1305);
+        config.security.crypto_algorithms.encryption_algorithm = "DES".to_string();
+        assert!(ProtocolEngine::from_config(&config).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_key_mismatch_fails_at_confirmation() {
+        // b derived a different key, e.g. from a corrupted QR payload
+        let (mut a, mut b) = confirming_pair([0x11; 32], [0x22; 32]).await;
+
+        let a_tag = a.key_confirmation_tag().unwrap();
+        let b_tag = b.key_confirmation_tag().unwrap();
+
+        assert!(matches!(b.confirm_peer_key(&a_tag).await, Err(ProtocolError::KeyConfirmationFailed)));
+        assert!(matches!(a.confirm_peer_key(&b_tag).await, Err(ProtocolError::KeyConfirmationFailed)));
+
+        // Neither side reaches a state where it would try to exchange messages
+        assert!(matches!(b.get_state().await, ProtocolState::Error(_)));
+        assert!(b.get_shared_secret().is_none());
+        assert!(matches!(b.encrypt_message(b"hello").await, Err(ProtocolError::InvalidState)));
+    }
+
+    #[tokio::test]
+    async fn test_reflected_tag_is_rejected() {
+        let (mut a, _b) = confirming_pair([0x11; 32], [0x11; 32]).await;
+        let own_tag = a.key_confirmation_tag().unwrap();
+        assert!(matches!(a.confirm_peer_key(&own_tag).await, Err(ProtocolError::KeyConfirmationFailed)));
+    }
+
+    #[tokio::test]
+    async fn test_nonce_cannot_be_consumed_twice() {
+        let mut registry = NonceRegistry::new(Duration::from_secs(30));
+        let nonce = CryptoEngine::generate_nonce();
+
+        registry.issue(nonce);
+        assert!(registry.is_outstanding(&nonce));
+        registry.consume(&nonce).unwrap();
+        assert!(!registry.is_outstanding(&nonce));
+        assert!(matches!(registry.consume(&nonce), Err(ProtocolError::StaleNonce)));
+
+        // Never-issued nonces are rejected too
+        assert!(matches!(registry.consume(&[0u8; 16]), Err(ProtocolError::StaleNonce)));
+    }
+
+    #[tokio::test]
+    async fn test_expired_nonce_is_stale() {
+        let mut registry = NonceRegistry::new(Duration::from_millis(20));
+        let nonce = CryptoEngine::generate_nonce();
+        registry.issue(nonce);
+
+        tokio::time::sleep(Duration::from_millis(40)).await;
+        assert_eq!(registry.outstanding_count(), 0);
+        assert!(matches!(registry.consume(&nonce), Err(ProtocolError::StaleNonce)));
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_received_nonce_is_rejected() {
+        let mut engine = ProtocolEngine::new();
+        let nonce_frame = ProtocolEngine::new().nonce_frame(&CryptoEngine::generate_nonce(), None);
+
+        engine.receive_nonce(&nonce_frame).await.unwrap();
+        engine.set_state(ProtocolState::Idle).await;
+        assert!(matches!(engine.receive_nonce(&nonce_frame).await, Err(ProtocolError::StaleNonce)));
+        assert_eq!(engine.get_state().await, ProtocolState::Idle);
+    }
+
+    #[tokio::test]
+    async fn test_receive_nonce_produces_signed_qr_payload() {
+        let initiator = ProtocolEngine::new();
+        let nonce = CryptoEngine::generate_nonce();
+        initiator.nonce_registry().lock().await.issue(nonce);
+        initiator.set_state(ProtocolState::WaitingForQr).await;
+
+        let mut responder = ProtocolEngine::new();
+        responder.session_id = *initiator.get_session_id();
+        let previous_key = responder.get_local_public_key().to_vec();
+        let payload = responder.receive_nonce_payload(&initiator.nonce_frame(&nonce, None)).await.unwrap();
+        assert_eq!(responder.get_state().await, ProtocolState::WaitingForQr);
+        assert_ne!(payload.public_key, previous_key);
+
+        // What the initiator's camera decodes carries a valid signature over the nonce
+        let qr = responder.visual.encode_payload_bytes(&payload).unwrap();
+        let scanned = initiator.visual.decode_payload(&qr).unwrap();
+        assert_eq!(scanned.nonce, nonce);
+        assert_eq!(scanned.public_key, responder.get_local_public_key());
+        assert_eq!(&scanned.verify_signature().unwrap(), responder.crypto.ed25519_public_key());
+
+        // Swapping the echoed nonce breaks the signature
+        let mut forged = scanned.clone();
+        forged.nonce = CryptoEngine::generate_nonce();
+        assert!(forged.verify_signature().is_err());
+        let forged_qr = initiator.visual.encode_payload_bytes(&forged).unwrap();
+        let mut initiator = initiator;
+        assert!(matches!(initiator.process_qr_payload(&forged_qr).await, Err(ProtocolError::QrSignatureInvalid)));
+
+        // Unsigned payloads are refused outright
+        let mut unsigned = scanned.clone();
+        unsigned.signature.clear();
+        let unsigned_qr = initiator.visual.encode_payload_bytes(&unsigned).unwrap();
+        assert!(matches!(initiator.process_qr_payload(&unsigned_qr).await, Err(ProtocolError::QrSignatureInvalid)));
+
+        // So is a valid signature from anyone but the pinned responder
+        initiator.set_pinned_peer_identity(Some(*responder.identity_public_key()));
+        let mut resigned = scanned.clone();
+        resigned.sign(&CryptoEngine::new()).unwrap();
+        let resigned_qr = initiator.visual.encode_payload_bytes(&resigned).unwrap();
+        assert!(matches!(initiator.process_qr_payload(&resigned_qr).await, Err(ProtocolError::InvalidPeerIdentity(_))));
+        assert_eq!(initiator.get_state().await, ProtocolState::WaitingForQr);
+        assert_eq!(initiator.negotiated_version(), None);
+
+        // The genuine QR completes the handshake through the ACK and both tags
+        let channel = crate::loopback::LoopbackChannel::new();
+        let mut initiator_endpoint = channel.attach(&mut initiator);
+        let mut responder_endpoint = channel.attach(&mut responder);
+        initiator.process_qr_payload(&qr).await.unwrap();
+        crate::loopback::run_until_quiet(&mut initiator, &mut initiator_endpoint, &mut responder, &mut responder_endpoint).await.unwrap();
+        assert_eq!(responder.get_state().await, ProtocolState::Connected);
+        assert_eq!(initiator.get_state().await, ProtocolState::SecureChannelEstablished);
+        assert_eq!(initiator.get_shared_secret(), responder.get_shared_secret());
+    }
+
+    #[cfg(feature = "tracing")]
+    #[tokio::test]
+    async fn test_handshake_emits_phase_spans() {
+        use std::sync::Mutex as StdMutex;
+        use tracing_subscriber::layer::{Context, SubscriberExt};
+
+        #[derive(Clone, Default)]
+        struct Recorder {
+            spans: Arc<StdMutex<Vec<String>>>,
+            events: Arc<StdMutex<Vec<tracing::Level>>>,
+        }
+
+        impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for Recorder {
+            fn on_new_span(&self, attrs: &tracing::span::Attributes<'_>, _id: &tracing::span::Id, _ctx: Context<'_, S>) {
+                self.spans.lock().unwrap().push(attrs.metadata().name().to_string());
+            }
+
+            fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+                self.events.lock().unwrap().push(*event.metadata().level());
+            }
+        }
+
+        let recorder = Recorder::default();
+        let _guard = tracing::subscriber::set_default(tracing_subscriber::registry().with(recorder.clone()));
+
+        let mut initiator = ProtocolEngine::new();
+        let mut responder = ProtocolEngine::new();
+        initiator.initiate_handshake().await.unwrap();
+        responder.receive_nonce(&initiator.nonce_frame(&CryptoEngine::generate_nonce(), None)).await.unwrap();
+        // A corrupted QR scan fails inside its phase span
+        assert!(initiator.process_qr_payload(&[0u8; 8]).await.is_err());
+
+        let (a, mut b) = confirming_pair([0x11; 32], [0x22; 32]).await;
+        let tag = a.key_confirmation_tag().unwrap();
+        assert!(b.confirm_peer_key(&tag).await.is_err());
+
+        let spans = recorder.spans.lock().unwrap().clone();
+        for phase in ["handshake.initiate", "handshake.receive_nonce", "handshake.process_qr", "handshake.key_confirmation"] {
+            assert!(spans.iter().any(|name| name == phase), "missing span {} in {:?}", phase, spans);
+        }
+        // Failed phases and the confirmation mismatch are reported as events
+        let events = recorder.events.lock().unwrap();
+        assert!(events.iter().filter(|level| **level == tracing::Level::ERROR).count() >= 2);
+        assert!(events.contains(&tracing::Level::WARN));
+    }
+
+    #[tokio::test]
+    async fn test_psk_mode_is_negotiated_in_signed_qr() {
+        use crate::loopback::{run_until_quiet, LoopbackChannel};
+
+        let psk = PskFallback::new([0x5A; 32]);
+        let mut responder = ProtocolEngine::new();
+        let mut initiator = ProtocolEngine::new();
+        responder.set_psk_fallback(Some(psk.clone()));
+        responder.set_psk_mode(true).unwrap();
+        initiator.set_psk_fallback(Some(psk.clone()));
+        responder.set_session_id(*initiator.get_session_id()).await;
+
+        let channel = LoopbackChannel::new();
+        let mut initiator_endpoint = channel.attach(&mut initiator);
+        let mut responder_endpoint = channel.attach(&mut responder);
+        initiator.initiate_handshake().await.unwrap();
+        run_until_quiet(&mut initiator, &mut initiator_endpoint, &mut responder, &mut responder_endpoint).await.unwrap();
+
+        assert_eq!(initiator.get_shared_secret(), responder.get_shared_secret());
+        assert_eq!(initiator.get_state().await, ProtocolState::SecureChannelEstablished);
+        assert_eq!(responder.get_state().await, ProtocolState::Connected);
+        for engine in [&initiator, &responder] {
+            let info = engine.connection_info().await;
+            assert!(info.is_psk_mode());
+            assert!(!info.has_forward_secrecy());
+        }
+
+        // Traffic is authenticated under the PSK-derived key
+        let ciphertext = initiator.encrypt_message(b"degraded but sealed").await.unwrap();
+        assert_eq!(responder.decrypt_message(&ciphertext).await.unwrap(), b"degraded but sealed");
+        let nonce = responder.handshake_nonce.unwrap();
+        let mut imposter = ProtocolEngine::new();
+        imposter.set_session_id(*initiator.get_session_id()).await;
+        imposter.set_shared_secret(Some(PskFallback::new([0xA5; 32]).derive_session_key(&nonce, initiator.get_session_id())));
+        imposter.set_state(ProtocolState::Connected).await;
+        assert!(matches!(imposter.decrypt_message(&ciphertext).await, Err(ProtocolError::AuthenticationFailed)));
+
+        // PSK mode needs a PSK on the responder, and an initiator without one
+        // refuses the announcement
+        assert!(ProtocolEngine::new().set_psk_mode(true).is_err());
+        let mut strict = ProtocolEngine::new();
+        let nonce = CryptoEngine::generate_nonce();
+        strict.nonce_registry().lock().await.issue(nonce);
+        strict.set_state(ProtocolState::WaitingForQr).await;
+        let mut psk_responder = responder_in_psk_mode(&psk).await;
+        psk_responder.set_session_id(*strict.get_session_id()).await;
+        let payload = psk_responder.receive_nonce_payload(&strict.nonce_frame(&nonce, None)).await.unwrap();
+        let qr = psk_responder.visual.encode_payload_bytes(&payload).unwrap();
+        assert!(matches!(strict.process_qr_payload(&qr).await, Err(ProtocolError::KeyEstablishmentMismatch)));
+        assert_eq!(strict.connection_info().await.key_establishment, None);
+    }
+
+    #[tokio::test]
+    async fn test_psk_is_never_a_fallback_for_bad_input() {
+        let psk = PskFallback::new([0x5A; 32]);
+        let mut initiator = ProtocolEngine::new();
+        initiator.set_psk_fallback(Some(psk.clone()));
+        let nonce = CryptoEngine::generate_nonce();
+        initiator.nonce_registry().lock().await.issue(nonce);
+        initiator.set_state(ProtocolState::WaitingForQr).await;
+
+        // A truncated ECDH key is an error even with a PSK at hand
+        let mut responder = ProtocolEngine::new();
+        responder.set_session_id(*initiator.get_session_id()).await;
+        let mut payload = responder.receive_nonce_payload(&initiator.nonce_frame(&nonce, None)).await.unwrap();
+        payload.public_key.truncate(16);
+        payload.sign(&responder.crypto).unwrap();
+        let qr = responder.visual.encode_payload_bytes(&payload).unwrap();
+        assert!(matches!(initiator.process_qr_payload(&qr).await, Err(ProtocolError::CryptoError(_))));
+        assert_eq!(initiator.connection_info().await.key_establishment, None);
+
+        // Flipping the announced mode in transit breaks the signature
+        let mut psk_responder = responder_in_psk_mode(&psk).await;
+        psk_responder.set_session_id(*initiator.get_session_id()).await;
+        let mut payload = psk_responder.receive_nonce_payload(&initiator.nonce_frame(&CryptoEngine::generate_nonce(), None)).await.unwrap();
+        payload.psk = false;
+        assert!(payload.verify_signature().is_err());
+
+        // A PSK-mode responder turns down an ACK keyed by ECDH
+        let mut ack = ACK_PREFIX.to_vec();
+        ack.push(ACK_KEYED_ECDH);
+        ack.extend_from_slice(&32u16.to_be_bytes());
+        ack.extend_from_slice(ProtocolEngine::new().get_local_public_key());
+        assert!(matches!(psk_responder.receive_ack_frame(&ack).await, Err(ProtocolError::KeyEstablishmentMismatch)));
+        assert_eq!(psk_responder.get_state().await, ProtocolState::WaitingForQr);
+    }
+
+    /// Idle responder configured to announce PSK keying
+    async fn responder_in_psk_mode(psk: &PskFallback) -> ProtocolEngine {
+        let mut responder = ProtocolEngine::new();
+        responder.set_psk_fallback(Some(psk.clone()));
+        responder.set_psk_mode(true).unwrap();
+        responder
+    }
+
+    #[test]
+    fn test_session_ticket_with_unrepresentable_expiry_is_rejected() {
+        let mut bytes = vec![0x11; 16];
+        bytes.extend_from_slice(&u64::MAX.to_be_bytes());
+        bytes.extend_from_slice(&[0u8; SESSION_TICKET_SEAL_OVERHEAD + SESSION_TICKET_PLAINTEXT_LEN]);
+        assert_eq!(SessionTicket::from_bytes(&bytes), None);
+
+        bytes[16..24].copy_from_slice(&1_000_000u64.to_be_bytes());
+        let ticket = SessionTicket::from_bytes(&bytes).unwrap();
+        assert_eq!(ticket.expiry, std::time::UNIX_EPOCH + Duration::from_secs(1_000_000));
+        assert_eq!(ticket.to_bytes(), bytes);
+    }
+
+    #[tokio::test]
+    async fn test_session_expires_after_lifetime_and_requires_rehandshake() {
+        let clock = crate::clock::MockClock::new(std::time::UNIX_EPOCH + Duration::from_secs(1_000_000));
+        let mut a = ProtocolEngine::new();
+        a.set_clock(Arc::new(clock.clone()));
+        a.set_session_lifetime(Some(Duration::from_secs(600)));
+        let mut b = ProtocolEngine::new();
+        let (a_key, b_key) = (a.get_local_public_key().to_vec(), b.get_local_public_key().to_vec());
+        for (engine, peer_key) in [(&mut a, b_key), (&mut b, a_key)] {
+            engine.set_peer_public_key(Some(peer_key));
+            engine.set_shared_secret(Some([0x22; 32]));
+            engine.set_state(ProtocolState::Connected).await;
+        }
+
+        clock.advance(Duration::from_secs(599));
+        let ciphertext = b.encrypt_message(b"still fresh").await.unwrap();
+        assert_eq!(a.decrypt_message(&ciphertext).await.unwrap(), b"still fresh");
+
+        // Past the lifetime every use fails and the state is terminal
+        clock.advance(Duration::from_secs(2));
+        assert!(a.is_session_expired());
+        assert!(matches!(a.encrypt_message(b"late").await, Err(ProtocolError::SessionExpired)));
+        assert_eq!(a.get_state().await, ProtocolState::SessionExpired);
+        assert!(matches!(a.decrypt_message(&ciphertext).await, Err(ProtocolError::SessionExpired)));
+        assert!(matches!(a.poll_keepalive().await, Err(ProtocolError::SessionExpired)));
+
+        // Only a full handshake moves on, under a new session id and without the old key
+        let old_session = *a.get_session_id();
+        a.initiate_handshake().await.unwrap();
+        assert_eq!(a.get_state().await, ProtocolState::WaitingForQr);
+        assert_ne!(*a.get_session_id(), old_session);
+        assert!(a.get_shared_secret().is_none());
+        assert!(!a.is_session_expired());
+    }
+
+    /// Initiator waiting for the QR of `responder`, plus that QR's raw bytes
+    async fn awaiting_qr(responder: &ProtocolEngine) -> (ProtocolEngine, Vec<u8>) {
+        // These tests stop at the ACK; the challenge round runs over loopback
+        let mut initiator = ProtocolEngine::new();
+        initiator.set_mutual_authentication_required(false);
+        let nonce = CryptoEngine::generate_nonce();
+        initiator.nonce_registry().lock().await.issue(nonce);
+        initiator.set_state(ProtocolState::WaitingForQr).await;
+
+        let mut payload = VisualPayload {
+            session_id: *initiator.get_session_id(),
+            public_key: responder.get_local_public_key().to_vec(),
+            nonce,
+            signature: vec![],
+            version: None,
+            version_offer: None,
+            psk: false,
+        };
+        payload.sign(&responder.crypto).unwrap();
+        let qr = initiator.visual.encode_payload_bytes(&payload).unwrap();
+        (initiator, qr)
+    }
+
+    #[tokio::test]
+    async fn test_failed_ack_resumes_from_qr_checkpoint() {
+        let mut responder = ProtocolEngine::new();
+        let (mut initiator, qr) = awaiting_qr(&responder).await;
+
+        // The ACK cannot be sent the first time
+        initiator.get_audio_engine_mut().shutdown().await.unwrap();
+        assert!(matches!(initiator.process_qr_payload(&qr).await, Err(ProtocolError::AudioError(_))));
+        assert_eq!(initiator.get_state().await, ProtocolState::SendingAck);
+        assert_eq!(initiator.resumable_phase(), Some(ProtocolState::SendingAck));
+        let secret = *initiator.get_shared_secret().unwrap();
+
+        // The retry skips the spent nonce and the ECDH and only resends the ACK
+        initiator.get_audio_engine_mut().force_initialize_for_testing();
+        initiator.process_qr_payload(&qr).await.unwrap();
+        assert_eq!(initiator.get_state().await, ProtocolState::KeyConfirmation);
+        assert_eq!(initiator.get_shared_secret(), Some(&secret));
+        assert_eq!(initiator.resumable_phase(), None);
+
+        // The resumed handshake completes with the responder; both sides rotate
+        // their keypair on derivation, so the transcript uses the keys exchanged
+        let initiator_key = initiator.own_handshake_key().to_vec();
+        responder.handshake_public_key = Some(responder.get_local_public_key().to_vec());
+        let responder_secret = responder.crypto.derive_shared_secret(&initiator_key).unwrap();
+        responder.set_session_id(*initiator.get_session_id()).await;
+        responder.set_peer_public_key(Some(initiator_key));
+        responder.set_shared_secret(Some(responder_secret));
+        responder.set_state(ProtocolState::KeyConfirmation).await;
+
+        let initiator_tag = initiator.key_confirmation_tag().unwrap();
+        let responder_tag = responder.key_confirmation_tag().unwrap();
+        responder.confirm_peer_key(&initiator_tag).await.unwrap();
+        initiator.confirm_peer_key(&responder_tag).await.unwrap();
+        assert_eq!(initiator.get_state().await, ProtocolState::SecureChannelEstablished);
+    }
+
+    #[tokio::test]
+    async fn test_resume_rejects_different_transcript() {
+        let responder = ProtocolEngine::new();
+        let (mut initiator, qr) = awaiting_qr(&responder).await;
+
+        initiator.get_audio_engine_mut().shutdown().await.unwrap();
+        assert!(initiator.process_qr_payload(&qr).await.is_err());
+        initiator.get_audio_engine_mut().force_initialize_for_testing();
+        let secret = *initiator.get_shared_secret().unwrap();
+
+        // An unsigned retry carrying a different key changes nothing
+        let mut payload = initiator.visual.decode_payload(&qr).unwrap();
+        payload.public_key = ProtocolEngine::new().get_local_public_key().to_vec();
+        payload.signature.clear();
+        let forged = initiator.visual.encode_payload_bytes(&payload).unwrap();
+        assert!(matches!(initiator.process_qr_payload(&forged).await, Err(ProtocolError::QrSignatureInvalid)));
+
+        // A signed one for another transcript is turned away without tearing
+        // down the checkpoint it does not match
+        payload.sign(&responder.crypto).unwrap();
+        let other = initiator.visual.encode_payload_bytes(&payload).unwrap();
+        assert!(matches!(initiator.process_qr_payload(&other).await, Err(ProtocolError::HandshakeResumeRejected)));
+        assert_eq!(initiator.get_state().await, ProtocolState::SendingAck);
+        assert_eq!(initiator.get_shared_secret(), Some(&secret));
+        assert_eq!(initiator.resumable_phase(), Some(ProtocolState::SendingAck));
+
+        // Once the checkpoint has lapsed the handshake starts over
+        initiator.set_resume_timeout(Duration::ZERO);
+        tokio::time::sleep(Duration::from_millis(1)).await;
+        assert!(matches!(initiator.process_qr_payload(&qr).await, Err(ProtocolError::HandshakeResumeRejected)));
+        assert_eq!(initiator.get_state().await, ProtocolState::Idle);
+        assert!(initiator.get_shared_secret().is_none());
+        assert_eq!(initiator.resumable_phase(), None);
+    }
+
+    #[tokio::test]
+    async fn test_long_range_sync_rejects_wrong_length_session_id() {
+        let initiator = ProtocolEngine::new();
+        let session_id = initiator.get_session_id().as_bytes().to_vec();
+
+        // Truncated and padded patterns are rejected at the boundary, not compared
+        for pattern in [&session_id[..15], &[session_id.as_slice(), &[0u8]].concat()[..]] {
+            let mut receiver = ProtocolEngine::new();
+            receiver.set_session_id(*initiator.get_session_id()).await;
+            let result = receiver.receive_long_range_sync(pattern).await;
+            assert!(matches!(
+                result,
+                Err(ProtocolError::InvalidSessionId(SessionIdError::InvalidLength { expected: 16, actual })) if actual == pattern.len()
+            ));
+            assert_eq!(receiver.get_state().await, ProtocolState::Idle);
+        }
+
+        let mut receiver = ProtocolEngine::new();
+        receiver.set_session_id(*initiator.get_session_id()).await;
+        receiver.receive_long_range_sync(&session_id).await.unwrap();
+        assert_eq!(receiver.get_state().await, ProtocolState::LongRangeKeyExchange);
+    }
+
+    /// Replays a fixed list of session ids as a stream of random bytes
+    struct ScriptedRng(std::collections::VecDeque<u8>);
+
+    impl ScriptedRng {
+        fn new(ids: &[[u8; 16]]) -> Self {
+            Self(ids.iter().flatten().copied().collect())
+        }
+    }
+
+    impl RngCore for ScriptedRng {
+        fn next_u32(&mut self) -> u32 {
+            let mut bytes = [0u8; 4];
+            self.fill_bytes(&mut bytes);
+            u32::from_le_bytes(bytes)
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            let mut bytes = [0u8; 8];
+            self.fill_bytes(&mut bytes);
+            u64::from_le_bytes(bytes)
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            for byte in dest.iter_mut() {
+                *byte = self.0.pop_front().expect("script exhausted");
+            }
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_session_id_collision_regenerates_fresh_id() {
+        let (first, second) = ([1u8; 16], [2u8; 16]);
+        let script = ScriptedRng::new(&[first, first, second, first, first, first]);
+        let registry = Arc::new(Mutex::new(SessionRegistry::with_rng(Box::new(script))));
+
+        // Two engines of a multi-session receiver share the registry
+        let mut a = ProtocolEngine::new();
+        let mut b = ProtocolEngine::new();
+        a.set_session_registry(registry.clone());
+        b.set_session_registry(registry.clone());
+
+        // Each engine adopts the id it draws
+        let a_id = a.new_session_id().await.unwrap();
+        assert_eq!(a_id, SessionId::new(first));
+        assert_eq!(*a.get_session_id(), a_id);
+
+        // The second draw repeats the active id, so a fresh one is drawn instead
+        let b_id = b.new_session_id().await.unwrap();
+        assert_eq!(b_id, SessionId::new(second));
+        assert_eq!(*b.get_session_id(), b_id);
+        assert_eq!(registry.lock().await.active_count(), 2);
+
+        // Setting an id moves the engine's registry entry rather than leaking it
+        let restored = SessionId::new([3u8; 16]);
+        b.set_session_id(restored).await;
+        assert!(!registry.lock().await.is_active(&b_id));
+        assert!(registry.lock().await.is_active(&restored));
+        b.set_session_id(b_id).await;
+        assert!(!registry.lock().await.is_active(&restored));
+        assert_eq!(registry.lock().await.active_count(), 2);
+
+        // Closing a session reaps its id so it may be issued again
+        a.close_session().await;
+        assert!(!registry.lock().await.is_active(&a_id));
+        assert_eq!(a.new_session_id().await.unwrap(), SessionId::new(first));
+
+        // An RNG stuck on active ids gives up instead of looping forever
+        registry.lock().await.set_max_attempts(2);
+        assert!(matches!(b.new_session_id().await, Err(ProtocolError::SessionIdExhausted(2))));
+    }
+
+    /// Receiver waiting for the long-range ACK, plus the initiator that received its laser key
+    async fn awaiting_coupled_ack() -> (ProtocolEngine, ProtocolEngine) {
+        let mut initiator = ProtocolEngine::new();
+        let mut receiver = ProtocolEngine::new();
+        receiver.set_session_id(*initiator.get_session_id()).await;
+        receiver.set_state(ProtocolState::LongRangeAuth).await;
+
+        // The receiver's public key reached the initiator over the laser channel
+        initiator.set_peer_public_key(Some(receiver.get_local_public_key().to_vec()));
+        (initiator, receiver)
+    }
+
+    async fn signing_security_manager() -> SecurityManager {
+        let security = SecurityManager::new(crate::security::SecurityConfig::default());
+        security.derive_channel_keys(ChannelType::Laser, b"laser-seed").await.unwrap();
+        security.derive_channel_keys(ChannelType::Ultrasound, b"ultrasound-seed").await.unwrap();
+        security
+    }
+
+    #[tokio::test]
+    async fn test_valid_cross_signature_completes_long_range_handshake() {
+        let (mut initiator, mut receiver) = awaiting_coupled_ack().await;
+        let initiator_security = signing_security_manager().await;
+        let receiver_security = SecurityManager::new(crate::security::SecurityConfig::default());
+
+        let ack = initiator.sign_coupled_ack(&initiator_security).await.unwrap();
+        let signing_key = initiator_security.cross_channel_public_key().await;
+        receiver.receive_signed_coupled_ack(&ack, &signing_key, &receiver_security).await.unwrap();
+        assert_eq!(receiver.get_state().await, ProtocolState::LongRangeConnected);
+    }
+
+    #[tokio::test]
+    async fn test_coupling_is_refused_when_the_retroreflector_check_fails() {
+        let (mut initiator, mut receiver) = awaiting_coupled_ack().await;
+        let initiator_security = signing_security_manager().await;
+        let receiver_security = SecurityManager::new(crate::security::SecurityConfig::default());
+        let ack = initiator.sign_coupled_ack(&initiator_security).await.unwrap();
+        let signing_key = initiator_security.cross_channel_public_key().await;
+
+        // A laser that never came up cannot rule out a reflector
+        receiver.laser = Some(LaserEngine::new(LaserConfig::default(), ReceptionConfig::default()));
+        assert!(matches!(
+            receiver.receive_signed_coupled_ack(&ack, &signing_key, &receiver_security).await,
+            Err(ProtocolError::LaserError(LaserError::HardwareUnavailable))
+        ));
+        assert_eq!(receiver.get_state().await, ProtocolState::LongRangeAuth);
+    }
+
+    #[tokio::test]
+    async fn test_forged_cross_signature_fails_at_ack() {
+        let (mut initiator, mut receiver) = awaiting_coupled_ack().await;
+        let initiator_security = signing_security_manager().await;
+        let attacker_security = signing_security_manager().await;
+        let receiver_security = SecurityManager::new(crate::security::SecurityConfig::default());
+        let signing_key = initiator_security.cross_channel_public_key().await;
+
+        // Signed by someone other than the expected peer
+        let forged = initiator.sign_coupled_ack(&attacker_security).await.unwrap();
+        assert!(matches!(
+            receiver.receive_signed_coupled_ack(&forged, &signing_key, &receiver_security).await,
+            Err(ProtocolError::CrossChannelSignatureFailed)
+        ));
+        assert!(matches!(receiver.get_state().await, ProtocolState::Error(_)));
+
+        // A genuine signature over data from another session is rejected too
+        receiver.set_state(ProtocolState::LongRangeAuth).await;
+        let mut replayed = initiator.sign_coupled_ack(&initiator_security).await.unwrap();
+        replayed.ultrasound_data = vec![0u8; 16];
+        assert!(matches!(
+            receiver.receive_signed_coupled_ack(&replayed, &signing_key, &receiver_security).await,
+            Err(ProtocolError::CrossChannelSignatureFailed)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_coupled_ack_off_the_wire_must_be_signed() {
+        let (mut initiator, mut receiver) = awaiting_coupled_ack().await;
+        let initiator_security = signing_security_manager().await;
+        let receiver_security = SecurityManager::new(crate::security::SecurityConfig::default());
+        let signing_key = initiator_security.cross_channel_public_key().await;
+
+        // A bare ACK no longer connects anything
+        assert!(matches!(
+            receiver.receive_coupled_ack(b"LONG_RANGE_ACK", 1, &signing_key, &receiver_security).await,
+            Err(ProtocolError::CrossChannelSignatureFailed)
+        ));
+        assert_eq!(receiver.get_state().await, ProtocolState::LongRangeAuth);
+
+        let ack = initiator.sign_coupled_ack(&initiator_security).await.unwrap();
+        receiver.receive_coupled_ack(&ack.to_bytes(), 1, &signing_key, &receiver_security).await.unwrap();
+        assert_eq!(receiver.get_state().await, ProtocolState::LongRangeConnected);
+    }
+
+    #[tokio::test]
+    async fn test_coupled_ack_sequence_is_signed_and_windows_reset_per_handshake() {
+        let (mut initiator, mut receiver) = awaiting_coupled_ack().await;
+        receiver.channel_validator = Some(ChannelValidator::new());
+        let initiator_security = signing_security_manager().await;
+        let receiver_security = SecurityManager::new(crate::security::SecurityConfig::default());
+        let signing_key = initiator_security.cross_channel_public_key().await;
+        let is_replay = |result: Result<(), ProtocolError>| {
+            matches!(result, Err(ProtocolError::ChannelValidatorError(ValidationError::ReplayDetected(..))))
+        };
+
+        let first = initiator.sign_coupled_ack(&initiator_security).await.unwrap();
+        let second = initiator.sign_coupled_ack(&initiator_security).await.unwrap();
+        assert_eq!((first.sequence_number, second.sequence_number), (1, 2));
+
+        // No laser frame has arrived, so coupling can't complete, but the window
+        // records the authenticated sequence number
+        let result = receiver.receive_coupled_ack(&first.to_bytes(), 1, &signing_key, &receiver_security).await;
+        assert!(matches!(result, Err(ProtocolError::CoupledChannelValidationFailed)));
+        assert!(is_replay(receiver.receive_coupled_ack(&first.to_bytes(), 1, &signing_key, &receiver_security).await));
+
+        // A sequence number far ahead fails the signature and never reaches the window
+        let mut forged = second.clone();
+        forged.sequence_number = 1 << 62;
+        assert!(matches!(
+            receiver.receive_coupled_ack(&forged.to_bytes(), 1, &signing_key, &receiver_security).await,
+            Err(ProtocolError::CrossChannelSignatureFailed)
+        ));
+        let result = receiver.receive_coupled_ack(&second.to_bytes(), 1, &signing_key, &receiver_security).await;
+        assert!(matches!(result, Err(ProtocolError::CoupledChannelValidationFailed)));
+
+        // A new handshake starts with fresh windows
+        receiver.set_state(ProtocolState::Idle).await;
+        receiver.receive_long_range_sync(initiator.get_session_id().as_bytes()).await.unwrap();
+        receiver.set_state(ProtocolState::LongRangeAuth).await;
+        let result = receiver.receive_coupled_ack(&first.to_bytes(), 1, &signing_key, &receiver_security).await;
+        assert!(matches!(result, Err(ProtocolError::CoupledChannelValidationFailed)));
+    }
+
+    /// Two engines with a confirmed secure channel and a short keepalive interval
+    async fn keepalive_pair(interval: Duration) -> (ProtocolEngine, ProtocolEngine) {
+        let (mut a, mut b) = confirming_pair([0x33; 32], [0x33; 32]).await;
+        let a_tag = a.key_confirmation_tag().unwrap();
+        let b_tag = b.key_confirmation_tag().unwrap();
+        b.confirm_peer_key(&a_tag).await.unwrap();
+        a.confirm_peer_key(&b_tag).await.unwrap();
+
+        for engine in [&mut a, &mut b] {
+            engine.set_keepalive_interval(interval);
+            engine.set_max_missed_keepalives(2);
+        }
+        (a, b)
+    }
+
+    #[tokio::test]
+    async fn test_keepalives_keep_idle_session_alive() {
+        let interval = Duration::from_millis(20);
+        let (mut a, mut b) = keepalive_pair(interval).await;
+
+        // Not idle yet
+        assert!(a.poll_keepalive().await.unwrap().is_none());
+
+        for _ in 0..5 {
+            tokio::time::sleep(interval).await;
+            let ping = a.poll_keepalive().await.unwrap().expect("idle session sends a ping");
+            assert!(ProtocolEngine::is_keepalive_frame(&ping));
+
+            let pong = b.handle_keepalive(&ping).await.unwrap().expect("ping is answered");
+            assert!(a.handle_keepalive(&pong).await.unwrap().is_none());
+            assert_eq!(a.missed_keepalives(), 0);
+
+            // Replayed frames are rejected
+            assert!(matches!(b.handle_keepalive(&ping).await, Err(ProtocolError::InvalidKeepalive)));
+            assert!(matches!(a.handle_keepalive(&pong).await, Err(ProtocolError::InvalidKeepalive)));
+        }
+        assert_eq!(a.get_state().await, ProtocolState::SecureChannelEstablished);
+
+        // Reflected and tampered frames fail authentication
+        tokio::time::sleep(interval).await;
+        let mut ping = a.poll_keepalive().await.unwrap().unwrap();
+        assert!(matches!(a.handle_keepalive(&ping).await, Err(ProtocolError::AuthenticationFailed)));
+        ping[9] ^= 0x01;
+        assert!(matches!(b.handle_keepalive(&ping).await, Err(ProtocolError::AuthenticationFailed)));
+    }
+
+    #[tokio::test]
+    async fn test_unanswered_keepalives_report_unresponsive_peer() {
+        let interval = Duration::from_millis(20);
+        let (mut a, _b) = keepalive_pair(interval).await;
+
+        // The peer stops responding
+        tokio::time::sleep(interval).await;
+        assert!(a.poll_keepalive().await.unwrap().is_some());
+        tokio::time::sleep(interval).await;
+        assert!(a.poll_keepalive().await.unwrap().is_some());
+        assert_eq!(a.missed_keepalives(), 1);
+
+        tokio::time::sleep(interval).await;
+        assert!(matches!(a.poll_keepalive().await, Err(ProtocolError::PeerUnresponsive(2))));
+        assert!(matches!(a.get_state().await, ProtocolState::Error(_)));
+    }
+
+    #[tokio::test]
+    async fn test_transcript_records_transitions_and_failures() {
+        let (mut a, b) = confirming_pair([0x44; 32], [0x55; 32]).await;
+        let b_tag = b.key_confirmation_tag().unwrap();
+        assert!(matches!(a.confirm_peer_key(&b_tag).await, Err(ProtocolError::KeyConfirmationFailed)));
+
+        let transcript = a.get_transcript();
+        assert_eq!(transcript.len(), 2);
+        assert_eq!(transcript[0].event, "set_state");
+        assert_eq!(transcript[0].from, ProtocolState::Idle);
+        assert_eq!(transcript[0].to, ProtocolState::KeyConfirmation);
+        assert!(transcript[0].error.is_none());
+        assert_eq!(transcript[1].event, "key_confirmation");
+        assert_eq!(transcript[1].from, ProtocolState::KeyConfirmation);
+        assert!(matches!(transcript[1].to, ProtocolState::Error(_)));
+        assert_eq!(transcript[1].error.as_deref(), Some(ProtocolError::KeyConfirmationFailed.to_string().as_str()));
+        assert!(transcript[1].to_string().contains("key_confirmation: KeyConfirmation -> Error"));
+
+        // Only the most recent transitions are kept
+        for _ in 0..TRANSCRIPT_CAPACITY {
+            a.set_state(ProtocolState::Idle).await;
+        }
+        let transcript = a.get_transcript();
+        assert_eq!(transcript.len(), TRANSCRIPT_CAPACITY);
+        assert!(transcript.iter().all(|entry| entry.event == "set_state"));
+        assert!(matches!(transcript[0].from, ProtocolState::Error(_)));
+    }
+}
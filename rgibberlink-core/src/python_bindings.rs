@@ -3,6 +3,7 @@ use pyo3::exceptions::PyRuntimeError;
 use std::collections::HashMap;
 use crate::crypto::{CryptoEngine, CryptoError};
 use crate::visual::{VisualEngine, VisualError, VisualPayload};
+use crate::session_id::SessionId;
 use crate::audio::AudioEngine;
 use crate::protocol::{ProtocolEngine, ProtocolError, ProtocolState};
 use crate::RgibberLink;
@@ -122,17 +123,20 @@ impl PyVisualPayload {
     fn new(session_id: [u8; 16], public_key: Vec<u8>, nonce: [u8; 16], signature: Vec<u8>) -> Self {
         Self {
             inner: VisualPayload {
-                session_id,
+                session_id: SessionId::new(session_id),
                 public_key,
                 nonce,
                 signature,
+                version: None,
+                version_offer: None,
+                psk: false,
             },
         }
     }
 
     #[getter]
     fn session_id(&self) -> [u8; 16] {
-        self.inner.session_id
+        *self.inner.session_id.as_bytes()
     }
 
     #[getter]
@@ -821,6 +825,26 @@ impl PyRangeMeasurement {
         self.inner.quality_score
     }
 
+    #[getter]
+    fn velocity_mps(&self) -> Option<f32> {
+        self.inner.velocity_mps
+    }
+
+    #[getter]
+    fn doppler_confidence(&self) -> f32 {
+        self.inner.doppler_confidence
+    }
+
+    #[getter]
+    fn angle_of_arrival_degrees(&self) -> Option<f32> {
+        self.inner.angle_of_arrival_degrees
+    }
+
+    #[getter]
+    fn multipath_confidence(&self) -> f32 {
+        self.inner.multipath_confidence
+    }
+
     #[getter]
     fn timestamp(&self) -> f64 {
         self.inner.timestamp.duration_since(std::time::UNIX_EPOCH)
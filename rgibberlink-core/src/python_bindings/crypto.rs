@@ -115,6 +115,9 @@ impl PyVisualPayload {
                 public_key,
                 nonce,
                 signature,
+                version: None,
+                version_offer: None,
+                psk: false,
             },
         }
     }
@@ -4,8 +4,10 @@
 //! Provides accurate distance measurements (10-200m) with 1m precision for adaptive power profiles.
 
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::collections::VecDeque;
-use tokio::sync::Mutex;
+use std::future::Future;
+use tokio::sync::{Mutex, Notify};
 use tokio::time::{Duration, Instant};
 use serde::{Deserialize, Serialize};
 
@@ -19,8 +21,28 @@ extern "C" {
     fn ultrasonic_start_listening(timeout_ms: u32) -> c_int;
     fn ultrasonic_get_echo_time() -> f64; // microseconds
     fn ultrasonic_get_signal_strength() -> f32;
+    fn ultrasonic_get_echo_samples(buffer: *mut f32, max_samples: u32, sample_rate_hz: *mut f32) -> c_int;
+    fn ultrasonic_get_secondary_echo_time() -> f64; // microseconds, second transducer
+    fn ultrasonic_get_echo_envelope(buffer: *mut f32, max_samples: u32, sample_rate_hz: *mut f32) -> c_int;
 }
 
+/// Sample rate of the simulated echo capture (four samples per 40 kHz cycle)
+#[cfg(not(target_os = "android"))]
+const SIMULATED_ECHO_SAMPLE_RATE_HZ: f32 = 160_000.0;
+/// Normalized autocorrelation below which a Doppler estimate is not reported
+pub const DOPPLER_MIN_CONFIDENCE: f32 = 0.5;
+/// Envelope rate of the mock echo train
+#[cfg(not(target_os = "android"))]
+const SIMULATED_ENVELOPE_SAMPLE_RATE_HZ: f32 = 20_000.0;
+/// Longest echo envelope read from the driver
+#[cfg(target_os = "android")]
+const ECHO_ENVELOPE_MAX_SAMPLES: usize = 1 << 16;
+/// After reverberation is subtracted, the earliest peak reaching this fraction
+/// of the strongest one is taken as the direct path
+pub const DIRECT_PATH_PEAK_FRACTION: f32 = 0.5;
+/// Envelope levels below this fraction of the peak are noise, not reverberation
+const REVERB_NOISE_FLOOR_FRACTION: f32 = 0.02;
+
 /// Comprehensive error types for range detection operations
 #[derive(Debug, thiserror::Error)]
 pub enum RangeDetectorError {
@@ -40,10 +62,15 @@ pub enum RangeDetectorError {
     InterferenceDetected,
     #[error("Temperature compensation failed")]
     TemperatureCompensationFailed,
+    #[error("No echo within the ranging window")]
+    NoEcho,
+    #[error("Measurement cancelled")]
+    Cancelled,
 }
 
 /// Configuration for ultrasonic ranging
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct RangingConfig {
     pub pulse_frequency_hz: f32,      // 40kHz typical for ultrasonic ranging
     pub pulse_duration_us: u32,       // Pulse length in microseconds
@@ -54,6 +81,9 @@ pub struct RangingConfig {
     pub signal_threshold: f32,       // Minimum signal strength for valid detection
     pub averaging_samples: usize,    // Number of samples for averaging
     pub temperature_celsius: f32,    // Ambient temperature for compensation
+    pub peer_return_tolerance_m: f32, // Returns this close to the peer range are the peer
+    pub dual_transducer_baseline_m: Option<f32>, // Spacing of a second receiver, enables angle of arrival
+    pub multipath_suppression: bool, // Re-time echoes from the envelope with wall reverberation removed
 }
 
 impl Default for RangingConfig {
@@ -68,6 +98,55 @@ impl Default for RangingConfig {
             signal_threshold: 0.3,
             averaging_samples: 5,
             temperature_celsius: 20.0,
+            peer_return_tolerance_m: 1.0,
+            dual_transducer_baseline_m: None,
+            multipath_suppression: false,
+        }
+    }
+}
+
+impl RangingConfig {
+    /// Longest time to wait for an echo: the listening timeout, extended to
+    /// cover a round trip at `max_range_m` if that takes longer
+    pub fn echo_timeout(&self) -> Duration {
+        let round_trip_s = self.max_range_m * 2.0 / self.speed_of_sound_mps.max(1.0);
+        Duration::from_millis(self.listening_timeout_ms as u64).max(Duration::from_secs_f32(round_trip_s))
+    }
+}
+
+/// Handle for cancelling in-flight measurements without locking the detector
+///
+/// Cancelling only affects measurements already started; later measurements
+/// run normally.
+#[derive(Debug, Clone, Default)]
+pub struct RangeCancelHandle {
+    generation: Arc<AtomicU64>,
+    notify: Arc<Notify>,
+}
+
+impl RangeCancelHandle {
+    /// Cancel every measurement currently in progress
+    pub fn cancel(&self) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        self.notify.notify_waiters();
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation.load(Ordering::SeqCst)
+    }
+
+    fn is_cancelled_since(&self, generation: u64) -> bool {
+        self.generation() != generation
+    }
+
+    /// Resolves once `cancel` is called after `generation` was observed
+    async fn cancelled_since(&self, generation: u64) {
+        loop {
+            let notified = self.notify.notified();
+            if self.is_cancelled_since(generation) {
+                return;
+            }
+            notified.await;
         }
     }
 }
@@ -80,6 +159,10 @@ pub struct RangeMeasurement {
     pub timestamp: Instant,
     pub quality_score: f32,          // 0.0-1.0 quality indicator
     pub temperature_compensated: bool,
+    pub velocity_mps: Option<f32>,   // Radial velocity from Doppler, positive when closing
+    pub doppler_confidence: f32,     // 0.0-1.0 normalized echo autocorrelation
+    pub angle_of_arrival_degrees: Option<f32>, // Bearing from broadside, positive towards the second transducer
+    pub multipath_confidence: f32,   // Share of echo energy in the direct path, 1.0 when not assessed
 }
 
 /// Range categories for adaptive profiles
@@ -121,6 +204,39 @@ pub struct RangeEnvironmentalConditions {
     pub visibility_meters: f32,
 }
 
+impl RangeEnvironmentalConditions {
+    /// Speed of sound in m/s for these conditions.
+    ///
+    /// 331.3 + 0.606·T is the dry-air value at T °C; water vapour is lighter than
+    /// air, adding about 0.0124 m/s per percent relative humidity. Pressure drops
+    /// out for an ideal gas (density scales with it), and wind cancels to first
+    /// order over a round trip, so neither enters.
+    pub fn speed_of_sound(&self) -> f32 {
+        331.3 + 0.606 * self.temperature_celsius + 0.0124 * self.humidity_percent.clamp(0.0, 100.0)
+    }
+
+    /// Radial velocity (m/s, positive when closing) behind a Doppler shift of
+    /// `shift_hz` on a carrier of `carrier_hz`, for an echo that travels both ways
+    pub fn doppler_velocity(&self, shift_hz: f32, carrier_hz: f32) -> f32 {
+        self.speed_of_sound() * shift_hz / (2.0 * carrier_hz)
+    }
+
+    /// Bearing (degrees from broadside) of an echo reaching two receivers
+    /// `baseline_m` apart `tdoa_s` apart, from `sin θ = c·Δt / d`. A delay no
+    /// geometry can produce (|c·Δt| well beyond `d`) gives `None`.
+    pub fn angle_of_arrival(&self, tdoa_s: f32, baseline_m: f32) -> Option<f32> {
+        if baseline_m <= 0.0 || !tdoa_s.is_finite() {
+            return None;
+        }
+        let sin_theta = self.speed_of_sound() * tdoa_s / baseline_m;
+        // Allow for timing jitter right at endfire
+        if sin_theta.abs() > 1.05 {
+            return None;
+        }
+        Some(sin_theta.clamp(-1.0, 1.0).asin().to_degrees())
+    }
+}
+
 impl Default for RangeEnvironmentalConditions {
     fn default() -> Self {
         Self {
@@ -133,58 +249,94 @@ impl Default for RangeEnvironmentalConditions {
     }
 }
 
-/// Simple Kalman filter for distance estimation
-#[derive(Debug)]
-struct DistanceKalmanFilter {
-    // State: [distance, velocity]
+/// Range-rate variance the filter starts from, (m/s)²: nothing is known about
+/// the target's motion until a second measurement arrives
+const KALMAN_INITIAL_RATE_VARIANCE: f32 = 100.0;
+
+/// Constant-velocity Kalman filter over `[range, range_rate]`.
+///
+/// Unmodelled acceleration is white noise of standard deviation
+/// `process_noise` (m/s²); each range measurement has standard deviation
+/// `measurement_noise` (m). The first measurement initializes the range.
+#[derive(Debug, Clone)]
+pub struct DistanceKalmanFilter {
     state: [f32; 2],
-    covariance: [f32; 4], // 2x2 matrix flattened
+    covariance: [[f32; 2]; 2],
     process_noise: f32,
     measurement_noise: f32,
+    initialized: bool,
 }
 
 impl DistanceKalmanFilter {
-    fn new() -> Self {
+    pub fn new(process_noise: f32, measurement_noise: f32) -> Self {
         Self {
             state: [0.0; 2],
-            covariance: [1.0, 0.0, 0.0, 1.0], // Identity matrix
-            process_noise: 0.1,
-            measurement_noise: 2.0, // Distance measurement noise in meters
+            covariance: [[0.0; 2]; 2],
+            process_noise: process_noise.abs(),
+            measurement_noise: measurement_noise.abs(),
+            initialized: false,
         }
     }
 
-    fn predict(&mut self, dt: f32) {
-        // State transition: distance += velocity * dt
+    /// Advance the state `dt` seconds
+    pub fn predict(&mut self, dt: f32) {
+        if !self.initialized || dt <= 0.0 {
+            return;
+        }
         self.state[0] += self.state[1] * dt;
 
-        // Update covariance
-        self.covariance[0] += self.process_noise + 2.0 * self.covariance[1] * dt + self.covariance[3] * dt * dt;
-        self.covariance[1] += self.covariance[3] * dt;
-        self.covariance[2] += self.covariance[3] * dt;
-        self.covariance[3] += self.process_noise;
+        // P = F P Fᵀ + Q, with F = [[1, dt], [0, 1]]
+        let [[p00, p01], [p10, p11]] = self.covariance;
+        let q = self.process_noise * self.process_noise;
+        let (dt2, dt3, dt4) = (dt * dt, dt * dt * dt, dt * dt * dt * dt);
+        self.covariance = [
+            [p00 + dt * (p01 + p10) + dt2 * p11 + q * dt4 / 4.0, p01 + dt * p11 + q * dt3 / 2.0],
+            [p10 + dt * p11 + q * dt3 / 2.0, p11 + q * dt2],
+        ];
     }
 
-    fn update(&mut self, measurement: f32) {
-        let innovation = measurement - self.state[0];
-        let innovation_covariance = self.covariance[0] + self.measurement_noise;
+    /// Fold in a range measurement (m)
+    pub fn update(&mut self, measurement: f32) {
+        let r = self.measurement_noise * self.measurement_noise;
+        if !self.initialized {
+            self.state = [measurement, 0.0];
+            self.covariance = [[r, 0.0], [0.0, KALMAN_INITIAL_RATE_VARIANCE]];
+            self.initialized = true;
+            return;
+        }
 
-        let kalman_gain = [
-            self.covariance[0] / innovation_covariance,
-            self.covariance[2] / innovation_covariance,
+        let [[p00, p01], [p10, p11]] = self.covariance;
+        let innovation = measurement - self.state[0];
+        let innovation_covariance = p00 + r;
+        let gain = [p00 / innovation_covariance, p10 / innovation_covariance];
+
+        self.state[0] += gain[0] * innovation;
+        self.state[1] += gain[1] * innovation;
+        self.covariance = [
+            [(1.0 - gain[0]) * p00, (1.0 - gain[0]) * p01],
+            [p10 - gain[1] * p00, p11 - gain[1] * p01],
         ];
+    }
 
-        self.state[0] += kalman_gain[0] * innovation;
-        self.state[1] += kalman_gain[1] * innovation;
+    /// Filtered range (m)
+    pub fn get_distance(&self) -> f32 {
+        self.state[0]
+    }
 
-        let temp_cov = 1.0 - kalman_gain[0];
-        self.covariance[0] *= temp_cov;
-        self.covariance[1] *= temp_cov;
-        self.covariance[2] *= temp_cov;
-        self.covariance[3] *= temp_cov;
+    /// Filtered range rate (m/s), positive when the target recedes
+    pub fn range_rate(&self) -> f32 {
+        self.state[1]
     }
 
-    fn get_distance(&self) -> f32 {
-        self.state[0]
+    /// Range expected `dt_secs` from the last update, at the current range rate
+    pub fn predicted_range_in(&self, dt_secs: f32) -> f32 {
+        self.state[0] + self.state[1] * dt_secs
+    }
+}
+
+impl Default for DistanceKalmanFilter {
+    fn default() -> Self {
+        Self::new(0.3, 2.0)
     }
 }
 
@@ -206,6 +358,154 @@ impl Default for MultiFrequencyConfig {
     }
 }
 
+/// Frequency offset of an echo from `carrier_hz`, with its confidence
+///
+/// The echo is mixed down to complex baseband, low-passed over one carrier
+/// period, and its autocorrelation taken at half the capture length: the phase
+/// advance over that lag gives the shift, and the normalized magnitude (near 1
+/// for a clean tone, near 0 for noise) the confidence. Shifts beyond
+/// ±`sample_rate_hz / samples.len()` alias.
+pub fn estimate_doppler_shift(samples: &[f32], sample_rate_hz: f32, carrier_hz: f32) -> Option<(f32, f32)> {
+    if sample_rate_hz <= 0.0 || carrier_hz <= 0.0 || carrier_hz >= sample_rate_hz / 2.0 {
+        return None;
+    }
+    let omega = 2.0 * std::f32::consts::PI * carrier_hz / sample_rate_hz;
+    let mixed: Vec<(f32, f32)> = samples
+        .iter()
+        .enumerate()
+        .map(|(n, &x)| {
+            let phase = omega * n as f32;
+            (x * phase.cos(), -x * phase.sin())
+        })
+        .collect();
+
+    // Boxcar over one carrier period nulls the 2·carrier image
+    let period = ((sample_rate_hz / carrier_hz).round() as usize).max(1);
+    let baseband: Vec<(f32, f32)> = mixed
+        .windows(period)
+        .map(|window| window.iter().fold((0.0, 0.0), |(re, im), &(i, q)| (re + i, im + q)))
+        .collect();
+
+    let lag = baseband.len() / 2;
+    if lag == 0 {
+        return None;
+    }
+    let (mut re, mut im, mut energy) = (0.0f32, 0.0f32, 0.0f32);
+    for (&(i1, q1), &(i0, q0)) in baseband[lag..].iter().zip(&baseband) {
+        // z[n + lag] * conj(z[n])
+        re += i1 * i0 + q1 * q0;
+        im += q1 * i0 - i1 * q0;
+        energy += 0.5 * (i1 * i1 + q1 * q1 + i0 * i0 + q0 * q0);
+    }
+    if energy <= f32::EPSILON {
+        return None;
+    }
+
+    let shift_hz = im.atan2(re) * sample_rate_hz / (2.0 * std::f32::consts::PI * lag as f32);
+    let confidence = ((re * re + im * im).sqrt() / energy).clamp(0.0, 1.0);
+    Some((shift_hz, confidence))
+}
+
+/// Exponential decay `amplitude · e^(-(t - onset) / τ)` fitted to the trailing
+/// edge of an echo train: the wall reflections that follow the direct echo
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReverbDecayModel {
+    pub onset_s: f32,
+    pub amplitude: f32,
+    pub time_constant_s: f32,
+}
+
+impl ReverbDecayModel {
+    /// Least-squares fit of the log envelope from `onset_index` on. The edge is
+    /// taken as the running maximum towards the end of the frame, so ghost
+    /// echoes lie on the model rather than above it.
+    pub fn fit(envelope: &[f32], sample_rate_hz: f32, onset_index: usize) -> Option<Self> {
+        if sample_rate_hz <= 0.0 || onset_index >= envelope.len() {
+            return None;
+        }
+        let floor = envelope.iter().copied().fold(0.0f32, f32::max) * REVERB_NOISE_FLOOR_FRACTION;
+        let mut edge = envelope[onset_index..].to_vec();
+        for i in (0..edge.len() - 1).rev() {
+            edge[i] = edge[i].max(edge[i + 1]);
+        }
+
+        let (mut n, mut sum_t, mut sum_y, mut sum_tt, mut sum_ty) = (0.0f64, 0.0f64, 0.0f64, 0.0f64, 0.0f64);
+        // The edge never rises, so the first sample in the noise ends the tail
+        for (i, &level) in edge.iter().take_while(|&&level| level > floor).enumerate() {
+            let t = i as f64 / sample_rate_hz as f64;
+            let y = (level as f64).ln();
+            n += 1.0;
+            sum_t += t;
+            sum_y += y;
+            sum_tt += t * t;
+            sum_ty += t * y;
+        }
+        let denominator = n * sum_tt - sum_t * sum_t;
+        if n < 2.0 || denominator <= 0.0 {
+            return None;
+        }
+        let slope = (n * sum_ty - sum_t * sum_y) / denominator;
+        if slope >= 0.0 {
+            return None;
+        }
+
+        Some(Self {
+            onset_s: onset_index as f32 / sample_rate_hz,
+            amplitude: ((sum_y - slope * sum_t) / n).exp() as f32,
+            time_constant_s: (-1.0 / slope) as f32,
+        })
+    }
+
+    /// Modelled reverberation `t_s` after the pulse; nothing before the onset
+    pub fn level_at(&self, t_s: f32) -> f32 {
+        if t_s < self.onset_s {
+            return 0.0;
+        }
+        self.amplitude * (-(t_s - self.onset_s) / self.time_constant_s).exp()
+    }
+}
+
+/// Direct-path echo picked out of a reverberant echo train
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DirectPathEcho {
+    pub delay_s: f32,
+    /// Share of the envelope's energy in the direct pulse, 0.0-1.0
+    pub confidence: f32,
+}
+
+/// Find the direct-path echo in `envelope` once `reverb` is subtracted
+///
+/// The earliest residual sample reaching `DIRECT_PATH_PEAK_FRACTION` of the
+/// residual peak starts the direct pulse, which lasts `pulse_duration_s`.
+pub fn detect_direct_path(
+    envelope: &[f32],
+    sample_rate_hz: f32,
+    pulse_duration_s: f32,
+    reverb: Option<&ReverbDecayModel>,
+) -> Option<DirectPathEcho> {
+    if sample_rate_hz <= 0.0 {
+        return None;
+    }
+    let residual: Vec<f32> = envelope
+        .iter()
+        .enumerate()
+        .map(|(n, &level)| (level - reverb.map_or(0.0, |model| model.level_at(n as f32 / sample_rate_hz))).max(0.0))
+        .collect();
+    let peak = residual.iter().copied().fold(0.0f32, f32::max);
+    if peak <= f32::EPSILON {
+        return None;
+    }
+
+    let start = residual.iter().position(|&level| level >= peak * DIRECT_PATH_PEAK_FRACTION)?;
+    let end = (start + ((pulse_duration_s * sample_rate_hz).ceil() as usize).max(1)).min(residual.len());
+    let direct_energy: f32 = residual[start..end].iter().map(|level| level * level).sum();
+    let total_energy: f32 = envelope.iter().map(|level| level * level).sum();
+    Some(DirectPathEcho {
+        delay_s: start as f32 / sample_rate_hz,
+        confidence: (direct_energy / total_energy).clamp(0.0, 1.0),
+    })
+}
+
 /// Ultrasonic range detector using time-of-flight measurements
 #[derive(Debug)]
 pub struct RangeDetector {
@@ -214,8 +514,21 @@ pub struct RangeDetector {
     measurement_history: Arc<Mutex<VecDeque<RangeMeasurement>>>,
     environmental_conditions: Arc<Mutex<RangeEnvironmentalConditions>>,
     kalman_filter: Arc<Mutex<DistanceKalmanFilter>>,
+    kalman_tracking: bool,
+    reverb_model: Arc<Mutex<Option<ReverbDecayModel>>>,
     multi_freq_config: MultiFrequencyConfig,
     last_measurement_time: Arc<Mutex<Instant>>,
+    cancel_handle: RangeCancelHandle,
+    #[cfg(not(target_os = "android"))]
+    simulated_target_m: Arc<Mutex<Option<f32>>>,
+    #[cfg(not(target_os = "android"))]
+    simulated_returns_m: Arc<Mutex<Vec<f32>>>,
+    #[cfg(not(target_os = "android"))]
+    simulated_velocity_mps: Arc<Mutex<f32>>,
+    #[cfg(not(target_os = "android"))]
+    simulated_bearing_deg: Arc<Mutex<f32>>,
+    #[cfg(not(target_os = "android"))]
+    simulated_multipath: Arc<Mutex<Vec<(f32, f32)>>>,
 }
 
 impl RangeDetector {
@@ -226,9 +539,22 @@ impl RangeDetector {
             is_active: Arc::new(Mutex::new(false)),
             measurement_history: Arc::new(Mutex::new(VecDeque::with_capacity(100))),
             environmental_conditions: Arc::new(Mutex::new(RangeEnvironmentalConditions::default())),
-            kalman_filter: Arc::new(Mutex::new(DistanceKalmanFilter::new())),
+            kalman_filter: Arc::new(Mutex::new(DistanceKalmanFilter::default())),
+            kalman_tracking: false,
+            reverb_model: Arc::new(Mutex::new(None)),
             multi_freq_config: MultiFrequencyConfig::default(),
             last_measurement_time: Arc::new(Mutex::new(Instant::now())),
+            cancel_handle: RangeCancelHandle::default(),
+            #[cfg(not(target_os = "android"))]
+            simulated_target_m: Arc::new(Mutex::new(None)),
+            #[cfg(not(target_os = "android"))]
+            simulated_returns_m: Arc::new(Mutex::new(Vec::new())),
+            #[cfg(not(target_os = "android"))]
+            simulated_velocity_mps: Arc::new(Mutex::new(0.0)),
+            #[cfg(not(target_os = "android"))]
+            simulated_bearing_deg: Arc::new(Mutex::new(0.0)),
+            #[cfg(not(target_os = "android"))]
+            simulated_multipath: Arc::new(Mutex::new(Vec::new())),
         }
     }
 
@@ -239,9 +565,22 @@ impl RangeDetector {
             is_active: Arc::new(Mutex::new(false)),
             measurement_history: Arc::new(Mutex::new(VecDeque::with_capacity(100))),
             environmental_conditions: Arc::new(Mutex::new(RangeEnvironmentalConditions::default())),
-            kalman_filter: Arc::new(Mutex::new(DistanceKalmanFilter::new())),
+            kalman_filter: Arc::new(Mutex::new(DistanceKalmanFilter::default())),
+            kalman_tracking: false,
+            reverb_model: Arc::new(Mutex::new(None)),
             multi_freq_config: MultiFrequencyConfig::default(),
             last_measurement_time: Arc::new(Mutex::new(Instant::now())),
+            cancel_handle: RangeCancelHandle::default(),
+            #[cfg(not(target_os = "android"))]
+            simulated_target_m: Arc::new(Mutex::new(None)),
+            #[cfg(not(target_os = "android"))]
+            simulated_returns_m: Arc::new(Mutex::new(Vec::new())),
+            #[cfg(not(target_os = "android"))]
+            simulated_velocity_mps: Arc::new(Mutex::new(0.0)),
+            #[cfg(not(target_os = "android"))]
+            simulated_bearing_deg: Arc::new(Mutex::new(0.0)),
+            #[cfg(not(target_os = "android"))]
+            simulated_multipath: Arc::new(Mutex::new(Vec::new())),
         }
     }
 
@@ -264,21 +603,116 @@ impl RangeDetector {
         *self.is_active.lock().await
     }
 
+    /// Get the active ranging configuration
+    pub fn get_config(&self) -> &RangingConfig {
+        &self.config
+    }
+
+    /// Lower or raise the range ceiling (never below the minimum detectable range)
+    pub fn set_max_range_m(&mut self, max_range_m: f32) {
+        self.config.max_range_m = max_range_m.max(self.config.min_range_m);
+    }
+
+    /// Set the ambient temperature in the environmental conditions and the
+    /// config, recomputing the configured speed of sound from it
+    pub async fn set_temperature_celsius(&mut self, temperature_celsius: f32) {
+        let mut conditions = self.environmental_conditions.lock().await;
+        conditions.temperature_celsius = temperature_celsius;
+        self.config.temperature_celsius = temperature_celsius;
+        self.config.speed_of_sound_mps = conditions.speed_of_sound();
+    }
+
+    /// Handle that cancels in-flight measurements; obtain it before handing the
+    /// detector to a task that may hold its lock while measuring
+    pub fn cancel_handle(&self) -> RangeCancelHandle {
+        self.cancel_handle.clone()
+    }
+
+    /// Filter every `measure_distance` result through a fresh constant-velocity
+    /// Kalman filter; see `DistanceKalmanFilter` for the noise parameters
+    pub fn enable_kalman_tracking(&mut self, process_noise: f32, measurement_noise: f32) {
+        self.kalman_filter = Arc::new(Mutex::new(DistanceKalmanFilter::new(process_noise, measurement_noise)));
+        self.kalman_tracking = true;
+    }
+
+    /// Current range tracking state, once tracking is enabled
+    pub async fn kalman_state(&self) -> Option<DistanceKalmanFilter> {
+        if !self.kalman_tracking {
+            return None;
+        }
+        Some(self.kalman_filter.lock().await.clone())
+    }
+
+    /// Place a simulated target for the mock backend (`None` restores random echoes,
+    /// an infinite distance never echoes)
+    #[cfg(not(target_os = "android"))]
+    pub async fn set_simulated_target(&self, distance_m: Option<f32>) {
+        *self.simulated_target_m.lock().await = distance_m;
+    }
+
+    /// Place additional reflectors for the mock backend, seen only by `scan_for_intruders`
+    #[cfg(not(target_os = "android"))]
+    pub async fn set_simulated_returns(&self, distances_m: Vec<f32>) {
+        *self.simulated_returns_m.lock().await = distances_m;
+    }
+
+    /// Radial velocity of the simulated target, positive when closing
+    #[cfg(not(target_os = "android"))]
+    pub async fn set_simulated_velocity(&self, velocity_mps: f32) {
+        *self.simulated_velocity_mps.lock().await = velocity_mps;
+    }
+
+    /// Bearing of the simulated target from broadside, positive towards the second transducer
+    #[cfg(not(target_os = "android"))]
+    pub async fn set_simulated_bearing(&self, bearing_deg: f32) {
+        *self.simulated_bearing_deg.lock().await = bearing_deg;
+    }
+
+    /// Ghost echoes of the simulated target as (range multiple, amplitude relative
+    /// to the direct echo); the mock driver reports the strongest return
+    #[cfg(not(target_os = "android"))]
+    pub async fn set_simulated_multipath(&self, ghosts: Vec<(f32, f32)>) {
+        *self.simulated_multipath.lock().await = ghosts;
+    }
+
+    /// Await an echo, bounded by the configured echo timeout and the cancel handle
+    async fn await_echo<T, F>(&self, generation: u64, echo: F) -> Result<T, RangeDetectorError>
+    where
+        F: Future<Output = Result<T, RangeDetectorError>>,
+    {
+        tokio::select! {
+            result = tokio::time::timeout(self.config.echo_timeout(), echo) => {
+                result.map_err(|_| RangeDetectorError::NoEcho)?
+            }
+            _ = self.cancel_handle.cancelled_since(generation) => Err(RangeDetectorError::Cancelled),
+        }
+    }
+
     /// Perform a single range measurement
     pub async fn measure_distance(&self) -> Result<RangeMeasurement, RangeDetectorError> {
+        let generation = self.cancel_handle.generation();
+        self.measure_distance_since(generation).await
+    }
+
+    async fn measure_distance_since(&self, generation: u64) -> Result<RangeMeasurement, RangeDetectorError> {
         if !self.is_active().await {
             return Err(RangeDetectorError::HardwareInitFailed);
         }
 
         // Update speed of sound based on environmental conditions
-        let speed_of_sound = self.calculate_speed_of_sound().await;
+        let speed_of_sound = self.speed_of_sound().await;
 
         // Transmit ultrasonic pulse
         self.transmit_pulse().await?;
 
         // Listen for echo
-        let echo_time_us = self.listen_for_echo().await?;
+        let echo_time_us = self.await_echo(generation, self.listen_for_echo()).await?;
         let signal_strength = self.get_signal_strength().await?;
+        let (echo_time_us, multipath_confidence) = if self.config.multipath_suppression {
+            self.suppress_multipath(echo_time_us).await
+        } else {
+            (echo_time_us, 1.0)
+        };
 
         // Validate signal strength
         if signal_strength < self.config.signal_threshold {
@@ -298,6 +732,10 @@ impl RangeDetector {
 
         // Calculate quality score based on signal strength and expected attenuation
         let quality_score = self.calculate_quality_score(distance_m, signal_strength);
+        let distance_m = if self.kalman_tracking { self.track(distance_m).await } else { distance_m };
+        let (velocity_mps, doppler_confidence) =
+            self.measure_doppler(self.config.pulse_frequency_hz, self.config.pulse_duration_us).await;
+        let angle_of_arrival_degrees = self.measure_angle_of_arrival(echo_time_us).await;
 
         let measurement = RangeMeasurement {
             distance_m,
@@ -305,6 +743,10 @@ impl RangeDetector {
             timestamp: Instant::now(),
             quality_score,
             temperature_compensated: true,
+            velocity_mps,
+            doppler_confidence,
+            angle_of_arrival_degrees,
+            multipath_confidence,
         };
 
         // Store measurement in history
@@ -313,13 +755,46 @@ impl RangeDetector {
         Ok(measurement)
     }
 
+    /// Distances of every in-range return except the known peer, nearest first
+    ///
+    /// Returns within `peer_return_tolerance_m` of `exclude_peer_range` are taken
+    /// to be the peer; pass `f32::NAN` to report every return. A listening window
+    /// that closes without an echo is a clear path. An inactive detector, a failed
+    /// pulse or a cancelled scan is an error, since nothing was checked.
+    pub async fn scan_for_intruders(&self, exclude_peer_range: f32) -> Result<Vec<f32>, RangeDetectorError> {
+        if !self.is_active().await {
+            return Err(RangeDetectorError::HardwareInitFailed);
+        }
+
+        let generation = self.cancel_handle.generation();
+        let speed_of_sound = self.speed_of_sound().await;
+        self.transmit_pulse().await?;
+        let echo_times_us = match self.await_echo(generation, self.listen_for_echoes(speed_of_sound)).await {
+            Ok(times) => times,
+            Err(RangeDetectorError::NoEcho) => Vec::new(),
+            Err(e) => return Err(e),
+        };
+
+        let tolerance = self.config.peer_return_tolerance_m;
+        let mut intruders: Vec<f32> = echo_times_us
+            .into_iter()
+            .map(|echo_time_us| (echo_time_us * speed_of_sound as f64 / 1_000_000.0 / 2.0) as f32)
+            .filter(|distance_m| *distance_m >= self.config.min_range_m && *distance_m <= self.config.max_range_m)
+            .filter(|distance_m| exclude_peer_range.is_nan() || (distance_m - exclude_peer_range).abs() > tolerance)
+            .collect();
+        intruders.sort_by(|a, b| a.total_cmp(b));
+        Ok(intruders)
+    }
+
     /// Perform multiple measurements and return averaged result
     pub async fn measure_distance_averaged(&self) -> Result<RangeMeasurement, RangeDetectorError> {
+        let generation = self.cancel_handle.generation();
         let mut measurements = Vec::new();
 
         for _ in 0..self.config.averaging_samples {
-            match self.measure_distance().await {
+            match self.measure_distance_since(generation).await {
                 Ok(measurement) => measurements.push(measurement),
+                Err(RangeDetectorError::Cancelled) => return Err(RangeDetectorError::Cancelled),
                 Err(e) => {
                     // Continue with other measurements, but if too many fail, return error
                     if measurements.len() < self.config.averaging_samples / 2 {
@@ -330,6 +805,9 @@ impl RangeDetector {
 
             // Small delay between measurements
             tokio::time::sleep(Duration::from_millis(50)).await;
+            if self.cancel_handle.is_cancelled_since(generation) {
+                return Err(RangeDetectorError::Cancelled);
+            }
         }
 
         if measurements.is_empty() {
@@ -349,6 +827,7 @@ impl RangeDetector {
         let avg_quality = measurements.iter()
             .map(|m| m.quality_score)
             .sum::<f32>() / measurements.len() as f32;
+        let (velocity_mps, doppler_confidence) = average_doppler(&measurements);
 
         Ok(RangeMeasurement {
             distance_m: avg_distance,
@@ -356,6 +835,10 @@ impl RangeDetector {
             timestamp: Instant::now(),
             quality_score: avg_quality,
             temperature_compensated: true,
+            velocity_mps,
+            doppler_confidence,
+            angle_of_arrival_degrees: average_angle(&measurements),
+            multipath_confidence: average_multipath_confidence(&measurements),
         })
     }
 
@@ -365,9 +848,11 @@ impl RangeDetector {
             return Err(RangeDetectorError::HardwareInitFailed);
         }
 
+        let generation = self.cancel_handle.generation();
         let mut frequency_measurements = Vec::new();
         let mut total_weight = 0.0;
         let mut weighted_distance = 0.0;
+        let mut all_silent = true;
 
         // Measure at multiple frequencies simultaneously for speed
         for (i, &frequency) in self.multi_freq_config.frequencies.iter().enumerate() {
@@ -375,38 +860,31 @@ impl RangeDetector {
             let weight = self.multi_freq_config.weights[i];
 
             // Quick measurement at this frequency
-            match self.measure_at_frequency(frequency, pulse_duration).await {
+            match self.await_echo(generation, self.measure_at_frequency(frequency, pulse_duration)).await {
                 Ok(measurement) => {
                     let distance = measurement.distance_m;
                     frequency_measurements.push(measurement);
                     weighted_distance += distance * weight;
                     total_weight += weight;
                 }
-                Err(_) => {
+                Err(RangeDetectorError::Cancelled) => return Err(RangeDetectorError::Cancelled),
+                Err(e) => {
                     // Skip failed measurements but continue with others
+                    all_silent &= matches!(e, RangeDetectorError::NoEcho);
                     continue;
                 }
             }
         }
 
         if frequency_measurements.is_empty() {
-            return Err(RangeDetectorError::EchoDetectionFailed);
+            return Err(if all_silent { RangeDetectorError::NoEcho } else { RangeDetectorError::EchoDetectionFailed });
         }
 
         let avg_distance = weighted_distance / total_weight;
 
         // Update Kalman filter
-        let mut kalman = self.kalman_filter.lock().await;
+        let filtered_distance = self.track(avg_distance).await;
         let now = Instant::now();
-        let dt = *self.last_measurement_time.lock().await;
-        let dt_seconds = now.duration_since(dt).as_secs_f32();
-
-        kalman.predict(dt_seconds);
-        kalman.update(avg_distance);
-
-        *self.last_measurement_time.lock().await = now;
-
-        let filtered_distance = kalman.get_distance();
 
         // Calculate combined quality score
         let avg_signal = frequency_measurements.iter()
@@ -416,6 +894,7 @@ impl RangeDetector {
         let quality_score = frequency_measurements.iter()
             .map(|m| m.quality_score)
             .sum::<f32>() / frequency_measurements.len() as f32;
+        let (velocity_mps, doppler_confidence) = average_doppler(&frequency_measurements);
 
         let measurement = RangeMeasurement {
             distance_m: filtered_distance,
@@ -423,6 +902,10 @@ impl RangeDetector {
             timestamp: now,
             quality_score,
             temperature_compensated: true,
+            velocity_mps,
+            doppler_confidence,
+            angle_of_arrival_degrees: average_angle(&frequency_measurements),
+            multipath_confidence: average_multipath_confidence(&frequency_measurements),
         };
 
         // Store in history
@@ -431,9 +914,21 @@ impl RangeDetector {
         Ok(measurement)
     }
 
+    /// Predict the tracked range up to now and fold in `distance_m`; returns the
+    /// filtered range
+    async fn track(&self, distance_m: f32) -> f32 {
+        let mut kalman = self.kalman_filter.lock().await;
+        let now = Instant::now();
+        let mut last_measurement_time = self.last_measurement_time.lock().await;
+        kalman.predict(now.duration_since(*last_measurement_time).as_secs_f32());
+        kalman.update(distance_m);
+        *last_measurement_time = now;
+        kalman.get_distance()
+    }
+
     /// Measure distance at a specific frequency
     async fn measure_at_frequency(&self, frequency: f32, pulse_duration: u32) -> Result<RangeMeasurement, RangeDetectorError> {
-        let speed_of_sound = self.calculate_speed_of_sound().await;
+        let speed_of_sound = self.speed_of_sound().await;
 
         // Transmit pulse at specific frequency
         #[cfg(target_os = "android")]
@@ -457,7 +952,7 @@ impl RangeDetector {
 
             let echo_time = unsafe { ultrasonic_get_echo_time() };
             if echo_time <= 0.0 {
-                return Err(RangeDetectorError::Timeout);
+                return Err(RangeDetectorError::NoEcho);
             }
 
             let signal_strength = unsafe { ultrasonic_get_signal_strength() };
@@ -475,6 +970,7 @@ impl RangeDetector {
             }
 
             let quality_score = self.calculate_quality_score(distance_m, signal_strength);
+            let (velocity_mps, doppler_confidence) = self.measure_doppler(frequency, pulse_duration).await;
 
             Ok(RangeMeasurement {
                 distance_m,
@@ -482,6 +978,10 @@ impl RangeDetector {
                 timestamp: Instant::now(),
                 quality_score,
                 temperature_compensated: true,
+                velocity_mps,
+                doppler_confidence,
+                angle_of_arrival_degrees: self.measure_angle_of_arrival(echo_time).await,
+                multipath_confidence: 1.0,
             })
         }
 
@@ -489,6 +989,22 @@ impl RangeDetector {
         {
             // Mock implementation for fast ranging
             use rand::Rng;
+            if let Some(echo_time_us) = self.simulated_echo_us(speed_of_sound).await {
+                let distance_m = (echo_time_us * speed_of_sound as f64 / 1_000_000.0 / 2.0) as f32;
+                let (velocity_mps, doppler_confidence) = self.measure_doppler(frequency, pulse_duration).await;
+                return Ok(RangeMeasurement {
+                    distance_m,
+                    signal_strength: 0.8,
+                    timestamp: Instant::now(),
+                    quality_score: self.calculate_quality_score(distance_m, 0.8),
+                    temperature_compensated: true,
+                    velocity_mps,
+                    doppler_confidence,
+                    angle_of_arrival_degrees: self.measure_angle_of_arrival(echo_time_us).await,
+                    multipath_confidence: 1.0,
+                });
+            }
+
             let mut rng = rand::thread_rng();
             let mock_distance = rng.gen_range(50.0..150.0);
             let round_trip_time_us = (mock_distance * 2.0 / speed_of_sound) * 1_000_000.0;
@@ -502,6 +1018,10 @@ impl RangeDetector {
                 timestamp: Instant::now(),
                 quality_score: rng.gen_range(0.7..0.95),
                 temperature_compensated: true,
+                velocity_mps: None,
+                doppler_confidence: 0.0,
+                angle_of_arrival_degrees: None,
+                multipath_confidence: 1.0,
             })
         }
     }
@@ -538,7 +1058,7 @@ impl RangeDetector {
 
             let echo_time = unsafe { ultrasonic_get_echo_time() };
             if echo_time <= 0.0 {
-                return Err(RangeDetectorError::Timeout);
+                return Err(RangeDetectorError::NoEcho);
             }
 
             Ok(echo_time)
@@ -546,17 +1066,204 @@ impl RangeDetector {
 
         #[cfg(not(target_os = "android"))]
         {
+            if let Some(echo_time_us) = self.simulated_echo_us(self.speed_of_sound().await).await {
+                return Ok(echo_time_us);
+            }
+
             // Mock implementation - simulate echo detection
             // Generate realistic round-trip time for 50-150m range
             use rand::Rng;
-            let mut rng = rand::thread_rng();
-            let mock_distance = rng.gen_range(50.0..150.0);
-            let speed_of_sound = 343.0; // m/s
-            let round_trip_time_us = (mock_distance * 2.0 / speed_of_sound) * 1_000_000.0;
+            let mock_distance = rand::thread_rng().gen_range(50.0..150.0);
+            let speed_of_sound = self.speed_of_sound().await;
+            let round_trip_time_us = (mock_distance * 2.0 / speed_of_sound) as f64 * 1_000_000.0;
             Ok(round_trip_time_us)
         }
     }
 
+    /// Listen for every echo of one pulse and return their times in microseconds
+    async fn listen_for_echoes(&self, speed_of_sound: f32) -> Result<Vec<f64>, RangeDetectorError> {
+        #[cfg(target_os = "android")]
+        {
+            // The transducer driver only reports the first return
+            let _ = speed_of_sound;
+            Ok(vec![self.listen_for_echo().await?])
+        }
+
+        #[cfg(not(target_os = "android"))]
+        {
+            let mut distances_m: Vec<f32> = self.simulated_returns_m.lock().await.clone();
+            distances_m.extend(*self.simulated_target_m.lock().await);
+            Ok(distances_m
+                .into_iter()
+                .filter(|distance_m| distance_m.is_finite())
+                .map(|distance_m| distance_m as f64 * 2.0 / speed_of_sound as f64 * 1_000_000.0)
+                .collect())
+        }
+    }
+
+    /// Echo from the simulated target, arriving after its real round-trip time
+    #[cfg(not(target_os = "android"))]
+    async fn simulated_echo_us(&self, speed_of_sound: f32) -> Option<f64> {
+        let distance_m = (*self.simulated_target_m.lock().await)?;
+        if !distance_m.is_finite() {
+            // Nothing in front of the transducer: the echo never comes back
+            std::future::pending::<()>().await;
+        }
+
+        // A ghost stronger than the direct echo is what a threshold detector reports
+        let range_multiple = self.simulated_multipath.lock().await.iter()
+            .filter(|(_, amplitude)| *amplitude > 1.0)
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map_or(1.0, |(multiple, _)| *multiple);
+        let round_trip_s = (distance_m * range_multiple) as f64 * 2.0 / speed_of_sound as f64;
+        tokio::time::sleep(Duration::from_secs_f64(round_trip_s)).await;
+        Some(round_trip_s * 1_000_000.0)
+    }
+
+    /// Radial velocity of the last echo and the confidence in it; the velocity
+    /// is withheld below `DOPPLER_MIN_CONFIDENCE`
+    async fn measure_doppler(&self, carrier_hz: f32, pulse_duration_us: u32) -> (Option<f32>, f32) {
+        let Some((samples, sample_rate_hz)) = self.capture_echo_samples(carrier_hz, pulse_duration_us).await else {
+            return (None, 0.0);
+        };
+        let Some((shift_hz, confidence)) = estimate_doppler_shift(&samples, sample_rate_hz, carrier_hz) else {
+            return (None, 0.0);
+        };
+        let velocity_mps = self.environmental_conditions.lock().await.doppler_velocity(shift_hz, carrier_hz);
+        ((confidence >= DOPPLER_MIN_CONFIDENCE).then_some(velocity_mps), confidence)
+    }
+
+    /// Bearing of the echo that reached the primary transducer after
+    /// `echo_time_us`, when a second transducer is configured
+    async fn measure_angle_of_arrival(&self, echo_time_us: f64) -> Option<f32> {
+        let baseline_m = self.config.dual_transducer_baseline_m?;
+        let secondary_us = self.secondary_echo_time_us(echo_time_us, baseline_m).await?;
+        let tdoa_s = ((echo_time_us - secondary_us) / 1_000_000.0) as f32;
+        self.environmental_conditions.lock().await.angle_of_arrival(tdoa_s, baseline_m)
+    }
+
+    /// Arrival time of the same echo at the second transducer
+    #[cfg(target_os = "android")]
+    async fn secondary_echo_time_us(&self, _echo_time_us: f64, _baseline_m: f32) -> Option<f64> {
+        let echo_time = unsafe { ultrasonic_get_secondary_echo_time() };
+        (echo_time > 0.0).then_some(echo_time)
+    }
+
+    /// The simulated echo reaches the second transducer earlier by `d·sin θ / c`
+    #[cfg(not(target_os = "android"))]
+    async fn secondary_echo_time_us(&self, echo_time_us: f64, baseline_m: f32) -> Option<f64> {
+        let bearing = self.simulated_bearing_deg.lock().await.to_radians();
+        let path_difference_m = baseline_m * bearing.sin();
+        Some(echo_time_us - (path_difference_m / self.speed_of_sound().await) as f64 * 1_000_000.0)
+    }
+
+    /// Re-time the echo on its envelope with the reverberation tail of the
+    /// previous pulse subtracted, then fit this pulse's tail for the next one.
+    /// Returns the direct-path echo time and the share of energy it carries.
+    async fn suppress_multipath(&self, echo_time_us: f64) -> (f64, f32) {
+        let Some((envelope, sample_rate_hz)) = self.capture_echo_envelope().await else {
+            return (echo_time_us, 1.0);
+        };
+        let pulse_duration_s = self.config.pulse_duration_us as f32 * 1e-6;
+        let pulse_len = ((pulse_duration_s * sample_rate_hz).ceil() as usize).max(1);
+
+        // The trailing edge starts after the first arrival strong enough to be an echo
+        let fitted = envelope.iter()
+            .position(|&level| level >= self.config.signal_threshold)
+            .and_then(|first| ReverbDecayModel::fit(&envelope, sample_rate_hz, first + pulse_len));
+        let mut reverb_model = self.reverb_model.lock().await;
+        // The first pulse has no predecessor and is cleaned with its own fit
+        let reverb = reverb_model.or(fitted);
+        *reverb_model = fitted;
+
+        match detect_direct_path(&envelope, sample_rate_hz, pulse_duration_s, reverb.as_ref()) {
+            Some(direct) => (direct.delay_s as f64 * 1_000_000.0, direct.confidence),
+            None => (echo_time_us, 1.0),
+        }
+    }
+
+    /// Amplitude envelope of the last pulse's echo train and its sample rate
+    #[cfg(target_os = "android")]
+    async fn capture_echo_envelope(&self) -> Option<(Vec<f32>, f32)> {
+        let mut buffer = vec![0.0f32; ECHO_ENVELOPE_MAX_SAMPLES];
+        let mut sample_rate_hz = 0.0f32;
+        let count = unsafe {
+            ultrasonic_get_echo_envelope(buffer.as_mut_ptr(), buffer.len() as u32, &mut sample_rate_hz)
+        };
+        if count <= 0 || sample_rate_hz <= 0.0 {
+            return None;
+        }
+        buffer.truncate(count as usize);
+        Some((buffer, sample_rate_hz))
+    }
+
+    /// Echo train of the simulated target over one echo timeout: the direct
+    /// echo, its ghosts and, when there are ghosts, a diffuse reverberant tail
+    #[cfg(not(target_os = "android"))]
+    async fn capture_echo_envelope(&self) -> Option<(Vec<f32>, f32)> {
+        use rand::Rng;
+        let distance_m = (*self.simulated_target_m.lock().await).filter(|d| d.is_finite())?;
+        let ghosts = self.simulated_multipath.lock().await.clone();
+        let speed_of_sound = self.speed_of_sound().await;
+        let pulse_duration_s = self.config.pulse_duration_us as f32 * 1e-6;
+
+        let direct_s = distance_m * 2.0 / speed_of_sound;
+        let mut returns = vec![(direct_s, 1.0)];
+        returns.extend(ghosts.iter().map(|&(multiple, amplitude)| (direct_s * multiple, amplitude)));
+
+        let mut rng = rand::thread_rng();
+        let count = (self.config.echo_timeout().as_secs_f32() * SIMULATED_ENVELOPE_SAMPLE_RATE_HZ) as usize;
+        let envelope = (0..count)
+            .map(|n| {
+                let t = n as f32 / SIMULATED_ENVELOPE_SAMPLE_RATE_HZ;
+                let pulses: f32 = returns.iter()
+                    .filter(|(arrival_s, _)| t >= *arrival_s && t < arrival_s + pulse_duration_s)
+                    .map(|(_, amplitude)| amplitude)
+                    .sum();
+                let tail_s = t - direct_s - pulse_duration_s;
+                let diffuse = if ghosts.is_empty() || tail_s < 0.0 { 0.0 } else { 0.2 * (-tail_s / 0.05).exp() };
+                pulses + diffuse + rng.gen_range(0.0..0.005)
+            })
+            .collect();
+        Some((envelope, SIMULATED_ENVELOPE_SAMPLE_RATE_HZ))
+    }
+
+    /// Raw samples of the last echo and their sample rate
+    #[cfg(target_os = "android")]
+    async fn capture_echo_samples(&self, _carrier_hz: f32, pulse_duration_us: u32) -> Option<(Vec<f32>, f32)> {
+        // Room for the pulse at up to 1 MS/s
+        let mut buffer = vec![0.0f32; pulse_duration_us.max(1) as usize];
+        let mut sample_rate_hz = 0.0f32;
+        let count = unsafe {
+            ultrasonic_get_echo_samples(buffer.as_mut_ptr(), buffer.len() as u32, &mut sample_rate_hz)
+        };
+        if count <= 0 || sample_rate_hz <= 0.0 {
+            return None;
+        }
+        buffer.truncate(count as usize);
+        Some((buffer, sample_rate_hz))
+    }
+
+    /// Echo of one pulse from the simulated target, shifted by its velocity and
+    /// lightly noisy
+    #[cfg(not(target_os = "android"))]
+    async fn capture_echo_samples(&self, carrier_hz: f32, pulse_duration_us: u32) -> Option<(Vec<f32>, f32)> {
+        use rand::Rng;
+        let velocity_mps = *self.simulated_velocity_mps.lock().await;
+        let speed_of_sound = self.speed_of_sound().await;
+        let echo_hz = carrier_hz * (speed_of_sound + velocity_mps) / (speed_of_sound - velocity_mps);
+
+        let mut rng = rand::thread_rng();
+        let count = (pulse_duration_us as f32 * 1e-6 * SIMULATED_ECHO_SAMPLE_RATE_HZ) as usize;
+        let samples = (0..count)
+            .map(|n| {
+                let t = n as f32 / SIMULATED_ECHO_SAMPLE_RATE_HZ;
+                (2.0 * std::f32::consts::PI * echo_hz * t).sin() + rng.gen_range(-0.02..0.02)
+            })
+            .collect();
+        Some((samples, SIMULATED_ECHO_SAMPLE_RATE_HZ))
+    }
+
     /// Get signal strength of received echo
     async fn get_signal_strength(&self) -> Result<f32, RangeDetectorError> {
         #[cfg(target_os = "android")]
@@ -574,29 +1281,10 @@ impl RangeDetector {
         }
     }
 
-    /// Calculate speed of sound based on environmental conditions
-    async fn calculate_speed_of_sound(&self) -> f32 {
-        let env = self.environmental_conditions.lock().await;
-
-        // Enhanced speed of sound calculation
-        // Base formula: v = 331.3 + 0.606 * T (m/s at T°C)
-        let base_speed = 331.3 + 0.606 * env.temperature_celsius;
-
-        // Humidity correction using more accurate formula
-        // The speed increases with humidity due to molecular weight effects
-        let humidity_factor = 1.0 + 0.000012 * env.humidity_percent * env.humidity_percent.sqrt();
-        let humidity_corrected = base_speed * humidity_factor;
-
-        // Pressure correction (ideal gas law)
-        // v ∝ √(γP/ρ) where γ is adiabatic index, P is pressure, ρ is density
-        let pressure_factor = (env.pressure_hpa / 1013.25).sqrt();
-        let pressure_corrected = humidity_corrected * pressure_factor;
-
-        // Wind correction (headwind increases effective speed)
-        // This is a simplified model - in reality wind affects the medium differently
-        let wind_correction = 0.001 * env.wind_speed_mps * env.wind_speed_mps.signum(); // Small correction
-
-        pressure_corrected + wind_correction
+    /// Speed of sound in m/s under the current environmental conditions, used
+    /// to turn echo times into distances
+    pub async fn speed_of_sound(&self) -> f32 {
+        self.environmental_conditions.lock().await.speed_of_sound()
     }
 
     /// Calculate measurement quality score
@@ -631,10 +1319,6 @@ impl RangeDetector {
     /// Update environmental conditions for compensation
     pub async fn update_environmental_conditions(&self, conditions: RangeEnvironmentalConditions) {
         *self.environmental_conditions.lock().await = conditions;
-
-        // Update speed of sound in config
-        let speed_of_sound = self.calculate_speed_of_sound().await;
-        // Note: In a real implementation, this would update the config atomically
     }
 
     /// Get current environmental conditions
@@ -655,6 +1339,30 @@ impl RangeDetector {
     }
 }
 
+/// Confidence-weighted velocity over the measurements that carried one, and
+/// the mean confidence of all of them
+fn average_doppler(measurements: &[RangeMeasurement]) -> (Option<f32>, f32) {
+    let weight: f32 = measurements.iter().filter(|m| m.velocity_mps.is_some()).map(|m| m.doppler_confidence).sum();
+    let velocity_mps = (weight > 0.0).then(|| {
+        measurements.iter()
+            .filter_map(|m| m.velocity_mps.map(|v| v * m.doppler_confidence))
+            .sum::<f32>() / weight
+    });
+    let confidence = measurements.iter().map(|m| m.doppler_confidence).sum::<f32>() / measurements.len().max(1) as f32;
+    (velocity_mps, confidence)
+}
+
+/// Mean direct-path share over `measurements`
+fn average_multipath_confidence(measurements: &[RangeMeasurement]) -> f32 {
+    measurements.iter().map(|m| m.multipath_confidence).sum::<f32>() / measurements.len().max(1) as f32
+}
+
+/// Mean bearing over the measurements that carried one
+fn average_angle(measurements: &[RangeMeasurement]) -> Option<f32> {
+    let angles: Vec<f32> = measurements.iter().filter_map(|m| m.angle_of_arrival_degrees).collect();
+    (!angles.is_empty()).then(|| angles.iter().sum::<f32>() / angles.len() as f32)
+}
+
 impl Default for RangeDetector {
     fn default() -> Self {
         Self::new()
@@ -700,6 +1408,10 @@ mod tests {
             timestamp: Instant::now(),
             quality_score: 0.9,
             temperature_compensated: true,
+            velocity_mps: None,
+            doppler_confidence: 0.0,
+            angle_of_arrival_degrees: None,
+            multipath_confidence: 1.0,
         };
 
         detector.store_measurement(measurement).await;
@@ -727,4 +1439,265 @@ mod tests {
         assert_eq!(retrieved.temperature_celsius, 30.0);
         assert_eq!(retrieved.humidity_percent, 70.0);
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_speed_of_sound_matches_reference_values() {
+        let dry = |temperature_celsius| RangeEnvironmentalConditions {
+            temperature_celsius,
+            humidity_percent: 0.0,
+            ..RangeEnvironmentalConditions::default()
+        };
+        // Dry-air reference values (m/s)
+        for (temperature, reference) in [(-10.0, 325.2), (0.0, 331.3), (20.0, 343.2), (30.0, 349.0)] {
+            let c = dry(temperature).speed_of_sound();
+            assert!((c - reference).abs() < 0.6, "{temperature} °C: {c} vs {reference}");
+        }
+        // Humid air is faster: 20 °C at 50% RH is ~344.0 m/s
+        let humid = RangeEnvironmentalConditions { humidity_percent: 50.0, ..dry(20.0) };
+        assert!((humid.speed_of_sound() - 344.0).abs() < 0.3);
+
+        // The detector converts with the conditions it was last given; a 20 °C swing
+        // is ~7 m at 200 m
+        let detector = RangeDetector::new();
+        detector.update_environmental_conditions(dry(0.0)).await;
+        let cold = detector.speed_of_sound().await;
+        detector.update_environmental_conditions(dry(20.0)).await;
+        let warm = detector.speed_of_sound().await;
+        assert!((200.0 * (warm / cold - 1.0) - 7.3).abs() < 0.1);
+    }
+
+    fn short_range_config() -> RangingConfig {
+        RangingConfig {
+            min_range_m: 1.0,
+            max_range_m: 20.0,
+            listening_timeout_ms: 100,
+            ..RangingConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_target_beyond_max_range_times_out() {
+        let mut detector = RangeDetector::with_config(short_range_config());
+        detector.initialize().await.unwrap();
+
+        // Echo from 500m would take ~3s; the window for 20m is ~120ms
+        detector.set_simulated_target(Some(500.0)).await;
+        let window = short_range_config().echo_timeout();
+
+        let start = Instant::now();
+        assert!(matches!(detector.measure_distance().await, Err(RangeDetectorError::NoEcho)));
+        assert!(start.elapsed() < window + Duration::from_millis(200));
+
+        let start = Instant::now();
+        assert!(matches!(detector.measure_distance_averaged().await, Err(RangeDetectorError::NoEcho)));
+        assert!(matches!(detector.measure_distance_fast().await, Err(RangeDetectorError::NoEcho)));
+        assert!(start.elapsed() < window * 4 + Duration::from_millis(500));
+
+        // A target inside the window is still measured
+        detector.set_simulated_target(Some(15.0)).await;
+        let measurement = detector.measure_distance().await.unwrap();
+        assert!((measurement.distance_m - 15.0).abs() < 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_measurement_cancellation() {
+        let mut detector = RangeDetector::with_config(RangingConfig {
+            listening_timeout_ms: 10_000,
+            ..RangingConfig::default()
+        });
+        detector.initialize().await.unwrap();
+        detector.set_simulated_target(Some(f32::INFINITY)).await;
+
+        let handle = detector.cancel_handle();
+        let canceller = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            handle.cancel();
+        });
+
+        let start = Instant::now();
+        assert!(matches!(detector.measure_distance().await, Err(RangeDetectorError::Cancelled)));
+        assert!(start.elapsed() < Duration::from_secs(1));
+        canceller.await.unwrap();
+
+        // Cancellation does not stick to later measurements
+        detector.set_simulated_target(Some(40.0)).await;
+        assert!(detector.measure_distance().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_scan_for_intruders_excludes_peer_return() {
+        let mut detector = RangeDetector::with_config(short_range_config());
+        // Nothing is checked before the hardware is up, which is not a clear path
+        assert!(matches!(detector.scan_for_intruders(15.0).await, Err(RangeDetectorError::HardwareInitFailed)));
+        detector.initialize().await.unwrap();
+        detector.set_simulated_target(Some(15.0)).await;
+        assert!(detector.scan_for_intruders(15.0).await.unwrap().is_empty());
+
+        // A second reflector in front of the peer, plus one beyond max range
+        detector.set_simulated_returns(vec![6.0, 80.0]).await;
+        let intruders = detector.scan_for_intruders(15.0).await.unwrap();
+        assert_eq!(intruders.len(), 1);
+        assert!((intruders[0] - 6.0).abs() < 0.5);
+
+        // Without a known peer every return is reported
+        assert_eq!(detector.scan_for_intruders(f32::NAN).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_doppler_velocity_from_echo_shift() {
+        // A clean tone 700 Hz above a 40 kHz carrier
+        let fs = 160_000.0;
+        let tone: Vec<f32> = (0..64)
+            .map(|n| (2.0 * std::f32::consts::PI * 40_700.0 * n as f32 / fs).sin())
+            .collect();
+        let (shift, confidence) = estimate_doppler_shift(&tone, fs, 40_000.0).unwrap();
+        assert!((shift - 700.0).abs() < 20.0, "{shift}");
+        assert!(confidence > 0.9);
+        assert!(estimate_doppler_shift(&[0.0; 64], fs, 40_000.0).is_none());
+
+        // v = c·Δf / 2f, with c from the conditions
+        let conditions = RangeEnvironmentalConditions::default();
+        let expected = conditions.speed_of_sound() * 700.0 / 80_000.0;
+        assert!((conditions.doppler_velocity(700.0, 40_000.0) - expected).abs() < 1e-4);
+
+        let mut detector = RangeDetector::with_config(short_range_config());
+        detector.initialize().await.unwrap();
+        detector.set_simulated_target(Some(15.0)).await;
+        for velocity in [0.0, 2.5, -4.0] {
+            detector.set_simulated_velocity(velocity).await;
+            let measurement = detector.measure_distance().await.unwrap();
+            let measured = measurement.velocity_mps.unwrap();
+            assert!((measured - velocity).abs() < 0.3, "{measured} vs {velocity}");
+            assert!(measurement.doppler_confidence > DOPPLER_MIN_CONFIDENCE);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_angle_of_arrival_from_tdoa() {
+        let conditions = RangeEnvironmentalConditions::default();
+        let c = conditions.speed_of_sound();
+        // Half the baseline of extra path is 30°
+        let thirty = conditions.angle_of_arrival(0.05 / c, 0.1).unwrap();
+        assert!((thirty - 30.0).abs() < 0.01);
+        assert!(conditions.angle_of_arrival(0.2 / c, 0.1).is_none());
+
+        let mut detector = RangeDetector::with_config(short_range_config());
+        detector.initialize().await.unwrap();
+        detector.set_simulated_target(Some(12.0)).await;
+        assert!(detector.measure_distance().await.unwrap().angle_of_arrival_degrees.is_none());
+
+        let mut detector = RangeDetector::with_config(RangingConfig {
+            dual_transducer_baseline_m: Some(0.15),
+            ..short_range_config()
+        });
+        detector.initialize().await.unwrap();
+        detector.set_simulated_target(Some(12.0)).await;
+        for bearing in [0.0, 25.0, -60.0] {
+            detector.set_simulated_bearing(bearing).await;
+            let measurement = detector.measure_distance().await.unwrap();
+            assert!((measurement.distance_m - 12.0).abs() < 0.5);
+            let measured = measurement.angle_of_arrival_degrees.unwrap();
+            assert!((measured - bearing).abs() < 0.5, "{measured} vs {bearing}");
+        }
+    }
+
+    #[test]
+    fn test_kalman_tracks_constant_velocity_target() {
+        // Target receding at 3 m/s from 50 m, ranged every 100 ms with ±1.5 m noise
+        let mut filter = DistanceKalmanFilter::new(0.5, 0.9);
+        let mut seed = 0x2545_F491u32;
+        let mut noise = || {
+            seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            (seed >> 8) as f32 / (1 << 24) as f32 * 3.0 - 1.5
+        };
+        let (mut raw_error, mut filtered_error) = (0.0, 0.0);
+        for step in 0..100 {
+            let truth = 50.0 + 3.0 * step as f32 * 0.1;
+            let measured = truth + noise();
+            filter.predict(0.1);
+            filter.update(measured);
+            if step >= 50 {
+                raw_error += (measured - truth).powi(2);
+                filtered_error += (filter.get_distance() - truth).powi(2);
+            }
+        }
+        assert!(filtered_error < raw_error / 3.0, "{filtered_error} vs {raw_error}");
+        assert!((filter.range_rate() - 3.0).abs() < 0.5);
+        let truth_in_1s = 50.0 + 3.0 * 10.9;
+        assert!((filter.predicted_range_in(1.0) - truth_in_1s).abs() < 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_measure_distance_returns_tracked_range() {
+        let mut detector = RangeDetector::with_config(short_range_config());
+        detector.initialize().await.unwrap();
+        detector.set_simulated_target(Some(12.0)).await;
+        assert!(detector.kalman_state().await.is_none());
+
+        detector.enable_kalman_tracking(0.5, 1.0);
+        let first = detector.measure_distance().await.unwrap();
+        assert!((first.distance_m - 12.0).abs() < 0.5);
+
+        // A single 4 m jump is only partly believed
+        detector.set_simulated_target(Some(16.0)).await;
+        let second = detector.measure_distance().await.unwrap();
+        assert!(second.distance_m > 12.0 && second.distance_m < 15.5, "{}", second.distance_m);
+        let state = detector.kalman_state().await.unwrap();
+        assert_eq!(state.get_distance(), second.distance_m);
+        assert!(state.predicted_range_in(1.0) > state.get_distance());
+    }
+
+    #[test]
+    fn test_reverb_subtraction_exposes_direct_path() {
+        // Direct echo at 50ms, a stronger wall ghost at 1.5x and weaker ones behind it
+        let rate = 20_000.0;
+        let pulse = 4;
+        let mut envelope = vec![0.001f32; 8_000];
+        for (start, amplitude) in [(1_000, 1.0), (1_500, 2.4), (2_000, 1.0), (3_000, 0.5)] {
+            envelope[start..start + pulse].iter_mut().for_each(|level| *level += amplitude);
+        }
+
+        let naive = detect_direct_path(&envelope, rate, 200e-6, None).unwrap();
+        assert!((naive.delay_s - 0.075).abs() < 1e-4);
+
+        let reverb = ReverbDecayModel::fit(&envelope, rate, 1_000 + pulse).unwrap();
+        assert!(reverb.time_constant_s > 0.0 && reverb.level_at(0.04) == 0.0);
+        let direct = detect_direct_path(&envelope, rate, 200e-6, Some(&reverb)).unwrap();
+        assert!((direct.delay_s - 0.05).abs() < 1e-4);
+        assert!(direct.confidence < 0.2, "{}", direct.confidence);
+
+        let mut clean = vec![0.001f32; 8_000];
+        clean[1_000..1_000 + pulse].iter_mut().for_each(|level| *level += 1.0);
+        assert!(detect_direct_path(&clean, rate, 200e-6, None).unwrap().confidence > 0.9);
+    }
+
+    #[tokio::test]
+    async fn test_multipath_suppression_rejects_corridor_ghosts() {
+        let config = RangingConfig { max_range_m: 40.0, ..short_range_config() };
+        let mut detector = RangeDetector::with_config(config.clone());
+        detector.initialize().await.unwrap();
+        detector.set_simulated_target(Some(10.0)).await;
+        detector.set_simulated_multipath(vec![(1.5, 2.4), (2.0, 1.0), (3.0, 0.5)]).await;
+
+        // The strongest return is the ghost at 15m
+        let inflated = detector.measure_distance().await.unwrap();
+        assert!((inflated.distance_m - 15.0).abs() < 0.1);
+        assert_eq!(inflated.multipath_confidence, 1.0);
+
+        let mut detector = RangeDetector::with_config(RangingConfig { multipath_suppression: true, ..config });
+        detector.initialize().await.unwrap();
+        detector.set_simulated_target(Some(10.0)).await;
+        detector.set_simulated_multipath(vec![(1.5, 2.4), (2.0, 1.0), (3.0, 0.5)]).await;
+        for _ in 0..2 {
+            let measurement = detector.measure_distance().await.unwrap();
+            assert!((measurement.distance_m - 10.0).abs() < 0.1, "{}", measurement.distance_m);
+            assert!(measurement.multipath_confidence < 0.5);
+        }
+
+        detector.set_simulated_multipath(Vec::new()).await;
+        detector.measure_distance().await.unwrap();
+        let clean = detector.measure_distance().await.unwrap();
+        assert!((clean.distance_m - 10.0).abs() < 0.1);
+        assert!(clean.multipath_confidence > 0.8, "{}", clean.multipath_confidence);
+    }
+}
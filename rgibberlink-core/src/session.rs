@@ -0,0 +1,154 @@
+//! # Peer Sessions
+//!
+//! One `ProtocolEngine` carries one handshake and one session key, so talking to
+//! several devices at once (mesh use) needs an engine per peer. `SessionManager`
+//! keys a `PeerSession` (engine, inbound message queue and partial fragment
+//! groups) by `PeerIdentity.id`. Engines are built with the link's
+//! `SessionPolicy`, and all of them share one `SessionRegistry`, so session ids
+//! stay unique across peers.
+
+use crate::protocol::{ProtocolEngine, ProtocolError, SessionPolicy, SessionRegistry};
+use crate::security::PeerIdentity;
+use crate::{FragmentGroup, Message};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Protocol engine, inbound queue and fragment reassembly for a single peer
+#[derive(Clone)]
+pub struct PeerSession {
+    pub protocol: Arc<Mutex<ProtocolEngine>>,
+    pub message_queue: Arc<Mutex<Vec<Message>>>,
+    /// Partial fragment groups, by group id; only this peer's fragments land here
+    pub(crate) fragment_groups: Arc<Mutex<HashMap<u64, FragmentGroup>>>,
+}
+
+impl PeerSession {
+    async fn new(registry: Arc<Mutex<SessionRegistry>>, policy: &SessionPolicy) -> Result<Self, ProtocolError> {
+        let mut protocol = ProtocolEngine::new();
+        protocol.apply_session_policy(policy);
+        protocol.set_session_registry(registry);
+        protocol.new_session_id().await?;
+        Ok(Self {
+            protocol: Arc::new(Mutex::new(protocol)),
+            message_queue: Arc::new(Mutex::new(Vec::new())),
+            fragment_groups: Arc::new(Mutex::new(HashMap::new())),
+        })
+    }
+}
+
+/// Per-peer sessions keyed by `PeerIdentity.id` (`GL-XXXX-XXXX`)
+pub struct SessionManager {
+    sessions: HashMap<String, PeerSession>,
+    registry: Arc<Mutex<SessionRegistry>>,
+}
+
+impl SessionManager {
+    pub fn new() -> Self {
+        Self::with_registry(Arc::new(Mutex::new(SessionRegistry::new())))
+    }
+
+    /// Create engines that draw session ids from `registry`, e.g. the one used by
+    /// the link's default engine
+    pub fn with_registry(registry: Arc<Mutex<SessionRegistry>>) -> Self {
+        Self {
+            sessions: HashMap::new(),
+            registry,
+        }
+    }
+
+    /// Session for `peer_id`, creating it on first use with a fresh session id
+    /// and an engine configured by `policy`
+    pub async fn get_or_create(&mut self, peer_id: &str, policy: &SessionPolicy) -> Result<PeerSession, ProtocolError> {
+        if let Some(session) = self.sessions.get(peer_id) {
+            return Ok(session.clone());
+        }
+
+        let identity = PeerIdentity::from_string(peer_id)
+            .map_err(|_| ProtocolError::InvalidPeerIdentity(peer_id.to_string()))?;
+        let session = PeerSession::new(self.registry.clone(), policy).await?;
+        self.sessions.insert(identity.id, session.clone());
+        Ok(session)
+    }
+
+    /// Existing session for `peer_id`
+    pub fn get(&self, peer_id: &str) -> Option<PeerSession> {
+        self.sessions.get(peer_id).cloned()
+    }
+
+    /// Drop the session for `peer_id`, releasing its session id
+    pub async fn remove(&mut self, peer_id: &str) -> bool {
+        match self.sessions.remove(peer_id) {
+            Some(session) => {
+                session.protocol.lock().await.close_session().await;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Ids of all peers with a session
+    pub fn peer_ids(&self) -> Vec<String> {
+        self.sessions.keys().cloned().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.sessions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sessions.is_empty()
+    }
+}
+
+impl Default for SessionManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_sessions_are_per_peer_and_share_the_registry() {
+        let mut manager = SessionManager::new();
+        let policy = ProtocolEngine::new().session_policy();
+        assert!(matches!(manager.get_or_create("bogus", &policy).await, Err(ProtocolError::InvalidPeerIdentity(_))));
+
+        let first = manager.get_or_create("GL-AB12-CDEF", &policy).await.unwrap();
+        let again = manager.get_or_create("GL-AB12-CDEF", &policy).await.unwrap();
+        let second = manager.get_or_create("GL-9876-5432", &policy).await.unwrap();
+        assert!(Arc::ptr_eq(&first.protocol, &again.protocol));
+        assert!(!Arc::ptr_eq(&first.protocol, &second.protocol));
+        assert_eq!(manager.len(), 2);
+
+        // Both engines draw distinct ids from the one registry
+        let registry = first.protocol.lock().await.session_registry();
+        assert!(Arc::ptr_eq(&registry, &second.protocol.lock().await.session_registry()));
+        let first_id = *first.protocol.lock().await.get_session_id();
+        assert_ne!(first_id, *second.protocol.lock().await.get_session_id());
+        assert_eq!(registry.lock().await.active_count(), 2);
+
+        assert!(manager.remove("GL-AB12-CDEF").await);
+        assert!(manager.get("GL-AB12-CDEF").is_none());
+        assert!(!registry.lock().await.is_active(&first_id));
+        assert_eq!(manager.peer_ids(), vec!["GL-9876-5432".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_peer_engines_follow_the_session_policy() {
+        let mut template = ProtocolEngine::new();
+        template.set_cipher_suite(crate::crypto::CipherSuite::ChaCha20Poly1305);
+        template.set_session_lifetime(Some(std::time::Duration::from_secs(120)));
+        template.set_key_confirmation_required(!template.is_key_confirmation_required());
+        template.set_mutual_authentication_required(!template.is_mutual_authentication_required());
+        let policy = template.session_policy();
+
+        let mut manager = SessionManager::new();
+        let session = manager.get_or_create("GL-AB12-CDEF", &policy).await.unwrap();
+        assert_eq!(session.protocol.lock().await.session_policy(), policy);
+        assert_ne!(ProtocolEngine::new().session_policy(), policy);
+    }
+}
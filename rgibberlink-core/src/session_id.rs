@@ -0,0 +1,121 @@
+//! # Session Identifiers
+//!
+//! Every handshake is identified by a 16-byte session id that is signed into the
+//! QR payload, echoed by the long-range sync pulse and bound into key
+//! confirmation and keepalive transcripts. `SessionId` carries that length in its
+//! type: bytes arriving from the wire, bindings or hex strings are checked once
+//! when they are converted, so a short or long id is rejected with
+//! `SessionIdError` instead of being silently truncated or padded further in.
+
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use thiserror::Error;
+
+/// Length of a session id in bytes
+pub const SESSION_ID_LEN: usize = 16;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum SessionIdError {
+    #[error("Session ID must be {expected} bytes, got {actual}")]
+    InvalidLength { expected: usize, actual: usize },
+    #[error("Session ID is not valid hex")]
+    InvalidHex,
+}
+
+/// Validated 16-byte session identifier; serializes exactly like `[u8; 16]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SessionId([u8; SESSION_ID_LEN]);
+
+impl SessionId {
+    pub const fn new(bytes: [u8; SESSION_ID_LEN]) -> Self {
+        Self(bytes)
+    }
+
+    /// Fresh random session id
+    pub fn random() -> Self {
+        let mut bytes = [0u8; SESSION_ID_LEN];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        Self(bytes)
+    }
+
+    /// Take a session id from untrusted bytes, which must be exactly 16 long
+    pub fn from_slice(bytes: &[u8]) -> Result<Self, SessionIdError> {
+        let bytes: [u8; SESSION_ID_LEN] = bytes.try_into().map_err(|_| SessionIdError::InvalidLength {
+            expected: SESSION_ID_LEN,
+            actual: bytes.len(),
+        })?;
+        Ok(Self(bytes))
+    }
+
+    /// Parse a session id from 32 hex digits
+    pub fn from_hex(hex_str: &str) -> Result<Self, SessionIdError> {
+        let bytes = hex::decode(hex_str).map_err(|_| SessionIdError::InvalidHex)?;
+        Self::from_slice(&bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; SESSION_ID_LEN] {
+        &self.0
+    }
+
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.0)
+    }
+}
+
+impl From<[u8; SESSION_ID_LEN]> for SessionId {
+    fn from(bytes: [u8; SESSION_ID_LEN]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl TryFrom<&[u8]> for SessionId {
+    type Error = SessionIdError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Self::from_slice(bytes)
+    }
+}
+
+impl AsRef<[u8]> for SessionId {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl fmt::Display for SessionId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_id_rejects_wrong_lengths() {
+        let bytes: Vec<u8> = (0..16).collect();
+        let id = SessionId::from_slice(&bytes).unwrap();
+        assert_eq!(id.as_bytes().as_slice(), bytes.as_slice());
+        assert_eq!(SessionId::from_hex(&id.to_hex()).unwrap(), id);
+        assert_eq!(id.to_string(), "000102030405060708090a0b0c0d0e0f");
+
+        for len in [0, 15, 17, 32] {
+            assert_eq!(
+                SessionId::from_slice(&vec![0xAA; len]),
+                Err(SessionIdError::InvalidLength { expected: SESSION_ID_LEN, actual: len })
+            );
+        }
+        assert_eq!(SessionId::from_hex("0001"), Err(SessionIdError::InvalidLength { expected: 16, actual: 2 }));
+        assert_eq!(SessionId::from_hex("not hex"), Err(SessionIdError::InvalidHex));
+        assert_ne!(SessionId::random(), SessionId::random());
+
+        // Same wire format as the raw array, and a wrong-length array fails to decode
+        let cbor = serde_cbor::to_vec(&id).unwrap();
+        assert_eq!(cbor, serde_cbor::to_vec(id.as_bytes()).unwrap());
+        let short = serde_cbor::to_vec(&[0u8; 15]).unwrap();
+        assert!(serde_cbor::from_slice::<SessionId>(&short).is_err());
+    }
+}
@@ -0,0 +1,49 @@
+//! # Telemetry Module
+//!
+//! Feature-gated structured logging. With the `tracing` feature enabled the engines
+//! emit `tracing` spans for handshake phases and events for fallback transitions,
+//! safety violations and crypto operations; without it these macros compile to nothing.
+//!
+//! Handshake phases are instrumented with
+//! `#[cfg_attr(feature = "tracing", tracing::instrument(...))]` on the phase methods,
+//! using span names of the form `handshake.<phase>`.
+
+/// Emit an error-level event when the `tracing` feature is enabled
+macro_rules! trace_error {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "tracing")]
+        {
+            tracing::error!($($arg)*);
+        }
+    }};
+}
+
+/// Emit a warn-level event when the `tracing` feature is enabled
+macro_rules! trace_warn {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "tracing")]
+        {
+            tracing::warn!($($arg)*);
+        }
+    }};
+}
+
+/// Emit an info-level event when the `tracing` feature is enabled
+macro_rules! trace_info {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "tracing")]
+        {
+            tracing::info!($($arg)*);
+        }
+    }};
+}
+
+/// Emit a debug-level event when the `tracing` feature is enabled
+macro_rules! trace_debug {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "tracing")]
+        {
+            tracing::debug!($($arg)*);
+        }
+    }};
+}
@@ -0,0 +1,329 @@
+//! # Multi-Tone Modem
+//!
+//! Multi-tone FSK transmissions: every frame sounds one tone per payload
+//! nibble, each nibble in its own group of 16 bins spaced 46.875 Hz apart
+//! (48 kHz / 1024), and each group of bytes is held for `frames_per_tx` frames.
+//! A start marker precedes the payload and an end marker follows it, so a
+//! receiver can find the transmission in a longer capture.
+//!
+//! The tone grid borrows GGWave's, but this is not GGWave and does not
+//! interoperate with the ggwave library: markers and framing are our own,
+//! payloads are framed as `[len][data][crc32]` with no Reed-Solomon, and the
+//! ultrasonic protocol sits in the 18-22 kHz band used by the rest of the audio
+//! stack, two bytes per frame so it stays below Nyquist at 44.1 kHz. Corrupted
+//! frames are detected rather than corrected, and resending is left to the
+//! protocol layer.
+
+use crate::audio::AudioError;
+use crate::demod::Demodulator;
+
+/// Width of one tone bin: a 1024-sample frame at 48 kHz
+pub const TONE_BIN_HZ: f32 = 46.875;
+/// Largest payload one transmission carries
+pub const TONE_MAX_PAYLOAD: usize = 140;
+/// Tone bins per nibble group
+const NIBBLE_BINS: usize = 16;
+/// Tones sounded together in a start or end marker
+const MARKER_TONES: usize = 16;
+/// Frames each marker is held for
+const MARKER_FRAMES: usize = 16;
+/// Marker windows are searched in steps of this fraction of a frame
+const MARKER_SEARCH_STEPS_PER_FRAME: usize = 4;
+/// Share of a window's energy the start-marker tones must carry to count as marker;
+/// payload frames put at most half their tones on marker bins
+const MARKER_ENERGY_FRACTION: f32 = 0.75;
+/// Length byte before the payload and CRC-32 after it
+const FRAME_OVERHEAD: usize = 1 + 4;
+
+/// Tone layouts supported by the audio engine
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToneProtocol {
+    /// 1875-6375 Hz, three bytes per three frames
+    Audible,
+    /// 18-21 kHz, two bytes per nine frames
+    Ultrasonic,
+}
+
+impl ToneProtocol {
+    /// Index of the lowest tone bin
+    fn freq_start_bin(self) -> usize {
+        match self {
+            ToneProtocol::Audible => 40,
+            ToneProtocol::Ultrasonic => 384,
+        }
+    }
+
+    /// Frames every group of bytes is held for
+    pub fn frames_per_tx(self) -> usize {
+        match self {
+            ToneProtocol::Audible => 3,
+            ToneProtocol::Ultrasonic => 9,
+        }
+    }
+
+    /// Bytes sounded together in one group of frames
+    pub fn bytes_per_tx(self) -> usize {
+        match self {
+            ToneProtocol::Audible => 3,
+            ToneProtocol::Ultrasonic => 2,
+        }
+    }
+
+    /// Index one past the highest tone bin
+    fn freq_end_bin(self) -> usize {
+        self.freq_start_bin() + 2 * self.bytes_per_tx() * NIBBLE_BINS
+    }
+}
+
+/// Samples per frame at `sample_rate`, chosen so bins stay close to `TONE_BIN_HZ`
+/// and every tone completes a whole number of cycles per frame
+pub fn samples_per_frame(sample_rate: u32) -> usize {
+    (sample_rate as f32 / TONE_BIN_HZ).round() as usize
+}
+
+/// Samples in a transmission of the largest payload, markers included
+pub fn max_transmission_samples(protocol: ToneProtocol, sample_rate: u32) -> usize {
+    let group_count = (TONE_MAX_PAYLOAD + FRAME_OVERHEAD).div_ceil(protocol.bytes_per_tx());
+    (2 * MARKER_FRAMES + group_count * protocol.frames_per_tx()) * samples_per_frame(sample_rate)
+}
+
+/// Modulate `data` into raw samples (unnormalized) for `protocol`
+pub fn encode(protocol: ToneProtocol, data: &[u8], sample_rate: u32) -> Result<Vec<f32>, AudioError> {
+    if data.len() > TONE_MAX_PAYLOAD {
+        return Err(AudioError::InvalidParameters);
+    }
+    let bin_hz = bin_hz(protocol, sample_rate)?;
+    let frame_len = samples_per_frame(sample_rate);
+
+    let mut body = Vec::with_capacity(data.len() + FRAME_OVERHEAD);
+    body.push(data.len() as u8);
+    body.extend_from_slice(data);
+    body.extend_from_slice(&crc32fast::hash(&body).to_be_bytes());
+
+    let mut samples = Vec::with_capacity(max_transmission_samples(protocol, sample_rate));
+
+    let start_marker = tone_frame(&marker_bins(protocol, true), bin_hz, sample_rate, frame_len);
+    for _ in 0..MARKER_FRAMES {
+        samples.extend_from_slice(&start_marker);
+    }
+
+    for group in body.chunks(protocol.bytes_per_tx()) {
+        let mut bins = Vec::with_capacity(2 * protocol.bytes_per_tx());
+        for slot in 0..2 * protocol.bytes_per_tx() {
+            // Low nibble first, missing bytes of the last group sent as zero
+            let byte = group.get(slot / 2).copied().unwrap_or(0);
+            let nibble = if slot % 2 == 0 { byte & 0x0F } else { byte >> 4 };
+            bins.push(protocol.freq_start_bin() + slot * NIBBLE_BINS + nibble as usize);
+        }
+        let frame = tone_frame(&bins, bin_hz, sample_rate, frame_len);
+        for _ in 0..protocol.frames_per_tx() {
+            samples.extend_from_slice(&frame);
+        }
+    }
+
+    let end_marker = tone_frame(&marker_bins(protocol, false), bin_hz, sample_rate, frame_len);
+    for _ in 0..MARKER_FRAMES {
+        samples.extend_from_slice(&end_marker);
+    }
+
+    Ok(samples)
+}
+
+/// Find and decode a `protocol` transmission in `samples`.
+///
+/// Returns `Ok(None)` when no start marker is present and
+/// `AudioError::ReceptionError` when one is found but the frame that follows is
+/// truncated or fails its checksum.
+pub fn decode(
+    protocol: ToneProtocol,
+    samples: &[f32],
+    sample_rate: u32,
+    demodulator: &dyn Demodulator,
+) -> Result<Option<Vec<u8>>, AudioError> {
+    let bin_hz = bin_hz(protocol, sample_rate)?;
+    let frame_len = samples_per_frame(sample_rate);
+    let Some(data_start) = find_payload_start(protocol, samples, sample_rate, bin_hz, demodulator) else {
+        return Ok(None);
+    };
+
+    // Read each group from the middle frame of its span, which tolerates the
+    // marker edge being off by up to half a group
+    let group_len = protocol.frames_per_tx() * frame_len;
+    let read_group = |index: usize| -> Result<Vec<u8>, AudioError> {
+        let start = data_start + index * group_len + (group_len - frame_len) / 2;
+        let window = samples
+            .get(start..start + frame_len)
+            .ok_or_else(|| AudioError::ReceptionError("Tone transmission truncated".to_string()))?;
+        Ok(demodulate_group(protocol, window, sample_rate, bin_hz, demodulator))
+    };
+
+    let mut body = read_group(0)?;
+    let body_len = body[0] as usize + FRAME_OVERHEAD;
+    if body[0] as usize > TONE_MAX_PAYLOAD {
+        return Err(AudioError::ReceptionError(format!("Tone frame length {} out of range", body[0])));
+    }
+    for index in 1..body_len.div_ceil(protocol.bytes_per_tx()) {
+        body.extend(read_group(index)?);
+    }
+    body.truncate(body_len);
+
+    let (framed, crc) = body.split_at(body_len - 4);
+    if crc32fast::hash(framed).to_be_bytes() != crc {
+        return Err(AudioError::ReceptionError("Tone frame checksum mismatch".to_string()));
+    }
+    Ok(Some(framed[1..].to_vec()))
+}
+
+/// Tone bin spacing at `sample_rate`, rejecting rates too low for `protocol`'s band
+fn bin_hz(protocol: ToneProtocol, sample_rate: u32) -> Result<f32, AudioError> {
+    let frame_len = samples_per_frame(sample_rate);
+    if frame_len == 0 {
+        return Err(AudioError::InvalidParameters);
+    }
+    let bin_hz = sample_rate as f32 / frame_len as f32;
+    if protocol.freq_end_bin() as f32 * bin_hz >= sample_rate as f32 / 2.0 {
+        return Err(AudioError::InvalidParameters);
+    }
+    Ok(bin_hz)
+}
+
+/// Interleaved marker tones: the start marker takes bins 0, 3, 4, 7, ... from the
+/// bottom of the band and the end marker the bins in between
+fn marker_bins(protocol: ToneProtocol, start: bool) -> Vec<usize> {
+    (0..MARKER_TONES)
+        .map(|i| {
+            let odd = i % 2 == 1;
+            protocol.freq_start_bin() + 2 * i + usize::from(odd == start)
+        })
+        .collect()
+}
+
+/// One frame of equal-amplitude tones at `bins`
+fn tone_frame(bins: &[usize], bin_hz: f32, sample_rate: u32, frame_len: usize) -> Vec<f32> {
+    let amplitude = 1.0 / bins.len() as f32;
+    (0..frame_len)
+        .map(|i| {
+            let t = i as f32 / sample_rate as f32;
+            bins.iter()
+                .map(|&bin| (2.0 * std::f32::consts::PI * bin as f32 * bin_hz * t).sin())
+                .sum::<f32>()
+                * amplitude
+        })
+        .collect()
+}
+
+/// Offset of the first payload sample: one frame past the last window that is
+/// still dominated by a full-length start marker
+fn find_payload_start(
+    protocol: ToneProtocol,
+    samples: &[f32],
+    sample_rate: u32,
+    bin_hz: f32,
+    demodulator: &dyn Demodulator,
+) -> Option<usize> {
+    let frame_len = samples_per_frame(sample_rate);
+    let step = (frame_len / MARKER_SEARCH_STEPS_PER_FRAME).max(1);
+    let start_tones: Vec<f32> = marker_bins(protocol, true).iter().map(|&bin| bin as f32 * bin_hz).collect();
+    let end_tones: Vec<f32> = marker_bins(protocol, false).iter().map(|&bin| bin as f32 * bin_hz).collect();
+    let mut start_powers = vec![0.0f32; MARKER_TONES];
+    let mut end_powers = vec![0.0f32; MARKER_TONES];
+
+    let mut is_start_marker = |window: &[f32]| {
+        let energy = window.iter().map(|s| s * s).sum::<f32>() / window.len() as f32;
+        if energy <= f32::EPSILON {
+            return false;
+        }
+        demodulator.goertzel_powers(window, &start_tones, sample_rate as f32, &mut start_powers);
+        demodulator.goertzel_powers(window, &end_tones, sample_rate as f32, &mut end_powers);
+        let start_power: f32 = start_powers.iter().sum();
+        start_power >= MARKER_ENERGY_FRACTION * energy && start_power > 4.0 * end_powers.iter().sum::<f32>()
+    };
+
+    let mut run_start = None;
+    let mut position = 0;
+    while position + frame_len <= samples.len() {
+        let in_marker = is_start_marker(&samples[position..position + frame_len]);
+        match (in_marker, run_start) {
+            (true, None) => run_start = Some(position),
+            (false, Some(first)) => {
+                // Require most of the marker so stray tones are not mistaken for one
+                let last = position - step;
+                if last - first >= frame_len * MARKER_FRAMES / 2 {
+                    return Some(last + frame_len);
+                }
+                run_start = None;
+            }
+            _ => {}
+        }
+        position += step;
+    }
+    None
+}
+
+/// Strongest tone of every nibble group in one frame-long window
+fn demodulate_group(
+    protocol: ToneProtocol,
+    window: &[f32],
+    sample_rate: u32,
+    bin_hz: f32,
+    demodulator: &dyn Demodulator,
+) -> Vec<u8> {
+    let mut powers = vec![0.0f32; NIBBLE_BINS];
+    let nibbles: Vec<u8> = (0..2 * protocol.bytes_per_tx())
+        .map(|slot| {
+            let first_bin = protocol.freq_start_bin() + slot * NIBBLE_BINS;
+            let tones: Vec<f32> = (0..NIBBLE_BINS).map(|offset| (first_bin + offset) as f32 * bin_hz).collect();
+            demodulator.goertzel_powers(window, &tones, sample_rate as f32, &mut powers);
+            powers.iter().enumerate().fold((0, f32::MIN), |best, (nibble, &power)| {
+                if power > best.1 { (nibble, power) } else { best }
+            }).0 as u8
+        })
+        .collect();
+
+    nibbles.chunks(2).map(|pair| pair[0] | (pair[1] << 4)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::demod::ScalarDemodulator;
+
+    /// `samples` with deterministic uniform noise of the given peak amplitude added
+    fn with_noise(samples: &[f32], amplitude: f32) -> Vec<f32> {
+        use rand::{Rng, SeedableRng};
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0x6767);
+        samples.iter().map(|s| s + rng.gen_range(-amplitude..amplitude)).collect()
+    }
+
+    #[test]
+    fn test_round_trip_found_inside_longer_capture() {
+        let payload = b"pairing nonce 0123456789".to_vec();
+        for (protocol, sample_rate) in [(ToneProtocol::Audible, 48000), (ToneProtocol::Ultrasonic, 44100)] {
+            let encoded = encode(protocol, &payload, sample_rate).unwrap();
+
+            // Unaligned leading silence and trailing room noise around the transmission
+            let mut capture = vec![0.0f32; 1234];
+            capture.extend(&encoded);
+            capture.extend(vec![0.0f32; 5000]);
+            let capture = with_noise(&capture, 0.05);
+
+            assert_eq!(decode(protocol, &capture, sample_rate, &ScalarDemodulator).unwrap(), Some(payload.clone()), "{:?}", protocol);
+        }
+    }
+
+    #[test]
+    fn test_missing_truncated_and_invalid_transmissions() {
+        let protocol = ToneProtocol::Ultrasonic;
+        let silence = with_noise(&vec![0.0f32; 48000], 0.05);
+        assert_eq!(decode(protocol, &silence, 48000, &ScalarDemodulator).unwrap(), None);
+
+        let encoded = encode(protocol, b"cut short", 48000).unwrap();
+        let truncated = &encoded[..encoded.len() / 2];
+        assert!(matches!(decode(protocol, truncated, 48000, &ScalarDemodulator), Err(AudioError::ReceptionError(_))));
+
+        // The ultrasonic band does not fit below Nyquist at 32 kHz
+        assert!(matches!(encode(protocol, b"x", 32000), Err(AudioError::InvalidParameters)));
+        assert!(matches!(encode(protocol, &[0u8; TONE_MAX_PAYLOAD + 1], 48000), Err(AudioError::InvalidParameters)));
+        assert_eq!(encode(protocol, &[0u8; TONE_MAX_PAYLOAD], 48000).unwrap().len(), max_transmission_samples(protocol, 48000));
+    }
+}
@@ -2,6 +2,10 @@ use std::sync::Arc;
 use tokio::sync::Mutex;
 use std::collections::VecDeque;
 use reed_solomon_erasure::galois_8::ReedSolomon;
+use crate::channel_validator::{preamble_waveform, PREAMBLE_SAMPLES_PER_SYMBOL, PREAMBLE_SYMBOL_RATE_HZ};
+
+/// Speed of sound in air at 20°C (m/s)
+pub const SPEED_OF_SOUND_M_S: f32 = 343.0;
 
 /// Comprehensive error types for ultrasonic beam operations
 #[derive(Debug, Clone, thiserror::Error)]
@@ -35,7 +39,8 @@ pub enum UltrasonicBeamError {
 }
 
 /// Configuration for multi-band beam forming parameters (noisy environments)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 pub struct BeamConfig {
     pub fundamental_bands: Vec<f32>,     // e.g., [40kHz, 48kHz, 56kHz]
     pub harmonic_bands: Vec<f32>,        // e.g., [80kHz, 96kHz, 112kHz]
@@ -45,6 +50,8 @@ pub struct BeamConfig {
     pub power_level: f32,               // Transmission power (0.0-1.0)
     pub snr_threshold: f32,            // SNR threshold for channel selection
     pub enable_beamforming: bool,      // Enable directional beamforming
+    pub transducer_aperture: f32,      // Transducer diameter in meters
+    pub carrier_frequency: f32,        // Carrier the beam's diffraction is computed at (Hz)
 }
 
 impl Default for BeamConfig {
@@ -58,6 +65,8 @@ impl Default for BeamConfig {
             power_level: 0.8,            // 80% power
             snr_threshold: 10.0,         // 10dB SNR threshold
             enable_beamforming: true,    // Enable beamforming by default
+            transducer_aperture: 0.05,   // 50mm parametric array
+            carrier_frequency: 40000.0,  // 40kHz carrier
         }
     }
 }
@@ -91,11 +100,31 @@ pub struct UltrasonicChannelDiagnostics {
     pub detected_failures: Vec<UltrasonicBeamError>,
 }
 
+/// Per-element drive timing that steers a phased array towards a direction
+#[derive(Debug, Clone)]
+pub struct PhasedArraySteering {
+    pub azimuth: f32,            // Degrees, positive towards +x
+    pub elevation: f32,          // Degrees, positive towards +y
+    pub carrier_frequency: f32,  // Hz the phases were computed for
+    pub delays_s: Vec<f32>,      // Per-element time delay, earliest element at 0
+    pub phases_rad: Vec<f32>,    // Per-element phase delay at the carrier, in [0, 2π)
+}
+
+/// Simulated planar transducer array, element positions in meters in the array plane
+#[derive(Debug, Clone)]
+struct PhasedArray {
+    element_positions: Vec<(f32, f32)>,
+    steering: Option<PhasedArraySteering>,
+}
+
 /// Ultrasonic beam engine for focused ultrasound communication
 pub struct UltrasonicBeamEngine {
     config: BeamConfig,
     is_active: bool,
     reception_buffer: Arc<Mutex<VecDeque<BeamReception>>>,
+    phased_array: Option<PhasedArray>,
+    /// Zadoff-Chu phases of the coupling preamble led into generated audio
+    sync_preamble: Option<Vec<u8>>,
     // Placeholder for Android JNI integration
     // jni_interface: Option<JNIInterface>,
 }
@@ -107,6 +136,8 @@ impl UltrasonicBeamEngine {
             config: BeamConfig::default(),
             is_active: false,
             reception_buffer: Arc::new(Mutex::new(VecDeque::new())),
+            phased_array: None,
+            sync_preamble: None,
         }
     }
 
@@ -136,11 +167,18 @@ impl UltrasonicBeamEngine {
                 ));
             }
         }
+        if [config.transducer_aperture, config.carrier_frequency].iter().any(|v| !v.is_finite() || *v <= 0.0) {
+            return Err(UltrasonicBeamError::InvalidParameters(
+                "Transducer aperture and carrier frequency must be positive".to_string()
+            ));
+        }
 
         Ok(Self {
             config,
             is_active: false,
             reception_buffer: Arc::new(Mutex::new(VecDeque::new())),
+            phased_array: None,
+            sync_preamble: None,
         })
     }
 
@@ -164,59 +202,55 @@ impl UltrasonicBeamEngine {
         let sample_rate = 192000.0; // High sample rate for ultrasonic
         let mod_freq = self.config.modulation_frequency;
         let samples_per_bit = (sample_rate / mod_freq) as usize;
-        let total_samples = data.len() * samples_per_bit;
 
         // Combine fundamental and harmonic bands
         let all_bands: Vec<f32> = self.config.fundamental_bands.iter()
             .chain(self.config.harmonic_bands.iter())
             .cloned()
             .collect();
-        let num_bands = all_bands.len();
-
-        let mut signal = vec![0.0f32; total_samples];
-        let mut global_sample_idx = 0;
-
-        for &byte in data {
-            for bit in 0..8 {
-                let bit_value = (byte >> (7 - bit)) & 1;
-                let amplitude = if bit_value == 1 { 1.0 } else { 0.0 };
-
-                for _ in 0..samples_per_bit {
-                    let t = global_sample_idx as f32 / sample_rate;
-
-                    // Sum all carrier frequencies with beamforming phase
-                    let mut sample_sum = 0.0;
-                    for (band_idx, &carrier_freq) in all_bands.iter().enumerate() {
-                        // Phase offset for beamforming (directional pattern)
-                        let beam_phase = if self.config.enable_beamforming {
-                            // Simple delay-and-sum beamforming approximation
-                            (band_idx as f32 * self.config.beam_angle.to_radians()) /
-                            (self.config.range * 0.001) // Simplified phase delay
-                        } else {
-                            0.0
-                        };
-
-                        let carrier = (2.0 * std::f32::consts::PI * carrier_freq * t + beam_phase).sin();
-
-                        // Adjust amplitude based on band type (harmonics weaker)
-                        let band_amplitude = if band_idx < self.config.fundamental_bands.len() {
-                            self.config.power_level
-                        } else {
-                            self.config.power_level * 0.7 // Harmonics reduced by 30%
-                        };
-
-                        sample_sum += amplitude * carrier * band_amplitude;
-                    }
-
-                    if global_sample_idx < total_samples {
-                        signal[global_sample_idx] = sample_sum;
-                    }
-                    global_sample_idx += 1;
-                }
-            }
+
+        // Sum all carrier frequencies with beamforming phase at sample `index`
+        let carriers = |index: usize| -> f32 {
+            let t = index as f32 / sample_rate;
+            all_bands.iter().enumerate().map(|(band_idx, &carrier_freq)| {
+                // Phase offset for beamforming (directional pattern)
+                let beam_phase = if self.config.enable_beamforming {
+                    // Simple delay-and-sum beamforming approximation
+                    (band_idx as f32 * self.config.beam_angle.to_radians()) /
+                    (self.config.range * 0.001) // Simplified phase delay
+                } else {
+                    0.0
+                };
+
+                let carrier = (2.0 * std::f32::consts::PI * carrier_freq * t + beam_phase).sin();
+
+                // Adjust amplitude based on band type (harmonics weaker)
+                let band_amplitude = if band_idx < self.config.fundamental_bands.len() {
+                    self.config.power_level
+                } else {
+                    self.config.power_level * 0.7 // Harmonics reduced by 30%
+                };
+
+                carrier * band_amplitude
+            }).sum()
+        };
+
+        // The coupling preamble envelope leads the data, each of its samples
+        // held to the audio rate
+        let mut envelope: Vec<f32> = Vec::new();
+        if let Some(sequence) = &self.sync_preamble {
+            let hold = (sample_rate / (PREAMBLE_SYMBOL_RATE_HZ * PREAMBLE_SAMPLES_PER_SYMBOL as f32)) as usize;
+            envelope.extend(preamble_waveform(sequence).into_iter().flat_map(|level| std::iter::repeat_n(level, hold)));
         }
 
-        Ok(signal)
+        // On-off keyed bits, MSB first
+        let data_samples = data.len() * samples_per_bit;
+        envelope.extend(data.iter()
+            .flat_map(|&byte| (0..8).map(move |bit| if (byte >> (7 - bit)) & 1 == 1 { 1.0 } else { 0.0 }))
+            .flat_map(|amplitude| std::iter::repeat_n(amplitude, samples_per_bit))
+            .take(data_samples));
+
+        Ok(envelope.into_iter().enumerate().map(|(index, amplitude)| amplitude * carriers(index)).collect())
     }
 
     /// Detect presence via beam reception
@@ -233,6 +267,13 @@ impl UltrasonicBeamEngine {
         Ok(false)
     }
 
+    /// Lead all generated audio with the coupling preamble with these Zadoff-Chu
+    /// phases (`None` stops), so a coupled receiver can time this leg against
+    /// the laser one by correlation
+    pub fn set_sync_preamble(&mut self, sequence: Option<Vec<u8>>) {
+        self.sync_preamble = sequence;
+    }
+
     /// Transmit synchronization pulse for beam alignment
     pub async fn transmit_sync_pulse(&self, pattern: &[u8]) -> Result<(), UltrasonicBeamError> {
         if !self.is_active {
@@ -307,11 +348,108 @@ impl UltrasonicBeamEngine {
         Ok(())
     }
 
+    /// tan θ for the main-lobe half-angle of a circular piston transducer,
+    /// sin θ ≈ 1.22·λ/D. An aperture under ~1.22 wavelengths radiates into the
+    /// whole half-space (infinite tangent).
+    fn divergence_tan(&self) -> f32 {
+        let wavelength = SPEED_OF_SOUND_M_S / self.config.carrier_frequency;
+        let sin = (1.22 * wavelength / self.config.transducer_aperture).min(1.0);
+        sin / (1.0 - sin * sin).sqrt()
+    }
+
+    /// Diameter (meters) of the beam footprint `distance_m` from the transducer.
+    ///
+    /// Far from the transducer the beam spreads at the diffraction half-angle
+    /// θ ≈ 1.22·λ/D; close in it is never narrower than the aperture. θ runs to
+    /// the first null, so this bounds the -3dB width from above, which errs on
+    /// the side of a larger footprint when reasoning about interception.
+    pub fn beam_width_at(&self, distance_m: f32) -> f32 {
+        let spread = 2.0 * distance_m.max(0.0) * self.divergence_tan();
+        spread.max(self.config.transducer_aperture)
+    }
+
+    /// Distance (meters) up to which the beam is too narrow for a second
+    /// receiver to sit in it beside the intended one.
+    ///
+    /// A receiver the size of our own transducer fills the footprint until it
+    /// grows past two apertures, i.e. out to D / tan θ. Coupling within this
+    /// distance keeps eavesdroppers out of the beam; beyond it only the beam's
+    /// short range does.
+    pub fn min_safe_coupling_distance(&self) -> f32 {
+        self.config.transducer_aperture / self.divergence_tan()
+    }
+
     /// Check if beam engine is active
     pub fn is_active(&self) -> bool {
         self.is_active
     }
 
+    /// Switch to phased-array transmit mode with the given element layout (meters)
+    pub fn configure_phased_array(&mut self, element_positions: Vec<(f32, f32)>) -> Result<(), UltrasonicBeamError> {
+        if element_positions.is_empty() {
+            return Err(UltrasonicBeamError::InvalidParameters(
+                "Phased array requires at least one element".to_string()
+            ));
+        }
+        if element_positions.iter().any(|&(x, y)| !x.is_finite() || !y.is_finite()) {
+            return Err(UltrasonicBeamError::InvalidParameters(
+                "Phased array element positions must be finite".to_string()
+            ));
+        }
+        self.phased_array = Some(PhasedArray {
+            element_positions,
+            steering: None,
+        });
+        Ok(())
+    }
+
+    /// Electronically steer the array towards `azimuth`/`elevation` (degrees off boresight)
+    ///
+    /// An element at `(x, y)` sees the wavefront towards the target
+    /// `x·cos(el)·sin(az) + y·sin(el)` meters earlier than the array origin, so it is
+    /// delayed by that path difference over the speed of sound. Delays are shifted so
+    /// the earliest-firing element fires at zero.
+    pub fn steer_array(&mut self, azimuth: f32, elevation: f32) -> Result<PhasedArraySteering, UltrasonicBeamError> {
+        if !(-90.0..=90.0).contains(&azimuth) || !(-90.0..=90.0).contains(&elevation) {
+            return Err(UltrasonicBeamError::InvalidParameters(
+                format!("Steering angle ({}°, {}°) outside ±90°", azimuth, elevation)
+            ));
+        }
+        let carrier_frequency = self.config.fundamental_bands[0];
+        let array = self.phased_array.as_mut().ok_or_else(|| {
+            UltrasonicBeamError::BeamFormingError("Phased array not configured".to_string())
+        })?;
+
+        let (az, el) = (azimuth.to_radians(), elevation.to_radians());
+        let (ux, uy) = (el.cos() * az.sin(), el.sin());
+        let path_lead: Vec<f32> = array.element_positions.iter()
+            .map(|&(x, y)| x * ux + y * uy)
+            .collect();
+        let min_lead = path_lead.iter().copied().fold(f32::INFINITY, f32::min);
+
+        let delays_s: Vec<f32> = path_lead.iter()
+            .map(|lead| (lead - min_lead) / SPEED_OF_SOUND_M_S)
+            .collect();
+        let phases_rad = delays_s.iter()
+            .map(|delay| (2.0 * std::f32::consts::PI * carrier_frequency * delay).rem_euclid(2.0 * std::f32::consts::PI))
+            .collect();
+
+        let steering = PhasedArraySteering {
+            azimuth,
+            elevation,
+            carrier_frequency,
+            delays_s,
+            phases_rad,
+        };
+        array.steering = Some(steering.clone());
+        Ok(steering)
+    }
+
+    /// Current phased-array steering, if the array has been configured and steered
+    pub fn get_array_steering(&self) -> Option<&PhasedArraySteering> {
+        self.phased_array.as_ref().and_then(|array| array.steering.as_ref())
+    }
+
     /// Detect specific ultrasonic channel failures
     pub async fn detect_channel_failures(&self) -> Vec<UltrasonicBeamError> {
         let mut failures = Vec::new();
@@ -407,6 +545,15 @@ mod tests {
         assert!(!signal.is_empty());
         // Verify signal contains modulated carrier
         assert!(signal.iter().any(|&s| s.abs() > 0.1));
+
+        // The coupling preamble leads the data at 24 audio samples per preamble sample
+        let sequence = crate::channel_validator::zadoff_chu_preamble(1, crate::channel_validator::PREAMBLE_LEN);
+        engine.set_sync_preamble(Some(sequence.clone()));
+        let led = engine.generate_parametric_audio(test_data).await.unwrap();
+        let preamble_samples = preamble_waveform(&sequence).len() * 24;
+        assert_eq!(led.len(), preamble_samples + signal.len());
+        assert!(led[..preamble_samples].iter().any(|&s| s.abs() > 0.1));
+        assert!(led[preamble_samples..].iter().zip(&signal).all(|(a, b)| (*a == 0.0) == (*b == 0.0)));
     }
 
     #[tokio::test]
@@ -418,4 +565,66 @@ mod tests {
         let result = engine.transmit_control_data(&large_data, 1).await;
         assert!(matches!(result, Err(UltrasonicBeamError::InvalidParameters(_))));
     }
+
+    #[tokio::test]
+    async fn test_beam_width_follows_diffraction() {
+        let engine = UltrasonicBeamEngine::new();
+        let aperture = engine.get_config().transducer_aperture;
+
+        // 50mm at 40kHz: sin θ = 1.22 · 8.575mm / 50mm, θ ≈ 12.1°
+        let half_angle = (1.22 * SPEED_OF_SOUND_M_S / 40000.0 / aperture).asin();
+        assert!((half_angle.to_degrees() - 12.08).abs() < 0.05);
+        assert_eq!(engine.beam_width_at(0.0), aperture);
+        assert!((engine.beam_width_at(20.0) - 40.0 * half_angle.tan()).abs() < 1e-3);
+
+        // Two apertures wide exactly at the safe coupling distance
+        let safe = engine.min_safe_coupling_distance();
+        assert!((safe - 0.234).abs() < 0.001);
+        assert!((engine.beam_width_at(safe) - 2.0 * aperture).abs() < 1e-5);
+        assert!(engine.beam_width_at(safe + 0.01) > 2.0 * aperture);
+
+        // A higher carrier diffracts less, so the beam stays narrow for longer
+        let narrow = UltrasonicBeamEngine::with_config(BeamConfig { carrier_frequency: 56000.0, ..Default::default() }).unwrap();
+        assert!(narrow.beam_width_at(20.0) < engine.beam_width_at(20.0));
+        assert!(narrow.min_safe_coupling_distance() > safe);
+
+        // Sub-wavelength apertures have no beam to speak of
+        let point = UltrasonicBeamEngine::with_config(BeamConfig { transducer_aperture: 0.005, ..Default::default() }).unwrap();
+        assert_eq!(point.beam_width_at(1.0), f32::INFINITY);
+        assert_eq!(point.min_safe_coupling_distance(), 0.0);
+        assert!(UltrasonicBeamEngine::with_config(BeamConfig { transducer_aperture: 0.0, ..Default::default() }).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_phased_array_steering_delays() {
+        let mut engine = UltrasonicBeamEngine::new();
+        assert!(engine.steer_array(10.0, 0.0).is_err());
+
+        // Half-wavelength linear array at 40 kHz plus one element above the origin
+        let pitch = SPEED_OF_SOUND_M_S / 40000.0 / 2.0;
+        engine.configure_phased_array(vec![(0.0, 0.0), (pitch, 0.0), (2.0 * pitch, 0.0), (0.0, pitch)]).unwrap();
+
+        // Boresight: every element fires together
+        let boresight = engine.steer_array(0.0, 0.0).unwrap();
+        assert!(boresight.delays_s.iter().all(|d| d.abs() < 1e-9));
+
+        // 30° azimuth: x elements lead by x·sin(30°), the y element sees no path difference
+        let steering = engine.steer_array(30.0, 0.0).unwrap();
+        let step = pitch * 0.5 / SPEED_OF_SOUND_M_S;
+        let expected = [0.0, step, 2.0 * step, 0.0];
+        for (delay, want) in steering.delays_s.iter().zip(expected.iter()) {
+            assert!((delay - want).abs() < 1e-9, "delay {} != {}", delay, want);
+        }
+        // Half-wavelength pitch at sin(30°) is a quarter-cycle step
+        assert!((steering.phases_rad[1] - std::f32::consts::FRAC_PI_2).abs() < 1e-3);
+        assert!((steering.phases_rad[2] - std::f32::consts::PI).abs() < 1e-3);
+
+        // Steering downwards makes the element above the origin fire first
+        let down = engine.steer_array(0.0, -30.0).unwrap();
+        assert!(down.delays_s[3].abs() < 1e-9);
+        assert!((down.delays_s[0] - step).abs() < 1e-9);
+        assert_eq!(engine.get_array_steering().unwrap().elevation, -30.0);
+
+        assert!(engine.steer_array(120.0, 0.0).is_err());
+    }
 }
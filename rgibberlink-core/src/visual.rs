@@ -4,6 +4,12 @@ use serde::{Deserialize, Serialize};
 use serde_cbor;
 use crc32fast;
 use std::time::{SystemTime, UNIX_EPOCH};
+use crate::crypto::CryptoEngine;
+use crate::protocol::ProtocolVersion;
+use crate::session_id::SessionId;
+
+#[cfg(feature = "qr-png")]
+pub use image::GrayImage;
 
 #[derive(Debug, Clone, thiserror::Error)]
 pub enum VisualError {
@@ -17,14 +23,191 @@ pub enum VisualError {
     DataTooLarge,
     #[error("Invalid compensation state")]
     InvalidCompensationState,
+    #[error("No QR code found in image")]
+    QrNotFound,
+    #[error("QR code could not be read: {0}")]
+    QrScanError(String),
+    #[error("QR payload signature is missing or invalid")]
+    InvalidSignature,
+    #[error("CBOR payload declares {declared} bytes or items, limit is {limit}")]
+    PayloadTooLarge { declared: u64, limit: usize },
+    #[error("QR module scale must be at least one pixel")]
+    InvalidModuleScale,
+    #[error("PNG encoding failed: {0}")]
+    PngEncodeError(String),
+    #[error("{len} bytes is not a {width}x{height} grayscale image")]
+    InvalidImageDimensions { width: u32, height: u32, len: usize },
+}
+
+/// Light border around rendered codes, in modules; the QR specification asks for four
+pub const DEFAULT_QUIET_ZONE_MODULES: u32 = 4;
+
+/// Default bound on a decoded CBOR payload: the capacity of a version 40 QR code
+pub const DEFAULT_MAX_CBOR_PAYLOAD: usize = 2953;
+
+/// Deepest array/map nesting accepted from scanned CBOR
+const MAX_CBOR_NESTING: usize = 16;
+
+/// Walk untrusted CBOR without allocating, checking every declared length before
+/// serde sees it.
+///
+/// Strings must fit in the remaining input and arrays/maps may not declare more
+/// items than bytes remain (each item takes at least one), so a crafted length
+/// prefix cannot make the decoder reserve memory. Declarations above `max_size`
+/// are rejected with `PayloadTooLarge`; truncated, indefinite-length, too deeply
+/// nested or trailing data is a `CborError`.
+pub fn check_cbor_bounds(data: &[u8], max_size: usize) -> Result<(), VisualError> {
+    if data.len() > max_size {
+        return Err(VisualError::PayloadTooLarge { declared: data.len() as u64, limit: max_size });
+    }
+    let end = check_cbor_item(data, 0, max_size, 0)?;
+    if end != data.len() {
+        return Err(VisualError::CborError);
+    }
+    Ok(())
 }
 
+/// Validate the item starting at `pos` and return the offset just past it
+fn check_cbor_item(data: &[u8], pos: usize, max_size: usize, depth: usize) -> Result<usize, VisualError> {
+    if depth > MAX_CBOR_NESTING {
+        return Err(VisualError::CborError);
+    }
+    let initial = *data.get(pos).ok_or(VisualError::CborError)?;
+    let (major, info) = (initial >> 5, initial & 0x1f);
+
+    let (argument, mut pos) = match info {
+        0..=23 => (info as u64, pos + 1),
+        24..=27 => {
+            let width = 1usize << (info - 24);
+            let bytes = data.get(pos + 1..pos + 1 + width).ok_or(VisualError::CborError)?;
+            (bytes.iter().fold(0u64, |value, &byte| (value << 8) | byte as u64), pos + 1 + width)
+        }
+        // Reserved values and indefinite lengths; our encoder never emits them
+        _ => return Err(VisualError::CborError),
+    };
+
+    let remaining = (data.len() - pos) as u64;
+    let bounded = |declared: u64| {
+        if declared > max_size as u64 {
+            Err(VisualError::PayloadTooLarge { declared, limit: max_size })
+        } else if declared > remaining {
+            Err(VisualError::CborError)
+        } else {
+            Ok(declared as usize)
+        }
+    };
+
+    match major {
+        // Integers and simple values/floats carry everything in the argument
+        0 | 1 | 7 => Ok(pos),
+        // Byte and text strings
+        2 | 3 => Ok(pos + bounded(argument)?),
+        // Arrays and maps (a map holds two items per entry)
+        4 | 5 => {
+            let items = if major == 5 { argument.saturating_mul(2) } else { argument };
+            for _ in 0..bounded(items)? {
+                pos = check_cbor_item(data, pos, max_size, depth + 1)?;
+            }
+            Ok(pos)
+        }
+        // Tags wrap a single item
+        _ => check_cbor_item(data, pos, max_size, depth + 1),
+    }
+}
+
+/// Image → raw QR bytes backend used by `VisualEngine::scan_image`
+#[cfg(feature = "qr-scan")]
+pub trait QrScanner {
+    fn scan(&self, image: &GrayImage) -> Result<Vec<u8>, VisualError>;
+}
+
+/// Default scanner backed by `rqrr`
+#[cfg(feature = "qr-scan")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct BuiltinQrScanner;
+
+#[cfg(feature = "qr-scan")]
+impl QrScanner for BuiltinQrScanner {
+    fn scan(&self, image: &GrayImage) -> Result<Vec<u8>, VisualError> {
+        let mut prepared = rqrr::PreparedImage::prepare(image.clone());
+        let grids = prepared.detect_grids();
+        let grid = grids.first().ok_or(VisualError::QrNotFound)?;
+
+        // Payloads are binary, so read raw bytes rather than a UTF-8 string
+        let mut data = Vec::new();
+        grid.decode_to(&mut data)
+            .map_err(|e| VisualError::QrScanError(e.to_string()))?;
+        Ok(data)
+    }
+}
+
+/// Signed QR payloads carry the signer's Ed25519 key followed by the signature
+pub const QR_SIGNATURE_LEN: usize = 32 + 64;
+/// Appended to the signed bytes of payloads announcing pre-shared-key keying
+const PSK_MARKER: &[u8] = b"PSK";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VisualPayload {
-    pub session_id: [u8; 16],
+    pub session_id: SessionId,
     pub public_key: Vec<u8>,
     pub nonce: [u8; 16],
     pub signature: Vec<u8>,
+    /// Version the responder negotiated from the initiator's nonce; absent in
+    /// payloads from peers that predate negotiation, which speak 1.0
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<ProtocolVersion>,
+    /// `ProtocolVersion::offer_digest` of the versions the initiator offered, as
+    /// the responder received them, so a trimmed offer fails the signature check
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version_offer: Option<[u8; 16]>,
+    /// The responder keys this session from the pre-shared key instead of ECDH
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub psk: bool,
+}
+
+impl VisualPayload {
+    /// Bytes covered by the payload signature: the session, the ECDH key, the echoed
+    /// nonce, the negotiated version, the offer it was picked from and the keying
+    /// mode, so none can be downgraded in transit
+    pub fn signed_bytes(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(16 + 2 + self.public_key.len() + 16 + 2 + 16 + PSK_MARKER.len());
+        data.extend_from_slice(self.session_id.as_bytes());
+        data.extend_from_slice(&(self.public_key.len() as u16).to_be_bytes());
+        data.extend_from_slice(&self.public_key);
+        data.extend_from_slice(&self.nonce);
+        if let Some(version) = self.version {
+            data.extend_from_slice(&[version.major, version.minor]);
+        }
+        if let Some(offer) = &self.version_offer {
+            data.extend_from_slice(offer);
+        }
+        if self.psk {
+            data.extend_from_slice(PSK_MARKER);
+        }
+        data
+    }
+
+    /// Sign the payload with `crypto`'s Ed25519 identity key
+    pub fn sign(&mut self, crypto: &CryptoEngine) -> Result<(), VisualError> {
+        let signature = crypto.sign_data(&self.signed_bytes()).map_err(|_| VisualError::InvalidSignature)?;
+        let mut signed = crypto.ed25519_public_key().to_vec();
+        signed.extend(signature);
+        self.signature = signed;
+        Ok(())
+    }
+
+    /// Verify the signature and return the signer's Ed25519 key for pinning
+    pub fn verify_signature(&self) -> Result<[u8; 32], VisualError> {
+        if self.signature.len() != QR_SIGNATURE_LEN {
+            return Err(VisualError::InvalidSignature);
+        }
+        let (signer, signature) = self.signature.split_at(32);
+        CryptoEngine::verify_log_signature(signer, &self.signed_bytes(), signature)
+            .map_err(|_| VisualError::InvalidSignature)?;
+        let mut signer_key = [0u8; 32];
+        signer_key.copy_from_slice(signer);
+        Ok(signer_key)
+    }
 }
 
 /// Compensation protocol states for noisy environments
@@ -46,7 +229,7 @@ pub enum CompensationState {
 #[derive(Serialize, Deserialize)]
 pub struct CompensationFrame {
     pub state: CompensationState,
-    pub session_id: [u8; 16],
+    pub session_id: SessionId,
     pub sequence_id: u32,
     pub timestamp: u64,
     pub payload: Option<Vec<u8>>, // MAC confirmation + ultrasonic profile
@@ -77,7 +260,7 @@ impl CompensationState {
 }
 
 impl CompensationFrame {
-    pub fn new(state: CompensationState, session_id: [u8; 16], sequence_id: u32, payload: Option<Vec<u8>>) -> Self {
+    pub fn new(state: CompensationState, session_id: SessionId, sequence_id: u32, payload: Option<Vec<u8>>) -> Self {
         let timestamp = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap_or_default()
@@ -103,19 +286,110 @@ impl CompensationFrame {
 #[derive(Debug)]
 pub struct VisualEngine {
     rs: ReedSolomon,
+    max_payload_size: usize,
+    quiet_zone_modules: u32,
 }
 
 impl VisualEngine {
     pub fn new() -> Self {
         // Reed-Solomon with 8 data shards and 4 parity shards for 12 total
         let rs = ReedSolomon::new(8, 4).expect("Failed to create Reed-Solomon codec");
-        Self { rs }
+        Self { rs, max_payload_size: DEFAULT_MAX_CBOR_PAYLOAD, quiet_zone_modules: DEFAULT_QUIET_ZONE_MODULES }
+    }
+
+    /// Bound the CBOR accepted from scanned codes (bytes, and items per array/map)
+    pub fn set_max_payload_size(&mut self, max_size: usize) {
+        self.max_payload_size = max_size;
+    }
+
+    pub fn max_payload_size(&self) -> usize {
+        self.max_payload_size
+    }
+
+    /// Width of the light border drawn around raster codes, in modules
+    pub fn set_quiet_zone_modules(&mut self, modules: u32) {
+        self.quiet_zone_modules = modules;
+    }
+
+    pub fn quiet_zone_modules(&self) -> u32 {
+        self.quiet_zone_modules
     }
 
     pub fn encode_payload(&self, payload: &VisualPayload) -> Result<String, VisualError> {
+        let code = QrCode::new(&self.encode_payload_bytes(payload)?).map_err(|_| VisualError::QrCodeError)?;
+        let svg = code.render::<qrcode::render::svg::Color>().build();
+
+        Ok(svg)
+    }
+
+    /// Render the payload QR code as a grayscale image
+    #[cfg(feature = "qr-scan")]
+    pub fn encode_payload_image(&self, payload: &VisualPayload) -> Result<GrayImage, VisualError> {
+        let code = QrCode::new(&self.encode_payload_bytes(payload)?).map_err(|_| VisualError::QrCodeError)?;
+        Ok(code.render::<image::Luma<u8>>().build())
+    }
+
+    /// Render the payload QR code as PNG bytes, `scale` pixels per module, inside
+    /// the configured quiet zone
+    #[cfg(feature = "qr-png")]
+    pub fn encode_payload_png(&self, payload: &VisualPayload, scale: u32) -> Result<Vec<u8>, VisualError> {
+        if scale == 0 {
+            return Err(VisualError::InvalidModuleScale);
+        }
+        let code = QrCode::new(&self.encode_payload_bytes(payload)?).map_err(|_| VisualError::QrCodeError)?;
+        let width = code.width() as u32;
+        let colors = code.to_colors();
+        let quiet_zone = self.quiet_zone_modules;
+
+        let side = (width + 2 * quiet_zone) * scale;
+        let image = GrayImage::from_fn(side, side, |x, y| {
+            let (column, row) = (x / scale, y / scale);
+            let in_code = |module: u32| (quiet_zone..quiet_zone + width).contains(&module);
+            let dark = in_code(column) && in_code(row)
+                && colors[((row - quiet_zone) * width + column - quiet_zone) as usize] == qrcode::Color::Dark;
+            image::Luma([if dark { 0 } else { 255 }])
+        });
+
+        let mut png = Vec::new();
+        image::DynamicImage::ImageLuma8(image)
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageOutputFormat::Png)
+            .map_err(|e| VisualError::PngEncodeError(e.to_string()))?;
+        Ok(png)
+    }
+
+    /// Scan a payload from a camera frame or image file using the default scanner
+    #[cfg(feature = "qr-scan")]
+    pub fn scan_image(&self, image: &GrayImage) -> Result<VisualPayload, VisualError> {
+        self.scan_image_with(&BuiltinQrScanner, image)
+    }
+
+    /// Scan a payload from a row-major 8-bit grayscale buffer, as camera
+    /// pipelines hand frames over; the inverse of `encode_payload_image`
+    #[cfg(feature = "qr-scan")]
+    pub fn decode_from_image(&self, gray: &[u8], width: u32, height: u32) -> Result<VisualPayload, VisualError> {
+        let image = (gray.len() as u64 == width as u64 * height as u64)
+            .then(|| GrayImage::from_raw(width, height, gray.to_vec()))
+            .flatten()
+            .ok_or(VisualError::InvalidImageDimensions { width, height, len: gray.len() })?;
+        self.scan_image(&image)
+    }
+
+    /// Scan a payload from an image using a custom scanning backend
+    #[cfg(feature = "qr-scan")]
+    pub fn scan_image_with(&self, scanner: &dyn QrScanner, image: &GrayImage) -> Result<VisualPayload, VisualError> {
+        let qr_data = scanner.scan(image)?;
+        self.decode_payload(&qr_data)
+    }
+
+    /// Serialize and RS-encode a payload into the raw bytes carried by the QR code
+    pub(crate) fn encode_payload_bytes(&self, payload: &VisualPayload) -> Result<Vec<u8>, VisualError> {
         // Serialize to CBOR
         let cbor_data = serde_cbor::to_vec(payload).map_err(|_| VisualError::CborError)?;
+        self.encode_cbor_bytes(cbor_data)
+    }
 
+    /// Length-prefix and RS-encode already serialized CBOR
+    fn encode_cbor_bytes(&self, cbor_data: Vec<u8>) -> Result<Vec<u8>, VisualError> {
         // Compress data (simple length-prefixed for prototype)
         let mut compressed = (cbor_data.len() as u16).to_le_bytes().to_vec();
         compressed.extend(cbor_data);
@@ -143,15 +417,11 @@ impl VisualEngine {
             encoded_data.extend(shard);
         }
 
-        // Generate QR code
         if encoded_data.len() > 2953 { // Max data for QR version 40
             return Err(VisualError::DataTooLarge);
         }
 
-        let code = QrCode::new(&encoded_data).map_err(|_| VisualError::QrCodeError)?;
-        let svg = code.render::<qrcode::render::svg::Color>().build();
-
-        Ok(svg)
+        Ok(encoded_data)
     }
 
     pub fn decode_payload(&self, qr_data: &[u8]) -> Result<VisualPayload, VisualError> {
@@ -181,12 +451,16 @@ impl VisualEngine {
             return Err(VisualError::CborError);
         }
         let data_len = u16::from_le_bytes([reconstructed[0], reconstructed[1]]) as usize;
+        if data_len > self.max_payload_size {
+            return Err(VisualError::PayloadTooLarge { declared: data_len as u64, limit: self.max_payload_size });
+        }
         if reconstructed.len() < 2 + data_len {
             return Err(VisualError::CborError);
         }
         let cbor_data = &reconstructed[2..2 + data_len];
 
-        // Deserialize from CBOR
+        // Scanned bytes are attacker-controlled: bound every length before deserializing
+        check_cbor_bounds(cbor_data, self.max_payload_size)?;
         let payload: VisualPayload = serde_cbor::from_slice(cbor_data).map_err(|_| VisualError::CborError)?;
 
         Ok(payload)
@@ -228,7 +502,7 @@ impl VisualEngine {
         encoded_data.extend_from_slice(&frame.crc.to_le_bytes());
 
         // Central: interleaved session_id and sequence
-        encoded_data.extend_from_slice(&frame.session_id);
+        encoded_data.extend_from_slice(frame.session_id.as_bytes());
         encoded_data.extend_from_slice(&frame.sequence_id.to_le_bytes());
 
         // Rest: ECC parity blocks
@@ -293,7 +567,9 @@ impl VisualEngine {
                 reconstructed.extend(shard);
             }
 
-            // Deserialize frame
+            // Deserialize frame; RS padding may trail the CBOR item, so only its
+            // declared lengths are checked here
+            check_cbor_item(&reconstructed, 0, self.max_payload_size, 0)?;
             let frame: CompensationFrame = serde_cbor::from_slice(&reconstructed)
                 .map_err(|_| VisualError::CborError)?;
 
@@ -306,3 +582,147 @@ impl VisualEngine {
         Err(VisualError::ReedSolomonError)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "qr-scan")]
+    fn test_scan_image_round_trip_through_png() {
+        let engine = VisualEngine::new();
+        let payload = VisualPayload {
+            session_id: SessionId::new([3u8; 16]),
+            public_key: (0..32).collect(),
+            nonce: [9u8; 16],
+            signature: vec![0xAB; 64],
+            version: None,
+            version_offer: None,
+            psk: false,
+        };
+
+        // Render to PNG and load it back as a camera frame would arrive
+        let image = engine.encode_payload_image(&payload).unwrap();
+        let mut png = Vec::new();
+        image::DynamicImage::ImageLuma8(image)
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageOutputFormat::Png)
+            .unwrap();
+        let frame = image::load_from_memory(&png).unwrap().to_luma8();
+
+        let scanned = engine.scan_image(&frame).unwrap();
+        assert_eq!(scanned.session_id, payload.session_id);
+        assert_eq!(scanned.public_key, payload.public_key);
+        assert_eq!(scanned.nonce, payload.nonce);
+        assert_eq!(scanned.signature, payload.signature);
+    }
+
+    #[test]
+    #[cfg(feature = "qr-scan")]
+    fn test_png_output_keeps_quiet_zone_and_scans() {
+        let mut engine = VisualEngine::new();
+        let payload = VisualPayload {
+            session_id: SessionId::new([5u8; 16]),
+            public_key: (0..32).collect(),
+            nonce: [1u8; 16],
+            signature: vec![0xCD; 64],
+            version: None,
+            version_offer: None,
+            psk: false,
+        };
+        let modules = QrCode::new(&engine.encode_payload_bytes(&payload).unwrap()).unwrap().width() as u32;
+
+        for (quiet_zone, scale) in [(DEFAULT_QUIET_ZONE_MODULES, 3), (6, 5)] {
+            engine.set_quiet_zone_modules(quiet_zone);
+            let png = engine.encode_payload_png(&payload, scale).unwrap();
+            let frame = image::load_from_memory(&png).unwrap().to_luma8();
+            assert_eq!(frame.width(), (modules + 2 * quiet_zone) * scale);
+
+            // The border is light and the finder pattern starts right inside it
+            let edge = quiet_zone * scale;
+            assert!((0..edge).all(|i| frame.get_pixel(i, i)[0] == 255));
+            assert_eq!(frame.get_pixel(edge, edge)[0], 0);
+
+            assert_eq!(engine.scan_image(&frame).unwrap().public_key, payload.public_key);
+        }
+
+        assert!(matches!(engine.encode_payload_png(&payload, 0), Err(VisualError::InvalidModuleScale)));
+    }
+
+    #[test]
+    #[cfg(feature = "qr-scan")]
+    fn test_decode_from_raw_grayscale_frame() {
+        let engine = VisualEngine::new();
+        let payload = VisualPayload {
+            session_id: SessionId::new([7u8; 16]),
+            public_key: (0..32).collect(),
+            nonce: [4u8; 16],
+            signature: vec![0xEF; 64],
+            version: None,
+            version_offer: None,
+            psk: false,
+        };
+        let image = engine.encode_payload_image(&payload).unwrap();
+        let (width, height) = image.dimensions();
+        let gray = image.into_raw();
+
+        let decoded = engine.decode_from_image(&gray, width, height).unwrap();
+        assert_eq!(decoded.session_id, payload.session_id);
+        assert_eq!(decoded.public_key, payload.public_key);
+        assert_eq!(decoded.nonce, payload.nonce);
+        assert_eq!(decoded.signature, payload.signature);
+
+        assert!(matches!(
+            engine.decode_from_image(&gray[1..], width, height),
+            Err(VisualError::InvalidImageDimensions { len, .. }) if len == gray.len() - 1
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "qr-scan")]
+    fn test_scan_image_without_qr_code() {
+        let engine = VisualEngine::new();
+        let blank = GrayImage::from_pixel(64, 64, image::Luma([255u8]));
+        assert!(matches!(engine.scan_image(&blank), Err(VisualError::QrNotFound)));
+    }
+
+    #[test]
+    fn test_crafted_cbor_length_rejected_before_allocation() {
+        let engine = VisualEngine::new();
+
+        // {"session_id": [<2^64 - 1 items>]}: a few bytes that would ask the decoder
+        // for an enormous array
+        let mut crafted = vec![0xa1, 0x6a];
+        crafted.extend_from_slice(b"session_id");
+        crafted.push(0x9b);
+        crafted.extend_from_slice(&u64::MAX.to_be_bytes());
+        crafted.push(0x00);
+
+        let qr = engine.encode_cbor_bytes(crafted).unwrap();
+        assert!(matches!(
+            engine.decode_payload(&qr),
+            Err(VisualError::PayloadTooLarge { declared: u64::MAX, limit: DEFAULT_MAX_CBOR_PAYLOAD })
+        ));
+
+        // Within the limit but longer than the input is malformed, not oversized
+        let mut truncated = vec![0x59, 0x01, 0x00];
+        truncated.extend_from_slice(&[0u8; 16]);
+        assert!(matches!(check_cbor_bounds(&truncated, DEFAULT_MAX_CBOR_PAYLOAD), Err(VisualError::CborError)));
+
+        // A lower configured limit applies to the whole payload
+        let payload = VisualPayload {
+            session_id: SessionId::new([3u8; 16]),
+            public_key: (0..32).collect(),
+            nonce: [9u8; 16],
+            signature: vec![0xAB; 96],
+            version: None,
+            version_offer: None,
+            psk: false,
+        };
+        let qr = engine.encode_payload_bytes(&payload).unwrap();
+        assert_eq!(engine.decode_payload(&qr).unwrap().signature, payload.signature);
+
+        let mut strict = VisualEngine::new();
+        strict.set_max_payload_size(64);
+        assert!(matches!(strict.decode_payload(&qr), Err(VisualError::PayloadTooLarge { limit: 64, .. })));
+    }
+}
@@ -7,8 +7,10 @@
 use wasm_bindgen::prelude::*;
 use web_sys::console;
 
-use crate::crypto::{CryptoEngine, CryptoError};
+use crate::crypto::{CipherSuite, CryptoEngine, CryptoError};
+use crate::protocol::ProtocolVersion;
 use crate::visual::{VisualEngine, VisualPayload};
+use crate::session_id::SessionId;
 
 /// WebAssembly-compatible cryptographic utilities
 #[wasm_bindgen]
@@ -69,20 +71,20 @@ impl WasmCryptoEngine {
         }
     }
 
-    /// Encrypt data with AES-GCM
+    /// Encrypt data with the default cipher suite (AES-256-GCM)
     #[wasm_bindgen]
     pub fn encrypt_data(&self, key_hex: &str, data: &str) -> Result<String, JsValue> {
         let key = hex::decode(key_hex)
             .map_err(|e| JsValue::from_str(&format!("Invalid key hex: {:?}", e)))?;
         let data_bytes = data.as_bytes();
 
-        let encrypted = CryptoEngine::encrypt_data(&key, data_bytes)
+        let encrypted = CryptoEngine::encrypt_data_suite(CipherSuite::default(), &key, data_bytes)
             .map_err(|e| JsValue::from_str(&format!("Encryption failed: {:?}", e)))?;
 
         Ok(hex::encode(encrypted))
     }
 
-    /// Decrypt data with AES-GCM
+    /// Decrypt data sealed with any supported cipher suite
     #[wasm_bindgen]
     pub fn decrypt_data(&self, key_hex: &str, encrypted_hex: &str) -> Result<String, JsValue> {
         let key = hex::decode(key_hex)
@@ -90,7 +92,7 @@ impl WasmCryptoEngine {
         let encrypted = hex::decode(encrypted_hex)
             .map_err(|e| JsValue::from_str(&format!("Invalid encrypted hex: {:?}", e)))?;
 
-        let decrypted = CryptoEngine::decrypt_data(&key, &encrypted)
+        let decrypted = CryptoEngine::decrypt_data_suite(&key, &encrypted)
             .map_err(|e| JsValue::from_str(&format!("Decryption failed: {:?}", e)))?;
 
         String::from_utf8(decrypted)
@@ -118,10 +120,8 @@ impl WasmVisualEngine {
     /// Generate QR code from payload
     #[wasm_bindgen]
     pub fn encode_payload(&self, session_id_hex: &str, public_key_hex: &str, nonce_hex: &str) -> Result<String, JsValue> {
-        let session_id = hex::decode(session_id_hex)
-            .map_err(|e| JsValue::from_str(&format!("Invalid session ID: {:?}", e)))?;
-        let session_id_array: [u8; 16] = session_id.try_into()
-            .map_err(|_| JsValue::from_str("Session ID must be 16 bytes"))?;
+        let session_id = SessionId::from_hex(session_id_hex)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
         let public_key = hex::decode(public_key_hex)
             .map_err(|e| JsValue::from_str(&format!("Invalid public key: {:?}", e)))?;
@@ -132,10 +132,13 @@ impl WasmVisualEngine {
             .map_err(|_| JsValue::from_str("Nonce must be 16 bytes"))?;
 
         let payload = VisualPayload {
-            session_id: session_id_array,
+            session_id,
             public_key,
             nonce: nonce_array,
             signature: Vec::new(), // Simplified for WebAssembly
+            version: None,
+            version_offer: None,
+            psk: false,
         };
 
         self.inner.encode_payload(&payload)
@@ -149,14 +152,7 @@ impl WasmVisualEngine {
         let payload = self.inner.decode_payload(qr_bytes)
             .map_err(|e| JsValue::from_str(&format!("QR decoding failed: {:?}", e)))?;
 
-        let result = serde_json::json!({
-            "session_id": hex::encode(payload.session_id),
-            "public_key": hex::encode(payload.public_key),
-            "nonce": hex::encode(payload.nonce),
-            "signature": hex::encode(payload.signature),
-        });
-
-        serde_json::to_string(&result)
+        serde_json::to_string(&payload_to_json(&payload))
             .map_err(|e| JsValue::from_str(&format!("JSON serialization failed: {:?}", e)))
     }
 }
@@ -167,7 +163,7 @@ impl WasmVisualEngine {
 pub struct WasmProtocolEngine {
     crypto: CryptoEngine,
     visual: VisualEngine,
-    session_id: [u8; 16],
+    session_id: SessionId,
     state: String,
     mode: String,
 }
@@ -179,8 +175,7 @@ impl WasmProtocolEngine {
     pub fn new() -> WasmProtocolEngine {
         console::log_1(&"Initializing WebAssembly protocol engine".into());
 
-        let mut session_id = [0u8; 16];
-        getrandom::getrandom(&mut session_id).unwrap_or_default();
+        let session_id = SessionId::random();
 
         WasmProtocolEngine {
             crypto: CryptoEngine::new(),
@@ -240,6 +235,9 @@ impl WasmProtocolEngine {
             public_key: self.crypto.public_key().to_vec(),
             nonce: nonce_array,
             signature: vec![], // Simplified for demo
+            version: None,
+            version_offer: None,
+            psk: false,
         };
 
         let qr_svg = self.visual.encode_payload(&payload)
@@ -283,7 +281,7 @@ impl WasmProtocolEngine {
 
         // For demo, use a dummy key. In real implementation, use shared secret
         let dummy_key = [1u8; 32];
-        let encrypted = CryptoEngine::encrypt_data(&dummy_key, message.as_bytes())
+        let encrypted = CryptoEngine::encrypt_data_suite(CipherSuite::default(), &dummy_key, message.as_bytes())
             .map_err(|e| JsValue::from_str(&format!("Encryption failed: {:?}", e)))?;
 
         Ok(hex::encode(encrypted))
@@ -301,7 +299,7 @@ impl WasmProtocolEngine {
 
         // For demo, use a dummy key. In real implementation, use shared secret
         let dummy_key = [1u8; 32];
-        let decrypted = CryptoEngine::decrypt_data(&dummy_key, &encrypted)
+        let decrypted = CryptoEngine::decrypt_data_suite(&dummy_key, &encrypted)
             .map_err(|e| JsValue::from_str(&format!("Decryption failed: {:?}", e)))?;
 
         String::from_utf8(decrypted)
@@ -317,7 +315,7 @@ impl WasmProtocolEngine {
     /// Get session ID
     #[wasm_bindgen]
     pub fn get_session_id(&self) -> String {
-        hex::encode(self.session_id)
+        self.session_id.to_hex()
     }
 
     /// Get public key
@@ -339,6 +337,138 @@ impl WasmProtocolEngine {
     }
 }
 
+// Synchronous one-shot API: plain byte arrays in and out and no runtime, for
+// pages that only need the crypto and QR steps of short-range pairing
+
+/// Encrypt `data` with the default cipher suite (AES-256-GCM) under a 32-byte key
+#[wasm_bindgen]
+pub fn wasm_encrypt(key: &[u8], data: &[u8]) -> Result<Vec<u8>, JsValue> {
+    CryptoEngine::encrypt_data_suite(CipherSuite::default(), key, data)
+        .map_err(|e| JsValue::from_str(&format!("Encryption failed: {:?}", e)))
+}
+
+/// Decrypt the output of `wasm_encrypt`
+#[wasm_bindgen]
+pub fn wasm_decrypt(key: &[u8], encrypted: &[u8]) -> Result<Vec<u8>, JsValue> {
+    CryptoEngine::decrypt_data_suite(key, encrypted)
+        .map_err(|e| JsValue::from_str(&format!("Decryption failed: {:?}", e)))
+}
+
+/// Device identity from `wasm_generate_keypair`
+#[wasm_bindgen]
+pub struct WasmKeypair {
+    secret_key: Vec<u8>,
+    public_key: Vec<u8>,
+    signing_public_key: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl WasmKeypair {
+    /// 32-byte ECDH scalar; the signing key is derived from it, so it restores the whole identity
+    #[wasm_bindgen(getter)]
+    pub fn secret_key(&self) -> Vec<u8> {
+        self.secret_key.clone()
+    }
+
+    /// X25519 public key, as shown in the pairing QR code
+    #[wasm_bindgen(getter)]
+    pub fn public_key(&self) -> Vec<u8> {
+        self.public_key.clone()
+    }
+
+    /// Ed25519 public key that QR payload signatures verify against
+    #[wasm_bindgen(getter)]
+    pub fn signing_public_key(&self) -> Vec<u8> {
+        self.signing_public_key.clone()
+    }
+}
+
+/// Generate a fresh device identity
+#[wasm_bindgen]
+pub fn wasm_generate_keypair() -> WasmKeypair {
+    let secret_key = CryptoEngine::generate_secure_random_bytes(32);
+    let mut seed = [0u8; 32];
+    seed.copy_from_slice(&secret_key);
+    let engine = CryptoEngine::from_ecdh_seed(seed);
+    WasmKeypair {
+        secret_key,
+        public_key: engine.public_key().to_vec(),
+        signing_public_key: engine.ed25519_public_key().to_vec(),
+    }
+}
+
+/// Render a pairing QR code as SVG from a payload in the JSON form
+/// `WasmVisualEngine::decode_payload` returns (hex fields; `signature`,
+/// `version`, `version_offer` and `psk` optional)
+#[wasm_bindgen]
+pub fn wasm_generate_qr_svg(payload_json: &str) -> Result<String, JsValue> {
+    generate_qr_svg(payload_json).map_err(|e| JsValue::from_str(&e))
+}
+
+fn generate_qr_svg(payload_json: &str) -> Result<String, String> {
+    VisualEngine::new().encode_payload(&payload_from_json(payload_json)?)
+        .map_err(|e| format!("QR encoding failed: {:?}", e))
+}
+
+/// JSON form of a pairing payload: byte fields as hex, and the negotiated
+/// version fields only when the payload carries them
+fn payload_to_json(payload: &VisualPayload) -> serde_json::Value {
+    let mut json = serde_json::json!({
+        "session_id": payload.session_id.to_hex(),
+        "public_key": hex::encode(&payload.public_key),
+        "nonce": hex::encode(payload.nonce),
+        "signature": hex::encode(&payload.signature),
+    });
+    if let Some(version) = payload.version {
+        json["version"] = serde_json::json!({ "major": version.major, "minor": version.minor });
+    }
+    if let Some(offer) = payload.version_offer {
+        json["version_offer"] = serde_json::Value::String(hex::encode(offer));
+    }
+    if payload.psk {
+        json["psk"] = serde_json::Value::Bool(true);
+    }
+    json
+}
+
+/// Inverse of `payload_to_json`
+fn payload_from_json(payload_json: &str) -> Result<VisualPayload, String> {
+    let json: serde_json::Value = serde_json::from_str(payload_json)
+        .map_err(|e| format!("Invalid payload JSON: {:?}", e))?;
+    let hex_field = |name: &str| -> Result<Option<Vec<u8>>, String> {
+        match json.get(name) {
+            None => Ok(None),
+            Some(value) => value.as_str()
+                .ok_or_else(|| format!("Invalid {}: expected a hex string", name))
+                .and_then(|value| hex::decode(value).map_err(|e| format!("Invalid {} hex: {:?}", name, e)))
+                .map(Some),
+        }
+    };
+    let required = |name: &str| hex_field(name)?.ok_or_else(|| format!("Missing {}", name));
+
+    let session_id = json.get("session_id").and_then(|value| value.as_str())
+        .ok_or_else(|| "Missing session_id".to_string())?;
+    let version = match json.get("version") {
+        Some(version) => Some(serde_json::from_value::<ProtocolVersion>(version.clone())
+            .map_err(|e| format!("Invalid version: {:?}", e))?),
+        None => None,
+    };
+    let version_offer = match hex_field("version_offer")? {
+        Some(offer) => Some(offer.try_into().map_err(|_| "Version offer must be 16 bytes".to_string())?),
+        None => None,
+    };
+
+    Ok(VisualPayload {
+        session_id: SessionId::from_hex(session_id).map_err(|e| e.to_string())?,
+        public_key: required("public_key")?,
+        nonce: required("nonce")?.try_into().map_err(|_| "Nonce must be 16 bytes".to_string())?,
+        signature: hex_field("signature")?.unwrap_or_default(),
+        version,
+        version_offer,
+        psk: json.get("psk").and_then(|value| value.as_bool()).unwrap_or(false),
+    })
+}
+
 /// Initialize WebAssembly module
 #[wasm_bindgen(start)]
 pub fn main() {
@@ -374,3 +504,82 @@ pub fn get_build_info() -> String {
         env!("CARGO_PKG_VERSION")
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn versioned_payload() -> VisualPayload {
+        VisualPayload {
+            session_id: SessionId::new([3u8; 16]),
+            public_key: (0..32).collect(),
+            nonce: [9u8; 16],
+            signature: vec![0xAB; 96],
+            version: Some(ProtocolVersion::new(1, 1)),
+            version_offer: Some([0x5A; 16]),
+            psk: true,
+        }
+    }
+
+    #[test]
+    fn test_qr_svg_keeps_negotiated_version() {
+        let payload = versioned_payload();
+        let json = payload_to_json(&payload).to_string();
+
+        let parsed = payload_from_json(&json).unwrap();
+        assert_eq!(parsed.version, payload.version);
+        assert_eq!(parsed.version_offer, payload.version_offer);
+        assert!(parsed.psk);
+
+        let engine = VisualEngine::new();
+        assert_eq!(engine.encode_payload_bytes(&parsed).unwrap(), engine.encode_payload_bytes(&payload).unwrap());
+        assert_eq!(generate_qr_svg(&json).unwrap(), engine.encode_payload(&payload).unwrap());
+    }
+
+    #[test]
+    fn test_qr_svg_accepts_unversioned_payloads() {
+        let payload = VisualPayload { version: None, version_offer: None, psk: false, signature: Vec::new(), ..versioned_payload() };
+        let json = payload_to_json(&payload);
+        assert!(json.get("version").is_none() && json.get("version_offer").is_none() && json.get("psk").is_none());
+
+        // `signature` may be left out entirely
+        let mut unsigned = json.clone();
+        unsigned.as_object_mut().unwrap().remove("signature");
+        let parsed = payload_from_json(&unsigned.to_string()).unwrap();
+        assert!(parsed.signature.is_empty() && parsed.version.is_none());
+        assert_eq!(generate_qr_svg(&unsigned.to_string()).unwrap(), VisualEngine::new().encode_payload(&payload).unwrap());
+    }
+
+    #[test]
+    fn test_qr_svg_rejects_malformed_payloads() {
+        let json = payload_to_json(&versioned_payload());
+        let with = |field: &str, value: serde_json::Value| {
+            let mut json = json.clone();
+            json[field] = value;
+            generate_qr_svg(&json.to_string()).unwrap_err()
+        };
+
+        assert_eq!(with("nonce", "0011".into()), "Nonce must be 16 bytes");
+        assert_eq!(with("version_offer", "00".into()), "Version offer must be 16 bytes");
+        assert!(with("version", "1.1".into()).starts_with("Invalid version"));
+        assert!(with("public_key", "zz".into()).starts_with("Invalid public_key hex"));
+
+        let mut missing = json.clone();
+        missing.as_object_mut().unwrap().remove("public_key");
+        assert_eq!(generate_qr_svg(&missing.to_string()).unwrap_err(), "Missing public_key");
+        assert!(generate_qr_svg("not json").unwrap_err().starts_with("Invalid payload JSON"));
+    }
+
+    #[test]
+    fn test_sync_helpers_round_trip() {
+        let keypair = wasm_generate_keypair();
+        assert_eq!(keypair.secret_key().len(), 32);
+        let engine = CryptoEngine::from_ecdh_seed(keypair.secret_key().try_into().unwrap());
+        assert_eq!(keypair.public_key(), engine.public_key());
+        assert_eq!(keypair.signing_public_key(), engine.ed25519_public_key().to_vec());
+
+        let key = [0x42u8; 32];
+        let sealed = wasm_encrypt(&key, b"pairing").unwrap();
+        assert_eq!(wasm_decrypt(&key, &sealed).unwrap(), b"pairing");
+    }
+}